@@ -0,0 +1,8 @@
+//! Market analytics.
+//!
+//! Descriptions of market state that strategies and the risk manager can
+//! condition on, such as the volatility and trend regime of a symbol.
+
+mod regime;
+
+pub use regime::{Regime, RegimeConfig, RegimeDetector, TrendRegime, VolatilityRegime};
@@ -0,0 +1,295 @@
+//! Market regime detection.
+//!
+//! A regime combines a volatility bucket and a trend direction:
+//! - Volatility is the standard deviation of log returns over a short
+//!   window, bucketed by its percentile rank within a longer history, so
+//!   "high" means high for this symbol.
+//! - Trend compares the close with a moving average, with a neutral band
+//!   around it.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use trading_core::traits::StreamingIndicator;
+use trading_core::types::{Bar, BarSeries};
+use trading_indicators::{RollingStats, StreamingSma};
+
+/// Volatility bucket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VolatilityRegime {
+    /// Below the low percentile of recent volatility
+    Low,
+    /// Between the low and high percentiles
+    Normal,
+    /// Above the high percentile of recent volatility
+    High,
+}
+
+impl std::fmt::Display for VolatilityRegime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VolatilityRegime::Low => write!(f, "low"),
+            VolatilityRegime::Normal => write!(f, "normal"),
+            VolatilityRegime::High => write!(f, "high"),
+        }
+    }
+}
+
+/// Trend direction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrendRegime {
+    /// Close above the trend band
+    Up,
+    /// Close within the trend band
+    Sideways,
+    /// Close below the trend band
+    Down,
+}
+
+impl std::fmt::Display for TrendRegime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TrendRegime::Up => write!(f, "up"),
+            TrendRegime::Sideways => write!(f, "sideways"),
+            TrendRegime::Down => write!(f, "down"),
+        }
+    }
+}
+
+/// Market regime of a symbol at a bar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Regime {
+    /// Volatility bucket
+    pub volatility: VolatilityRegime,
+    /// Trend direction
+    pub trend: TrendRegime,
+}
+
+impl Regime {
+    /// Whether volatility is in the high bucket.
+    pub fn is_high_volatility(&self) -> bool {
+        self.volatility == VolatilityRegime::High
+    }
+}
+
+impl std::fmt::Display for Regime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-vol {}", self.volatility, self.trend)
+    }
+}
+
+/// Regime detector settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RegimeConfig {
+    /// Bars of returns in the realized volatility
+    pub volatility_window: usize,
+    /// Realized volatility values kept to rank the current one against
+    pub volatility_history: usize,
+    /// Percentile rank at or below which volatility is low
+    pub low_percentile: f64,
+    /// Percentile rank at or above which volatility is high
+    pub high_percentile: f64,
+    /// Moving average period of the trend filter
+    pub trend_period: usize,
+    /// Distance from the moving average, in percent, still counted as sideways
+    pub trend_band_pct: f64,
+}
+
+impl Default for RegimeConfig {
+    fn default() -> Self {
+        Self {
+            volatility_window: 20,
+            volatility_history: 252,
+            low_percentile: 25.0,
+            high_percentile: 75.0,
+            trend_period: 50,
+            trend_band_pct: 1.0,
+        }
+    }
+}
+
+/// Streaming regime detector for one symbol.
+#[derive(Debug, Clone)]
+pub struct RegimeDetector {
+    config: RegimeConfig,
+    prev_close: Option<f64>,
+    returns: RollingStats,
+    /// Recent realized volatility values, oldest first
+    history: VecDeque<f64>,
+    trend: StreamingSma,
+    current: Option<Regime>,
+}
+
+impl Default for RegimeDetector {
+    fn default() -> Self {
+        Self::new(RegimeConfig::default())
+    }
+}
+
+impl RegimeDetector {
+    /// Create a detector.
+    pub fn new(config: RegimeConfig) -> Self {
+        assert!(
+            config.volatility_window > 1,
+            "Volatility window must be greater than 1"
+        );
+        assert!(
+            config.volatility_history >= config.volatility_window,
+            "Volatility history must cover at least the volatility window"
+        );
+        assert!(
+            0.0 <= config.low_percentile
+                && config.low_percentile < config.high_percentile
+                && config.high_percentile <= 100.0,
+            "Percentiles must satisfy 0 <= low < high <= 100"
+        );
+        assert!(
+            config.trend_band_pct >= 0.0,
+            "Trend band must not be negative"
+        );
+        Self {
+            returns: RollingStats::new(config.volatility_window),
+            history: VecDeque::with_capacity(config.volatility_history + 1),
+            trend: StreamingSma::new(config.trend_period),
+            prev_close: None,
+            current: None,
+            config,
+        }
+    }
+
+    /// Regime of every bar of a series; `None` until the detector has
+    /// warmed up.
+    pub fn label(config: RegimeConfig, series: &BarSeries) -> Vec<Option<Regime>> {
+        let mut detector = Self::new(config);
+        series.iter().map(|bar| detector.update(bar)).collect()
+    }
+
+    /// Feed the next bar and return its regime.
+    pub fn update(&mut self, bar: &Bar) -> Option<Regime> {
+        let trend_ma = self.trend.update(bar.close);
+        if let Some(prev) = self.prev_close.replace(bar.close) {
+            if prev > 0.0 && bar.close > 0.0 {
+                self.returns.push((bar.close / prev).ln());
+            }
+        }
+        if self.returns.is_full() {
+            self.history.push_back(self.returns.std_dev());
+            if self.history.len() > self.config.volatility_history {
+                self.history.pop_front();
+            }
+        }
+
+        self.current = self.classify(bar.close, trend_ma);
+        self.current
+    }
+
+    fn classify(&self, close: f64, trend_ma: Option<f64>) -> Option<Regime> {
+        let trend_ma = trend_ma?;
+        // Rank against at least one window of history
+        if self.history.len() < self.config.volatility_window {
+            return None;
+        }
+        let current = *self.history.back()?;
+        let below = self.history.iter().filter(|&&v| v < current).count();
+        let rank = below as f64 / (self.history.len() - 1).max(1) as f64 * 100.0;
+        let volatility = if rank >= self.config.high_percentile {
+            VolatilityRegime::High
+        } else if rank <= self.config.low_percentile {
+            VolatilityRegime::Low
+        } else {
+            VolatilityRegime::Normal
+        };
+
+        let band = trend_ma * self.config.trend_band_pct / 100.0;
+        let trend = if close > trend_ma + band {
+            TrendRegime::Up
+        } else if close < trend_ma - band {
+            TrendRegime::Down
+        } else {
+            TrendRegime::Sideways
+        };
+
+        Some(Regime { volatility, trend })
+    }
+
+    /// Regime of the latest bar, once warmed up.
+    pub fn current(&self) -> Option<Regime> {
+        self.current
+    }
+
+    /// Realized volatility (standard deviation of log returns) of the
+    /// latest window.
+    pub fn realized_volatility(&self) -> Option<f64> {
+        self.returns.is_full().then(|| self.returns.std_dev())
+    }
+
+    /// Bars needed before a regime is reported.
+    pub fn warmup_period(&self) -> usize {
+        (2 * self.config.volatility_window).max(self.config.trend_period)
+    }
+
+    /// Forget all bars.
+    pub fn reset(&mut self) {
+        self.prev_close = None;
+        self.returns.clear();
+        self.history.clear();
+        self.trend.reset();
+        self.current = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use trading_core::types::Timeframe;
+
+    /// Rising prices whose bar-to-bar swings widen after `calm` bars.
+    fn series(calm: usize, total: usize) -> BarSeries {
+        let mut series = BarSeries::new("TEST".to_string(), Timeframe::Daily);
+        for i in 0..total {
+            let swing = if i < calm { 0.002 } else { 0.03 };
+            let sign = if i % 2 == 0 { 1.0 } else { -1.0 };
+            let close = 100.0 * (1.0 + 0.003 * i as f64) * (1.0 + sign * swing);
+            series.push(Bar::new(i as i64, close, close, close, close, 1000.0));
+        }
+        series
+    }
+
+    #[test]
+    fn test_detects_volatility_and_trend() {
+        let config = RegimeConfig {
+            volatility_window: 10,
+            volatility_history: 100,
+            trend_period: 20,
+            ..Default::default()
+        };
+        let labels = RegimeDetector::label(config.clone(), &series(80, 100));
+
+        let warmup = RegimeDetector::new(config).warmup_period();
+        assert!(labels[..warmup - 1].iter().all(Option::is_none));
+
+        let calm = labels[70].unwrap();
+        assert_ne!(calm.volatility, VolatilityRegime::High);
+        assert_eq!(calm.trend, TrendRegime::Up);
+
+        let stormy = labels[95].unwrap();
+        assert!(stormy.is_high_volatility());
+        assert_eq!(stormy.to_string(), format!("high-vol {}", stormy.trend));
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut detector = RegimeDetector::default();
+        for bar in series(300, 300).iter() {
+            detector.update(bar);
+        }
+        assert!(detector.current().is_some());
+        assert!(detector.realized_volatility().is_some());
+
+        detector.reset();
+        assert_eq!(detector.current(), None);
+        assert_eq!(detector.realized_volatility(), None);
+    }
+}
@@ -0,0 +1,92 @@
+//! Benchmarks for parameter sweeps run in parallel, one backtest per thread.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::collections::HashMap;
+use std::thread;
+use trading_backtest::{BacktestConfig, BacktestEngine, BacktestReport};
+use trading_core::types::Bar;
+use trading_strategies::{MACrossoverConfig, MACrossoverStrategy, MaType};
+
+const SWEEP_SIZE: usize = 16;
+
+fn generate_test_data(size: usize) -> HashMap<String, Vec<Bar>> {
+    let bars = (0..size)
+        .map(|i| {
+            let price = 100.0 + (i as f64 * 0.05).sin() * 10.0 + i as f64 * 0.01;
+            Bar::new(
+                i as i64 * 86_400_000,
+                price,
+                price + 1.0,
+                price - 1.0,
+                price + 0.5,
+                1_000_000.0,
+            )
+        })
+        .collect();
+    HashMap::from([("TEST".to_string(), bars)])
+}
+
+/// Fast/slow periods of the `index`th point of the sweep.
+fn periods(index: usize) -> (usize, usize) {
+    let fast = 5 + (index % 4) * 5;
+    let slow = fast * 2 + (index / 4) * 10;
+    (fast, slow)
+}
+
+fn run_one(index: usize, data: HashMap<String, Vec<Bar>>) -> BacktestReport {
+    let (fast_period, slow_period) = periods(index);
+    let mut strategy = MACrossoverStrategy::new(MACrossoverConfig {
+        symbols: vec!["TEST".to_string()],
+        fast_period,
+        slow_period,
+        ma_type: MaType::Ema,
+        signal_threshold: 0.0,
+    });
+    let engine = BacktestEngine::new(BacktestConfig {
+        seed: Some(42),
+        ..BacktestConfig::default()
+    });
+
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(engine.run(&mut strategy, data))
+}
+
+fn benchmark_parallel_sweep(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ParameterSweep");
+    group.sample_size(10);
+
+    for size in [1000, 5000].iter() {
+        let data = generate_test_data(*size);
+
+        group.bench_with_input(BenchmarkId::new("sequential", size), &data, |b, data| {
+            b.iter(|| {
+                for index in 0..SWEEP_SIZE {
+                    black_box(run_one(index, data.clone()));
+                }
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("threads_16", size), &data, |b, data| {
+            b.iter(|| {
+                thread::scope(|scope| {
+                    let handles: Vec<_> = (0..SWEEP_SIZE)
+                        .map(|index| {
+                            let data = data.clone();
+                            scope.spawn(move || run_one(index, data))
+                        })
+                        .collect();
+                    for handle in handles {
+                        black_box(handle.join().unwrap());
+                    }
+                })
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_parallel_sweep);
+criterion_main!(benches);
@@ -0,0 +1,317 @@
+//! Comparison of a backtest against a saved baseline report, for CI gates.
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::{BacktestReport, BacktestStats};
+
+/// A headline metric checked against the baseline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BaselineMetric {
+    /// Total return, in percentage points
+    TotalReturn,
+    /// Annualized return, in percentage points
+    AnnualizedReturn,
+    /// Maximum drawdown, in percentage points
+    MaxDrawdown,
+    /// Sharpe ratio
+    Sharpe,
+    /// Sortino ratio
+    Sortino,
+    /// Number of trades
+    Trades,
+    /// Win rate, in percentage points
+    WinRate,
+    /// Profit factor
+    ProfitFactor,
+}
+
+impl BaselineMetric {
+    /// Every metric, in report order.
+    pub const ALL: [BaselineMetric; 8] = [
+        BaselineMetric::TotalReturn,
+        BaselineMetric::AnnualizedReturn,
+        BaselineMetric::MaxDrawdown,
+        BaselineMetric::Sharpe,
+        BaselineMetric::Sortino,
+        BaselineMetric::Trades,
+        BaselineMetric::WinRate,
+        BaselineMetric::ProfitFactor,
+    ];
+
+    /// Value of the metric in `stats`.
+    pub fn value(&self, stats: &BacktestStats) -> f64 {
+        let decimal = |d: Decimal| d.try_into().unwrap_or(0.0);
+        match self {
+            BaselineMetric::TotalReturn => decimal(stats.total_return_pct),
+            BaselineMetric::AnnualizedReturn => decimal(stats.annualized_return_pct),
+            BaselineMetric::MaxDrawdown => decimal(stats.max_drawdown_pct),
+            BaselineMetric::Sharpe => stats.sharpe_ratio,
+            BaselineMetric::Sortino => stats.sortino_ratio,
+            BaselineMetric::Trades => stats.total_trades as f64,
+            BaselineMetric::WinRate => decimal(stats.win_rate_pct),
+            BaselineMetric::ProfitFactor => decimal(stats.profit_factor),
+        }
+    }
+
+    /// Whether a larger value is better.
+    pub fn higher_is_better(&self) -> bool {
+        !matches!(self, BaselineMetric::MaxDrawdown)
+    }
+}
+
+impl std::fmt::Display for BaselineMetric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            BaselineMetric::TotalReturn => "return",
+            BaselineMetric::AnnualizedReturn => "annualized_return",
+            BaselineMetric::MaxDrawdown => "drawdown",
+            BaselineMetric::Sharpe => "sharpe",
+            BaselineMetric::Sortino => "sortino",
+            BaselineMetric::Trades => "trades",
+            BaselineMetric::WinRate => "win_rate",
+            BaselineMetric::ProfitFactor => "profit_factor",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl std::str::FromStr for BaselineMetric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        BaselineMetric::ALL
+            .into_iter()
+            .find(|m| m.to_string() == s.to_lowercase())
+            .ok_or_else(|| format!("Invalid baseline metric: {}", s))
+    }
+}
+
+/// Tolerance for one metric, parsed from `metric=value`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MetricTolerance {
+    /// Metric the tolerance applies to
+    pub metric: BaselineMetric,
+    /// Largest allowed absolute difference
+    pub tolerance: f64,
+}
+
+impl std::str::FromStr for MetricTolerance {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (metric, tolerance) = s
+            .split_once('=')
+            .ok_or_else(|| format!("Expected metric=tolerance, got {}", s))?;
+        let tolerance: f64 = tolerance
+            .trim()
+            .parse()
+            .map_err(|_| format!("Invalid tolerance: {}", tolerance))?;
+        if tolerance < 0.0 {
+            return Err(format!("Tolerance must not be negative: {}", tolerance));
+        }
+        Ok(Self {
+            metric: metric.trim().parse()?,
+            tolerance,
+        })
+    }
+}
+
+/// Allowed absolute difference of each metric from the baseline.
+#[derive(Debug, Clone)]
+pub struct BaselineTolerances {
+    tolerances: BTreeMap<BaselineMetric, f64>,
+}
+
+impl Default for BaselineTolerances {
+    /// Tight enough to catch any behavioral change, loose enough to ignore
+    /// floating-point noise; trade counts must match exactly.
+    fn default() -> Self {
+        let tolerances = BaselineMetric::ALL
+            .into_iter()
+            .map(|m| {
+                let tolerance = if m == BaselineMetric::Trades {
+                    0.0
+                } else {
+                    0.01
+                };
+                (m, tolerance)
+            })
+            .collect();
+        Self { tolerances }
+    }
+}
+
+impl BaselineTolerances {
+    /// Override the tolerance of one metric.
+    pub fn with(mut self, tolerance: MetricTolerance) -> Self {
+        self.tolerances
+            .insert(tolerance.metric, tolerance.tolerance);
+        self
+    }
+
+    /// Tolerance of `metric`.
+    pub fn get(&self, metric: BaselineMetric) -> f64 {
+        self.tolerances.get(&metric).copied().unwrap_or(0.0)
+    }
+}
+
+/// One metric of the run next to its baseline value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricDiff {
+    /// Metric compared
+    pub metric: BaselineMetric,
+    /// Value in the baseline report
+    pub baseline: f64,
+    /// Value in this run
+    pub current: f64,
+    /// Largest allowed absolute difference
+    pub tolerance: f64,
+}
+
+impl MetricDiff {
+    /// Whether the difference is within tolerance.
+    pub fn within_tolerance(&self) -> bool {
+        // A small epsilon so a tolerance of 0 still accepts rounding noise
+        (self.current - self.baseline).abs() <= self.tolerance + 1e-9
+    }
+
+    /// Whether the run is worse than the baseline beyond tolerance.
+    pub fn is_regression(&self) -> bool {
+        let worse = if self.metric.higher_is_better() {
+            self.current < self.baseline
+        } else {
+            self.current > self.baseline
+        };
+        worse && !self.within_tolerance()
+    }
+}
+
+/// A run's headline metrics diffed against a baseline report.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BaselineDiff {
+    /// One entry per metric
+    pub metrics: Vec<MetricDiff>,
+}
+
+impl BaselineDiff {
+    /// Compare `current` against `baseline`.
+    pub fn compare(
+        baseline: &BacktestReport,
+        current: &BacktestReport,
+        tolerances: &BaselineTolerances,
+    ) -> Self {
+        let metrics = BaselineMetric::ALL
+            .into_iter()
+            .map(|metric| MetricDiff {
+                metric,
+                baseline: metric.value(&baseline.stats),
+                current: metric.value(&current.stats),
+                tolerance: tolerances.get(metric),
+            })
+            .collect();
+        Self { metrics }
+    }
+
+    /// Whether every metric is within tolerance. Improvements beyond
+    /// tolerance fail too: the baseline no longer describes the strategy
+    /// and should be regenerated.
+    pub fn passed(&self) -> bool {
+        self.metrics.iter().all(MetricDiff::within_tolerance)
+    }
+
+    /// Metrics that got worse beyond tolerance.
+    pub fn regressions(&self) -> impl Iterator<Item = &MetricDiff> {
+        self.metrics.iter().filter(|m| m.is_regression())
+    }
+
+    /// Render as a text table.
+    pub fn render(&self) -> String {
+        let mut s = String::new();
+        s.push_str("BASELINE COMPARISON\n");
+        s.push_str("───────────────────────────────────────────────────────────\n");
+        s.push_str(&format!(
+            "  {:<18} {:>12} {:>12} {:>10} {:>10}\n",
+            "Metric", "Baseline", "Current", "Delta", "Tolerance"
+        ));
+        for m in &self.metrics {
+            let status = if m.within_tolerance() {
+                ""
+            } else if m.is_regression() {
+                "  REGRESSED"
+            } else {
+                "  CHANGED"
+            };
+            s.push_str(&format!(
+                "  {:<18} {:>12.4} {:>12.4} {:>+10.4} {:>10.4}{}\n",
+                m.metric.to_string(),
+                m.baseline,
+                m.current,
+                m.current - m.baseline,
+                m.tolerance,
+                status
+            ));
+        }
+        s.push_str(if self.passed() {
+            "  Result: PASS\n"
+        } else {
+            "  Result: FAIL\n"
+        });
+        s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BacktestConfig;
+    use rust_decimal_macros::dec;
+    use trading_core::types::Portfolio;
+
+    fn report(return_pct: Decimal, sharpe: f64, trades: usize) -> BacktestReport {
+        let mut stats = BacktestStats::new(dec!(100000));
+        stats.total_return_pct = return_pct;
+        stats.sharpe_ratio = sharpe;
+        stats.total_trades = trades;
+        BacktestReport {
+            config: BacktestConfig::default(),
+            stats,
+            final_portfolio: Portfolio::new(dec!(100000)),
+            seed: 0,
+            scenarios: Vec::new(),
+            provenance: None,
+        }
+    }
+
+    #[test]
+    fn test_diff_against_baseline() {
+        let baseline = report(dec!(12.5), 1.2, 40);
+        let tolerances = BaselineTolerances::default();
+
+        let same = BaselineDiff::compare(&baseline, &report(dec!(12.5), 1.2, 40), &tolerances);
+        assert!(same.passed());
+
+        let worse = BaselineDiff::compare(&baseline, &report(dec!(11.0), 1.2, 40), &tolerances);
+        assert!(!worse.passed());
+        let regressed: Vec<_> = worse.regressions().map(|m| m.metric).collect();
+        assert_eq!(regressed, vec![BaselineMetric::TotalReturn]);
+
+        let loose = tolerances.with("return=2".parse().unwrap());
+        assert!(BaselineDiff::compare(&baseline, &report(dec!(11.0), 1.2, 40), &loose).passed());
+
+        // More trades is neither better nor worse, but still a change
+        let changed = BaselineDiff::compare(&baseline, &report(dec!(12.5), 1.2, 41), &loose);
+        assert!(!changed.passed());
+    }
+
+    #[test]
+    fn test_parse_tolerance() {
+        let t: MetricTolerance = "sharpe=0.05".parse().unwrap();
+        assert_eq!(t.metric, BaselineMetric::Sharpe);
+        assert_eq!(t.tolerance, 0.05);
+        assert!("sharpe".parse::<MetricTolerance>().is_err());
+        assert!("bogus=1".parse::<MetricTolerance>().is_err());
+    }
+}
@@ -0,0 +1,105 @@
+//! Checkpoints for resuming interrupted backtests.
+//!
+//! A checkpoint captures everything the engine mutates while running: the
+//! broker's portfolio and working orders, statistics, open positions and
+//! pending events. Strategies are opaque trait objects, so their state is
+//! rebuilt on resume by replaying the bars already processed through
+//! `Strategy::act`, discarding the actions, and the recorded fills through
+//! `Strategy::on_fill` between them.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use trading_core::error::{TradingError, TradingResult};
+use trading_core::types::{Order, Portfolio};
+use trading_risk::SymbolBlacklist;
+use uuid::Uuid;
+
+use crate::events::{BracketExit, Event};
+use crate::rng::SimulationRng;
+use crate::statistics::BacktestStats;
+
+/// When and where to write checkpoints.
+#[derive(Debug, Clone)]
+pub struct CheckpointConfig {
+    /// Checkpoint file, overwritten on every save
+    pub path: PathBuf,
+    /// Bars processed between checkpoints
+    pub every_bars: usize,
+}
+
+impl CheckpointConfig {
+    /// Create a checkpoint config.
+    pub fn new(path: impl Into<PathBuf>, every_bars: usize) -> Self {
+        Self {
+            path: path.into(),
+            every_bars: every_bars.max(1),
+        }
+    }
+}
+
+/// Saved engine state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestCheckpoint {
+    /// When the checkpoint was written
+    pub created_at: DateTime<Utc>,
+    /// Hash of config, strategy and data; resuming requires a match
+    pub fingerprint: String,
+    /// Number of bars processed
+    pub cursor: usize,
+    /// Timestamp of the last processed bar (ms)
+    pub last_timestamp: i64,
+    /// Broker portfolio
+    pub portfolio: Portfolio,
+    /// Orders submitted but not yet filled
+    pub orders: Vec<Order>,
+    /// Statistics so far
+    pub stats: BacktestStats,
+    /// Open positions: symbol -> (entry_price, quantity)
+    pub open_positions: HashMap<String, (Decimal, Decimal)>,
+    /// Symbols with an order between signal and fill
+    pub in_flight: Vec<String>,
+    /// Events scheduled after the last processed bar
+    pub pending_events: Vec<(i64, Event)>,
+    /// Symbols blacklisted after repeated rejections
+    pub blacklist: SymbolBlacklist,
+    /// Slippage noise generator position
+    pub slippage_rng: SimulationRng,
+    /// Fill jitter generator position
+    pub fill_rng: SimulationRng,
+    /// Bracket exits to place once an entry fills: order id -> (stop loss, take profit)
+    #[serde(default)]
+    pub brackets: HashMap<Uuid, (Option<Decimal>, Option<Decimal>)>,
+    /// Bracket exits resting at the broker, by order id
+    #[serde(default)]
+    pub exits: HashMap<Uuid, BracketExit>,
+    /// Orders reported to the strategy as filled, each after how many bars
+    /// and snapshots it had been given
+    #[serde(default)]
+    pub fills: Vec<(usize, Order)>,
+}
+
+impl BacktestCheckpoint {
+    /// Write the checkpoint, replacing any previous one atomically.
+    pub fn save(&self, path: impl AsRef<Path>) -> TradingResult<()> {
+        let path = path.as_ref();
+        let json =
+            serde_json::to_string(self).map_err(|e| TradingError::Serialization(e.to_string()))?;
+
+        // Write then rename so an interrupted save never corrupts the last checkpoint
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Read a checkpoint.
+    pub fn load(path: impl AsRef<Path>) -> TradingResult<Self> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path)?;
+        serde_json::from_str(&json)
+            .map_err(|e| TradingError::Serialization(format!("{}: {}", path.display(), e)))
+    }
+}
@@ -1,17 +1,67 @@
 //! Backtesting engine.
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tracing::{debug, info, warn};
 use trading_broker::PaperBroker;
-use trading_core::traits::{Broker, Strategy};
-use trading_core::types::{Bar, BarSeries, Side, SignalType, Timeframe};
+use trading_core::error::{TradingError, TradingResult};
+use trading_core::traits::{
+    debug_check_metadata, Broker, SimulatedClock, Strategy, StrategyAction,
+};
+use trading_core::types::{
+    Bar, Instrument, MarketSnapshot, MultiTimeframeSeries, Order, OrderRequest, OrderType, Side,
+    Signal, SignalType, TimeInForce, Timeframe,
+};
+use trading_data::{dedup_bars, DuplicateBarPolicy};
 use trading_risk::{RiskConfig, RiskManager};
+use trading_runtime::{OrderPipeline, PipelineDecision};
+use uuid::Uuid;
 
+use crate::checkpoint::{BacktestCheckpoint, CheckpointConfig};
+use crate::events::{
+    BracketExit, Event, EventQueue, FillEvent, LatencyModel, MarketEvent, OrderEvent, OrdersEvent,
+    SignalEvent,
+};
+use crate::progress::{BacktestProgress, ProgressConfig};
 use crate::report::BacktestReport;
-use crate::statistics::{BacktestStats, TradeRecord};
+use crate::rng::{SimulationRng, FILL_STREAM, SLIPPAGE_STREAM};
+use crate::scenario::{Scenario, ScenarioResult};
+use crate::sim_portfolio::{PortfolioMode, SimPortfolio};
+use crate::statistics::{
+    BacktestStats, OpenPositionRecord, SignalAuditRecord, SignalDecision, TradeReason, TradeRecord,
+};
+use crate::store::config_hash;
+
+/// How positions still open on the last bar are treated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EndOfBacktest {
+    /// Record a synthetic closing trade at the last known price
+    #[default]
+    CloseAtLastPrice,
+    /// Mark open positions to market and count their P&L in trade stats,
+    /// without recording a closing trade
+    MarkToMarket,
+    /// Leave open positions out of the trade statistics entirely
+    ExcludeOpen,
+}
+
+impl std::str::FromStr for EndOfBacktest {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "close" | "close_at_last_price" => Ok(EndOfBacktest::CloseAtLastPrice),
+            "mark" | "mark_to_market" => Ok(EndOfBacktest::MarkToMarket),
+            "exclude" | "exclude_open" => Ok(EndOfBacktest::ExcludeOpen),
+            _ => Err(format!("Invalid end-of-backtest mode: {}", s)),
+        }
+    }
+}
 
 /// Backtest configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,6 +74,37 @@ pub struct BacktestConfig {
     pub slippage_pct: Decimal,
     /// Risk configuration
     pub risk_config: RiskConfig,
+    /// Handling of positions still open at the end of the run
+    #[serde(default)]
+    pub end_of_backtest: EndOfBacktest,
+    /// Resolution of bars sharing a symbol and timestamp
+    #[serde(default)]
+    pub duplicate_bars: DuplicateBarPolicy,
+    /// Signal-to-order and order-to-fill delays
+    #[serde(default)]
+    pub latency: LatencyModel,
+    /// Standard deviation of random extra slippage, in percent
+    #[serde(default)]
+    pub slippage_noise_pct: Decimal,
+    /// Seed for stochastic models (random if unset; the seed used is
+    /// recorded in the report)
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Timeframe of the input bars; strategies' higher timeframes are
+    /// resampled from it
+    #[serde(default)]
+    pub timeframe: Timeframe,
+    /// Arithmetic of the per-bar portfolio accounting
+    #[serde(default)]
+    pub portfolio_mode: PortfolioMode,
+    /// Instruments by symbol, for rounding orders and contract multipliers
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub instruments: HashMap<String, Instrument>,
+    /// Attach the risk manager's stop loss to signal entries, so a bar
+    /// trading through it closes the position (off in configs saved
+    /// before stops were simulated)
+    #[serde(default)]
+    pub enforce_stops: bool,
 }
 
 impl Default for BacktestConfig {
@@ -33,19 +114,85 @@ impl Default for BacktestConfig {
             commission: Decimal::ZERO,
             slippage_pct: dec!(0.05),
             risk_config: RiskConfig::default(),
+            end_of_backtest: EndOfBacktest::default(),
+            duplicate_bars: DuplicateBarPolicy::default(),
+            latency: LatencyModel::default(),
+            slippage_noise_pct: Decimal::ZERO,
+            seed: None,
+            timeframe: Timeframe::default(),
+            portfolio_mode: PortfolioMode::default(),
+            instruments: HashMap::new(),
+            enforce_stops: true,
+        }
+    }
+}
+
+impl BacktestConfig {
+    /// The default configuration with settings of the same name replaced
+    /// by `overrides`, as the language bindings take them.
+    pub fn from_overrides(
+        overrides: serde_json::Map<String, serde_json::Value>,
+    ) -> TradingResult<Self> {
+        let mut settings = serde_json::to_value(Self::default())
+            .map_err(|e| TradingError::Serialization(e.to_string()))?;
+        if let Some(settings) = settings.as_object_mut() {
+            settings.extend(overrides.clone());
+        }
+        let config: Self = serde_json::from_value(settings)
+            .map_err(|e| TradingError::Config(format!("Invalid backtest config: {}", e)))?;
+
+        // Unknown settings would otherwise be silently ignored. Empty maps
+        // and lists are let through, as settings left empty aren't serialized
+        let known = serde_json::to_value(&config)
+            .map_err(|e| TradingError::Serialization(e.to_string()))?;
+        let empty = |value: &serde_json::Value| match value {
+            serde_json::Value::Object(map) => map.is_empty(),
+            serde_json::Value::Array(items) => items.is_empty(),
+            _ => false,
+        };
+        match overrides
+            .iter()
+            .find(|(key, value)| known.get(key.as_str()).is_none() && !empty(value))
+        {
+            Some((key, _)) => Err(TradingError::Config(format!(
+                "Unknown backtest setting {}",
+                key
+            ))),
+            None => Ok(config),
         }
     }
 }
 
+/// Bars of all symbols in processing order: (timestamp, symbol, bar).
+type Timeline = Vec<(i64, String, Bar)>;
+
 /// Backtesting engine.
 pub struct BacktestEngine {
     config: BacktestConfig,
+    checkpoint: Option<CheckpointConfig>,
+    progress: Option<ProgressConfig>,
 }
 
 impl BacktestEngine {
     /// Create a new backtest engine.
     pub fn new(config: BacktestConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            checkpoint: None,
+            progress: None,
+        }
+    }
+
+    /// Periodically save a checkpoint that a later run can resume from.
+    pub fn with_checkpoints(mut self, checkpoint: CheckpointConfig) -> Self {
+        self.checkpoint = Some(checkpoint);
+        self
+    }
+
+    /// Report progress while running.
+    pub fn with_progress(mut self, progress: ProgressConfig) -> Self {
+        self.progress = Some(progress);
+        self
     }
 
     /// Run a backtest.
@@ -54,162 +201,256 @@ impl BacktestEngine {
         strategy: &mut dyn Strategy,
         data: HashMap<String, Vec<Bar>>,
     ) -> BacktestReport {
-        let broker = PaperBroker::new(self.config.initial_capital)
-            .with_slippage(self.config.slippage_pct)
-            .with_commission(self.config.commission);
-
-        let risk_manager = RiskManager::new(self.config.risk_config.clone());
-
-        let mut stats = BacktestStats::new(self.config.initial_capital);
-        let mut series_map: HashMap<String, BarSeries> = HashMap::new();
-        // Track open positions: symbol -> (entry_price, quantity)
-        let mut open_positions: HashMap<String, (Decimal, Decimal)> = HashMap::new();
-
-        // Initialize bar series
-        for symbol in data.keys() {
-            series_map.insert(
-                symbol.clone(),
-                BarSeries::with_capacity(symbol.clone(), Timeframe::Daily, 500),
-            );
+        let (data, timeline) = self.prepare(data);
+        self.execute(strategy, data, timeline, None).await
+    }
+
+    /// Continue a backtest from a checkpoint.
+    ///
+    /// The strategy should be freshly constructed with the same parameters;
+    /// it is brought up to date by replaying the bars already processed.
+    /// Fails if the config, strategy or data differ from the checkpointed run.
+    pub async fn resume(
+        &self,
+        strategy: &mut dyn Strategy,
+        data: HashMap<String, Vec<Bar>>,
+        checkpoint: BacktestCheckpoint,
+    ) -> TradingResult<BacktestReport> {
+        let (data, timeline) = self.prepare(data);
+
+        if checkpoint.fingerprint != self.fingerprint(strategy, &timeline) {
+            return Err(TradingError::Validation(
+                "Checkpoint was written by a different config, strategy or data set".to_string(),
+            ));
         }
+        if checkpoint.cursor > timeline.len() {
+            return Err(TradingError::Validation(format!(
+                "Checkpoint cursor {} is past the end of the data ({} bars)",
+                checkpoint.cursor,
+                timeline.len()
+            )));
+        }
+
+        Ok(self
+            .execute(strategy, data, timeline, Some(checkpoint))
+            .await)
+    }
+
+    /// Re-run a backtest on data shocked by `scenario` and compare it with
+    /// the unshocked `baseline` run.
+    ///
+    /// `strategy` should be freshly constructed. The baseline's seed is
+    /// reused so stochastic models see the same draws.
+    pub async fn run_scenario(
+        &self,
+        strategy: &mut dyn Strategy,
+        data: &HashMap<String, Vec<Bar>>,
+        scenario: &Scenario,
+        baseline: &BacktestReport,
+    ) -> ScenarioResult {
+        let config = BacktestConfig {
+            seed: Some(baseline.seed),
+            ..self.config.clone()
+        };
+        // No checkpoints: they would overwrite the baseline run's
+        let engine = BacktestEngine::new(config);
+        let (data, timeline) = engine.prepare(scenario.apply(data));
+        let report = engine.execute(strategy, data, timeline, None).await;
+        ScenarioResult::new(scenario, &report, baseline)
+    }
+
+    /// Deduplicate bars and merge all symbols into one time-ordered list.
+    fn prepare(&self, data: HashMap<String, Vec<Bar>>) -> (HashMap<String, Vec<Bar>>, Timeline) {
+        // Collapse duplicate timestamps so no bar is processed twice
+        let data: HashMap<String, Vec<Bar>> = data
+            .into_iter()
+            .map(|(symbol, bars)| {
+                let (bars, _) = dedup_bars(&symbol, bars, self.config.duplicate_bars);
+                (symbol, bars)
+            })
+            .collect();
 
         // Get all timestamps and sort them
-        let mut all_timestamps: Vec<(i64, String, Bar)> = Vec::new();
+        let mut all_timestamps: Timeline = Vec::new();
         for (symbol, bars) in &data {
             for bar in bars {
                 all_timestamps.push((bar.timestamp, symbol.clone(), *bar));
             }
         }
-        all_timestamps.sort_by_key(|(ts, _, _)| *ts);
+        // Symbol breaks ties so runs don't depend on HashMap iteration order
+        all_timestamps.sort_by(|a, b| (a.0, &a.1).cmp(&(b.0, &b.1)));
 
-        // Process bars in chronological order
-        for (timestamp, symbol, bar) in all_timestamps {
-            // Add bar to series
-            if let Some(series) = series_map.get_mut(&symbol) {
-                series.push(bar);
-
-                // Get signal from strategy
-                if let Some(signal) = strategy.on_bar(series) {
-                    // Skip duplicate entries: don't buy if already holding, don't sell if not holding
-                    let already_holding = open_positions.contains_key(&symbol);
-                    let skip = match signal.signal_type {
-                        SignalType::Buy if already_holding => true,
-                        SignalType::Sell | SignalType::CloseLong if !already_holding => true,
-                        _ => false,
-                    };
-
-                    if skip {
-                        // Don't process this signal, but continue processing the bar
-                    } else {
-                        // Evaluate with risk manager
-                        let current_price = Decimal::try_from(bar.close).unwrap_or(dec!(0));
-                        let portfolio = broker.get_account().await.unwrap();
-                        let decision =
-                            risk_manager.evaluate_signal(&portfolio, &signal, current_price);
-
-                        if let Some(order_request) = decision.order() {
-                            // Submit and execute order
-                            if let Ok(order) = broker.submit_order(order_request.clone()).await {
-                                if let Ok(filled) = broker.execute_at_price(order.id, current_price)
-                                {
-                                    let fill_price =
-                                        filled.filled_avg_price.unwrap_or(current_price);
-                                    let fill_qty = filled.filled_quantity;
-
-                                    // Calculate P&L for closing trades
-                                    let pnl = match order_request.side {
-                                        Side::Buy => {
-                                            // Opening a long position
-                                            let entry = open_positions
-                                                .entry(symbol.clone())
-                                                .or_insert((Decimal::ZERO, Decimal::ZERO));
-                                            // Weighted average entry price
-                                            if entry.1 + fill_qty > Decimal::ZERO {
-                                                entry.0 = (entry.0 * entry.1
-                                                    + fill_price * fill_qty)
-                                                    / (entry.1 + fill_qty);
-                                            }
-                                            entry.1 += fill_qty;
-                                            None
-                                        }
-                                        Side::Sell => {
-                                            // Closing (or reducing) a long position
-                                            if let Some(entry) = open_positions.get_mut(&symbol) {
-                                                if entry.1 > Decimal::ZERO {
-                                                    let close_qty = fill_qty.min(entry.1);
-                                                    let trade_pnl =
-                                                        (fill_price - entry.0) * close_qty;
-                                                    entry.1 -= close_qty;
-                                                    if entry.1 <= Decimal::ZERO {
-                                                        open_positions.remove(&symbol);
-                                                    }
-                                                    Some(trade_pnl)
-                                                } else {
-                                                    None
-                                                }
-                                            } else {
-                                                None
-                                            }
-                                        }
-                                    };
-
-                                    // Record trade
-                                    let trade = TradeRecord {
-                                        symbol: symbol.clone(),
-                                        side: order_request.side,
-                                        quantity: fill_qty,
-                                        price: fill_price,
-                                        timestamp: DateTime::from_timestamp_millis(timestamp)
-                                            .unwrap_or_else(Utc::now),
-                                        signal_type: signal.signal_type,
-                                        pnl,
-                                    };
-                                    stats.add_trade(trade);
-                                }
-                            }
-                        }
-                    } // else (not skipped)
+        (data, all_timestamps)
+    }
+
+    /// Identify a run so a checkpoint is only resumed against the same inputs.
+    fn fingerprint(&self, strategy: &dyn Strategy, timeline: &[(i64, String, Bar)]) -> String {
+        config_hash(&serde_json::json!({
+            "config": self.config,
+            "strategy": strategy.name(),
+            "symbols": strategy.symbols(),
+            "warmup_period": strategy.warmup_period(),
+            "bars": timeline.len(),
+            "first": timeline.first().map(|(ts, _, _)| ts),
+            "last": timeline.last().map(|(ts, _, _)| ts),
+        }))
+    }
+
+    async fn execute(
+        &self,
+        strategy: &mut dyn Strategy,
+        data: HashMap<String, Vec<Bar>>,
+        timeline: Timeline,
+        resume: Option<BacktestCheckpoint>,
+    ) -> BacktestReport {
+        let mut sim = Simulation::new(&self.config, data.keys(), strategy.timeframes());
+        let fingerprint = self.fingerprint(strategy, &timeline);
+
+        let mut start = 0;
+        if let Some(checkpoint) = resume {
+            // Rebuild series and strategy state, then restore everything else
+            let fills = &checkpoint.fills;
+            for (index, (timestamp, symbol, bar)) in
+                timeline[..checkpoint.cursor].iter().enumerate()
+            {
+                sim.replay(strategy, symbol, *bar, fills);
+                if timeline
+                    .get(index + 1)
+                    .map_or(true, |(next, _, _)| next != timestamp)
+                {
+                    sim.replay_snapshot(strategy, *timestamp, fills);
                 }
             }
+            // Fills after the last call before the checkpoint
+            Simulation::replay_fills(strategy, sim.calls, fills);
+            start = checkpoint.cursor;
+            info!("Resuming backtest at bar {} of {}", start, timeline.len());
+            sim.restore(&self.config, strategy.name(), checkpoint);
+        }
 
-            // Update prices for all positions
-            let mut prices = HashMap::new();
-            for (sym, bars) in &data {
-                if let Some(b) = bars.iter().find(|b| b.timestamp == timestamp) {
-                    prices.insert(sym.clone(), Decimal::try_from(b.close).unwrap_or(dec!(0)));
+        // Process bars in chronological order
+        for (index, (timestamp, symbol, bar)) in timeline.iter().enumerate().skip(start) {
+            let timestamp = *timestamp;
+            let first = timeline[..index].partition_point(|(ts, _, _)| *ts < timestamp);
+            let last = index + timeline[index..].partition_point(|(ts, _, _)| *ts == timestamp);
+            if index == first || index == start {
+                sim.opening = timeline[first..last]
+                    .iter()
+                    .map(|(_, symbol, bar)| (symbol.clone(), *bar))
+                    .collect();
+            }
+            sim.queue.push(
+                timestamp,
+                Event::Market(MarketEvent {
+                    symbol: symbol.clone(),
+                    bar: *bar,
+                }),
+            );
+            while let Some((due, event)) = sim.queue.pop_due(timestamp) {
+                sim.dispatch(strategy, due, event).await;
+            }
+
+            // Every symbol's bar at this timestamp is in
+            if last == index + 1 {
+                sim.on_snapshot(strategy, timestamp);
+                while let Some((due, event)) = sim.queue.pop_due(timestamp) {
+                    sim.dispatch(strategy, due, event).await;
                 }
             }
-            broker.update_prices(&prices);
+
+            // Mark positions to every symbol's bar at this timestamp
+            sim.mark_to_market(&timeline[first..last]);
 
             // Record equity
-            let portfolio = broker.get_account().await.unwrap();
-            stats.record_equity(timestamp, portfolio.equity);
-        }
+            let equity = sim.equity();
+            sim.stats.record_equity(timestamp, equity);
+            if let Some(progress) = &self.progress {
+                progress.report(BacktestProgress {
+                    bars_processed: index + 1,
+                    total_bars: timeline.len(),
+                    timestamp,
+                    equity,
+                });
+            }
 
-        // Close any remaining open positions at last known price for complete P&L
-        for (symbol, (entry_price, quantity)) in &open_positions {
-            if *quantity > Decimal::ZERO {
-                // Find last bar price for this symbol
-                if let Some(bars) = data.get(symbol) {
-                    if let Some(last_bar) = bars.last() {
-                        let close_price = Decimal::try_from(last_bar.close).unwrap_or(dec!(0));
-                        let pnl = (close_price - entry_price) * quantity;
-                        let trade = TradeRecord {
-                            symbol: symbol.clone(),
-                            side: Side::Sell,
-                            quantity: *quantity,
-                            price: close_price,
-                            timestamp: DateTime::from_timestamp_millis(last_bar.timestamp)
-                                .unwrap_or_else(Utc::now),
-                            signal_type: SignalType::CloseLong,
-                            pnl: Some(pnl),
-                        };
-                        stats.add_trade(trade);
+            if let Some(checkpoint) = &self.checkpoint {
+                let cursor = index + 1;
+                if cursor % checkpoint.every_bars == 0 {
+                    let state = sim.checkpoint(&fingerprint, cursor, timestamp).await;
+                    // A failed checkpoint shouldn't abort a long run
+                    if let Err(e) = state.save(&checkpoint.path) {
+                        warn!("Failed to write checkpoint {:?}: {}", checkpoint.path, e);
                     }
                 }
             }
         }
 
+        // Orders still in flight when the data ends never reach the market
+        for (due, event) in sim.queue.drain() {
+            debug!(
+                "Dropping {} event due at {} after end of data",
+                event.symbol(),
+                due
+            );
+        }
+
+        sim.sync_broker();
+        if let Some(error) = sim.stats.float_equity_error {
+            if error > FLOAT_EQUITY_TOLERANCE {
+                warn!(
+                    "f64 portfolio equity drifted up to {:.4} from the Decimal equity",
+                    error
+                );
+            }
+        }
+        let Simulation {
+            broker,
+            mut stats,
+            open_positions,
+            seed,
+            ..
+        } = sim;
+
+        // Settle positions still open on the last bar
+        for (symbol, (entry_price, quantity)) in &open_positions {
+            if *quantity <= Decimal::ZERO {
+                continue;
+            }
+            // Find last bar price for this symbol
+            let Some(last_bar) = data.get(symbol).and_then(|bars| bars.last()) else {
+                continue;
+            };
+            let close_price = Decimal::try_from(last_bar.close).unwrap_or(dec!(0));
+            let pnl = (close_price - entry_price) * quantity;
+            let timestamp =
+                DateTime::from_timestamp_millis(last_bar.timestamp).unwrap_or_else(Utc::now);
+
+            stats.add_open_position(
+                OpenPositionRecord {
+                    symbol: symbol.clone(),
+                    quantity: *quantity,
+                    entry_price: *entry_price,
+                    mark_price: close_price,
+                    unrealized_pnl: pnl,
+                    timestamp,
+                },
+                self.config.end_of_backtest == EndOfBacktest::MarkToMarket,
+            );
+
+            if self.config.end_of_backtest == EndOfBacktest::CloseAtLastPrice {
+                stats.add_trade(TradeRecord {
+                    symbol: symbol.clone(),
+                    side: Side::Sell,
+                    quantity: *quantity,
+                    price: close_price,
+                    timestamp,
+                    signal_type: SignalType::CloseLong,
+                    pnl: Some(pnl),
+                    reason: TradeReason::EndOfBacktest,
+                });
+            }
+        }
+
         // Final statistics
         let final_portfolio = broker.get_account().await.unwrap();
         stats.finalize(&final_portfolio);
@@ -218,14 +459,810 @@ impl BacktestEngine {
             config: self.config.clone(),
             stats,
             final_portfolio,
+            seed,
+            scenarios: Vec::new(),
+            provenance: None,
+        }
+    }
+}
+
+/// Equity drift, in account currency, beyond which validate mode warns.
+const FLOAT_EQUITY_TOLERANCE: f64 = 0.01;
+
+/// Mutable state of a single backtest run.
+struct Simulation {
+    broker: PaperBroker,
+    /// Simulated time, moved to each event as it is handled
+    clock: Arc<SimulatedClock>,
+    portfolio_mode: PortfolioMode,
+    /// `f64` mirror of the broker's account, unused in `Decimal` mode
+    sim_portfolio: SimPortfolio,
+    pipeline: OrderPipeline,
+    latency: LatencyModel,
+    queue: EventQueue,
+    stats: BacktestStats,
+    series_map: HashMap<String, MultiTimeframeSeries>,
+    /// Each symbol's bar at the current timestamp, processed or not
+    opening: HashMap<String, Bar>,
+    /// Open positions: symbol -> (entry_price, quantity)
+    open_positions: HashMap<String, (Decimal, Decimal)>,
+    /// Symbols with an order between signal and fill
+    in_flight: HashSet<String>,
+    /// Bracket exits to place once an entry fills: order id -> (stop loss, take profit)
+    brackets: HashMap<Uuid, (Option<Decimal>, Option<Decimal>)>,
+    /// Bracket exits resting at the broker, by order id
+    exits: HashMap<Uuid, BracketExit>,
+    /// Protect signal entries with the risk manager's stop loss
+    enforce_stops: bool,
+    /// Whether bars are shorter than a trading session
+    intraday: bool,
+    /// Session date of each symbol's latest bar, on intraday bars
+    sessions: HashMap<String, NaiveDate>,
+    /// Bars and snapshots handed to the strategy so far
+    calls: usize,
+    /// Orders reported to the strategy as filled, each with `calls` at the
+    /// time, so a resumed run can report them again in the same place
+    fills: Vec<(usize, Order)>,
+    slippage_noise_pct: f64,
+    seed: u64,
+    slippage_rng: SimulationRng,
+    fill_rng: SimulationRng,
+}
+
+impl Simulation {
+    fn new<'a>(
+        config: &BacktestConfig,
+        symbols: impl Iterator<Item = &'a String>,
+        timeframes: &[Timeframe],
+    ) -> Self {
+        let clock = Arc::new(SimulatedClock::default());
+        let broker = PaperBroker::new(config.initial_capital)
+            .with_slippage(config.slippage_pct)
+            .with_commission(config.commission)
+            .with_instruments(config.instruments.clone())
+            .with_clock(clock.clone());
+
+        let series_map = symbols
+            .map(|symbol| {
+                (
+                    symbol.clone(),
+                    MultiTimeframeSeries::new(symbol.clone(), config.timeframe, 500, timeframes),
+                )
+            })
+            .collect();
+
+        let rng = config
+            .seed
+            .map(SimulationRng::new)
+            .unwrap_or_else(SimulationRng::from_entropy);
+
+        Self {
+            broker,
+            clock,
+            portfolio_mode: config.portfolio_mode,
+            sim_portfolio: SimPortfolio::new(config.initial_capital.try_into().unwrap_or(0.0))
+                .with_instruments(&config.instruments),
+            pipeline: OrderPipeline::new(
+                RiskManager::new(config.risk_config.clone())
+                    .with_periods_per_year(config.timeframe.periods_per_year()),
+            )
+            .with_instruments(config.instruments.clone()),
+            latency: config.latency,
+            queue: EventQueue::new(),
+            stats: BacktestStats::new(config.initial_capital)
+                .with_periods_per_year(config.timeframe.periods_per_year()),
+            series_map,
+            opening: HashMap::new(),
+            open_positions: HashMap::new(),
+            in_flight: HashSet::new(),
+            brackets: HashMap::new(),
+            exits: HashMap::new(),
+            enforce_stops: config.enforce_stops,
+            intraday: config.timeframe.is_intraday(),
+            sessions: HashMap::new(),
+            calls: 0,
+            fills: Vec::new(),
+            slippage_noise_pct: config.slippage_noise_pct.try_into().unwrap_or(0.0),
+            seed: rng.seed(),
+            slippage_rng: rng.fork(SLIPPAGE_STREAM),
+            fill_rng: rng.fork(FILL_STREAM),
+        }
+    }
+
+    /// Feed an already-processed bar to the strategy, discarding actions.
+    fn replay(
+        &mut self,
+        strategy: &mut dyn Strategy,
+        symbol: &str,
+        bar: Bar,
+        fills: &[(usize, Order)],
+    ) {
+        self.new_session(symbol, &bar);
+        if let Some(series) = self.series_map.get_mut(symbol) {
+            series.push(bar);
+            Self::replay_fills(strategy, self.calls, fills);
+            strategy.act(series);
+            self.calls += 1;
+        }
+    }
+
+    /// Feed an already-processed snapshot to the strategy, discarding actions.
+    fn replay_snapshot(&mut self, strategy: &mut dyn Strategy, now: i64, fills: &[(usize, Order)]) {
+        Self::replay_fills(strategy, self.calls, fills);
+        strategy.on_snapshot(&MarketSnapshot::new(now, self.series_map.values()));
+        self.calls += 1;
+    }
+
+    /// Report the checkpointed fills that came after `calls` strategy calls.
+    fn replay_fills(strategy: &mut dyn Strategy, calls: usize, fills: &[(usize, Order)]) {
+        let from = fills.partition_point(|(at, _)| *at < calls);
+        let to = fills.partition_point(|(at, _)| *at <= calls);
+        for (_, order) in &fills[from..to] {
+            strategy.on_fill(order);
+        }
+    }
+
+    /// Restore broker, stats and pending events from a checkpoint.
+    ///
+    /// The risk manager's trade history is rebuilt from the restored trades.
+    fn restore(&mut self, config: &BacktestConfig, strategy: &str, checkpoint: BacktestCheckpoint) {
+        self.sim_portfolio = SimPortfolio::from_portfolio(&checkpoint.portfolio)
+            .with_instruments(&config.instruments);
+        self.broker.restore(checkpoint.portfolio, checkpoint.orders);
+        self.pipeline = OrderPipeline::new(
+            RiskManager::new(config.risk_config.clone())
+                .with_periods_per_year(config.timeframe.periods_per_year()),
+        )
+        .with_instruments(config.instruments.clone())
+        .with_blacklist(checkpoint.blacklist);
+        self.stats = checkpoint.stats;
+        for pnl in self.stats.trades.iter().filter_map(|t| t.pnl) {
+            self.pipeline.record_closed_trade(strategy, pnl);
+        }
+        self.open_positions = checkpoint.open_positions;
+        self.in_flight = checkpoint.in_flight.into_iter().collect();
+        self.brackets = checkpoint.brackets;
+        self.exits = checkpoint.exits;
+        self.fills = checkpoint.fills;
+        for (due, event) in checkpoint.pending_events {
+            self.queue.push(due, event);
+        }
+        self.seed = checkpoint.slippage_rng.seed();
+        self.slippage_rng = checkpoint.slippage_rng;
+        self.fill_rng = checkpoint.fill_rng;
+        self.clock.advance_to(checkpoint.last_timestamp);
+    }
+
+    /// Capture the current state.
+    async fn checkpoint(
+        &self,
+        fingerprint: &str,
+        cursor: usize,
+        timestamp: i64,
+    ) -> BacktestCheckpoint {
+        self.sync_broker();
+        BacktestCheckpoint {
+            created_at: Utc::now(),
+            fingerprint: fingerprint.to_string(),
+            cursor,
+            last_timestamp: timestamp,
+            portfolio: self.broker.portfolio_snapshot(),
+            orders: self.broker.get_open_orders().await.unwrap_or_default(),
+            stats: self.stats.clone(),
+            open_positions: self.open_positions.clone(),
+            in_flight: self.in_flight.iter().cloned().collect(),
+            pending_events: self.queue.pending(),
+            blacklist: self.pipeline.blacklist().clone(),
+            slippage_rng: self.slippage_rng.clone(),
+            fill_rng: self.fill_rng.clone(),
+            brackets: self.brackets.clone(),
+            exits: self.exits.clone(),
+            fills: self.fills.clone(),
+        }
+    }
+
+    async fn dispatch(&mut self, strategy: &mut dyn Strategy, now: i64, event: Event) {
+        self.clock.advance_to(now);
+        match event {
+            Event::Market(e) => self.on_market(strategy, now, e).await,
+            Event::Signal(e) => self.on_signal(now, e).await,
+            Event::Orders(e) => self.on_orders(now, e).await,
+            Event::Order(e) => self.on_order(now, e).await,
+            Event::Fill(e) => self.on_fill(strategy, now, e).await,
+        }
+    }
+
+    /// Mark positions to the closes of `bars`.
+    fn mark_to_market(&mut self, bars: &[(i64, String, Bar)]) {
+        if self.portfolio_mode != PortfolioMode::Decimal {
+            for (_, symbol, bar) in bars {
+                self.sim_portfolio.update_price(symbol, bar.close);
+            }
+        }
+        if self.portfolio_mode != PortfolioMode::Float {
+            let prices = bars
+                .iter()
+                .map(|(_, symbol, bar)| {
+                    let price = Decimal::try_from(bar.close).unwrap_or(dec!(0));
+                    (symbol.clone(), price)
+                })
+                .collect();
+            self.broker.update_prices(&prices);
+        }
+        self.check_float_equity();
+    }
+
+    /// Current equity, from the `f64` portfolio in `Float` mode.
+    fn equity(&self) -> Decimal {
+        match self.portfolio_mode {
+            PortfolioMode::Float => {
+                Decimal::try_from(self.sim_portfolio.equity()).unwrap_or_default()
+            }
+            PortfolioMode::Decimal | PortfolioMode::Validate => self.broker.equity(),
+        }
+    }
+
+    /// In `Validate` mode, record how far the `f64` equity has drifted.
+    fn check_float_equity(&mut self) {
+        if self.portfolio_mode != PortfolioMode::Validate {
+            return;
+        }
+        let exact: f64 = self.broker.equity().try_into().unwrap_or(0.0);
+        let error = (self.sim_portfolio.equity() - exact).abs();
+        let max = self.stats.float_equity_error.get_or_insert(0.0);
+        *max = max.max(error);
+    }
+
+    /// In `Float` mode the broker's positions aren't marked every bar; bring
+    /// them up to date before anything reads the broker's account.
+    fn sync_broker(&self) {
+        if self.portfolio_mode == PortfolioMode::Float {
+            self.broker.update_prices(&self.sim_portfolio.prices());
+        }
+    }
+
+    /// Latest close for a symbol.
+    fn last_price(&self, symbol: &str) -> Option<Decimal> {
+        let bar = self.series_map.get(symbol)?.primary().last()?;
+        Decimal::try_from(bar.close).ok()
+    }
+
+    /// Price of `symbol` in effect at `now`: the open of its bar starting
+    /// then if that bar hasn't been processed yet, else the latest close.
+    fn price_at(&self, symbol: &str, now: i64) -> Option<Decimal> {
+        let seen = self.series_map.get(symbol)?.primary().last();
+        match self.opening.get(symbol) {
+            Some(bar) if bar.timestamp == now && seen.map_or(true, |b| b.timestamp < now) => {
+                Decimal::try_from(bar.open).ok()
+            }
+            _ => self.last_price(symbol),
+        }
+    }
+
+    async fn on_market(&mut self, strategy: &mut dyn Strategy, now: i64, event: MarketEvent) {
+        if !self.series_map.contains_key(&event.symbol) {
+            return;
+        }
+        // Day orders last until the end of the session they trade in. On
+        // daily bars orders go in after the close, so that is the next bar
+        if self.new_session(&event.symbol, &event.bar) {
+            self.expire_day_orders(&event.symbol);
+        }
+        self.fill_resting(strategy, now, &event.symbol, &event.bar)
+            .await;
+        if !self.intraday {
+            self.expire_day_orders(&event.symbol);
+        }
+        let Some(series) = self.series_map.get_mut(&event.symbol) else {
+            return;
+        };
+        series.push(event.bar);
+        self.pipeline.on_bar(&event.symbol, &event.bar);
+
+        let action = strategy.act(series);
+        self.calls += 1;
+        // A forced exit takes the place of whatever the strategy wanted
+        if let Some(signal) = self.time_exit(&event.symbol, &event.bar).await {
+            let price = Decimal::try_from(event.bar.close).unwrap_or(dec!(0));
+            self.queue
+                .push(now, Event::Signal(SignalEvent { signal, price }));
+            return;
+        }
+        self.queue_action(strategy, now, action);
+    }
+
+    /// Whether `bar` opens a new intraday session for the symbol.
+    fn new_session(&mut self, symbol: &str, bar: &Bar) -> bool {
+        if !self.intraday {
+            return false;
+        }
+        let Some(date) = DateTime::from_timestamp_millis(bar.timestamp).map(|t| t.date_naive())
+        else {
+            return false;
+        };
+        self.sessions
+            .insert(symbol.to_string(), date)
+            .is_some_and(|previous| previous < date)
+    }
+
+    /// Expire the symbol's unfilled day orders, with any bracket exits
+    /// they would have placed.
+    fn expire_day_orders(&mut self, symbol: &str) {
+        for order in self.broker.expire_day_orders(symbol) {
+            debug!("{} {} order expired unfilled", symbol, order.side);
+            self.brackets.remove(&order.id);
+        }
+    }
+
+    /// Forced exit for a symbol's position on a new bar, if the risk
+    /// config's time exit calls for one.
+    async fn time_exit(&mut self, symbol: &str, bar: &Bar) -> Option<Signal> {
+        if !self.pipeline.has_time_exit() {
+            return None;
+        }
+        self.sync_broker();
+        let portfolio = self.broker.get_account().await.ok()?;
+        self.pipeline.time_exit(&portfolio, symbol, bar)
+    }
+
+    /// Give the strategy a snapshot of every symbol at `now`.
+    fn on_snapshot(&mut self, strategy: &mut dyn Strategy, now: i64) {
+        let action = strategy.on_snapshot(&MarketSnapshot::new(now, self.series_map.values()));
+        self.calls += 1;
+        self.queue_action(strategy, now, action);
+    }
+
+    /// Queue what a strategy asked for, priced at the latest closes.
+    fn queue_action(&mut self, strategy: &dyn Strategy, now: i64, action: Option<StrategyAction>) {
+        match action {
+            Some(StrategyAction::Signal(signal)) => {
+                debug_check_metadata(strategy, &signal);
+                let price = self.last_price(&signal.symbol).unwrap_or(dec!(0));
+                self.queue
+                    .push(now, Event::Signal(SignalEvent { signal, price }));
+            }
+            Some(StrategyAction::Orders(orders)) => {
+                let prices = orders
+                    .legs
+                    .iter()
+                    .filter_map(|leg| Some((leg.symbol.clone(), self.last_price(&leg.symbol)?)))
+                    .collect();
+                self.queue
+                    .push(now, Event::Orders(OrdersEvent { orders, prices }));
+            }
+            None => {}
+        }
+    }
+
+    /// Fill resting limit and stop orders whose price `bar` reached.
+    async fn fill_resting(
+        &mut self,
+        strategy: &mut dyn Strategy,
+        now: i64,
+        symbol: &str,
+        bar: &Bar,
+    ) {
+        let mut resting: Vec<Order> = self
+            .broker
+            .get_open_orders()
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|o| o.symbol == symbol)
+            .collect();
+        resting.sort_by_key(|o| o.created_at);
+
+        for order in resting {
+            let Some(price) = trigger_price(&order, bar) else {
+                continue;
+            };
+            let signal_type = self.signal_type(&order);
+            self.fill(strategy, now, order, signal_type, price).await;
+        }
+    }
+
+    /// Signal type recorded for an order that no signal produced.
+    fn signal_type(&self, order: &Order) -> SignalType {
+        match (self.exits.contains_key(&order.id), order.side) {
+            (true, Side::Sell) => SignalType::CloseLong,
+            (true, Side::Buy) => SignalType::CloseShort,
+            (false, Side::Buy) => SignalType::Buy,
+            (false, Side::Sell) => SignalType::Sell,
+        }
+    }
+
+    async fn on_signal(&mut self, now: i64, event: SignalEvent) {
+        let symbol = &event.signal.symbol;
+        self.pipeline.record_signal(&event.signal);
+        self.sync_broker();
+        let portfolio = self.broker.get_account().await.unwrap();
+        let decision = self.pipeline.evaluate(
+            &portfolio,
+            &event.signal,
+            event.price,
+            self.in_flight.contains(symbol),
+        );
+
+        if matches!(decision, PipelineDecision::Reject { .. }) {
+            self.stats.risk_rejections += 1;
+        }
+        self.audit(now, &event.signal, &decision);
+        if let PipelineDecision::Submit {
+            order,
+            stop_loss_price,
+            ..
+        } = decision
+        {
+            let mut request = *order;
+            // The stop rests as the exit of a bracket once the entry fills
+            if let Some(stop) = stop_loss_price.filter(|_| self.enforce_stops) {
+                if request.stop_loss.is_none() {
+                    request = request.with_stop_loss(stop);
+                }
+            }
+            self.in_flight.insert(symbol.clone());
+            self.queue.push(
+                now + self.latency.order_latency_ms,
+                Event::Order(OrderEvent {
+                    request,
+                    signal_type: event.signal.signal_type,
+                }),
+            );
+        }
+    }
+
+    /// Record what the pipeline decided about a signal.
+    fn audit(&mut self, now: i64, signal: &Signal, decision: &PipelineDecision) {
+        let (decision, reason, quantity) = match decision {
+            PipelineDecision::Skip { reason } => (SignalDecision::Skipped, reason.clone(), None),
+            PipelineDecision::Reject { reason } => (SignalDecision::Rejected, reason.clone(), None),
+            PipelineDecision::Submit {
+                order, modified, ..
+            } => match modified {
+                Some(reason) => (
+                    SignalDecision::Modified,
+                    reason.clone(),
+                    Some(order.quantity),
+                ),
+                None => (
+                    SignalDecision::Approved,
+                    String::new(),
+                    Some(order.quantity),
+                ),
+            },
+        };
+        self.stats.signal_audit.push(SignalAuditRecord {
+            timestamp: DateTime::from_timestamp_millis(now).unwrap_or_else(Utc::now),
+            symbol: signal.symbol.clone(),
+            signal_type: Some(signal.signal_type),
+            decision,
+            reason,
+            quantity,
+        });
+    }
+
+    async fn on_orders(&mut self, now: i64, event: OrdersEvent) {
+        self.sync_broker();
+        let portfolio = self.broker.get_account().await.unwrap();
+        let decision =
+            self.pipeline
+                .evaluate_orders(&portfolio, &event.orders, &event.prices, |symbol| {
+                    self.in_flight.contains(symbol)
+                });
+
+        match decision {
+            Ok(legs) => {
+                for request in legs {
+                    self.stats.signal_audit.push(SignalAuditRecord {
+                        timestamp: DateTime::from_timestamp_millis(now).unwrap_or_else(Utc::now),
+                        symbol: request.symbol.clone(),
+                        signal_type: None,
+                        decision: SignalDecision::Approved,
+                        reason: String::new(),
+                        quantity: Some(request.quantity),
+                    });
+                    self.in_flight.insert(request.symbol.clone());
+                    self.queue.push(
+                        now + self.latency.order_latency_ms,
+                        Event::Order(OrderEvent {
+                            signal_type: match request.side {
+                                Side::Buy => SignalType::Buy,
+                                Side::Sell => SignalType::Sell,
+                            },
+                            request,
+                        }),
+                    );
+                }
+            }
+            Err(not_sent) => {
+                if not_sent.rejected {
+                    self.stats.risk_rejections += 1;
+                }
+                debug!("Strategy orders not sent: {}", not_sent.reason);
+                for leg in &event.orders.legs {
+                    self.stats.signal_audit.push(SignalAuditRecord {
+                        timestamp: DateTime::from_timestamp_millis(now).unwrap_or_else(Utc::now),
+                        symbol: leg.symbol.clone(),
+                        signal_type: None,
+                        decision: if not_sent.rejected {
+                            SignalDecision::Rejected
+                        } else {
+                            SignalDecision::Skipped
+                        },
+                        reason: not_sent.reason.clone(),
+                        quantity: None,
+                    });
+                }
+            }
+        }
+    }
+
+    async fn on_order(&mut self, now: i64, event: OrderEvent) {
+        let symbol = event.request.symbol.clone();
+        let bracket = (event.request.stop_loss, event.request.take_profit);
+        let is_bracket = event.request.is_bracket();
+        let result = self.broker.submit_order(event.request).await;
+        self.pipeline.record_submission(&symbol, &result);
+        match result {
+            Ok(order)
+                if order.order_type != OrderType::Market
+                    && order.order_type != OrderType::Limit =>
+            {
+                // Stops rest until a bar trades through them
+                if is_bracket {
+                    self.brackets.insert(order.id, bracket);
+                }
+                self.in_flight.remove(&symbol);
+            }
+            Ok(order) => {
+                if is_bracket {
+                    self.brackets.insert(order.id, bracket);
+                }
+                self.queue.push(
+                    now + self.latency.fill_latency_ms
+                        + self.fill_rng.range(0, self.latency.fill_jitter_ms),
+                    Event::Fill(FillEvent {
+                        order,
+                        signal_type: event.signal_type,
+                    }),
+                );
+            }
+            Err(_) => {
+                self.in_flight.remove(&symbol);
+            }
+        }
+    }
+
+    async fn on_fill(&mut self, strategy: &mut dyn Strategy, now: i64, event: FillEvent) {
+        self.in_flight.remove(&event.order.symbol);
+        let Some(market_price) = self.price_at(&event.order.symbol, now) else {
+            return;
+        };
+        self.fill(strategy, now, event.order, event.signal_type, market_price)
+            .await;
+    }
+
+    /// Execute an order at `market_price`, book the fill and report it to
+    /// the strategy.
+    ///
+    /// Limit orders the price doesn't reach stay open and are retried
+    /// against later bars.
+    async fn fill(
+        &mut self,
+        strategy: &mut dyn Strategy,
+        now: i64,
+        order: Order,
+        signal_type: SignalType,
+        mut market_price: Decimal,
+    ) {
+        let symbol = order.symbol.clone();
+        // Already filled or canceled, e.g. as the other leg of a bracket
+        match self.broker.get_order(&order.id.to_string()).await {
+            Ok(current) if current.status.is_active() => {}
+            _ => return,
+        }
+
+        // Random extra slippage always works against the order
+        if self.slippage_noise_pct > 0.0 {
+            let noise = self
+                .slippage_rng
+                .normal(0.0, self.slippage_noise_pct / 100.0)
+                .abs();
+            let noise = Decimal::try_from(noise).unwrap_or_default();
+            market_price *= match order.side {
+                Side::Buy => Decimal::ONE + noise,
+                Side::Sell => Decimal::ONE - noise,
+            };
+        }
+        let filled = match self.broker.execute_at_price(order.id, market_price) {
+            Ok(filled) if filled.is_filled() => filled,
+            // Immediate-or-cancel and fill-or-kill orders that missed
+            Ok(unfilled) => {
+                if unfilled.status.is_terminal() {
+                    self.brackets.remove(&unfilled.id);
+                }
+                return;
+            }
+            Err(e) => {
+                debug!("Canceling {} order that can't fill: {}", symbol, e);
+                let _ = self.broker.cancel_order(&order.id.to_string()).await;
+                self.brackets.remove(&order.id);
+                return;
+            }
+        };
+
+        let fill_price = filled.filled_avg_price.unwrap_or(market_price);
+        let fill_qty = filled.filled_quantity;
+
+        if self.portfolio_mode != PortfolioMode::Decimal {
+            let commission = filled.fills.last().map_or(Decimal::ZERO, |f| f.commission);
+            self.sim_portfolio.apply_fill(
+                &symbol,
+                filled.side,
+                fill_qty.try_into().unwrap_or(0.0),
+                fill_price.try_into().unwrap_or(0.0),
+                commission.try_into().unwrap_or(0.0),
+            );
+            self.check_float_equity();
+        }
+
+        // Calculate P&L for closing trades
+        let pnl = match filled.side {
+            Side::Buy => {
+                // Opening a long position
+                let entry = self
+                    .open_positions
+                    .entry(symbol.clone())
+                    .or_insert((Decimal::ZERO, Decimal::ZERO));
+                // Weighted average entry price
+                if entry.1 + fill_qty > Decimal::ZERO {
+                    entry.0 = (entry.0 * entry.1 + fill_price * fill_qty) / (entry.1 + fill_qty);
+                }
+                entry.1 += fill_qty;
+                None
+            }
+            Side::Sell => {
+                // Closing (or reducing) a long position
+                match self.open_positions.get_mut(&symbol) {
+                    Some(entry) if entry.1 > Decimal::ZERO => {
+                        let close_qty = fill_qty.min(entry.1);
+                        let trade_pnl = (fill_price - entry.0) * close_qty;
+                        entry.1 -= close_qty;
+                        if entry.1 <= Decimal::ZERO {
+                            self.open_positions.remove(&symbol);
+                        }
+                        Some(trade_pnl)
+                    }
+                    _ => None,
+                }
+            }
+        };
+
+        let reason = match self.exits.get(&filled.id) {
+            Some(_) if filled.order_type == OrderType::Stop => TradeReason::StopLoss,
+            Some(_) => TradeReason::TakeProfit,
+            None => TradeReason::Signal,
+        };
+
+        // Record trade
+        self.stats.add_trade(TradeRecord {
+            symbol: symbol.clone(),
+            side: filled.side,
+            quantity: fill_qty,
+            price: fill_price,
+            timestamp: DateTime::from_timestamp_millis(now).unwrap_or_else(Utc::now),
+            signal_type,
+            pnl,
+            reason,
+        });
+        if let Some(pnl) = pnl {
+            self.pipeline.record_closed_trade(strategy.name(), pnl);
+        }
+        self.fills.push((self.calls, filled.clone()));
+        strategy.on_fill(&filled);
+
+        if let Some((stop_loss, take_profit)) = self.brackets.remove(&filled.id) {
+            self.place_exits(&filled, stop_loss, take_profit).await;
+        }
+        if let Some(sibling) = self.exits.remove(&filled.id).and_then(|e| e.sibling) {
+            self.exits.remove(&sibling);
+            let _ = self.broker.cancel_order(&sibling.to_string()).await;
+        }
+        // Exits left over once the position is gone would open a new one
+        if self
+            .broker
+            .get_position(&symbol)
+            .await
+            .ok()
+            .flatten()
+            .is_none()
+        {
+            let orphans: Vec<Uuid> = self
+                .exits
+                .iter()
+                .filter(|(_, exit)| exit.symbol == symbol)
+                .map(|(id, _)| *id)
+                .collect();
+            for id in orphans {
+                self.exits.remove(&id);
+                let _ = self.broker.cancel_order(&id.to_string()).await;
+            }
+        }
+    }
+
+    /// Place the stop-loss and take-profit exits of a filled bracket entry,
+    /// each canceling the other when it fills.
+    async fn place_exits(
+        &mut self,
+        entry: &Order,
+        stop_loss: Option<Decimal>,
+        take_profit: Option<Decimal>,
+    ) {
+        let side = entry.side.opposite();
+        let quantity = entry.filled_quantity;
+        let requests = [
+            stop_loss.map(|price| OrderRequest::stop(&entry.symbol, side, quantity, price)),
+            take_profit.map(|price| {
+                OrderRequest::limit(&entry.symbol, side, quantity, price)
+                    .with_time_in_force(TimeInForce::GTC)
+            }),
+        ];
+
+        let mut ids = Vec::new();
+        for request in requests.into_iter().flatten() {
+            match self.broker.submit_order(request).await {
+                Ok(order) => ids.push(order.id),
+                Err(e) => warn!("Failed to place bracket exit for {}: {}", entry.symbol, e),
+            }
+        }
+        for &id in &ids {
+            self.exits.insert(
+                id,
+                BracketExit {
+                    symbol: entry.symbol.clone(),
+                    sibling: ids.iter().copied().find(|&other| other != id),
+                },
+            );
         }
     }
 }
 
+/// Price at which a resting order fills on `bar`, if the bar reached it.
+///
+/// Orders fill at their own price, or at the open if the bar gapped
+/// through it.
+fn trigger_price(order: &Order, bar: &Bar) -> Option<Decimal> {
+    let open = Decimal::try_from(bar.open).ok()?;
+    let high = Decimal::try_from(bar.high).ok()?;
+    let low = Decimal::try_from(bar.low).ok()?;
+    match (order.order_type, order.side) {
+        (OrderType::Limit, Side::Buy) => {
+            let limit = order.limit_price?;
+            (low <= limit).then(|| open.min(limit))
+        }
+        (OrderType::Limit, Side::Sell) => {
+            let limit = order.limit_price?;
+            (high >= limit).then(|| open.max(limit))
+        }
+        (OrderType::Stop, Side::Buy) => {
+            let stop = order.stop_price?;
+            (high >= stop).then(|| open.max(stop))
+        }
+        (OrderType::Stop, Side::Sell) => {
+            let stop = order.stop_price?;
+            (low <= stop).then(|| open.min(stop))
+        }
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use trading_strategies::{MACrossoverConfig, MACrossoverStrategy};
+    use trading_core::types::Signal;
+    use trading_strategies::{
+        MACrossoverConfig, MACrossoverStrategy, MaType, RebalanceConfig, RebalanceStrategy,
+    };
 
     fn generate_test_data() -> HashMap<String, Vec<Bar>> {
         let mut data = HashMap::new();
@@ -255,7 +1292,7 @@ mod tests {
             symbols: vec!["TEST".to_string()],
             fast_period: 5,
             slow_period: 10,
-            use_ema: true,
+            ma_type: MaType::Ema,
             signal_threshold: 0.0,
         };
         let mut strategy = MACrossoverStrategy::new(strategy_config);
@@ -265,4 +1302,907 @@ mod tests {
 
         assert!(report.stats.bars_processed > 0);
     }
+
+    fn trending_data() -> HashMap<String, Vec<Bar>> {
+        // Dip then a steady rally so the crossover buys and never sells
+        let bars: Vec<Bar> = (0..60)
+            .map(|i| {
+                let price = if i < 20 {
+                    100.0 - i as f64
+                } else {
+                    80.0 + (i - 20) as f64 * 2.0
+                };
+                Bar::new(
+                    i as i64 * 86400000,
+                    price,
+                    price + 1.0,
+                    price - 1.0,
+                    price,
+                    1e6,
+                )
+            })
+            .collect();
+        HashMap::from([("TEST".to_string(), bars)])
+    }
+
+    async fn run_with(end_of_backtest: EndOfBacktest) -> BacktestReport {
+        let engine = BacktestEngine::new(BacktestConfig {
+            end_of_backtest,
+            ..Default::default()
+        });
+        let mut strategy = MACrossoverStrategy::new(MACrossoverConfig {
+            symbols: vec!["TEST".to_string()],
+            fast_period: 3,
+            slow_period: 6,
+            ma_type: MaType::Sma,
+            signal_threshold: 0.0,
+        });
+        engine.run(&mut strategy, trending_data()).await
+    }
+
+    #[tokio::test]
+    async fn test_end_of_backtest_modes() {
+        let closed = run_with(EndOfBacktest::CloseAtLastPrice).await;
+        let marked = run_with(EndOfBacktest::MarkToMarket).await;
+        let excluded = run_with(EndOfBacktest::ExcludeOpen).await;
+
+        // Every mode reports the open position separately
+        for report in [&closed, &marked, &excluded] {
+            assert_eq!(report.stats.open_positions.len(), 1);
+            assert!(report.stats.open_pnl > Decimal::ZERO);
+        }
+
+        // Only close-at-last-price records a synthetic closing trade
+        assert_eq!(closed.stats.trades.len(), marked.stats.trades.len() + 1);
+        assert_eq!(marked.stats.trades.len(), excluded.stats.trades.len());
+
+        // Mark-to-market counts the open P&L, exclude-open does not
+        assert_eq!(closed.stats.total_trades, marked.stats.total_trades);
+        assert_eq!(excluded.stats.total_trades + 1, marked.stats.total_trades);
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_bars_processed_once() {
+        let bars: Vec<Bar> = (0..10)
+            .map(|i| Bar::new(i * 86400000, 100.0, 101.0, 99.0, 100.0, 1e6))
+            .collect();
+        let mut doubled = bars.clone();
+        doubled.extend(bars);
+
+        let engine = BacktestEngine::new(BacktestConfig::default());
+        let mut strategy = MACrossoverStrategy::new(MACrossoverConfig {
+            symbols: vec!["TEST".to_string()],
+            ..Default::default()
+        });
+        let report = engine
+            .run(
+                &mut strategy,
+                HashMap::from([("TEST".to_string(), doubled)]),
+            )
+            .await;
+
+        assert_eq!(report.stats.bars_processed, 10);
+    }
+
+    #[tokio::test]
+    async fn test_fill_latency_uses_later_price() {
+        let immediate = run_with(EndOfBacktest::CloseAtLastPrice).await;
+
+        let engine = BacktestEngine::new(BacktestConfig {
+            latency: LatencyModel {
+                order_latency_ms: 0,
+                fill_latency_ms: 2 * 86400000,
+                ..Default::default()
+            },
+            slippage_pct: dec!(0),
+            ..Default::default()
+        });
+        let mut strategy = MACrossoverStrategy::new(MACrossoverConfig {
+            symbols: vec!["TEST".to_string()],
+            fast_period: 3,
+            slow_period: 6,
+            ma_type: MaType::Sma,
+            signal_threshold: 0.0,
+        });
+        let data = trending_data();
+        let delayed = engine.run(&mut strategy, data.clone()).await;
+
+        // A fill due at a bar's timestamp executes before that bar is seen,
+        // at its open, so a two-day delay fills higher in the rally
+        let first_buy = |r: &BacktestReport| {
+            r.stats
+                .trades
+                .iter()
+                .find(|t| t.side == Side::Buy)
+                .map(|t| (t.timestamp, t.price))
+                .unwrap()
+        };
+        let (ts_now, px_now) = first_buy(&immediate);
+        let (ts_later, px_later) = first_buy(&delayed);
+        assert_eq!((ts_later - ts_now).num_days(), 2);
+        assert!(px_later > px_now);
+        let bar = data["TEST"]
+            .iter()
+            .find(|b| b.timestamp == ts_later.timestamp_millis())
+            .unwrap();
+        assert_eq!(px_later, Decimal::try_from(bar.open).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_resume_from_checkpoint_matches_full_run() {
+        let path =
+            std::env::temp_dir().join(format!("trading-checkpoint-{}.json", std::process::id()));
+        let config = BacktestConfig {
+            latency: LatencyModel {
+                order_latency_ms: 0,
+                fill_latency_ms: 86400000,
+                ..Default::default()
+            },
+            slippage_noise_pct: dec!(0.2),
+            seed: Some(11),
+            ..Default::default()
+        };
+        let strategy = || {
+            MACrossoverStrategy::new(MACrossoverConfig {
+                symbols: vec!["TEST".to_string()],
+                fast_period: 5,
+                slow_period: 10,
+                ma_type: MaType::Ema,
+                signal_threshold: 0.0,
+            })
+        };
+
+        // Last checkpoint lands at bar 45 of 100
+        let engine =
+            BacktestEngine::new(config.clone()).with_checkpoints(CheckpointConfig::new(&path, 45));
+        let full = engine.run(&mut strategy(), generate_test_data()).await;
+
+        let checkpoint = BacktestCheckpoint::load(&path).unwrap();
+        assert_eq!(checkpoint.cursor, 90);
+
+        let resumed = BacktestEngine::new(config.clone())
+            .resume(&mut strategy(), generate_test_data(), checkpoint.clone())
+            .await
+            .unwrap();
+        assert_eq!(resumed.stats.trades.len(), full.stats.trades.len());
+        assert_eq!(resumed.stats.equity_curve, full.stats.equity_curve);
+        assert_eq!(resumed.final_portfolio.equity, full.final_portfolio.equity);
+
+        // A different config is refused
+        let other = BacktestEngine::new(BacktestConfig::default())
+            .resume(&mut strategy(), generate_test_data(), checkpoint)
+            .await;
+        assert!(other.is_err());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_resume_restores_holdings_from_fills() {
+        let path = std::env::temp_dir().join(format!(
+            "trading-checkpoint-fills-{}.json",
+            std::process::id()
+        ));
+        let strategy = || {
+            RebalanceStrategy::new(RebalanceConfig {
+                symbols: vec!["TEST".to_string()],
+                weights: HashMap::from([("TEST".to_string(), 0.05)]),
+                interval: None,
+                drift_threshold_pct: 1.0,
+                ..Default::default()
+            })
+        };
+        let engine = BacktestEngine::new(BacktestConfig::default())
+            .with_checkpoints(CheckpointConfig::new(&path, 45));
+        let full = engine.run(&mut strategy(), generate_test_data()).await;
+
+        let checkpoint = BacktestCheckpoint::load(&path).unwrap();
+        assert!(!checkpoint.fills.is_empty());
+        let resumed = BacktestEngine::new(BacktestConfig::default())
+            .resume(&mut strategy(), generate_test_data(), checkpoint)
+            .await
+            .unwrap();
+
+        // Without its fills the strategy would think it held nothing and
+        // buy its whole target again
+        let trades = |r: &BacktestReport| {
+            r.stats
+                .trades
+                .iter()
+                .map(|t| (t.timestamp, t.side, t.quantity))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(trades(&resumed), trades(&full));
+        assert_eq!(resumed.final_portfolio.equity, full.final_portfolio.equity);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_seed_reproduces_stochastic_run() {
+        let run = |seed| async move {
+            let engine = BacktestEngine::new(BacktestConfig {
+                slippage_noise_pct: dec!(0.5),
+                latency: LatencyModel {
+                    fill_jitter_ms: 86400000,
+                    ..Default::default()
+                },
+                seed,
+                ..Default::default()
+            });
+            let mut strategy = MACrossoverStrategy::new(MACrossoverConfig {
+                symbols: vec!["TEST".to_string()],
+                fast_period: 5,
+                slow_period: 10,
+                ma_type: MaType::Ema,
+                signal_threshold: 0.0,
+            });
+            engine.run(&mut strategy, generate_test_data()).await
+        };
+
+        let a = run(Some(7)).await;
+        let b = run(Some(7)).await;
+        assert_eq!(a.seed, 7);
+        assert_eq!(a.stats.equity_curve, b.stats.equity_curve);
+
+        // An unseeded run records the seed it drew, which replays it exactly
+        let random = run(None).await;
+        let replay = run(Some(random.seed)).await;
+        assert_eq!(random.stats.equity_curve, replay.stats.equity_curve);
+    }
+
+    /// Records how many completed daily bars it sees on each hourly bar.
+    struct DailyProbe {
+        symbols: Vec<String>,
+        seen: Vec<usize>,
+    }
+
+    impl Strategy for DailyProbe {
+        fn name(&self) -> &str {
+            "daily_probe"
+        }
+
+        fn on_bar(&mut self, _series: &trading_core::types::BarSeries) -> Option<Signal> {
+            None
+        }
+
+        fn timeframes(&self) -> &[Timeframe] {
+            &[Timeframe::Daily]
+        }
+
+        fn on_bars(&mut self, series: &MultiTimeframeSeries) -> Option<Signal> {
+            self.seen
+                .push(series.get(Timeframe::Daily).map_or(0, |s| s.len()));
+            None
+        }
+
+        fn reset(&mut self) {
+            self.seen.clear();
+        }
+
+        fn state(&self) -> trading_core::traits::StrategyState {
+            Default::default()
+        }
+
+        fn warmup_period(&self) -> usize {
+            0
+        }
+
+        fn symbols(&self) -> &[String] {
+            &self.symbols
+        }
+    }
+
+    /// Records the bars it has seen and the symbols current at each snapshot.
+    struct SnapshotProbe {
+        symbols: Vec<String>,
+        bars: usize,
+        snapshots: Vec<(i64, usize, usize)>,
+    }
+
+    impl Strategy for SnapshotProbe {
+        fn name(&self) -> &str {
+            "snapshot_probe"
+        }
+
+        fn on_bar(&mut self, _series: &trading_core::types::BarSeries) -> Option<Signal> {
+            self.bars += 1;
+            None
+        }
+
+        fn on_snapshot(&mut self, snapshot: &MarketSnapshot) -> Option<StrategyAction> {
+            self.snapshots
+                .push((snapshot.timestamp(), self.bars, snapshot.current().count()));
+            None
+        }
+
+        fn reset(&mut self) {
+            self.bars = 0;
+            self.snapshots.clear();
+        }
+
+        fn state(&self) -> trading_core::traits::StrategyState {
+            Default::default()
+        }
+
+        fn warmup_period(&self) -> usize {
+            0
+        }
+
+        fn symbols(&self) -> &[String] {
+            &self.symbols
+        }
+    }
+
+    /// Buys on the fifth bar and records when its fills happened.
+    struct FillClockProbe {
+        symbols: Vec<String>,
+        bars: usize,
+        filled_at: Vec<DateTime<Utc>>,
+    }
+
+    impl Strategy for FillClockProbe {
+        fn name(&self) -> &str {
+            "fill_clock_probe"
+        }
+
+        fn on_bar(&mut self, series: &trading_core::types::BarSeries) -> Option<Signal> {
+            self.bars += 1;
+            let bar = series.last()?;
+            (self.bars == 5).then(|| Signal::buy("TEST", bar.close, bar.timestamp))
+        }
+
+        fn on_fill(&mut self, order: &Order) {
+            self.filled_at.extend(order.filled_at);
+        }
+
+        fn reset(&mut self) {
+            self.bars = 0;
+            self.filled_at.clear();
+        }
+
+        fn state(&self) -> trading_core::traits::StrategyState {
+            Default::default()
+        }
+
+        fn warmup_period(&self) -> usize {
+            0
+        }
+
+        fn symbols(&self) -> &[String] {
+            &self.symbols
+        }
+    }
+
+    /// Places one limit entry with a bracket on the tenth bar.
+    struct BracketTrader {
+        symbols: Vec<String>,
+    }
+
+    impl Strategy for BracketTrader {
+        fn name(&self) -> &str {
+            "bracket_trader"
+        }
+
+        fn on_bar(&mut self, _series: &trading_core::types::BarSeries) -> Option<Signal> {
+            None
+        }
+
+        fn act(&mut self, series: &MultiTimeframeSeries) -> Option<StrategyAction> {
+            (series.primary().len() == 11).then(|| {
+                let entry = OrderRequest::limit("TEST", Side::Buy, dec!(10), dec!(89.5))
+                    .with_stop_loss(dec!(80))
+                    .with_take_profit(dec!(100));
+                StrategyAction::Orders(trading_core::traits::StrategyOrders::single(entry))
+            })
+        }
+
+        fn reset(&mut self) {}
+
+        fn state(&self) -> trading_core::traits::StrategyState {
+            Default::default()
+        }
+
+        fn warmup_period(&self) -> usize {
+            0
+        }
+
+        fn symbols(&self) -> &[String] {
+            &self.symbols
+        }
+    }
+
+    /// Places one limit buy at 85 on the tenth bar, with a time in force.
+    struct LimitTrader {
+        symbols: Vec<String>,
+        time_in_force: TimeInForce,
+    }
+
+    impl Strategy for LimitTrader {
+        fn name(&self) -> &str {
+            "limit_trader"
+        }
+
+        fn on_bar(&mut self, _series: &trading_core::types::BarSeries) -> Option<Signal> {
+            None
+        }
+
+        fn act(&mut self, series: &MultiTimeframeSeries) -> Option<StrategyAction> {
+            (series.primary().len() == 11).then(|| {
+                let entry = OrderRequest::limit("TEST", Side::Buy, dec!(10), dec!(85))
+                    .with_time_in_force(self.time_in_force);
+                StrategyAction::Orders(trading_core::traits::StrategyOrders::single(entry))
+            })
+        }
+
+        fn reset(&mut self) {}
+
+        fn state(&self) -> trading_core::traits::StrategyState {
+            Default::default()
+        }
+
+        fn warmup_period(&self) -> usize {
+            0
+        }
+
+        fn symbols(&self) -> &[String] {
+            &self.symbols
+        }
+    }
+
+    /// Buys in two half tranches, then exits half and the rest.
+    struct ScaledTrader {
+        symbols: Vec<String>,
+    }
+
+    impl Strategy for ScaledTrader {
+        fn name(&self) -> &str {
+            "scaled_trader"
+        }
+
+        fn on_bar(&mut self, series: &trading_core::types::BarSeries) -> Option<Signal> {
+            let bar = series.last()?;
+            let signal = match series.len() {
+                25 | 30 => Signal::buy("TEST", bar.close, bar.timestamp).with_fraction(0.5),
+                40 => Signal::close_long("TEST", bar.close, bar.timestamp).with_fraction(0.5),
+                50 => Signal::close_long("TEST", bar.close, bar.timestamp),
+                _ => return None,
+            };
+            Some(signal)
+        }
+
+        fn reset(&mut self) {}
+
+        fn state(&self) -> trading_core::traits::StrategyState {
+            Default::default()
+        }
+
+        fn warmup_period(&self) -> usize {
+            0
+        }
+
+        fn symbols(&self) -> &[String] {
+            &self.symbols
+        }
+    }
+
+    #[tokio::test]
+    async fn test_scaled_entries_and_partial_exits() {
+        let engine = BacktestEngine::new(BacktestConfig {
+            slippage_pct: dec!(0),
+            ..Default::default()
+        });
+        let mut strategy = ScaledTrader {
+            symbols: vec!["TEST".to_string()],
+        };
+        let report = engine.run(&mut strategy, trending_data()).await;
+
+        let trades = &report.stats.trades;
+        let sides: Vec<Side> = trades.iter().map(|t| t.side).collect();
+        assert_eq!(sides, vec![Side::Buy, Side::Buy, Side::Sell, Side::Sell]);
+
+        // Half the combined position goes first, at a profit on the
+        // average entry, and the rest with the final exit
+        let bought = trades[0].quantity + trades[1].quantity;
+        let average =
+            (trades[0].price * trades[0].quantity + trades[1].price * trades[1].quantity) / bought;
+        assert_eq!(trades[2].quantity, (bought / dec!(2)).floor());
+        assert_eq!(trades[3].quantity, bought - trades[2].quantity);
+        assert_eq!(
+            trades[2].pnl,
+            Some((trades[2].price - average) * trades[2].quantity)
+        );
+        assert!(report.final_portfolio.positions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_stop_loss_closes_position() {
+        // The rally gaps down 40 points just after the first tranche
+        let mut data = trending_data();
+        for bar in data.get_mut("TEST").unwrap().iter_mut().skip(27) {
+            bar.open -= 40.0;
+            bar.high -= 40.0;
+            bar.low -= 40.0;
+            bar.close -= 40.0;
+        }
+        let run = |enforce_stops| {
+            let engine = BacktestEngine::new(BacktestConfig {
+                enforce_stops,
+                ..Default::default()
+            });
+            let data = data.clone();
+            async move {
+                let mut strategy = ScaledTrader {
+                    symbols: vec!["TEST".to_string()],
+                };
+                engine.run(&mut strategy, data).await
+            }
+        };
+
+        // The stop fills at the gapped open and the next tranche starts over
+        let report = run(true).await;
+        let trades = &report.stats.trades;
+        let reasons: Vec<TradeReason> = trades.iter().map(|t| t.reason).collect();
+        assert_eq!(
+            reasons,
+            vec![
+                TradeReason::Signal,
+                TradeReason::StopLoss,
+                TradeReason::Signal,
+                TradeReason::Signal,
+                TradeReason::Signal,
+            ]
+        );
+        assert_eq!(trades[1].signal_type, SignalType::CloseLong);
+        assert!(trades[1].price < dec!(60));
+        assert!(trades[1].pnl.unwrap() < Decimal::ZERO);
+        assert_eq!(report.stats.trades_for(TradeReason::StopLoss), 1);
+
+        // Without stops the first tranche rides the gap down
+        let ignored = run(false).await;
+        assert!(ignored
+            .stats
+            .trades
+            .iter()
+            .all(|t| t.reason == TradeReason::Signal));
+        assert_eq!(ignored.stats.trades.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_time_exit_closes_held_positions() {
+        let engine = BacktestEngine::new(BacktestConfig {
+            risk_config: RiskConfig {
+                time_exit: trading_risk::TimeExit {
+                    max_bars: Some(3),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        let mut strategy = ScaledTrader {
+            symbols: vec!["TEST".to_string()],
+        };
+        let report = engine.run(&mut strategy, trending_data()).await;
+
+        // Each tranche is closed three bars in, before the strategy's own
+        // exits find anything to close
+        let trades: Vec<(Side, SignalType)> = report
+            .stats
+            .trades
+            .iter()
+            .map(|t| (t.side, t.signal_type))
+            .collect();
+        assert_eq!(
+            trades,
+            vec![
+                (Side::Buy, SignalType::Buy),
+                (Side::Sell, SignalType::CloseLong),
+                (Side::Buy, SignalType::Buy),
+                (Side::Sell, SignalType::CloseLong),
+            ]
+        );
+        assert!(report.final_portfolio.positions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rejected_signals_are_audited() {
+        let engine = BacktestEngine::new(BacktestConfig {
+            risk_config: RiskConfig {
+                limits: trading_risk::PortfolioLimits {
+                    max_positions: 0,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        let mut strategy = MACrossoverStrategy::new(MACrossoverConfig {
+            symbols: vec!["TEST".to_string()],
+            fast_period: 3,
+            slow_period: 6,
+            ma_type: MaType::Sma,
+            signal_threshold: 0.0,
+        });
+        let report = engine.run(&mut strategy, trending_data()).await;
+
+        assert!(report.stats.trades.is_empty());
+        let rejected: Vec<&SignalAuditRecord> = report
+            .stats
+            .signal_audit
+            .iter()
+            .filter(|r| r.decision == SignalDecision::Rejected)
+            .collect();
+        assert_eq!(rejected.len(), report.stats.risk_rejections);
+        assert!(!rejected.is_empty());
+        assert!(rejected[0].reason.contains("Max positions"));
+        assert_eq!(rejected[0].signal_type, Some(SignalType::Buy));
+        assert_eq!(
+            report.stats.blocking_reasons(5)[0].1,
+            report.stats.signal_audit.len()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_strategy_orders_rest_and_bracket_exits() {
+        let engine = BacktestEngine::new(BacktestConfig {
+            slippage_pct: dec!(0),
+            ..Default::default()
+        });
+        let mut strategy = BracketTrader {
+            symbols: vec!["TEST".to_string()],
+        };
+        let report = engine.run(&mut strategy, trending_data()).await;
+
+        // The limit rests below the close of 90, fills at the next bar's
+        // open, and the stop takes the position out at the bottom of the dip
+        let trades = &report.stats.trades;
+        assert_eq!(trades.len(), 2);
+        assert_eq!(trades[0].price, dec!(89));
+        assert_eq!(trades[0].timestamp.timestamp_millis(), 11 * 86400000);
+        assert_eq!(trades[1].signal_type, SignalType::CloseLong);
+        assert_eq!(trades[1].price, dec!(80));
+        assert_eq!(trades[1].pnl, Some(dec!(-90)));
+
+        // The take-profit was canceled with the position
+        assert!(report.final_portfolio.positions.is_empty());
+        assert!(report.stats.open_positions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_time_in_force() {
+        async fn entries(time_in_force: TimeInForce) -> Vec<TradeRecord> {
+            let engine = BacktestEngine::new(BacktestConfig {
+                slippage_pct: dec!(0),
+                ..Default::default()
+            });
+            let mut strategy = LimitTrader {
+                symbols: vec!["TEST".to_string()],
+                time_in_force,
+            };
+            let report = engine.run(&mut strategy, trending_data()).await;
+            report
+                .stats
+                .trades
+                .into_iter()
+                .filter(|t| t.side == Side::Buy)
+                .collect()
+        }
+
+        // The dip reaches 85 four bars after the order goes in: a good-til-
+        // canceled order waits for it, a day order only gets the next bar,
+        // and an immediate-or-cancel order only the price it was sent at
+        let gtc = entries(TimeInForce::GTC).await;
+        assert_eq!(gtc.len(), 1);
+        assert_eq!(gtc[0].price, dec!(85));
+        assert_eq!(gtc[0].timestamp.timestamp_millis(), 14 * 86400000);
+        assert!(entries(TimeInForce::Day).await.is_empty());
+        assert!(entries(TimeInForce::IOC).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_strategy_tracks_its_fills() {
+        let engine = BacktestEngine::new(BacktestConfig::default());
+        let mut strategy = RebalanceStrategy::new(RebalanceConfig {
+            symbols: vec!["TEST".to_string()],
+            weights: HashMap::from([("TEST".to_string(), 0.05)]),
+            interval: None,
+            drift_threshold_pct: 1.0,
+            ..Default::default()
+        });
+        let report = engine.run(&mut strategy, trending_data()).await;
+
+        // The strategy only knows its holdings from the fills it was sent
+        let held = report.final_portfolio.positions["TEST"].market_value;
+        let value: f64 = strategy.state().custom["portfolio_value"].as_f64().unwrap();
+        let weight = strategy.state().indicators["weight_TEST"];
+        assert!(weight > 0.0);
+        assert!((weight * value - f64::try_from(held).unwrap()).abs() < 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_fills_are_stamped_with_simulated_time() {
+        let engine = BacktestEngine::new(BacktestConfig::default());
+        let mut strategy = FillClockProbe {
+            symbols: vec!["TEST".to_string()],
+            bars: 0,
+            filled_at: Vec::new(),
+        };
+        let report = engine.run(&mut strategy, trending_data()).await;
+
+        // Stamped with the bar the fill happened on, not when the test ran
+        let traded: Vec<_> = report.stats.trades.iter().map(|t| t.timestamp).collect();
+        assert!(!traded.is_empty());
+        assert_eq!(strategy.filled_at, traded);
+        assert!(traded
+            .iter()
+            .all(|t| t.timestamp_millis() < 60 * 86_400_000));
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_follows_each_timestamp() {
+        let bar = |day: i64| Bar::new(day * 86_400_000, 100.0, 101.0, 99.0, 100.0, 1e3);
+        let data = HashMap::from([
+            ("AAA".to_string(), (0..4).map(bar).collect()),
+            ("BBB".to_string(), [0, 1, 3].into_iter().map(bar).collect()),
+        ]);
+        let engine = BacktestEngine::new(BacktestConfig::default());
+        let mut strategy = SnapshotProbe {
+            symbols: vec!["AAA".to_string(), "BBB".to_string()],
+            bars: 0,
+            snapshots: Vec::new(),
+        };
+        engine.run(&mut strategy, data).await;
+
+        // One snapshot per timestamp, after every symbol's bar at it
+        assert_eq!(
+            strategy.snapshots,
+            vec![
+                (0, 2, 2),
+                (86_400_000, 4, 2),
+                (2 * 86_400_000, 5, 1),
+                (3 * 86_400_000, 7, 2),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_strategy_sees_resampled_timeframe() {
+        let bars: Vec<Bar> = (0..48)
+            .map(|i| Bar::new(i * 3_600_000, 100.0, 101.0, 99.0, 100.0, 1e3))
+            .collect();
+        let engine = BacktestEngine::new(BacktestConfig {
+            timeframe: Timeframe::Hour1,
+            ..Default::default()
+        });
+        let mut strategy = DailyProbe {
+            symbols: vec!["TEST".to_string()],
+            seen: Vec::new(),
+        };
+        engine
+            .run(&mut strategy, HashMap::from([("TEST".to_string(), bars)]))
+            .await;
+
+        // A day completes with its last hourly bar, never earlier
+        assert_eq!(strategy.seen.len(), 48);
+        assert_eq!(strategy.seen[22], 0);
+        assert_eq!(strategy.seen[23], 1);
+        assert_eq!(strategy.seen[46], 1);
+        assert_eq!(strategy.seen[47], 2);
+    }
+
+    #[test]
+    fn test_end_of_backtest_parse() {
+        assert_eq!(
+            "mark".parse::<EndOfBacktest>().unwrap(),
+            EndOfBacktest::MarkToMarket
+        );
+        assert!("bogus".parse::<EndOfBacktest>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_progress_reports() {
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let engine = BacktestEngine::new(BacktestConfig::default())
+            .with_progress(ProgressConfig::new(sender, 30));
+        let mut strategy = MACrossoverStrategy::new(MACrossoverConfig {
+            symbols: vec!["TEST".to_string()],
+            fast_period: 3,
+            slow_period: 6,
+            ma_type: MaType::Sma,
+            signal_threshold: 0.0,
+        });
+        let report = engine.run(&mut strategy, generate_test_data()).await;
+
+        let mut reports = Vec::new();
+        while let Ok(progress) = receiver.try_recv() {
+            reports.push(progress);
+        }
+        let processed: Vec<usize> = reports.iter().map(|p| p.bars_processed).collect();
+        assert_eq!(processed, vec![30, 60, 90, 100]);
+        let last = reports.last().unwrap();
+        assert_eq!(last.fraction(), 1.0);
+        assert_eq!(last.equity, report.stats.final_equity);
+    }
+
+    #[test]
+    fn test_config_from_overrides() {
+        let overrides = |json: serde_json::Value| match json {
+            serde_json::Value::Object(map) => map,
+            _ => unreachable!(),
+        };
+        let config = BacktestConfig::from_overrides(overrides(serde_json::json!({
+            "initial_capital": "5000",
+            "seed": 3,
+            "instruments": {},
+        })))
+        .unwrap();
+        assert_eq!(config.initial_capital, dec!(5000));
+        assert_eq!(config.seed, Some(3));
+        assert_eq!(config.commission, Decimal::ZERO);
+
+        let err = BacktestConfig::from_overrides(overrides(serde_json::json!({ "bogus": 1 })));
+        assert!(err.unwrap_err().to_string().contains("bogus"));
+        let err = BacktestConfig::from_overrides(overrides(serde_json::json!({ "seed": "x" })));
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn test_parallel_runs_match_sequential() {
+        fn run(fast_period: usize) -> (Decimal, usize) {
+            let engine = BacktestEngine::new(BacktestConfig {
+                seed: Some(7),
+                ..Default::default()
+            });
+            let mut strategy = MACrossoverStrategy::new(MACrossoverConfig {
+                symbols: vec!["TEST".to_string()],
+                fast_period,
+                slow_period: fast_period * 2,
+                ma_type: MaType::Ema,
+                signal_threshold: 0.0,
+            });
+            let report = tokio::runtime::Builder::new_current_thread()
+                .build()
+                .unwrap()
+                .block_on(engine.run(&mut strategy, generate_test_data()));
+            (report.stats.final_equity, report.stats.total_trades)
+        }
+
+        // Each run owns its broker, so threads can't see each other's fills
+        let parallel: Vec<_> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (2..10).map(|fast| scope.spawn(move || run(fast))).collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        let sequential: Vec<_> = (2..10).map(run).collect();
+        assert_eq!(parallel, sequential);
+    }
+
+    #[tokio::test]
+    async fn test_float_portfolio_tracks_decimal() {
+        async fn run(portfolio_mode: PortfolioMode) -> BacktestReport {
+            let engine = BacktestEngine::new(BacktestConfig {
+                portfolio_mode,
+                ..Default::default()
+            });
+            let mut strategy = MACrossoverStrategy::new(MACrossoverConfig {
+                symbols: vec!["TEST".to_string()],
+                fast_period: 5,
+                slow_period: 10,
+                ma_type: MaType::Ema,
+                signal_threshold: 0.0,
+            });
+            engine.run(&mut strategy, generate_test_data()).await
+        }
+
+        let decimal = run(PortfolioMode::Decimal).await;
+        let float = run(PortfolioMode::Float).await;
+        let validate = run(PortfolioMode::Validate).await;
+
+        assert!(decimal.stats.total_trades > 0);
+        assert_eq!(float.stats.total_trades, decimal.stats.total_trades);
+        assert!((float.stats.final_equity - decimal.stats.final_equity).abs() < dec!(0.01));
+        assert_eq!(validate.stats.final_equity, decimal.stats.final_equity);
+        assert!(validate.stats.float_equity_error.unwrap() < FLOAT_EQUITY_TOLERANCE);
+        assert!(decimal.stats.float_equity_error.is_none());
+    }
 }
@@ -0,0 +1,227 @@
+//! Backtest events and event queue.
+//!
+//! The engine moves every bar through the same pipeline:
+//! market data → strategy signal → order → fill. Strategies that build
+//! their own orders skip the signal stage. Each stage is an event
+//! scheduled on a time-ordered queue, so latency between stages can be
+//! modeled by scheduling the next event later than the current one.
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use trading_core::traits::StrategyOrders;
+use trading_core::types::{Bar, Order, OrderRequest, Signal, SignalType};
+use uuid::Uuid;
+
+/// New bar for a symbol.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketEvent {
+    /// Symbol
+    pub symbol: String,
+    /// Bar
+    pub bar: Bar,
+}
+
+/// Signal emitted by the strategy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignalEvent {
+    /// Strategy signal
+    pub signal: Signal,
+    /// Market price when the signal was generated
+    pub price: Decimal,
+}
+
+/// Orders built by the strategy, waiting for risk validation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrdersEvent {
+    /// Strategy orders
+    pub orders: StrategyOrders,
+    /// Market price per leg symbol when the orders were placed
+    pub prices: HashMap<String, Decimal>,
+}
+
+/// Order approved by risk management, waiting to reach the broker.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderEvent {
+    /// Order request
+    pub request: OrderRequest,
+    /// Signal type that produced the order
+    pub signal_type: SignalType,
+}
+
+/// Order accepted by the broker, waiting to be filled.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FillEvent {
+    /// Submitted order
+    pub order: Order,
+    /// Signal type that produced the order
+    pub signal_type: SignalType,
+}
+
+/// Bracket exit resting at the broker.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BracketExit {
+    /// Symbol of the position the exit closes
+    pub symbol: String,
+    /// Other exit of the bracket, canceled when this one fills
+    pub sibling: Option<Uuid>,
+}
+
+/// Backtest event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Event {
+    Market(MarketEvent),
+    Signal(SignalEvent),
+    Orders(OrdersEvent),
+    Order(OrderEvent),
+    Fill(FillEvent),
+}
+
+impl Event {
+    /// Symbol the event refers to.
+    pub fn symbol(&self) -> &str {
+        match self {
+            Event::Market(e) => &e.symbol,
+            Event::Signal(e) => &e.signal.symbol,
+            Event::Orders(e) => e.orders.legs.first().map_or("", |leg| &leg.symbol),
+            Event::Order(e) => &e.request.symbol,
+            Event::Fill(e) => &e.order.symbol,
+        }
+    }
+}
+
+/// Delays between pipeline stages.
+///
+/// The default of zero processes signal, order and fill on the bar that
+/// produced them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LatencyModel {
+    /// Delay from signal to the order reaching the broker (ms)
+    pub order_latency_ms: i64,
+    /// Delay from order submission to fill (ms)
+    pub fill_latency_ms: i64,
+    /// Maximum random extra fill delay, drawn uniformly (ms)
+    #[serde(default)]
+    pub fill_jitter_ms: i64,
+}
+
+#[derive(Debug)]
+struct QueuedEvent {
+    due: i64,
+    seq: u64,
+    event: Event,
+}
+
+impl PartialEq for QueuedEvent {
+    fn eq(&self, other: &Self) -> bool {
+        self.due == other.due && self.seq == other.seq
+    }
+}
+
+impl Eq for QueuedEvent {}
+
+impl PartialOrd for QueuedEvent {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueuedEvent {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed so the max-heap pops the earliest event first
+        (other.due, other.seq).cmp(&(self.due, self.seq))
+    }
+}
+
+/// Time-ordered event queue.
+///
+/// Events due at the same time are popped in insertion order.
+#[derive(Debug, Default)]
+pub struct EventQueue {
+    heap: BinaryHeap<QueuedEvent>,
+    seq: u64,
+}
+
+impl EventQueue {
+    /// Create an empty queue.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Schedule an event at `due` (timestamp in ms).
+    pub fn push(&mut self, due: i64, event: Event) {
+        self.heap.push(QueuedEvent {
+            due,
+            seq: self.seq,
+            event,
+        });
+        self.seq += 1;
+    }
+
+    /// Pop the next event due at or before `now`.
+    pub fn pop_due(&mut self, now: i64) -> Option<(i64, Event)> {
+        if self.heap.peek()?.due > now {
+            return None;
+        }
+        self.heap.pop().map(|q| (q.due, q.event))
+    }
+
+    /// Number of pending events.
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Check if no events are pending.
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Copy of the pending events in due order.
+    pub fn pending(&self) -> Vec<(i64, Event)> {
+        let mut events: Vec<&QueuedEvent> = self.heap.iter().collect();
+        events.sort_by_key(|q| (q.due, q.seq));
+        events
+            .into_iter()
+            .map(|q| (q.due, q.event.clone()))
+            .collect()
+    }
+
+    /// Drop all pending events, returning them in due order.
+    pub fn drain(&mut self) -> Vec<(i64, Event)> {
+        let mut events = Vec::with_capacity(self.heap.len());
+        while let Some(q) = self.heap.pop() {
+            events.push((q.due, q.event));
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn market(symbol: &str, ts: i64) -> Event {
+        Event::Market(MarketEvent {
+            symbol: symbol.to_string(),
+            bar: Bar::new(ts, 1.0, 1.0, 1.0, 1.0, 1.0),
+        })
+    }
+
+    #[test]
+    fn test_queue_orders_by_time_then_insertion() {
+        let mut queue = EventQueue::new();
+        queue.push(20, market("C", 20));
+        queue.push(10, market("A", 10));
+        queue.push(10, market("B", 10));
+
+        assert!(queue.pop_due(5).is_none());
+        assert_eq!(queue.pop_due(10).unwrap().1.symbol(), "A");
+        assert_eq!(queue.pop_due(10).unwrap().1.symbol(), "B");
+        assert!(queue.pop_due(10).is_none());
+        assert_eq!(queue.len(), 1);
+        assert_eq!(queue.drain().len(), 1);
+        assert!(queue.is_empty());
+    }
+}
@@ -1,9 +1,53 @@
 //! Backtesting engine.
 
+mod baseline;
+mod checkpoint;
 mod engine;
+mod events;
+mod matrix;
+mod monte_carlo;
+mod optimize;
+mod options;
+mod progress;
+mod provenance;
 mod report;
+mod rng;
+mod scenario;
+mod sim_portfolio;
+mod soak;
 mod statistics;
+mod store;
+mod sweep;
 
-pub use engine::{BacktestConfig, BacktestEngine};
-pub use report::BacktestReport;
-pub use statistics::{BacktestStats, TradeRecord};
+pub use baseline::{BaselineDiff, BaselineMetric, BaselineTolerances, MetricDiff, MetricTolerance};
+pub use checkpoint::{BacktestCheckpoint, CheckpointConfig};
+pub use engine::{BacktestConfig, BacktestEngine, EndOfBacktest};
+pub use events::{
+    BracketExit, Event, EventQueue, FillEvent, LatencyModel, MarketEvent, OrderEvent, OrdersEvent,
+    SignalEvent,
+};
+pub use matrix::{MatrixRow, StrategyMatrix};
+pub use monte_carlo::MonteCarloSummary;
+pub use optimize::{
+    FoldScheme, Objective, OptimizationReport, OptimizationRow, ParamRange, ParameterGrid,
+    ParameterOptimizer, WalkForwardFold,
+};
+pub use options::{
+    OptionIncomeBacktest, OptionIncomeConfig, OptionIncomeResult, OptionIncomeStrategy, OptionTrade,
+};
+pub use progress::{BacktestProgress, ProgressConfig};
+pub use provenance::{DataSource, Provenance};
+pub use report::{BacktestReport, ReportFormat};
+pub use rng::{
+    SimulationRng, FAULT_STREAM, FEED_STREAM, FILL_STREAM, MONTE_CARLO_STREAM, SLIPPAGE_STREAM,
+};
+pub use scenario::{Scenario, ScenarioResult, Shock};
+pub use sim_portfolio::{PortfolioMode, SimPortfolio};
+pub use soak::{
+    FaultConfig, FaultyBroker, Invariant, InvariantViolation, SoakConfig, SoakReport, SoakTest,
+};
+pub use statistics::{
+    BacktestStats, OpenPositionRecord, SignalAuditRecord, SignalDecision, TradeReason, TradeRecord,
+};
+pub use store::{config_hash, ResultsStore, RunComparison, RunMetrics, StoredRun};
+pub use sweep::{SweepRow, TimeframeSweep};
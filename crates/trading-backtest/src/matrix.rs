@@ -0,0 +1,118 @@
+//! Side-by-side comparison of strategies run over the same data.
+
+use serde::{Deserialize, Serialize};
+
+use crate::report::BacktestReport;
+use crate::store::RunMetrics;
+
+/// Headline metrics of one strategy in a comparison.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatrixRow {
+    /// Strategy name
+    pub strategy: String,
+    /// Headline metrics of its run
+    pub metrics: RunMetrics,
+}
+
+/// Results of several strategies backtested over the same data and config.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StrategyMatrix {
+    /// One row per strategy, in the order they were run
+    pub rows: Vec<MatrixRow>,
+}
+
+impl StrategyMatrix {
+    /// Add a strategy's run.
+    pub fn add(&mut self, strategy: impl Into<String>, report: &BacktestReport) {
+        self.rows.push(MatrixRow {
+            strategy: strategy.into(),
+            metrics: RunMetrics::from(&report.stats),
+        });
+    }
+
+    /// Rows ranked by Sharpe ratio, best first; ties go to the higher return.
+    pub fn ranked(&self) -> Vec<&MatrixRow> {
+        let mut rows: Vec<&MatrixRow> = self.rows.iter().collect();
+        rows.sort_by(|a, b| {
+            b.metrics
+                .sharpe_ratio
+                .total_cmp(&a.metrics.sharpe_ratio)
+                .then(b.metrics.total_return_pct.cmp(&a.metrics.total_return_pct))
+        });
+        rows
+    }
+
+    /// Render as a text table.
+    pub fn render(&self) -> String {
+        let width = self
+            .rows
+            .iter()
+            .map(|r| r.strategy.len())
+            .max()
+            .unwrap_or(0)
+            .max(8);
+
+        let mut s = String::new();
+        s.push_str("STRATEGY COMPARISON\n");
+        s.push_str("───────────────────────────────────────────────────────────\n");
+        s.push_str(&format!(
+            "  {:>4}  {:<width$} {:>10} {:>8} {:>9} {:>7} {:>9}\n",
+            "Rank", "Strategy", "Return", "Sharpe", "Max DD", "Trades", "Win Rate"
+        ));
+        for (rank, row) in self.ranked().into_iter().enumerate() {
+            let m = &row.metrics;
+            s.push_str(&format!(
+                "  {:>4}  {:<width$} {:>9.2}% {:>8.2} {:>8.2}% {:>7} {:>8.2}%\n",
+                rank + 1,
+                row.strategy,
+                m.total_return_pct,
+                m.sharpe_ratio,
+                m.max_drawdown_pct,
+                m.total_trades,
+                m.win_rate_pct
+            ));
+        }
+        s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BacktestConfig, BacktestStats};
+    use rust_decimal_macros::dec;
+    use trading_core::types::Portfolio;
+
+    fn report(return_pct: rust_decimal::Decimal, sharpe: f64) -> BacktestReport {
+        let mut stats = BacktestStats::new(dec!(100000));
+        stats.total_return_pct = return_pct;
+        stats.sharpe_ratio = sharpe;
+        BacktestReport {
+            config: BacktestConfig::default(),
+            stats,
+            final_portfolio: Portfolio::new(dec!(100000)),
+            seed: 0,
+            scenarios: Vec::new(),
+            provenance: None,
+        }
+    }
+
+    #[test]
+    fn test_ranked_by_sharpe() {
+        let mut matrix = StrategyMatrix::default();
+        matrix.add("momentum", &report(dec!(12), 0.8));
+        matrix.add("rsi", &report(dec!(5), 1.4));
+        matrix.add("ma_crossover", &report(dec!(9), 0.8));
+
+        let order: Vec<&str> = matrix
+            .ranked()
+            .iter()
+            .map(|r| r.strategy.as_str())
+            .collect();
+        assert_eq!(order, vec!["rsi", "momentum", "ma_crossover"]);
+
+        let table = matrix.render();
+        assert!(table.contains("ma_crossover"));
+        assert!(table.contains("12.00%"));
+    }
+}
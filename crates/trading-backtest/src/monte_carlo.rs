@@ -0,0 +1,146 @@
+//! Monte Carlo resampling of backtest trades.
+//!
+//! Closed-trade P&Ls are drawn with replacement to build alternative
+//! equity paths, showing how much of a result is down to trade ordering
+//! and luck rather than edge.
+
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+use crate::rng::SimulationRng;
+use crate::statistics::BacktestStats;
+
+/// Outcome distribution over resampled runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonteCarloSummary {
+    /// Number of resampled runs
+    pub runs: usize,
+    /// Seed the resampling was drawn from
+    pub seed: u64,
+    /// 5th percentile of final equity
+    pub final_equity_p5: Decimal,
+    /// Median final equity
+    pub final_equity_p50: Decimal,
+    /// 95th percentile of final equity
+    pub final_equity_p95: Decimal,
+    /// Median maximum drawdown percentage
+    pub max_drawdown_pct_p50: Decimal,
+    /// 95th percentile of maximum drawdown percentage
+    pub max_drawdown_pct_p95: Decimal,
+    /// Share of runs ending below initial capital (percentage)
+    pub loss_probability_pct: Decimal,
+}
+
+impl MonteCarloSummary {
+    /// Bootstrap `runs` equity paths from the closed trades in `stats`.
+    ///
+    /// Returns `None` if there are no closed trades to resample.
+    pub fn bootstrap(stats: &BacktestStats, runs: usize, rng: &mut SimulationRng) -> Option<Self> {
+        let pnls: Vec<Decimal> = stats.trades.iter().filter_map(|t| t.pnl).collect();
+        if pnls.is_empty() || runs == 0 {
+            return None;
+        }
+
+        let mut finals = Vec::with_capacity(runs);
+        let mut drawdowns = Vec::with_capacity(runs);
+        for _ in 0..runs {
+            let mut equity = stats.initial_capital;
+            let mut peak = equity;
+            let mut max_drawdown = Decimal::ZERO;
+            for _ in 0..pnls.len() {
+                equity += pnls[rng.index(pnls.len())];
+                peak = peak.max(equity);
+                if peak > Decimal::ZERO {
+                    max_drawdown = max_drawdown.max((peak - equity) / peak * dec!(100));
+                }
+            }
+            finals.push(equity);
+            drawdowns.push(max_drawdown);
+        }
+        finals.sort();
+        drawdowns.sort();
+
+        let losses = finals
+            .iter()
+            .filter(|e| **e < stats.initial_capital)
+            .count();
+
+        Some(Self {
+            runs,
+            seed: rng.seed(),
+            final_equity_p5: percentile(&finals, 5),
+            final_equity_p50: percentile(&finals, 50),
+            final_equity_p95: percentile(&finals, 95),
+            max_drawdown_pct_p50: percentile(&drawdowns, 50),
+            max_drawdown_pct_p95: percentile(&drawdowns, 95),
+            loss_probability_pct: Decimal::from(losses * 100) / Decimal::from(runs),
+        })
+    }
+
+    /// Render as text.
+    pub fn render(&self) -> String {
+        let mut s = String::new();
+        s.push_str(&format!(
+            "MONTE CARLO ({} runs, seed {})\n",
+            self.runs, self.seed
+        ));
+        s.push_str("───────────────────────────────────────────────────────────\n");
+        s.push_str(&format!(
+            "  Final Equity p5/p50/p95: ${:.2} / ${:.2} / ${:.2}\n",
+            self.final_equity_p5, self.final_equity_p50, self.final_equity_p95
+        ));
+        s.push_str(&format!(
+            "  Max Drawdown p50/p95:    {:.2}% / {:.2}%\n",
+            self.max_drawdown_pct_p50, self.max_drawdown_pct_p95
+        ));
+        s.push_str(&format!(
+            "  Probability of Loss:     {:.1}%\n",
+            self.loss_probability_pct
+        ));
+        s
+    }
+}
+
+/// Nearest-rank percentile of sorted values.
+fn percentile(sorted: &[Decimal], pct: usize) -> Decimal {
+    let rank = (pct * sorted.len()).div_ceil(100).max(1);
+    sorted[rank.min(sorted.len()) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::MONTE_CARLO_STREAM;
+    use crate::statistics::TradeRecord;
+    use chrono::Utc;
+    use trading_core::types::{Side, SignalType};
+
+    #[test]
+    fn test_bootstrap_is_reproducible() {
+        let mut stats = BacktestStats::new(dec!(10000));
+        for pnl in [dec!(300), dec!(-200), dec!(150), dec!(-100), dec!(250)] {
+            stats.add_trade(TradeRecord {
+                symbol: "TEST".to_string(),
+                side: Side::Sell,
+                quantity: dec!(1),
+                price: dec!(100),
+                timestamp: Utc::now(),
+                signal_type: SignalType::CloseLong,
+                pnl: Some(pnl),
+                reason: Default::default(),
+            });
+        }
+
+        let run = |seed| {
+            let mut rng = SimulationRng::new(seed).fork(MONTE_CARLO_STREAM);
+            MonteCarloSummary::bootstrap(&stats, 500, &mut rng).unwrap()
+        };
+        let a = run(7);
+        let b = run(7);
+        assert_eq!(a.final_equity_p50, b.final_equity_p50);
+        assert_eq!(a.max_drawdown_pct_p95, b.max_drawdown_pct_p95);
+        assert!(a.final_equity_p5 <= a.final_equity_p50);
+        assert!(a.final_equity_p50 <= a.final_equity_p95);
+    }
+}
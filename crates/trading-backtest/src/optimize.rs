@@ -0,0 +1,635 @@
+//! Strategy parameter optimization: a grid search over strategy settings,
+//! scored on the whole data or walk-forward.
+
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::str::FromStr;
+use trading_core::error::StrategyError;
+use trading_core::traits::Strategy;
+use trading_core::types::Bar;
+
+use crate::engine::{BacktestConfig, BacktestEngine};
+use crate::store::RunMetrics;
+
+/// Most values a single range may expand to.
+const MAX_RANGE_VALUES: usize = 10_000;
+
+/// Values to try for one strategy setting.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParamRange {
+    /// Setting name, a top-level key of the strategy's configuration
+    pub name: String,
+    /// Values, in the order they are tried
+    pub values: Vec<Value>,
+}
+
+impl FromStr for ParamRange {
+    type Err = String;
+
+    /// Parse `name=<start>..<end>[:<step>]` (inclusive, step 1 by default)
+    /// or a list `name=<a>,<b>,...` of numbers, booleans or strings.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, spec) = s
+            .split_once('=')
+            .ok_or_else(|| format!("Expected name=values in parameter: {}", s))?;
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(format!("Missing name in parameter: {}", s));
+        }
+
+        let values = match spec.split_once("..") {
+            Some((start, rest)) => {
+                let (end, step) = match rest.split_once(':') {
+                    Some((end, step)) => (end, Some(step)),
+                    None => (rest, None),
+                };
+                range_values(start.trim(), end.trim(), step.map(str::trim))
+                    .map_err(|e| format!("{} in parameter: {}", e, s))?
+            }
+            None => spec
+                .split(',')
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(|v| serde_json::from_str(v).unwrap_or_else(|_| Value::from(v)))
+                .collect(),
+        };
+        if values.is_empty() {
+            return Err(format!("No values in parameter: {}", s));
+        }
+        Ok(Self {
+            name: name.to_string(),
+            values,
+        })
+    }
+}
+
+/// Values from `start` to `end` inclusive: integers when all bounds are,
+/// floats otherwise.
+fn range_values(start: &str, end: &str, step: Option<&str>) -> Result<Vec<Value>, String> {
+    if let (Ok(start), Ok(end), Ok(step)) = (
+        start.parse::<i64>(),
+        end.parse::<i64>(),
+        step.map_or(Ok(1), str::parse::<i64>),
+    ) {
+        if step <= 0 || end < start {
+            return Err("Empty range".to_string());
+        }
+        if ((end - start) / step) as usize >= MAX_RANGE_VALUES {
+            return Err("Too many values".to_string());
+        }
+        return Ok((start..=end)
+            .step_by(step as usize)
+            .map(Value::from)
+            .collect());
+    }
+
+    let number = |v: &str| {
+        v.parse::<f64>()
+            .map_err(|_| format!("Invalid number {}", v))
+    };
+    let (start, end, step) = (number(start)?, number(end)?, step.map_or(Ok(1.0), number)?);
+    if [start, end, step].iter().any(|v| !v.is_finite()) || step <= 0.0 || end < start {
+        return Err("Empty range".to_string());
+    }
+    // Tolerate rounding in the step so the end value is included
+    let count = ((end - start) / step + 1e-9).floor() as usize + 1;
+    if count > MAX_RANGE_VALUES {
+        return Err("Too many values".to_string());
+    }
+    Ok((0..count)
+        .map(|i| Value::from(((start + i as f64 * step) * 1e9).round() / 1e9))
+        .collect())
+}
+
+/// Every combination of the given parameter values.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ParameterGrid {
+    /// One range per setting
+    pub ranges: Vec<ParamRange>,
+}
+
+impl ParameterGrid {
+    /// Grid over `ranges`; each setting may appear once.
+    pub fn new(ranges: Vec<ParamRange>) -> Result<Self, String> {
+        for (i, range) in ranges.iter().enumerate() {
+            if ranges[..i].iter().any(|r| r.name == range.name) {
+                return Err(format!("Parameter {} given more than once", range.name));
+            }
+        }
+        Ok(Self { ranges })
+    }
+
+    /// Number of combinations.
+    pub fn len(&self) -> usize {
+        self.ranges.iter().map(|r| r.values.len()).product()
+    }
+
+    /// Whether the grid has no ranges.
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Settings of each combination; the last range varies fastest.
+    pub fn combinations(&self) -> Vec<Map<String, Value>> {
+        let mut combinations = vec![Map::new()];
+        for range in &self.ranges {
+            combinations = combinations
+                .into_iter()
+                .flat_map(|params| {
+                    range.values.iter().map(move |value| {
+                        let mut params = params.clone();
+                        params.insert(range.name.clone(), value.clone());
+                        params
+                    })
+                })
+                .collect();
+        }
+        combinations
+    }
+}
+
+/// Metric a parameter set is scored on; higher is better.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Objective {
+    /// Sharpe ratio
+    #[default]
+    Sharpe,
+    /// Sortino ratio
+    Sortino,
+    /// Total return percentage
+    Return,
+    /// Gross profit over gross loss
+    ProfitFactor,
+    /// Annualized return over maximum drawdown
+    Calmar,
+}
+
+impl Objective {
+    /// Score of a run's metrics.
+    pub fn score(&self, metrics: &RunMetrics) -> f64 {
+        let f = |d: rust_decimal::Decimal| d.to_f64().unwrap_or_default();
+        match self {
+            Objective::Sharpe => metrics.sharpe_ratio,
+            Objective::Sortino => metrics.sortino_ratio,
+            Objective::Return => f(metrics.total_return_pct),
+            Objective::ProfitFactor => f(metrics.profit_factor),
+            // Without a drawdown the return alone ranks the run
+            Objective::Calmar => {
+                let drawdown = f(metrics.max_drawdown_pct).abs();
+                let annualized = f(metrics.annualized_return_pct);
+                if drawdown > 0.0 {
+                    annualized / drawdown
+                } else {
+                    annualized
+                }
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Objective {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Objective::Sharpe => write!(f, "sharpe"),
+            Objective::Sortino => write!(f, "sortino"),
+            Objective::Return => write!(f, "return"),
+            Objective::ProfitFactor => write!(f, "profit_factor"),
+            Objective::Calmar => write!(f, "calmar"),
+        }
+    }
+}
+
+impl FromStr for Objective {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('-', "_").as_str() {
+            "sharpe" => Ok(Objective::Sharpe),
+            "sortino" => Ok(Objective::Sortino),
+            "return" | "total_return" => Ok(Objective::Return),
+            "profit_factor" => Ok(Objective::ProfitFactor),
+            "calmar" => Ok(Objective::Calmar),
+            _ => Err(format!("Invalid objective: {}", s)),
+        }
+    }
+}
+
+/// How the data is split to score parameter sets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FoldScheme {
+    /// One run over all the data
+    #[default]
+    None,
+    /// The data is cut into equal time windows; each window after the
+    /// first is scored out of sample, with the parameters that did best on
+    /// all the windows before it
+    WalkForward,
+}
+
+impl std::fmt::Display for FoldScheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FoldScheme::None => write!(f, "none"),
+            FoldScheme::WalkForward => write!(f, "walk-forward"),
+        }
+    }
+}
+
+impl FromStr for FoldScheme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('_', "-").as_str() {
+            "none" | "full" => Ok(FoldScheme::None),
+            "walk-forward" | "walkforward" | "wf" => Ok(FoldScheme::WalkForward),
+            _ => Err(format!("Invalid fold scheme: {}", s)),
+        }
+    }
+}
+
+/// Results of one parameter set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptimizationRow {
+    /// Settings tried, on top of the strategy's configuration
+    pub params: Map<String, Value>,
+    /// Objective over all the data
+    pub score: f64,
+    /// Mean objective over the walk-forward test windows
+    pub out_of_sample: Option<f64>,
+    /// Headline metrics of the run over all the data
+    pub metrics: RunMetrics,
+}
+
+/// One walk-forward step: parameters chosen on the training window and
+/// how they did on the following test window.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalkForwardFold {
+    /// Start of the training window (ms); it ends where the test starts
+    pub train_start: i64,
+    /// Start of the test window (ms)
+    pub test_start: i64,
+    /// End of the test window (ms), exclusive
+    pub test_end: i64,
+    /// Best parameters on the training window
+    pub params: Map<String, Value>,
+    /// Their objective on the training window
+    pub in_sample: f64,
+    /// Their objective on the test window
+    pub out_of_sample: f64,
+}
+
+/// Results of a parameter optimization.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptimizationReport {
+    /// Strategy name
+    pub strategy: String,
+    /// Metric parameter sets were scored on
+    pub objective: Objective,
+    /// How the data was split
+    pub folds: FoldScheme,
+    /// One row per parameter set, in grid order
+    pub rows: Vec<OptimizationRow>,
+    /// Walk-forward steps, oldest first
+    pub walk_forward: Vec<WalkForwardFold>,
+}
+
+impl OptimizationReport {
+    /// Rows ranked best first: by out-of-sample score when walking
+    /// forward, otherwise by score; ties go to the higher return.
+    pub fn ranked(&self) -> Vec<&OptimizationRow> {
+        let rank = |row: &OptimizationRow| row.out_of_sample.unwrap_or(row.score);
+        let mut rows: Vec<&OptimizationRow> = self.rows.iter().collect();
+        rows.sort_by(|a, b| {
+            rank(b)
+                .total_cmp(&rank(a))
+                .then(b.metrics.total_return_pct.cmp(&a.metrics.total_return_pct))
+        });
+        rows
+    }
+
+    /// Best parameter set.
+    pub fn best(&self) -> Option<&OptimizationRow> {
+        self.ranked().into_iter().next()
+    }
+
+    /// Render the `top` best rows and the walk-forward steps as text.
+    pub fn render(&self, top: usize) -> String {
+        let params = |params: &Map<String, Value>| {
+            params
+                .iter()
+                .map(|(name, value)| format!("{}={}", name, value))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        let ranked = self.ranked();
+        let width = ranked
+            .iter()
+            .take(top)
+            .map(|r| params(&r.params).len())
+            .max()
+            .unwrap_or(0)
+            .max(10);
+        let walk_forward = self.folds == FoldScheme::WalkForward;
+
+        let mut s = String::new();
+        s.push_str(&format!(
+            "OPTIMIZATION: {} by {} ({} parameter sets",
+            self.strategy,
+            self.objective,
+            self.rows.len()
+        ));
+        if walk_forward {
+            s.push_str(&format!(", {} walk-forward folds", self.walk_forward.len()));
+        }
+        s.push_str(")\n");
+        s.push_str("───────────────────────────────────────────────────────────\n");
+        s.push_str(&format!(
+            "  {:>4}  {:<width$} {:>9}",
+            "Rank", "Parameters", "Score"
+        ));
+        if walk_forward {
+            s.push_str(&format!(" {:>9}", "OOS"));
+        }
+        s.push_str(&format!(
+            " {:>10} {:>8} {:>9} {:>7}\n",
+            "Return", "Sharpe", "Max DD", "Trades"
+        ));
+        for (rank, row) in ranked.iter().take(top).enumerate() {
+            let m = &row.metrics;
+            s.push_str(&format!(
+                "  {:>4}  {:<width$} {:>9.3}",
+                rank + 1,
+                params(&row.params),
+                row.score
+            ));
+            if walk_forward {
+                s.push_str(&format!(" {:>9.3}", row.out_of_sample.unwrap_or_default()));
+            }
+            s.push_str(&format!(
+                " {:>9.2}% {:>8.2} {:>8.2}% {:>7}\n",
+                m.total_return_pct, m.sharpe_ratio, m.max_drawdown_pct, m.total_trades
+            ));
+        }
+        if ranked.len() > top {
+            s.push_str(&format!("  ... {} more\n", ranked.len() - top));
+        }
+
+        if walk_forward {
+            s.push_str("\nWALK-FORWARD\n");
+            s.push_str(&format!(
+                "  {:<10} {:<10} {:>9} {:>9}  {}\n",
+                "Test from", "Test to", "IS", "OOS", "Chosen parameters"
+            ));
+            let day = |ms: i64| {
+                chrono::DateTime::from_timestamp_millis(ms)
+                    .map(|t| t.format("%Y-%m-%d").to_string())
+                    .unwrap_or_default()
+            };
+            for fold in &self.walk_forward {
+                s.push_str(&format!(
+                    "  {:<10} {:<10} {:>9.3} {:>9.3}  {}\n",
+                    day(fold.test_start),
+                    day(fold.test_end),
+                    fold.in_sample,
+                    fold.out_of_sample,
+                    params(&fold.params)
+                ));
+            }
+        }
+        s
+    }
+}
+
+/// Grid search of a strategy's settings.
+pub struct ParameterOptimizer {
+    grid: ParameterGrid,
+    objective: Objective,
+    folds: FoldScheme,
+    windows: usize,
+}
+
+impl ParameterOptimizer {
+    /// Optimizer over `grid`, scored by the Sharpe ratio on all the data.
+    pub fn new(grid: ParameterGrid) -> Self {
+        Self {
+            grid,
+            objective: Objective::default(),
+            folds: FoldScheme::default(),
+            windows: 4,
+        }
+    }
+
+    /// Set the metric to maximize.
+    pub fn with_objective(mut self, objective: Objective) -> Self {
+        self.objective = objective;
+        self
+    }
+
+    /// Set how the data is split, and the number of walk-forward test
+    /// windows.
+    pub fn with_folds(mut self, folds: FoldScheme, windows: usize) -> Self {
+        self.folds = folds;
+        self.windows = windows.max(1);
+        self
+    }
+
+    /// Backtest every parameter set with `config`. `create` builds the
+    /// strategy for a parameter set.
+    pub async fn run<F>(
+        &self,
+        strategy: &str,
+        config: &BacktestConfig,
+        data: &HashMap<String, Vec<Bar>>,
+        mut create: F,
+    ) -> Result<OptimizationReport, StrategyError>
+    where
+        F: FnMut(&Map<String, Value>) -> Result<Box<dyn Strategy>, StrategyError>,
+    {
+        let engine = BacktestEngine::new(config.clone());
+        let windows = match self.folds {
+            FoldScheme::None => Vec::new(),
+            FoldScheme::WalkForward => time_windows(data, self.windows + 1),
+        };
+
+        let combinations = self.grid.combinations();
+        let mut rows = Vec::with_capacity(combinations.len());
+        // Objective of each parameter set on each window
+        let mut window_scores: Vec<Vec<f64>> = Vec::with_capacity(combinations.len());
+        for (i, params) in combinations.into_iter().enumerate() {
+            tracing::debug!("Parameter set {}: {:?}", i + 1, params);
+            let report = engine.run(create(&params)?.as_mut(), data.clone()).await;
+            let metrics = RunMetrics::from(&report.stats);
+
+            let mut scores = Vec::with_capacity(windows.len());
+            for &(start, end) in &windows {
+                let report = engine
+                    .run(create(&params)?.as_mut(), slice(data, start, end))
+                    .await;
+                scores.push(self.objective.score(&RunMetrics::from(&report.stats)));
+            }
+            rows.push(OptimizationRow {
+                params,
+                score: self.objective.score(&metrics),
+                out_of_sample: None,
+                metrics,
+            });
+            window_scores.push(scores);
+        }
+
+        // Walk forward: choose on the windows so far, test on the next
+        let mut walk_forward = Vec::new();
+        for test in 1..windows.len() {
+            let mut chosen: Option<(usize, f64)> = None;
+            for (row, scores) in window_scores.iter().enumerate() {
+                let in_sample = scores[..test].iter().sum::<f64>() / test as f64;
+                if chosen.map_or(true, |(_, best)| in_sample > best) {
+                    chosen = Some((row, in_sample));
+                }
+            }
+            if let Some((row, in_sample)) = chosen {
+                walk_forward.push(WalkForwardFold {
+                    train_start: windows[0].0,
+                    test_start: windows[test].0,
+                    test_end: windows[test].1,
+                    params: rows[row].params.clone(),
+                    in_sample,
+                    out_of_sample: window_scores[row][test],
+                });
+            }
+        }
+        if windows.len() > 1 {
+            for (row, scores) in rows.iter_mut().zip(&window_scores) {
+                row.out_of_sample =
+                    Some(scores[1..].iter().sum::<f64>() / (scores.len() - 1) as f64);
+            }
+        }
+
+        Ok(OptimizationReport {
+            strategy: strategy.to_string(),
+            objective: self.objective,
+            folds: self.folds,
+            rows,
+            walk_forward,
+        })
+    }
+}
+
+/// `count` equal, consecutive time windows `[start, end)` spanning the
+/// data.
+fn time_windows(data: &HashMap<String, Vec<Bar>>, count: usize) -> Vec<(i64, i64)> {
+    let timestamps = data.values().flatten().map(|bar| bar.timestamp);
+    let (Some(first), Some(last)) = (timestamps.clone().min(), timestamps.max()) else {
+        return Vec::new();
+    };
+    let span = (last + 1 - first) as f64 / count as f64;
+    let bound = |i: usize| first + (span * i as f64).round() as i64;
+    (0..count)
+        .map(|i| {
+            let end = if i + 1 == count {
+                last + 1
+            } else {
+                bound(i + 1)
+            };
+            (bound(i), end)
+        })
+        .collect()
+}
+
+/// Bars of each symbol in `[start, end)`.
+fn slice(data: &HashMap<String, Vec<Bar>>, start: i64, end: i64) -> HashMap<String, Vec<Bar>> {
+    data.iter()
+        .map(|(symbol, bars)| {
+            let bars = bars
+                .iter()
+                .filter(|bar| bar.timestamp >= start && bar.timestamp < end)
+                .copied()
+                .collect::<Vec<_>>();
+            (symbol.clone(), bars)
+        })
+        .filter(|(_, bars)| !bars.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use trading_strategies::{MACrossoverConfig, MACrossoverStrategy};
+
+    #[test]
+    fn test_param_ranges_and_grid() {
+        let period: ParamRange = "period=5..15:5".parse().unwrap();
+        assert_eq!(period.values, vec![Value::from(5), 10.into(), 15.into()]);
+        let threshold: ParamRange = "threshold=0.1..0.3:0.1".parse().unwrap();
+        assert_eq!(
+            threshold.values,
+            vec![Value::from(0.1), 0.2.into(), 0.3.into()]
+        );
+        let ma: ParamRange = "ma_type=sma,ema".parse().unwrap();
+        assert_eq!(ma.values, vec![Value::from("sma"), "ema".into()]);
+        assert!("period=30..5".parse::<ParamRange>().is_err());
+        assert!("period".parse::<ParamRange>().is_err());
+
+        let grid = ParameterGrid::new(vec![period.clone(), ma]).unwrap();
+        assert_eq!(grid.len(), 6);
+        let combinations = grid.combinations();
+        assert_eq!(combinations.len(), 6);
+        assert_eq!(combinations[1]["period"], 5);
+        assert_eq!(combinations[1]["ma_type"], "ema");
+        assert!(ParameterGrid::new(vec![period.clone(), period]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_walk_forward_optimization() {
+        let bars: Vec<Bar> = (0..400)
+            .map(|i| {
+                let price = 100.0 + (i as f64 * 0.08).sin() * 10.0 + i as f64 * 0.02;
+                Bar::new(
+                    i as i64 * 86_400_000,
+                    price,
+                    price + 1.0,
+                    price - 1.0,
+                    price + 0.5,
+                    1e6,
+                )
+            })
+            .collect();
+        let data = HashMap::from([("TEST".to_string(), bars)]);
+        let grid = ParameterGrid::new(vec![
+            "fast_period=5..10:5".parse().unwrap(),
+            "slow_period=20,30".parse().unwrap(),
+        ])
+        .unwrap();
+        let config = BacktestConfig {
+            seed: Some(7),
+            ..BacktestConfig::default()
+        };
+
+        let report = ParameterOptimizer::new(grid)
+            .with_objective(Objective::Return)
+            .with_folds(FoldScheme::WalkForward, 3)
+            .run("ma_crossover", &config, &data, |params| {
+                let mut settings = serde_json::to_value(MACrossoverConfig::default()).unwrap();
+                settings.as_object_mut().unwrap().extend(params.clone());
+                settings["symbols"] = serde_json::json!(["TEST"]);
+                let config: MACrossoverConfig = serde_json::from_value(settings)
+                    .map_err(|e| StrategyError::InvalidConfig(e.to_string()))?;
+                Ok(Box::new(MACrossoverStrategy::new(config)))
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(report.rows.len(), 4);
+        assert_eq!(report.walk_forward.len(), 3);
+        assert!(report.rows.iter().all(|r| r.out_of_sample.is_some()));
+        let best = report.best().unwrap();
+        assert!(report
+            .rows
+            .iter()
+            .all(|r| r.out_of_sample <= best.out_of_sample));
+        assert!(report.render(2).contains("WALK-FORWARD"));
+    }
+}
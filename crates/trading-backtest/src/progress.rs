@@ -0,0 +1,57 @@
+//! Progress reports from a running backtest.
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Where a running backtest has got to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestProgress {
+    /// Bars processed so far
+    pub bars_processed: usize,
+    /// Bars in the whole run
+    pub total_bars: usize,
+    /// Time of the latest bar (Unix milliseconds)
+    pub timestamp: i64,
+    /// Equity after the latest bar
+    pub equity: Decimal,
+}
+
+impl BacktestProgress {
+    /// Share of the run done, from 0 to 1.
+    pub fn fraction(&self) -> f64 {
+        if self.total_bars == 0 {
+            1.0
+        } else {
+            self.bars_processed as f64 / self.total_bars as f64
+        }
+    }
+}
+
+/// How often and where to send progress.
+#[derive(Debug, Clone)]
+pub struct ProgressConfig {
+    /// Receives a report every `every_bars` bars and after the last one
+    pub sender: UnboundedSender<BacktestProgress>,
+    /// Bars processed between reports
+    pub every_bars: usize,
+}
+
+impl ProgressConfig {
+    /// Create a progress config.
+    pub fn new(sender: UnboundedSender<BacktestProgress>, every_bars: usize) -> Self {
+        Self {
+            sender,
+            every_bars: every_bars.max(1),
+        }
+    }
+
+    /// Send `progress` if it is due; a receiver that went away is ignored.
+    pub(crate) fn report(&self, progress: BacktestProgress) {
+        if progress.bars_processed % self.every_bars == 0
+            || progress.bars_processed == progress.total_bars
+        {
+            let _ = self.sender.send(progress);
+        }
+    }
+}
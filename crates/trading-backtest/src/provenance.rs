@@ -0,0 +1,150 @@
+//! Provenance of a backtest report: what produced it and from which data.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use trading_core::types::Bar;
+
+use crate::store::fnv1a;
+
+/// Input data of one symbol, identified well enough to tell whether it
+/// has changed since the run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DataSource {
+    /// Symbol the data was loaded for
+    pub symbol: String,
+    /// File or directory the data was loaded from
+    pub path: PathBuf,
+    /// Bars loaded
+    pub bars: usize,
+    /// Timestamp of the first bar (ms)
+    pub first_timestamp: Option<i64>,
+    /// Timestamp of the last bar (ms)
+    pub last_timestamp: Option<i64>,
+    /// Hash of the bar contents
+    pub fingerprint: String,
+}
+
+impl DataSource {
+    /// Describe the `bars` loaded for `symbol` from `path`.
+    pub fn new(symbol: impl Into<String>, path: impl AsRef<Path>, bars: &[Bar]) -> Self {
+        let bytes = bars.iter().flat_map(|b| {
+            [b.open, b.high, b.low, b.close, b.volume]
+                .into_iter()
+                .flat_map(|v| v.to_bits().to_le_bytes())
+                .chain(b.timestamp.to_le_bytes())
+        });
+        Self {
+            symbol: symbol.into(),
+            path: path.as_ref().to_path_buf(),
+            bars: bars.len(),
+            first_timestamp: bars.first().map(|b| b.timestamp),
+            last_timestamp: bars.last().map(|b| b.timestamp),
+            fingerprint: format!("{:016x}", fnv1a(bytes)),
+        }
+    }
+}
+
+/// Everything needed to re-run a backtest exactly.
+///
+/// The backtest and risk configuration and the seed are already part of
+/// the report; this adds what the engine never sees.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Provenance {
+    /// Version of the program that ran the backtest
+    pub version: String,
+    /// When the run was made
+    pub created_at: DateTime<Utc>,
+    /// Strategy name
+    pub strategy: String,
+    /// Strategy configuration as resolved for the run
+    pub strategy_config: Value,
+    /// Command-line arguments as parsed, defaults filled in
+    pub args: Value,
+    /// Input data per symbol
+    pub data: Vec<DataSource>,
+}
+
+impl Provenance {
+    /// Record a run of `strategy` configured with `strategy_config`.
+    pub fn new(
+        version: impl Into<String>,
+        strategy: impl Into<String>,
+        strategy_config: Value,
+    ) -> Self {
+        Self {
+            version: version.into(),
+            created_at: Utc::now(),
+            strategy: strategy.into(),
+            strategy_config,
+            args: Value::Null,
+            data: Vec::new(),
+        }
+    }
+
+    /// Set the parsed command-line arguments.
+    pub fn with_args(mut self, args: Value) -> Self {
+        self.args = args;
+        self
+    }
+
+    /// Describe the data loaded from `path`.
+    pub fn with_data(mut self, path: impl AsRef<Path>, data: &HashMap<String, Vec<Bar>>) -> Self {
+        self.data = data
+            .iter()
+            .map(|(symbol, bars)| DataSource::new(symbol, path.as_ref(), bars))
+            .collect();
+        self.data.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+        self
+    }
+
+    /// Differences between the recorded data and `data`, one line each.
+    pub fn data_changes(&self, data: &HashMap<String, Vec<Bar>>) -> Vec<String> {
+        let mut changes = Vec::new();
+        for recorded in &self.data {
+            match data.get(&recorded.symbol) {
+                None => changes.push(format!("{}: no data loaded", recorded.symbol)),
+                Some(bars) => {
+                    let current = DataSource::new(&recorded.symbol, &recorded.path, bars);
+                    if current.fingerprint != recorded.fingerprint {
+                        changes.push(format!(
+                            "{}: {} bars now, {} when the report was saved",
+                            recorded.symbol, current.bars, recorded.bars
+                        ));
+                    }
+                }
+            }
+        }
+        for symbol in data.keys() {
+            if !self.data.iter().any(|d| &d.symbol == symbol) {
+                changes.push(format!("{}: not in the original run", symbol));
+            }
+        }
+        changes.sort();
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_data_changes() {
+        let bars: Vec<Bar> = (0..5)
+            .map(|i| Bar::new(i * 60_000, 100.0, 101.0, 99.0, 100.5, 1_000.0))
+            .collect();
+        let data = HashMap::from([("AAPL".to_string(), bars.clone())]);
+        let provenance =
+            Provenance::new("0.1.0", "momentum", Value::Null).with_data("data/aapl.csv", &data);
+        assert!(provenance.data_changes(&data).is_empty());
+
+        let mut edited = bars;
+        edited[2].close = 100.6;
+        let changes = provenance.data_changes(&HashMap::from([("AAPL".to_string(), edited)]));
+        assert_eq!(changes.len(), 1);
+        assert!(changes[0].starts_with("AAPL"));
+    }
+}
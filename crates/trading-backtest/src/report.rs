@@ -1,9 +1,13 @@
 //! Backtest report generation.
 
 use serde::{Deserialize, Serialize};
+use std::path::Path;
+use trading_core::error::{TradingError, TradingResult};
 use trading_core::types::Portfolio;
 
-use crate::{BacktestConfig, BacktestStats};
+use crate::{
+    BacktestConfig, BacktestStats, Provenance, ScenarioResult, SignalDecision, TradeReason,
+};
 
 /// Complete backtest report.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,9 +18,73 @@ pub struct BacktestReport {
     pub stats: BacktestStats,
     /// Final portfolio state
     pub final_portfolio: Portfolio,
+    /// Seed of the run's stochastic models; pass it back to reproduce the run
+    #[serde(default)]
+    pub seed: u64,
+    /// Results of stress scenarios run against the same strategy
+    #[serde(default)]
+    pub scenarios: Vec<ScenarioResult>,
+    /// What produced the report, for re-running it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+}
+
+/// Format a report is rendered in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportFormat {
+    /// The text summary
+    #[default]
+    Text,
+    /// The full report as JSON, as saved with `--save`
+    Json,
+    /// A standalone HTML page with an equity chart and the trades
+    Html,
+    /// The equity curve as CSV
+    EquityCsv,
+    /// The trades as CSV
+    TradesCsv,
+}
+
+impl std::fmt::Display for ReportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReportFormat::Text => write!(f, "text"),
+            ReportFormat::Json => write!(f, "json"),
+            ReportFormat::Html => write!(f, "html"),
+            ReportFormat::EquityCsv => write!(f, "equity_csv"),
+            ReportFormat::TradesCsv => write!(f, "trades_csv"),
+        }
+    }
+}
+
+impl std::str::FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('-', "_").as_str() {
+            "text" | "txt" => Ok(ReportFormat::Text),
+            "json" => Ok(ReportFormat::Json),
+            "html" => Ok(ReportFormat::Html),
+            "equity_csv" | "csv" => Ok(ReportFormat::EquityCsv),
+            "trades_csv" => Ok(ReportFormat::TradesCsv),
+            _ => Err(format!("Invalid report format: {}", s)),
+        }
+    }
 }
 
 impl BacktestReport {
+    /// Render in `format`.
+    pub fn render(&self, format: ReportFormat) -> Result<String, serde_json::Error> {
+        Ok(match format {
+            ReportFormat::Text => self.summary(),
+            ReportFormat::Json => self.to_json()?,
+            ReportFormat::Html => self.to_html(),
+            ReportFormat::EquityCsv => self.equity_to_csv(),
+            ReportFormat::TradesCsv => self.trades_to_csv(),
+        })
+    }
+
     /// Generate a text summary.
     pub fn summary(&self) -> String {
         let mut s = String::new();
@@ -59,6 +127,18 @@ impl BacktestReport {
             "  Sortino Ratio:       {:.2}\n",
             self.stats.sortino_ratio
         ));
+        if let Some(var) = &self.stats.value_at_risk {
+            s.push_str(&format!(
+                "  VaR (95%, 1 bar):    {:.2}% (parametric {:.2}%)\n",
+                var.historical_var * 100.0,
+                var.parametric_var * 100.0
+            ));
+            s.push_str(&format!(
+                "  Expected Shortfall:  {:.2}% (parametric {:.2}%)\n",
+                var.historical_es * 100.0,
+                var.parametric_es * 100.0
+            ));
+        }
         s.push_str(&format!(
             "  Profit Factor:       {:.2}\n",
             self.stats.profit_factor
@@ -95,8 +175,33 @@ impl BacktestReport {
             "  Avg Loss:            ${:.2}\n",
             self.stats.avg_loss
         ));
+        for (label, reason) in [
+            ("Stop-Loss Exits", TradeReason::StopLoss),
+            ("Take-Profit Exits", TradeReason::TakeProfit),
+        ] {
+            let count = self.stats.trades_for(reason);
+            if count > 0 {
+                s.push_str(&format!("  {:<21}{}\n", format!("{}:", label), count));
+            }
+        }
         s.push('\n');
 
+        if !self.stats.open_positions.is_empty() {
+            s.push_str("OPEN POSITIONS AT END\n");
+            s.push_str("───────────────────────────────────────────────────────────\n");
+            for pos in &self.stats.open_positions {
+                s.push_str(&format!(
+                    "  {:<8} {:>10} @ ${:.2} → ${:.2}  P&L ${:.2}\n",
+                    pos.symbol, pos.quantity, pos.entry_price, pos.mark_price, pos.unrealized_pnl
+                ));
+            }
+            s.push_str(&format!(
+                "  Open P&L:            ${:.2}\n",
+                self.stats.open_pnl
+            ));
+            s.push('\n');
+        }
+
         s.push_str("EXECUTION\n");
         s.push_str("───────────────────────────────────────────────────────────\n");
         s.push_str(&format!(
@@ -107,8 +212,66 @@ impl BacktestReport {
             "  Equity Points:       {}\n",
             self.stats.equity_curve.len()
         ));
+        s.push_str(&format!("  Seed:                {}\n", self.seed));
+        s.push_str(&format!(
+            "  Risk Rejections:     {}\n",
+            self.stats.risk_rejections
+        ));
+        if let Some(error) = self.stats.float_equity_error {
+            s.push_str(&format!("  Float Equity Drift:  {:.6}\n", error));
+        }
         s.push('\n');
 
+        if !self.stats.signal_audit.is_empty() {
+            s.push_str("SIGNALS\n");
+            s.push_str("───────────────────────────────────────────────────────────\n");
+            for (decision, count) in self.stats.signal_decisions() {
+                let label = match decision {
+                    SignalDecision::Approved => "Approved:",
+                    SignalDecision::Modified => "Modified:",
+                    SignalDecision::Skipped => "Skipped:",
+                    SignalDecision::Rejected => "Rejected:",
+                };
+                s.push_str(&format!("  {:<20} {}\n", label, count));
+            }
+            let reasons = self.stats.blocking_reasons(5);
+            if !reasons.is_empty() {
+                s.push_str("  Top reasons not traded:\n");
+                for (reason, count) in reasons {
+                    s.push_str(&format!("    {:>5}  {}\n", count, reason));
+                }
+            }
+            s.push('\n');
+        }
+
+        if !self.scenarios.is_empty() {
+            s.push_str("STRESS SCENARIOS\n");
+            s.push_str("───────────────────────────────────────────────────────────\n");
+            let width = self
+                .scenarios
+                .iter()
+                .map(|r| r.scenario.chars().count())
+                .max()
+                .unwrap_or(0)
+                .max(16);
+            s.push_str(&format!(
+                "  {:<width$} {:>9} {:>9} {:>8} {:>7} {:>8}\n",
+                "Scenario", "Return", "Change", "Max DD", "Trades", "Blocked"
+            ));
+            for result in &self.scenarios {
+                s.push_str(&format!(
+                    "  {:<width$} {:>8.2}% {:>+8.2}% {:>7.2}% {:>7} {:>8}\n",
+                    result.scenario,
+                    result.total_return_pct,
+                    result.return_change_pct,
+                    result.max_drawdown_pct,
+                    result.total_trades,
+                    result.risk_rejections
+                ));
+            }
+            s.push('\n');
+        }
+
         s.push_str("═══════════════════════════════════════════════════════════\n");
 
         s
@@ -119,6 +282,14 @@ impl BacktestReport {
         serde_json::to_string_pretty(self)
     }
 
+    /// Read a report saved as JSON.
+    pub fn load(path: impl AsRef<Path>) -> TradingResult<Self> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path)?;
+        serde_json::from_str(&json)
+            .map_err(|e| TradingError::Serialization(format!("{}: {}", path.display(), e)))
+    }
+
     /// Export to CSV (equity curve only).
     pub fn equity_to_csv(&self) -> String {
         let mut csv = String::from("timestamp,equity\n");
@@ -127,12 +298,176 @@ impl BacktestReport {
         }
         csv
     }
+
+    /// Export the trades to CSV.
+    pub fn trades_to_csv(&self) -> String {
+        let mut csv = String::from("timestamp,symbol,side,quantity,price,signal,reason,pnl\n");
+        for trade in &self.stats.trades {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{},{}\n",
+                trade.timestamp.to_rfc3339(),
+                trade.symbol,
+                trade.side,
+                trade.quantity,
+                trade.price,
+                trade.signal_type,
+                trade.reason,
+                trade.pnl.map(|pnl| pnl.to_string()).unwrap_or_default()
+            ));
+        }
+        csv
+    }
+
+    /// Export as a standalone HTML page: the headline metrics, the equity
+    /// curve as an SVG chart, signal decisions and the trades.
+    pub fn to_html(&self) -> String {
+        let stats = &self.stats;
+        let mut s = String::new();
+        s.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+        s.push_str("<title>Backtest Report</title>\n<style>\n");
+        s.push_str("body{font-family:sans-serif;margin:2em;color:#222}");
+        s.push_str("table{border-collapse:collapse;margin-bottom:2em}");
+        s.push_str("td,th{padding:4px 12px;border-bottom:1px solid #ddd;text-align:right}");
+        s.push_str("td:first-child,th:first-child{text-align:left}");
+        s.push_str(".loss{color:#c0392b}.win{color:#27ae60}\n");
+        s.push_str("</style>\n</head>\n<body>\n<h1>Backtest Report</h1>\n");
+
+        s.push_str("<h2>Performance</h2>\n<table>\n");
+        let mut metrics = vec![
+            ("Initial Capital", format!("${:.2}", stats.initial_capital)),
+            ("Final Equity", format!("${:.2}", stats.final_equity)),
+            ("Total Return", format!("{:.2}%", stats.total_return_pct)),
+            (
+                "Annualized Return",
+                format!("{:.2}%", stats.annualized_return_pct),
+            ),
+            ("Max Drawdown", format!("{:.2}%", stats.max_drawdown_pct)),
+            ("Sharpe Ratio", format!("{:.2}", stats.sharpe_ratio)),
+            ("Sortino Ratio", format!("{:.2}", stats.sortino_ratio)),
+            ("Profit Factor", format!("{:.2}", stats.profit_factor)),
+            ("Total Trades", stats.total_trades.to_string()),
+            ("Win Rate", format!("{:.2}%", stats.win_rate_pct)),
+            ("Avg Win", format!("${:.2}", stats.avg_win)),
+            ("Avg Loss", format!("${:.2}", stats.avg_loss)),
+            ("Bars Processed", stats.bars_processed.to_string()),
+            ("Seed", self.seed.to_string()),
+        ];
+        if !stats.open_positions.is_empty() {
+            metrics.push(("Open P&L", format!("${:.2}", stats.open_pnl)));
+        }
+        for (label, value) in metrics {
+            s.push_str(&format!("<tr><td>{}</td><td>{}</td></tr>\n", label, value));
+        }
+        s.push_str("</table>\n");
+
+        if stats.equity_curve.len() > 1 {
+            s.push_str("<h2>Equity</h2>\n");
+            s.push_str(&equity_svg(&stats.equity_curve));
+        }
+
+        if !stats.signal_audit.is_empty() {
+            s.push_str("<h2>Signals</h2>\n<table>\n");
+            for (decision, count) in stats.signal_decisions() {
+                s.push_str(&format!(
+                    "<tr><td>{}</td><td>{}</td></tr>\n",
+                    decision, count
+                ));
+            }
+            for (reason, count) in stats.blocking_reasons(5) {
+                s.push_str(&format!(
+                    "<tr><td>Not traded: {}</td><td>{}</td></tr>\n",
+                    escape_html(&reason),
+                    count
+                ));
+            }
+            s.push_str("</table>\n");
+        }
+
+        if !stats.trades.is_empty() {
+            s.push_str("<h2>Trades</h2>\n<table>\n");
+            s.push_str(
+                "<tr><th>Time</th><th>Symbol</th><th>Side</th><th>Quantity</th>\
+                 <th>Price</th><th>Reason</th><th>P&amp;L</th></tr>\n",
+            );
+            for trade in &stats.trades {
+                let pnl = match trade.pnl {
+                    Some(pnl) if pnl.is_sign_negative() => {
+                        format!("<td class=\"loss\">{:.2}</td>", pnl)
+                    }
+                    Some(pnl) => format!("<td class=\"win\">{:.2}</td>", pnl),
+                    None => "<td></td>".to_string(),
+                };
+                s.push_str(&format!(
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{:.2}</td><td>{}</td>{}</tr>\n",
+                    trade.timestamp.format("%Y-%m-%d %H:%M"),
+                    escape_html(&trade.symbol),
+                    trade.side,
+                    trade.quantity,
+                    trade.price,
+                    trade.reason,
+                    pnl
+                ));
+            }
+            s.push_str("</table>\n");
+        }
+
+        s.push_str("</body>\n</html>\n");
+        s
+    }
+}
+
+/// Equity curve as an inline SVG line chart.
+fn equity_svg(curve: &[(i64, rust_decimal::Decimal)]) -> String {
+    use rust_decimal::prelude::ToPrimitive;
+
+    const WIDTH: f64 = 800.0;
+    const HEIGHT: f64 = 240.0;
+    let values: Vec<f64> = curve
+        .iter()
+        .map(|(_, equity)| equity.to_f64().unwrap_or_default())
+        .collect();
+    let low = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let high = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let range = (high - low).max(f64::EPSILON);
+    let step = WIDTH / (values.len() - 1) as f64;
+    let points: Vec<String> = values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            format!(
+                "{:.1},{:.1}",
+                i as f64 * step,
+                HEIGHT - (value - low) / range * HEIGHT
+            )
+        })
+        .collect();
+    format!(
+        "<svg viewBox=\"0 0 {} {}\" width=\"{}\" height=\"{}\">\n\
+         <polyline fill=\"none\" stroke=\"#2980b9\" stroke-width=\"1.5\" points=\"{}\"/>\n\
+         </svg>\n<p>Low ${:.2}, high ${:.2}</p>\n",
+        WIDTH,
+        HEIGHT,
+        WIDTH,
+        HEIGHT,
+        points.join(" "),
+        low,
+        high
+    )
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::TradeRecord;
     use rust_decimal_macros::dec;
+    use trading_core::types::{Side, SignalType};
 
     #[test]
     fn test_report_summary() {
@@ -147,10 +482,50 @@ mod tests {
             config,
             stats,
             final_portfolio: Portfolio::new(dec!(110000)),
+            seed: 0,
+            scenarios: Vec::new(),
+            provenance: None,
         };
 
         let summary = report.summary();
         assert!(summary.contains("Total Return"));
         assert!(summary.contains("10.00%"));
     }
+
+    #[test]
+    fn test_render_formats() {
+        let mut stats = BacktestStats::new(dec!(100000));
+        stats.record_equity(0, dec!(100000));
+        stats.record_equity(86_400_000, dec!(101000));
+        stats.add_trade(TradeRecord {
+            symbol: "AT&T".to_string(),
+            side: Side::Sell,
+            quantity: dec!(10),
+            price: dec!(101),
+            timestamp: chrono::DateTime::from_timestamp_millis(86_400_000).unwrap(),
+            signal_type: SignalType::CloseLong,
+            pnl: Some(dec!(-5)),
+            reason: TradeReason::StopLoss,
+        });
+        let report = BacktestReport {
+            config: BacktestConfig::default(),
+            stats,
+            final_portfolio: Portfolio::new(dec!(101000)),
+            seed: 3,
+            scenarios: Vec::new(),
+            provenance: None,
+        };
+
+        let html = report.render(ReportFormat::Html).unwrap();
+        assert!(html.contains("<polyline"));
+        assert!(html.contains("AT&amp;T"));
+        let csv = report.render("trades-csv".parse().unwrap()).unwrap();
+        assert_eq!(csv.lines().count(), 2);
+        assert!(csv.contains(",stop_loss,-5"));
+        let json = report.render(ReportFormat::Json).unwrap();
+        assert_eq!(
+            serde_json::from_str::<BacktestReport>(&json).unwrap().seed,
+            3
+        );
+    }
 }
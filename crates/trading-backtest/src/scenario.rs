@@ -0,0 +1,301 @@
+//! Stress scenarios.
+//!
+//! A scenario replays the historical data with shocks injected at a point
+//! in the run (a gap down, amplified volatility, a data outage), so the
+//! strategy and risk limits can be judged on conditions the sample did not
+//! contain.
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use trading_core::types::Bar;
+
+use crate::report::BacktestReport;
+
+const DAY_MS: i64 = 86_400_000;
+
+/// A single market shock.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Shock {
+    /// All prices from the shock onwards drop by `pct` percent
+    GapDown { pct: f64 },
+    /// Bar-to-bar moves are multiplied by `multiplier` for `bars` bars
+    Volatility { multiplier: f64, bars: usize },
+    /// No bars arrive for `days` days
+    DataOutage { days: u32 },
+}
+
+impl FromStr for Shock {
+    type Err = String;
+
+    /// Parse `gap_down:<pct>`, `volatility:<multiplier>[:<bars>]` or
+    /// `outage:<days>`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(':');
+        let kind = parts.next().unwrap_or_default().to_lowercase();
+        let args: Vec<&str> = parts.collect();
+        let number = |i: usize| -> Result<f64, String> {
+            args.get(i)
+                .ok_or_else(|| format!("Missing value in shock: {}", s))?
+                .parse::<f64>()
+                .map_err(|_| format!("Invalid value in shock: {}", s))
+        };
+
+        let shock = match kind.as_str() {
+            "gap_down" | "gap" => Shock::GapDown { pct: number(0)? },
+            "volatility" | "vol" => Shock::Volatility {
+                multiplier: number(0)?,
+                bars: if args.len() > 1 {
+                    number(1)? as usize
+                } else {
+                    20
+                },
+            },
+            "outage" | "data_outage" => Shock::DataOutage {
+                days: number(0)? as u32,
+            },
+            _ => return Err(format!("Unknown shock: {}", s)),
+        };
+
+        match shock {
+            Shock::GapDown { pct } if !(0.0..100.0).contains(&pct) => {
+                Err(format!("Gap must be between 0 and 100%: {}", s))
+            }
+            Shock::Volatility { multiplier, .. } if multiplier <= 0.0 => {
+                Err(format!("Volatility multiplier must be positive: {}", s))
+            }
+            _ => Ok(shock),
+        }
+    }
+}
+
+/// Shocks applied together at one point in the data.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Scenario {
+    /// Label shown in the report
+    pub name: String,
+    /// Where the shocks hit, as a percentage of the way through the data
+    pub at_pct: f64,
+    /// Shocks to inject
+    pub shocks: Vec<Shock>,
+}
+
+impl Scenario {
+    /// Create a scenario hitting halfway through the data.
+    pub fn new(name: impl Into<String>, shocks: Vec<Shock>) -> Self {
+        Self {
+            name: name.into(),
+            at_pct: 50.0,
+            shocks,
+        }
+    }
+
+    /// Set where the shocks hit (percentage of the way through the data).
+    pub fn at(mut self, at_pct: f64) -> Self {
+        self.at_pct = at_pct.clamp(0.0, 100.0);
+        self
+    }
+
+    /// Built-in scenarios: a 10% gap down, doubled volatility for 20 bars
+    /// and a 5-day data outage.
+    pub fn standard() -> Vec<Self> {
+        vec![
+            Self::new("Gap down 10%", vec![Shock::GapDown { pct: 10.0 }]),
+            Self::new(
+                "Volatility x2",
+                vec![Shock::Volatility {
+                    multiplier: 2.0,
+                    bars: 20,
+                }],
+            ),
+            Self::new("Data outage 5d", vec![Shock::DataOutage { days: 5 }]),
+        ]
+    }
+
+    /// Copy of `data` with this scenario's shocks injected.
+    pub fn apply(&self, data: &HashMap<String, Vec<Bar>>) -> HashMap<String, Vec<Bar>> {
+        let first = data.values().flatten().map(|b| b.timestamp).min();
+        let last = data.values().flatten().map(|b| b.timestamp).max();
+        let (Some(first), Some(last)) = (first, last) else {
+            return data.clone();
+        };
+        let shock_at = first + ((last - first) as f64 * self.at_pct / 100.0) as i64;
+
+        data.iter()
+            .map(|(symbol, bars)| (symbol.clone(), self.shock_series(bars, shock_at)))
+            .collect()
+    }
+
+    fn shock_series(&self, bars: &[Bar], shock_at: i64) -> Vec<Bar> {
+        let mut bars = bars.to_vec();
+        bars.sort_by_key(|b| b.timestamp);
+
+        let outage_end = self
+            .shocks
+            .iter()
+            .filter_map(|s| match s {
+                Shock::DataOutage { days } => Some(shock_at + *days as i64 * DAY_MS),
+                _ => None,
+            })
+            .max();
+
+        let mut shocked = Vec::with_capacity(bars.len());
+        // Ratio of shocked to original price, carried forward after the shock
+        let mut scale = 1.0;
+        let mut prev_close: Option<f64> = None;
+        let mut started = false;
+        let mut volatile: Option<(f64, usize)> = None;
+
+        for bar in bars {
+            if bar.timestamp < shock_at {
+                prev_close = Some(bar.close);
+                shocked.push(bar);
+                continue;
+            }
+            if outage_end.is_some_and(|end| bar.timestamp < end) {
+                continue;
+            }
+
+            // Price shocks land on the first bar delivered after the shock
+            if !started {
+                started = true;
+                for shock in &self.shocks {
+                    match *shock {
+                        Shock::GapDown { pct } => scale *= 1.0 - pct / 100.0,
+                        Shock::Volatility { multiplier, bars } => {
+                            volatile = Some((multiplier, bars))
+                        }
+                        Shock::DataOutage { .. } => {}
+                    }
+                }
+            }
+
+            let multiplier = match &mut volatile {
+                Some((multiplier, remaining)) if *remaining > 0 => {
+                    *remaining -= 1;
+                    *multiplier
+                }
+                _ => 1.0,
+            };
+
+            // Move each price relative to the previous close by the multiplier
+            let base = prev_close.unwrap_or(bar.open);
+            let transform =
+                |price: f64| (base * scale * (1.0 + multiplier * (price / base - 1.0))).max(0.01);
+
+            let mut out = bar;
+            out.open = transform(bar.open);
+            out.high = transform(bar.high);
+            out.low = transform(bar.low);
+            out.close = transform(bar.close);
+            out.vwap = bar.vwap.map(transform);
+
+            if bar.close > 0.0 {
+                scale = out.close / bar.close;
+            }
+            prev_close = Some(bar.close);
+            shocked.push(out);
+        }
+
+        shocked
+    }
+}
+
+impl FromStr for Scenario {
+    type Err = String;
+
+    /// Parse shocks joined by `+`, optionally followed by `@<pct>` for where
+    /// they hit, e.g. `gap_down:15+volatility:3:10@25`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (shocks, at) = match s.split_once('@') {
+            Some((shocks, at)) => (
+                shocks,
+                at.parse::<f64>()
+                    .map_err(|_| format!("Invalid scenario position: {}", at))?,
+            ),
+            None => (s, 50.0),
+        };
+        let shocks = shocks
+            .split('+')
+            .map(str::parse)
+            .collect::<Result<Vec<Shock>, _>>()?;
+        Ok(Self::new(s, shocks).at(at))
+    }
+}
+
+/// How a run fared under a scenario, compared with the unshocked run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScenarioResult {
+    /// Scenario label
+    pub scenario: String,
+    /// Total return percentage
+    pub total_return_pct: Decimal,
+    /// Difference from the baseline total return, in percentage points
+    pub return_change_pct: Decimal,
+    /// Maximum drawdown percentage
+    pub max_drawdown_pct: Decimal,
+    /// Closed trades
+    pub total_trades: usize,
+    /// Signals blocked by risk limits
+    pub risk_rejections: usize,
+    /// Final equity
+    pub final_equity: Decimal,
+}
+
+impl ScenarioResult {
+    /// Summarize a scenario run against the baseline.
+    pub fn new(scenario: &Scenario, run: &BacktestReport, baseline: &BacktestReport) -> Self {
+        Self {
+            scenario: scenario.name.clone(),
+            total_return_pct: run.stats.total_return_pct,
+            return_change_pct: run.stats.total_return_pct - baseline.stats.total_return_pct,
+            max_drawdown_pct: run.stats.max_drawdown_pct,
+            total_trades: run.stats.total_trades,
+            risk_rejections: run.stats.risk_rejections,
+            final_equity: run.stats.final_equity,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bars(closes: &[f64]) -> HashMap<String, Vec<Bar>> {
+        let bars = closes
+            .iter()
+            .enumerate()
+            .map(|(i, c)| Bar::new(i as i64 * DAY_MS, *c, c + 1.0, c - 1.0, *c, 1000.0))
+            .collect();
+        HashMap::from([("TEST".to_string(), bars)])
+    }
+
+    #[test]
+    fn test_shocks_applied_after_shock_point() {
+        let data = bars(&[100.0, 102.0, 101.0, 103.0, 104.0]);
+
+        let gap: Scenario = "gap_down:10@50".parse().unwrap();
+        let shocked = &gap.apply(&data)["TEST"];
+        assert_eq!(shocked[1].close, 102.0);
+        assert!((shocked[2].close - 90.9).abs() < 1e-9);
+        assert!((shocked[4].close - 93.6).abs() < 1e-9);
+
+        let vol: Scenario = "volatility:2:1@50".parse().unwrap();
+        let shocked = &vol.apply(&data)["TEST"];
+        // 102 -> 101 becomes 102 -> 100; later moves keep their size
+        assert!((shocked[2].close - 100.0).abs() < 1e-9);
+        assert!((shocked[3].close - 100.0 * 103.0 / 101.0).abs() < 1e-9);
+        assert!(shocked[2].high > shocked[2].low);
+
+        let outage: Scenario = "outage:2@25".parse().unwrap();
+        let shocked = &outage.apply(&data)["TEST"];
+        assert_eq!(
+            shocked.iter().map(|b| b.close).collect::<Vec<_>>(),
+            vec![100.0, 103.0, 104.0]
+        );
+
+        assert!("crash:10".parse::<Scenario>().is_err());
+    }
+}
@@ -0,0 +1,253 @@
+//! Floating-point portfolio for the simulation hot loop.
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use trading_core::types::{Instrument, Portfolio, Side};
+
+/// Arithmetic used for the engine's per-bar portfolio accounting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PortfolioMode {
+    /// Exact `Decimal` accounting in the broker
+    #[default]
+    Decimal,
+    /// `f64` accounting in a [`SimPortfolio`]; faster, for large
+    /// optimization runs where cent-level drift doesn't matter
+    Float,
+    /// Run both and track how far the `f64` equity drifts from the
+    /// `Decimal` equity, which stays authoritative
+    Validate,
+}
+
+impl std::str::FromStr for PortfolioMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "decimal" => Ok(PortfolioMode::Decimal),
+            "float" | "f64" => Ok(PortfolioMode::Float),
+            "validate" => Ok(PortfolioMode::Validate),
+            _ => Err(format!("Invalid portfolio mode: {}", s)),
+        }
+    }
+}
+
+/// A position tracked in `f64`.
+#[derive(Debug, Clone, Copy)]
+struct SimPosition {
+    quantity: f64,
+    avg_entry_price: f64,
+    current_price: f64,
+    multiplier: f64,
+}
+
+/// Cash and positions in `f64`, mirroring the broker's `Decimal` accounting.
+#[derive(Debug, Clone, Default)]
+pub struct SimPortfolio {
+    cash: f64,
+    positions: HashMap<String, SimPosition>,
+    /// Contract multipliers of the symbols that have one
+    multipliers: HashMap<String, f64>,
+}
+
+impl SimPortfolio {
+    /// Create a portfolio holding only cash.
+    pub fn new(cash: f64) -> Self {
+        Self {
+            cash,
+            positions: HashMap::new(),
+            multipliers: HashMap::new(),
+        }
+    }
+
+    /// Set contract multipliers by symbol; other symbols trade as shares.
+    pub fn with_instruments(mut self, instruments: &HashMap<String, Instrument>) -> Self {
+        self.multipliers = instruments
+            .iter()
+            .map(|(symbol, i)| (symbol.clone(), to_f64(i.multiplier)))
+            .collect();
+        self
+    }
+
+    /// Convert a `Decimal` portfolio, e.g. one restored from a checkpoint.
+    pub fn from_portfolio(portfolio: &Portfolio) -> Self {
+        let positions = portfolio
+            .positions
+            .iter()
+            .map(|(symbol, p)| {
+                let position = SimPosition {
+                    quantity: to_f64(p.quantity),
+                    avg_entry_price: to_f64(p.avg_entry_price),
+                    current_price: to_f64(p.current_price),
+                    multiplier: to_f64(p.multiplier),
+                };
+                (symbol.clone(), position)
+            })
+            .collect();
+        let multipliers = portfolio
+            .positions
+            .iter()
+            .map(|(symbol, p)| (symbol.clone(), to_f64(p.multiplier)))
+            .collect();
+        Self {
+            cash: to_f64(portfolio.cash),
+            positions,
+            multipliers,
+        }
+    }
+
+    /// Available cash.
+    pub fn cash(&self) -> f64 {
+        self.cash
+    }
+
+    /// Signed quantity held in `symbol`.
+    pub fn quantity(&self, symbol: &str) -> f64 {
+        self.positions.get(symbol).map_or(0.0, |p| p.quantity)
+    }
+
+    /// Cash plus the market value of all positions.
+    pub fn equity(&self) -> f64 {
+        self.cash
+            + self
+                .positions
+                .values()
+                .map(|p| p.quantity * p.current_price * p.multiplier)
+                .sum::<f64>()
+    }
+
+    /// Mark the position in `symbol`, if any, to `price`.
+    pub fn update_price(&mut self, symbol: &str, price: f64) {
+        if let Some(position) = self.positions.get_mut(symbol) {
+            position.current_price = price;
+        }
+    }
+
+    /// Latest price of every position, for syncing the broker.
+    pub fn prices(&self) -> HashMap<String, Decimal> {
+        self.positions
+            .iter()
+            .map(|(symbol, p)| {
+                let price = Decimal::try_from(p.current_price).unwrap_or_default();
+                (symbol.clone(), price)
+            })
+            .collect()
+    }
+
+    /// Apply a fill, returning the realized P&L.
+    pub fn apply_fill(
+        &mut self,
+        symbol: &str,
+        side: Side,
+        quantity: f64,
+        price: f64,
+        commission: f64,
+    ) -> f64 {
+        let multiplier = self.multipliers.get(symbol).copied().unwrap_or(1.0);
+        let fill_value = price * quantity * multiplier;
+        match side {
+            Side::Buy => self.cash -= fill_value + commission,
+            Side::Sell => self.cash += fill_value - commission,
+        }
+
+        let fill_qty = match side {
+            Side::Buy => quantity,
+            Side::Sell => -quantity,
+        };
+        let position = self
+            .positions
+            .entry(symbol.to_string())
+            .or_insert(SimPosition {
+                quantity: 0.0,
+                avg_entry_price: 0.0,
+                current_price: 0.0,
+                multiplier,
+            });
+
+        let mut realized = 0.0;
+        if position.quantity == 0.0 || position.quantity.signum() == fill_qty.signum() {
+            // Adding to the position
+            let new_quantity = position.quantity + fill_qty;
+            if new_quantity != 0.0 {
+                position.avg_entry_price = (position.quantity * position.avg_entry_price
+                    + fill_qty * price)
+                    / new_quantity;
+            }
+            position.quantity = new_quantity;
+        } else {
+            // Reducing or reversing
+            let close_qty = fill_qty.abs().min(position.quantity.abs());
+            realized = close_qty
+                * (price - position.avg_entry_price)
+                * position.quantity.signum()
+                * position.multiplier;
+
+            let remaining = fill_qty.abs() - close_qty;
+            if remaining > 0.0 {
+                position.quantity = fill_qty.signum() * remaining;
+                position.avg_entry_price = price;
+            } else {
+                position.quantity += fill_qty;
+            }
+        }
+
+        if position.quantity == 0.0 {
+            self.positions.remove(symbol);
+        }
+        realized
+    }
+}
+
+fn to_f64(value: Decimal) -> f64 {
+    value.try_into().unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use trading_core::types::Position;
+
+    #[test]
+    fn test_matches_decimal_accounting() {
+        let mut decimal = Portfolio::new(dec!(100000));
+        let mut float = SimPortfolio::new(100000.0);
+
+        let fills = [
+            (Side::Buy, dec!(100), dec!(150.25)),
+            (Side::Buy, dec!(50), dec!(152.10)),
+            (Side::Sell, dec!(120), dec!(155.40)),
+            // Reverses into a short
+            (Side::Sell, dec!(60), dec!(154.00)),
+        ];
+        for (side, quantity, price) in fills {
+            let fill_value = quantity * price;
+            match side {
+                Side::Buy => decimal.cash -= fill_value,
+                Side::Sell => decimal.cash += fill_value,
+            }
+            decimal
+                .positions
+                .entry("AAPL".to_string())
+                .or_insert_with(|| Position::new("AAPL", Decimal::ZERO, Decimal::ZERO))
+                .apply_fill(side, quantity, price);
+            float.apply_fill("AAPL", side, to_f64(quantity), to_f64(price), 0.0);
+        }
+        decimal.update_prices(&HashMap::from([("AAPL".to_string(), dec!(151.75))]));
+        float.update_price("AAPL", 151.75);
+
+        assert_eq!(float.quantity("AAPL"), -30.0);
+        assert!((float.cash() - to_f64(decimal.cash)).abs() < 1e-6);
+        assert!((float.equity() - to_f64(decimal.equity)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_portfolio_mode_parse() {
+        assert_eq!(
+            "validate".parse::<PortfolioMode>().unwrap(),
+            PortfolioMode::Validate
+        );
+        assert!("bogus".parse::<PortfolioMode>().is_err());
+    }
+}
@@ -0,0 +1,724 @@
+//! Soak testing with fault injection.
+//!
+//! A soak test drives the live [`TradingRuntime`] with simulated market data
+//! for as long as it is asked to, while injecting the faults a deployment
+//! meets in practice: bars that never arrive, broker API errors, partial
+//! fills and skewed clocks. After every bar it checks that no order was
+//! duplicated, that broker positions still match the fills that built them,
+//! and that no step hung.
+
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+use trading_broker::PaperBroker;
+use trading_core::error::BrokerError;
+use trading_core::traits::{Broker, Strategy};
+use trading_core::types::{Bar, Order, OrderRequest, Portfolio, Position, Side, Timeframe};
+use trading_runtime::{OrderPipeline, StepOutcome, TradingRuntime};
+use uuid::Uuid;
+
+use crate::rng::{SimulationRng, FAULT_STREAM, FEED_STREAM, FILL_STREAM};
+
+/// Fault rates injected during a soak test.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FaultConfig {
+    /// Chance a batch of bars is lost, in percent
+    pub feed_drop_pct: f64,
+    /// Chance a broker call fails with an HTTP 500, in percent
+    pub api_error_pct: f64,
+    /// Chance an order fills only partly on a bar, in percent
+    pub partial_fill_pct: f64,
+    /// Largest offset applied to bar timestamps, in milliseconds
+    pub clock_skew_ms: i64,
+}
+
+impl Default for FaultConfig {
+    fn default() -> Self {
+        Self {
+            feed_drop_pct: 1.0,
+            api_error_pct: 2.0,
+            partial_fill_pct: 10.0,
+            clock_skew_ms: 2_000,
+        }
+    }
+}
+
+/// Broker that fails a share of calls with HTTP 500s before they reach the
+/// wrapped paper broker.
+pub struct FaultyBroker {
+    inner: Arc<PaperBroker>,
+    error_pct: f64,
+    rng: Mutex<SimulationRng>,
+    injected: AtomicU64,
+}
+
+impl FaultyBroker {
+    /// Wrap `inner`, failing `error_pct` percent of calls.
+    pub fn new(inner: Arc<PaperBroker>, error_pct: f64, rng: SimulationRng) -> Self {
+        Self {
+            inner,
+            error_pct,
+            rng: Mutex::new(rng),
+            injected: AtomicU64::new(0),
+        }
+    }
+
+    /// Number of errors injected so far.
+    pub fn injected(&self) -> u64 {
+        self.injected.load(Ordering::Relaxed)
+    }
+
+    fn fault(&self) -> Result<(), BrokerError> {
+        if self.rng.lock().unwrap().next_f64() * 100.0 >= self.error_pct {
+            return Ok(());
+        }
+        self.injected.fetch_add(1, Ordering::Relaxed);
+        Err(BrokerError::Http {
+            status: 500,
+            code: None,
+            message: "injected fault".to_string(),
+        })
+    }
+}
+
+#[async_trait]
+impl Broker for FaultyBroker {
+    async fn get_account(&self) -> Result<Portfolio, BrokerError> {
+        self.fault()?;
+        self.inner.get_account().await
+    }
+
+    async fn submit_order(&self, request: OrderRequest) -> Result<Order, BrokerError> {
+        self.fault()?;
+        self.inner.submit_order(request).await
+    }
+
+    async fn cancel_order(&self, order_id: &str) -> Result<(), BrokerError> {
+        self.fault()?;
+        self.inner.cancel_order(order_id).await
+    }
+
+    async fn get_order(&self, order_id: &str) -> Result<Order, BrokerError> {
+        self.fault()?;
+        self.inner.get_order(order_id).await
+    }
+
+    async fn get_open_orders(&self) -> Result<Vec<Order>, BrokerError> {
+        self.fault()?;
+        self.inner.get_open_orders().await
+    }
+
+    async fn get_positions(&self) -> Result<Vec<Position>, BrokerError> {
+        self.fault()?;
+        self.inner.get_positions().await
+    }
+
+    async fn get_position(&self, symbol: &str) -> Result<Option<Position>, BrokerError> {
+        self.fault()?;
+        self.inner.get_position(symbol).await
+    }
+
+    async fn close_position(&self, symbol: &str) -> Result<Order, BrokerError> {
+        self.fault()?;
+        self.inner.close_position(symbol).await
+    }
+
+    async fn close_all_positions(&self) -> Result<Vec<Order>, BrokerError> {
+        self.fault()?;
+        self.inner.close_all_positions().await
+    }
+
+    async fn cancel_all_orders(&self) -> Result<(), BrokerError> {
+        self.fault()?;
+        self.inner.cancel_all_orders().await
+    }
+
+    async fn is_market_open(&self) -> Result<bool, BrokerError> {
+        self.fault()?;
+        self.inner.is_market_open().await
+    }
+
+    fn name(&self) -> &str {
+        "Faulty Paper Broker"
+    }
+}
+
+/// Invariant a soak test checks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Invariant {
+    /// At most one working order per symbol and side
+    NoDuplicateOrders,
+    /// Broker positions equal the sum of fills
+    PositionsReconcile,
+    /// Every step finishes within the stall timeout
+    NoStalls,
+}
+
+impl std::fmt::Display for Invariant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Invariant::NoDuplicateOrders => write!(f, "no duplicate orders"),
+            Invariant::PositionsReconcile => write!(f, "positions reconcile"),
+            Invariant::NoStalls => write!(f, "no stalls"),
+        }
+    }
+}
+
+/// An invariant found broken.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvariantViolation {
+    /// Bar the violation was found after
+    pub bar: u64,
+    /// Invariant broken
+    pub invariant: Invariant,
+    /// What was observed
+    pub detail: String,
+}
+
+/// Soak test settings.
+#[derive(Debug, Clone)]
+pub struct SoakConfig {
+    /// Symbols to simulate
+    pub symbols: Vec<String>,
+    /// Timeframe of the simulated bars
+    pub timeframe: Timeframe,
+    /// Wall-clock time to run for
+    pub duration: Duration,
+    /// Stop after this many bars, if sooner
+    pub max_bars: Option<u64>,
+    /// Wall-clock pause between bars
+    pub bar_interval: Duration,
+    /// Longest a single step may take before it counts as hung
+    pub stall_timeout: Duration,
+    /// Starting account balance
+    pub initial_capital: Decimal,
+    /// Standard deviation of bar-to-bar returns
+    pub volatility: f64,
+    /// Faults to inject
+    pub faults: FaultConfig,
+    /// Seed for the market data and faults
+    pub seed: u64,
+}
+
+impl Default for SoakConfig {
+    fn default() -> Self {
+        Self {
+            symbols: vec!["SOAK".to_string()],
+            timeframe: Timeframe::Minute1,
+            duration: Duration::from_secs(3600),
+            max_bars: None,
+            bar_interval: Duration::ZERO,
+            stall_timeout: Duration::from_secs(10),
+            initial_capital: Decimal::from(100_000),
+            volatility: 0.01,
+            faults: FaultConfig::default(),
+            seed: 0,
+        }
+    }
+}
+
+/// Stability report of a soak test.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SoakReport {
+    /// Seed the run was drawn from
+    pub seed: u64,
+    /// Faults injected
+    pub faults: FaultConfig,
+    /// Wall-clock run time in seconds
+    pub elapsed_secs: f64,
+    /// Bars simulated, including dropped ones
+    pub bars: u64,
+    /// Signals and strategy order batches produced
+    pub signals: u64,
+    /// Orders accepted by the broker
+    pub orders_submitted: u64,
+    /// Submissions that failed
+    pub order_failures: u64,
+    /// Fills executed
+    pub fills: u64,
+    /// Fills that left the order partly open
+    pub partial_fills: u64,
+    /// Bar batches dropped
+    pub feed_drops: u64,
+    /// Bars delivered with a skewed timestamp
+    pub skewed_bars: u64,
+    /// Broker errors injected
+    pub api_errors: u64,
+    /// Slowest step in milliseconds
+    pub max_step_ms: f64,
+    /// Account equity at the end
+    pub final_equity: Decimal,
+    /// Invariants found broken
+    pub violations: Vec<InvariantViolation>,
+}
+
+impl SoakReport {
+    /// Whether every invariant held.
+    pub fn passed(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Render the report for display.
+    pub fn render(&self) -> String {
+        let mut s = String::new();
+        s.push_str(&format!(
+            "SOAK TEST ({:.0}s, {} bars, seed {})\n",
+            self.elapsed_secs, self.bars, self.seed
+        ));
+        s.push_str("───────────────────────────────────────────────────────────\n");
+        s.push_str(&format!(
+            "  Faults:        {} feed drops, {} API errors, {} partial fills, {} skewed bars\n",
+            self.feed_drops, self.api_errors, self.partial_fills, self.skewed_bars
+        ));
+        s.push_str(&format!(
+            "  Activity:      {} signals, {} orders, {} failed, {} fills\n",
+            self.signals, self.orders_submitted, self.order_failures, self.fills
+        ));
+        s.push_str(&format!("  Slowest Step:  {:.1} ms\n", self.max_step_ms));
+        s.push_str(&format!("  Final Equity:  ${:.2}\n", self.final_equity));
+        if self.passed() {
+            s.push_str("  Invariants:    all held\n");
+        } else {
+            s.push_str(&format!(
+                "  Invariants:    {} violations\n",
+                self.violations.len()
+            ));
+            for v in &self.violations {
+                s.push_str(&format!(
+                    "    bar {}: {}: {}\n",
+                    v.bar, v.invariant, v.detail
+                ));
+            }
+        }
+        s
+    }
+
+    fn violate(&mut self, invariant: Invariant, detail: String) {
+        warn!("Soak invariant '{}' broken: {}", invariant, detail);
+        self.violations.push(InvariantViolation {
+            bar: self.bars,
+            invariant,
+            detail,
+        });
+    }
+
+    fn record(&mut self, outcome: StepOutcome) {
+        match outcome {
+            StepOutcome::NoSignal => return,
+            StepOutcome::Submitted { .. } => self.orders_submitted += 1,
+            StepOutcome::Failed { .. } => self.order_failures += 1,
+            StepOutcome::OrdersSubmitted { orders } => self.orders_submitted += orders.len() as u64,
+            StepOutcome::OrdersFailed { submitted, .. } => {
+                self.orders_submitted += submitted.len() as u64;
+                self.order_failures += 1;
+            }
+            StepOutcome::Skipped { .. }
+            | StepOutcome::Rejected { .. }
+            | StepOutcome::OrdersNotSent { .. } => {}
+        }
+        self.signals += 1;
+    }
+}
+
+/// Random-walk prices for each symbol, delivered with feed drops and clock
+/// skew.
+struct SimulatedMarket {
+    symbols: Vec<String>,
+    closes: Vec<f64>,
+    timeframe_ms: i64,
+    timestamp: i64,
+    volatility: f64,
+    faults: FaultConfig,
+    rng: SimulationRng,
+}
+
+/// One bar of simulated market time.
+struct MarketStep {
+    /// Bars delivered to the runtime; empty if the feed dropped them
+    bars: Vec<(String, Bar)>,
+    /// True closes, which the exchange fills at whether or not they arrived
+    closes: HashMap<String, Decimal>,
+    /// Bars delivered with a skewed timestamp
+    skewed: u64,
+}
+
+impl SimulatedMarket {
+    fn new(config: &SoakConfig, rng: SimulationRng) -> Self {
+        Self {
+            symbols: config.symbols.clone(),
+            closes: vec![100.0; config.symbols.len()],
+            timeframe_ms: config.timeframe.as_millis() as i64,
+            timestamp: 0,
+            volatility: config.volatility,
+            faults: config.faults,
+            rng,
+        }
+    }
+
+    fn step(&mut self) -> MarketStep {
+        self.timestamp += self.timeframe_ms;
+        let dropped = self.rng.next_f64() * 100.0 < self.faults.feed_drop_pct;
+
+        let mut step = MarketStep {
+            bars: Vec::with_capacity(self.symbols.len()),
+            closes: HashMap::with_capacity(self.symbols.len()),
+            skewed: 0,
+        };
+        for (symbol, close) in self.symbols.iter().zip(self.closes.iter_mut()) {
+            let open = *close;
+            *close = (open * (1.0 + self.rng.normal(0.0, self.volatility))).max(0.01);
+            let wick = open.max(*close) * self.rng.normal(0.0, self.volatility).abs() / 2.0;
+            let skew = self
+                .rng
+                .range(-self.faults.clock_skew_ms, self.faults.clock_skew_ms);
+            if let Ok(price) = Decimal::try_from(*close) {
+                step.closes.insert(symbol.clone(), price.round_dp(2));
+            }
+            if dropped {
+                continue;
+            }
+            if skew != 0 {
+                step.skewed += 1;
+            }
+            step.bars.push((
+                symbol.clone(),
+                Bar::new(
+                    self.timestamp + skew,
+                    open,
+                    open.max(*close) + wick,
+                    open.min(*close) - wick,
+                    *close,
+                    1e6,
+                ),
+            ));
+        }
+        step
+    }
+}
+
+/// Runs a strategy through the live runtime under fault injection.
+pub struct SoakTest {
+    config: SoakConfig,
+}
+
+impl SoakTest {
+    /// Create a soak test.
+    pub fn new(config: SoakConfig) -> Self {
+        assert!(!config.symbols.is_empty(), "Soak test needs a symbol");
+        Self { config }
+    }
+
+    /// Run until the duration or bar limit is reached, or a step hangs.
+    pub async fn run(&self, strategy: Box<dyn Strategy>, pipeline: OrderPipeline) -> SoakReport {
+        let config = &self.config;
+        let rng = SimulationRng::new(config.seed);
+        let exchange = Arc::new(PaperBroker::new(config.initial_capital));
+        let broker = Arc::new(FaultyBroker::new(
+            exchange.clone(),
+            config.faults.api_error_pct,
+            rng.fork(FAULT_STREAM),
+        ));
+        let mut runtime = TradingRuntime::new(strategy, broker.clone(), pipeline, config.timeframe);
+        let mut market = SimulatedMarket::new(config, rng.fork(FEED_STREAM));
+        let mut fill_rng = rng.fork(FILL_STREAM);
+
+        let mut report = SoakReport {
+            seed: config.seed,
+            faults: config.faults,
+            elapsed_secs: 0.0,
+            bars: 0,
+            signals: 0,
+            orders_submitted: 0,
+            order_failures: 0,
+            fills: 0,
+            partial_fills: 0,
+            feed_drops: 0,
+            skewed_bars: 0,
+            api_errors: 0,
+            max_step_ms: 0.0,
+            final_equity: config.initial_capital,
+            violations: Vec::new(),
+        };
+        let mut checker = InvariantChecker::default();
+
+        let started = Instant::now();
+        while started.elapsed() < config.duration
+            && config.max_bars.map_or(true, |max| report.bars < max)
+        {
+            let step = market.step();
+            report.bars += 1;
+            report.skewed_bars += step.skewed;
+
+            // The exchange keeps trading while the feed is down
+            checker
+                .fill_orders(&exchange, &step.closes, &mut fill_rng, config, &mut report)
+                .await;
+
+            if step.bars.is_empty() {
+                report.feed_drops += 1;
+            } else {
+                let timestamp = step.bars[0].1.timestamp;
+                let step_started = Instant::now();
+                let outcomes = tokio::time::timeout(config.stall_timeout, async {
+                    if let Err(e) = runtime.supervise(timestamp).await {
+                        debug!("Soak supervise failed: {}", e);
+                    }
+                    let mut outcomes = Vec::with_capacity(step.bars.len());
+                    for (symbol, bar) in step.bars {
+                        outcomes.push(runtime.on_bar(&symbol, bar).await);
+                    }
+                    outcomes.push(runtime.on_snapshot(timestamp).await);
+                    outcomes
+                })
+                .await;
+                report.max_step_ms = report
+                    .max_step_ms
+                    .max(step_started.elapsed().as_secs_f64() * 1000.0);
+
+                let Ok(outcomes) = outcomes else {
+                    report.violate(
+                        Invariant::NoStalls,
+                        format!("step took longer than {:?}", config.stall_timeout),
+                    );
+                    break;
+                };
+                for outcome in outcomes {
+                    report.record(outcome);
+                }
+            }
+
+            checker.check(&exchange, &mut report).await;
+
+            if !config.bar_interval.is_zero() {
+                tokio::time::sleep(config.bar_interval).await;
+            }
+        }
+
+        report.elapsed_secs = started.elapsed().as_secs_f64();
+        report.api_errors = broker.injected();
+        report.final_equity = exchange.equity();
+        report
+    }
+}
+
+/// Fill ledger and the violations already reported, so a lasting problem
+/// is reported once rather than on every bar.
+#[derive(Default)]
+struct InvariantChecker {
+    /// Quantity filled so far per order
+    filled: HashMap<Uuid, Decimal>,
+    /// Net position per symbol implied by the fills
+    ledger: HashMap<String, Decimal>,
+    duplicates_reported: HashSet<Uuid>,
+    mismatches_reported: HashSet<String>,
+}
+
+impl InvariantChecker {
+    /// Fill working orders at the bar's closes, some of them only partly.
+    async fn fill_orders(
+        &mut self,
+        exchange: &PaperBroker,
+        closes: &HashMap<String, Decimal>,
+        rng: &mut SimulationRng,
+        config: &SoakConfig,
+        report: &mut SoakReport,
+    ) {
+        exchange.update_prices(closes);
+
+        // Sorted so a seed replays the same fills
+        let mut open = exchange.get_open_orders().await.unwrap_or_default();
+        open.sort_by_key(|o| (o.created_at, o.id));
+
+        for order in open {
+            let (id, remaining, symbol) = (order.id, order.remaining_quantity(), order.symbol);
+            let Some(price) = closes.get(&symbol) else {
+                continue;
+            };
+            let partial = rng.next_f64() * 100.0 < config.faults.partial_fill_pct;
+            let quantity = if partial {
+                (remaining / Decimal::TWO).floor().max(Decimal::ONE)
+            } else {
+                remaining
+            };
+            match exchange.execute_partial(id, *price, quantity) {
+                Ok(order) => self.record_fill(&order, report),
+                Err(e) => {
+                    // A real broker rejects what it can't fill
+                    debug!("Soak fill of {} failed, canceling: {}", symbol, e);
+                    let _ = exchange.cancel_order(&id.to_string()).await;
+                }
+            }
+        }
+    }
+
+    fn record_fill(&mut self, order: &Order, report: &mut SoakReport) {
+        let before = self.filled.insert(order.id, order.filled_quantity);
+        let delta = order.filled_quantity - before.unwrap_or_default();
+        if delta <= Decimal::ZERO {
+            return;
+        }
+        report.fills += 1;
+        if order.filled_quantity < order.quantity {
+            report.partial_fills += 1;
+        }
+        let signed = match order.side {
+            Side::Buy => delta,
+            Side::Sell => -delta,
+        };
+        *self.ledger.entry(order.symbol.clone()).or_default() += signed;
+    }
+
+    /// Check the invariants against the exchange's view.
+    async fn check(&mut self, exchange: &PaperBroker, report: &mut SoakReport) {
+        let orders = exchange.get_open_orders().await.unwrap_or_default();
+        let mut working: HashMap<(&str, Side), Vec<Uuid>> = HashMap::new();
+        for order in &orders {
+            working
+                .entry((order.symbol.as_str(), order.side))
+                .or_default()
+                .push(order.id);
+        }
+        for ((symbol, side), ids) in working {
+            if ids.len() < 2 {
+                continue;
+            }
+            // Report again only when a new order joins the duplicates
+            let new = ids
+                .iter()
+                .filter(|id| self.duplicates_reported.insert(**id))
+                .count();
+            if new > 0 {
+                report.violate(
+                    Invariant::NoDuplicateOrders,
+                    format!("{} working {} orders for {}", ids.len(), side, symbol),
+                );
+            }
+        }
+
+        let positions: HashMap<String, Decimal> = exchange
+            .get_positions()
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|p| (p.symbol, p.quantity))
+            .collect();
+        let symbols: HashSet<&String> = positions.keys().chain(self.ledger.keys()).collect();
+        let mut mismatched = Vec::new();
+        for symbol in symbols {
+            let held = positions.get(symbol).copied().unwrap_or_default();
+            let expected = self.ledger.get(symbol).copied().unwrap_or_default();
+            if held == expected {
+                self.mismatches_reported.remove(symbol);
+            } else if !self.mismatches_reported.contains(symbol) {
+                mismatched.push((symbol.clone(), held, expected));
+            }
+        }
+        for (symbol, held, expected) in mismatched {
+            report.violate(
+                Invariant::PositionsReconcile,
+                format!(
+                    "broker holds {} {} but fills add up to {}",
+                    held, symbol, expected
+                ),
+            );
+            self.mismatches_reported.insert(symbol);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use trading_risk::{RiskConfig, RiskManager};
+    use trading_strategies::{MACrossoverConfig, MACrossoverStrategy, MaType};
+
+    #[tokio::test]
+    async fn test_soak_survives_faults() {
+        let config = SoakConfig {
+            symbols: vec!["AAA".to_string(), "BBB".to_string()],
+            max_bars: Some(3_000),
+            faults: FaultConfig {
+                feed_drop_pct: 5.0,
+                api_error_pct: 10.0,
+                partial_fill_pct: 30.0,
+                clock_skew_ms: 30_000,
+            },
+            seed: 7,
+            ..Default::default()
+        };
+        let strategy = MACrossoverStrategy::new(MACrossoverConfig {
+            symbols: config.symbols.clone(),
+            fast_period: 5,
+            slow_period: 20,
+            ma_type: MaType::Sma,
+            signal_threshold: 0.0,
+        });
+        let pipeline = OrderPipeline::new(RiskManager::new(RiskConfig::default()));
+
+        let report = SoakTest::new(config)
+            .run(Box::new(strategy), pipeline)
+            .await;
+
+        assert_eq!(report.bars, 3_000);
+        assert!(report.feed_drops > 0);
+        assert!(report.api_errors > 0);
+        assert!(report.skewed_bars > 0);
+        assert!(report.partial_fills > 0);
+        assert!(report.orders_submitted > 0);
+        assert!(report.passed(), "{}", report.render());
+    }
+
+    #[tokio::test]
+    async fn test_checker_reports_duplicates_once() {
+        let exchange = PaperBroker::new(dec!(100000));
+        let mut checker = InvariantChecker::default();
+        let mut report = SoakReport {
+            seed: 0,
+            faults: FaultConfig::default(),
+            elapsed_secs: 0.0,
+            bars: 0,
+            signals: 0,
+            orders_submitted: 0,
+            order_failures: 0,
+            fills: 0,
+            partial_fills: 0,
+            feed_drops: 0,
+            skewed_bars: 0,
+            api_errors: 0,
+            max_step_ms: 0.0,
+            final_equity: Decimal::ZERO,
+            violations: Vec::new(),
+        };
+
+        for _ in 0..2 {
+            exchange
+                .submit_order(OrderRequest::market("AAA", Side::Buy, dec!(10)))
+                .await
+                .unwrap();
+        }
+        checker.check(&exchange, &mut report).await;
+        checker.check(&exchange, &mut report).await;
+        assert_eq!(report.violations.len(), 1);
+        assert_eq!(report.violations[0].invariant, Invariant::NoDuplicateOrders);
+
+        // A fill the checker did not see breaks reconciliation
+        let order = exchange
+            .submit_order(OrderRequest::market("BBB", Side::Buy, dec!(5)))
+            .await
+            .unwrap();
+        exchange.execute_at_price(order.id, dec!(50)).unwrap();
+        checker.check(&exchange, &mut report).await;
+        assert_eq!(report.violations.len(), 2);
+        assert_eq!(
+            report.violations[1].invariant,
+            Invariant::PositionsReconcile
+        );
+    }
+}
@@ -5,6 +5,34 @@ use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
 use trading_core::types::{Portfolio, Side, SignalType};
+use trading_indicators::RunningStats;
+use trading_risk::VarEstimate;
+
+/// What made a trade happen.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TradeReason {
+    /// A strategy signal or order
+    #[default]
+    Signal,
+    /// A position's stop loss was hit
+    StopLoss,
+    /// A position's take-profit target was hit
+    TakeProfit,
+    /// Closed at the last price when the backtest ended
+    EndOfBacktest,
+}
+
+impl std::fmt::Display for TradeReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TradeReason::Signal => write!(f, "signal"),
+            TradeReason::StopLoss => write!(f, "stop_loss"),
+            TradeReason::TakeProfit => write!(f, "take_profit"),
+            TradeReason::EndOfBacktest => write!(f, "end_of_backtest"),
+        }
+    }
+}
 
 /// Record of a single trade.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,6 +44,73 @@ pub struct TradeRecord {
     pub timestamp: DateTime<Utc>,
     pub signal_type: SignalType,
     pub pnl: Option<Decimal>,
+    /// What triggered the trade
+    #[serde(default)]
+    pub reason: TradeReason,
+}
+
+/// Position still open when the backtest ended.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenPositionRecord {
+    pub symbol: String,
+    pub quantity: Decimal,
+    pub entry_price: Decimal,
+    /// Last known price the position was marked at
+    pub mark_price: Decimal,
+    pub unrealized_pnl: Decimal,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// What the pipeline did with a signal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignalDecision {
+    /// Sent to the broker as generated
+    Approved,
+    /// Sent after risk management changed it
+    Modified,
+    /// Ignored (duplicate, blacklisted, strategy disabled, ...)
+    Skipped,
+    /// Blocked by risk management
+    Rejected,
+}
+
+impl SignalDecision {
+    pub const ALL: [SignalDecision; 4] = [
+        SignalDecision::Approved,
+        SignalDecision::Modified,
+        SignalDecision::Skipped,
+        SignalDecision::Rejected,
+    ];
+}
+
+impl std::fmt::Display for SignalDecision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SignalDecision::Approved => write!(f, "approved"),
+            SignalDecision::Modified => write!(f, "modified"),
+            SignalDecision::Skipped => write!(f, "skipped"),
+            SignalDecision::Rejected => write!(f, "rejected"),
+        }
+    }
+}
+
+/// A signal the pipeline evaluated, and what it decided.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignalAuditRecord {
+    pub timestamp: DateTime<Utc>,
+    pub symbol: String,
+    /// `None` for orders the strategy built itself
+    pub signal_type: Option<SignalType>,
+    pub decision: SignalDecision,
+    /// Why the signal was modified, skipped or rejected; empty if approved
+    pub reason: String,
+    /// Quantity sent, if any
+    pub quantity: Option<Decimal>,
+}
+
+fn default_periods_per_year() -> f64 {
+    252.0
 }
 
 /// Backtest statistics.
@@ -35,6 +130,9 @@ pub struct BacktestStats {
     pub sharpe_ratio: f64,
     /// Sortino ratio
     pub sortino_ratio: f64,
+    /// One-bar Value-at-Risk and Expected Shortfall of equity at 95%
+    #[serde(default)]
+    pub value_at_risk: Option<VarEstimate>,
     /// Total number of trades
     pub total_trades: usize,
     /// Number of winning trades
@@ -53,14 +151,38 @@ pub struct BacktestStats {
     pub profit_factor: Decimal,
     /// Number of bars processed
     pub bars_processed: usize,
+    /// Bars per year, used to annualize returns and ratios
+    #[serde(default = "default_periods_per_year")]
+    pub periods_per_year: f64,
+    /// Signals blocked by risk limits
+    #[serde(default)]
+    pub risk_rejections: usize,
+    /// Every evaluated signal with its decision
+    #[serde(default)]
+    pub signal_audit: Vec<SignalAuditRecord>,
+    /// Largest gap between the `f64` and `Decimal` equity, in validate mode
+    #[serde(default)]
+    pub float_equity_error: Option<f64>,
     /// Equity curve
     pub equity_curve: Vec<(i64, Decimal)>,
     /// All trades
     pub trades: Vec<TradeRecord>,
+    /// Positions still open at the end of the run
+    #[serde(default)]
+    pub open_positions: Vec<OpenPositionRecord>,
+    /// Unrealized P&L of positions open at the end of the run
+    #[serde(default)]
+    pub open_pnl: Decimal,
+    /// Open-position P&L counted in trade statistics (mark-to-market)
+    #[serde(skip)]
+    marked_pnls: Vec<Decimal>,
     /// Peak equity (for drawdown)
     peak_equity: Decimal,
     /// Daily returns for Sharpe calculation
     daily_returns: Vec<f64>,
+    /// Running mean and variance of `daily_returns`
+    #[serde(skip)]
+    return_stats: RunningStats,
 }
 
 impl BacktestStats {
@@ -74,6 +196,7 @@ impl BacktestStats {
             max_drawdown_pct: Decimal::ZERO,
             sharpe_ratio: 0.0,
             sortino_ratio: 0.0,
+            value_at_risk: None,
             total_trades: 0,
             winning_trades: 0,
             losing_trades: 0,
@@ -83,11 +206,63 @@ impl BacktestStats {
             avg_loss: Decimal::ZERO,
             profit_factor: Decimal::ZERO,
             bars_processed: 0,
+            periods_per_year: default_periods_per_year(),
+            risk_rejections: 0,
+            signal_audit: Vec::new(),
+            float_equity_error: None,
             equity_curve: Vec::new(),
             trades: Vec::new(),
+            open_positions: Vec::new(),
+            open_pnl: Decimal::ZERO,
+            marked_pnls: Vec::new(),
             peak_equity: initial_capital,
             daily_returns: Vec::new(),
+            return_stats: RunningStats::new(),
+        }
+    }
+
+    /// Set the bars per year used to annualize (252 for daily bars).
+    pub fn with_periods_per_year(mut self, periods_per_year: f64) -> Self {
+        self.periods_per_year = periods_per_year;
+        self
+    }
+
+    /// Number of audited signals per decision.
+    pub fn signal_decisions(&self) -> Vec<(SignalDecision, usize)> {
+        SignalDecision::ALL
+            .iter()
+            .map(|&decision| {
+                let count = self
+                    .signal_audit
+                    .iter()
+                    .filter(|record| record.decision == decision)
+                    .count();
+                (decision, count)
+            })
+            .collect()
+    }
+
+    /// Most common reasons signals were skipped or rejected, most frequent
+    /// first.
+    pub fn blocking_reasons(&self, limit: usize) -> Vec<(String, usize)> {
+        let mut counts: Vec<(String, usize)> = Vec::new();
+        for record in self.signal_audit.iter().filter(|r| {
+            matches!(
+                r.decision,
+                SignalDecision::Skipped | SignalDecision::Rejected
+            )
+        }) {
+            match counts
+                .iter_mut()
+                .find(|(reason, _)| *reason == record.reason)
+            {
+                Some((_, count)) => *count += 1,
+                None => counts.push((record.reason.clone(), 1)),
+            }
         }
+        counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        counts.truncate(limit);
+        counts
     }
 
     /// Record equity at a timestamp.
@@ -100,6 +275,7 @@ impl BacktestStats {
                     .parse::<f64>()
                     .unwrap_or(0.0);
                 self.daily_returns.push(ret);
+                self.return_stats.push(ret);
             }
         }
 
@@ -125,6 +301,23 @@ impl BacktestStats {
         self.trades.push(trade);
     }
 
+    /// Number of trades made for `reason`.
+    pub fn trades_for(&self, reason: TradeReason) -> usize {
+        self.trades.iter().filter(|t| t.reason == reason).count()
+    }
+
+    /// Record a position still open at the end of the run.
+    ///
+    /// When `count_in_trade_stats` is set, its unrealized P&L is counted
+    /// like a closed trade without adding a trade record.
+    pub fn add_open_position(&mut self, position: OpenPositionRecord, count_in_trade_stats: bool) {
+        self.open_pnl += position.unrealized_pnl;
+        if count_in_trade_stats {
+            self.marked_pnls.push(position.unrealized_pnl);
+        }
+        self.open_positions.push(position);
+    }
+
     /// Calculate final statistics.
     pub fn finalize(&mut self, portfolio: &Portfolio) {
         self.final_equity = portfolio.equity;
@@ -135,36 +328,42 @@ impl BacktestStats {
                 (self.final_equity - self.initial_capital) / self.initial_capital * dec!(100);
         }
 
-        // Annualized return (assuming daily bars)
+        // Annualized return
         if !self.equity_curve.is_empty() {
-            let days = self.equity_curve.len() as f64;
+            let periods = self.equity_curve.len() as f64;
             let total_return = self
                 .total_return_pct
                 .to_string()
                 .parse::<f64>()
                 .unwrap_or(0.0)
                 / 100.0;
-            let annualized = ((1.0 + total_return).powf(252.0 / days) - 1.0) * 100.0;
+            let annualized =
+                ((1.0 + total_return).powf(self.periods_per_year / periods) - 1.0) * 100.0;
             self.annualized_return_pct = Decimal::try_from(annualized).unwrap_or(Decimal::ZERO);
         }
 
-        // Calculate trade statistics (only count round-trip trades with P&L)
+        // Calculate trade statistics (only count round-trip trades with P&L,
+        // plus open positions marked to market when configured)
         let mut total_profit = Decimal::ZERO;
         let mut total_loss = Decimal::ZERO;
 
-        for trade in &self.trades {
-            if let Some(pnl) = trade.pnl {
-                self.total_trades += 1;
-                if pnl > Decimal::ZERO {
-                    self.winning_trades += 1;
-                    total_profit += pnl;
-                } else if pnl < Decimal::ZERO {
-                    self.losing_trades += 1;
-                    total_loss += pnl.abs();
-                }
-                if pnl == Decimal::ZERO {
-                    self.breakeven_trades += 1;
-                }
+        let pnls = self
+            .trades
+            .iter()
+            .filter_map(|trade| trade.pnl)
+            .chain(self.marked_pnls.iter().copied());
+
+        for pnl in pnls {
+            self.total_trades += 1;
+            if pnl > Decimal::ZERO {
+                self.winning_trades += 1;
+                total_profit += pnl;
+            } else if pnl < Decimal::ZERO {
+                self.losing_trades += 1;
+                total_loss += pnl.abs();
+            }
+            if pnl == Decimal::ZERO {
+                self.breakeven_trades += 1;
             }
         }
 
@@ -189,20 +388,15 @@ impl BacktestStats {
 
         // Sharpe ratio
         if !self.daily_returns.is_empty() {
-            let mean: f64 =
-                self.daily_returns.iter().sum::<f64>() / self.daily_returns.len() as f64;
-            let variance: f64 = self
-                .daily_returns
-                .iter()
-                .map(|r| (r - mean).powi(2))
-                .sum::<f64>()
-                / self.daily_returns.len() as f64;
-            let std_dev = variance.sqrt();
+            let mean = self.return_stats.mean();
+            let std_dev = self.return_stats.std_dev();
 
             if std_dev > 0.0 {
-                self.sharpe_ratio = (mean * 252.0_f64.sqrt()) / std_dev;
+                self.sharpe_ratio = (mean * self.periods_per_year.sqrt()) / std_dev;
             }
 
+            self.value_at_risk = VarEstimate::from_returns(&self.daily_returns, 0.95);
+
             // Sortino ratio (only downside deviation)
             let negative_returns: Vec<f64> = self
                 .daily_returns
@@ -218,7 +412,7 @@ impl BacktestStats {
                 let downside_dev = downside_variance.sqrt();
 
                 if downside_dev > 0.0 {
-                    self.sortino_ratio = (mean * 252.0_f64.sqrt()) / downside_dev;
+                    self.sortino_ratio = (mean * self.periods_per_year.sqrt()) / downside_dev;
                 }
             }
         }
@@ -0,0 +1,439 @@
+//! Persistent store of backtest runs and run comparison.
+//!
+//! Each run is saved as a JSON file under the store directory, keyed by a
+//! run id derived from the run time and a hash of its configuration.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use trading_core::error::{TradingError, TradingResult};
+
+use crate::{BacktestReport, BacktestStats};
+
+/// Headline metrics of a stored run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunMetrics {
+    /// Final equity
+    pub final_equity: Decimal,
+    /// Total return percentage
+    pub total_return_pct: Decimal,
+    /// Annualized return percentage
+    pub annualized_return_pct: Decimal,
+    /// Maximum drawdown percentage
+    pub max_drawdown_pct: Decimal,
+    /// Sharpe ratio
+    pub sharpe_ratio: f64,
+    /// Sortino ratio
+    pub sortino_ratio: f64,
+    /// Number of round-trip trades
+    pub total_trades: usize,
+    /// Win rate percentage
+    pub win_rate_pct: Decimal,
+    /// Profit factor
+    pub profit_factor: Decimal,
+}
+
+impl From<&BacktestStats> for RunMetrics {
+    fn from(stats: &BacktestStats) -> Self {
+        Self {
+            final_equity: stats.final_equity,
+            total_return_pct: stats.total_return_pct,
+            annualized_return_pct: stats.annualized_return_pct,
+            max_drawdown_pct: stats.max_drawdown_pct,
+            sharpe_ratio: stats.sharpe_ratio,
+            sortino_ratio: stats.sortino_ratio,
+            total_trades: stats.total_trades,
+            win_rate_pct: stats.win_rate_pct,
+            profit_factor: stats.profit_factor,
+        }
+    }
+}
+
+/// A single persisted backtest run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredRun {
+    /// Run identifier
+    pub id: String,
+    /// When the run was recorded
+    pub created_at: DateTime<Utc>,
+    /// Strategy name
+    pub strategy: String,
+    /// Symbols traded
+    pub symbols: Vec<String>,
+    /// Stable hash of `parameters`
+    pub config_hash: String,
+    /// Backtest and strategy parameters used for the run
+    pub parameters: Value,
+    /// Headline metrics
+    pub metrics: RunMetrics,
+}
+
+impl StoredRun {
+    /// Build a run record from a finished backtest.
+    pub fn new(
+        strategy: impl Into<String>,
+        symbols: Vec<String>,
+        parameters: Value,
+        report: &BacktestReport,
+    ) -> Self {
+        let created_at = Utc::now();
+        let config_hash = config_hash(&parameters);
+        Self {
+            id: format!(
+                "{}-{}",
+                created_at.format("%Y%m%d-%H%M%S"),
+                &config_hash[..8]
+            ),
+            created_at,
+            strategy: strategy.into(),
+            symbols,
+            config_hash,
+            parameters,
+            metrics: RunMetrics::from(&report.stats),
+        }
+    }
+}
+
+/// Stable hash of a configuration value.
+///
+/// Object keys are sorted before hashing, so the hash does not depend on
+/// field order.
+pub fn config_hash(parameters: &Value) -> String {
+    format!("{:016x}", fnv1a(canonical_json(parameters).bytes()))
+}
+
+/// 64-bit FNV-1a hash.
+pub(crate) fn fnv1a(bytes: impl IntoIterator<Item = u8>) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
+
+fn canonical_json(value: &Value) -> String {
+    match value {
+        Value::Object(map) => {
+            let sorted: BTreeMap<_, _> = map.iter().collect();
+            let fields: Vec<String> = sorted
+                .into_iter()
+                .map(|(k, v)| format!("{}:{}", Value::String(k.clone()), canonical_json(v)))
+                .collect();
+            format!("{{{}}}", fields.join(","))
+        }
+        Value::Array(items) => {
+            let items: Vec<String> = items.iter().map(canonical_json).collect();
+            format!("[{}]", items.join(","))
+        }
+        other => other.to_string(),
+    }
+}
+
+/// Directory-backed store of backtest runs.
+#[derive(Debug, Clone)]
+pub struct ResultsStore {
+    root: PathBuf,
+}
+
+impl ResultsStore {
+    /// Open a store rooted at `root`, creating the directory if needed.
+    pub fn open(root: impl AsRef<Path>) -> TradingResult<Self> {
+        let root = root.as_ref().to_path_buf();
+        std::fs::create_dir_all(&root)?;
+        Ok(Self { root })
+    }
+
+    /// Store directory.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Persist a run, returning the path it was written to.
+    ///
+    /// If a run with the same id already exists a numeric suffix is added.
+    pub fn save(&self, run: &mut StoredRun) -> TradingResult<PathBuf> {
+        let base_id = run.id.clone();
+        let mut suffix = 1;
+        while self.path_for(&run.id).exists() {
+            suffix += 1;
+            run.id = format!("{}-{}", base_id, suffix);
+        }
+
+        let path = self.path_for(&run.id);
+        let json = serde_json::to_string_pretty(run)
+            .map_err(|e| TradingError::Serialization(e.to_string()))?;
+        std::fs::write(&path, json)?;
+        Ok(path)
+    }
+
+    /// Load a run by id or unique id prefix.
+    pub fn load(&self, id: &str) -> TradingResult<StoredRun> {
+        let exact = self.path_for(id);
+        if exact.exists() {
+            return Self::read(&exact);
+        }
+
+        let matches: Vec<StoredRun> = self
+            .list()?
+            .into_iter()
+            .filter(|run| run.id.starts_with(id))
+            .collect();
+        match matches.len() {
+            0 => Err(TradingError::Validation(format!("No stored run '{}'", id))),
+            1 => Ok(matches.into_iter().next().unwrap()),
+            n => Err(TradingError::Validation(format!(
+                "Run id '{}' is ambiguous ({} matches)",
+                id, n
+            ))),
+        }
+    }
+
+    /// All stored runs, oldest first.
+    pub fn list(&self) -> TradingResult<Vec<StoredRun>> {
+        let mut runs = Vec::new();
+        for entry in std::fs::read_dir(&self.root)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                runs.push(Self::read(&path)?);
+            }
+        }
+        runs.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+        Ok(runs)
+    }
+
+    fn path_for(&self, id: &str) -> PathBuf {
+        self.root.join(format!("{}.json", id))
+    }
+
+    fn read(path: &Path) -> TradingResult<StoredRun> {
+        let content = std::fs::read_to_string(path)?;
+        serde_json::from_str(&content)
+            .map_err(|e| TradingError::Serialization(format!("{}: {}", path.display(), e)))
+    }
+}
+
+/// Side-by-side comparison of stored runs.
+#[derive(Debug, Clone)]
+pub struct RunComparison {
+    runs: Vec<StoredRun>,
+}
+
+impl RunComparison {
+    /// Compare the given runs; the first run is the reference.
+    pub fn new(runs: Vec<StoredRun>) -> Self {
+        Self { runs }
+    }
+
+    /// Parameters whose values differ between runs, keyed by dotted path.
+    pub fn parameter_deltas(&self) -> BTreeMap<String, Vec<Option<String>>> {
+        let flattened: Vec<BTreeMap<String, String>> = self
+            .runs
+            .iter()
+            .map(|run| {
+                let mut out = BTreeMap::new();
+                flatten(&run.parameters, String::new(), &mut out);
+                out
+            })
+            .collect();
+
+        let mut keys: Vec<&String> = flattened.iter().flat_map(|m| m.keys()).collect();
+        keys.sort();
+        keys.dedup();
+
+        keys.into_iter()
+            .filter_map(|key| {
+                let values: Vec<Option<String>> =
+                    flattened.iter().map(|m| m.get(key).cloned()).collect();
+                let differs = values.windows(2).any(|w| w[0] != w[1]);
+                differs.then(|| (key.clone(), values))
+            })
+            .collect()
+    }
+
+    /// Render the comparison as a text table.
+    pub fn render(&self) -> String {
+        let mut s = String::new();
+        let label_width = 22;
+        let col_width = self
+            .runs
+            .iter()
+            .map(|r| r.id.len())
+            .max()
+            .unwrap_or(0)
+            .max(14);
+
+        let row = |label: &str, values: Vec<String>| {
+            let mut line = format!("  {:<label_width$}", label);
+            for v in values {
+                line.push_str(&format!(" {:>col_width$}", v));
+            }
+            line.push('\n');
+            line
+        };
+
+        s.push_str("RUN COMPARISON\n");
+        s.push_str("───────────────────────────────────────────────────────────\n");
+        s.push_str(&row(
+            "Run",
+            self.runs.iter().map(|r| r.id.clone()).collect(),
+        ));
+        s.push_str(&row(
+            "Strategy",
+            self.runs.iter().map(|r| r.strategy.clone()).collect(),
+        ));
+        s.push_str(&row(
+            "Config Hash",
+            self.runs
+                .iter()
+                .map(|r| r.config_hash[..8].to_string())
+                .collect(),
+        ));
+        s.push_str(&row(
+            "Total Return",
+            self.metric(|m| format!("{:.2}%", m.total_return_pct)),
+        ));
+        s.push_str(&row(
+            "Annualized Return",
+            self.metric(|m| format!("{:.2}%", m.annualized_return_pct)),
+        ));
+        s.push_str(&row(
+            "Max Drawdown",
+            self.metric(|m| format!("{:.2}%", m.max_drawdown_pct)),
+        ));
+        s.push_str(&row(
+            "Sharpe Ratio",
+            self.metric(|m| format!("{:.2}", m.sharpe_ratio)),
+        ));
+        s.push_str(&row(
+            "Sortino Ratio",
+            self.metric(|m| format!("{:.2}", m.sortino_ratio)),
+        ));
+        s.push_str(&row(
+            "Total Trades",
+            self.metric(|m| m.total_trades.to_string()),
+        ));
+        s.push_str(&row(
+            "Win Rate",
+            self.metric(|m| format!("{:.2}%", m.win_rate_pct)),
+        ));
+        s.push_str(&row(
+            "Profit Factor",
+            self.metric(|m| format!("{:.2}", m.profit_factor)),
+        ));
+        s.push('\n');
+
+        s.push_str("PARAMETER DELTAS\n");
+        s.push_str("───────────────────────────────────────────────────────────\n");
+        let deltas = self.parameter_deltas();
+        if deltas.is_empty() {
+            s.push_str("  (identical parameters)\n");
+        }
+        for (key, values) in deltas {
+            s.push_str(&format!("  {}\n", key));
+            for (run, value) in self.runs.iter().zip(values) {
+                s.push_str(&format!(
+                    "    {:<col_width$} {}\n",
+                    run.id,
+                    value.as_deref().unwrap_or("-")
+                ));
+            }
+        }
+
+        s
+    }
+
+    fn metric(&self, f: impl Fn(&RunMetrics) -> String) -> Vec<String> {
+        self.runs.iter().map(|r| f(&r.metrics)).collect()
+    }
+}
+
+fn flatten(value: &Value, prefix: String, out: &mut BTreeMap<String, String>) {
+    match value {
+        Value::Object(map) => {
+            for (k, v) in map {
+                let key = if prefix.is_empty() {
+                    k.clone()
+                } else {
+                    format!("{}.{}", prefix, k)
+                };
+                flatten(v, key, out);
+            }
+        }
+        other => {
+            out.insert(prefix, other.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BacktestConfig;
+    use rust_decimal_macros::dec;
+    use serde_json::json;
+    use trading_core::types::Portfolio;
+
+    fn create_report(return_pct: Decimal) -> BacktestReport {
+        let mut stats = BacktestStats::new(dec!(100000));
+        stats.total_return_pct = return_pct;
+        BacktestReport {
+            config: BacktestConfig::default(),
+            stats,
+            final_portfolio: Portfolio::new(dec!(100000)),
+            seed: 0,
+            scenarios: Vec::new(),
+            provenance: None,
+        }
+    }
+
+    fn temp_store(name: &str) -> ResultsStore {
+        let dir =
+            std::env::temp_dir().join(format!("trading-results-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        ResultsStore::open(dir).unwrap()
+    }
+
+    #[test]
+    fn test_config_hash_ignores_key_order() {
+        let a = json!({"fast_period": 10, "slow_period": 20});
+        let b = json!({"slow_period": 20, "fast_period": 10});
+        assert_eq!(config_hash(&a), config_hash(&b));
+        assert_ne!(config_hash(&a), config_hash(&json!({"fast_period": 12})));
+    }
+
+    #[test]
+    fn test_save_load_and_compare() {
+        let store = temp_store("compare");
+
+        let mut first = StoredRun::new(
+            "ma_crossover",
+            vec!["AAPL".to_string()],
+            json!({"strategy": {"fast_period": 10, "slow_period": 20}}),
+            &create_report(dec!(5)),
+        );
+        let mut second = StoredRun::new(
+            "ma_crossover",
+            vec!["AAPL".to_string()],
+            json!({"strategy": {"fast_period": 12, "slow_period": 20}}),
+            &create_report(dec!(7)),
+        );
+        store.save(&mut first).unwrap();
+        store.save(&mut second).unwrap();
+
+        assert_eq!(store.list().unwrap().len(), 2);
+        let loaded = store.load(&second.id).unwrap();
+        assert_eq!(loaded.metrics.total_return_pct, dec!(7));
+
+        let comparison = RunComparison::new(vec![first, loaded]);
+        let deltas = comparison.parameter_deltas();
+        assert_eq!(deltas.len(), 1);
+        assert!(deltas.contains_key("strategy.fast_period"));
+        assert!(comparison.render().contains("7.00%"));
+
+        let _ = std::fs::remove_dir_all(store.root());
+    }
+}
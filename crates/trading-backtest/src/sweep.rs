@@ -0,0 +1,132 @@
+//! Comparison of one strategy run at several timeframes.
+
+use serde::{Deserialize, Serialize};
+use trading_core::types::Timeframe;
+
+use crate::report::BacktestReport;
+use crate::store::RunMetrics;
+
+/// Headline metrics of the strategy at one timeframe.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SweepRow {
+    /// Bar timeframe of the run
+    pub timeframe: Timeframe,
+    /// Bars processed
+    pub bars: usize,
+    /// Headline metrics of the run
+    pub metrics: RunMetrics,
+}
+
+/// Results of a strategy backtested on the same data resampled to several
+/// timeframes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeframeSweep {
+    /// Strategy name
+    pub strategy: String,
+    /// One row per timeframe, shortest first
+    pub rows: Vec<SweepRow>,
+}
+
+impl TimeframeSweep {
+    /// Create an empty sweep for `strategy`.
+    pub fn new(strategy: impl Into<String>) -> Self {
+        Self {
+            strategy: strategy.into(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Add the run at `timeframe`.
+    pub fn add(&mut self, timeframe: Timeframe, report: &BacktestReport) {
+        self.rows.push(SweepRow {
+            timeframe,
+            bars: report.stats.bars_processed,
+            metrics: RunMetrics::from(&report.stats),
+        });
+        self.rows.sort_by_key(|r| r.timeframe.as_secs());
+    }
+
+    /// Timeframe with the best Sharpe ratio; ties go to the higher return.
+    pub fn best(&self) -> Option<&SweepRow> {
+        self.rows.iter().max_by(|a, b| {
+            a.metrics
+                .sharpe_ratio
+                .total_cmp(&b.metrics.sharpe_ratio)
+                .then(a.metrics.total_return_pct.cmp(&b.metrics.total_return_pct))
+        })
+    }
+
+    /// Render as a text table, marking the best timeframe.
+    pub fn render(&self) -> String {
+        let best = self.best().map(|r| r.timeframe);
+
+        let mut s = String::new();
+        s.push_str(&format!("TIMEFRAME SWEEP: {}\n", self.strategy));
+        s.push_str("───────────────────────────────────────────────────────────\n");
+        s.push_str(&format!(
+            "  {:<9} {:>8} {:>10} {:>8} {:>9} {:>7} {:>9}\n",
+            "Timeframe", "Bars", "Return", "Sharpe", "Max DD", "Trades", "Win Rate"
+        ));
+        for row in &self.rows {
+            let m = &row.metrics;
+            let marker = if Some(row.timeframe) == best {
+                "*"
+            } else {
+                " "
+            };
+            s.push_str(&format!(
+                "{} {:<9} {:>8} {:>9.2}% {:>8.2} {:>8.2}% {:>7} {:>8.2}%\n",
+                marker,
+                row.timeframe.to_string(),
+                row.bars,
+                m.total_return_pct,
+                m.sharpe_ratio,
+                m.max_drawdown_pct,
+                m.total_trades,
+                m.win_rate_pct
+            ));
+        }
+        if let Some(best) = best {
+            s.push_str(&format!("  Best timeframe: {}\n", best));
+        }
+        s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BacktestConfig, BacktestStats};
+    use rust_decimal_macros::dec;
+    use trading_core::types::Portfolio;
+
+    fn report(bars: usize, sharpe: f64) -> BacktestReport {
+        let mut stats = BacktestStats::new(dec!(100000));
+        stats.bars_processed = bars;
+        stats.sharpe_ratio = sharpe;
+        BacktestReport {
+            config: BacktestConfig::default(),
+            stats,
+            final_portfolio: Portfolio::new(dec!(100000)),
+            seed: 0,
+            scenarios: Vec::new(),
+            provenance: None,
+        }
+    }
+
+    #[test]
+    fn test_rows_ordered_and_best_marked() {
+        let mut sweep = TimeframeSweep::new("momentum");
+        sweep.add(Timeframe::Daily, &report(250, 0.4));
+        sweep.add(Timeframe::Minute5, &report(19_500, -0.2));
+        sweep.add(Timeframe::Hour1, &report(1_750, 1.1));
+
+        let order: Vec<Timeframe> = sweep.rows.iter().map(|r| r.timeframe).collect();
+        assert_eq!(
+            order,
+            vec![Timeframe::Minute5, Timeframe::Hour1, Timeframe::Daily]
+        );
+        assert_eq!(sweep.best().unwrap().timeframe, Timeframe::Hour1);
+        assert!(sweep.render().contains("Best timeframe: 1h"));
+    }
+}
@@ -11,7 +11,8 @@ use tracing::{debug, info};
 use trading_core::error::BrokerError;
 use trading_core::traits::Broker;
 use trading_core::types::{
-    Bar, Fill, Order, OrderRequest, OrderStatus, OrderType, Portfolio, Position, Side,
+    Bar, Fill, MarketClock, OptionContract, Order, OrderRequest, OrderStatus, OrderType, Portfolio,
+    Position, Side,
 };
 use uuid::Uuid;
 
@@ -139,6 +140,23 @@ struct CreateOrderRequest {
     limit_price: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     stop_price: Option<String>,
+    /// `bracket` with both exits, `oto` with one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    order_class: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    take_profit: Option<TakeProfitLeg>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop_loss: Option<StopLossLeg>,
+}
+
+#[derive(Debug, Serialize)]
+struct TakeProfitLeg {
+    limit_price: String,
+}
+
+#[derive(Debug, Serialize)]
+struct StopLossLeg {
+    stop_price: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -189,12 +207,55 @@ struct AlpacaClock {
     #[allow(dead_code)]
     timestamp: String,
     is_open: bool,
-    #[allow(dead_code)]
     next_open: String,
-    #[allow(dead_code)]
     next_close: String,
 }
 
+/// Error body returned by the Alpaca API.
+#[derive(Debug, Deserialize)]
+struct AlpacaError {
+    code: Option<serde_json::Value>,
+    message: String,
+}
+
+/// Turn an unsuccessful response into a typed error.
+async fn api_error(resp: reqwest::Response) -> BrokerError {
+    let status = resp.status().as_u16();
+    let retry_after_secs = resp
+        .headers()
+        .get(header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse().ok());
+    let body = resp.text().await.unwrap_or_default();
+    classify_error(status, retry_after_secs, &body)
+}
+
+fn classify_error(status: u16, retry_after_secs: Option<u64>, body: &str) -> BrokerError {
+    let (code, message) = match serde_json::from_str::<AlpacaError>(body) {
+        Ok(e) => {
+            let code = e.code.map(|c| match c {
+                serde_json::Value::String(s) => s,
+                other => other.to_string(),
+            });
+            (code, e.message)
+        }
+        Err(_) => (None, body.to_string()),
+    };
+
+    match status {
+        401 => BrokerError::AuthenticationError(message),
+        // Alpaca doesn't always send Retry-After
+        429 => BrokerError::RateLimited {
+            retry_after_secs: retry_after_secs.unwrap_or(1),
+        },
+        _ => BrokerError::Http {
+            status,
+            code,
+            message,
+        },
+    }
+}
+
 /// Alpaca broker client.
 pub struct AlpacaBroker {
     config: AlpacaConfig,
@@ -261,9 +322,7 @@ impl AlpacaBroker {
             .map_err(|e| BrokerError::Connection(e.to_string()))?;
 
         if !resp.status().is_success() {
-            let status = resp.status();
-            let text = resp.text().await.unwrap_or_default();
-            return Err(BrokerError::ApiError(format!("{}: {}", status, text)));
+            return Err(api_error(resp).await);
         }
 
         let data: AlpacaSingleBarsResponse = resp
@@ -302,9 +361,7 @@ impl AlpacaBroker {
             .map_err(|e| BrokerError::Connection(e.to_string()))?;
 
         if !resp.status().is_success() {
-            let status = resp.status();
-            let text = resp.text().await.unwrap_or_default();
-            return Err(BrokerError::ApiError(format!("{}: {}", status, text)));
+            return Err(api_error(resp).await);
         }
 
         let data: AlpacaLatestQuotesResponse = resp
@@ -380,6 +437,7 @@ impl AlpacaBroker {
 
         let filled_avg_price = order.filled_avg_price.as_ref().and_then(|p| p.parse().ok());
 
+        let contract = order.symbol.parse::<OptionContract>().ok();
         let mut result = Order {
             id,
             client_order_id: order.client_order_id,
@@ -402,6 +460,7 @@ impl AlpacaBroker {
             expired_at: None,
             canceled_at,
             extended_hours: false,
+            contract,
         };
 
         if status == OrderStatus::Filled || status == OrderStatus::PartiallyFilled {
@@ -429,6 +488,8 @@ impl AlpacaBroker {
         let cost_basis: Decimal = p.cost_basis.parse().unwrap_or(dec!(0));
         let unrealized_pnl: Decimal = p.unrealized_pl.parse().unwrap_or(dec!(0));
         let unrealized_pnl_percent: Decimal = p.unrealized_plpc.parse().unwrap_or(dec!(0));
+        // Option positions come back under their OCC symbol
+        let contract = p.symbol.parse::<OptionContract>().ok();
 
         Position {
             symbol: p.symbol,
@@ -440,6 +501,8 @@ impl AlpacaBroker {
             unrealized_pnl,
             unrealized_pnl_percent,
             realized_pnl: dec!(0),
+            multiplier: contract.as_ref().map_or(dec!(1), |c| c.multiplier),
+            contract,
         }
     }
 }
@@ -457,9 +520,7 @@ impl Broker for AlpacaBroker {
             .map_err(|e| BrokerError::Connection(e.to_string()))?;
 
         if !resp.status().is_success() {
-            let status = resp.status();
-            let text = resp.text().await.unwrap_or_default();
-            return Err(BrokerError::ApiError(format!("{}: {}", status, text)));
+            return Err(api_error(resp).await);
         }
 
         let account: AlpacaAccount = resp
@@ -512,6 +573,17 @@ impl Broker for AlpacaBroker {
             time_in_force: "day".to_string(),
             limit_price: request.limit_price.map(|p| p.to_string()),
             stop_price: request.stop_price.map(|p| p.to_string()),
+            order_class: match (request.stop_loss, request.take_profit) {
+                (Some(_), Some(_)) => Some("bracket".to_string()),
+                (None, None) => None,
+                _ => Some("oto".to_string()),
+            },
+            take_profit: request.take_profit.map(|p| TakeProfitLeg {
+                limit_price: p.to_string(),
+            }),
+            stop_loss: request.stop_loss.map(|p| StopLossLeg {
+                stop_price: p.to_string(),
+            }),
         };
 
         debug!("Submitting order: {:?}", create_req);
@@ -525,9 +597,7 @@ impl Broker for AlpacaBroker {
             .map_err(|e| BrokerError::Connection(e.to_string()))?;
 
         if !resp.status().is_success() {
-            let status = resp.status();
-            let text = resp.text().await.unwrap_or_default();
-            return Err(BrokerError::OrderRejected(format!("{}: {}", status, text)));
+            return Err(api_error(resp).await);
         }
 
         let order: AlpacaOrder = resp
@@ -552,9 +622,7 @@ impl Broker for AlpacaBroker {
             .map_err(|e| BrokerError::Connection(e.to_string()))?;
 
         if !resp.status().is_success() {
-            let status = resp.status();
-            let text = resp.text().await.unwrap_or_default();
-            return Err(BrokerError::ApiError(format!("{}: {}", status, text)));
+            return Err(api_error(resp).await);
         }
         info!("Order canceled: {}", order_id);
         Ok(())
@@ -569,10 +637,12 @@ impl Broker for AlpacaBroker {
             .await
             .map_err(|e| BrokerError::Connection(e.to_string()))?;
 
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(BrokerError::OrderNotFound(order_id.to_string()));
+        }
+
         if !resp.status().is_success() {
-            let status = resp.status();
-            let text = resp.text().await.unwrap_or_default();
-            return Err(BrokerError::OrderNotFound(format!("{}: {}", status, text)));
+            return Err(api_error(resp).await);
         }
 
         let order: AlpacaOrder = resp
@@ -593,9 +663,7 @@ impl Broker for AlpacaBroker {
             .map_err(|e| BrokerError::Connection(e.to_string()))?;
 
         if !resp.status().is_success() {
-            let status = resp.status();
-            let text = resp.text().await.unwrap_or_default();
-            return Err(BrokerError::ApiError(format!("{}: {}", status, text)));
+            return Err(api_error(resp).await);
         }
 
         let orders: Vec<AlpacaOrder> = resp
@@ -615,9 +683,7 @@ impl Broker for AlpacaBroker {
             .map_err(|e| BrokerError::Connection(e.to_string()))?;
 
         if !resp.status().is_success() {
-            let status = resp.status();
-            let text = resp.text().await.unwrap_or_default();
-            return Err(BrokerError::ApiError(format!("{}: {}", status, text)));
+            return Err(api_error(resp).await);
         }
 
         let positions: Vec<AlpacaPosition> = resp
@@ -644,9 +710,7 @@ impl Broker for AlpacaBroker {
         }
 
         if !resp.status().is_success() {
-            let status = resp.status();
-            let text = resp.text().await.unwrap_or_default();
-            return Err(BrokerError::ApiError(format!("{}: {}", status, text)));
+            return Err(api_error(resp).await);
         }
 
         let p: AlpacaPosition = resp
@@ -666,9 +730,7 @@ impl Broker for AlpacaBroker {
             .map_err(|e| BrokerError::Connection(e.to_string()))?;
 
         if !resp.status().is_success() {
-            let status = resp.status();
-            let text = resp.text().await.unwrap_or_default();
-            return Err(BrokerError::ApiError(format!("{}: {}", status, text)));
+            return Err(api_error(resp).await);
         }
 
         let order: AlpacaOrder = resp
@@ -689,9 +751,7 @@ impl Broker for AlpacaBroker {
             .map_err(|e| BrokerError::Connection(e.to_string()))?;
 
         if !resp.status().is_success() {
-            let status = resp.status();
-            let text = resp.text().await.unwrap_or_default();
-            return Err(BrokerError::ApiError(format!("{}: {}", status, text)));
+            return Err(api_error(resp).await);
         }
 
         let orders: Vec<AlpacaOrder> = resp
@@ -712,15 +772,17 @@ impl Broker for AlpacaBroker {
             .map_err(|e| BrokerError::Connection(e.to_string()))?;
 
         if !resp.status().is_success() {
-            let status = resp.status();
-            let text = resp.text().await.unwrap_or_default();
-            return Err(BrokerError::ApiError(format!("{}: {}", status, text)));
+            return Err(api_error(resp).await);
         }
         info!("All orders canceled");
         Ok(())
     }
 
     async fn is_market_open(&self) -> Result<bool, BrokerError> {
+        Ok(self.market_clock().await?.is_open)
+    }
+
+    async fn market_clock(&self) -> Result<MarketClock, BrokerError> {
         let url = format!("{}/v2/clock", self.config.base_url());
         let resp = self
             .client
@@ -730,16 +792,23 @@ impl Broker for AlpacaBroker {
             .map_err(|e| BrokerError::Connection(e.to_string()))?;
 
         if !resp.status().is_success() {
-            let status = resp.status();
-            let text = resp.text().await.unwrap_or_default();
-            return Err(BrokerError::ApiError(format!("{}: {}", status, text)));
+            return Err(api_error(resp).await);
         }
 
         let clock: AlpacaClock = resp
             .json()
             .await
             .map_err(|e| BrokerError::ApiError(e.to_string()))?;
-        Ok(clock.is_open)
+        let time = |s: &str| {
+            DateTime::parse_from_rfc3339(s)
+                .ok()
+                .map(|t| t.with_timezone(&Utc))
+        };
+        Ok(MarketClock {
+            is_open: clock.is_open,
+            next_open: time(&clock.next_open),
+            next_close: time(&clock.next_close),
+        })
     }
 
     fn name(&self) -> &str {
@@ -750,3 +819,29 @@ impl Broker for AlpacaBroker {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_error() {
+        let rejected = classify_error(
+            403,
+            None,
+            r#"{"code":40310000,"message":"insufficient buying power"}"#,
+        );
+        assert!(rejected.is_rejection());
+        assert_eq!(rejected.code(), Some("40310000"));
+
+        let limited = classify_error(429, Some(5), "");
+        assert_eq!(
+            limited.retry_after(),
+            Some(std::time::Duration::from_secs(5))
+        );
+
+        let outage = classify_error(502, None, "<html>Bad Gateway</html>");
+        assert!(outage.is_retryable());
+        assert_eq!(outage.http_status(), Some(502));
+    }
+}
@@ -0,0 +1,264 @@
+//! Broker wrapper that logs orders instead of sending them.
+
+use async_trait::async_trait;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tracing::{info, warn};
+use trading_core::error::BrokerError;
+use trading_core::traits::Broker;
+use trading_core::types::{
+    BrokerSnapshot, MarketClock, Order, OrderRequest, OrderStatus, Portfolio, Position, Side,
+};
+use uuid::Uuid;
+
+/// Forwards every read to the wrapped broker but intercepts every write,
+/// logging the order, cancel or close it would have sent.
+///
+/// Intercepted orders come back accepted and read as canceled afterwards,
+/// since they never reach the market, so callers tracking them move on.
+pub struct DryRunBroker<B> {
+    inner: B,
+    name: String,
+    /// Orders that would have been submitted, by ID
+    intercepted: Mutex<HashMap<Uuid, Order>>,
+}
+
+impl<B: Broker> DryRunBroker<B> {
+    /// Wrap a broker.
+    pub fn new(inner: B) -> Self {
+        let name = format!("{} (dry run)", inner.name());
+        Self {
+            inner,
+            name,
+            intercepted: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The wrapped broker.
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+
+    /// Orders that would have been submitted, oldest first.
+    pub fn intercepted(&self) -> Vec<Order> {
+        let mut orders: Vec<Order> = self.intercepted.lock().unwrap().values().cloned().collect();
+        orders.sort_by_key(|o| o.created_at);
+        orders
+    }
+
+    /// The intercepted order with `order_id`, if it is one.
+    fn find(&self, order_id: &str) -> Option<Order> {
+        let id = Uuid::parse_str(order_id).ok()?;
+        self.intercepted.lock().unwrap().get(&id).cloned()
+    }
+}
+
+#[async_trait]
+impl<B: Broker> Broker for DryRunBroker<B> {
+    async fn get_account(&self) -> Result<Portfolio, BrokerError> {
+        self.inner.get_account().await
+    }
+
+    async fn submit_order(&self, request: OrderRequest) -> Result<Order, BrokerError> {
+        // What the order would have met, best-effort
+        let position = match self.inner.get_position(&request.symbol).await {
+            Ok(position) => position.map_or(Decimal::ZERO, |p| p.quantity),
+            Err(e) => {
+                warn!(symbol = %request.symbol, "Dry run: failed to read position: {}", e);
+                Decimal::ZERO
+            }
+        };
+        let buying_power = self.inner.get_buying_power().await.ok();
+        info!(
+            symbol = %request.symbol,
+            side = %request.side,
+            quantity = %request.quantity,
+            order_type = %request.order_type,
+            limit_price = ?request.limit_price,
+            stop_price = ?request.stop_price,
+            trail_amount = ?request.trail_amount,
+            time_in_force = ?request.time_in_force,
+            stop_loss = ?request.stop_loss,
+            take_profit = ?request.take_profit,
+            client_order_id = ?request.client_order_id,
+            extended_hours = request.extended_hours,
+            position = %position,
+            buying_power = ?buying_power,
+            "Dry run: would submit {} {} {} {}",
+            request.order_type,
+            request.side,
+            request.quantity,
+            request.symbol
+        );
+
+        let mut order = Order::from_request(&request);
+        order.status = OrderStatus::Accepted;
+        order.submitted_at = Some(order.created_at);
+        self.intercepted
+            .lock()
+            .unwrap()
+            .insert(order.id, order.clone());
+        Ok(order)
+    }
+
+    async fn cancel_order(&self, order_id: &str) -> Result<(), BrokerError> {
+        match self.find(order_id) {
+            Some(order) => info!(symbol = %order.symbol, "Dry run: would cancel {}", order_id),
+            None => {
+                let order = self.inner.get_order(order_id).await?;
+                if order.status.is_terminal() {
+                    return Err(BrokerError::OrderRejected(
+                        "Order already terminal".to_string(),
+                    ));
+                }
+                info!(
+                    symbol = %order.symbol,
+                    "Dry run: would cancel {} {} {} ({})",
+                    order.side, order.quantity, order.symbol, order_id
+                );
+            }
+        }
+        Ok(())
+    }
+
+    async fn get_order(&self, order_id: &str) -> Result<Order, BrokerError> {
+        match self.find(order_id) {
+            Some(mut order) => {
+                order.status = OrderStatus::Canceled;
+                order.canceled_at = Some(Utc::now());
+                Ok(order)
+            }
+            None => self.inner.get_order(order_id).await,
+        }
+    }
+
+    async fn get_open_orders(&self) -> Result<Vec<Order>, BrokerError> {
+        self.inner.get_open_orders().await
+    }
+
+    async fn get_positions(&self) -> Result<Vec<Position>, BrokerError> {
+        self.inner.get_positions().await
+    }
+
+    async fn get_position(&self, symbol: &str) -> Result<Option<Position>, BrokerError> {
+        self.inner.get_position(symbol).await
+    }
+
+    async fn close_position(&self, symbol: &str) -> Result<Order, BrokerError> {
+        let position = self
+            .inner
+            .get_position(symbol)
+            .await?
+            .filter(|p| !p.quantity.is_zero())
+            .ok_or_else(|| BrokerError::PositionNotFound(symbol.to_string()))?;
+        let side = if position.is_long() {
+            Side::Sell
+        } else {
+            Side::Buy
+        };
+        self.submit_order(OrderRequest::market(symbol, side, position.quantity.abs()))
+            .await
+    }
+
+    async fn close_all_positions(&self) -> Result<Vec<Order>, BrokerError> {
+        let mut orders = Vec::new();
+        for position in self.inner.get_positions().await? {
+            if !position.quantity.is_zero() {
+                orders.push(self.close_position(&position.symbol).await?);
+            }
+        }
+        Ok(orders)
+    }
+
+    async fn cancel_all_orders(&self) -> Result<(), BrokerError> {
+        let orders = self.inner.get_open_orders().await?;
+        info!("Dry run: would cancel {} open orders", orders.len());
+        for order in orders {
+            info!(
+                symbol = %order.symbol,
+                "Dry run: would cancel {} {} {} ({})",
+                order.side, order.quantity, order.symbol, order.id
+            );
+        }
+        Ok(())
+    }
+
+    async fn is_market_open(&self) -> Result<bool, BrokerError> {
+        self.inner.is_market_open().await
+    }
+
+    async fn market_clock(&self) -> Result<MarketClock, BrokerError> {
+        self.inner.market_clock().await
+    }
+
+    async fn get_buying_power(&self) -> Result<Decimal, BrokerError> {
+        self.inner.get_buying_power().await
+    }
+
+    async fn snapshot(&self) -> Result<BrokerSnapshot, BrokerError> {
+        self.inner.snapshot().await
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PaperBroker;
+    use rust_decimal_macros::dec;
+
+    #[tokio::test]
+    async fn test_writes_are_intercepted() {
+        let paper = PaperBroker::new(dec!(100000));
+        let resting = paper
+            .submit_order(OrderRequest::limit("AAPL", Side::Buy, dec!(10), dec!(90)))
+            .await
+            .unwrap();
+        let buy = paper
+            .submit_order(OrderRequest::market("MSFT", Side::Buy, dec!(5)))
+            .await
+            .unwrap();
+        paper.execute_at_price(buy.id, dec!(100)).unwrap();
+        let broker = DryRunBroker::new(paper);
+        assert_eq!(broker.name(), "Paper Broker (dry run)");
+
+        // The order is accepted but never reaches the wrapped broker
+        let order = broker
+            .submit_order(OrderRequest::market("AAPL", Side::Buy, dec!(10)))
+            .await
+            .unwrap();
+        assert_eq!(order.status, OrderStatus::Accepted);
+        assert_eq!(broker.inner().get_open_orders().await.unwrap().len(), 1);
+        assert_eq!(
+            broker
+                .get_order(&order.id.to_string())
+                .await
+                .unwrap()
+                .status,
+            OrderStatus::Canceled
+        );
+
+        // Cancels and closes leave the real orders and positions alone
+        broker.cancel_all_orders().await.unwrap();
+        broker.cancel_order(&resting.id.to_string()).await.unwrap();
+        let closes = broker.close_all_positions().await.unwrap();
+        assert_eq!(closes.len(), 1);
+        assert_eq!(closes[0].side, Side::Sell);
+        assert_eq!(closes[0].quantity, dec!(5));
+        assert_eq!(
+            broker
+                .get_order(&resting.id.to_string())
+                .await
+                .unwrap()
+                .status,
+            resting.status
+        );
+        assert_eq!(broker.get_positions().await.unwrap().len(), 1);
+        assert_eq!(broker.intercepted().len(), 2);
+    }
+}
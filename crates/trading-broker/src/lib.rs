@@ -1,7 +1,13 @@
 //! Broker integrations.
 
+#[cfg(not(target_arch = "wasm32"))]
 mod alpaca;
+mod dry_run;
+mod order_book;
 mod paper;
 
+#[cfg(not(target_arch = "wasm32"))]
 pub use alpaca::{AlpacaBroker, AlpacaConfig};
-pub use paper::PaperBroker;
+pub use dry_run::DryRunBroker;
+pub use order_book::{BookFill, BookMatcher, DepthUpdate, OrderBook, RestingOrder};
+pub use paper::{PaperBroker, PaperLatency};
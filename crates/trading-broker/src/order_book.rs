@@ -0,0 +1,523 @@
+//! Level-2 order books and simulated matching against them.
+//!
+//! [`OrderBook`] keeps aggregated depth per price level from snapshots and
+//! incremental updates. [`BookMatcher`] simulates our own orders against a
+//! book: marketable orders take liquidity level by level, and the rest
+//! rests at its limit behind the size already quoted there, filling in
+//! price-time priority as trades print through it or the other side moves
+//! onto it. Our orders never change the recorded book, beyond the
+//! liquidity they take from it.
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use trading_core::types::{QuoteTick, Side};
+use uuid::Uuid;
+
+/// A change to one price level. A zero size removes the level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DepthUpdate {
+    /// Buy for a bid level, Sell for an ask level
+    pub side: Side,
+    pub price: Decimal,
+    pub size: Decimal,
+}
+
+impl DepthUpdate {
+    pub fn bid(price: Decimal, size: Decimal) -> Self {
+        Self {
+            side: Side::Buy,
+            price,
+            size,
+        }
+    }
+
+    pub fn ask(price: Decimal, size: Decimal) -> Self {
+        Self {
+            side: Side::Sell,
+            price,
+            size,
+        }
+    }
+}
+
+/// Aggregated depth for one symbol.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OrderBook {
+    pub symbol: String,
+    /// Time of the last update (ms)
+    pub timestamp: i64,
+    bids: BTreeMap<Decimal, Decimal>,
+    asks: BTreeMap<Decimal, Decimal>,
+}
+
+impl OrderBook {
+    /// Create an empty book.
+    pub fn new(symbol: impl Into<String>) -> Self {
+        Self {
+            symbol: symbol.into(),
+            ..Default::default()
+        }
+    }
+
+    /// A symmetric book around `mid`, `levels` deep on each side with
+    /// `size` at every level, for testing quoting logic without recorded
+    /// depth. The best bid and ask sit half a `tick` either side of `mid`.
+    pub fn synthetic(
+        symbol: impl Into<String>,
+        mid: Decimal,
+        tick: Decimal,
+        levels: usize,
+        size: Decimal,
+    ) -> Self {
+        let mut book = Self::new(symbol);
+        let half = tick / Decimal::TWO;
+        for level in 0..levels {
+            let offset = half + tick * Decimal::from(level);
+            book.apply(DepthUpdate::bid(mid - offset, size));
+            book.apply(DepthUpdate::ask(mid + offset, size));
+        }
+        book
+    }
+
+    /// Replace the whole book.
+    pub fn apply_snapshot(
+        &mut self,
+        timestamp: i64,
+        bids: impl IntoIterator<Item = (Decimal, Decimal)>,
+        asks: impl IntoIterator<Item = (Decimal, Decimal)>,
+    ) {
+        self.timestamp = timestamp;
+        self.bids = bids
+            .into_iter()
+            .filter(|(_, s)| *s > Decimal::ZERO)
+            .collect();
+        self.asks = asks
+            .into_iter()
+            .filter(|(_, s)| *s > Decimal::ZERO)
+            .collect();
+    }
+
+    /// Set or remove one level.
+    pub fn apply(&mut self, update: DepthUpdate) {
+        let levels = self.levels_mut(update.side);
+        if update.size > Decimal::ZERO {
+            levels.insert(update.price, update.size);
+        } else {
+            levels.remove(&update.price);
+        }
+    }
+
+    fn levels_mut(&mut self, side: Side) -> &mut BTreeMap<Decimal, Decimal> {
+        match side {
+            Side::Buy => &mut self.bids,
+            Side::Sell => &mut self.asks,
+        }
+    }
+
+    /// Highest bid as (price, size).
+    pub fn best_bid(&self) -> Option<(Decimal, Decimal)> {
+        self.bids.iter().next_back().map(|(p, s)| (*p, *s))
+    }
+
+    /// Lowest ask as (price, size).
+    pub fn best_ask(&self) -> Option<(Decimal, Decimal)> {
+        self.asks.iter().next().map(|(p, s)| (*p, *s))
+    }
+
+    /// Midpoint of the best bid and ask.
+    pub fn mid(&self) -> Option<Decimal> {
+        Some((self.best_bid()?.0 + self.best_ask()?.0) / Decimal::TWO)
+    }
+
+    /// Best ask less best bid.
+    pub fn spread(&self) -> Option<Decimal> {
+        Some(self.best_ask()?.0 - self.best_bid()?.0)
+    }
+
+    /// Size quoted at `price` on `side`.
+    pub fn size_at(&self, side: Side, price: Decimal) -> Decimal {
+        let levels = match side {
+            Side::Buy => &self.bids,
+            Side::Sell => &self.asks,
+        };
+        levels.get(&price).copied().unwrap_or_default()
+    }
+
+    /// Up to `levels` levels of `side`, best first.
+    pub fn depth(&self, side: Side, levels: usize) -> Vec<(Decimal, Decimal)> {
+        match side {
+            Side::Buy => self
+                .bids
+                .iter()
+                .rev()
+                .take(levels)
+                .map(|(p, s)| (*p, *s))
+                .collect(),
+            Side::Sell => self
+                .asks
+                .iter()
+                .take(levels)
+                .map(|(p, s)| (*p, *s))
+                .collect(),
+        }
+    }
+
+    /// Top of book as a quote.
+    pub fn quote(&self) -> Option<QuoteTick> {
+        let (bid, bid_size) = self.best_bid()?;
+        let (ask, ask_size) = self.best_ask()?;
+        let f = |d: Decimal| d.try_into().unwrap_or(0.0);
+        Some(QuoteTick::new(self.timestamp, f(bid), f(ask)).with_sizes(f(bid_size), f(ask_size)))
+    }
+}
+
+/// A fill of one of our orders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BookFill {
+    pub order_id: Uuid,
+    pub side: Side,
+    pub price: Decimal,
+    pub quantity: Decimal,
+    /// Whether the order was resting (maker) rather than taking liquidity
+    pub maker: bool,
+}
+
+/// One of our orders resting in the book.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RestingOrder {
+    pub id: Uuid,
+    pub side: Side,
+    pub price: Decimal,
+    pub remaining: Decimal,
+    /// Book size at our price that arrived before us and fills first
+    pub queue_ahead: Decimal,
+    /// Arrival order, for time priority among our own orders
+    seq: u64,
+}
+
+/// Simulates our orders against an [`OrderBook`] in price-time priority.
+#[derive(Debug, Clone)]
+pub struct BookMatcher {
+    book: OrderBook,
+    resting: Vec<RestingOrder>,
+    next_seq: u64,
+}
+
+impl BookMatcher {
+    pub fn new(book: OrderBook) -> Self {
+        Self {
+            book,
+            resting: Vec::new(),
+            next_seq: 0,
+        }
+    }
+
+    /// The book as last updated, less liquidity our orders took.
+    pub fn book(&self) -> &OrderBook {
+        &self.book
+    }
+
+    /// Our orders resting in the book, in priority order per side.
+    pub fn resting(&self) -> Vec<RestingOrder> {
+        let mut resting = self.resting.clone();
+        resting.sort_by(priority);
+        resting
+    }
+
+    /// Submit an order; `limit` of `None` is a market order. The marketable
+    /// part fills immediately against the opposite side, best level first.
+    /// A limit order's remainder rests at the back of the queue at its
+    /// price; a market order's remainder is dropped.
+    pub fn submit(
+        &mut self,
+        id: Uuid,
+        side: Side,
+        quantity: Decimal,
+        limit: Option<Decimal>,
+    ) -> Vec<BookFill> {
+        let mut fills = Vec::new();
+        let mut remaining = quantity;
+        let opposite = opposite(side);
+        while remaining > Decimal::ZERO {
+            let best = match side {
+                Side::Buy => self.book.best_ask(),
+                Side::Sell => self.book.best_bid(),
+            };
+            let Some((price, size)) = best else {
+                break;
+            };
+            let crosses = limit.map_or(true, |limit| match side {
+                Side::Buy => price <= limit,
+                Side::Sell => price >= limit,
+            });
+            if !crosses {
+                break;
+            }
+            let quantity = remaining.min(size);
+            self.book.apply(DepthUpdate {
+                side: opposite,
+                price,
+                size: size - quantity,
+            });
+            remaining -= quantity;
+            fills.push(BookFill {
+                order_id: id,
+                side,
+                price,
+                quantity,
+                maker: false,
+            });
+        }
+
+        if let (Some(price), true) = (limit, remaining > Decimal::ZERO) {
+            self.resting.push(RestingOrder {
+                id,
+                side,
+                price,
+                remaining,
+                queue_ahead: self.book.size_at(side, price),
+                seq: self.next_seq,
+            });
+            self.next_seq += 1;
+        }
+        fills
+    }
+
+    /// Cancel a resting order. Returns whether it was resting.
+    pub fn cancel(&mut self, id: Uuid) -> bool {
+        let before = self.resting.len();
+        self.resting.retain(|o| o.id != id);
+        self.resting.len() < before
+    }
+
+    /// Apply a depth update. Size leaving our level shortens the queue
+    /// ahead of us (cancels are assumed to come from the front), and an
+    /// opposite level moving onto or through our price trades with the
+    /// queue ahead of us, then fills us at our price, up to its size.
+    pub fn apply(&mut self, update: DepthUpdate) -> Vec<BookFill> {
+        self.book.apply(update);
+        for order in &mut self.resting {
+            if order.side == update.side && order.price == update.price {
+                order.queue_ahead = order.queue_ahead.min(update.size);
+            }
+        }
+
+        let (fills, available) = self.match_resting(
+            opposite(update.side),
+            update.size,
+            |order| match order.side {
+                Side::Buy => update.price <= order.price,
+                Side::Sell => update.price >= order.price,
+            },
+            |_| true,
+        );
+        if !fills.is_empty() {
+            // Liquidity we traded with is gone from the book
+            self.book.apply(DepthUpdate {
+                size: available,
+                ..update
+            });
+        }
+        self.remove_filled();
+        fills
+    }
+
+    /// Apply a trade print of `size` at `price`. Resting orders priced
+    /// better than the trade fill first; at the trade price, the queue
+    /// ahead of an order fills before it does.
+    pub fn on_trade(&mut self, price: Decimal, size: Decimal) -> Vec<BookFill> {
+        let mut fills = Vec::new();
+        for side in [Side::Buy, Side::Sell] {
+            let (side_fills, _) = self.match_resting(
+                side,
+                size,
+                |order| match side {
+                    Side::Buy => price <= order.price,
+                    Side::Sell => price >= order.price,
+                },
+                |order| order.price == price,
+            );
+            fills.extend(side_fills);
+        }
+        self.remove_filled();
+        fills
+    }
+
+    /// Trade `available` against our resting orders on `side` that it
+    /// `reaches`, in priority order. Where the level is `queued`, book size
+    /// ahead of each order trades before it, and what trades there moves
+    /// every one of our orders at that price up the queue. Returns the
+    /// fills and the size left over.
+    fn match_resting(
+        &mut self,
+        side: Side,
+        mut available: Decimal,
+        reaches: impl Fn(&RestingOrder) -> bool,
+        queued: impl Fn(&RestingOrder) -> bool,
+    ) -> (Vec<BookFill>, Decimal) {
+        // Our orders grouped by price, best first
+        let mut levels: Vec<Vec<usize>> = Vec::new();
+        for index in self.priority_indices(side) {
+            match levels.last_mut() {
+                Some(level) if self.resting[level[0]].price == self.resting[index].price => {
+                    level.push(index)
+                }
+                _ => levels.push(vec![index]),
+            }
+        }
+
+        let mut fills = Vec::new();
+        for level in &levels {
+            let first = self.resting[level[0]];
+            if available <= Decimal::ZERO || !reaches(&first) {
+                continue;
+            }
+            let queued = queued(&first);
+            // Book size traded at this level; the queue ahead of our later
+            // orders includes what was ahead of our earlier ones
+            let mut traded = Decimal::ZERO;
+            for &index in level {
+                let order = &mut self.resting[index];
+                if queued {
+                    let ahead = (order.queue_ahead - traded)
+                        .max(Decimal::ZERO)
+                        .min(available);
+                    available -= ahead;
+                    traded += ahead;
+                    if order.queue_ahead > traded {
+                        break;
+                    }
+                }
+                let quantity = order.remaining.min(available);
+                if quantity > Decimal::ZERO {
+                    available -= quantity;
+                    fills.push(fill(order, quantity));
+                }
+            }
+            for &index in level {
+                let order = &mut self.resting[index];
+                order.queue_ahead -= order.queue_ahead.min(traded);
+            }
+        }
+        (fills, available)
+    }
+
+    /// Indices of resting orders on `side`, best price first, then oldest.
+    fn priority_indices(&self, side: Side) -> Vec<usize> {
+        let mut indices: Vec<usize> = (0..self.resting.len())
+            .filter(|&i| self.resting[i].side == side)
+            .collect();
+        indices.sort_by(|&a, &b| priority(&self.resting[a], &self.resting[b]));
+        indices
+    }
+
+    fn remove_filled(&mut self) {
+        self.resting.retain(|o| o.remaining > Decimal::ZERO);
+    }
+}
+
+fn opposite(side: Side) -> Side {
+    match side {
+        Side::Buy => Side::Sell,
+        Side::Sell => Side::Buy,
+    }
+}
+
+/// Price-time priority: bids highest first, asks lowest first, then by
+/// arrival.
+fn priority(a: &RestingOrder, b: &RestingOrder) -> std::cmp::Ordering {
+    let by_price = match a.side {
+        Side::Buy => b.price.cmp(&a.price),
+        Side::Sell => a.price.cmp(&b.price),
+    };
+    let is_ask = |o: &RestingOrder| o.side == Side::Sell;
+    is_ask(a)
+        .cmp(&is_ask(b))
+        .then(by_price)
+        .then(a.seq.cmp(&b.seq))
+}
+
+/// Fill `quantity` of a resting order at its own price.
+fn fill(order: &mut RestingOrder, quantity: Decimal) -> BookFill {
+    order.remaining -= quantity;
+    BookFill {
+        order_id: order.id,
+        side: order.side,
+        price: order.price,
+        quantity,
+        maker: true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_market_order_walks_the_book() {
+        let book = OrderBook::synthetic("AAPL", dec!(100), dec!(0.02), 3, dec!(10));
+        assert_eq!(book.best_bid(), Some((dec!(99.99), dec!(10))));
+        assert_eq!(book.spread(), Some(dec!(0.02)));
+
+        let mut matcher = BookMatcher::new(book);
+        let fills = matcher.submit(Uuid::new_v4(), Side::Buy, dec!(15), None);
+        let taken: Vec<_> = fills.iter().map(|f| (f.price, f.quantity)).collect();
+        assert_eq!(
+            taken,
+            vec![(dec!(100.01), dec!(10)), (dec!(100.03), dec!(5))]
+        );
+        assert!(fills.iter().all(|f| !f.maker));
+        assert_eq!(matcher.book().best_ask(), Some((dec!(100.03), dec!(5))));
+
+        // A limit below the ask rests instead
+        let fills = matcher.submit(Uuid::new_v4(), Side::Sell, dec!(5), Some(dec!(100.02)));
+        assert!(fills.is_empty());
+        assert_eq!(matcher.resting()[0].queue_ahead, Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_resting_orders_fill_in_price_time_priority() {
+        let book = OrderBook::synthetic("AAPL", dec!(100), dec!(0.02), 3, dec!(10));
+        let mut matcher = BookMatcher::new(book);
+        let first = Uuid::new_v4();
+        let second = Uuid::new_v4();
+        matcher.submit(first, Side::Buy, dec!(5), Some(dec!(99.99)));
+        matcher.submit(second, Side::Buy, dec!(5), Some(dec!(99.99)));
+        let ahead = |m: &BookMatcher| {
+            m.resting()
+                .iter()
+                .map(|o| o.queue_ahead)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ahead(&matcher), vec![dec!(10), dec!(10)]);
+
+        // The ten ahead of us trade first, moving both orders up, then our
+        // orders in arrival order
+        assert!(matcher.on_trade(dec!(99.99), dec!(6)).is_empty());
+        assert_eq!(ahead(&matcher), vec![dec!(4), dec!(4)]);
+        let fills = matcher.on_trade(dec!(99.99), dec!(7));
+        assert_eq!(fills.len(), 1);
+        assert_eq!((fills[0].order_id, fills[0].quantity), (first, dec!(3)));
+        assert!(fills[0].maker);
+        assert_eq!(ahead(&matcher), vec![dec!(0), dec!(0)]);
+
+        // Five more join behind us, then a third order behind them. The ask
+        // dropping onto our bid fills our first two, then trades with the
+        // queue ahead of the third
+        matcher.apply(DepthUpdate::bid(dec!(99.99), dec!(5)));
+        let third = Uuid::new_v4();
+        matcher.submit(third, Side::Buy, dec!(4), Some(dec!(99.99)));
+        let fills = matcher.apply(DepthUpdate::ask(dec!(99.99), dec!(9)));
+        let filled: Vec<_> = fills.iter().map(|f| (f.order_id, f.quantity)).collect();
+        assert_eq!(filled, vec![(first, dec!(2)), (second, dec!(5))]);
+        assert_eq!(matcher.book().best_ask(), Some((dec!(100.01), dec!(10))));
+        assert_eq!(ahead(&matcher), vec![dec!(3)]);
+
+        let fills = matcher.on_trade(dec!(99.99), dec!(4));
+        assert_eq!((fills[0].order_id, fills[0].quantity), (third, dec!(1)));
+        assert!(matcher.cancel(third));
+        assert!(matcher.resting().is_empty());
+    }
+}
@@ -1,34 +1,88 @@
 //! Paper trading broker for backtesting and simulation.
 
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
 use trading_core::error::BrokerError;
-use trading_core::traits::Broker;
+use trading_core::traits::{Broker, Clock, SystemClock};
 use trading_core::types::{
-    Fill, Order, OrderRequest, OrderStatus, OrderType, Portfolio, Position, Side,
+    BrokerSnapshot, Fill, Instrument, Order, OrderRequest, OrderStatus, OrderType, Portfolio,
+    Position, QuoteTick, Side, TimeInForce,
 };
 use uuid::Uuid;
 
+/// Simulated exchange latency, so paper trading and replay tests see the
+/// races that instant fills hide, such as a cancel arriving after a fill.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PaperLatency {
+    /// Delay before a submitted order is acknowledged
+    pub submit_delay: Duration,
+    /// Time after acknowledgement before an order can fill
+    pub fill_delay: Duration,
+    /// Time a cancel spends in flight, during which the order can still fill
+    pub cancel_delay: Duration,
+}
+
+impl PaperLatency {
+    /// Whether any delay is configured.
+    pub fn is_zero(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Account and orders, kept behind a single lock so every operation sees
+/// and leaves a consistent view.
+#[derive(Debug)]
+struct BrokerState {
+    portfolio: Portfolio,
+    orders: HashMap<Uuid, Order>,
+    /// When each order reached the simulated exchange, with latency on
+    acknowledged: HashMap<Uuid, Instant>,
+    /// Latest quote per symbol, for quote fills
+    quotes: HashMap<String, QuoteTick>,
+}
+
 /// Paper trading broker for simulation.
+///
+/// Each broker owns its account outright and shares nothing with other
+/// instances, so parallel backtests should each construct their own; the
+/// lock only guards against concurrent callers of the same broker and is
+/// uncontended on the backtest hot path.
 pub struct PaperBroker {
-    portfolio: Arc<Mutex<Portfolio>>,
-    orders: Arc<Mutex<HashMap<Uuid, Order>>>,
+    state: Mutex<BrokerState>,
     slippage_pct: Decimal,
     commission_per_share: Decimal,
+    latency: PaperLatency,
+    /// Instruments by symbol, for contract multipliers
+    instruments: HashMap<String, Instrument>,
+    /// Fill at the prevailing bid/ask instead of the given price plus slippage
+    quote_fills: bool,
+    /// Time that orders and fills are stamped with
+    clock: Arc<dyn Clock>,
 }
 
 impl PaperBroker {
     /// Create a new paper broker with initial capital.
     pub fn new(initial_capital: Decimal) -> Self {
         Self {
-            portfolio: Arc::new(Mutex::new(Portfolio::new(initial_capital))),
-            orders: Arc::new(Mutex::new(HashMap::new())),
+            state: Mutex::new(BrokerState {
+                portfolio: Portfolio::new(initial_capital),
+                orders: HashMap::new(),
+                acknowledged: HashMap::new(),
+                quotes: HashMap::new(),
+            }),
             slippage_pct: dec!(0.05), // 0.05% slippage
             commission_per_share: Decimal::ZERO,
+            latency: PaperLatency::default(),
+            instruments: HashMap::new(),
+            quote_fills: false,
+            clock: Arc::new(SystemClock),
         }
     }
 
@@ -44,13 +98,94 @@ impl PaperBroker {
         self
     }
 
-    /// Simulate order execution at a given price.
+    /// Set simulated exchange latency.
+    pub fn with_latency(mut self, latency: PaperLatency) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    /// Set the instruments traded; fills of symbols with a contract
+    /// multiplier move cash and P&L by it. Other symbols trade as shares.
+    pub fn with_instruments(mut self, instruments: HashMap<String, Instrument>) -> Self {
+        self.instruments = instruments;
+        self
+    }
+
+    /// Stamp orders and fills with `clock` instead of wall time, e.g. a
+    /// backtest's simulated clock.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Fill against quotes: once a symbol has a quote (see
+    /// [`update_quote`](Self::update_quote)), buys fill at the ask and sells
+    /// at the bid, with no slippage added, so the spread is the cost of
+    /// trading. Symbols without a quote fill as before.
+    pub fn with_quote_fills(mut self) -> Self {
+        self.quote_fills = true;
+        self
+    }
+
+    /// Record the latest quote for `symbol`.
+    pub fn update_quote(&self, symbol: &str, quote: QuoteTick) {
+        let mut state = self.state.lock().unwrap();
+        if quote.is_valid() {
+            state.quotes.insert(symbol.to_string(), quote);
+        } else {
+            // A one-sided or crossed book can't be filled against
+            state.quotes.remove(symbol);
+        }
+    }
+
+    /// Latest quote recorded for `symbol`.
+    pub fn quote(&self, symbol: &str) -> Option<QuoteTick> {
+        self.state.lock().unwrap().quotes.get(symbol).copied()
+    }
+
+    /// Units of the price one contract of `order` is worth: the option
+    /// contract's multiplier, else the instrument's.
+    fn multiplier(&self, order: &Order) -> Decimal {
+        if let Some(contract) = &order.contract {
+            return contract.multiplier;
+        }
+        self.instruments
+            .get(&order.symbol)
+            .map_or(Decimal::ONE, |i| i.multiplier)
+    }
+
+    /// Simulate order execution at a given price, or at the quote with
+    /// quote fills on.
+    ///
+    /// With a fill delay, an order that has not been at the exchange long
+    /// enough is returned unfilled.
     pub fn execute_at_price(
         &self,
         order_id: Uuid,
         market_price: Decimal,
     ) -> Result<Order, BrokerError> {
-        let mut orders = self.orders.lock().unwrap();
+        self.execute_partial(order_id, market_price, Decimal::MAX)
+    }
+
+    /// Simulate a fill of at most `quantity` at a given price.
+    ///
+    /// The order stays partially filled until later calls fill the rest,
+    /// unless its time in force says otherwise: an immediate-or-cancel
+    /// order fills what it can now and cancels the rest, and a fill-or-kill
+    /// order fills in full now or not at all.
+    pub fn execute_partial(
+        &self,
+        order_id: Uuid,
+        market_price: Decimal,
+        quantity: Decimal,
+    ) -> Result<Order, BrokerError> {
+        let mut state = self.state.lock().unwrap();
+        let BrokerState {
+            portfolio,
+            orders,
+            acknowledged,
+            quotes,
+        } = &mut *state;
         let order = orders
             .get_mut(&order_id)
             .ok_or_else(|| BrokerError::OrderNotFound(order_id.to_string()))?;
@@ -59,61 +194,100 @@ impl PaperBroker {
             return Ok(order.clone());
         }
 
-        // Apply slippage
-        let fill_price = match order.side {
-            Side::Buy => market_price * (dec!(1) + self.slippage_pct / dec!(100)),
-            Side::Sell => market_price * (dec!(1) - self.slippage_pct / dec!(100)),
+        if let Some(at) = acknowledged.get(&order_id) {
+            if at.elapsed() < self.latency.fill_delay {
+                return Ok(order.clone());
+            }
+        }
+
+        // Buys lift the ask and sells hit the bid; the spread replaces slippage
+        let quoted = quotes
+            .get(&order.symbol)
+            .filter(|_| self.quote_fills)
+            .and_then(|q| Decimal::try_from(q.price_for(order.side)).ok());
+        let market_price = quoted.unwrap_or(market_price);
+        let slippage_pct = if quoted.is_some() {
+            Decimal::ZERO
+        } else {
+            self.slippage_pct
         };
 
-        // Check if limit order can be filled
-        if order.order_type == OrderType::Limit {
-            if let Some(limit) = order.limit_price {
-                match order.side {
-                    Side::Buy if fill_price > limit => {
-                        return Ok(order.clone()); // Can't fill above limit
-                    }
-                    Side::Sell if fill_price < limit => {
-                        return Ok(order.clone()); // Can't fill below limit
-                    }
-                    _ => {}
+        // Immediate-or-cancel and fill-or-kill orders get this one chance
+        let immediate = matches!(order.time_in_force, TimeInForce::IOC | TimeInForce::FOK);
+
+        // Limit orders fill only once the market reaches the limit
+        let limit = order
+            .limit_price
+            .filter(|_| order.order_type == OrderType::Limit);
+        if let Some(limit) = limit {
+            let unreached = match order.side {
+                Side::Buy => market_price > limit,
+                Side::Sell => market_price < limit,
+            };
+            if unreached {
+                if immediate {
+                    cancel(order, self.clock.now());
                 }
+                return Ok(order.clone());
             }
         }
 
+        // Apply slippage, never past the limit
+        let mut fill_price = match order.side {
+            Side::Buy => market_price * (dec!(1) + slippage_pct / dec!(100)),
+            Side::Sell => market_price * (dec!(1) - slippage_pct / dec!(100)),
+        };
+        if let Some(limit) = limit {
+            fill_price = match order.side {
+                Side::Buy => fill_price.min(limit),
+                Side::Sell => fill_price.max(limit),
+            };
+        }
+
+        let quantity = quantity.min(order.remaining_quantity());
+        if quantity <= Decimal::ZERO {
+            return Ok(order.clone());
+        }
+        if order.time_in_force == TimeInForce::FOK && quantity < order.remaining_quantity() {
+            cancel(order, self.clock.now());
+            return Ok(order.clone());
+        }
+
         // Check buying power for buys
+        let multiplier = self.multiplier(order);
         if order.side == Side::Buy {
-            let portfolio = self.portfolio.lock().unwrap();
-            let cost = fill_price * order.quantity;
+            let cost = fill_price * quantity * multiplier;
             if cost > portfolio.cash {
+                if immediate {
+                    cancel(order, self.clock.now());
+                }
                 return Err(BrokerError::InsufficientFunds {
                     required: cost,
                     available: portfolio.cash,
                 });
             }
-            drop(portfolio);
         }
 
         // Calculate commission
-        let commission = self.commission_per_share * order.quantity;
+        let commission = self.commission_per_share * quantity;
 
         // Create fill
         let fill = Fill {
             id: Uuid::new_v4().to_string(),
             order_id,
-            quantity: order.quantity,
+            quantity,
             price: fill_price,
             commission,
-            timestamp: Utc::now(),
+            timestamp: self.clock.now(),
         };
 
         order.add_fill(fill);
-        order.status = OrderStatus::Filled;
-
-        // Update portfolio
-        let mut portfolio = self.portfolio.lock().unwrap();
+        if order.time_in_force == TimeInForce::IOC && !order.is_filled() {
+            cancel(order, self.clock.now());
+        }
 
         // Update cash
-        let fill_value = fill_price * order.quantity;
+        let fill_value = fill_price * quantity * multiplier;
         match order.side {
             Side::Buy => {
                 portfolio.cash -= fill_value + commission;
@@ -127,9 +301,16 @@ impl PaperBroker {
         let position = portfolio
             .positions
             .entry(order.symbol.clone())
-            .or_insert_with(|| Position::new(&order.symbol, Decimal::ZERO, Decimal::ZERO));
+            .or_insert_with(|| {
+                let position = Position::new(&order.symbol, Decimal::ZERO, Decimal::ZERO)
+                    .with_multiplier(multiplier);
+                match &order.contract {
+                    Some(contract) => position.with_contract(contract.clone()),
+                    None => position,
+                }
+            });
 
-        position.apply_fill(order.side, order.quantity, fill_price);
+        position.apply_fill(order.side, quantity, fill_price);
 
         if position.is_flat() {
             portfolio.positions.remove(&order.symbol);
@@ -141,33 +322,87 @@ impl PaperBroker {
         Ok(order.clone())
     }
 
+    /// Expire a symbol's working day orders at the end of its session.
+    ///
+    /// Market orders fill as soon as they reach the market, so only orders
+    /// waiting on a price expire. Returns the orders that expired.
+    pub fn expire_day_orders(&self, symbol: &str) -> Vec<Order> {
+        let now = self.clock.now();
+        let mut state = self.state.lock().unwrap();
+        state
+            .orders
+            .values_mut()
+            .filter(|o| {
+                o.symbol == symbol
+                    && o.time_in_force == TimeInForce::Day
+                    && o.order_type != OrderType::Market
+                    && o.status.is_active()
+            })
+            .map(|o| {
+                o.status = OrderStatus::Expired;
+                o.updated_at = now;
+                o.clone()
+            })
+            .collect()
+    }
+
     /// Update all position prices.
     pub fn update_prices(&self, prices: &HashMap<String, Decimal>) {
-        let mut portfolio = self.portfolio.lock().unwrap();
-        portfolio.update_prices(prices);
+        self.state.lock().unwrap().portfolio.update_prices(prices);
+    }
+
+    /// Current account equity, without cloning the portfolio.
+    pub fn equity(&self) -> Decimal {
+        self.state.lock().unwrap().portfolio.equity
     }
 
     /// Get a snapshot of the portfolio.
     pub fn portfolio_snapshot(&self) -> Portfolio {
-        self.portfolio.lock().unwrap().clone()
+        self.state.lock().unwrap().portfolio.clone()
+    }
+
+    /// Replace the portfolio and orders, e.g. when resuming a checkpoint.
+    pub fn restore(&self, portfolio: Portfolio, orders: Vec<Order>) {
+        *self.state.lock().unwrap() = BrokerState {
+            portfolio,
+            orders: orders.into_iter().map(|o| (o.id, o)).collect(),
+            acknowledged: HashMap::new(),
+            quotes: HashMap::new(),
+        };
     }
 }
 
+/// Cancel what is left of an order.
+fn cancel(order: &mut Order, now: DateTime<Utc>) {
+    order.status = OrderStatus::Canceled;
+    order.canceled_at = Some(now);
+}
+
 #[async_trait]
 impl Broker for PaperBroker {
     async fn get_account(&self) -> Result<Portfolio, BrokerError> {
-        Ok(self.portfolio.lock().unwrap().clone())
+        Ok(self.state.lock().unwrap().portfolio.clone())
     }
 
     async fn submit_order(&self, request: OrderRequest) -> Result<Order, BrokerError> {
         // Note: buying power check for market orders happens in execute_at_price
         // since we don't know the fill price at submission time.
 
-        let order = Order::from_request(&request);
+        let mut order = Order::from_request(&request);
         let order_id = order.id;
+        let now = self.clock.now();
+        order.created_at = now;
+        order.updated_at = now;
 
-        let mut orders = self.orders.lock().unwrap();
-        orders.insert(order_id, order.clone());
+        if !self.latency.submit_delay.is_zero() {
+            tokio::time::sleep(self.latency.submit_delay).await;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        state.orders.insert(order_id, order.clone());
+        if !self.latency.is_zero() {
+            state.acknowledged.insert(order_id, Instant::now());
+        }
 
         Ok(order)
     }
@@ -176,11 +411,22 @@ impl Broker for PaperBroker {
         let uuid = Uuid::parse_str(order_id)
             .map_err(|_| BrokerError::OrderNotFound(order_id.to_string()))?;
 
-        let mut orders = self.orders.lock().unwrap();
-        let order = orders
+        // The order can still fill while the cancel is on its way
+        if !self.latency.cancel_delay.is_zero() {
+            tokio::time::sleep(self.latency.cancel_delay).await;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        let order = state
+            .orders
             .get_mut(&uuid)
             .ok_or_else(|| BrokerError::OrderNotFound(order_id.to_string()))?;
 
+        if order.status == OrderStatus::Filled {
+            return Err(BrokerError::OrderRejected(
+                "Order filled before the cancel arrived".to_string(),
+            ));
+        }
         if order.status.is_terminal() {
             return Err(BrokerError::OrderRejected(
                 "Order already terminal".to_string(),
@@ -188,7 +434,7 @@ impl Broker for PaperBroker {
         }
 
         order.status = OrderStatus::Canceled;
-        order.canceled_at = Some(Utc::now());
+        order.canceled_at = Some(self.clock.now());
 
         Ok(())
     }
@@ -197,16 +443,18 @@ impl Broker for PaperBroker {
         let uuid = Uuid::parse_str(order_id)
             .map_err(|_| BrokerError::OrderNotFound(order_id.to_string()))?;
 
-        let orders = self.orders.lock().unwrap();
-        orders
+        let state = self.state.lock().unwrap();
+        state
+            .orders
             .get(&uuid)
             .cloned()
             .ok_or_else(|| BrokerError::OrderNotFound(order_id.to_string()))
     }
 
     async fn get_open_orders(&self) -> Result<Vec<Order>, BrokerError> {
-        let orders = self.orders.lock().unwrap();
-        Ok(orders
+        let state = self.state.lock().unwrap();
+        Ok(state
+            .orders
             .values()
             .filter(|o| o.status.is_active())
             .cloned()
@@ -214,19 +462,20 @@ impl Broker for PaperBroker {
     }
 
     async fn get_positions(&self) -> Result<Vec<Position>, BrokerError> {
-        let portfolio = self.portfolio.lock().unwrap();
-        Ok(portfolio.positions.values().cloned().collect())
+        let state = self.state.lock().unwrap();
+        Ok(state.portfolio.positions.values().cloned().collect())
     }
 
     async fn get_position(&self, symbol: &str) -> Result<Option<Position>, BrokerError> {
-        let portfolio = self.portfolio.lock().unwrap();
-        Ok(portfolio.positions.get(symbol).cloned())
+        let state = self.state.lock().unwrap();
+        Ok(state.portfolio.positions.get(symbol).cloned())
     }
 
     async fn close_position(&self, symbol: &str) -> Result<Order, BrokerError> {
         let (side, quantity) = {
-            let portfolio = self.portfolio.lock().unwrap();
-            let position = portfolio
+            let state = self.state.lock().unwrap();
+            let position = state
+                .portfolio
                 .positions
                 .get(symbol)
                 .ok_or_else(|| BrokerError::PositionNotFound(symbol.to_string()))?;
@@ -245,8 +494,8 @@ impl Broker for PaperBroker {
 
     async fn close_all_positions(&self) -> Result<Vec<Order>, BrokerError> {
         let symbols: Vec<String> = {
-            let portfolio = self.portfolio.lock().unwrap();
-            portfolio.positions.keys().cloned().collect()
+            let state = self.state.lock().unwrap();
+            state.portfolio.positions.keys().cloned().collect()
         };
 
         let mut orders = Vec::new();
@@ -259,8 +508,9 @@ impl Broker for PaperBroker {
 
     async fn cancel_all_orders(&self) -> Result<(), BrokerError> {
         let order_ids: Vec<String> = {
-            let orders = self.orders.lock().unwrap();
-            orders
+            let state = self.state.lock().unwrap();
+            state
+                .orders
                 .values()
                 .filter(|o| o.status.is_active())
                 .map(|o| o.id.to_string())
@@ -278,6 +528,21 @@ impl Broker for PaperBroker {
         Ok(true) // Paper trading is always open
     }
 
+    async fn snapshot(&self) -> Result<BrokerSnapshot, BrokerError> {
+        let state = self.state.lock().unwrap();
+        let open_orders = state
+            .orders
+            .values()
+            .filter(|o| o.status.is_active())
+            .cloned()
+            .collect();
+        Ok(BrokerSnapshot::new(
+            state.portfolio.clone(),
+            open_orders,
+            true,
+        ))
+    }
+
     fn name(&self) -> &str {
         "Paper Broker"
     }
@@ -286,6 +551,7 @@ impl Broker for PaperBroker {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use trading_core::types::{AssetClass, OptionContract, OptionRight};
 
     #[tokio::test]
     async fn test_paper_broker_buy() {
@@ -323,4 +589,228 @@ mod tests {
         let pos = broker.get_position("AAPL").await.unwrap();
         assert!(pos.is_none());
     }
+
+    #[tokio::test]
+    async fn test_contract_multiplier() {
+        let es = Instrument::new("ES", AssetClass::Future).with_multiplier(dec!(50));
+        let broker = PaperBroker::new(dec!(1000000))
+            .with_slippage(Decimal::ZERO)
+            .with_instruments(HashMap::from([("ES".to_string(), es)]));
+
+        let order = broker
+            .submit_order(OrderRequest::market("ES", Side::Buy, dec!(2)))
+            .await
+            .unwrap();
+        broker.execute_at_price(order.id, dec!(5000)).unwrap();
+        let account = broker.get_account().await.unwrap();
+        assert_eq!(account.cash, dec!(500000));
+        assert_eq!(account.positions["ES"].cost_basis, dec!(500000));
+
+        // Ten points on two contracts
+        let close = broker.close_position("ES").await.unwrap();
+        broker.execute_at_price(close.id, dec!(5010)).unwrap();
+        let account = broker.get_account().await.unwrap();
+        assert_eq!(account.cash, dec!(1001000));
+    }
+
+    #[tokio::test]
+    async fn test_option_orders_use_contract_multiplier() {
+        let expiry = chrono::NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let put = OptionContract::new("SPY", dec!(480), expiry, OptionRight::Put);
+        let broker = PaperBroker::new(dec!(100000)).with_slippage(Decimal::ZERO);
+
+        // Write one put for 2.50 and buy it back at 1.00
+        let order = broker
+            .submit_order(OrderRequest::market("", Side::Sell, dec!(1)).with_contract(put.clone()))
+            .await
+            .unwrap();
+        broker.execute_at_price(order.id, dec!(2.5)).unwrap();
+        let account = broker.get_account().await.unwrap();
+        assert_eq!(account.cash, dec!(100250));
+        let position = &account.positions["SPY240315P00480000"];
+        assert_eq!(position.contract.as_ref(), Some(&put));
+        assert_eq!(position.multiplier, dec!(100));
+
+        let order = broker
+            .submit_order(OrderRequest::market("", Side::Buy, dec!(1)).with_contract(put))
+            .await
+            .unwrap();
+        broker.execute_at_price(order.id, dec!(1)).unwrap();
+        let account = broker.get_account().await.unwrap();
+        assert_eq!(account.cash, dec!(100150));
+        assert!(account.positions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_limit_fill_never_worse_than_limit() {
+        let broker = PaperBroker::new(dec!(100000)).with_slippage(dec!(1));
+        let order = broker
+            .submit_order(OrderRequest::limit("AAPL", Side::Buy, dec!(10), dec!(150)))
+            .await
+            .unwrap();
+
+        let resting = broker.execute_at_price(order.id, dec!(151)).unwrap();
+        assert_eq!(resting.status, OrderStatus::Pending);
+
+        // Slippage would take the fill above the limit
+        let filled = broker.execute_at_price(order.id, dec!(150)).unwrap();
+        assert_eq!(filled.filled_avg_price, Some(dec!(150)));
+    }
+
+    #[tokio::test]
+    async fn test_quote_fills_cross_the_spread() {
+        let broker = PaperBroker::new(dec!(100000)).with_quote_fills();
+        broker.update_quote("AAPL", QuoteTick::new(0, 99.98, 100.02));
+
+        let buy = broker
+            .submit_order(OrderRequest::market("AAPL", Side::Buy, dec!(10)))
+            .await
+            .unwrap();
+        let buy = broker.execute_at_price(buy.id, dec!(100)).unwrap();
+        assert_eq!(buy.filled_avg_price, Some(dec!(100.02)));
+
+        // The ask is above the limit even though the last price is not
+        let limit = broker
+            .submit_order(OrderRequest::limit("AAPL", Side::Buy, dec!(10), dec!(100)))
+            .await
+            .unwrap();
+        let limit = broker.execute_at_price(limit.id, dec!(100)).unwrap();
+        assert_eq!(limit.filled_quantity, Decimal::ZERO);
+
+        let sell = broker.close_position("AAPL").await.unwrap();
+        let sell = broker.execute_at_price(sell.id, dec!(100)).unwrap();
+        assert_eq!(sell.filled_avg_price, Some(dec!(99.98)));
+
+        // No quote: the price given plus slippage, as without quote fills
+        let other = broker
+            .submit_order(OrderRequest::market("MSFT", Side::Buy, dec!(1)))
+            .await
+            .unwrap();
+        let other = broker.execute_at_price(other.id, dec!(400)).unwrap();
+        assert_eq!(other.filled_avg_price, Some(dec!(400.2)));
+    }
+
+    #[tokio::test]
+    async fn test_partial_fills() {
+        let broker = PaperBroker::new(dec!(100000)).with_slippage(dec!(0));
+        let order = broker
+            .submit_order(OrderRequest::market("AAPL", Side::Buy, dec!(100)))
+            .await
+            .unwrap();
+
+        let partial = broker
+            .execute_partial(order.id, dec!(150), dec!(40))
+            .unwrap();
+        assert_eq!(partial.status, OrderStatus::PartiallyFilled);
+        assert_eq!(broker.get_open_orders().await.unwrap().len(), 1);
+        let position = broker.get_position("AAPL").await.unwrap().unwrap();
+        assert_eq!(position.quantity, dec!(40));
+
+        // The rest fills at the next price
+        let filled = broker.execute_at_price(order.id, dec!(160)).unwrap();
+        assert_eq!(filled.status, OrderStatus::Filled);
+        assert_eq!(filled.filled_quantity, dec!(100));
+        assert_eq!(filled.filled_avg_price, Some(dec!(156)));
+        let position = broker.get_position("AAPL").await.unwrap().unwrap();
+        assert_eq!(position.quantity, dec!(100));
+    }
+
+    #[tokio::test]
+    async fn test_time_in_force() {
+        let broker = PaperBroker::new(dec!(100000)).with_slippage(dec!(0));
+        let submit = |tif| {
+            broker.submit_order(
+                OrderRequest::limit("AAPL", Side::Buy, dec!(100), dec!(150))
+                    .with_time_in_force(tif),
+            )
+        };
+
+        // Immediate-or-cancel: no second chance at the limit, and whatever
+        // doesn't fill at once is canceled
+        let ioc = submit(TimeInForce::IOC).await.unwrap();
+        let missed = broker.execute_at_price(ioc.id, dec!(151)).unwrap();
+        assert_eq!(missed.status, OrderStatus::Canceled);
+        assert_eq!(missed.filled_quantity, dec!(0));
+        let ioc = submit(TimeInForce::IOC).await.unwrap();
+        let partial = broker.execute_partial(ioc.id, dec!(150), dec!(40)).unwrap();
+        assert_eq!(partial.status, OrderStatus::Canceled);
+        assert_eq!(partial.filled_quantity, dec!(40));
+
+        // Fill-or-kill: all or nothing
+        let fok = submit(TimeInForce::FOK).await.unwrap();
+        let killed = broker.execute_partial(fok.id, dec!(150), dec!(40)).unwrap();
+        assert_eq!(killed.status, OrderStatus::Canceled);
+        assert_eq!(killed.filled_quantity, dec!(0));
+        let fok = submit(TimeInForce::FOK).await.unwrap();
+        let filled = broker.execute_at_price(fok.id, dec!(150)).unwrap();
+        assert_eq!(filled.status, OrderStatus::Filled);
+        let position = broker.get_position("AAPL").await.unwrap().unwrap();
+        assert_eq!(position.quantity, dec!(140));
+
+        // Day orders expire with the session; GTC orders keep working
+        let day = submit(TimeInForce::Day).await.unwrap();
+        let gtc = submit(TimeInForce::GTC).await.unwrap();
+        let expired = broker.expire_day_orders("AAPL");
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].id, day.id);
+        assert_eq!(expired[0].status, OrderStatus::Expired);
+        let open = broker.get_open_orders().await.unwrap();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].id, gtc.id);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_is_consistent() {
+        let broker = PaperBroker::new(dec!(100000));
+
+        let buy = broker
+            .submit_order(OrderRequest::market("AAPL", Side::Buy, dec!(100)))
+            .await
+            .unwrap();
+        broker.execute_at_price(buy.id, dec!(150)).unwrap();
+        let working = broker
+            .submit_order(OrderRequest::limit("MSFT", Side::Buy, dec!(10), dec!(300)))
+            .await
+            .unwrap();
+
+        let snapshot = broker.snapshot().await.unwrap();
+        assert!(snapshot.atomic);
+        assert_eq!(snapshot.open_orders.len(), 1);
+        assert_eq!(snapshot.open_orders[0].id, working.id);
+        assert_eq!(snapshot.position("AAPL").unwrap().quantity, dec!(100));
+        assert_eq!(snapshot.cash(), broker.get_account().await.unwrap().cash);
+    }
+
+    #[tokio::test]
+    async fn test_latency_exposes_cancel_after_fill() {
+        let latency = PaperLatency {
+            submit_delay: Duration::from_millis(5),
+            fill_delay: Duration::from_millis(30),
+            cancel_delay: Duration::from_millis(30),
+        };
+        let broker = std::sync::Arc::new(PaperBroker::new(dec!(100000)).with_latency(latency));
+
+        let order = broker
+            .submit_order(OrderRequest::market("AAPL", Side::Buy, dec!(10)))
+            .await
+            .unwrap();
+        // Too early to fill
+        let early = broker.execute_at_price(order.id, dec!(150)).unwrap();
+        assert_eq!(early.status, OrderStatus::Pending);
+
+        tokio::time::sleep(latency.fill_delay).await;
+        let cancel = tokio::spawn({
+            let broker = broker.clone();
+            let id = order.id.to_string();
+            async move { broker.cancel_order(&id).await }
+        });
+        // The fill lands while the cancel is in flight
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        let filled = broker.execute_at_price(order.id, dec!(150)).unwrap();
+        assert_eq!(filled.status, OrderStatus::Filled);
+
+        let err = cancel.await.unwrap().unwrap_err();
+        assert!(err.is_rejection());
+        assert!(err.to_string().contains("filled before the cancel"));
+    }
 }
@@ -1,8 +1,17 @@
 //! Configuration management.
 
+mod portfolio;
 mod settings;
+mod validate;
+mod watch;
 
-pub use settings::{AlpacaConfig, AppConfig, LoggingConfig, RiskSettings};
+pub use portfolio::{load_portfolio_config, PortfolioConfig, StrategyInstance};
+pub use settings::{
+    AlpacaConfig, AppConfig, DashboardSettings, JournalSettings, LoggingConfig, RiskSettings,
+    WebSettings,
+};
+pub use validate::{validate_config, validate_config_str, ConfigIssue, ConfigReport, Severity};
+pub use watch::ConfigWatcher;
 
 use config::{Config, ConfigError, Environment, File};
 use std::path::Path;
@@ -18,5 +27,43 @@ pub fn load_config(path: &Path) -> Result<AppConfig, ConfigError> {
         )
         .build()?;
 
-    config.try_deserialize()
+    let mut app: AppConfig = config.try_deserialize()?;
+    // Keys come back lower-cased; symbols are upper case everywhere else
+    app.instruments = app
+        .instruments
+        .into_iter()
+        .map(|(symbol, mut instrument)| {
+            instrument.symbol = symbol.to_uppercase();
+            (instrument.symbol.clone(), instrument)
+        })
+        .collect();
+    Ok(app)
+}
+
+/// Load strategy settings from a TOML file, or JSON for `.json` files.
+///
+/// Keys are kept as written, unlike [`load_config`].
+pub fn load_strategy_config(path: &Path) -> Result<serde_json::Value, ConfigError> {
+    let text = std::fs::read_to_string(path).map_err(|e| ConfigError::Foreign(Box::new(e)))?;
+    if path.extension().is_some_and(|ext| ext == "json") {
+        serde_json::from_str(&text).map_err(|e| ConfigError::Foreign(Box::new(e)))
+    } else {
+        toml::from_str(&text).map_err(|e| ConfigError::Foreign(Box::new(e)))
+    }
+}
+
+/// Save strategy settings as TOML, or JSON for `.json` files, in the form
+/// [`load_strategy_config`] reads back. TOML has no null, so unset
+/// settings are left out.
+pub fn save_strategy_config(path: &Path, settings: &serde_json::Value) -> Result<(), ConfigError> {
+    let text = if path.extension().is_some_and(|ext| ext == "json") {
+        serde_json::to_string_pretty(settings).map_err(|e| ConfigError::Foreign(Box::new(e)))?
+    } else {
+        let mut settings = settings.clone();
+        if let Some(table) = settings.as_object_mut() {
+            table.retain(|_, value| !value.is_null());
+        }
+        toml::to_string(&settings).map_err(|e| ConfigError::Foreign(Box::new(e)))?
+    };
+    std::fs::write(path, text).map_err(|e| ConfigError::Foreign(Box::new(e)))
 }
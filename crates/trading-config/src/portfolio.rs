@@ -0,0 +1,142 @@
+//! Portfolio files: several strategy instances run side by side in one
+//! paper or live session.
+//!
+//! ```toml
+//! [[strategy]]
+//! name = "trend"
+//! strategy = "ma_crossover"
+//! symbols = ["AAPL", "MSFT"]
+//! allocation = 0.6
+//! params = { fast_period = 10, slow_period = 30 }
+//! ```
+
+use config::ConfigError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use trading_risk::{AllocationMethod, AllocatorConfig};
+
+/// One strategy instance of a portfolio.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrategyInstance {
+    /// Name the instance's signals are tagged with (defaults to the
+    /// strategy)
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Registry name of the strategy
+    pub strategy: String,
+    /// Settings overriding the strategy's default configuration
+    #[serde(default)]
+    pub params: serde_json::Value,
+    /// Symbols the instance trades
+    pub symbols: Vec<String>,
+    /// Share of capital, from 0 to 1
+    #[serde(default)]
+    pub allocation: Option<f64>,
+}
+
+impl StrategyInstance {
+    /// Name the instance's signals are tagged with.
+    pub fn label(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.strategy)
+    }
+}
+
+/// Strategy instances run together against one broker connection.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PortfolioConfig {
+    /// Instances, as `[[strategy]]` tables
+    #[serde(default, rename = "strategy")]
+    pub strategies: Vec<StrategyInstance>,
+}
+
+impl PortfolioConfig {
+    /// Check the instances: each trades something, names are unique, and
+    /// allocations are given for all instances or none and add up to at
+    /// most 1.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.strategies.is_empty() {
+            return Err("A portfolio needs at least one [[strategy]]".to_string());
+        }
+        for (i, instance) in self.strategies.iter().enumerate() {
+            if instance.symbols.is_empty() {
+                return Err(format!("{} has no symbols", instance.label()));
+            }
+            if self.strategies[..i]
+                .iter()
+                .any(|other| other.label() == instance.label())
+            {
+                return Err(format!(
+                    "{} appears more than once; give each instance a distinct name",
+                    instance.label()
+                ));
+            }
+            if let Some(allocation) = instance.allocation {
+                if !(0.0..=1.0).contains(&allocation) {
+                    return Err(format!(
+                        "{} allocation must be between 0 and 1, got {}",
+                        instance.label(),
+                        allocation
+                    ));
+                }
+            }
+        }
+
+        let allocated = self
+            .strategies
+            .iter()
+            .filter(|i| i.allocation.is_some())
+            .count();
+        if allocated > 0 && allocated < self.strategies.len() {
+            return Err("Give every instance an allocation, or none".to_string());
+        }
+        let total: f64 = self.strategies.iter().filter_map(|i| i.allocation).sum();
+        if total > 1.0 + 1e-9 {
+            return Err(format!("Allocations add up to {}, more than 1", total));
+        }
+        Ok(())
+    }
+
+    /// Every symbol traded, in the order first listed.
+    pub fn symbols(&self) -> Vec<String> {
+        let mut symbols: Vec<String> = Vec::new();
+        for symbol in self.strategies.iter().flat_map(|i| &i.symbols) {
+            if !symbols.contains(symbol) {
+                symbols.push(symbol.clone());
+            }
+        }
+        symbols
+    }
+
+    /// Fixed allocation of the instances' shares, if they have them.
+    pub fn allocation(&self) -> Option<AllocatorConfig> {
+        let budgets: HashMap<String, f64> = self
+            .strategies
+            .iter()
+            .filter_map(|i| Some((i.label().to_string(), i.allocation?)))
+            .collect();
+        (!budgets.is_empty()).then(|| AllocatorConfig {
+            method: AllocationMethod::Fixed,
+            budgets,
+            ..Default::default()
+        })
+    }
+}
+
+/// Load and check a portfolio file (TOML, or JSON for `.json` files).
+/// Symbols are upper-cased.
+pub fn load_portfolio_config(path: &Path) -> Result<PortfolioConfig, ConfigError> {
+    let text = std::fs::read_to_string(path).map_err(|e| ConfigError::Foreign(Box::new(e)))?;
+    let mut portfolio: PortfolioConfig = if path.extension().is_some_and(|ext| ext == "json") {
+        serde_json::from_str(&text).map_err(|e| ConfigError::Foreign(Box::new(e)))?
+    } else {
+        toml::from_str(&text).map_err(|e| ConfigError::Foreign(Box::new(e)))?
+    };
+    for instance in &mut portfolio.strategies {
+        for symbol in &mut instance.symbols {
+            *symbol = symbol.to_uppercase();
+        }
+    }
+    portfolio.validate().map_err(ConfigError::Message)?;
+    Ok(portfolio)
+}
@@ -2,7 +2,14 @@
 
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use trading_risk::{PositionSizingMethod, StopLossMethod};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use trading_core::types::Instrument;
+use trading_monitor::{default_layout, NotificationConfig, Panel, ThemeConfig};
+use trading_risk::{
+    AllocatorConfig, CircuitBreakerConfig, PositionSizingMethod, RiskConfig, RiskPreset,
+    SessionConfig, StopLossMethod, SupervisorConfig, TakeProfitMethod, TimeExit,
+};
 
 /// Main application configuration.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -17,6 +24,26 @@ pub struct AppConfig {
     pub risk: RiskSettings,
     #[serde(default)]
     pub backtest: BacktestSettings,
+    #[serde(default)]
+    pub supervisor: SupervisorConfig,
+    #[serde(default)]
+    pub circuit_breaker: CircuitBreakerConfig,
+    /// End-of-session cleanup in paper and live trading
+    #[serde(default)]
+    pub session: SessionConfig,
+    #[serde(default)]
+    pub dashboard: DashboardSettings,
+    #[serde(default)]
+    pub web: WebSettings,
+    /// Slack, Telegram, email and webhook notifications
+    #[serde(default)]
+    pub notifications: NotificationConfig,
+    #[serde(default)]
+    pub journal: JournalSettings,
+    /// Instruments by symbol: asset class, contract multiplier, and the
+    /// lot and tick rules orders are rounded to
+    #[serde(default)]
+    pub instruments: HashMap<String, Instrument>,
 }
 
 /// General app settings.
@@ -74,26 +101,119 @@ impl Default for AlpacaConfig {
 }
 
 /// Risk management settings.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// `preset` picks the starting point; any other field given overrides
+/// that part of it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct RiskSettings {
-    pub max_position_pct: Decimal,
-    pub max_exposure_pct: Decimal,
-    pub daily_loss_limit_pct: Decimal,
-    pub max_drawdown_pct: Decimal,
-    pub position_sizing: PositionSizingMethod,
-    pub stop_loss: StopLossMethod,
+    /// Named preset to start from (moderate when unset)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preset: Option<RiskPreset>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_position_pct: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_exposure_pct: Option<Decimal>,
+    /// Maximum net exposure (longs minus shorts), in percent of equity
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_net_exposure_pct: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub daily_loss_limit_pct: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_drawdown_pct: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position_sizing: Option<PositionSizingMethod>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_loss: Option<StopLossMethod>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub take_profit: Option<TakeProfitMethod>,
+    /// Maximum combined exposure to one sector, in percent of equity
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_sector_pct: Option<Decimal>,
+    /// Symbols by sector, e.g. `technology = ["AAPL", "MSFT"]`
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub sectors: HashMap<String, Vec<String>>,
+    /// Maximum combined exposure to one asset class, in percent of equity
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_asset_class_pct: Option<Decimal>,
+    /// Symbols by asset class, e.g. `crypto = ["BTCUSD", "ETHUSD"]`
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub asset_classes: HashMap<String, Vec<String>>,
+    /// Maximum combined exposure to symbols correlated with a new position,
+    /// in percent of equity
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_correlated_pct: Option<Decimal>,
+    /// Return correlation at which holdings count as correlated
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlation_threshold: Option<f64>,
+    /// Bars of returns correlations are measured over
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlation_window: Option<usize>,
+    /// Percentage of the normal size taken in a high-volatility regime
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub high_volatility_size_pct: Option<Decimal>,
+    /// Force-close positions after a holding period or at a time of day
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_exit: Option<TimeExit>,
+    /// Divide capital across the strategies of a multi-strategy portfolio
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allocation: Option<AllocatorConfig>,
 }
 
-impl Default for RiskSettings {
-    fn default() -> Self {
-        use rust_decimal_macros::dec;
-        Self {
-            max_position_pct: dec!(10),
-            max_exposure_pct: dec!(80),
-            daily_loss_limit_pct: dec!(3),
-            max_drawdown_pct: dec!(20),
-            position_sizing: PositionSizingMethod::PercentEquity { percent: dec!(2) },
-            stop_loss: StopLossMethod::FixedPercent { percent: dec!(2) },
+impl RiskSettings {
+    /// Build the risk manager configuration from these settings.
+    pub fn to_risk_config(&self) -> RiskConfig {
+        let mut config = self.preset.unwrap_or_default().config();
+        if let Some(method) = &self.position_sizing {
+            config.position_sizing = method.clone();
+        }
+        if let Some(method) = &self.stop_loss {
+            config.stop_loss = method.clone();
+        }
+        if let Some(method) = &self.take_profit {
+            config.take_profit = Some(method.clone());
+        }
+        if let Some(pct) = self.high_volatility_size_pct {
+            config.high_volatility_size_pct = pct;
+        }
+        if let Some(time_exit) = &self.time_exit {
+            config.time_exit = time_exit.clone();
+        }
+        config.allocation = self.allocation.clone();
+        let limits = &mut config.limits;
+        limits.max_position_pct = self.max_position_pct.unwrap_or(limits.max_position_pct);
+        limits.max_exposure_pct = self.max_exposure_pct.unwrap_or(limits.max_exposure_pct);
+        limits.max_net_exposure_pct = self.max_net_exposure_pct.or(limits.max_net_exposure_pct);
+        limits.daily_loss_limit_pct = self
+            .daily_loss_limit_pct
+            .unwrap_or(limits.daily_loss_limit_pct);
+        limits.max_drawdown_pct = self.max_drawdown_pct.unwrap_or(limits.max_drawdown_pct);
+        limits.max_sector_pct = self.max_sector_pct.or(limits.max_sector_pct);
+        limits.sectors = self.sectors.clone();
+        limits.max_asset_class_pct = self.max_asset_class_pct.or(limits.max_asset_class_pct);
+        limits.asset_classes = self.asset_classes.clone();
+        limits.max_correlated_pct = self.max_correlated_pct.or(limits.max_correlated_pct);
+        limits.correlation_threshold = self
+            .correlation_threshold
+            .unwrap_or(limits.correlation_threshold);
+        limits.correlation_window = self.correlation_window.unwrap_or(limits.correlation_window);
+        config
+    }
+
+    /// Resolve the effective risk configuration; a preset chosen on the
+    /// command line replaces the file's settings, keeping only the sector
+    /// and asset class maps, time exits and strategy allocation.
+    pub fn resolve(&self, preset: Option<RiskPreset>) -> RiskConfig {
+        match preset {
+            Some(preset) => {
+                let mut config = preset.config();
+                config.limits.sectors = self.sectors.clone();
+                config.limits.asset_classes = self.asset_classes.clone();
+                config.time_exit = self.time_exit.clone().unwrap_or_default();
+                config.allocation = self.allocation.clone();
+                config
+            }
+            None => self.to_risk_config(),
         }
     }
 }
@@ -116,3 +236,91 @@ impl Default for BacktestSettings {
         }
     }
 }
+
+/// TUI dashboard settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DashboardSettings {
+    /// Extra symbols quoted alongside the traded ones
+    #[serde(default)]
+    pub watchlist: Vec<String>,
+    /// Redraw interval in milliseconds
+    #[serde(default = "default_refresh_ms")]
+    pub refresh_ms: u64,
+    /// Color preset and overrides
+    #[serde(default)]
+    pub theme: ThemeConfig,
+    /// Panel rows from top to bottom; unlisted panels are hidden
+    #[serde(default = "default_layout")]
+    pub layout: Vec<Vec<Panel>>,
+}
+
+fn default_refresh_ms() -> u64 {
+    250
+}
+
+impl Default for DashboardSettings {
+    fn default() -> Self {
+        Self {
+            watchlist: Vec::new(),
+            refresh_ms: default_refresh_ms(),
+            theme: ThemeConfig::default(),
+            layout: default_layout(),
+        }
+    }
+}
+
+/// Trade journal settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalSettings {
+    /// Record paper and live sessions
+    #[serde(default = "default_journal_enabled")]
+    pub enabled: bool,
+    /// JSON-lines file entries are appended to
+    #[serde(default = "default_journal_path")]
+    pub path: PathBuf,
+}
+
+fn default_journal_enabled() -> bool {
+    true
+}
+
+fn default_journal_path() -> PathBuf {
+    PathBuf::from("data/journal.jsonl")
+}
+
+impl Default for JournalSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_journal_enabled(),
+            path: default_journal_path(),
+        }
+    }
+}
+
+/// Web dashboard settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebSettings {
+    /// Address the HTTP server listens on
+    #[serde(default = "default_web_bind")]
+    pub bind: String,
+    /// Websocket push interval in milliseconds
+    #[serde(default = "default_web_refresh_ms")]
+    pub refresh_ms: u64,
+}
+
+fn default_web_bind() -> String {
+    "127.0.0.1:8080".to_string()
+}
+
+fn default_web_refresh_ms() -> u64 {
+    1000
+}
+
+impl Default for WebSettings {
+    fn default() -> Self {
+        Self {
+            bind: default_web_bind(),
+            refresh_ms: default_web_refresh_ms(),
+        }
+    }
+}
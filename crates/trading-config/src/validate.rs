@@ -0,0 +1,612 @@
+//! Config file checks with line- and field-level messages.
+//!
+//! Each section is deserialized on its own, so one mistake doesn't hide the
+//! rest, and keys the settings don't know are reported rather than silently
+//! ignored. Values that parse but can't work (a 150% position limit, a bad
+//! bind address) are caught too.
+
+use rust_decimal::Decimal;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use trading_core::types::Instrument;
+use trading_monitor::{NotificationConfig, Notifications, Theme};
+use trading_risk::{CircuitBreakerConfig, SessionConfig, SupervisorConfig};
+
+use crate::settings::{
+    AlpacaConfig, AppConfig, BacktestSettings, DashboardSettings, JournalSettings, LoggingConfig,
+    RiskSettings, WebSettings,
+};
+
+/// How serious a config issue is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// The config can't be used as written
+    Error,
+    /// The config loads, but probably not as intended
+    Warning,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// A problem found in a config file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigIssue {
+    pub severity: Severity,
+    /// Line of the file (1-based), where it could be found
+    pub line: Option<usize>,
+    /// Dotted path of the setting, e.g. `risk.max_position_pct`
+    pub field: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: ", self.severity)?;
+        if let Some(line) = self.line {
+            write!(f, "line {}, ", line)?;
+        }
+        if !self.field.is_empty() {
+            write!(f, "{}: ", self.field)?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Result of checking a config file.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigReport {
+    /// The settings, if every section parsed
+    pub config: Option<AppConfig>,
+    pub issues: Vec<ConfigIssue>,
+}
+
+impl ConfigReport {
+    /// Whether the config can be used: there may be warnings, but no errors.
+    pub fn is_valid(&self) -> bool {
+        self.config.is_some() && !self.issues.iter().any(|i| i.severity == Severity::Error)
+    }
+}
+
+/// Check the config file at `path`.
+pub fn validate_config(path: &Path) -> ConfigReport {
+    match std::fs::read_to_string(path) {
+        Ok(text) => validate_config_str(&text),
+        Err(e) => ConfigReport {
+            config: None,
+            issues: vec![ConfigIssue {
+                severity: Severity::Error,
+                line: None,
+                field: String::new(),
+                message: format!("Failed to read {}: {}", path.display(), e),
+            }],
+        },
+    }
+}
+
+/// Check the text of a config file.
+pub fn validate_config_str(text: &str) -> ConfigReport {
+    let mut checker = Checker {
+        text,
+        issues: Vec::new(),
+    };
+    let table: toml::Table = match toml::from_str(text) {
+        Ok(table) => table,
+        Err(e) => {
+            checker.issues.push(ConfigIssue {
+                severity: Severity::Error,
+                line: e.span().map(|span| line_at(text, span.start)),
+                field: String::new(),
+                message: e.message().to_string(),
+            });
+            return ConfigReport {
+                config: None,
+                issues: checker.issues,
+            };
+        }
+    };
+
+    let mut config = AppConfig::default();
+    let mut parsed = true;
+    for (name, value) in &table {
+        let ok = match name.as_str() {
+            "app" => checker.section(name, value, &mut config.app),
+            "logging" => checker.section(name, value, &mut config.logging),
+            "alpaca" => checker.section(name, value, &mut config.alpaca),
+            "risk" => checker.section(name, value, &mut config.risk),
+            "backtest" => checker.section(name, value, &mut config.backtest),
+            "supervisor" => checker.section(name, value, &mut config.supervisor),
+            "circuit_breaker" => checker.section(name, value, &mut config.circuit_breaker),
+            "session" => checker.section(name, value, &mut config.session),
+            "dashboard" => checker.section(name, value, &mut config.dashboard),
+            "web" => checker.section(name, value, &mut config.web),
+            "notifications" => checker.section(name, value, &mut config.notifications),
+            "journal" => checker.section(name, value, &mut config.journal),
+            "instruments" => checker.section(name, value, &mut config.instruments),
+            _ => {
+                checker.warn(&[name.as_str()], "unknown section, ignored".to_string());
+                true
+            }
+        };
+        parsed &= ok;
+    }
+    if !parsed {
+        checker
+            .issues
+            .sort_by_key(|issue| issue.line.unwrap_or(usize::MAX));
+        return ConfigReport {
+            config: None,
+            issues: checker.issues,
+        };
+    }
+
+    checker.settings(&config);
+    checker
+        .issues
+        .sort_by_key(|issue| issue.line.unwrap_or(usize::MAX));
+    ConfigReport {
+        config: Some(config),
+        issues: checker.issues,
+    }
+}
+
+/// Collects the issues of one file.
+struct Checker<'a> {
+    text: &'a str,
+    issues: Vec<ConfigIssue>,
+}
+
+impl Checker<'_> {
+    fn push(&mut self, severity: Severity, path: &[&str], message: String) {
+        self.issues.push(ConfigIssue {
+            severity,
+            line: find_line(self.text, path),
+            field: path.join("."),
+            message,
+        });
+    }
+
+    fn error(&mut self, path: &[&str], message: String) {
+        self.push(Severity::Error, path, message);
+    }
+
+    fn warn(&mut self, path: &[&str], message: String) {
+        self.push(Severity::Warning, path, message);
+    }
+
+    /// Deserialize a section into `out`, reporting the field that failed
+    /// and any keys the section doesn't know.
+    fn section<T: DeserializeOwned + Serialize>(
+        &mut self,
+        name: &str,
+        value: &toml::Value,
+        out: &mut T,
+    ) -> bool {
+        match serde_path_to_error::deserialize::<_, T>(value.clone()) {
+            Ok(section) => {
+                if let Ok(known) = toml::Value::try_from(&section) {
+                    let mut unknown = Vec::new();
+                    unknown_keys(value, &known, &mut vec![name.to_string()], &mut unknown);
+                    for path in unknown {
+                        let path: Vec<&str> = path.iter().map(String::as_str).collect();
+                        self.warn(&path, "unknown key, ignored".to_string());
+                    }
+                }
+                *out = section;
+                true
+            }
+            Err(e) => {
+                let mut path = vec![name.to_string()];
+                path.extend(field_path(&e.path().to_string()));
+                let path: Vec<&str> = path.iter().map(String::as_str).collect();
+                self.error(&path, e.into_inner().to_string());
+                false
+            }
+        }
+    }
+
+    /// Check values that parse but can't work as intended.
+    fn settings(&mut self, config: &AppConfig) {
+        self.logging(&config.logging);
+        self.alpaca(&config.alpaca);
+        self.risk(&config.risk);
+        self.backtest(&config.backtest);
+        self.supervisor(&config.supervisor);
+        self.circuit_breaker(&config.circuit_breaker);
+        self.session(&config.session);
+        self.dashboard(&config.dashboard);
+        self.web(&config.web);
+        self.notifications(&config.notifications);
+        self.journal(&config.journal);
+        self.instruments(&config.instruments);
+    }
+
+    fn logging(&mut self, logging: &LoggingConfig) {
+        if !["trace", "debug", "info", "warn", "error"].contains(&logging.level.as_str()) {
+            self.error(
+                &["logging", "level"],
+                format!(
+                    "'{}' is not a log level (trace, debug, info, warn or error)",
+                    logging.level
+                ),
+            );
+        }
+        if !["pretty", "json"].contains(&logging.format.as_str()) {
+            self.error(
+                &["logging", "format"],
+                format!("'{}' is not a log format (pretty or json)", logging.format),
+            );
+        }
+    }
+
+    fn alpaca(&mut self, alpaca: &AlpacaConfig) {
+        if !alpaca.base_url.starts_with("https://") && !alpaca.base_url.starts_with("http://") {
+            self.error(
+                &["alpaca", "base_url"],
+                format!("'{}' is not an http(s) URL", alpaca.base_url),
+            );
+        } else if alpaca.paper != alpaca.base_url.contains("paper-api") {
+            self.warn(
+                &["alpaca", "base_url"],
+                format!(
+                    "paper = {} but the URL is the {} endpoint",
+                    alpaca.paper,
+                    if alpaca.paper { "live" } else { "paper" }
+                ),
+            );
+        }
+        for (key, value) in [
+            ("api_key_env", &alpaca.api_key_env),
+            ("api_secret_env", &alpaca.api_secret_env),
+        ] {
+            if value.is_empty() || value.starts_with("YOUR_") {
+                self.warn(
+                    &["alpaca", key],
+                    "is empty or a placeholder; set it before paper or live trading".to_string(),
+                );
+            }
+        }
+    }
+
+    fn risk(&mut self, risk: &RiskSettings) {
+        let hundred = Decimal::ONE_HUNDRED;
+        for (key, value) in [
+            ("max_position_pct", risk.max_position_pct),
+            ("daily_loss_limit_pct", risk.daily_loss_limit_pct),
+            ("max_drawdown_pct", risk.max_drawdown_pct),
+            ("high_volatility_size_pct", risk.high_volatility_size_pct),
+        ] {
+            if let Some(pct) = value.filter(|pct| *pct <= Decimal::ZERO || *pct > hundred) {
+                self.error(&["risk", key], format!("{}% is outside 0 to 100%", pct));
+            }
+        }
+        for (key, value) in [
+            ("max_exposure_pct", risk.max_exposure_pct),
+            ("max_net_exposure_pct", risk.max_net_exposure_pct),
+            ("max_sector_pct", risk.max_sector_pct),
+            ("max_asset_class_pct", risk.max_asset_class_pct),
+            ("max_correlated_pct", risk.max_correlated_pct),
+        ] {
+            if let Some(pct) = value.filter(|pct| *pct <= Decimal::ZERO) {
+                self.error(&["risk", key], format!("{}% must be positive", pct));
+            }
+        }
+        if let Some(threshold) = risk
+            .correlation_threshold
+            .filter(|t| !(0.0..=1.0).contains(t))
+        {
+            self.error(
+                &["risk", "correlation_threshold"],
+                format!("{} is outside 0 to 1", threshold),
+            );
+        }
+        if risk.correlation_window.is_some_and(|w| w < 2) {
+            self.error(
+                &["risk", "correlation_window"],
+                "needs at least 2 bars".to_string(),
+            );
+        }
+        if let Some(allocation) = &risk.allocation {
+            if let Some((name, _)) = allocation.budgets.iter().find(|(_, b)| **b < 0.0) {
+                self.error(
+                    &["risk", "allocation", "budgets", name],
+                    "budgets can't be negative".to_string(),
+                );
+            }
+        }
+
+        let limits = risk.to_risk_config().limits;
+        if limits.max_position_pct > limits.max_exposure_pct && limits.max_position_pct <= hundred {
+            self.warn(
+                &["risk", "max_position_pct"],
+                format!(
+                    "{}% is more than max_exposure_pct ({}%), which caps it",
+                    limits.max_position_pct, limits.max_exposure_pct
+                ),
+            );
+        }
+        if risk.max_sector_pct.is_some() && risk.sectors.is_empty() {
+            self.warn(
+                &["risk", "max_sector_pct"],
+                "has no effect without [risk.sectors]".to_string(),
+            );
+        }
+        if risk.max_asset_class_pct.is_some() && risk.asset_classes.is_empty() {
+            self.warn(
+                &["risk", "max_asset_class_pct"],
+                "has no effect without [risk.asset_classes]".to_string(),
+            );
+        }
+    }
+
+    fn backtest(&mut self, backtest: &BacktestSettings) {
+        if backtest.default_capital <= Decimal::ZERO {
+            self.error(
+                &["backtest", "default_capital"],
+                "must be positive".to_string(),
+            );
+        }
+        for (key, value) in [
+            ("commission", backtest.commission),
+            ("slippage_pct", backtest.slippage_pct),
+        ] {
+            if value < Decimal::ZERO {
+                self.error(&["backtest", key], "can't be negative".to_string());
+            }
+        }
+    }
+
+    fn supervisor(&mut self, supervisor: &SupervisorConfig) {
+        if supervisor.daily_loss_multiple <= Decimal::ZERO {
+            self.error(
+                &["supervisor", "daily_loss_multiple"],
+                "must be positive".to_string(),
+            );
+        }
+    }
+
+    fn circuit_breaker(&mut self, breaker: &CircuitBreakerConfig) {
+        if let Some(pct) = breaker
+            .max_error_rate_pct
+            .filter(|pct| *pct <= 0.0 || *pct > 100.0)
+        {
+            self.error(
+                &["circuit_breaker", "max_error_rate_pct"],
+                format!("{}% is outside 0 to 100%", pct),
+            );
+        }
+        if breaker.max_error_rate_pct.is_some() && breaker.error_window == 0 {
+            self.error(
+                &["circuit_breaker", "error_window"],
+                "must be at least 1 call".to_string(),
+            );
+        }
+        if breaker.max_slippage_pct.is_some_and(|pct| pct <= 0.0) {
+            self.error(
+                &["circuit_breaker", "max_slippage_pct"],
+                "must be positive".to_string(),
+            );
+        }
+    }
+
+    fn session(&mut self, session: &SessionConfig) {
+        if session.flatten && !session.cancel_orders {
+            self.warn(
+                &["session", "flatten"],
+                "working orders are left open while positions are closed; set cancel_orders = true"
+                    .to_string(),
+            );
+        }
+    }
+
+    fn dashboard(&mut self, dashboard: &DashboardSettings) {
+        if dashboard.refresh_ms == 0 {
+            self.error(
+                &["dashboard", "refresh_ms"],
+                "must be at least 1 ms".to_string(),
+            );
+        }
+        if let Err(e) = Theme::from_config(&dashboard.theme) {
+            self.error(&["dashboard", "theme"], e);
+        }
+        if dashboard.layout.iter().all(|row| row.is_empty()) {
+            self.warn(&["dashboard", "layout"], "shows no panels".to_string());
+        }
+    }
+
+    fn web(&mut self, web: &WebSettings) {
+        if web.bind.parse::<std::net::SocketAddr>().is_err() {
+            self.error(
+                &["web", "bind"],
+                format!("'{}' is not an address such as 127.0.0.1:8080", web.bind),
+            );
+        }
+        if web.refresh_ms == 0 {
+            self.error(&["web", "refresh_ms"], "must be at least 1 ms".to_string());
+        }
+    }
+
+    fn notifications(&mut self, notifications: &NotificationConfig) {
+        if let Err(e) = Notifications::from_config(notifications) {
+            self.error(&["notifications"], e.to_string());
+        }
+    }
+
+    fn journal(&mut self, journal: &JournalSettings) {
+        if journal.enabled && journal.path.as_os_str().is_empty() {
+            self.error(&["journal", "path"], "is empty".to_string());
+        }
+    }
+
+    fn instruments(&mut self, instruments: &HashMap<String, Instrument>) {
+        for (symbol, rules) in instruments {
+            for (key, value) in [
+                ("lot_size", rules.lot_size),
+                ("tick_size", rules.tick_size),
+                ("min_notional", rules.min_notional),
+            ] {
+                if value < Decimal::ZERO {
+                    self.error(
+                        &["instruments", symbol, key],
+                        "can't be negative".to_string(),
+                    );
+                }
+            }
+            if rules.multiplier <= Decimal::ZERO {
+                self.error(
+                    &["instruments", symbol, "multiplier"],
+                    "must be positive".to_string(),
+                );
+            }
+        }
+    }
+}
+
+/// Paths of the keys in `written` that are missing from `known`, the same
+/// settings serialized back. Empty tables and arrays are skipped, as
+/// settings that are empty by default aren't written out.
+fn unknown_keys(
+    written: &toml::Value,
+    known: &toml::Value,
+    path: &mut Vec<String>,
+    unknown: &mut Vec<Vec<String>>,
+) {
+    match (written, known) {
+        (toml::Value::Table(written), toml::Value::Table(known)) => {
+            for (key, value) in written {
+                path.push(key.clone());
+                match known.get(key) {
+                    Some(known) => unknown_keys(value, known, path, unknown),
+                    None if is_empty(value) => {}
+                    None => unknown.push(path.clone()),
+                }
+                path.pop();
+            }
+        }
+        (toml::Value::Array(written), toml::Value::Array(known)) => {
+            for (value, known) in written.iter().zip(known) {
+                unknown_keys(value, known, path, unknown);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_empty(value: &toml::Value) -> bool {
+    match value {
+        toml::Value::Table(table) => table.is_empty(),
+        toml::Value::Array(array) => array.is_empty(),
+        _ => false,
+    }
+}
+
+/// Keys of a serde path such as `layout[0][1]` or `position_sizing.percent`.
+fn field_path(path: &str) -> Vec<String> {
+    path.split('.')
+        .map(|part| part.split('[').next().unwrap_or_default().to_string())
+        .filter(|part| !part.is_empty() && part != "?")
+        .collect()
+}
+
+/// Line (1-based) of byte `offset`.
+fn line_at(text: &str, offset: usize) -> usize {
+    text[..offset.min(text.len())].matches('\n').count() + 1
+}
+
+/// Line defining the setting at `path`, or failing that the line of its
+/// closest enclosing key or table.
+fn find_line(text: &str, path: &[&str]) -> Option<usize> {
+    let unquote = |key: &str| key.trim().trim_matches('"').trim_matches('\'').to_string();
+    let keys = |dotted: &str| dotted.split('.').map(unquote).collect::<Vec<_>>();
+    let mut table: Vec<String> = Vec::new();
+    let mut best: Option<(usize, usize)> = None;
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        let full = if let Some(header) = line.strip_prefix('[') {
+            let header = header.trim_start_matches('[');
+            let header = header.split(']').next().unwrap_or_default();
+            table = keys(header);
+            table.clone()
+        } else if let Some((key, _)) = line.split_once('=').filter(|_| !line.starts_with('#')) {
+            let mut full = table.clone();
+            full.extend(keys(key));
+            full
+        } else {
+            continue;
+        };
+        // Keys compare case-insensitively, as the config loader lower-cases
+        // them
+        let matched = full
+            .iter()
+            .zip(path)
+            .take_while(|(a, b)| a.eq_ignore_ascii_case(b))
+            .count();
+        if matched == full.len() && best.map_or(true, |(_, len)| matched > len) {
+            best = Some((number + 1, matched));
+            if matched == path.len() {
+                break;
+            }
+        }
+    }
+    best.map(|(line, _)| line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config_is_clean() {
+        let report = validate_config_str(include_str!("../../../config/default.toml"));
+        assert!(report.is_valid());
+        // Placeholder credentials are the only thing to point out
+        assert!(report
+            .issues
+            .iter()
+            .all(|issue| issue.field.starts_with("alpaca.api_")));
+    }
+
+    #[test]
+    fn test_issues_name_line_and_field() {
+        let text = "\
+[app]
+name = \"test\"
+environment = \"test\"
+
+[risk]
+max_position_pct = \"ten\"
+
+[web]
+bind = \"localhost\"
+refersh_ms = 500
+";
+        let report = validate_config_str(text);
+        assert!(!report.is_valid());
+        let error = &report.issues[0];
+        assert_eq!(error.severity, Severity::Error);
+        assert_eq!(error.line, Some(6));
+        assert_eq!(error.field, "risk.max_position_pct");
+        // The other sections are still checked
+        assert_eq!(report.issues[1].field, "web.refersh_ms");
+        assert_eq!(report.issues.len(), 2);
+
+        // With the type fixed, the values and the typo are checked
+        let report = validate_config_str(&text.replace("\"ten\"", "150.0"));
+        let issues: Vec<(Severity, Option<usize>, &str)> = report
+            .issues
+            .iter()
+            .map(|i| (i.severity, i.line, i.field.as_str()))
+            .collect();
+        assert!(issues.contains(&(Severity::Error, Some(6), "risk.max_position_pct")));
+        assert!(issues.contains(&(Severity::Error, Some(9), "web.bind")));
+        assert!(issues.contains(&(Severity::Warning, Some(10), "web.refersh_ms")));
+    }
+}
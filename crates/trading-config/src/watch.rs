@@ -0,0 +1,77 @@
+//! Change detection for config files, for reloading them while trading.
+
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// What a file looked like when last checked: modification time and size,
+/// or `None` while it is missing.
+type Stamp = Option<(SystemTime, u64)>;
+
+/// Polls a set of files for changes.
+///
+/// Editors often save by replacing a file, so a file that disappears and
+/// comes back counts as changed rather than as an error.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigWatcher {
+    files: Vec<(PathBuf, Stamp)>,
+}
+
+impl ConfigWatcher {
+    /// Watch `paths`, taking their current state as the baseline.
+    pub fn new(paths: impl IntoIterator<Item = PathBuf>) -> Self {
+        Self {
+            files: paths
+                .into_iter()
+                .map(|path| {
+                    let stamp = stamp(&path);
+                    (path, stamp)
+                })
+                .collect(),
+        }
+    }
+
+    /// Files watched.
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.files.iter().map(|(path, _)| path.as_path())
+    }
+
+    /// Files that changed since the last call, or since the watcher was
+    /// created.
+    pub fn changed(&mut self) -> Vec<PathBuf> {
+        let mut changed = Vec::new();
+        for (path, last) in &mut self.files {
+            let now = stamp(path);
+            if now != *last {
+                *last = now;
+                changed.push(path.clone());
+            }
+        }
+        changed
+    }
+}
+
+fn stamp(path: &Path) -> Stamp {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reports_each_change_once() {
+        let path = std::env::temp_dir().join(format!("trading-watch-{}.toml", std::process::id()));
+        std::fs::write(&path, "a = 1\n").unwrap();
+        let mut watcher = ConfigWatcher::new([path.clone()]);
+        assert!(watcher.changed().is_empty());
+
+        // Size differs even where the clock is too coarse to tell
+        std::fs::write(&path, "a = 10\n").unwrap();
+        assert_eq!(watcher.changed(), vec![path.clone()]);
+        assert!(watcher.changed().is_empty());
+
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(watcher.changed(), vec![path]);
+    }
+}
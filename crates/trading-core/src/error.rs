@@ -1,5 +1,10 @@
 //! Error types for the trading system.
+//!
+//! Broker and data errors classify themselves (`is_retryable`,
+//! `retry_after`, `http_status`) so retry and order-management layers can
+//! decide what to do without matching on error text.
 
+use std::time::Duration;
 use thiserror::Error;
 
 /// Top-level trading system error.
@@ -94,10 +99,70 @@ pub enum BrokerError {
     #[error("API error: {0}")]
     ApiError(String),
 
+    #[error("HTTP {status}{}: {message}", code_suffix(.code))]
+    Http {
+        /// HTTP status code
+        status: u16,
+        /// Broker-specific error code from the response body
+        code: Option<String>,
+        message: String,
+    },
+
     #[error("WebSocket error: {0}")]
     WebSocketError(String),
 }
 
+impl BrokerError {
+    /// Whether the same request may succeed if retried.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            BrokerError::Connection(_)
+            | BrokerError::NetworkError(_)
+            | BrokerError::WebSocketError(_)
+            | BrokerError::RateLimited { .. } => true,
+            BrokerError::Http { status, .. } => is_retryable_status(*status),
+            _ => false,
+        }
+    }
+
+    /// How long the broker asked us to wait before retrying.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            BrokerError::RateLimited { retry_after_secs } => {
+                Some(Duration::from_secs(*retry_after_secs))
+            }
+            _ => None,
+        }
+    }
+
+    /// HTTP status of the failed request, if it got a response.
+    pub fn http_status(&self) -> Option<u16> {
+        match self {
+            BrokerError::Http { status, .. } => Some(*status),
+            BrokerError::RateLimited { .. } => Some(429),
+            _ => None,
+        }
+    }
+
+    /// Broker-specific error code, if the broker sent one.
+    pub fn code(&self) -> Option<&str> {
+        match self {
+            BrokerError::Http { code, .. } => code.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Whether the broker refused the order itself, as opposed to the
+    /// request failing; resubmitting it unchanged will fail again.
+    pub fn is_rejection(&self) -> bool {
+        match self {
+            BrokerError::OrderRejected(_) | BrokerError::InsufficientFunds { .. } => true,
+            BrokerError::Http { status, .. } => matches!(status, 403 | 422),
+            _ => false,
+        }
+    }
+}
+
 /// Data source errors.
 #[derive(Error, Debug)]
 pub enum DataError {
@@ -119,10 +184,46 @@ pub enum DataError {
     #[error("Cache error: {0}")]
     CacheError(String),
 
+    #[error("Rate limited: retry after {retry_after_secs} seconds")]
+    RateLimited { retry_after_secs: u64 },
+
+    #[error("HTTP {status}: {message}")]
+    Http { status: u16, message: String },
+
     #[error("Data source error: {0}")]
     Internal(String),
 }
 
+impl DataError {
+    /// Whether the same request may succeed if retried.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            DataError::ConnectionError(_) | DataError::RateLimited { .. } => true,
+            DataError::Http { status, .. } => is_retryable_status(*status),
+            _ => false,
+        }
+    }
+
+    /// How long the source asked us to wait before retrying.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            DataError::RateLimited { retry_after_secs } => {
+                Some(Duration::from_secs(*retry_after_secs))
+            }
+            _ => None,
+        }
+    }
+
+    /// HTTP status of the failed request, if it got a response.
+    pub fn http_status(&self) -> Option<u16> {
+        match self {
+            DataError::Http { status, .. } => Some(*status),
+            DataError::RateLimited { .. } => Some(429),
+            _ => None,
+        }
+    }
+}
+
 /// Indicator calculation errors.
 #[derive(Error, Debug)]
 pub enum IndicatorError {
@@ -136,5 +237,103 @@ pub enum IndicatorError {
     CalculationError(String),
 }
 
+/// Notification delivery errors.
+#[derive(Error, Debug)]
+pub enum NotifyError {
+    #[error("Invalid notifier configuration: {0}")]
+    Configuration(String),
+
+    #[error("Failed to reach notification service: {0}")]
+    Connection(String),
+
+    #[error("Notification service returned HTTP {status}: {message}")]
+    Http { status: u16, message: String },
+}
+
+impl TradingError {
+    /// Whether the failed operation may succeed if retried.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            TradingError::Broker(e) => e.is_retryable(),
+            TradingError::Data(e) => e.is_retryable(),
+            TradingError::Io(e) => matches!(
+                e.kind(),
+                std::io::ErrorKind::Interrupted
+                    | std::io::ErrorKind::TimedOut
+                    | std::io::ErrorKind::WouldBlock
+            ),
+            _ => false,
+        }
+    }
+
+    /// How long to wait before retrying, if the remote side said.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            TradingError::Broker(e) => e.retry_after(),
+            TradingError::Data(e) => e.retry_after(),
+            _ => None,
+        }
+    }
+
+    /// HTTP status of the failed request, if it got a response.
+    pub fn http_status(&self) -> Option<u16> {
+        match self {
+            TradingError::Broker(e) => e.http_status(),
+            TradingError::Data(e) => e.http_status(),
+            _ => None,
+        }
+    }
+}
+
+fn code_suffix(code: &Option<String>) -> String {
+    code.as_ref()
+        .map(|c| format!(" (code {})", c))
+        .unwrap_or_default()
+}
+
+/// Timeouts, rate limits and server-side failures are worth retrying;
+/// other client errors are not.
+fn is_retryable_status(status: u16) -> bool {
+    matches!(status, 408 | 429) || (500..600).contains(&status)
+}
+
 /// Result type alias for trading operations.
 pub type TradingResult<T> = Result<T, TradingError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retryability_classification() {
+        let unavailable = BrokerError::Http {
+            status: 503,
+            code: None,
+            message: "unavailable".into(),
+        };
+        assert!(unavailable.is_retryable());
+        assert!(!unavailable.is_rejection());
+
+        let rejected = BrokerError::Http {
+            status: 403,
+            code: Some("40310000".into()),
+            message: "insufficient buying power".into(),
+        };
+        assert!(!rejected.is_retryable());
+        assert!(rejected.is_rejection());
+        assert_eq!(rejected.code(), Some("40310000"));
+        assert_eq!(
+            rejected.to_string(),
+            "HTTP 403 (code 40310000): insufficient buying power"
+        );
+
+        let limited = TradingError::from(BrokerError::RateLimited {
+            retry_after_secs: 3,
+        });
+        assert!(limited.is_retryable());
+        assert_eq!(limited.retry_after(), Some(Duration::from_secs(3)));
+        assert_eq!(limited.http_status(), Some(429));
+
+        assert!(!TradingError::Data(DataError::ParseError("bad row".into())).is_retryable());
+    }
+}
@@ -1,7 +1,7 @@
 //! Broker trait definition.
 
 use crate::error::BrokerError;
-use crate::types::{Order, OrderRequest, Portfolio, Position};
+use crate::types::{BrokerSnapshot, MarketClock, Order, OrderRequest, Portfolio, Position};
 use async_trait::async_trait;
 
 /// Trait for broker integrations.
@@ -65,16 +65,61 @@ pub trait Broker: Send + Sync {
     /// Check if the market is currently open.
     async fn is_market_open(&self) -> Result<bool, BrokerError>;
 
+    /// Whether the market is open and when it next opens and closes.
+    ///
+    /// The default only knows whether it is open; brokers with a market
+    /// calendar should report the session times too.
+    async fn market_clock(&self) -> Result<MarketClock, BrokerError> {
+        Ok(MarketClock::open(self.is_market_open().await?))
+    }
+
     /// Get the current buying power.
     async fn get_buying_power(&self) -> Result<rust_decimal::Decimal, BrokerError> {
         let account = self.get_account().await?;
         Ok(account.buying_power)
     }
 
+    /// Get a point-in-time view of open orders, positions and cash.
+    ///
+    /// Reconciliation should use this rather than separate calls, which can
+    /// race with fills. The default is best-effort for brokers that can only
+    /// be read piecewise: open orders are read before and after the account
+    /// and the read is retried if they changed in between. Brokers that hold
+    /// their state locally should override it with an atomic snapshot.
+    async fn snapshot(&self) -> Result<BrokerSnapshot, BrokerError> {
+        let mut before = self.get_open_orders().await?;
+        let mut attempts = 1;
+        loop {
+            let account = self.get_account().await?;
+            let after = self.get_open_orders().await?;
+            if same_orders(&before, &after) || attempts == SNAPSHOT_ATTEMPTS {
+                return Ok(BrokerSnapshot::new(account, after, false));
+            }
+            before = after;
+            attempts += 1;
+        }
+    }
+
     /// Get the broker name.
     fn name(&self) -> &str;
 }
 
+/// Reads of a best-effort snapshot before settling for a changing view.
+const SNAPSHOT_ATTEMPTS: usize = 3;
+
+/// Whether two reads of the open orders show the same orders in the same state.
+fn same_orders(a: &[Order], b: &[Order]) -> bool {
+    let key = |orders: &[Order]| {
+        let mut keys: Vec<_> = orders
+            .iter()
+            .map(|o| (o.id, o.status, o.filled_quantity))
+            .collect();
+        keys.sort_by_key(|(id, _, _)| *id);
+        keys
+    };
+    a.len() == b.len() && key(a) == key(b)
+}
+
 #[cfg(test)]
 mod tests {
     // Broker tests would typically use mock implementations
@@ -0,0 +1,98 @@
+//! Clock trait, so components can run on wall time or simulated time.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::atomic::{AtomicI64, Ordering};
+
+/// Source of the current time.
+///
+/// Live and paper trading use [`SystemClock`]; backtests use a
+/// [`SimulatedClock`] moved along with the data, so anything stamped or
+/// scheduled by the clock lines up with the bars rather than with when the
+/// backtest happened to run.
+#[async_trait]
+pub trait Clock: Send + Sync {
+    /// The current time.
+    fn now(&self) -> DateTime<Utc>;
+
+    /// Wait until `deadline`; returns at once if it has passed.
+    async fn sleep_until(&self, deadline: DateTime<Utc>);
+
+    /// The current time as Unix milliseconds.
+    fn now_millis(&self) -> i64 {
+        self.now().timestamp_millis()
+    }
+}
+
+/// Wall-clock time.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+#[async_trait]
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+
+    async fn sleep_until(&self, deadline: DateTime<Utc>) {
+        if let Ok(wait) = (deadline - Utc::now()).to_std() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// Time that moves only when told to.
+///
+/// The clock never goes backwards: advancing to an earlier time is
+/// ignored. Sleeping jumps the clock to the deadline instead of waiting.
+#[derive(Debug, Default)]
+pub struct SimulatedClock {
+    millis: AtomicI64,
+}
+
+impl SimulatedClock {
+    /// Create a clock reading `start` (Unix milliseconds).
+    pub fn new(start: i64) -> Self {
+        Self {
+            millis: AtomicI64::new(start),
+        }
+    }
+
+    /// Move the clock forward to `timestamp` (Unix milliseconds).
+    pub fn advance_to(&self, timestamp: i64) {
+        self.millis.fetch_max(timestamp, Ordering::Relaxed);
+    }
+}
+
+#[async_trait]
+impl Clock for SimulatedClock {
+    fn now(&self) -> DateTime<Utc> {
+        DateTime::from_timestamp_millis(self.now_millis())
+            .unwrap_or_else(|| DateTime::from_timestamp(0, 0).unwrap())
+    }
+
+    fn now_millis(&self) -> i64 {
+        self.millis.load(Ordering::Relaxed)
+    }
+
+    async fn sleep_until(&self, deadline: DateTime<Utc>) {
+        self.advance_to(deadline.timestamp_millis());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_simulated_clock_only_moves_forward() {
+        let clock = SimulatedClock::new(1_000);
+        clock.advance_to(5_000);
+        clock.advance_to(2_000);
+        assert_eq!(clock.now_millis(), 5_000);
+
+        let deadline = DateTime::from_timestamp_millis(60_000).unwrap();
+        clock.sleep_until(deadline).await;
+        assert_eq!(clock.now(), deadline);
+    }
+}
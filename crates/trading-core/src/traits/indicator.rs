@@ -25,6 +25,13 @@ pub trait Indicator: Send + Sync {
     /// Get the name of the indicator.
     fn name(&self) -> &str;
 
+    /// Identify the computation, every parameter included, e.g. to cache
+    /// its results. Indicators with parameters besides the period must
+    /// override it.
+    fn cache_key(&self) -> String {
+        format!("{}({})", self.name(), self.period())
+    }
+
     /// Validate that there's enough data.
     fn validate_data(&self, data: &[f64]) -> Result<(), IndicatorError> {
         if data.len() < self.period() {
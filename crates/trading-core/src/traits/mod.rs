@@ -1,11 +1,16 @@
 //! Core traits for the trading system.
 
 mod broker;
+mod clock;
 mod data_source;
 mod indicator;
 mod strategy;
 
 pub use broker::Broker;
+pub use clock::{Clock, SimulatedClock, SystemClock};
 pub use data_source::{DataSource, Quote, QuoteSource};
 pub use indicator::{Indicator, MultiOutputIndicator, StreamingIndicator};
-pub use strategy::{Strategy, StrategyConfig, StrategyState};
+pub use strategy::{
+    debug_check_metadata, Strategy, StrategyAction, StrategyConfig, StrategyOrders, StrategyState,
+    StreamingState,
+};
@@ -1,7 +1,10 @@
 //! Strategy trait definitions.
 
 use crate::error::StrategyError;
-use crate::types::{BarSeries, Order, Signal};
+use crate::types::{
+    Bar, BarSeries, MarketSnapshot, MetadataSchema, MultiTimeframeSeries, Order, OrderRequest,
+    Signal, Timeframe,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -41,10 +44,59 @@ impl Default for StrategyState {
     }
 }
 
+/// What a strategy asks for on a bar.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StrategyAction {
+    /// A signal the risk manager sizes and turns into an order
+    Signal(Signal),
+    /// Orders the strategy built itself; the risk manager only validates them
+    Orders(StrategyOrders),
+}
+
+/// Orders a strategy submits together.
+///
+/// Legs are all-or-nothing: if risk checks reject or resize any leg of a
+/// multi-leg batch, none of them is sent. Legs may carry bracket exits
+/// (see [`OrderRequest::with_stop_loss`]).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrategyOrders {
+    /// Orders to submit
+    pub legs: Vec<OrderRequest>,
+    /// Why the strategy placed them
+    pub reason: String,
+}
+
+impl StrategyOrders {
+    /// Create a batch from its legs.
+    pub fn new(legs: Vec<OrderRequest>) -> Self {
+        Self {
+            legs,
+            reason: String::new(),
+        }
+    }
+
+    /// Create a batch holding a single order.
+    pub fn single(order: OrderRequest) -> Self {
+        Self::new(vec![order])
+    }
+
+    /// Set the reason.
+    pub fn with_reason(mut self, reason: impl Into<String>) -> Self {
+        self.reason = reason.into();
+        self
+    }
+}
+
 /// Core strategy trait.
 ///
 /// All trading strategies must implement this trait to integrate with
 /// the trading system. Strategies receive bar data and emit trading signals.
+///
+/// Strategies can run in streaming mode by keeping their indicators in a
+/// [`StreamingState`] and feeding it only the bars added since the previous
+/// call, instead of recomputing indicators over the whole series on every
+/// bar.
 pub trait Strategy: Send + Sync {
     /// Get the unique name of this strategy.
     fn name(&self) -> &str;
@@ -62,6 +114,42 @@ pub trait Strategy: Send + Sync {
     /// * `None` if no action is needed
     fn on_bar(&mut self, series: &BarSeries) -> Option<Signal>;
 
+    /// Higher timeframes the strategy wants alongside its primary bars.
+    ///
+    /// The engine resamples primary bars into these and passes them to
+    /// [`on_bars`](Strategy::on_bars). Defaults to none.
+    fn timeframes(&self) -> &[Timeframe] {
+        &[]
+    }
+
+    /// Process a new primary bar with access to all subscribed timeframes.
+    ///
+    /// This is the entry point the engines call. The default forwards the
+    /// primary series to [`on_bar`](Strategy::on_bar); multi-timeframe
+    /// strategies override it (e.g. signal on 1h bars, filter on the 1d trend).
+    fn on_bars(&mut self, series: &MultiTimeframeSeries) -> Option<Signal> {
+        self.on_bar(series.primary())
+    }
+
+    /// Decide what to do on a new primary bar.
+    ///
+    /// The engines call this rather than [`on_bars`](Strategy::on_bars).
+    /// The default wraps the signal from `on_bars`; strategies that need
+    /// limit prices, brackets or several legs override it and return
+    /// [`StrategyAction::Orders`].
+    fn act(&mut self, series: &MultiTimeframeSeries) -> Option<StrategyAction> {
+        self.on_bars(series).map(StrategyAction::Signal)
+    }
+
+    /// Act on every symbol's series once all bars at a timestamp are in.
+    ///
+    /// The engines call this after the per-symbol [`act`](Strategy::act)
+    /// calls of each timestamp. Cross-sectional strategies (ranking or
+    /// rotating a basket) override it; the default does nothing.
+    fn on_snapshot(&mut self, _snapshot: &MarketSnapshot) -> Option<StrategyAction> {
+        None
+    }
+
     /// Called when an order is filled.
     ///
     /// Strategies can use this to track positions and update internal state.
@@ -90,6 +178,109 @@ pub trait Strategy: Send + Sync {
     fn description(&self) -> &str {
         ""
     }
+
+    /// Strategies a meta-strategy runs, for monitoring. Defaults to none.
+    fn members(&self) -> Vec<&dyn Strategy> {
+        Vec::new()
+    }
+
+    /// Indicator keys the strategy puts in its signal metadata, if declared.
+    ///
+    /// Engines check emitted signals against it in debug builds (see
+    /// [`debug_check_metadata`]).
+    fn metadata_schema(&self) -> Option<MetadataSchema> {
+        None
+    }
+}
+
+/// Check a signal against the emitting strategy's metadata schema.
+///
+/// Only active in debug builds, where a mismatch panics so that a renamed
+/// or missing indicator fails tests rather than silently emptying a
+/// dashboard column.
+pub fn debug_check_metadata(strategy: &dyn Strategy, signal: &Signal) {
+    if !cfg!(debug_assertions) {
+        return;
+    }
+    if let Some(schema) = strategy.metadata_schema() {
+        if let Err(e) = schema.validate(&signal.metadata) {
+            panic!(
+                "{} emitted a {} signal with invalid metadata: {}",
+                strategy.name(),
+                signal.signal_type,
+                e
+            );
+        }
+    }
+}
+
+/// Per-symbol state of a streaming strategy.
+///
+/// Holds one `S` (typically a set of streaming indicators) per symbol and
+/// remembers the last bar fed to it, so each bar of a series is consumed
+/// exactly once however often `on_bar` sees it. If a series is rewound (its
+/// newest bar is older than the last one consumed) the state is rebuilt from
+/// the bars available.
+#[derive(Debug, Clone)]
+pub struct StreamingState<S> {
+    symbols: HashMap<String, (i64, S)>,
+}
+
+impl<S> Default for StreamingState<S> {
+    fn default() -> Self {
+        Self {
+            symbols: HashMap::new(),
+        }
+    }
+}
+
+impl<S> StreamingState<S> {
+    /// Create an empty state.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed `update` the bars of `series` not consumed yet, oldest first,
+    /// creating the symbol's state with `init` on first use.
+    ///
+    /// Returns the result for the newest bar, or `None` if there were no new
+    /// bars.
+    pub fn update<T>(
+        &mut self,
+        series: &BarSeries,
+        init: impl FnOnce() -> S,
+        mut update: impl FnMut(&mut S, &Bar) -> T,
+    ) -> Option<T> {
+        let newest = series.last()?.timestamp;
+        if !matches!(self.symbols.get(&series.symbol), Some((last, _)) if newest >= *last) {
+            self.symbols
+                .insert(series.symbol.clone(), (i64::MIN, init()));
+        }
+        let (last, state) = self.symbols.get_mut(&series.symbol)?;
+
+        let bars = series.bars();
+        let start = bars
+            .iter()
+            .rposition(|b| b.timestamp <= *last)
+            .map_or(0, |i| i + 1);
+
+        let mut result = None;
+        for bar in bars.range(start..) {
+            result = Some(update(state, bar));
+        }
+        *last = newest;
+        result
+    }
+
+    /// State of a symbol, if it has seen any bars.
+    pub fn get(&self, symbol: &str) -> Option<&S> {
+        self.symbols.get(symbol).map(|(_, state)| state)
+    }
+
+    /// Drop the state of all symbols.
+    pub fn clear(&mut self) {
+        self.symbols.clear();
+    }
 }
 
 #[cfg(test)]
@@ -149,4 +340,36 @@ mod tests {
         assert!(strategy.is_warmed_up(20));
         assert!(strategy.is_warmed_up(100));
     }
+
+    #[test]
+    fn test_streaming_state_consumes_each_bar_once() {
+        let mut series = BarSeries::new("TEST".to_string(), Timeframe::Daily);
+        let mut state: StreamingState<Vec<f64>> = StreamingState::new();
+        let feed = |state: &mut StreamingState<Vec<f64>>, series: &BarSeries| {
+            state.update(series, Vec::new, |seen, bar| {
+                seen.push(bar.close);
+                seen.len()
+            })
+        };
+
+        for i in 0..3 {
+            let price = 100.0 + i as f64;
+            series.push(Bar::new(i * 1000, price, price, price, price, 1.0));
+        }
+        // A first call catches up on all bars, repeated calls add nothing
+        assert_eq!(feed(&mut state, &series), Some(3));
+        assert_eq!(feed(&mut state, &series), None);
+
+        series.push(Bar::new(3000, 103.0, 103.0, 103.0, 103.0, 1.0));
+        assert_eq!(feed(&mut state, &series), Some(4));
+        assert_eq!(
+            state.get("TEST").unwrap(),
+            &vec![100.0, 101.0, 102.0, 103.0]
+        );
+
+        // A rewound series rebuilds the state
+        series.clear();
+        series.push(Bar::new(0, 100.0, 100.0, 100.0, 100.0, 1.0));
+        assert_eq!(feed(&mut state, &series), Some(1));
+    }
 }
@@ -0,0 +1,26 @@
+//! Market hours as reported by a broker.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Whether the market is open, and when it next opens and closes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MarketClock {
+    /// Whether the market is open now
+    pub is_open: bool,
+    /// Start of the next session, if the broker reports it
+    pub next_open: Option<DateTime<Utc>>,
+    /// End of the current or next session, if the broker reports it
+    pub next_close: Option<DateTime<Utc>>,
+}
+
+impl MarketClock {
+    /// Clock that only knows whether the market is open.
+    pub fn open(is_open: bool) -> Self {
+        Self {
+            is_open,
+            next_open: None,
+            next_close: None,
+        }
+    }
+}
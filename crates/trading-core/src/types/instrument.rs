@@ -0,0 +1,291 @@
+//! Instrument metadata and trading rules.
+
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
+
+use super::{OrderRequest, Side};
+
+/// Kind of asset an instrument is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetClass {
+    /// Stocks and ETFs
+    #[default]
+    Equity,
+    Crypto,
+    /// Currency pairs
+    Forex,
+    /// Futures contracts, usually with a multiplier
+    Future,
+    /// Listed options (see [`OptionContract`](super::OptionContract))
+    Option,
+}
+
+impl std::fmt::Display for AssetClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AssetClass::Equity => write!(f, "equity"),
+            AssetClass::Crypto => write!(f, "crypto"),
+            AssetClass::Forex => write!(f, "forex"),
+            AssetClass::Future => write!(f, "future"),
+            AssetClass::Option => write!(f, "option"),
+        }
+    }
+}
+
+impl std::str::FromStr for AssetClass {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "equity" | "stock" | "etf" => Ok(AssetClass::Equity),
+            "crypto" => Ok(AssetClass::Crypto),
+            "forex" | "fx" => Ok(AssetClass::Forex),
+            "future" | "futures" => Ok(AssetClass::Future),
+            "option" | "options" => Ok(AssetClass::Option),
+            _ => Err(format!("Invalid asset class: {}", s)),
+        }
+    }
+}
+
+/// A tradable instrument: what it is, where it trades, and the increments
+/// and minimums the exchange accepts.
+///
+/// A zero lot size, tick size or minimum notional means the constraint
+/// does not apply; the default instrument leaves orders unchanged. Prices
+/// are per unit, and one contract is worth `multiplier` units, so values
+/// and P&L are `quantity * price * multiplier`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Instrument {
+    pub symbol: String,
+    pub asset_class: AssetClass,
+    /// Listing exchange or venue, e.g. CME
+    pub exchange: Option<String>,
+    /// Currency prices are quoted in
+    pub currency: String,
+    /// Units of the price one contract is worth (50 for an E-mini S&P
+    /// future, 1 for shares, coins and FX)
+    pub multiplier: Decimal,
+    /// Quantity increment (1 for whole shares, 0.0001 for some crypto)
+    pub lot_size: Decimal,
+    /// Price increment (0.01 for most US equities)
+    pub tick_size: Decimal,
+    /// Smallest order value accepted
+    pub min_notional: Decimal,
+}
+
+impl Default for Instrument {
+    fn default() -> Self {
+        Self {
+            symbol: String::new(),
+            asset_class: AssetClass::Equity,
+            exchange: None,
+            currency: "USD".to_string(),
+            multiplier: Decimal::ONE,
+            lot_size: Decimal::ZERO,
+            tick_size: Decimal::ZERO,
+            min_notional: Decimal::ZERO,
+        }
+    }
+}
+
+impl Instrument {
+    /// Create an instrument with no trading constraints, quoted in USD.
+    pub fn new(symbol: impl Into<String>, asset_class: AssetClass) -> Self {
+        Self {
+            symbol: symbol.into(),
+            asset_class,
+            ..Default::default()
+        }
+    }
+
+    /// Set the listing exchange.
+    pub fn with_exchange(mut self, exchange: impl Into<String>) -> Self {
+        self.exchange = Some(exchange.into());
+        self
+    }
+
+    /// Set the quote currency.
+    pub fn with_currency(mut self, currency: impl Into<String>) -> Self {
+        self.currency = currency.into();
+        self
+    }
+
+    /// Set the contract multiplier.
+    pub fn with_multiplier(mut self, multiplier: Decimal) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// Set the quantity increment.
+    pub fn with_lot_size(mut self, lot_size: Decimal) -> Self {
+        self.lot_size = lot_size;
+        self
+    }
+
+    /// Set the price increment.
+    pub fn with_tick_size(mut self, tick_size: Decimal) -> Self {
+        self.tick_size = tick_size;
+        self
+    }
+
+    /// Set the smallest order value accepted.
+    pub fn with_min_notional(mut self, min_notional: Decimal) -> Self {
+        self.min_notional = min_notional;
+        self
+    }
+
+    /// Value of `quantity` contracts at `price`.
+    pub fn notional(&self, quantity: Decimal, price: Decimal) -> Decimal {
+        quantity * price * self.multiplier
+    }
+
+    /// Value of a one-tick move on one contract (12.50 for an E-mini S&P
+    /// future); zero when there is no tick size.
+    pub fn tick_value(&self) -> Decimal {
+        self.tick_size * self.multiplier
+    }
+
+    /// P&L of holding `quantity` contracts (negative for short) from
+    /// `entry` to `exit`.
+    pub fn pnl(&self, quantity: Decimal, entry: Decimal, exit: Decimal) -> Decimal {
+        quantity * (exit - entry) * self.multiplier
+    }
+
+    /// Contracts worth the same as `units` units of the price, for turning
+    /// a size worked out by value into an order quantity.
+    pub fn contracts(&self, units: Decimal) -> Decimal {
+        if self.multiplier > Decimal::ZERO {
+            units / self.multiplier
+        } else {
+            units
+        }
+    }
+
+    /// Round a quantity down to whole lots, so it never exceeds the
+    /// amount it was sized at.
+    pub fn round_quantity(&self, quantity: Decimal) -> Decimal {
+        round_to(quantity, self.lot_size, RoundingStrategy::ToZero)
+    }
+
+    /// Round a price to the nearest tick.
+    pub fn round_price(&self, price: Decimal) -> Decimal {
+        round_to(
+            price,
+            self.tick_size,
+            RoundingStrategy::MidpointAwayFromZero,
+        )
+    }
+
+    /// Round a limit price to a tick without making it more aggressive:
+    /// buys round down and sells round up.
+    pub fn round_limit_price(&self, price: Decimal, side: Side) -> Decimal {
+        let strategy = match side {
+            Side::Buy => RoundingStrategy::ToNegativeInfinity,
+            Side::Sell => RoundingStrategy::ToPositiveInfinity,
+        };
+        round_to(price, self.tick_size, strategy)
+    }
+
+    /// Round an order's quantity and prices in place, checking it is still
+    /// worth at least the minimum notional at `price`.
+    pub fn apply(&self, order: &mut OrderRequest, price: Decimal) -> Result<(), String> {
+        order.quantity = self.round_quantity(order.quantity);
+        if order.quantity <= Decimal::ZERO {
+            return Err(format!(
+                "{} quantity rounds to zero at lot size {}",
+                order.symbol, self.lot_size
+            ));
+        }
+
+        order.limit_price = order
+            .limit_price
+            .map(|p| self.round_limit_price(p, order.side));
+        order.stop_price = order.stop_price.map(|p| self.round_price(p));
+        order.trail_amount = order.trail_amount.map(|p| self.round_price(p));
+        order.stop_loss = order.stop_loss.map(|p| self.round_price(p));
+        order.take_profit = order.take_profit.map(|p| self.round_price(p));
+
+        let notional = self.notional(order.quantity, order.limit_price.unwrap_or(price));
+        if notional < self.min_notional {
+            return Err(format!(
+                "{} order value {} is below the minimum of {}",
+                order.symbol,
+                notional.round_dp(2),
+                self.min_notional
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Round `value` to a multiple of `increment`; a zero increment leaves it as is.
+fn round_to(value: Decimal, increment: Decimal, strategy: RoundingStrategy) -> Decimal {
+    if increment <= Decimal::ZERO {
+        return value;
+    }
+    (value / increment).round_dp_with_strategy(0, strategy) * increment
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_apply_rounds_to_lots_and_ticks() {
+        let rules = Instrument::new("BTCUSD", AssetClass::Crypto)
+            .with_lot_size(dec!(0.001))
+            .with_tick_size(dec!(0.01))
+            .with_min_notional(dec!(10));
+
+        let mut buy = OrderRequest::limit("BTCUSD", Side::Buy, dec!(0.12345), dec!(43210.127));
+        rules.apply(&mut buy, dec!(43210)).unwrap();
+        assert_eq!(buy.quantity, dec!(0.123));
+        assert_eq!(buy.limit_price, Some(dec!(43210.12)));
+
+        let mut sell = OrderRequest::limit("BTCUSD", Side::Sell, dec!(0.5), dec!(43210.121));
+        rules.apply(&mut sell, dec!(43210)).unwrap();
+        assert_eq!(sell.limit_price, Some(dec!(43210.13)));
+
+        let mut dust = OrderRequest::market("BTCUSD", Side::Buy, dec!(0.0004));
+        assert!(rules
+            .apply(&mut dust, dec!(43210))
+            .unwrap_err()
+            .contains("lot size"));
+        let mut below_minimum = OrderRequest::market("BTCUSD", Side::Buy, dec!(0.001));
+        assert!(rules
+            .apply(&mut below_minimum, dec!(5000))
+            .unwrap_err()
+            .contains("minimum"));
+    }
+
+    #[test]
+    fn test_default_rules_leave_orders_unchanged() {
+        let mut order = OrderRequest::limit("AAPL", Side::Buy, dec!(10.5), dec!(150.123));
+        Instrument::default().apply(&mut order, dec!(150)).unwrap();
+        assert_eq!(order.quantity, dec!(10.5));
+        assert_eq!(order.limit_price, Some(dec!(150.123)));
+    }
+
+    #[test]
+    fn test_future_values_include_multiplier() {
+        let es = Instrument::new("ES", AssetClass::Future)
+            .with_exchange("CME")
+            .with_multiplier(dec!(50))
+            .with_lot_size(dec!(1))
+            .with_tick_size(dec!(0.25))
+            .with_min_notional(dec!(100000));
+        assert_eq!(es.notional(dec!(2), dec!(5000)), dec!(500000));
+        assert_eq!(es.pnl(dec!(-2), dec!(5000), dec!(4990.25)), dec!(975));
+        assert_eq!(es.contracts(dec!(100)), dec!(2));
+        assert_eq!(es.tick_value(), dec!(12.5));
+
+        // One contract at 5000 is worth 250,000, above the minimum
+        let mut order = OrderRequest::limit("ES", Side::Buy, dec!(1), dec!(5000.1));
+        es.apply(&mut order, dec!(5000)).unwrap();
+        assert_eq!(order.limit_price, Some(dec!(5000)));
+        assert_eq!("futures".parse::<AssetClass>().unwrap(), AssetClass::Future);
+    }
+}
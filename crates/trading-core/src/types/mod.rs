@@ -1,13 +1,27 @@
 //! Core data types for the trading system.
 
+mod clock;
+mod instrument;
+mod multi_timeframe;
 mod ohlcv;
+mod option;
 mod order;
 mod position;
 mod signal;
+mod snapshot;
+mod tick;
 mod timeframe;
 
+pub use clock::MarketClock;
+pub use instrument::{AssetClass, Instrument};
+pub use multi_timeframe::{MarketSnapshot, MultiTimeframeSeries};
 pub use ohlcv::{Bar, BarSeries, PreciseBar};
+pub use option::{
+    black_scholes, black_scholes_delta, OptionChain, OptionContract, OptionQuote, OptionRight,
+};
 pub use order::{Fill, Order, OrderRequest, OrderStatus, OrderType, Side, TimeInForce};
 pub use position::{Portfolio, Position};
-pub use signal::{Signal, SignalMetadata, SignalStrength, SignalType};
+pub use signal::{MetadataSchema, Signal, SignalMetadata, SignalStrength, SignalType};
+pub use snapshot::BrokerSnapshot;
+pub use tick::{QuoteTick, Tick};
 pub use timeframe::Timeframe;
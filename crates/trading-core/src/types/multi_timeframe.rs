@@ -0,0 +1,244 @@
+//! Bars of one symbol on several timeframes.
+
+use super::{Bar, BarSeries, Timeframe};
+
+/// Higher timeframe resampled from the primary bars.
+#[derive(Debug, Clone)]
+struct Resampled {
+    /// Completed bars
+    series: BarSeries,
+    /// Bar still being built from the current period
+    forming: Option<Bar>,
+}
+
+/// Primary bar series of a symbol plus series resampled to higher
+/// timeframes.
+///
+/// A resampled bar is only added once its period is complete (the last
+/// primary bar of the period has arrived, or a bar from a later period
+/// has), so strategies never see data from the future. The bar still
+/// forming is available separately via [`forming`](Self::forming).
+#[derive(Debug, Clone)]
+pub struct MultiTimeframeSeries {
+    primary: BarSeries,
+    resampled: Vec<(Timeframe, Resampled)>,
+}
+
+impl MultiTimeframeSeries {
+    /// Create a series for `primary` bars, resampled to each of
+    /// `timeframes`. Timeframes not longer than the primary are ignored.
+    pub fn new(
+        symbol: String,
+        primary: Timeframe,
+        capacity: usize,
+        timeframes: &[Timeframe],
+    ) -> Self {
+        let mut resampled: Vec<(Timeframe, Resampled)> = Vec::new();
+        for tf in timeframes {
+            if tf.as_millis() <= primary.as_millis() || resampled.iter().any(|(t, _)| t == tf) {
+                continue;
+            }
+            resampled.push((
+                *tf,
+                Resampled {
+                    series: BarSeries::with_capacity(symbol.clone(), *tf, capacity),
+                    forming: None,
+                },
+            ));
+        }
+
+        Self {
+            primary: BarSeries::with_capacity(symbol, primary, capacity),
+            resampled,
+        }
+    }
+
+    /// Symbol of the series.
+    pub fn symbol(&self) -> &str {
+        &self.primary.symbol
+    }
+
+    /// Bars at the primary timeframe.
+    pub fn primary(&self) -> &BarSeries {
+        &self.primary
+    }
+
+    /// Completed bars at `timeframe` (the primary series for its own timeframe).
+    pub fn get(&self, timeframe: Timeframe) -> Option<&BarSeries> {
+        if timeframe == self.primary.timeframe {
+            return Some(&self.primary);
+        }
+        self.resampled
+            .iter()
+            .find(|(tf, _)| *tf == timeframe)
+            .map(|(_, r)| &r.series)
+    }
+
+    /// Partial bar of the current `timeframe` period.
+    pub fn forming(&self, timeframe: Timeframe) -> Option<&Bar> {
+        self.resampled
+            .iter()
+            .find(|(tf, _)| *tf == timeframe)
+            .and_then(|(_, r)| r.forming.as_ref())
+    }
+
+    /// Resampled timeframes.
+    pub fn timeframes(&self) -> impl Iterator<Item = Timeframe> + '_ {
+        self.resampled.iter().map(|(tf, _)| *tf)
+    }
+
+    /// Add a primary bar, updating the resampled series.
+    pub fn push(&mut self, bar: Bar) {
+        let primary_ms = self.primary.timeframe.as_millis() as i64;
+        self.primary.push(bar);
+
+        for (tf, resampled) in &mut self.resampled {
+            let start = tf.bucket_start(bar.timestamp);
+
+            // A bar from a new period completes the previous one
+            if let Some(forming) = resampled.forming {
+                if tf.bucket_start(forming.timestamp) != start {
+                    resampled.series.push(forming);
+                    resampled.forming = None;
+                }
+            }
+
+            let forming = match resampled.forming.take() {
+                Some(mut forming) => {
+                    forming.high = forming.high.max(bar.high);
+                    forming.low = forming.low.min(bar.low);
+                    forming.close = bar.close;
+                    forming.volume += bar.volume;
+                    forming.vwap = None;
+                    forming
+                }
+                None => Bar {
+                    timestamp: start,
+                    vwap: None,
+                    ..bar
+                },
+            };
+
+            if bar.timestamp + primary_ms >= tf.bucket_end(bar.timestamp) {
+                resampled.series.push(forming);
+            } else {
+                resampled.forming = Some(forming);
+            }
+        }
+    }
+
+    /// Remove all bars.
+    pub fn clear(&mut self) {
+        self.primary.clear();
+        for (_, resampled) in &mut self.resampled {
+            resampled.series.clear();
+            resampled.forming = None;
+        }
+    }
+}
+
+/// Series of every symbol as of one timestamp.
+///
+/// Engines build one once all bars at a timestamp are in and pass it to
+/// [`Strategy::on_snapshot`](crate::traits::Strategy::on_snapshot), so
+/// cross-sectional strategies see the whole basket at once. Series are
+/// ordered by symbol.
+#[derive(Debug, Clone)]
+pub struct MarketSnapshot<'a> {
+    timestamp: i64,
+    series: Vec<&'a MultiTimeframeSeries>,
+}
+
+impl<'a> MarketSnapshot<'a> {
+    /// Create a snapshot at `timestamp` (Unix ms).
+    pub fn new(timestamp: i64, series: impl IntoIterator<Item = &'a MultiTimeframeSeries>) -> Self {
+        let mut series: Vec<_> = series.into_iter().collect();
+        series.sort_by(|a, b| a.symbol().cmp(b.symbol()));
+        Self { timestamp, series }
+    }
+
+    /// Timestamp (Unix ms) of the snapshot.
+    pub fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+
+    /// Series of a symbol.
+    pub fn get(&self, symbol: &str) -> Option<&'a MultiTimeframeSeries> {
+        self.series.iter().copied().find(|s| s.symbol() == symbol)
+    }
+
+    /// All series.
+    pub fn iter(&self) -> impl Iterator<Item = &'a MultiTimeframeSeries> + '_ {
+        self.series.iter().copied()
+    }
+
+    /// Series with a bar at the snapshot's timestamp.
+    pub fn current(&self) -> impl Iterator<Item = &'a MultiTimeframeSeries> + '_ {
+        self.iter().filter(|s| {
+            s.primary()
+                .last()
+                .is_some_and(|b| b.timestamp == self.timestamp)
+        })
+    }
+
+    /// Number of symbols.
+    pub fn len(&self) -> usize {
+        self.series.len()
+    }
+
+    /// Whether the snapshot holds no symbols.
+    pub fn is_empty(&self) -> bool {
+        self.series.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR_MS: i64 = 3_600_000;
+
+    #[test]
+    fn test_resamples_completed_periods_only() {
+        let mut series = MultiTimeframeSeries::new(
+            "TEST".to_string(),
+            Timeframe::Hour1,
+            100,
+            &[Timeframe::Hour4, Timeframe::Daily, Timeframe::Minute5],
+        );
+        assert_eq!(
+            series.timeframes().collect::<Vec<_>>(),
+            vec![Timeframe::Hour4, Timeframe::Daily]
+        );
+
+        for i in 0..6 {
+            let price = 100.0 + i as f64;
+            series.push(Bar::new(
+                i * HOUR_MS,
+                price,
+                price + 2.0,
+                price - 1.0,
+                price + 1.0,
+                10.0,
+            ));
+        }
+
+        let h4 = series.get(Timeframe::Hour4).unwrap();
+        assert_eq!(h4.len(), 1);
+        let bar = h4.last().unwrap();
+        assert_eq!(bar.timestamp, 0);
+        assert_eq!(bar.open, 100.0);
+        assert_eq!(bar.high, 105.0);
+        assert_eq!(bar.low, 99.0);
+        assert_eq!(bar.close, 104.0);
+        assert_eq!(bar.volume, 40.0);
+
+        let forming = series.forming(Timeframe::Hour4).unwrap();
+        assert_eq!(forming.timestamp, 4 * HOUR_MS);
+        assert_eq!(forming.close, 106.0);
+
+        // The day is still open
+        assert!(series.get(Timeframe::Daily).unwrap().is_empty());
+        assert_eq!(series.primary().len(), 6);
+    }
+}
@@ -4,8 +4,10 @@ use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 use super::Timeframe;
+use crate::error::DataError;
 
 /// Compact OHLCV bar optimized for performance.
 /// Uses f64 for fast indicator calculations.
@@ -144,7 +146,11 @@ impl From<Bar> for PreciseBar {
 }
 
 /// Time-series container for bars, optimized for sequential access.
-#[derive(Debug, Clone)]
+///
+/// Serializes as its symbol, timeframe, capacity and bars; see
+/// [`to_bytes`](Self::to_bytes) for a compact binary snapshot.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(from = "SeriesData")]
 pub struct BarSeries {
     /// Symbol identifier
     pub symbol: String,
@@ -154,8 +160,118 @@ pub struct BarSeries {
     bars: VecDeque<Bar>,
     /// Maximum capacity (0 = unlimited)
     capacity: usize,
+    /// Each field of the bars kept contiguous for slicing
+    columns: Columns,
+    /// Changes whenever the bars do
+    version: u64,
 }
 
+/// Source of [`BarSeries::version`] numbers, shared by every series so no
+/// two states of any series share one.
+static NEXT_VERSION: AtomicU64 = AtomicU64::new(1);
+
+fn next_version() -> u64 {
+    NEXT_VERSION.fetch_add(1, Ordering::Relaxed)
+}
+
+/// OHLCV columns stored next to the bars so they can be lent out as
+/// slices.
+///
+/// Evicted values are skipped by an offset and only dropped once they make
+/// up half the buffer, so pushing at capacity stays amortized O(1).
+#[derive(Debug, Clone, Default)]
+struct Columns {
+    /// Index of the oldest value still in the series
+    start: usize,
+    open: Vec<f64>,
+    high: Vec<f64>,
+    low: Vec<f64>,
+    close: Vec<f64>,
+    volume: Vec<f64>,
+}
+
+impl Columns {
+    fn columns_mut(&mut self) -> [&mut Vec<f64>; 5] {
+        [
+            &mut self.open,
+            &mut self.high,
+            &mut self.low,
+            &mut self.close,
+            &mut self.volume,
+        ]
+    }
+
+    fn push(&mut self, bar: &Bar) {
+        let values = [bar.open, bar.high, bar.low, bar.close, bar.volume];
+        for (column, value) in self.columns_mut().into_iter().zip(values) {
+            column.push(value);
+        }
+    }
+
+    fn pop_front(&mut self) {
+        self.start += 1;
+        if self.start * 2 >= self.close.len() {
+            let start = self.start;
+            for column in self.columns_mut() {
+                column.drain(..start);
+            }
+            self.start = 0;
+        }
+    }
+
+    fn clear(&mut self) {
+        self.start = 0;
+        for column in self.columns_mut() {
+            column.clear();
+        }
+    }
+}
+
+/// Serialized form of a [`BarSeries`].
+#[derive(Deserialize)]
+struct SeriesData {
+    symbol: String,
+    timeframe: Timeframe,
+    #[serde(default)]
+    capacity: usize,
+    bars: Vec<Bar>,
+}
+
+impl From<SeriesData> for BarSeries {
+    fn from(data: SeriesData) -> Self {
+        let reserve = data.capacity.min(data.bars.len());
+        let mut series = BarSeries::reserved(data.symbol, data.timeframe, data.capacity, reserve);
+        series.extend(data.bars);
+        series
+    }
+}
+
+impl Serialize for BarSeries {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct SeriesRef<'a> {
+            symbol: &'a str,
+            timeframe: Timeframe,
+            capacity: usize,
+            bars: &'a VecDeque<Bar>,
+        }
+        SeriesRef {
+            symbol: &self.symbol,
+            timeframe: self.timeframe,
+            capacity: self.capacity,
+            bars: &self.bars,
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Leading bytes of a binary bar series snapshot.
+const SNAPSHOT_MAGIC: &[u8; 4] = b"BARS";
+/// Version of the binary snapshot layout.
+const SNAPSHOT_VERSION: u8 = 1;
+/// Bytes per bar in a snapshot: timestamp, OHLCV, VWAP flag and VWAP.
+const BAR_RECORD_LEN: usize = 8 + 5 * 8 + 1 + 8;
+
 impl BarSeries {
     /// Create a new empty bar series.
     pub fn new(symbol: String, timeframe: Timeframe) -> Self {
@@ -164,17 +280,28 @@ impl BarSeries {
             timeframe,
             bars: VecDeque::new(),
             capacity: 0,
+            columns: Columns::default(),
+            version: next_version(),
         }
     }
 
     /// Create a bar series with a maximum capacity.
     /// When capacity is reached, oldest bars are removed.
     pub fn with_capacity(symbol: String, timeframe: Timeframe, capacity: usize) -> Self {
+        Self::reserved(symbol, timeframe, capacity, capacity)
+    }
+
+    /// A series keeping at most `capacity` bars, with room for `reserve`.
+    /// Stored series can claim any capacity, so they only allocate for the
+    /// bars they hold.
+    fn reserved(symbol: String, timeframe: Timeframe, capacity: usize, reserve: usize) -> Self {
         Self {
             symbol,
             timeframe,
-            bars: VecDeque::with_capacity(capacity),
+            bars: VecDeque::with_capacity(reserve),
             capacity,
+            columns: Columns::default(),
+            version: next_version(),
         }
     }
 
@@ -182,8 +309,11 @@ impl BarSeries {
     pub fn push(&mut self, bar: Bar) {
         if self.capacity > 0 && self.bars.len() >= self.capacity {
             self.bars.pop_front();
+            self.columns.pop_front();
         }
+        self.columns.push(&bar);
         self.bars.push_back(bar);
+        self.version = next_version();
     }
 
     /// Push multiple bars.
@@ -205,6 +335,16 @@ impl BarSeries {
         self.bars.is_empty()
     }
 
+    /// Identifies the series' current bars, for caching values computed
+    /// from them.
+    ///
+    /// A new number is taken on every change, unique across all series;
+    /// only clones of a series share its version.
+    #[inline]
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
     /// Get all bars as a slice.
     pub fn bars(&self) -> &VecDeque<Bar> {
         &self.bars
@@ -226,29 +366,56 @@ impl BarSeries {
         self.bars.get(index)
     }
 
+    /// Open prices, oldest first, without copying.
+    pub fn open_slice(&self) -> &[f64] {
+        &self.columns.open[self.columns.start..]
+    }
+
+    /// High prices, oldest first, without copying.
+    pub fn high_slice(&self) -> &[f64] {
+        &self.columns.high[self.columns.start..]
+    }
+
+    /// Low prices, oldest first, without copying.
+    pub fn low_slice(&self) -> &[f64] {
+        &self.columns.low[self.columns.start..]
+    }
+
+    /// Close prices, oldest first, without copying.
+    pub fn close_slice(&self) -> &[f64] {
+        &self.columns.close[self.columns.start..]
+    }
+
+    /// Volumes, oldest first, without copying.
+    pub fn volume_slice(&self) -> &[f64] {
+        &self.columns.volume[self.columns.start..]
+    }
+
     /// Extract close prices as a vector.
+    ///
+    /// Prefer [`close_slice`](Self::close_slice), which doesn't allocate.
     pub fn closes(&self) -> Vec<f64> {
-        self.bars.iter().map(|b| b.close).collect()
+        self.close_slice().to_vec()
     }
 
     /// Extract open prices as a vector.
     pub fn opens(&self) -> Vec<f64> {
-        self.bars.iter().map(|b| b.open).collect()
+        self.open_slice().to_vec()
     }
 
     /// Extract high prices as a vector.
     pub fn highs(&self) -> Vec<f64> {
-        self.bars.iter().map(|b| b.high).collect()
+        self.high_slice().to_vec()
     }
 
     /// Extract low prices as a vector.
     pub fn lows(&self) -> Vec<f64> {
-        self.bars.iter().map(|b| b.low).collect()
+        self.low_slice().to_vec()
     }
 
     /// Extract volumes as a vector.
     pub fn volumes(&self) -> Vec<f64> {
-        self.bars.iter().map(|b| b.volume).collect()
+        self.volume_slice().to_vec()
     }
 
     /// Extract typical prices as a vector.
@@ -259,23 +426,144 @@ impl BarSeries {
     /// Clear all bars.
     pub fn clear(&mut self) {
         self.bars.clear();
+        self.columns.clear();
+        self.version = next_version();
     }
 
     /// Get an iterator over the bars.
     pub fn iter(&self) -> impl Iterator<Item = &Bar> {
         self.bars.iter()
     }
+
+    /// Encode the series as a compact binary snapshot.
+    ///
+    /// The layout is little-endian: a `BARS` tag and version byte, the
+    /// timeframe and symbol as length-prefixed UTF-8, the capacity and bar
+    /// count as `u64`, then 57 bytes per bar (timestamp, OHLCV, a VWAP flag
+    /// and the VWAP).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let timeframe = self.timeframe.to_string();
+        let mut out = Vec::with_capacity(32 + self.symbol.len() + self.bars.len() * BAR_RECORD_LEN);
+        out.extend_from_slice(SNAPSHOT_MAGIC);
+        out.push(SNAPSHOT_VERSION);
+        out.push(timeframe.len() as u8);
+        out.extend_from_slice(timeframe.as_bytes());
+        out.extend_from_slice(&(self.symbol.len() as u16).to_le_bytes());
+        out.extend_from_slice(self.symbol.as_bytes());
+        out.extend_from_slice(&(self.capacity as u64).to_le_bytes());
+        out.extend_from_slice(&(self.bars.len() as u64).to_le_bytes());
+        for bar in &self.bars {
+            out.extend_from_slice(&bar.timestamp.to_le_bytes());
+            for value in [bar.open, bar.high, bar.low, bar.close, bar.volume] {
+                out.extend_from_slice(&value.to_le_bytes());
+            }
+            out.push(bar.vwap.is_some() as u8);
+            out.extend_from_slice(&bar.vwap.unwrap_or(0.0).to_le_bytes());
+        }
+        out
+    }
+
+    /// Decode a snapshot written by [`to_bytes`](Self::to_bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DataError> {
+        let mut reader = SnapshotReader { bytes };
+        if reader.take(4)? != SNAPSHOT_MAGIC {
+            return Err(DataError::ParseError(
+                "Not a bar series snapshot".to_string(),
+            ));
+        }
+        let version = reader.take(1)?[0];
+        if version != SNAPSHOT_VERSION {
+            return Err(DataError::ParseError(format!(
+                "Unsupported bar series snapshot version {}",
+                version
+            )));
+        }
+        let len = reader.take(1)?[0] as usize;
+        let timeframe: Timeframe = reader.utf8(len)?.parse().map_err(DataError::ParseError)?;
+        let len = u16::from_le_bytes(reader.array()?) as usize;
+        let symbol = reader.utf8(len)?.to_string();
+        let capacity = reader.u64()? as usize;
+        let count = reader.u64()? as usize;
+        // The header is untrusted: check the bars are there before
+        // allocating for them
+        let room = reader.bytes.len() / BAR_RECORD_LEN;
+        if count > room {
+            return Err(DataError::ParseError(format!(
+                "Bar series snapshot claims {} bars but holds at most {}",
+                count, room
+            )));
+        }
+
+        let mut series = BarSeries::reserved(symbol, timeframe, capacity, capacity.min(count));
+        for _ in 0..count {
+            let timestamp = i64::from_le_bytes(reader.array()?);
+            let mut bar = Bar::new(
+                timestamp,
+                reader.f64()?,
+                reader.f64()?,
+                reader.f64()?,
+                reader.f64()?,
+                reader.f64()?,
+            );
+            let has_vwap = reader.take(1)?[0] != 0;
+            let vwap = reader.f64()?;
+            if has_vwap {
+                bar.vwap = Some(vwap);
+            }
+            series.push(bar);
+        }
+        if !reader.bytes.is_empty() {
+            return Err(DataError::ParseError(format!(
+                "{} unexpected bytes after bar series snapshot",
+                reader.bytes.len()
+            )));
+        }
+        Ok(series)
+    }
+}
+
+/// Cursor over a binary snapshot.
+struct SnapshotReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> SnapshotReader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], DataError> {
+        if self.bytes.len() < n {
+            return Err(DataError::ParseError(
+                "Truncated bar series snapshot".to_string(),
+            ));
+        }
+        let (head, rest) = self.bytes.split_at(n);
+        self.bytes = rest;
+        Ok(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], DataError> {
+        let mut out = [0; N];
+        out.copy_from_slice(self.take(N)?);
+        Ok(out)
+    }
+
+    fn u64(&mut self) -> Result<u64, DataError> {
+        Ok(u64::from_le_bytes(self.array()?))
+    }
+
+    fn f64(&mut self) -> Result<f64, DataError> {
+        Ok(f64::from_le_bytes(self.array()?))
+    }
+
+    fn utf8(&mut self, n: usize) -> Result<&'a str, DataError> {
+        std::str::from_utf8(self.take(n)?)
+            .map_err(|e| DataError::ParseError(format!("Invalid bar series snapshot: {}", e)))
+    }
 }
 
 impl FromIterator<Bar> for BarSeries {
     fn from_iter<T: IntoIterator<Item = Bar>>(iter: T) -> Self {
-        let bars: VecDeque<Bar> = iter.into_iter().collect();
-        Self {
-            symbol: String::new(),
-            timeframe: Timeframe::Daily,
-            bars,
-            capacity: 0,
-        }
+        let mut series = BarSeries::new(String::new(), Timeframe::Daily);
+        series.extend(iter);
+        series
     }
 }
 
@@ -332,4 +620,78 @@ mod tests {
         let volumes = series.volumes();
         assert_eq!(volumes, vec![1000.0, 2000.0]);
     }
+
+    #[test]
+    fn test_columns_follow_capacity() {
+        let mut series = BarSeries::with_capacity("AAPL".to_string(), Timeframe::Daily, 3);
+        for i in 0..10 {
+            let price = 100.0 + i as f64;
+            series.push(Bar::new(i, price, price + 1.0, price - 1.0, price, 1000.0));
+        }
+
+        assert_eq!(series.close_slice(), series.closes().as_slice());
+        assert_eq!(series.high_slice(), &[108.0, 109.0, 110.0]);
+        assert_eq!(series.low_slice(), &[106.0, 107.0, 108.0]);
+        assert_eq!(series.open_slice(), &[107.0, 108.0, 109.0]);
+        assert_eq!(series.volume_slice().len(), 3);
+        let version = series.version();
+        series.clear();
+        assert!(series.close_slice().is_empty());
+        assert_ne!(series.version(), version);
+    }
+
+    #[test]
+    fn test_bar_series_snapshots_round_trip() {
+        let mut series = BarSeries::with_capacity("ES".to_string(), Timeframe::Hour1, 5);
+        series.push(Bar::new(1, 100.0, 101.0, 99.0, 100.5, 1000.0).with_vwap(100.2));
+        series.push(Bar::new(2, 100.5, 102.0, 100.0, 101.5, 2000.0));
+
+        let restored = BarSeries::from_bytes(&series.to_bytes()).unwrap();
+        assert_eq!(restored.symbol, "ES");
+        assert_eq!(restored.timeframe, Timeframe::Hour1);
+        assert_eq!(restored.bars(), series.bars());
+        assert_eq!(restored.close_slice(), &[100.5, 101.5]);
+
+        let json: BarSeries =
+            serde_json::from_str(&serde_json::to_string(&series).unwrap()).unwrap();
+        assert_eq!(json.bars(), series.bars());
+        assert_eq!(json.high_slice(), &[101.0, 102.0]);
+
+        let bytes = series.to_bytes();
+        assert!(BarSeries::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(BarSeries::from_bytes(b"nope").is_err());
+    }
+
+    #[test]
+    fn test_snapshot_capacity_is_not_preallocated() {
+        let mut series = BarSeries::with_capacity("ES".to_string(), Timeframe::Hour1, 5);
+        series.push(Bar::new(1, 100.0, 101.0, 99.0, 100.5, 1000.0));
+        series.push(Bar::new(2, 100.5, 102.0, 100.0, 101.5, 2000.0));
+
+        // Capacity 5, then 2 bars: claim an impossible capacity instead
+        let mut bytes = series.to_bytes();
+        let header: Vec<u8> = [5u64, 2].iter().flat_map(|n| n.to_le_bytes()).collect();
+        let at = bytes.windows(16).position(|w| w == header).unwrap();
+        bytes[at..at + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+        let mut restored = BarSeries::from_bytes(&bytes).unwrap();
+        assert_eq!(restored.bars(), series.bars());
+
+        // So does a count the bytes can't hold
+        let mut huge = bytes.clone();
+        huge[at + 8..at + 16].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(BarSeries::from_bytes(&huge).is_err());
+        huge[at + 8..at + 16].copy_from_slice(&3u64.to_le_bytes());
+        assert!(BarSeries::from_bytes(&huge).is_err());
+        for i in 3..10 {
+            restored.push(Bar::new(i, 100.0, 100.0, 100.0, 100.0, 1000.0));
+        }
+        assert_eq!(restored.len(), 9);
+
+        let json = serde_json::to_string(&series)
+            .unwrap()
+            .replace(r#""capacity":5"#, &format!(r#""capacity":{}"#, u64::MAX));
+        assert!(json.contains(&u64::MAX.to_string()));
+        let restored: BarSeries = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.bars(), series.bars());
+    }
 }
@@ -0,0 +1,412 @@
+//! Option contracts, chains and Black-Scholes pricing.
+
+use chrono::NaiveDate;
+use num_traits::ToPrimitive;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use super::{AssetClass, Instrument};
+
+/// Whether an option gives the right to buy or to sell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OptionRight {
+    Call,
+    Put,
+}
+
+impl std::fmt::Display for OptionRight {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OptionRight::Call => write!(f, "call"),
+            OptionRight::Put => write!(f, "put"),
+        }
+    }
+}
+
+impl FromStr for OptionRight {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "call" | "c" => Ok(OptionRight::Call),
+            "put" | "p" => Ok(OptionRight::Put),
+            _ => Err(format!("Invalid option right: {}", s)),
+        }
+    }
+}
+
+/// A listed option on an underlying symbol.
+///
+/// Prices are per share of the underlying; one contract covers
+/// `multiplier` shares (100 for US equity options).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct OptionContract {
+    pub underlying: String,
+    pub strike: Decimal,
+    /// Last trading day
+    pub expiry: NaiveDate,
+    pub right: OptionRight,
+    #[serde(default = "hundred")]
+    pub multiplier: Decimal,
+}
+
+fn hundred() -> Decimal {
+    dec!(100)
+}
+
+impl OptionContract {
+    /// Create a contract covering 100 shares.
+    pub fn new(
+        underlying: impl Into<String>,
+        strike: Decimal,
+        expiry: NaiveDate,
+        right: OptionRight,
+    ) -> Self {
+        Self {
+            underlying: underlying.into(),
+            strike,
+            expiry,
+            right,
+            multiplier: hundred(),
+        }
+    }
+
+    /// Set the shares one contract covers.
+    pub fn with_multiplier(mut self, multiplier: Decimal) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// OCC symbol, e.g. `AAPL240119C00150000`: underlying, expiry as
+    /// YYMMDD, C or P, and the strike in thousandths padded to 8 digits.
+    pub fn symbol(&self) -> String {
+        let strike = (self.strike * dec!(1000)).trunc().to_u64().unwrap_or(0);
+        let right = match self.right {
+            OptionRight::Call => 'C',
+            OptionRight::Put => 'P',
+        };
+        format!(
+            "{}{}{}{:08}",
+            self.underlying,
+            self.expiry.format("%y%m%d"),
+            right,
+            strike
+        )
+    }
+
+    /// The contract as an instrument, for sizing and rounding orders.
+    pub fn instrument(&self) -> Instrument {
+        Instrument::new(self.symbol(), AssetClass::Option)
+            .with_multiplier(self.multiplier)
+            .with_lot_size(Decimal::ONE)
+            .with_tick_size(dec!(0.01))
+    }
+
+    /// Value per share if exercised with the underlying at `spot`.
+    pub fn intrinsic_value(&self, spot: Decimal) -> Decimal {
+        let value = match self.right {
+            OptionRight::Call => spot - self.strike,
+            OptionRight::Put => self.strike - spot,
+        };
+        value.max(Decimal::ZERO)
+    }
+
+    /// Whether exercising with the underlying at `spot` is worth anything.
+    pub fn is_in_the_money(&self, spot: Decimal) -> bool {
+        self.intrinsic_value(spot) > Decimal::ZERO
+    }
+
+    /// Whether the contract has stopped trading by `date`.
+    pub fn is_expired(&self, date: NaiveDate) -> bool {
+        date > self.expiry
+    }
+
+    /// Time left on `date`, in years of 365 days.
+    pub fn years_to_expiry(&self, date: NaiveDate) -> f64 {
+        (self.expiry - date).num_days().max(0) as f64 / 365.0
+    }
+
+    /// Black-Scholes value per share on `date` (see [`black_scholes`]).
+    pub fn theoretical_price(&self, spot: f64, date: NaiveDate, rate: f64, volatility: f64) -> f64 {
+        black_scholes(
+            self.right,
+            spot,
+            self.strike.to_f64().unwrap_or(0.0),
+            self.years_to_expiry(date),
+            rate,
+            volatility,
+        )
+    }
+}
+
+impl std::fmt::Display for OptionContract {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+
+impl FromStr for OptionContract {
+    type Err = String;
+
+    /// Parse an OCC symbol; the underlying may be padded with spaces.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid option symbol: {}", s);
+        if s.len() < 16 || !s.is_ascii() {
+            return Err(invalid());
+        }
+        let (underlying, tail) = s.split_at(s.len() - 15);
+        let underlying = underlying.trim();
+        if underlying.is_empty() {
+            return Err(invalid());
+        }
+        let expiry = NaiveDate::parse_from_str(&tail[..6], "%y%m%d").map_err(|_| invalid())?;
+        let right = tail[6..7].parse().map_err(|_| invalid())?;
+        let strike = &tail[7..];
+        if !strike.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        let strike = Decimal::from_str(strike).map_err(|_| invalid())? / dec!(1000);
+        Ok(Self::new(underlying, strike.normalize(), expiry, right))
+    }
+}
+
+/// A quote for one contract in a chain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OptionQuote {
+    pub contract: OptionContract,
+    pub bid: Decimal,
+    pub ask: Decimal,
+    /// Annualized implied volatility, as a fraction
+    #[serde(default)]
+    pub implied_volatility: Option<f64>,
+    #[serde(default)]
+    pub open_interest: u64,
+}
+
+impl OptionQuote {
+    /// Midpoint of the bid and ask.
+    pub fn mid(&self) -> Decimal {
+        (self.bid + self.ask) / dec!(2)
+    }
+}
+
+/// The contracts listed on one underlying at a point in time.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OptionChain {
+    pub underlying: String,
+    pub quotes: Vec<OptionQuote>,
+}
+
+impl OptionChain {
+    /// Create an empty chain.
+    pub fn new(underlying: impl Into<String>) -> Self {
+        Self {
+            underlying: underlying.into(),
+            quotes: Vec::new(),
+        }
+    }
+
+    /// A chain priced with Black-Scholes at a single volatility, for
+    /// backtesting where no historical option quotes are available. Bid
+    /// and ask are both the theoretical value rounded to the cent.
+    pub fn theoretical(
+        underlying: impl Into<String>,
+        spot: f64,
+        date: NaiveDate,
+        expiries: &[NaiveDate],
+        strikes: &[Decimal],
+        rate: f64,
+        volatility: f64,
+    ) -> Self {
+        let mut chain = Self::new(underlying);
+        for &expiry in expiries {
+            for &strike in strikes {
+                for right in [OptionRight::Call, OptionRight::Put] {
+                    let contract = OptionContract::new(&chain.underlying, strike, expiry, right);
+                    let price = contract.theoretical_price(spot, date, rate, volatility);
+                    let price = Decimal::try_from(price).unwrap_or_default().round_dp(2);
+                    chain.quotes.push(OptionQuote {
+                        contract,
+                        bid: price,
+                        ask: price,
+                        implied_volatility: Some(volatility),
+                        open_interest: 0,
+                    });
+                }
+            }
+        }
+        chain
+    }
+
+    /// Expiry dates listed, earliest first.
+    pub fn expiries(&self) -> Vec<NaiveDate> {
+        let mut expiries: Vec<NaiveDate> = self.quotes.iter().map(|q| q.contract.expiry).collect();
+        expiries.sort();
+        expiries.dedup();
+        expiries
+    }
+
+    /// Strikes listed for `expiry`, lowest first.
+    pub fn strikes(&self, expiry: NaiveDate) -> Vec<Decimal> {
+        let mut strikes: Vec<Decimal> = self
+            .quotes
+            .iter()
+            .filter(|q| q.contract.expiry == expiry)
+            .map(|q| q.contract.strike)
+            .collect();
+        strikes.sort();
+        strikes.dedup();
+        strikes
+    }
+
+    /// The quote for an exact contract.
+    pub fn get(
+        &self,
+        expiry: NaiveDate,
+        strike: Decimal,
+        right: OptionRight,
+    ) -> Option<&OptionQuote> {
+        self.quotes.iter().find(|q| {
+            q.contract.expiry == expiry && q.contract.strike == strike && q.contract.right == right
+        })
+    }
+
+    /// The quote for `expiry` and `right` whose strike is closest to
+    /// `strike`, preferring the lower strike on a tie.
+    pub fn nearest(
+        &self,
+        expiry: NaiveDate,
+        right: OptionRight,
+        strike: Decimal,
+    ) -> Option<&OptionQuote> {
+        self.quotes
+            .iter()
+            .filter(|q| q.contract.expiry == expiry && q.contract.right == right)
+            .min_by_key(|q| ((q.contract.strike - strike).abs(), q.contract.strike))
+    }
+}
+
+/// Black-Scholes value of a European option, per share.
+///
+/// `years` is the time to expiry; `rate` (continuously compounded) and
+/// `volatility` are annualized fractions. At expiry, or with no
+/// volatility, this is the intrinsic value against the discounted strike.
+pub fn black_scholes(
+    right: OptionRight,
+    spot: f64,
+    strike: f64,
+    years: f64,
+    rate: f64,
+    volatility: f64,
+) -> f64 {
+    let discounted = strike * (-rate * years.max(0.0)).exp();
+    if years <= 0.0 || volatility <= 0.0 || spot <= 0.0 || strike <= 0.0 {
+        return match right {
+            OptionRight::Call => (spot - discounted).max(0.0),
+            OptionRight::Put => (discounted - spot).max(0.0),
+        };
+    }
+    let (d1, d2) = d1_d2(spot, strike, years, rate, volatility);
+    match right {
+        OptionRight::Call => spot * norm_cdf(d1) - discounted * norm_cdf(d2),
+        OptionRight::Put => discounted * norm_cdf(-d2) - spot * norm_cdf(-d1),
+    }
+}
+
+/// Black-Scholes delta: the change in option value per unit change in the
+/// underlying, between 0 and 1 for calls and -1 and 0 for puts.
+pub fn black_scholes_delta(
+    right: OptionRight,
+    spot: f64,
+    strike: f64,
+    years: f64,
+    rate: f64,
+    volatility: f64,
+) -> f64 {
+    if years <= 0.0 || volatility <= 0.0 || spot <= 0.0 || strike <= 0.0 {
+        let discounted = strike * (-rate * years.max(0.0)).exp();
+        return match right {
+            OptionRight::Call if spot > discounted => 1.0,
+            OptionRight::Put if spot < discounted => -1.0,
+            _ => 0.0,
+        };
+    }
+    let (d1, _) = d1_d2(spot, strike, years, rate, volatility);
+    match right {
+        OptionRight::Call => norm_cdf(d1),
+        OptionRight::Put => norm_cdf(d1) - 1.0,
+    }
+}
+
+fn d1_d2(spot: f64, strike: f64, years: f64, rate: f64, volatility: f64) -> (f64, f64) {
+    let spread = volatility * years.sqrt();
+    let d1 = ((spot / strike).ln() + (rate + volatility * volatility / 2.0) * years) / spread;
+    (d1, d1 - spread)
+}
+
+/// Standard normal CDF, via the Abramowitz-Stegun erf approximation
+/// (error below 1.5e-7).
+fn norm_cdf(x: f64) -> f64 {
+    let z = x.abs() / std::f64::consts::SQRT_2;
+    let t = 1.0 / (1.0 + 0.327_591_1 * z);
+    let poly = t
+        * (0.254_829_592
+            + t * (-0.284_496_736
+                + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let erf = 1.0 - poly * (-z * z).exp();
+    if x >= 0.0 {
+        0.5 * (1.0 + erf)
+    } else {
+        0.5 * (1.0 - erf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_occ_symbol_round_trip() {
+        let expiry = NaiveDate::from_ymd_opt(2024, 1, 19).unwrap();
+        let call = OptionContract::new("AAPL", dec!(152.5), expiry, OptionRight::Call);
+        assert_eq!(call.symbol(), "AAPL240119C00152500");
+        assert_eq!(
+            "AAPL  240119C00152500".parse::<OptionContract>().unwrap(),
+            call
+        );
+        assert!("AAPL240119X00152500".parse::<OptionContract>().is_err());
+
+        assert_eq!(call.intrinsic_value(dec!(160)), dec!(7.5));
+        assert!(!call.is_in_the_money(dec!(150)));
+        assert_eq!(call.instrument().notional(dec!(2), dec!(3)), dec!(600));
+
+        let today = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+        let strikes = [dec!(145), dec!(150), dec!(155)];
+        let chain = OptionChain::theoretical("AAPL", 150.0, today, &[expiry], &strikes, 0.0, 0.3);
+        assert_eq!(chain.quotes.len(), 6);
+        let put = chain.nearest(expiry, OptionRight::Put, dec!(152)).unwrap();
+        assert_eq!(put.contract.strike, dec!(150));
+        assert!(put.mid() > Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_black_scholes_matches_reference_values() {
+        // Hull's example: S=42, K=40, r=10%, sigma=20%, six months
+        let call = black_scholes(OptionRight::Call, 42.0, 40.0, 0.5, 0.1, 0.2);
+        let put = black_scholes(OptionRight::Put, 42.0, 40.0, 0.5, 0.1, 0.2);
+        assert!((call - 4.76).abs() < 0.01);
+        assert!((put - 0.81).abs() < 0.01);
+        // Put-call parity
+        assert!((call - put - (42.0 - 40.0 * (-0.05f64).exp())).abs() < 1e-6);
+
+        let delta = black_scholes_delta(OptionRight::Call, 42.0, 40.0, 0.5, 0.1, 0.2);
+        assert!((delta - 0.779).abs() < 0.001);
+        assert_eq!(
+            black_scholes(OptionRight::Put, 42.0, 40.0, 0.0, 0.1, 0.2),
+            0.0
+        );
+    }
+}
@@ -5,6 +5,8 @@ use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use super::OptionContract;
+
 /// Order side (buy or sell).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -161,6 +163,15 @@ pub struct OrderRequest {
     pub client_order_id: Option<String>,
     /// Extended hours trading
     pub extended_hours: bool,
+    /// Bracket stop-loss price, placed as an exit once the order fills
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_loss: Option<Decimal>,
+    /// Bracket take-profit price, placed as an exit once the order fills
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub take_profit: Option<Decimal>,
+    /// Option contract traded, when `symbol` is an option
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contract: Option<OptionContract>,
 }
 
 impl OrderRequest {
@@ -177,6 +188,9 @@ impl OrderRequest {
             time_in_force: TimeInForce::Day,
             client_order_id: None,
             extended_hours: false,
+            stop_loss: None,
+            take_profit: None,
+            contract: None,
         }
     }
 
@@ -198,6 +212,9 @@ impl OrderRequest {
             time_in_force: TimeInForce::Day,
             client_order_id: None,
             extended_hours: false,
+            stop_loss: None,
+            take_profit: None,
+            contract: None,
         }
     }
 
@@ -219,6 +236,9 @@ impl OrderRequest {
             time_in_force: TimeInForce::GTC,
             client_order_id: None,
             extended_hours: false,
+            stop_loss: None,
+            take_profit: None,
+            contract: None,
         }
     }
 
@@ -241,6 +261,9 @@ impl OrderRequest {
             time_in_force: TimeInForce::GTC,
             client_order_id: None,
             extended_hours: false,
+            stop_loss: None,
+            take_profit: None,
+            contract: None,
         }
     }
 
@@ -261,6 +284,30 @@ impl OrderRequest {
         self.extended_hours = true;
         self
     }
+
+    /// Attach a bracket stop-loss exit.
+    pub fn with_stop_loss(mut self, price: Decimal) -> Self {
+        self.stop_loss = Some(price);
+        self
+    }
+
+    /// Attach a bracket take-profit exit.
+    pub fn with_take_profit(mut self, price: Decimal) -> Self {
+        self.take_profit = Some(price);
+        self
+    }
+
+    /// Trade an option contract, setting the symbol to its OCC symbol.
+    pub fn with_contract(mut self, contract: OptionContract) -> Self {
+        self.symbol = contract.symbol();
+        self.contract = Some(contract);
+        self
+    }
+
+    /// Whether the order carries bracket exits.
+    pub fn is_bracket(&self) -> bool {
+        self.stop_loss.is_some() || self.take_profit.is_some()
+    }
 }
 
 /// A fill represents a partial or complete execution of an order.
@@ -325,6 +372,9 @@ pub struct Order {
     pub canceled_at: Option<DateTime<Utc>>,
     /// Extended hours flag
     pub extended_hours: bool,
+    /// Option contract traded, when `symbol` is an option
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contract: Option<OptionContract>,
 }
 
 impl Order {
@@ -356,6 +406,7 @@ impl Order {
             expired_at: None,
             canceled_at: None,
             extended_hours: request.extended_hours,
+            contract: request.contract.clone(),
         }
     }
 
@@ -388,12 +439,13 @@ impl Order {
 
         self.filled_avg_price = Some(total_value / total_qty);
         self.filled_quantity = total_qty;
+        let at = fill.timestamp;
         self.fills.push(fill);
-        self.updated_at = Utc::now();
+        self.updated_at = at;
 
         if self.filled_quantity >= self.quantity {
             self.status = OrderStatus::Filled;
-            self.filled_at = Some(Utc::now());
+            self.filled_at = Some(at);
         } else {
             self.status = OrderStatus::PartiallyFilled;
         }
@@ -5,7 +5,7 @@ use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-use super::{Order, Side};
+use super::{OptionContract, Order, Side};
 
 /// A position in a single security.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,9 +18,9 @@ pub struct Position {
     pub avg_entry_price: Decimal,
     /// Current market price
     pub current_price: Decimal,
-    /// Market value (quantity * current_price)
+    /// Market value (quantity * current_price * multiplier)
     pub market_value: Decimal,
-    /// Cost basis (quantity * avg_entry_price)
+    /// Cost basis (quantity * avg_entry_price * multiplier)
     pub cost_basis: Decimal,
     /// Unrealized profit/loss
     pub unrealized_pnl: Decimal,
@@ -28,6 +28,17 @@ pub struct Position {
     pub unrealized_pnl_percent: Decimal,
     /// Realized profit/loss from closed portions
     pub realized_pnl: Decimal,
+    /// Units of the price one contract is worth (see
+    /// [`Instrument`](super::Instrument))
+    #[serde(default = "one")]
+    pub multiplier: Decimal,
+    /// Option contract held, when `symbol` is an option
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contract: Option<OptionContract>,
+}
+
+fn one() -> Decimal {
+    Decimal::ONE
 }
 
 impl Position {
@@ -48,9 +59,26 @@ impl Position {
             unrealized_pnl: Decimal::ZERO,
             unrealized_pnl_percent: Decimal::ZERO,
             realized_pnl: Decimal::ZERO,
+            multiplier: Decimal::ONE,
+            contract: None,
         }
     }
 
+    /// Set the contract multiplier, revaluing the position.
+    pub fn with_multiplier(mut self, multiplier: Decimal) -> Self {
+        self.multiplier = multiplier;
+        self.cost_basis = self.quantity * self.avg_entry_price * multiplier;
+        self.update_price(self.current_price);
+        self
+    }
+
+    /// Hold an option contract, taking its multiplier.
+    pub fn with_contract(self, contract: OptionContract) -> Self {
+        let mut position = self.with_multiplier(contract.multiplier);
+        position.contract = Some(contract);
+        position
+    }
+
     /// Check if this is a long position.
     pub fn is_long(&self) -> bool {
         self.quantity > Decimal::ZERO
@@ -74,7 +102,7 @@ impl Position {
     /// Update the current market price and recalculate values.
     pub fn update_price(&mut self, price: Decimal) {
         self.current_price = price;
-        self.market_value = self.quantity * price;
+        self.market_value = self.quantity * price * self.multiplier;
         self.unrealized_pnl = self.market_value - self.cost_basis;
 
         if self.cost_basis != Decimal::ZERO {
@@ -113,10 +141,10 @@ impl Position {
             // Calculate realized P&L on the closed portion
             if self.quantity > Decimal::ZERO {
                 // Was long, now selling
-                realized = close_qty * (price - self.avg_entry_price);
+                realized = close_qty * (price - self.avg_entry_price) * self.multiplier;
             } else {
                 // Was short, now buying
-                realized = close_qty * (self.avg_entry_price - price);
+                realized = close_qty * (self.avg_entry_price - price) * self.multiplier;
             }
             self.realized_pnl += realized;
 
@@ -132,7 +160,7 @@ impl Position {
         }
 
         // Update derived values
-        self.cost_basis = self.quantity * self.avg_entry_price;
+        self.cost_basis = self.quantity * self.avg_entry_price * self.multiplier;
         self.update_price(self.current_price);
 
         realized
@@ -198,6 +226,35 @@ impl Portfolio {
         self.positions.values().map(|p| p.market_value).sum()
     }
 
+    /// Combined market value of the long positions.
+    pub fn long_exposure(&self) -> Decimal {
+        self.positions
+            .values()
+            .filter(|p| p.is_long())
+            .map(|p| p.market_value.abs())
+            .sum()
+    }
+
+    /// Combined market value of the short positions, as a positive amount.
+    pub fn short_exposure(&self) -> Decimal {
+        self.positions
+            .values()
+            .filter(|p| p.is_short())
+            .map(|p| p.market_value.abs())
+            .sum()
+    }
+
+    /// Long plus short exposure: everything at risk, whichever way.
+    pub fn gross_exposure(&self) -> Decimal {
+        self.long_exposure() + self.short_exposure()
+    }
+
+    /// Long minus short exposure: the directional bet on the market, with
+    /// shorts offsetting longs.
+    pub fn net_exposure(&self) -> Decimal {
+        self.long_exposure() - self.short_exposure()
+    }
+
     /// Update the equity and related calculations.
     pub fn update_equity(&mut self) {
         let market_value: Decimal = self.positions.values().map(|p| p.market_value).sum();
@@ -322,6 +379,19 @@ mod tests {
         assert!(position.is_flat());
     }
 
+    #[test]
+    fn test_position_multiplier() {
+        // Two E-mini contracts at 50 per point
+        let mut position = Position::new("ES", dec!(2), dec!(5000)).with_multiplier(dec!(50));
+        assert_eq!(position.market_value, dec!(500000));
+        position.update_price(dec!(5010));
+        assert_eq!(position.unrealized_pnl, dec!(1000));
+
+        let realized = position.apply_fill(Side::Sell, dec!(1), dec!(5020));
+        assert_eq!(realized, dec!(1000));
+        assert_eq!(position.market_value, dec!(250500));
+    }
+
     #[test]
     fn test_portfolio_creation() {
         let portfolio = Portfolio::new(dec!(100000));
@@ -348,4 +418,20 @@ mod tests {
         let ret = portfolio.total_return();
         assert_eq!(ret, dec!(20)); // 20% return
     }
+
+    #[test]
+    fn test_portfolio_exposure() {
+        let mut portfolio = Portfolio::new(dec!(100000));
+        for (symbol, quantity) in [("AAPL", dec!(100)), ("MSFT", dec!(-40))] {
+            portfolio.positions.insert(
+                symbol.to_string(),
+                Position::new(symbol, quantity, dec!(100)),
+            );
+        }
+
+        assert_eq!(portfolio.long_exposure(), dec!(10000));
+        assert_eq!(portfolio.short_exposure(), dec!(4000));
+        assert_eq!(portfolio.gross_exposure(), dec!(14000));
+        assert_eq!(portfolio.net_exposure(), dec!(6000));
+    }
 }
@@ -133,6 +133,65 @@ impl SignalMetadata {
     }
 }
 
+/// Indicator keys a strategy puts in its signal metadata.
+///
+/// Declaring them lets analytics and dashboards key on stable names:
+/// every signal carries the required keys, may carry the optional ones,
+/// and carries nothing else.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetadataSchema {
+    /// Keys present on every signal
+    pub required: Vec<String>,
+    /// Keys present on some signals (e.g. entries only)
+    #[serde(default)]
+    pub optional: Vec<String>,
+}
+
+impl MetadataSchema {
+    /// Create a schema from its required and optional keys.
+    pub fn new(required: &[&str], optional: &[&str]) -> Self {
+        Self {
+            required: required.iter().map(|k| k.to_string()).collect(),
+            optional: optional.iter().map(|k| k.to_string()).collect(),
+        }
+    }
+
+    /// All declared keys, required first.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.required
+            .iter()
+            .chain(&self.optional)
+            .map(String::as_str)
+    }
+
+    /// Check metadata against the schema: required keys present, no
+    /// undeclared keys, and every value finite.
+    pub fn validate(&self, metadata: &SignalMetadata) -> Result<(), String> {
+        if let Some(missing) = self
+            .required
+            .iter()
+            .find(|k| !metadata.indicators.contains_key(*k))
+        {
+            return Err(format!("missing indicator '{}'", missing));
+        }
+
+        let mut keys: Vec<&String> = metadata.indicators.keys().collect();
+        keys.sort();
+        for key in keys {
+            if !self.keys().any(|k| k == key) {
+                return Err(format!("undeclared indicator '{}'", key));
+            }
+            if !metadata.indicators[key].is_finite() {
+                return Err(format!(
+                    "indicator '{}' is {}",
+                    key, metadata.indicators[key]
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
 /// A trading signal generated by a strategy.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Signal {
@@ -148,6 +207,11 @@ pub struct Signal {
     pub timestamp: i64,
     /// Confidence level (0.0 to 1.0)
     pub confidence: f64,
+    /// Share of a position to act on (0.0 to 1.0): entries open or add
+    /// this share of a full-size position, exits close this share of the
+    /// open position. `None` acts on the whole position.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fraction: Option<f64>,
     /// Additional metadata
     pub metadata: SignalMetadata,
 }
@@ -167,6 +231,7 @@ impl Signal {
             price,
             timestamp,
             confidence: 1.0,
+            fraction: None,
             metadata: SignalMetadata::default(),
         }
     }
@@ -208,6 +273,18 @@ impl Signal {
         self
     }
 
+    /// Act on only part of a position: scale in with an entry, or take a
+    /// partial exit.
+    pub fn with_fraction(mut self, fraction: f64) -> Self {
+        self.fraction = Some(fraction.clamp(0.0, 1.0));
+        self
+    }
+
+    /// Share of a position the signal acts on, 1.0 for all of it.
+    pub fn fraction(&self) -> f64 {
+        self.fraction.unwrap_or(1.0)
+    }
+
     /// Set the metadata.
     pub fn with_metadata(mut self, metadata: SignalMetadata) -> Self {
         self.metadata = metadata;
@@ -224,6 +301,26 @@ impl Signal {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_metadata_schema_validation() {
+        let schema = MetadataSchema::new(&["rsi"], &["trend"]);
+        let metadata = SignalMetadata::new("test").with_indicator("rsi", 28.0);
+        assert!(schema.validate(&metadata).is_ok());
+        assert!(schema
+            .validate(&metadata.clone().with_indicator("trend", 1.0))
+            .is_ok());
+
+        let missing = SignalMetadata::new("test").with_indicator("trend", 1.0);
+        assert_eq!(
+            schema.validate(&missing),
+            Err("missing indicator 'rsi'".to_string())
+        );
+        let renamed = metadata.clone().with_indicator("rsi_14", 28.0);
+        assert!(schema.validate(&renamed).unwrap_err().contains("rsi_14"));
+        let nan = SignalMetadata::new("test").with_indicator("rsi", f64::NAN);
+        assert!(schema.validate(&nan).is_err());
+    }
+
     #[test]
     fn test_signal_type_classification() {
         assert!(SignalType::Buy.is_entry());
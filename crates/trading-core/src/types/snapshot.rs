@@ -0,0 +1,48 @@
+//! Point-in-time view of a broker account.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use super::{Order, Portfolio, Position};
+
+/// Open orders, positions and cash of a broker account as of one moment.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrokerSnapshot {
+    /// When the snapshot was taken
+    pub taken_at: DateTime<Utc>,
+    /// Account state, including positions and cash
+    pub account: Portfolio,
+    /// Orders still working
+    pub open_orders: Vec<Order>,
+    /// Whether the view was captured atomically. Best-effort snapshots
+    /// are read in several calls and can miss a change made in between.
+    pub atomic: bool,
+}
+
+impl BrokerSnapshot {
+    /// Create a snapshot taken now.
+    pub fn new(account: Portfolio, open_orders: Vec<Order>, atomic: bool) -> Self {
+        Self {
+            taken_at: Utc::now(),
+            account,
+            open_orders,
+            atomic,
+        }
+    }
+
+    /// Available cash.
+    pub fn cash(&self) -> Decimal {
+        self.account.cash
+    }
+
+    /// Open positions.
+    pub fn positions(&self) -> impl Iterator<Item = &Position> {
+        self.account.positions.values()
+    }
+
+    /// Position in `symbol`, if any.
+    pub fn position(&self, symbol: &str) -> Option<&Position> {
+        self.account.positions.get(symbol)
+    }
+}
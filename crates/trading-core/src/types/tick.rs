@@ -0,0 +1,105 @@
+//! Tick-level market data: trades and top-of-book quotes.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::Side;
+
+/// A single trade print.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Tick {
+    /// Unix timestamp in milliseconds
+    pub timestamp: i64,
+    pub price: f64,
+    pub size: f64,
+}
+
+impl Tick {
+    /// Create a new trade tick.
+    pub fn new(timestamp: i64, price: f64, size: f64) -> Self {
+        Self {
+            timestamp,
+            price,
+            size,
+        }
+    }
+
+    /// Get the timestamp as a DateTime.
+    pub fn datetime(&self) -> DateTime<Utc> {
+        DateTime::from_timestamp_millis(self.timestamp)
+            .unwrap_or_else(|| DateTime::from_timestamp(0, 0).unwrap())
+    }
+}
+
+/// Best bid and ask at a point in time.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct QuoteTick {
+    /// Unix timestamp in milliseconds
+    pub timestamp: i64,
+    pub bid: f64,
+    pub ask: f64,
+    #[serde(default)]
+    pub bid_size: f64,
+    #[serde(default)]
+    pub ask_size: f64,
+}
+
+impl QuoteTick {
+    /// Create a new quote with no sizes.
+    pub fn new(timestamp: i64, bid: f64, ask: f64) -> Self {
+        Self {
+            timestamp,
+            bid,
+            ask,
+            bid_size: 0.0,
+            ask_size: 0.0,
+        }
+    }
+
+    /// Set the sizes shown at the bid and ask.
+    pub fn with_sizes(mut self, bid_size: f64, ask_size: f64) -> Self {
+        self.bid_size = bid_size;
+        self.ask_size = ask_size;
+        self
+    }
+
+    /// Get the timestamp as a DateTime.
+    pub fn datetime(&self) -> DateTime<Utc> {
+        DateTime::from_timestamp_millis(self.timestamp)
+            .unwrap_or_else(|| DateTime::from_timestamp(0, 0).unwrap())
+    }
+
+    /// Midpoint of the bid and ask.
+    pub fn mid(&self) -> f64 {
+        (self.bid + self.ask) / 2.0
+    }
+
+    /// Ask less bid.
+    pub fn spread(&self) -> f64 {
+        self.ask - self.bid
+    }
+
+    /// Spread in basis points of the midpoint.
+    pub fn spread_bps(&self) -> f64 {
+        let mid = self.mid();
+        if mid > 0.0 {
+            self.spread() / mid * 10_000.0
+        } else {
+            0.0
+        }
+    }
+
+    /// Price a market order on `side` trades at: the ask for buys, the bid
+    /// for sells.
+    pub fn price_for(&self, side: Side) -> f64 {
+        match side {
+            Side::Buy => self.ask,
+            Side::Sell => self.bid,
+        }
+    }
+
+    /// Whether both sides are quoted and not crossed.
+    pub fn is_valid(&self) -> bool {
+        self.bid > 0.0 && self.ask >= self.bid
+    }
+}
@@ -1,5 +1,6 @@
 //! Timeframe definitions for market data.
 
+use chrono::{DateTime, Datelike, Months, NaiveDate};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::str::FromStr;
@@ -72,6 +73,64 @@ impl Timeframe {
         )
     }
 
+    /// Bars per year, used to annualize returns.
+    ///
+    /// Assumes 252 trading days of 6.5 hours, so intraday bars only count
+    /// regular trading hours.
+    pub fn periods_per_year(&self) -> f64 {
+        match self {
+            Timeframe::Weekly => 52.0,
+            Timeframe::Monthly => 12.0,
+            Timeframe::Daily => 252.0,
+            intraday => 252.0 * 6.5 * 3600.0 / intraday.as_secs() as f64,
+        }
+    }
+
+    /// Start (Unix ms) of the bar period containing `timestamp`.
+    ///
+    /// Periods are aligned to UTC: days at midnight, weeks on Monday and
+    /// months on the 1st.
+    pub fn bucket_start(&self, timestamp: i64) -> i64 {
+        const DAY_MS: i64 = 86_400_000;
+        match self {
+            // 1970-01-01 was a Thursday; shift so weeks start on Monday
+            Timeframe::Weekly => {
+                let offset = 3 * DAY_MS;
+                (timestamp + offset) - (timestamp + offset).rem_euclid(7 * DAY_MS) - offset
+            }
+            Timeframe::Monthly => {
+                let date = DateTime::from_timestamp_millis(timestamp)
+                    .unwrap_or_default()
+                    .date_naive();
+                NaiveDate::from_ymd_opt(date.year(), date.month(), 1)
+                    .and_then(|d| d.and_hms_opt(0, 0, 0))
+                    .map(|d| d.and_utc().timestamp_millis())
+                    .unwrap_or(timestamp)
+            }
+            _ => {
+                let span = self.as_millis() as i64;
+                timestamp - timestamp.rem_euclid(span)
+            }
+        }
+    }
+
+    /// End (Unix ms, exclusive) of the bar period containing `timestamp`.
+    pub fn bucket_end(&self, timestamp: i64) -> i64 {
+        let start = self.bucket_start(timestamp);
+        match self {
+            Timeframe::Monthly => {
+                let date = DateTime::from_timestamp_millis(start)
+                    .unwrap_or_default()
+                    .date_naive();
+                date.checked_add_months(Months::new(1))
+                    .and_then(|d| d.and_hms_opt(0, 0, 0))
+                    .map(|d| d.and_utc().timestamp_millis())
+                    .unwrap_or(start + self.as_millis() as i64)
+            }
+            _ => start + self.as_millis() as i64,
+        }
+    }
+
     /// Get all available timeframes.
     pub fn all() -> &'static [Timeframe] {
         &[
@@ -109,6 +168,10 @@ impl FromStr for Timeframe {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // "1M" is months; lowercase "1m" is minutes
+        if s == "1M" {
+            return Ok(Timeframe::Monthly);
+        }
         match s.to_lowercase().as_str() {
             "1m" | "1min" | "minute" => Ok(Timeframe::Minute1),
             "5m" | "5min" => Ok(Timeframe::Minute5),
@@ -118,7 +181,7 @@ impl FromStr for Timeframe {
             "4h" | "4hour" => Ok(Timeframe::Hour4),
             "1d" | "day" | "daily" => Ok(Timeframe::Daily),
             "1w" | "week" | "weekly" => Ok(Timeframe::Weekly),
-            "1M" | "month" | "monthly" => Ok(Timeframe::Monthly),
+            "month" | "monthly" => Ok(Timeframe::Monthly),
             _ => Err(format!("Invalid timeframe: {}", s)),
         }
     }
@@ -128,6 +191,14 @@ impl FromStr for Timeframe {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_periods_per_year() {
+        assert_eq!(Timeframe::Daily.periods_per_year(), 252.0);
+        assert_eq!(Timeframe::Hour1.periods_per_year(), 1638.0);
+        assert_eq!(Timeframe::Minute1.periods_per_year(), 98280.0);
+        assert_eq!(Timeframe::Weekly.periods_per_year(), 52.0);
+    }
+
     #[test]
     fn test_timeframe_duration() {
         assert_eq!(Timeframe::Minute1.as_secs(), 60);
@@ -148,6 +219,19 @@ mod tests {
         assert_eq!(Timeframe::Daily.to_string(), "1d");
     }
 
+    #[test]
+    fn test_bucket_alignment() {
+        // 2024-01-17 13:45 UTC, a Wednesday
+        let ts = 1_705_499_100_000;
+        assert_eq!(Timeframe::Hour1.bucket_start(ts), 1_705_496_400_000);
+        assert_eq!(Timeframe::Daily.bucket_start(ts), 1_705_449_600_000);
+        // Monday 2024-01-15
+        assert_eq!(Timeframe::Weekly.bucket_start(ts), 1_705_276_800_000);
+        // 2024-01-01 to 2024-02-01
+        assert_eq!(Timeframe::Monthly.bucket_start(ts), 1_704_067_200_000);
+        assert_eq!(Timeframe::Monthly.bucket_end(ts), 1_706_745_600_000);
+    }
+
     #[test]
     fn test_is_intraday() {
         assert!(Timeframe::Minute1.is_intraday());
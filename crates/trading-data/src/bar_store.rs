@@ -0,0 +1,343 @@
+//! Saving downloaded bars: one CSV or Parquet file per symbol, or a shared
+//! SQLite cache.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use trading_core::error::DataError;
+use trading_core::types::{Bar, Timeframe};
+
+/// Format bars are saved in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BarFormat {
+    /// `{SYMBOL}.csv`, readable by `backtest --data`
+    #[default]
+    Csv,
+    /// `{SYMBOL}.parquet` (needs the `parquet` feature)
+    Parquet,
+    /// `bars.sqlite`, keyed by symbol, timeframe and timestamp (needs the
+    /// `sqlite` feature)
+    Sqlite,
+}
+
+impl std::fmt::Display for BarFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BarFormat::Csv => write!(f, "csv"),
+            BarFormat::Parquet => write!(f, "parquet"),
+            BarFormat::Sqlite => write!(f, "sqlite"),
+        }
+    }
+}
+
+impl std::str::FromStr for BarFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "csv" => Ok(BarFormat::Csv),
+            "parquet" => Ok(BarFormat::Parquet),
+            "sqlite" | "sqlite3" => Ok(BarFormat::Sqlite),
+            _ => Err(format!("Invalid bar format: {}", s)),
+        }
+    }
+}
+
+impl BarFormat {
+    /// File `symbol`'s bars are saved to under `dir`.
+    pub fn path(&self, dir: &Path, symbol: &str) -> PathBuf {
+        match self {
+            BarFormat::Csv => dir.join(format!("{}.csv", symbol)),
+            BarFormat::Parquet => dir.join(format!("{}.parquet", symbol)),
+            BarFormat::Sqlite => dir.join("bars.sqlite"),
+        }
+    }
+
+    /// Save `symbol`'s bars under `dir`, creating it if needed, and return
+    /// the file written. CSV and Parquet files are replaced; the SQLite
+    /// cache keeps other bars and overwrites those with the same timestamp.
+    pub fn save(
+        &self,
+        dir: &Path,
+        symbol: &str,
+        timeframe: Timeframe,
+        bars: &[Bar],
+    ) -> Result<PathBuf, DataError> {
+        std::fs::create_dir_all(dir).map_err(|e| DataError::CacheError(e.to_string()))?;
+        let path = self.path(dir, symbol);
+        match self {
+            BarFormat::Csv => write_csv(&path, bars)?,
+            BarFormat::Parquet => write_parquet(&path, bars)?,
+            BarFormat::Sqlite => {
+                write_sqlite(&path, symbol, timeframe, bars)?;
+            }
+        }
+        Ok(path)
+    }
+}
+
+/// Write bars as CSV with a `timestamp,open,high,low,close,volume` header.
+pub fn write_csv(path: &Path, bars: &[Bar]) -> Result<(), DataError> {
+    let cache_error = |e: csv::Error| DataError::CacheError(e.to_string());
+    let mut writer = csv::Writer::from_path(path).map_err(cache_error)?;
+    writer
+        .write_record(["timestamp", "open", "high", "low", "close", "volume"])
+        .map_err(cache_error)?;
+    for bar in bars {
+        writer
+            .write_record([
+                bar.datetime().format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+                bar.open.to_string(),
+                bar.high.to_string(),
+                bar.low.to_string(),
+                bar.close.to_string(),
+                bar.volume.to_string(),
+            ])
+            .map_err(cache_error)?;
+    }
+    writer
+        .flush()
+        .map_err(|e| DataError::CacheError(e.to_string()))
+}
+
+/// Write bars as Parquet, with the timestamp as UTC milliseconds.
+#[cfg(feature = "parquet")]
+pub fn write_parquet(path: &Path, bars: &[Bar]) -> Result<(), DataError> {
+    use arrow_array::{ArrayRef, Float64Array, RecordBatch, TimestampMillisecondArray};
+    use arrow_schema::{DataType, Field, Schema, TimeUnit};
+    use parquet::arrow::ArrowWriter;
+    use std::sync::Arc;
+
+    let cache_error = |e: &dyn std::fmt::Display| DataError::CacheError(e.to_string());
+    let column = |name: &str| Field::new(name, DataType::Float64, false);
+    let schema = Arc::new(Schema::new(vec![
+        Field::new(
+            "timestamp",
+            DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+            false,
+        ),
+        column("open"),
+        column("high"),
+        column("low"),
+        column("close"),
+        column("volume"),
+    ]));
+    let prices = |price: fn(&Bar) -> f64| -> ArrayRef {
+        Arc::new(Float64Array::from_iter_values(bars.iter().map(price)))
+    };
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(
+                TimestampMillisecondArray::from_iter_values(bars.iter().map(|b| b.timestamp))
+                    .with_timezone("UTC"),
+            ),
+            prices(|b| b.open),
+            prices(|b| b.high),
+            prices(|b| b.low),
+            prices(|b| b.close),
+            prices(|b| b.volume),
+        ],
+    )
+    .map_err(|e| cache_error(&e))?;
+
+    let file = std::fs::File::create(path).map_err(|e| cache_error(&e))?;
+    let mut writer = ArrowWriter::try_new(file, schema, None).map_err(|e| cache_error(&e))?;
+    writer.write(&batch).map_err(|e| cache_error(&e))?;
+    writer.close().map_err(|e| cache_error(&e))?;
+    Ok(())
+}
+
+#[cfg(not(feature = "parquet"))]
+pub fn write_parquet(_path: &Path, _bars: &[Bar]) -> Result<(), DataError> {
+    Err(DataError::CacheError(
+        "Parquet output needs the `parquet` feature".to_string(),
+    ))
+}
+
+#[cfg(feature = "sqlite")]
+fn write_sqlite(
+    path: &Path,
+    symbol: &str,
+    timeframe: Timeframe,
+    bars: &[Bar],
+) -> Result<usize, DataError> {
+    SqliteBarStore::open(path)?.write(symbol, timeframe, bars)
+}
+
+#[cfg(not(feature = "sqlite"))]
+fn write_sqlite(
+    _path: &Path,
+    _symbol: &str,
+    _timeframe: Timeframe,
+    _bars: &[Bar],
+) -> Result<usize, DataError> {
+    Err(DataError::CacheError(
+        "SQLite output needs the `sqlite` feature".to_string(),
+    ))
+}
+
+/// SQLite cache of bars for any number of symbols and timeframes.
+#[cfg(feature = "sqlite")]
+pub struct SqliteBarStore {
+    conn: rusqlite::Connection,
+}
+
+#[cfg(feature = "sqlite")]
+impl SqliteBarStore {
+    /// Open the cache at `path`, creating it if needed.
+    pub fn open(path: &Path) -> Result<Self, DataError> {
+        let conn = rusqlite::Connection::open(path).map_err(sqlite_error)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS bars (
+                symbol TEXT NOT NULL,
+                timeframe TEXT NOT NULL,
+                timestamp INTEGER NOT NULL,
+                open REAL NOT NULL,
+                high REAL NOT NULL,
+                low REAL NOT NULL,
+                close REAL NOT NULL,
+                volume REAL NOT NULL,
+                PRIMARY KEY (symbol, timeframe, timestamp)
+            )",
+        )
+        .map_err(sqlite_error)?;
+        Ok(Self { conn })
+    }
+
+    /// Store bars, replacing any with the same timestamp. Returns the
+    /// number written.
+    pub fn write(
+        &mut self,
+        symbol: &str,
+        timeframe: Timeframe,
+        bars: &[Bar],
+    ) -> Result<usize, DataError> {
+        let tx = self.conn.transaction().map_err(sqlite_error)?;
+        {
+            let mut insert = tx
+                .prepare(
+                    "INSERT OR REPLACE INTO bars
+                     (symbol, timeframe, timestamp, open, high, low, close, volume)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                )
+                .map_err(sqlite_error)?;
+            for bar in bars {
+                insert
+                    .execute(rusqlite::params![
+                        symbol,
+                        timeframe.to_string(),
+                        bar.timestamp,
+                        bar.open,
+                        bar.high,
+                        bar.low,
+                        bar.close,
+                        bar.volume
+                    ])
+                    .map_err(sqlite_error)?;
+            }
+        }
+        tx.commit().map_err(sqlite_error)?;
+        Ok(bars.len())
+    }
+
+    /// Stored bars of a symbol and timeframe, oldest first.
+    pub fn read(&self, symbol: &str, timeframe: Timeframe) -> Result<Vec<Bar>, DataError> {
+        let mut query = self
+            .conn
+            .prepare(
+                "SELECT timestamp, open, high, low, close, volume FROM bars
+                 WHERE symbol = ?1 AND timeframe = ?2 ORDER BY timestamp",
+            )
+            .map_err(sqlite_error)?;
+        let rows = query
+            .query_map(rusqlite::params![symbol, timeframe.to_string()], |row| {
+                Ok(Bar::new(
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                    row.get(5)?,
+                ))
+            })
+            .map_err(sqlite_error)?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(sqlite_error)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+fn sqlite_error(e: rusqlite::Error) -> DataError {
+    DataError::CacheError(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CsvDataSource;
+
+    fn bars() -> Vec<Bar> {
+        (0..3)
+            .map(|i| {
+                let price = 100.0 + i as f64;
+                Bar::new(
+                    1_704_153_600_000 + i * 86_400_000,
+                    price,
+                    price + 1.0,
+                    price - 1.0,
+                    price + 0.5,
+                    1e6,
+                )
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_csv_round_trip() {
+        let dir = std::env::temp_dir().join(format!("trading-bars-{}", std::process::id()));
+        let path = BarFormat::Csv
+            .save(&dir, "TEST", Timeframe::Daily, &bars())
+            .unwrap();
+        assert_eq!(path, dir.join("TEST.csv"));
+
+        let loaded = CsvDataSource::new(path.to_str().unwrap())
+            .unwrap()
+            .load_all("TEST", Timeframe::Daily)
+            .await
+            .unwrap();
+        assert_eq!(loaded, bars());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn test_parquet_rows() {
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+
+        let dir = std::env::temp_dir().join(format!("trading-bars-parquet-{}", std::process::id()));
+        let path = BarFormat::Parquet
+            .save(&dir, "TEST", Timeframe::Daily, &bars())
+            .unwrap();
+        let reader = SerializedFileReader::new(std::fs::File::open(&path).unwrap()).unwrap();
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 3);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_sqlite_upserts() {
+        let path = std::env::temp_dir().join(format!("trading-bars-{}.sqlite", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut store = SqliteBarStore::open(&path).unwrap();
+        store.write("TEST", Timeframe::Daily, &bars()).unwrap();
+        let mut revised = bars()[2];
+        revised.close = 200.0;
+        store.write("TEST", Timeframe::Daily, &[revised]).unwrap();
+
+        let stored = store.read("TEST", Timeframe::Daily).unwrap();
+        assert_eq!(stored.len(), 3);
+        assert_eq!(stored[2].close, 200.0);
+        assert!(store.read("TEST", Timeframe::Hour1).unwrap().is_empty());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
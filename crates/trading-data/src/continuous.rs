@@ -0,0 +1,302 @@
+//! Continuous futures series.
+//!
+//! A futures contract trades for a few months, so testing a strategy over
+//! years needs one series stitched from successive contracts. The series
+//! follows the front contract and rolls to the next once the next one
+//! trades more (by volume or open interest), or when the front expires.
+//! The price gap at each roll would show up as a jump no position could
+//! have captured, so earlier bars are back-adjusted to line up with the
+//! contract trading now.
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use trading_core::error::DataError;
+use trading_core::types::Bar;
+
+/// When to roll from one contract to the next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RollRule {
+    /// Once the next contract trades more volume in a bar
+    #[default]
+    Volume,
+    /// Once the next contract has more open interest; contracts without
+    /// open interest data roll at expiry
+    OpenInterest,
+}
+
+impl std::fmt::Display for RollRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RollRule::Volume => write!(f, "volume"),
+            RollRule::OpenInterest => write!(f, "open_interest"),
+        }
+    }
+}
+
+impl std::str::FromStr for RollRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "volume" => Ok(RollRule::Volume),
+            "open_interest" | "open-interest" | "oi" => Ok(RollRule::OpenInterest),
+            _ => Err(format!("Invalid roll rule: {}", s)),
+        }
+    }
+}
+
+/// How bars before a roll are adjusted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackAdjust {
+    /// Leave prices as traded, gaps and all
+    None,
+    /// Shift earlier prices by the gap at each roll, keeping point moves
+    /// (and so P&L per contract) intact
+    #[default]
+    Difference,
+    /// Scale earlier prices by the ratio at each roll, keeping percentage
+    /// moves intact and prices positive
+    Ratio,
+}
+
+impl std::fmt::Display for BackAdjust {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BackAdjust::None => write!(f, "none"),
+            BackAdjust::Difference => write!(f, "difference"),
+            BackAdjust::Ratio => write!(f, "ratio"),
+        }
+    }
+}
+
+impl std::str::FromStr for BackAdjust {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(BackAdjust::None),
+            "difference" | "diff" | "panama" => Ok(BackAdjust::Difference),
+            "ratio" => Ok(BackAdjust::Ratio),
+            _ => Err(format!("Invalid back-adjustment: {}", s)),
+        }
+    }
+}
+
+/// Bars of one futures contract.
+#[derive(Debug, Clone)]
+pub struct ContractBars {
+    /// Contract symbol, e.g. ESH24
+    pub symbol: String,
+    /// Last trading day
+    pub expiry: NaiveDate,
+    pub bars: Vec<Bar>,
+    /// Open interest by bar timestamp
+    pub open_interest: HashMap<i64, f64>,
+}
+
+impl ContractBars {
+    /// Contract expiring on the day of its last bar.
+    pub fn new(symbol: impl Into<String>, mut bars: Vec<Bar>) -> Result<Self, DataError> {
+        bars.sort_by_key(|b| b.timestamp);
+        let expiry = bars
+            .last()
+            .ok_or(DataError::NoDataAvailable)?
+            .datetime()
+            .date_naive();
+        Ok(Self {
+            symbol: symbol.into(),
+            expiry,
+            bars,
+            open_interest: HashMap::new(),
+        })
+    }
+
+    /// Set the last trading day.
+    pub fn with_expiry(mut self, expiry: NaiveDate) -> Self {
+        self.expiry = expiry;
+        self
+    }
+
+    /// Set open interest by bar timestamp.
+    pub fn with_open_interest(
+        mut self,
+        open_interest: impl IntoIterator<Item = (i64, f64)>,
+    ) -> Self {
+        self.open_interest = open_interest.into_iter().collect();
+        self
+    }
+}
+
+/// A switch from one contract to the next.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Roll {
+    /// First bar taken from the new contract (ms)
+    pub timestamp: i64,
+    pub from: String,
+    pub to: String,
+    /// Close of the new contract less the close of the old one
+    pub gap: f64,
+}
+
+/// A series stitched from successive contracts.
+#[derive(Debug, Clone)]
+pub struct ContinuousSeries {
+    pub bars: Vec<Bar>,
+    pub rolls: Vec<Roll>,
+}
+
+/// Stitch `contracts` into one series, rolling by `roll` and adjusting the
+/// bars before each roll by `adjust`.
+///
+/// Contracts are taken in expiry order and the series never rolls back to
+/// an earlier one. The newest contract's prices are left as traded.
+pub fn continuous_series(
+    mut contracts: Vec<ContractBars>,
+    roll: RollRule,
+    adjust: BackAdjust,
+) -> Result<ContinuousSeries, DataError> {
+    contracts.retain(|c| !c.bars.is_empty());
+    if contracts.is_empty() {
+        return Err(DataError::NoDataAvailable);
+    }
+    contracts.sort_by_key(|c| c.expiry);
+    let by_time: Vec<HashMap<i64, &Bar>> = contracts
+        .iter()
+        .map(|c| c.bars.iter().map(|b| (b.timestamp, b)).collect())
+        .collect();
+    let timestamps: BTreeSet<i64> = contracts
+        .iter()
+        .flat_map(|c| c.bars.iter().map(|b| b.timestamp))
+        .collect();
+
+    let activity = |i: usize, ts: i64| -> f64 {
+        match roll {
+            RollRule::Volume => by_time[i].get(&ts).map_or(0.0, |b| b.volume),
+            RollRule::OpenInterest => contracts[i].open_interest.get(&ts).copied().unwrap_or(0.0),
+        }
+    };
+
+    let mut active = 0;
+    let mut stitched: Vec<Bar> = Vec::with_capacity(timestamps.len());
+    let mut rolls: Vec<Roll> = Vec::new();
+    let mut last_close: Option<f64> = None;
+
+    for ts in timestamps {
+        let date = chrono::DateTime::from_timestamp_millis(ts)
+            .map(|dt| dt.date_naive())
+            .unwrap_or(NaiveDate::MIN);
+        let next = active + 1;
+        let due = next < contracts.len()
+            && by_time[next].contains_key(&ts)
+            && (date > contracts[active].expiry || activity(next, ts) > activity(active, ts));
+        if due {
+            let new_close = by_time[next][&ts].close;
+            let old_close = by_time[active].get(&ts).map(|b| b.close).or(last_close);
+            rolls.push(Roll {
+                timestamp: ts,
+                from: contracts[active].symbol.clone(),
+                to: contracts[next].symbol.clone(),
+                gap: old_close.map_or(0.0, |old| new_close - old),
+            });
+            adjust_history(&mut stitched, adjust, new_close, old_close);
+            active = next;
+        }
+        if let Some(bar) = by_time[active].get(&ts) {
+            last_close = Some(bar.close);
+            stitched.push(**bar);
+        }
+    }
+
+    Ok(ContinuousSeries {
+        bars: stitched,
+        rolls,
+    })
+}
+
+/// Line the bars so far up with the contract rolled into.
+fn adjust_history(bars: &mut [Bar], adjust: BackAdjust, new_close: f64, old_close: Option<f64>) {
+    let Some(old_close) = old_close else {
+        return;
+    };
+    match adjust {
+        BackAdjust::None => {}
+        BackAdjust::Difference => {
+            let gap = new_close - old_close;
+            for bar in bars {
+                shift(bar, |p| p + gap);
+            }
+        }
+        BackAdjust::Ratio if old_close > 0.0 => {
+            let ratio = new_close / old_close;
+            for bar in bars {
+                shift(bar, |p| p * ratio);
+            }
+        }
+        BackAdjust::Ratio => {}
+    }
+}
+
+fn shift(bar: &mut Bar, f: impl Fn(f64) -> f64) {
+    bar.open = f(bar.open);
+    bar.high = f(bar.high);
+    bar.low = f(bar.low);
+    bar.close = f(bar.close);
+    bar.vwap = bar.vwap.map(&f);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY_MS: i64 = 86_400_000;
+
+    fn contract(symbol: &str, days: std::ops::Range<i64>, close: f64, volume: f64) -> ContractBars {
+        let bars = days
+            .map(|d| Bar::new(d * DAY_MS, close, close, close, close, volume))
+            .collect();
+        ContractBars::new(symbol, bars).unwrap()
+    }
+
+    #[test]
+    fn test_rolls_on_volume_and_back_adjusts() {
+        // The back month trades 5 points higher and takes over the volume on day 3
+        let front = contract("ESH24", 0..5, 100.0, 1000.0);
+        let mut back = contract("ESM24", 2..8, 105.0, 500.0);
+        back.bars[1].volume = 2000.0;
+
+        let series =
+            continuous_series(vec![back, front], RollRule::Volume, BackAdjust::Difference).unwrap();
+        assert_eq!(series.rolls.len(), 1);
+        assert_eq!(series.rolls[0].timestamp, 3 * DAY_MS);
+        assert_eq!(series.rolls[0].gap, 5.0);
+        assert_eq!(series.bars.len(), 8);
+        assert!(series.bars.iter().all(|b| b.close == 105.0));
+
+        let front = contract("ESH24", 0..5, 100.0, 1000.0);
+        let back = contract("ESM24", 2..8, 110.0, 500.0);
+        let series =
+            continuous_series(vec![front, back], RollRule::Volume, BackAdjust::Ratio).unwrap();
+        // Rolled at expiry, the first bar after the front's last
+        assert_eq!(series.rolls[0].timestamp, 5 * DAY_MS);
+        assert!((series.bars[0].close - 110.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_open_interest_roll() {
+        let front = contract("CLF24", 0..5, 70.0, 1000.0)
+            .with_open_interest((0..5).map(|d| (d * DAY_MS, 100.0 - d as f64 * 20.0)));
+        let back = contract("CLG24", 0..10, 71.0, 10.0)
+            .with_open_interest((0..10).map(|d| (d * DAY_MS, 30.0 + d as f64 * 20.0)));
+
+        let series =
+            continuous_series(vec![front, back], RollRule::OpenInterest, BackAdjust::None).unwrap();
+        // The back month's open interest overtakes on day 2, 70 to 60
+        assert_eq!(series.rolls[0].timestamp, 2 * DAY_MS);
+        assert_eq!(series.bars[1].close, 70.0);
+        assert_eq!(series.bars[2].close, 71.0);
+        assert_eq!("oi".parse::<RollRule>().unwrap(), RollRule::OpenInterest);
+    }
+}
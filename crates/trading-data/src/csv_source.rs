@@ -3,10 +3,12 @@
 use chrono::{NaiveDate, NaiveDateTime};
 use csv::ReaderBuilder;
 use serde::Deserialize;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use trading_core::error::DataError;
 use trading_core::types::{Bar, Timeframe};
 
+use crate::ContractBars;
+
 /// CSV record format.
 #[derive(Debug, Deserialize)]
 struct CsvRecord {
@@ -27,6 +29,8 @@ struct CsvRecord {
     close: f64,
     #[serde(alias = "Volume", alias = "volume", default)]
     volume: f64,
+    #[serde(alias = "OpenInterest", alias = "Open Interest", alias = "oi", default)]
+    open_interest: Option<f64>,
 }
 
 /// CSV data source for historical data.
@@ -45,6 +49,21 @@ impl CsvDataSource {
         })
     }
 
+    /// The CSV file of `symbol` in `dir`: `{symbol}.csv` or
+    /// `{symbol}_daily.csv`, with the symbol as given or in lowercase.
+    pub fn find(dir: &Path, symbol: &str) -> Option<PathBuf> {
+        let lower = symbol.to_lowercase();
+        [
+            format!("{}.csv", symbol),
+            format!("{}.csv", lower),
+            format!("{}_daily.csv", symbol),
+            format!("{}_daily.csv", lower),
+        ]
+        .into_iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.exists())
+    }
+
     /// Load all bars from the CSV file.
     pub async fn load_all(
         &self,
@@ -54,8 +73,30 @@ impl CsvDataSource {
         self.load_from_path(&self.path)
     }
 
+    /// Load a futures contract's bars, with open interest from an
+    /// `open_interest` column where there is one. The contract is taken to
+    /// expire on the day of its last bar.
+    pub fn load_contract(&self, symbol: &str) -> Result<ContractBars, DataError> {
+        let rows = self.read_rows(&self.path)?;
+        let open_interest: Vec<(i64, f64)> = rows
+            .iter()
+            .filter_map(|(bar, oi)| oi.map(|oi| (bar.timestamp, oi)))
+            .collect();
+        let bars = rows.into_iter().map(|(bar, _)| bar).collect();
+        Ok(ContractBars::new(symbol, bars)?.with_open_interest(open_interest))
+    }
+
     /// Load bars from a specific path.
     fn load_from_path(&self, path: &str) -> Result<Vec<Bar>, DataError> {
+        Ok(self
+            .read_rows(path)?
+            .into_iter()
+            .map(|(bar, _)| bar)
+            .collect())
+    }
+
+    /// Bars and open interest, sorted by timestamp.
+    fn read_rows(&self, path: &str) -> Result<Vec<(Bar, Option<f64>)>, DataError> {
         let mut reader = ReaderBuilder::new()
             .has_headers(true)
             .flexible(true)
@@ -69,18 +110,19 @@ impl CsvDataSource {
 
             let timestamp = self.parse_timestamp(&record.date)?;
 
-            bars.push(Bar::new(
+            let bar = Bar::new(
                 timestamp,
                 record.open,
                 record.high,
                 record.low,
                 record.close,
                 record.volume,
-            ));
+            );
+            bars.push((bar, record.open_interest));
         }
 
         // Sort by timestamp
-        bars.sort_by_key(|b| b.timestamp);
+        bars.sort_by_key(|(b, _)| b.timestamp);
 
         Ok(bars)
     }
@@ -141,4 +183,18 @@ mod tests {
         assert!(source.parse_timestamp("1705312800000").is_ok()); // Unix ms
         assert!(source.parse_timestamp("1705312800").is_ok()); // Unix sec
     }
+
+    #[test]
+    fn test_find() {
+        let dir = std::env::temp_dir().join(format!("trading-csv-find-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("spy_daily.csv"), "").unwrap();
+
+        assert_eq!(
+            CsvDataSource::find(&dir, "SPY"),
+            Some(dir.join("spy_daily.csv"))
+        );
+        assert_eq!(CsvDataSource::find(&dir, "QQQ"), None);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -0,0 +1,467 @@
+//! Historical bar downloads from market data providers.
+
+use async_trait::async_trait;
+use chrono::{DateTime, SecondsFormat, Utc};
+use reqwest::{Client, Response};
+use serde::{Deserialize, Serialize};
+use trading_core::error::DataError;
+use trading_core::types::{Bar, Timeframe};
+
+/// Market data provider to download bars from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadSource {
+    /// Alpaca market data (IEX feed), with the broker credentials
+    Alpaca,
+    /// Yahoo Finance chart API, no key needed
+    #[default]
+    Yahoo,
+    /// Polygon.io aggregates, with an API key
+    Polygon,
+}
+
+impl std::fmt::Display for DownloadSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DownloadSource::Alpaca => write!(f, "alpaca"),
+            DownloadSource::Yahoo => write!(f, "yahoo"),
+            DownloadSource::Polygon => write!(f, "polygon"),
+        }
+    }
+}
+
+impl std::str::FromStr for DownloadSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "alpaca" => Ok(DownloadSource::Alpaca),
+            "yahoo" => Ok(DownloadSource::Yahoo),
+            "polygon" => Ok(DownloadSource::Polygon),
+            _ => Err(format!("Invalid download source: {}", s)),
+        }
+    }
+}
+
+/// Provider of historical bars.
+#[async_trait]
+pub trait BarDownloader: Send + Sync {
+    /// Provider name, for logs.
+    fn name(&self) -> &str;
+
+    /// Bars of `symbol` from `start` (inclusive) to `end` (exclusive),
+    /// oldest first, following the provider's pagination.
+    async fn download(
+        &self,
+        symbol: &str,
+        timeframe: Timeframe,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<Bar>, DataError>;
+}
+
+/// Turn a non-success response into an error carrying its body.
+async fn http_error(resp: Response) -> DataError {
+    let status = resp.status();
+    let body = resp.text().await.unwrap_or_default();
+    DataError::ConnectionError(format!("HTTP {}: {}", status, body.trim()))
+}
+
+fn unsupported(source: &str, timeframe: Timeframe) -> DataError {
+    DataError::InvalidTimeframe(format!("{} has no {} bars", source, timeframe))
+}
+
+/// Alpaca's historical stock bars.
+pub struct AlpacaDownloader {
+    client: Client,
+    api_key: String,
+    api_secret: String,
+    base_url: String,
+}
+
+impl AlpacaDownloader {
+    /// Create a downloader with Alpaca API credentials.
+    pub fn new(api_key: impl Into<String>, api_secret: impl Into<String>) -> Self {
+        Self {
+            client: Client::new(),
+            api_key: api_key.into(),
+            api_secret: api_secret.into(),
+            base_url: "https://data.alpaca.markets".to_string(),
+        }
+    }
+
+    /// Use another API endpoint.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct AlpacaBar {
+    t: String,
+    o: f64,
+    h: f64,
+    l: f64,
+    c: f64,
+    v: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct AlpacaBarsPage {
+    #[serde(default)]
+    bars: Option<Vec<AlpacaBar>>,
+    next_page_token: Option<String>,
+}
+
+#[async_trait]
+impl BarDownloader for AlpacaDownloader {
+    fn name(&self) -> &str {
+        "alpaca"
+    }
+
+    async fn download(
+        &self,
+        symbol: &str,
+        timeframe: Timeframe,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<Bar>, DataError> {
+        let timeframe = match timeframe {
+            Timeframe::Minute1 => "1Min",
+            Timeframe::Minute5 => "5Min",
+            Timeframe::Minute15 => "15Min",
+            Timeframe::Minute30 => "30Min",
+            Timeframe::Hour1 => "1Hour",
+            Timeframe::Hour4 => "4Hour",
+            Timeframe::Daily => "1Day",
+            Timeframe::Weekly => "1Week",
+            Timeframe::Monthly => "1Month",
+        };
+        let url = format!("{}/v2/stocks/{}/bars", self.base_url, symbol);
+        let mut bars = Vec::new();
+        let mut page_token: Option<String> = None;
+        loop {
+            let mut params = vec![
+                ("timeframe", timeframe.to_string()),
+                ("start", start.to_rfc3339_opts(SecondsFormat::Secs, true)),
+                ("end", end.to_rfc3339_opts(SecondsFormat::Secs, true)),
+                ("limit", "10000".to_string()),
+                ("feed", "iex".to_string()),
+            ];
+            if let Some(token) = page_token.take() {
+                params.push(("page_token", token));
+            }
+            let resp = self
+                .client
+                .get(&url)
+                .header("APCA-API-KEY-ID", &self.api_key)
+                .header("APCA-API-SECRET-KEY", &self.api_secret)
+                .query(&params)
+                .send()
+                .await
+                .map_err(|e| DataError::ConnectionError(e.to_string()))?;
+            if !resp.status().is_success() {
+                return Err(http_error(resp).await);
+            }
+            let page: AlpacaBarsPage = resp
+                .json()
+                .await
+                .map_err(|e| DataError::ParseError(e.to_string()))?;
+            for bar in page.bars.unwrap_or_default() {
+                let timestamp = DateTime::parse_from_rfc3339(&bar.t)
+                    .map_err(|e| DataError::ParseError(format!("{}: {}", bar.t, e)))?
+                    .timestamp_millis();
+                bars.push(Bar::new(timestamp, bar.o, bar.h, bar.l, bar.c, bar.v));
+            }
+            match page.next_page_token {
+                Some(token) if !token.is_empty() => page_token = Some(token),
+                _ => break,
+            }
+        }
+        Ok(bars)
+    }
+}
+
+/// Yahoo Finance's chart API.
+pub struct YahooDownloader {
+    client: Client,
+    base_url: String,
+}
+
+impl Default for YahooDownloader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl YahooDownloader {
+    /// Create a downloader.
+    pub fn new() -> Self {
+        Self {
+            // Yahoo turns away clients without a user agent
+            client: Client::builder()
+                .user_agent(concat!("trading/", env!("CARGO_PKG_VERSION")))
+                .build()
+                .unwrap_or_default(),
+            base_url: "https://query1.finance.yahoo.com".to_string(),
+        }
+    }
+
+    /// Use another API endpoint.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct YahooResponse {
+    chart: YahooChart,
+}
+
+#[derive(Debug, Deserialize)]
+struct YahooChart {
+    result: Option<Vec<YahooResult>>,
+    error: Option<YahooError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct YahooError {
+    description: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct YahooResult {
+    #[serde(default)]
+    timestamp: Vec<i64>,
+    indicators: YahooIndicators,
+}
+
+#[derive(Debug, Deserialize)]
+struct YahooIndicators {
+    quote: Vec<YahooQuote>,
+}
+
+/// Columns of prices; gaps (halts, holidays) are null.
+#[derive(Debug, Deserialize)]
+struct YahooQuote {
+    open: Vec<Option<f64>>,
+    high: Vec<Option<f64>>,
+    low: Vec<Option<f64>>,
+    close: Vec<Option<f64>>,
+    volume: Vec<Option<f64>>,
+}
+
+/// Bars of a chart API response, skipping rows with missing prices.
+fn parse_yahoo(symbol: &str, body: &str) -> Result<Vec<Bar>, DataError> {
+    let response: YahooResponse =
+        serde_json::from_str(body).map_err(|e| DataError::ParseError(e.to_string()))?;
+    if let Some(error) = response.chart.error {
+        return Err(DataError::SymbolNotFound(format!(
+            "{}: {}",
+            symbol, error.description
+        )));
+    }
+    let Some(result) = response.chart.result.and_then(|r| r.into_iter().next()) else {
+        return Err(DataError::SymbolNotFound(symbol.to_string()));
+    };
+    let Some(quote) = result.indicators.quote.into_iter().next() else {
+        return Ok(Vec::new());
+    };
+    let bars = result
+        .timestamp
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &timestamp)| {
+            Some(Bar::new(
+                timestamp * 1000,
+                (*quote.open.get(i)?)?,
+                (*quote.high.get(i)?)?,
+                (*quote.low.get(i)?)?,
+                (*quote.close.get(i)?)?,
+                quote.volume.get(i).copied().flatten().unwrap_or(0.0),
+            ))
+        })
+        .collect();
+    Ok(bars)
+}
+
+#[async_trait]
+impl BarDownloader for YahooDownloader {
+    fn name(&self) -> &str {
+        "yahoo"
+    }
+
+    async fn download(
+        &self,
+        symbol: &str,
+        timeframe: Timeframe,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<Bar>, DataError> {
+        let interval = match timeframe {
+            Timeframe::Minute1 => "1m",
+            Timeframe::Minute5 => "5m",
+            Timeframe::Minute15 => "15m",
+            Timeframe::Minute30 => "30m",
+            Timeframe::Hour1 => "60m",
+            Timeframe::Daily => "1d",
+            Timeframe::Weekly => "1wk",
+            Timeframe::Monthly => "1mo",
+            Timeframe::Hour4 => return Err(unsupported("yahoo", timeframe)),
+        };
+        let url = format!("{}/v8/finance/chart/{}", self.base_url, symbol);
+        let resp = self
+            .client
+            .get(&url)
+            .query(&[
+                ("period1", start.timestamp().to_string()),
+                ("period2", end.timestamp().to_string()),
+                ("interval", interval.to_string()),
+                ("events", "history".to_string()),
+            ])
+            .send()
+            .await
+            .map_err(|e| DataError::ConnectionError(e.to_string()))?;
+        // Unknown symbols come back as 404 with an error body
+        if !resp.status().is_success() && resp.status() != reqwest::StatusCode::NOT_FOUND {
+            return Err(http_error(resp).await);
+        }
+        let body = resp
+            .text()
+            .await
+            .map_err(|e| DataError::ConnectionError(e.to_string()))?;
+        parse_yahoo(symbol, &body)
+    }
+}
+
+/// Polygon.io's aggregate bars.
+pub struct PolygonDownloader {
+    client: Client,
+    api_key: String,
+    base_url: String,
+}
+
+impl PolygonDownloader {
+    /// Create a downloader with a Polygon API key.
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            client: Client::new(),
+            api_key: api_key.into(),
+            base_url: "https://api.polygon.io".to_string(),
+        }
+    }
+
+    /// Use another API endpoint.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PolygonBar {
+    t: i64,
+    o: f64,
+    h: f64,
+    l: f64,
+    c: f64,
+    #[serde(default)]
+    v: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct PolygonPage {
+    #[serde(default)]
+    results: Vec<PolygonBar>,
+    next_url: Option<String>,
+}
+
+#[async_trait]
+impl BarDownloader for PolygonDownloader {
+    fn name(&self) -> &str {
+        "polygon"
+    }
+
+    async fn download(
+        &self,
+        symbol: &str,
+        timeframe: Timeframe,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<Bar>, DataError> {
+        let (multiplier, timespan) = match timeframe {
+            Timeframe::Minute1 => (1, "minute"),
+            Timeframe::Minute5 => (5, "minute"),
+            Timeframe::Minute15 => (15, "minute"),
+            Timeframe::Minute30 => (30, "minute"),
+            Timeframe::Hour1 => (1, "hour"),
+            Timeframe::Hour4 => (4, "hour"),
+            Timeframe::Daily => (1, "day"),
+            Timeframe::Weekly => (1, "week"),
+            Timeframe::Monthly => (1, "month"),
+        };
+        // The range is inclusive at both ends
+        let mut url = format!(
+            "{}/v2/aggs/ticker/{}/range/{}/{}/{}/{}?adjusted=true&sort=asc&limit=50000",
+            self.base_url,
+            symbol,
+            multiplier,
+            timespan,
+            start.timestamp_millis(),
+            end.timestamp_millis() - 1
+        );
+        let mut bars = Vec::new();
+        loop {
+            let resp = self
+                .client
+                .get(&url)
+                .query(&[("apiKey", &self.api_key)])
+                .send()
+                .await
+                .map_err(|e| DataError::ConnectionError(e.to_string()))?;
+            if !resp.status().is_success() {
+                return Err(http_error(resp).await);
+            }
+            let page: PolygonPage = resp
+                .json()
+                .await
+                .map_err(|e| DataError::ParseError(e.to_string()))?;
+            bars.extend(
+                page.results
+                    .into_iter()
+                    .map(|bar| Bar::new(bar.t, bar.o, bar.h, bar.l, bar.c, bar.v)),
+            );
+            match page.next_url {
+                Some(next) => url = next,
+                None => break,
+            }
+        }
+        Ok(bars)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_yahoo_skips_missing_rows() {
+        let body = r#"{"chart":{"result":[{"timestamp":[1704205800,1704292200,1704378600],
+            "indicators":{"quote":[{"open":[187.15,null,182.15],"high":[188.44,null,183.09],
+            "low":[183.89,null,180.88],"close":[185.64,null,181.91],
+            "volume":[82488700,null,71983600]}]}}],"error":null}}"#;
+        let bars = parse_yahoo("AAPL", body).unwrap();
+        assert_eq!(bars.len(), 2);
+        assert_eq!(bars[0].timestamp, 1_704_205_800_000);
+        assert_eq!(bars[1].close, 181.91);
+
+        let missing = r#"{"chart":{"result":null,"error":{"code":"Not Found",
+            "description":"No data found, symbol may be delisted"}}}"#;
+        assert!(matches!(
+            parse_yahoo("NOPE", missing),
+            Err(DataError::SymbolNotFound(_))
+        ));
+    }
+}
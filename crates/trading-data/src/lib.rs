@@ -1,10 +1,28 @@
 //! Data sources for trading.
 
+mod bar_store;
 mod cache;
+mod continuous;
 mod csv_source;
+#[cfg(not(target_arch = "wasm32"))]
+mod download;
+mod merge;
+mod resample;
 
+#[cfg(feature = "sqlite")]
+pub use bar_store::SqliteBarStore;
+pub use bar_store::{write_csv, write_parquet, BarFormat};
 pub use cache::DataCache;
+pub use continuous::{
+    continuous_series, BackAdjust, ContinuousSeries, ContractBars, Roll, RollRule,
+};
 pub use csv_source::CsvDataSource;
+#[cfg(not(target_arch = "wasm32"))]
+pub use download::{
+    AlpacaDownloader, BarDownloader, DownloadSource, PolygonDownloader, YahooDownloader,
+};
+pub use merge::{dedup_bars, merge_bars, DuplicateBarPolicy, MergeSummary};
+pub use resample::resample_bars;
 
 use trading_core::error::DataError;
 use trading_core::types::{Bar, Timeframe};
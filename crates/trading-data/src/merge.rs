@@ -0,0 +1,215 @@
+//! Merging bars from multiple sources.
+//!
+//! Bars for the same symbol and timestamp can arrive more than once, either
+//! from overlapping files or from several providers/exchanges. The merge
+//! policy decides deterministically which bar survives.
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+use trading_core::types::Bar;
+
+/// How bars sharing a timestamp are combined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateBarPolicy {
+    /// Keep the bar from the highest-priority source (earliest in the list)
+    #[default]
+    Priority,
+    /// Volume-weight open/close/vwap, take the extreme high/low and sum volume
+    VolumeWeighted,
+}
+
+impl std::str::FromStr for DuplicateBarPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "priority" => Ok(DuplicateBarPolicy::Priority),
+            "volume_weighted" | "volume-weighted" | "vwap" => {
+                Ok(DuplicateBarPolicy::VolumeWeighted)
+            }
+            _ => Err(format!("Invalid duplicate bar policy: {}", s)),
+        }
+    }
+}
+
+/// Outcome of a merge.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MergeSummary {
+    /// Bars dropped or folded into another bar
+    pub duplicates: usize,
+    /// Timestamps where the duplicate bars disagreed on prices or volume
+    pub conflicts: usize,
+}
+
+/// Merge bar sources for one symbol into a single sorted, de-duplicated series.
+///
+/// `sources` are ordered by priority, highest first. Conflicting duplicates
+/// are logged at warn level.
+pub fn merge_bars(
+    symbol: &str,
+    sources: Vec<Vec<Bar>>,
+    policy: DuplicateBarPolicy,
+) -> (Vec<Bar>, MergeSummary) {
+    // Tag each bar with its source rank so the sort is deterministic
+    let mut tagged: Vec<(usize, Bar)> = sources
+        .into_iter()
+        .enumerate()
+        .flat_map(|(rank, bars)| bars.into_iter().map(move |bar| (rank, bar)))
+        .collect();
+    tagged.sort_by_key(|(rank, bar)| (bar.timestamp, *rank));
+
+    let mut merged: Vec<Bar> = Vec::with_capacity(tagged.len());
+    let mut summary = MergeSummary::default();
+    let mut group: Vec<Bar> = Vec::new();
+
+    let mut flush = |group: &mut Vec<Bar>, merged: &mut Vec<Bar>| {
+        if group.is_empty() {
+            return;
+        }
+        if group.len() > 1 {
+            summary.duplicates += group.len() - 1;
+            let first = group[0];
+            if group.iter().any(|bar| !same_bar(bar, &first)) {
+                summary.conflicts += 1;
+                warn!(
+                    "{}: {} conflicting bars at {}, resolving with {:?}",
+                    symbol,
+                    group.len(),
+                    first.timestamp,
+                    policy
+                );
+            } else {
+                debug!(
+                    "{}: dropping {} identical duplicate bars at {}",
+                    symbol,
+                    group.len() - 1,
+                    first.timestamp
+                );
+            }
+        }
+        merged.push(resolve(group, policy));
+        group.clear();
+    };
+
+    for (_, bar) in tagged {
+        if group
+            .last()
+            .is_some_and(|last| last.timestamp != bar.timestamp)
+        {
+            flush(&mut group, &mut merged);
+        }
+        group.push(bar);
+    }
+    flush(&mut group, &mut merged);
+
+    (merged, summary)
+}
+
+/// De-duplicate a single series (equivalent to merging one source).
+pub fn dedup_bars(
+    symbol: &str,
+    bars: Vec<Bar>,
+    policy: DuplicateBarPolicy,
+) -> (Vec<Bar>, MergeSummary) {
+    merge_bars(symbol, vec![bars], policy)
+}
+
+fn same_bar(a: &Bar, b: &Bar) -> bool {
+    a.open == b.open
+        && a.high == b.high
+        && a.low == b.low
+        && a.close == b.close
+        && a.volume == b.volume
+}
+
+fn resolve(group: &[Bar], policy: DuplicateBarPolicy) -> Bar {
+    let first = group[0];
+    if group.len() == 1 {
+        return first;
+    }
+
+    match policy {
+        DuplicateBarPolicy::Priority => first,
+        DuplicateBarPolicy::VolumeWeighted => {
+            let volume: f64 = group.iter().map(|b| b.volume).sum();
+            let weighted = |f: fn(&Bar) -> f64| {
+                if volume > 0.0 {
+                    group.iter().map(|b| f(b) * b.volume).sum::<f64>() / volume
+                } else {
+                    group.iter().map(f).sum::<f64>() / group.len() as f64
+                }
+            };
+            let vwap = if group.iter().all(|b| b.vwap.is_some()) {
+                Some(weighted(|b| b.vwap.unwrap_or(b.close)))
+            } else {
+                None
+            };
+            Bar {
+                timestamp: first.timestamp,
+                open: weighted(|b| b.open),
+                high: group.iter().map(|b| b.high).fold(f64::MIN, f64::max),
+                low: group.iter().map(|b| b.low).fold(f64::MAX, f64::min),
+                close: weighted(|b| b.close),
+                volume,
+                vwap,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(ts: i64, close: f64, volume: f64) -> Bar {
+        Bar::new(ts, close, close + 1.0, close - 1.0, close, volume)
+    }
+
+    #[test]
+    fn test_priority_prefers_first_source() {
+        let primary = vec![bar(1, 100.0, 10.0), bar(2, 101.0, 10.0)];
+        let secondary = vec![bar(2, 105.0, 30.0), bar(3, 102.0, 10.0)];
+
+        let (bars, summary) = merge_bars(
+            "TEST",
+            vec![primary, secondary],
+            DuplicateBarPolicy::Priority,
+        );
+
+        assert_eq!(bars.len(), 3);
+        assert_eq!(bars[1].close, 101.0);
+        assert_eq!(summary.duplicates, 1);
+        assert_eq!(summary.conflicts, 1);
+    }
+
+    #[test]
+    fn test_volume_weighted_merge() {
+        let (bars, summary) = merge_bars(
+            "TEST",
+            vec![vec![bar(1, 100.0, 10.0)], vec![bar(1, 104.0, 30.0)]],
+            DuplicateBarPolicy::VolumeWeighted,
+        );
+
+        assert_eq!(bars.len(), 1);
+        assert!((bars[0].close - 103.0).abs() < 1e-9);
+        assert_eq!(bars[0].high, 105.0);
+        assert_eq!(bars[0].low, 99.0);
+        assert_eq!(bars[0].volume, 40.0);
+        assert_eq!(summary.conflicts, 1);
+    }
+
+    #[test]
+    fn test_identical_duplicates_are_not_conflicts() {
+        let (bars, summary) = dedup_bars(
+            "TEST",
+            vec![bar(2, 100.0, 10.0), bar(1, 99.0, 10.0), bar(2, 100.0, 10.0)],
+            DuplicateBarPolicy::Priority,
+        );
+
+        assert_eq!(bars.len(), 2);
+        assert_eq!(bars[0].timestamp, 1);
+        assert_eq!(summary.duplicates, 1);
+        assert_eq!(summary.conflicts, 0);
+    }
+}
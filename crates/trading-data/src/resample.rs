@@ -0,0 +1,83 @@
+//! Resampling bars to a longer timeframe.
+
+use trading_core::types::{Bar, Timeframe};
+
+/// Aggregate `bars` into bars of `timeframe`.
+///
+/// Bars are grouped by the `timeframe` period they start in; each group
+/// becomes one bar stamped with the period start. The last period is kept
+/// even if the data ends before it does. VWAP is volume-weighted when every
+/// bar in the period has one.
+pub fn resample_bars(bars: &[Bar], timeframe: Timeframe) -> Vec<Bar> {
+    let mut bars = bars.to_vec();
+    bars.sort_by_key(|b| b.timestamp);
+
+    let mut resampled: Vec<Bar> = Vec::new();
+    // Volume-weighted VWAP sum of the period being built, if every bar had one
+    let mut vwap_sum: Option<f64> = None;
+
+    for bar in bars {
+        let start = timeframe.bucket_start(bar.timestamp);
+        match resampled.last_mut() {
+            Some(current) if current.timestamp == start => {
+                current.high = current.high.max(bar.high);
+                current.low = current.low.min(bar.low);
+                current.close = bar.close;
+                current.volume += bar.volume;
+                vwap_sum = vwap_sum.zip(bar.vwap).map(|(sum, v)| sum + v * bar.volume);
+                current.vwap = vwap_sum
+                    .filter(|_| current.volume > 0.0)
+                    .map(|sum| sum / current.volume);
+            }
+            _ => {
+                vwap_sum = bar.vwap.map(|v| v * bar.volume);
+                resampled.push(Bar {
+                    timestamp: start,
+                    ..bar
+                });
+            }
+        }
+    }
+
+    resampled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR_MS: i64 = 3_600_000;
+
+    #[test]
+    fn test_resample_to_longer_timeframe() {
+        let bars: Vec<Bar> = (0..6)
+            .map(|i| {
+                let price = 100.0 + i as f64;
+                Bar::new(
+                    i * HOUR_MS,
+                    price,
+                    price + 2.0,
+                    price - 1.0,
+                    price + 1.0,
+                    10.0,
+                )
+                .with_vwap(price)
+            })
+            .collect();
+
+        let h4 = resample_bars(&bars, Timeframe::Hour4);
+        assert_eq!(h4.len(), 2);
+        assert_eq!(h4[0].timestamp, 0);
+        assert_eq!(h4[0].open, 100.0);
+        assert_eq!(h4[0].high, 105.0);
+        assert_eq!(h4[0].low, 99.0);
+        assert_eq!(h4[0].close, 104.0);
+        assert_eq!(h4[0].volume, 40.0);
+        assert!((h4[0].vwap.unwrap() - 101.5).abs() < 1e-10);
+
+        // The trailing partial period is kept
+        assert_eq!(h4[1].timestamp, 4 * HOUR_MS);
+        assert_eq!(h4[1].close, 106.0);
+        assert_eq!(h4[1].volume, 20.0);
+    }
+}
@@ -1,8 +1,11 @@
 //! Benchmarks for indicator implementations.
 
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
-use trading_core::traits::Indicator;
-use trading_indicators::{simd, Ema, Rsi, Sma};
+use trading_core::traits::{Indicator, StreamingIndicator};
+use trading_indicators::{
+    simd, Ema, Rsi, Sma, StreamingBollinger, StreamingEma, StreamingMacd, StreamingRsi,
+    StreamingWma,
+};
 
 fn generate_test_data(size: usize) -> Vec<f64> {
     (0..size)
@@ -43,6 +46,15 @@ fn benchmark_ema(c: &mut Criterion) {
         group.bench_with_input(BenchmarkId::new("simd", size), &data, |b, data| {
             b.iter(|| simd::ema_simd(black_box(data), black_box(20)))
         });
+
+        group.bench_with_input(BenchmarkId::new("streaming", size), &data, |b, data| {
+            b.iter(|| {
+                let mut ema = StreamingEma::new(20);
+                for &value in data {
+                    black_box(ema.update(black_box(value)));
+                }
+            })
+        });
     }
 
     group.finish();
@@ -62,6 +74,15 @@ fn benchmark_rsi(c: &mut Criterion) {
         group.bench_with_input(BenchmarkId::new("simd", size), &data, |b, data| {
             b.iter(|| simd::rsi_simd(black_box(data), black_box(14)))
         });
+
+        group.bench_with_input(BenchmarkId::new("streaming", size), &data, |b, data| {
+            b.iter(|| {
+                let mut rsi = StreamingRsi::new(14);
+                for &value in data {
+                    black_box(rsi.update(black_box(value)));
+                }
+            })
+        });
     }
 
     group.finish();
@@ -81,11 +102,100 @@ fn benchmark_std_dev(c: &mut Criterion) {
     group.finish();
 }
 
+fn benchmark_wma(c: &mut Criterion) {
+    let mut group = c.benchmark_group("WMA");
+
+    for size in [1000, 10000, 100000].iter() {
+        let data = generate_test_data(*size);
+
+        group.bench_with_input(BenchmarkId::new("simd", size), &data, |b, data| {
+            b.iter(|| simd::wma_simd(black_box(data), black_box(20)))
+        });
+
+        group.bench_with_input(BenchmarkId::new("streaming", size), &data, |b, data| {
+            b.iter(|| {
+                let mut wma = StreamingWma::new(20);
+                for &value in data {
+                    black_box(wma.update(black_box(value)));
+                }
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn benchmark_atr(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ATR");
+
+    for size in [1000, 10000, 100000].iter() {
+        let close = generate_test_data(*size);
+        let high: Vec<f64> = close.iter().map(|c| c + 1.0).collect();
+        let low: Vec<f64> = close.iter().map(|c| c - 1.0).collect();
+
+        group.bench_with_input(BenchmarkId::new("simd", size), &close, |b, close| {
+            b.iter(|| simd::atr_simd(black_box(&high), black_box(&low), close, 14))
+        });
+    }
+
+    group.finish();
+}
+
+fn benchmark_bollinger(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Bollinger");
+
+    for size in [1000, 10000, 100000].iter() {
+        let data = generate_test_data(*size);
+
+        group.bench_with_input(BenchmarkId::new("simd", size), &data, |b, data| {
+            b.iter(|| simd::bollinger_simd(black_box(data), black_box(20), 2.0))
+        });
+
+        group.bench_with_input(BenchmarkId::new("streaming", size), &data, |b, data| {
+            b.iter(|| {
+                let mut bands = StreamingBollinger::new(20, 2.0);
+                for &value in data {
+                    black_box(bands.update(black_box(value)));
+                }
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn benchmark_macd(c: &mut Criterion) {
+    let mut group = c.benchmark_group("MACD");
+
+    for size in [1000, 10000, 100000].iter() {
+        let data = generate_test_data(*size);
+
+        group.bench_with_input(BenchmarkId::new("simd", size), &data, |b, data| {
+            b.iter(|| simd::macd_simd(black_box(data), 12, 26, 9))
+        });
+
+        group.bench_with_input(BenchmarkId::new("streaming", size), &data, |b, data| {
+            b.iter(|| {
+                let mut macd = StreamingMacd::new();
+                for &value in data {
+                    black_box(macd.update(black_box(value)));
+                }
+            })
+        });
+    }
+
+    group.finish();
+}
+
 criterion_group!(
     benches,
     benchmark_sma,
     benchmark_ema,
     benchmark_rsi,
-    benchmark_std_dev
+    benchmark_std_dev,
+    benchmark_wma,
+    benchmark_atr,
+    benchmark_bollinger,
+    benchmark_macd
 );
 criterion_main!(benches);
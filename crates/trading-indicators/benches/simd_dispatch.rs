@@ -0,0 +1,98 @@
+//! Benchmarks comparing the SIMD kernel levels the CPU supports.
+//!
+//! Each reduction runs once per available level; the `portable` rows are the
+//! `wide` baseline. Build with `--features avx512` to include AVX-512.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use trading_indicators::simd::{SimdKernels, SimdLevel};
+
+fn generate_test_data(size: usize) -> Vec<f64> {
+    (0..size)
+        .map(|i| 100.0 + (i as f64 * 0.1).sin() * 10.0)
+        .collect()
+}
+
+fn available_kernels() -> Vec<SimdKernels> {
+    SimdLevel::ALL
+        .into_iter()
+        .filter_map(SimdKernels::for_level)
+        .collect()
+}
+
+fn benchmark_sum(c: &mut Criterion) {
+    let mut group = c.benchmark_group("simd_sum");
+
+    for size in [1000, 100000] {
+        let data = generate_test_data(size);
+        for kernels in available_kernels() {
+            group.bench_with_input(
+                BenchmarkId::new(kernels.level.to_string(), size),
+                &data,
+                |b, data| b.iter(|| (kernels.sum)(black_box(data))),
+            );
+        }
+    }
+
+    group.finish();
+}
+
+fn benchmark_dot(c: &mut Criterion) {
+    let mut group = c.benchmark_group("simd_dot");
+
+    for size in [1000, 100000] {
+        let a = generate_test_data(size);
+        let b = generate_test_data(size);
+        for kernels in available_kernels() {
+            group.bench_with_input(
+                BenchmarkId::new(kernels.level.to_string(), size),
+                &size,
+                |bench, _| bench.iter(|| (kernels.dot)(black_box(&a), black_box(&b))),
+            );
+        }
+    }
+
+    group.finish();
+}
+
+fn benchmark_minmax(c: &mut Criterion) {
+    let mut group = c.benchmark_group("simd_minmax");
+
+    for size in [1000, 100000] {
+        let data = generate_test_data(size);
+        for kernels in available_kernels() {
+            group.bench_with_input(
+                BenchmarkId::new(kernels.level.to_string(), size),
+                &data,
+                |b, data| b.iter(|| (kernels.minmax)(black_box(data))),
+            );
+        }
+    }
+
+    group.finish();
+}
+
+fn benchmark_sum_sq_dev(c: &mut Criterion) {
+    let mut group = c.benchmark_group("simd_sum_sq_dev");
+
+    for size in [1000, 100000] {
+        let data = generate_test_data(size);
+        for kernels in available_kernels() {
+            group.bench_with_input(
+                BenchmarkId::new(kernels.level.to_string(), size),
+                &data,
+                |b, data| b.iter(|| (kernels.sum_sq_dev)(black_box(data), black_box(100.0))),
+            );
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    benchmark_sum,
+    benchmark_dot,
+    benchmark_minmax,
+    benchmark_sum_sq_dev
+);
+criterion_main!(benches);
@@ -0,0 +1,196 @@
+//! Caching indicator results per series.
+//!
+//! Several strategies (or one strategy asking twice) often want the same
+//! RSI or moving average of the same bars within one bar. An
+//! [`IndicatorCache`] keeps the last values computed for each series and
+//! indicator, keyed by [`BarSeries::version`], and hands them back until
+//! the series changes.
+//!
+//! ```
+//! use trading_core::types::{Bar, BarSeries, Timeframe};
+//! use trading_indicators::{IndicatorCache, Rsi};
+//!
+//! let mut series = BarSeries::new("AAPL".to_string(), Timeframe::Daily);
+//! for i in 0..30 {
+//!     let price = 100.0 + (i as f64 * 0.4).sin() * 3.0;
+//!     series.push(Bar::new(i, price, price, price, price, 1000.0));
+//! }
+//!
+//! let mut cache = IndicatorCache::new();
+//! let first = *cache.calculate(&series, &Rsi::new(14)).last().unwrap();
+//! let again = *cache.calculate(&series, &Rsi::new(14)).last().unwrap();
+//! assert_eq!(first, again);
+//! assert_eq!((cache.hits(), cache.misses()), (1, 1));
+//! ```
+
+use std::any::Any;
+use std::collections::HashMap;
+use trading_core::traits::Indicator;
+use trading_core::types::BarSeries;
+
+/// Values computed from one version of a series.
+struct Entry {
+    version: u64,
+    values: Box<dyn Any + Send + Sync>,
+}
+
+/// Last computed indicator values per series and indicator id.
+#[derive(Default)]
+pub struct IndicatorCache {
+    /// Entries by symbol, then indicator id
+    entries: HashMap<String, HashMap<String, Entry>>,
+    hits: u64,
+    misses: u64,
+}
+
+impl std::fmt::Debug for IndicatorCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IndicatorCache")
+            .field("entries", &self.len())
+            .field("hits", &self.hits)
+            .field("misses", &self.misses)
+            .finish()
+    }
+}
+
+impl IndicatorCache {
+    /// Create an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Values of indicator `id` over `series`, computed by `compute` only if
+    /// nothing is cached for the series' current version.
+    ///
+    /// `id` must identify the computation, parameters included, e.g.
+    /// `"BB(20,2)"`.
+    pub fn get_or_compute<T: Send + Sync + 'static>(
+        &mut self,
+        series: &BarSeries,
+        id: &str,
+        compute: impl FnOnce(&BarSeries) -> Vec<T>,
+    ) -> &[T] {
+        if !self.entries.contains_key(&series.symbol) {
+            self.entries.insert(series.symbol.clone(), HashMap::new());
+        }
+        let slots = self
+            .entries
+            .get_mut(&series.symbol)
+            .expect("inserted above");
+
+        let version = series.version();
+        let fresh = slots
+            .get(id)
+            .is_some_and(|entry| entry.version == version && entry.values.is::<Vec<T>>());
+        if fresh {
+            self.hits += 1;
+        } else {
+            self.misses += 1;
+            let values = Box::new(compute(series));
+            slots.insert(id.to_string(), Entry { version, values });
+        }
+        slots[id]
+            .values
+            .downcast_ref::<Vec<T>>()
+            .expect("type checked above")
+    }
+
+    /// `indicator` over the series' closes, cached under its
+    /// [`Indicator::cache_key`].
+    pub fn calculate<I>(&mut self, series: &BarSeries, indicator: &I) -> &[I::Output]
+    where
+        I: Indicator,
+        I::Output: Send + Sync + 'static,
+    {
+        self.get_or_compute(series, &indicator.cache_key(), |series| {
+            indicator.calculate(series.close_slice())
+        })
+    }
+
+    /// Number of lookups answered from the cache.
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Number of lookups that had to compute.
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    /// Number of cached results.
+    pub fn len(&self) -> usize {
+        self.entries.values().map(HashMap::len).sum()
+    }
+
+    /// Check if nothing is cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drop every cached result for `symbol`.
+    pub fn invalidate(&mut self, symbol: &str) {
+        self.entries.remove(symbol);
+    }
+
+    /// Drop every cached result.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BollingerBands, Ema, Kama};
+    use trading_core::traits::MultiOutputIndicator;
+    use trading_core::types::{Bar, Timeframe};
+
+    fn series(symbol: &str, n: i64) -> BarSeries {
+        let mut series = BarSeries::new(symbol.to_string(), Timeframe::Daily);
+        for i in 0..n {
+            let price = 100.0 + i as f64;
+            series.push(Bar::new(i, price, price, price, price, 1000.0));
+        }
+        series
+    }
+
+    #[test]
+    fn test_recomputes_only_when_the_series_changes() {
+        let mut aapl = series("AAPL", 30);
+        let msft = series("MSFT", 30);
+        let mut cache = IndicatorCache::new();
+        let ema = Ema::new(10);
+
+        let before = *cache.calculate(&aapl, &ema).last().unwrap();
+        cache.calculate(&aapl, &ema);
+        cache.calculate(&msft, &ema);
+        let bands = BollingerBands::with_params(20, 2.0);
+        cache.get_or_compute(&aapl, "BB(20,2)", |s| bands.calculate(s.close_slice()));
+        assert_eq!((cache.hits(), cache.misses()), (1, 3));
+        assert_eq!(cache.len(), 3);
+
+        aapl.push(Bar::new(30, 200.0, 200.0, 200.0, 200.0, 1000.0));
+        let after = *cache.calculate(&aapl, &ema).last().unwrap();
+        assert!(after > before);
+        assert_eq!(cache.misses(), 4);
+
+        // Same id, different output type: computed again rather than misread
+        let counts = cache.get_or_compute(&aapl, &ema.cache_key(), |s| vec![s.len()]);
+        assert_eq!(counts, &[31]);
+    }
+
+    #[test]
+    fn test_parameters_besides_the_period_are_keyed() {
+        let aapl = series("AAPL", 40);
+        let mut cache = IndicatorCache::new();
+        let fast = Kama::with_smoothing(10, 2, 30);
+        let slow = Kama::with_smoothing(10, 5, 20);
+
+        let a = cache.calculate(&aapl, &fast).to_vec();
+        let b = cache.calculate(&aapl, &slow).to_vec();
+        assert_eq!(cache.misses(), 2);
+        assert_eq!(a, fast.calculate(aapl.close_slice()));
+        assert_eq!(b, slow.calculate(aapl.close_slice()));
+        assert_ne!(a, b);
+    }
+}
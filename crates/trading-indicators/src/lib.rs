@@ -1,18 +1,47 @@
 //! Technical indicators with SIMD optimization.
 //!
 //! This crate provides efficient implementations of common technical indicators:
-//! - Moving averages (SMA, EMA, WMA)
-//! - Momentum indicators (RSI, MACD, Stochastic)
-//! - Volatility indicators (ATR, Bollinger Bands, Standard Deviation)
+//! - Moving averages (SMA, EMA, WMA, Hull, KAMA, DEMA, TEMA)
+//! - Momentum indicators (RSI, MACD, Stochastic, ADX, CCI, Williams %R, ROC, Aroon)
+//! - Volatility indicators (ATR, Bollinger Bands, Donchian Channels, Standard Deviation)
+//! - Candlestick patterns (engulfing, hammer, doji, morning/evening star)
+//!
+//! Streaming variants (`StreamingSma`, `StreamingEma`, `StreamingWma`,
+//! `StreamingHma`, `StreamingKama`, `StreamingDema`, `StreamingTema`, `StreamingRsi`,
+//! `StreamingMacd`, `StreamingStochastic`, `StreamingAtr`,
+//! `StreamingBollinger`, `StreamingStdDev`) update in O(1) per value for
+//! bar-by-bar use. `IndicatorPipeline` chains them into named steps (RSI of
+//! an EMA, standard deviation of returns) evaluated once per bar. The
+//! `rolling` module holds the O(1) window statistics they are built on
+//! (`RollingSum`, `RollingStats`, `RollingMax`, `RollingMin`).
+//! `IndicatorCache` keeps full-series results until the series changes, so
+//! the same indicator asked for twice in one bar is computed once.
 //!
 //! Many indicators have SIMD-optimized implementations for improved performance
 //! during backtesting over large datasets.
 
+pub mod cache;
 pub mod momentum;
 pub mod moving_average;
+pub mod patterns;
+pub mod pipeline;
+pub mod rolling;
 pub mod simd;
 pub mod volatility;
 
-pub use momentum::{Macd, MacdOutput, Rsi, Stochastic, StochasticOutput};
-pub use moving_average::{Ema, Sma, Wma};
-pub use volatility::{Atr, BollingerBands, BollingerOutput, StdDev};
+pub use cache::IndicatorCache;
+pub use momentum::{
+    Adx, AdxOutput, Aroon, AroonOutput, Cci, Macd, MacdOutput, Roc, Rsi, Stochastic,
+    StochasticOutput, StreamingMacd, StreamingRsi, StreamingStochastic, WilliamsR,
+};
+pub use moving_average::{
+    Dema, Ema, Hma, Kama, MaType, Sma, StreamingDema, StreamingEma, StreamingHma, StreamingKama,
+    StreamingSma, StreamingTema, StreamingWma, Tema, Wma,
+};
+pub use patterns::{PatternBias, PatternDetector, PatternEvent, PatternKind};
+pub use pipeline::{IndicatorPipeline, Input, Transform};
+pub use rolling::{RollingMax, RollingMin, RollingStats, RollingSum, RunningStats};
+pub use volatility::{
+    Atr, BollingerBands, BollingerOutput, DonchianChannels, DonchianOutput, StdDev, StreamingAtr,
+    StreamingBollinger, StreamingStdDev,
+};
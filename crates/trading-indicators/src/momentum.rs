@@ -1,7 +1,11 @@
 //! Momentum indicators.
 
 use serde::{Deserialize, Serialize};
-use trading_core::traits::{Indicator, MultiOutputIndicator};
+use std::collections::VecDeque;
+use trading_core::traits::{Indicator, MultiOutputIndicator, StreamingIndicator};
+
+use crate::moving_average::{StreamingEma, StreamingSma};
+use crate::simd;
 
 /// Relative Strength Index (RSI).
 ///
@@ -94,6 +98,91 @@ impl Indicator for Rsi {
     }
 }
 
+/// Streaming RSI using Wilder's smoothing, matching [`Rsi`].
+#[derive(Debug, Clone)]
+pub struct StreamingRsi {
+    period: usize,
+    prev: Option<f64>,
+    /// Price changes seen while seeding the averages
+    count: usize,
+    avg_gain: f64,
+    avg_loss: f64,
+}
+
+impl StreamingRsi {
+    /// Create a new streaming RSI.
+    pub fn new(period: usize) -> Self {
+        assert!(period > 0, "Period must be greater than 0");
+        Self {
+            period,
+            prev: None,
+            count: 0,
+            avg_gain: 0.0,
+            avg_loss: 0.0,
+        }
+    }
+}
+
+impl StreamingIndicator for StreamingRsi {
+    type Output = f64;
+
+    fn update(&mut self, value: f64) -> Option<f64> {
+        let prev = self.prev.replace(value)?;
+        let change = value - prev;
+        let (gain, loss) = if change > 0.0 {
+            (change, 0.0)
+        } else {
+            (0.0, -change)
+        };
+
+        let period = self.period as f64;
+        if self.count < self.period {
+            // Initial averages are simple means of the first `period` changes
+            self.count += 1;
+            self.avg_gain += gain;
+            self.avg_loss += loss;
+            if self.count == self.period {
+                self.avg_gain /= period;
+                self.avg_loss /= period;
+            }
+        } else {
+            self.avg_gain = (self.avg_gain * (period - 1.0) + gain) / period;
+            self.avg_loss = (self.avg_loss * (period - 1.0) + loss) / period;
+        }
+        self.current()
+    }
+
+    fn current(&self) -> Option<f64> {
+        if !self.is_ready() {
+            return None;
+        }
+        if self.avg_loss == 0.0 {
+            Some(100.0)
+        } else {
+            Some(100.0 - (100.0 / (1.0 + self.avg_gain / self.avg_loss)))
+        }
+    }
+
+    fn reset(&mut self) {
+        self.prev = None;
+        self.count = 0;
+        self.avg_gain = 0.0;
+        self.avg_loss = 0.0;
+    }
+
+    fn is_ready(&self) -> bool {
+        self.count >= self.period
+    }
+
+    fn period(&self) -> usize {
+        self.period + 1
+    }
+
+    fn name(&self) -> &str {
+        "RSI"
+    }
+}
+
 /// MACD (Moving Average Convergence Divergence) output.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct MacdOutput {
@@ -168,6 +257,9 @@ impl MultiOutputIndicator for Macd {
         if data.len() < self.slow_period + self.signal_period {
             return vec![];
         }
+        if data.len() >= simd::SIMD_MIN_LEN {
+            return simd::macd_simd(data, self.fast_period, self.slow_period, self.signal_period);
+        }
 
         // Calculate EMAs
         let fast_ema = Self::calculate_ema(data, self.fast_period);
@@ -213,6 +305,80 @@ impl MultiOutputIndicator for Macd {
     }
 }
 
+/// Streaming MACD, matching [`Macd`].
+#[derive(Debug, Clone)]
+pub struct StreamingMacd {
+    fast: StreamingEma,
+    slow: StreamingEma,
+    signal: StreamingEma,
+    current: Option<MacdOutput>,
+}
+
+impl StreamingMacd {
+    /// Create a streaming MACD with default parameters (12, 26, 9).
+    pub fn new() -> Self {
+        Self::with_periods(12, 26, 9)
+    }
+
+    /// Create a streaming MACD with custom periods.
+    pub fn with_periods(fast: usize, slow: usize, signal: usize) -> Self {
+        assert!(fast > 0 && slow > 0 && signal > 0);
+        assert!(fast < slow, "Fast period must be less than slow period");
+        Self {
+            fast: StreamingEma::new(fast),
+            slow: StreamingEma::new(slow),
+            signal: StreamingEma::new(signal),
+            current: None,
+        }
+    }
+}
+
+impl Default for StreamingMacd {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StreamingIndicator for StreamingMacd {
+    type Output = MacdOutput;
+
+    fn update(&mut self, value: f64) -> Option<MacdOutput> {
+        let fast = self.fast.update(value);
+        let slow = self.slow.update(value);
+        let macd = fast? - slow?;
+        let signal = self.signal.update(macd)?;
+        self.current = Some(MacdOutput {
+            macd,
+            signal,
+            histogram: macd - signal,
+        });
+        self.current
+    }
+
+    fn current(&self) -> Option<MacdOutput> {
+        self.current
+    }
+
+    fn reset(&mut self) {
+        self.fast.reset();
+        self.slow.reset();
+        self.signal.reset();
+        self.current = None;
+    }
+
+    fn is_ready(&self) -> bool {
+        self.current.is_some()
+    }
+
+    fn period(&self) -> usize {
+        self.slow.period() + self.signal.period() - 1
+    }
+
+    fn name(&self) -> &str {
+        "MACD"
+    }
+}
+
 /// Stochastic oscillator output.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct StochasticOutput {
@@ -316,6 +482,533 @@ impl Indicator for Stochastic {
     fn name(&self) -> &str {
         "Stochastic"
     }
+
+    fn cache_key(&self) -> String {
+        format!("Stochastic({},{})", self.k_period, self.d_period)
+    }
+}
+
+/// Streaming stochastic oscillator, matching [`Stochastic`].
+///
+/// The highest high and lowest low of the window are tracked with monotonic
+/// queues, so each update is amortized O(1).
+#[derive(Debug, Clone)]
+pub struct StreamingStochastic {
+    k_period: usize,
+    /// Bars seen, used to expire queue entries
+    count: usize,
+    /// (index, high) with decreasing highs
+    highs: VecDeque<(usize, f64)>,
+    /// (index, low) with increasing lows
+    lows: VecDeque<(usize, f64)>,
+    d: StreamingSma,
+    current: Option<StochasticOutput>,
+}
+
+impl StreamingStochastic {
+    /// Create a streaming stochastic oscillator with default parameters (14, 3).
+    pub fn new() -> Self {
+        Self::with_periods(14, 3)
+    }
+
+    /// Create with custom periods.
+    pub fn with_periods(k_period: usize, d_period: usize) -> Self {
+        assert!(k_period > 0 && d_period > 0);
+        Self {
+            k_period,
+            count: 0,
+            highs: VecDeque::with_capacity(k_period),
+            lows: VecDeque::with_capacity(k_period),
+            d: StreamingSma::new(d_period),
+            current: None,
+        }
+    }
+
+    /// Update with a bar's high, low and close.
+    pub fn update_ohlc(&mut self, high: f64, low: f64, close: f64) -> Option<StochasticOutput> {
+        let index = self.count;
+        self.count += 1;
+
+        while self.highs.back().is_some_and(|&(_, h)| h <= high) {
+            self.highs.pop_back();
+        }
+        self.highs.push_back((index, high));
+        while self.lows.back().is_some_and(|&(_, l)| l >= low) {
+            self.lows.pop_back();
+        }
+        self.lows.push_back((index, low));
+
+        // Drop entries that left the window
+        let oldest = (index + 1).saturating_sub(self.k_period);
+        while self.highs.front().is_some_and(|&(i, _)| i < oldest) {
+            self.highs.pop_front();
+        }
+        while self.lows.front().is_some_and(|&(i, _)| i < oldest) {
+            self.lows.pop_front();
+        }
+
+        if self.count < self.k_period {
+            return None;
+        }
+
+        let highest = self.highs.front()?.1;
+        let lowest = self.lows.front()?.1;
+        let range = highest - lowest;
+        let k = if range == 0.0 {
+            50.0 // Undefined, use midpoint
+        } else {
+            ((close - lowest) / range) * 100.0
+        };
+
+        let d = self.d.update(k)?;
+        self.current = Some(StochasticOutput { k, d });
+        self.current
+    }
+}
+
+impl Default for StreamingStochastic {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StreamingIndicator for StreamingStochastic {
+    type Output = StochasticOutput;
+
+    /// Update using the close as high and low, like [`Stochastic::calculate`].
+    fn update(&mut self, value: f64) -> Option<StochasticOutput> {
+        self.update_ohlc(value, value, value)
+    }
+
+    fn current(&self) -> Option<StochasticOutput> {
+        self.current
+    }
+
+    fn reset(&mut self) {
+        self.count = 0;
+        self.highs.clear();
+        self.lows.clear();
+        self.d.reset();
+        self.current = None;
+    }
+
+    fn is_ready(&self) -> bool {
+        self.current.is_some()
+    }
+
+    fn period(&self) -> usize {
+        self.k_period + self.d.period() - 1
+    }
+
+    fn name(&self) -> &str {
+        "Stochastic"
+    }
+}
+
+/// ADX output.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct AdxOutput {
+    /// Average Directional Index (trend strength, 0-100)
+    pub adx: f64,
+    /// Positive directional indicator (+DI)
+    pub plus_di: f64,
+    /// Negative directional indicator (-DI)
+    pub minus_di: f64,
+}
+
+/// Average Directional Index (ADX) with +DI/-DI.
+///
+/// Measures trend strength regardless of direction; +DI and -DI give the
+/// direction. Uses Wilder's smoothing throughout.
+#[derive(Debug, Clone)]
+pub struct Adx {
+    period: usize,
+}
+
+impl Adx {
+    /// Create a new ADX indicator.
+    ///
+    /// Common period is 14.
+    pub fn new(period: usize) -> Self {
+        assert!(period > 0, "Period must be greater than 0");
+        Self { period }
+    }
+
+    /// Calculate ADX from OHLC data.
+    pub fn calculate_ohlc(&self, high: &[f64], low: &[f64], close: &[f64]) -> Vec<AdxOutput> {
+        let len = high.len().min(low.len()).min(close.len());
+        if len < 2 * self.period {
+            return vec![];
+        }
+
+        // True range and directional movement
+        let mut tr = Vec::with_capacity(len - 1);
+        let mut plus_dm = Vec::with_capacity(len - 1);
+        let mut minus_dm = Vec::with_capacity(len - 1);
+
+        for i in 1..len {
+            let high_low = high[i] - low[i];
+            let high_close = (high[i] - close[i - 1]).abs();
+            let low_close = (low[i] - close[i - 1]).abs();
+            tr.push(high_low.max(high_close).max(low_close));
+
+            let up = high[i] - high[i - 1];
+            let down = low[i - 1] - low[i];
+            plus_dm.push(if up > down && up > 0.0 { up } else { 0.0 });
+            minus_dm.push(if down > up && down > 0.0 { down } else { 0.0 });
+        }
+
+        let period_f64 = self.period as f64;
+        let smooth = |values: &[f64]| -> Vec<f64> {
+            let mut avg: f64 = values[..self.period].iter().sum::<f64>() / period_f64;
+            let mut result = Vec::with_capacity(values.len() - self.period + 1);
+            result.push(avg);
+            for &value in &values[self.period..] {
+                avg = (avg * (period_f64 - 1.0) + value) / period_f64;
+                result.push(avg);
+            }
+            result
+        };
+
+        let atr = smooth(&tr);
+        let plus = smooth(&plus_dm);
+        let minus = smooth(&minus_dm);
+
+        // Directional indicators and DX per bar
+        let di: Vec<(f64, f64, f64)> = atr
+            .iter()
+            .zip(plus.iter().zip(minus.iter()))
+            .map(|(&atr, (&plus, &minus))| {
+                let (plus_di, minus_di) = if atr > 0.0 {
+                    (100.0 * plus / atr, 100.0 * minus / atr)
+                } else {
+                    (0.0, 0.0)
+                };
+                let sum = plus_di + minus_di;
+                let dx = if sum > 0.0 {
+                    100.0 * (plus_di - minus_di).abs() / sum
+                } else {
+                    0.0
+                };
+                (plus_di, minus_di, dx)
+            })
+            .collect();
+
+        let dx: Vec<f64> = di.iter().map(|&(_, _, dx)| dx).collect();
+        smooth(&dx)
+            .into_iter()
+            .zip(&di[self.period - 1..])
+            .map(|(adx, &(plus_di, minus_di, _))| AdxOutput {
+                adx,
+                plus_di,
+                minus_di,
+            })
+            .collect()
+    }
+}
+
+impl Default for Adx {
+    fn default() -> Self {
+        Self::new(14)
+    }
+}
+
+impl Indicator for Adx {
+    type Output = AdxOutput;
+
+    /// Calculate using close prices only (uses close as high/low approximation).
+    fn calculate(&self, data: &[f64]) -> Vec<AdxOutput> {
+        self.calculate_ohlc(data, data, data)
+    }
+
+    fn period(&self) -> usize {
+        2 * self.period
+    }
+
+    fn name(&self) -> &str {
+        "ADX"
+    }
+}
+
+/// Commodity Channel Index (CCI).
+///
+/// Distance of the typical price `(high + low + close) / 3` from its moving
+/// average, in units of 0.015 mean deviations; readings beyond ±100 mark
+/// unusually strong moves.
+#[derive(Debug, Clone)]
+pub struct Cci {
+    period: usize,
+}
+
+impl Cci {
+    /// Scaling constant that puts most readings within ±100.
+    const CONSTANT: f64 = 0.015;
+
+    /// Create a new CCI indicator.
+    ///
+    /// Common period is 20.
+    pub fn new(period: usize) -> Self {
+        assert!(period > 0, "Period must be greater than 0");
+        Self { period }
+    }
+
+    /// Calculate CCI from OHLC data.
+    pub fn calculate_ohlc(&self, high: &[f64], low: &[f64], close: &[f64]) -> Vec<f64> {
+        let len = high.len().min(low.len()).min(close.len());
+        if len < self.period {
+            return vec![];
+        }
+
+        let typical: Vec<f64> = (0..len)
+            .map(|i| (high[i] + low[i] + close[i]) / 3.0)
+            .collect();
+        let period_f64 = self.period as f64;
+
+        typical
+            .windows(self.period)
+            .map(|window| {
+                let mean = window.iter().sum::<f64>() / period_f64;
+                let deviation = window.iter().map(|tp| (tp - mean).abs()).sum::<f64>() / period_f64;
+                let last = window[self.period - 1];
+                if deviation == 0.0 {
+                    0.0
+                } else {
+                    (last - mean) / (Self::CONSTANT * deviation)
+                }
+            })
+            .collect()
+    }
+}
+
+impl Default for Cci {
+    fn default() -> Self {
+        Self::new(20)
+    }
+}
+
+impl Indicator for Cci {
+    type Output = f64;
+
+    /// Calculate using close prices only (uses close as high/low approximation).
+    fn calculate(&self, data: &[f64]) -> Vec<f64> {
+        self.calculate_ohlc(data, data, data)
+    }
+
+    fn period(&self) -> usize {
+        self.period
+    }
+
+    fn name(&self) -> &str {
+        "CCI"
+    }
+}
+
+/// Williams %R.
+///
+/// Where the close sits within the high-low range of the period, from 0
+/// (at the high) to -100 (at the low).
+#[derive(Debug, Clone)]
+pub struct WilliamsR {
+    period: usize,
+}
+
+impl WilliamsR {
+    /// Create a new Williams %R indicator.
+    ///
+    /// Common period is 14.
+    pub fn new(period: usize) -> Self {
+        assert!(period > 0, "Period must be greater than 0");
+        Self { period }
+    }
+
+    /// Calculate Williams %R from OHLC data.
+    pub fn calculate_ohlc(&self, high: &[f64], low: &[f64], close: &[f64]) -> Vec<f64> {
+        let len = high.len().min(low.len()).min(close.len());
+        if len < self.period {
+            return vec![];
+        }
+
+        ((self.period - 1)..len)
+            .map(|i| {
+                let start = i + 1 - self.period;
+                let highest = high[start..=i]
+                    .iter()
+                    .cloned()
+                    .fold(f64::NEG_INFINITY, f64::max);
+                let lowest = low[start..=i].iter().cloned().fold(f64::INFINITY, f64::min);
+                let range = highest - lowest;
+                if range == 0.0 {
+                    -50.0 // Undefined, use midpoint
+                } else {
+                    (highest - close[i]) / range * -100.0
+                }
+            })
+            .collect()
+    }
+}
+
+impl Default for WilliamsR {
+    fn default() -> Self {
+        Self::new(14)
+    }
+}
+
+impl Indicator for WilliamsR {
+    type Output = f64;
+
+    /// Calculate using close prices only (uses close as high/low approximation).
+    fn calculate(&self, data: &[f64]) -> Vec<f64> {
+        self.calculate_ohlc(data, data, data)
+    }
+
+    fn period(&self) -> usize {
+        self.period
+    }
+
+    fn name(&self) -> &str {
+        "Williams %R"
+    }
+}
+
+/// Rate of Change (ROC).
+///
+/// Percentage change of the close over `period` bars.
+#[derive(Debug, Clone)]
+pub struct Roc {
+    period: usize,
+}
+
+impl Roc {
+    /// Create a new ROC indicator.
+    ///
+    /// Common periods are 10 and 12.
+    pub fn new(period: usize) -> Self {
+        assert!(period > 0, "Period must be greater than 0");
+        Self { period }
+    }
+}
+
+impl Default for Roc {
+    fn default() -> Self {
+        Self::new(10)
+    }
+}
+
+impl Indicator for Roc {
+    type Output = f64;
+
+    fn calculate(&self, data: &[f64]) -> Vec<f64> {
+        if data.len() <= self.period {
+            return vec![];
+        }
+
+        data.iter()
+            .zip(&data[self.period..])
+            .map(|(&past, &current)| {
+                if past == 0.0 {
+                    0.0
+                } else {
+                    (current - past) / past * 100.0
+                }
+            })
+            .collect()
+    }
+
+    fn period(&self) -> usize {
+        self.period + 1
+    }
+
+    fn name(&self) -> &str {
+        "ROC"
+    }
+}
+
+/// Aroon output.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct AroonOutput {
+    /// Aroon Up: recency of the highest high (0-100)
+    pub up: f64,
+    /// Aroon Down: recency of the lowest low (0-100)
+    pub down: f64,
+    /// Up minus down (-100 to 100)
+    pub oscillator: f64,
+}
+
+/// Aroon indicator.
+///
+/// How many bars have passed since the highest high and the lowest low of
+/// the last `period + 1` bars; 100 means the extreme is the current bar.
+#[derive(Debug, Clone)]
+pub struct Aroon {
+    period: usize,
+}
+
+impl Aroon {
+    /// Create a new Aroon indicator.
+    ///
+    /// Common period is 25.
+    pub fn new(period: usize) -> Self {
+        assert!(period > 0, "Period must be greater than 0");
+        Self { period }
+    }
+
+    /// Calculate Aroon from high and low data.
+    pub fn calculate_hl(&self, high: &[f64], low: &[f64]) -> Vec<AroonOutput> {
+        let len = high.len().min(low.len());
+        if len <= self.period {
+            return vec![];
+        }
+
+        let period_f64 = self.period as f64;
+        // Ties go to the most recent bar
+        let bars_since = |window: &[f64], better: fn(f64, f64) -> bool| {
+            let mut best = 0;
+            for (i, &value) in window.iter().enumerate() {
+                if !better(window[best], value) {
+                    best = i;
+                }
+            }
+            (window.len() - 1 - best) as f64
+        };
+
+        (self.period..len)
+            .map(|i| {
+                let start = i - self.period;
+                let since_high = bars_since(&high[start..=i], |best, v| best > v);
+                let since_low = bars_since(&low[start..=i], |best, v| best < v);
+                let up = 100.0 * (period_f64 - since_high) / period_f64;
+                let down = 100.0 * (period_f64 - since_low) / period_f64;
+                AroonOutput {
+                    up,
+                    down,
+                    oscillator: up - down,
+                }
+            })
+            .collect()
+    }
+}
+
+impl Default for Aroon {
+    fn default() -> Self {
+        Self::new(25)
+    }
+}
+
+impl MultiOutputIndicator for Aroon {
+    type Outputs = AroonOutput;
+
+    /// Calculate using close prices only (uses close as high/low approximation).
+    fn calculate(&self, data: &[f64]) -> Vec<AroonOutput> {
+        self.calculate_hl(data, data)
+    }
+
+    fn period(&self) -> usize {
+        self.period + 1
+    }
+
+    fn name(&self) -> &str {
+        "Aroon"
+    }
 }
 
 #[cfg(test)]
@@ -412,4 +1105,126 @@ mod tests {
         // Close at high = %K should be 100
         assert!((result.last().unwrap().k - 100.0).abs() < 1e-10);
     }
+
+    #[test]
+    fn test_streaming_rsi_matches_batch() {
+        let data = vec![
+            44.0, 44.3, 44.1, 43.6, 44.3, 44.8, 45.1, 45.4, 45.8, 46.1, 45.9, 46.3,
+        ];
+        let batch = Rsi::new(5).calculate(&data);
+
+        let mut rsi = StreamingRsi::new(5);
+        let streamed: Vec<f64> = data.iter().filter_map(|&v| rsi.update(v)).collect();
+        assert_eq!(streamed.len(), batch.len());
+        for (a, b) in streamed.iter().zip(&batch) {
+            assert!((a - b).abs() < 1e-10);
+        }
+
+        rsi.reset();
+        assert!(!rsi.is_ready());
+        assert!(rsi.current().is_none());
+    }
+
+    #[test]
+    fn test_streaming_macd_and_stochastic_match_batch() {
+        let data: Vec<f64> = (0..60)
+            .map(|i| 100.0 + (i as f64 * 0.3).sin() * 4.0 + i as f64 * 0.2)
+            .collect();
+
+        let batch = Macd::with_periods(5, 10, 4).calculate(&data);
+        let mut macd = StreamingMacd::with_periods(5, 10, 4);
+        let streamed: Vec<MacdOutput> = data.iter().filter_map(|&v| macd.update(v)).collect();
+        // Batch output starts one bar later, so compare the aligned tails
+        let tail = &streamed[streamed.len() - batch.len()..];
+        for (a, b) in tail.iter().zip(&batch) {
+            assert!((a.macd - b.macd).abs() < 1e-9);
+            assert!((a.signal - b.signal).abs() < 1e-9);
+        }
+
+        let highs: Vec<f64> = data.iter().map(|c| c + 1.5).collect();
+        let lows: Vec<f64> = data.iter().map(|c| c - 1.0).collect();
+        let batch = Stochastic::with_periods(7, 3).calculate_ohlc(&highs, &lows, &data);
+        let mut stoch = StreamingStochastic::with_periods(7, 3);
+        let streamed: Vec<StochasticOutput> = (0..data.len())
+            .filter_map(|i| stoch.update_ohlc(highs[i], lows[i], data[i]))
+            .collect();
+        assert_eq!(streamed.len(), batch.len());
+        for (a, b) in streamed.iter().zip(&batch) {
+            assert!((a.k - b.k).abs() < 1e-9);
+            assert!((a.d - b.d).abs() < 1e-9);
+        }
+
+        stoch.reset();
+        assert!(!stoch.is_ready());
+    }
+
+    #[test]
+    fn test_adx_trend_strength() {
+        let adx = Adx::new(5);
+
+        // Steady uptrend: strong trend, +DI dominates
+        let close: Vec<f64> = (0..30).map(|i| 100.0 + i as f64).collect();
+        let high: Vec<f64> = close.iter().map(|c| c + 0.5).collect();
+        let low: Vec<f64> = close.iter().map(|c| c - 0.5).collect();
+        let result = adx.calculate_ohlc(&high, &low, &close);
+        assert_eq!(result.len(), 30 - 2 * 5 + 1);
+        let last = result.last().unwrap();
+        assert!(last.plus_di > last.minus_di);
+        assert_eq!(last.minus_di, 0.0);
+        assert!((last.adx - 100.0).abs() < 1e-9);
+
+        // Choppy, directionless market: weak trend
+        let close: Vec<f64> = (0..30)
+            .map(|i| if i % 2 == 0 { 100.0 } else { 101.0 })
+            .collect();
+        let high: Vec<f64> = close.iter().map(|c| c + 0.5).collect();
+        let low: Vec<f64> = close.iter().map(|c| c - 0.5).collect();
+        let result = adx.calculate_ohlc(&high, &low, &close);
+        assert!(result.last().unwrap().adx < 25.0);
+
+        assert!(adx
+            .calculate_ohlc(&high[..9], &low[..9], &close[..9])
+            .is_empty());
+    }
+
+    #[test]
+    fn test_cci_reference_values() {
+        let data = vec![1.0, 2.0, 3.0, 2.0];
+        let result = Cci::new(3).calculate(&data);
+        // (3 - 2) / (0.015 * 2/3) and (2 - 7/3) / (0.015 * 4/9)
+        assert_eq!(result.len(), 2);
+        assert!((result[0] - 100.0).abs() < 1e-9);
+        assert!((result[1] + 50.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_williams_r_reference_values() {
+        let high = [10.0, 12.0, 11.0, 13.0];
+        let low = [8.0, 9.0, 7.0, 10.0];
+        let close = [9.0, 11.0, 8.0, 12.0];
+        let result = WilliamsR::new(3).calculate_ohlc(&high, &low, &close);
+        assert_eq!(result.len(), 2);
+        assert!((result[0] + 80.0).abs() < 1e-9); // (12 - 8) / (12 - 7)
+        assert!((result[1] + 100.0 / 6.0).abs() < 1e-9); // (13 - 12) / (13 - 7)
+    }
+
+    #[test]
+    fn test_roc_reference_values() {
+        let result = Roc::new(2).calculate(&[100.0, 105.0, 110.0, 99.0]);
+        assert_eq!(result.len(), 2);
+        assert!((result[0] - 10.0).abs() < 1e-9);
+        assert!((result[1] + 600.0 / 105.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_aroon_reference_values() {
+        let high = [1.0, 5.0, 3.0, 2.0, 4.0];
+        let low = [3.0, 1.0, 2.0, 0.5, 2.0];
+        let result = Aroon::new(4).calculate_hl(&high, &low);
+        assert_eq!(result.len(), 1);
+        // High 3 bars ago, low 1 bar ago
+        assert!((result[0].up - 25.0).abs() < 1e-9);
+        assert!((result[0].down - 75.0).abs() < 1e-9);
+        assert!((result[0].oscillator + 50.0).abs() < 1e-9);
+    }
 }
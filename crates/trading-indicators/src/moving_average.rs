@@ -1,6 +1,90 @@
 //! Moving average indicators.
 
-use trading_core::traits::Indicator;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use trading_core::traits::{Indicator, StreamingIndicator};
+
+use crate::rolling::RollingSum;
+use crate::simd;
+
+/// Moving average kinds, for choosing one by configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MaType {
+    /// Simple moving average
+    Sma,
+    /// Exponential moving average
+    #[default]
+    Ema,
+    /// Weighted moving average
+    Wma,
+    /// Hull moving average
+    Hull,
+    /// Kaufman adaptive moving average
+    Kama,
+    /// Double exponential moving average
+    Dema,
+    /// Triple exponential moving average
+    Tema,
+}
+
+impl MaType {
+    /// Streaming moving average of this kind over `period` values.
+    pub fn streaming(self, period: usize) -> Box<dyn StreamingIndicator<Output = f64>> {
+        match self {
+            MaType::Sma => Box::new(StreamingSma::new(period)),
+            MaType::Ema => Box::new(StreamingEma::new(period)),
+            MaType::Wma => Box::new(StreamingWma::new(period)),
+            MaType::Hull => Box::new(StreamingHma::new(period)),
+            MaType::Kama => Box::new(StreamingKama::new(period)),
+            MaType::Dema => Box::new(StreamingDema::new(period)),
+            MaType::Tema => Box::new(StreamingTema::new(period)),
+        }
+    }
+
+    /// Values needed before a `period` average of this kind produces output.
+    pub fn warmup(self, period: usize) -> usize {
+        match self {
+            MaType::Sma | MaType::Ema | MaType::Wma => period,
+            MaType::Hull => period + hull_sqrt_period(period) - 1,
+            MaType::Kama => period + 1,
+            MaType::Dema => 2 * period - 1,
+            MaType::Tema => 3 * period - 2,
+        }
+    }
+}
+
+impl std::fmt::Display for MaType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            MaType::Sma => "sma",
+            MaType::Ema => "ema",
+            MaType::Wma => "wma",
+            MaType::Hull => "hull",
+            MaType::Kama => "kama",
+            MaType::Dema => "dema",
+            MaType::Tema => "tema",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl std::str::FromStr for MaType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "sma" => Ok(MaType::Sma),
+            "ema" => Ok(MaType::Ema),
+            "wma" => Ok(MaType::Wma),
+            "hull" | "hma" => Ok(MaType::Hull),
+            "kama" => Ok(MaType::Kama),
+            "dema" => Ok(MaType::Dema),
+            "tema" => Ok(MaType::Tema),
+            _ => Err(format!("Invalid moving average type: {}", s)),
+        }
+    }
+}
 
 /// Simple Moving Average (SMA).
 ///
@@ -112,6 +196,10 @@ impl Indicator for Ema {
     fn name(&self) -> &str {
         "EMA"
     }
+
+    fn cache_key(&self) -> String {
+        format!("EMA({},{})", self.period, self.multiplier)
+    }
 }
 
 /// Weighted Moving Average (WMA).
@@ -143,6 +231,9 @@ impl Indicator for Wma {
         if data.len() < self.period {
             return vec![];
         }
+        if data.len() >= simd::SIMD_MIN_LEN {
+            return simd::wma_simd(data, self.period);
+        }
 
         let mut result = Vec::with_capacity(data.len() - self.period + 1);
 
@@ -167,6 +258,265 @@ impl Indicator for Wma {
     }
 }
 
+/// Period of the final smoothing WMA of a Hull MA.
+fn hull_sqrt_period(period: usize) -> usize {
+    ((period as f64).sqrt() as usize).max(1)
+}
+
+/// Hull Moving Average (HMA).
+///
+/// `WMA(2 * WMA(n/2) - WMA(n), sqrt(n))`: nearly lag-free while staying
+/// smooth. Needs `n + sqrt(n) - 1` values for the first output.
+#[derive(Debug, Clone)]
+pub struct Hma {
+    period: usize,
+}
+
+impl Hma {
+    /// Create a new HMA with the specified period.
+    pub fn new(period: usize) -> Self {
+        assert!(period > 0, "Period must be greater than 0");
+        Self { period }
+    }
+}
+
+impl Indicator for Hma {
+    type Output = f64;
+
+    fn calculate(&self, data: &[f64]) -> Vec<f64> {
+        let half = (self.period / 2).max(1);
+        let full = Wma::new(self.period).calculate(data);
+        if full.is_empty() {
+            return vec![];
+        }
+        let half = Wma::new(half).calculate(&data[self.period - half..]);
+        let diff: Vec<f64> = half.iter().zip(&full).map(|(h, f)| 2.0 * h - f).collect();
+        Wma::new(hull_sqrt_period(self.period)).calculate(&diff)
+    }
+
+    fn period(&self) -> usize {
+        MaType::Hull.warmup(self.period)
+    }
+
+    fn name(&self) -> &str {
+        "HMA"
+    }
+}
+
+/// Kaufman Adaptive Moving Average (KAMA).
+///
+/// An EMA whose smoothing follows the efficiency ratio (net change over
+/// path length) of the last `period` values: fast in trends, nearly flat
+/// in noise. Needs `period + 1` values for the first output.
+#[derive(Debug, Clone)]
+pub struct Kama {
+    period: usize,
+    fast_sc: f64,
+    slow_sc: f64,
+}
+
+impl Kama {
+    /// Create a new KAMA with the standard 2/30 fast/slow periods.
+    pub fn new(period: usize) -> Self {
+        Self::with_smoothing(period, 2, 30)
+    }
+
+    /// Create a KAMA with custom fast and slow smoothing periods.
+    pub fn with_smoothing(period: usize, fast: usize, slow: usize) -> Self {
+        assert!(period > 0, "Period must be greater than 0");
+        assert!(
+            fast > 0 && fast < slow,
+            "Fast period must be below slow period"
+        );
+        Self {
+            period,
+            fast_sc: 2.0 / (fast as f64 + 1.0),
+            slow_sc: 2.0 / (slow as f64 + 1.0),
+        }
+    }
+
+    /// Smoothing constant for an efficiency ratio.
+    fn smoothing(&self, efficiency: f64) -> f64 {
+        (efficiency * (self.fast_sc - self.slow_sc) + self.slow_sc).powi(2)
+    }
+}
+
+/// Net change over path length; 1 when there is no movement at all.
+fn efficiency_ratio(change: f64, volatility: f64) -> f64 {
+    if volatility > 0.0 {
+        change.abs() / volatility
+    } else {
+        1.0
+    }
+}
+
+impl Indicator for Kama {
+    type Output = f64;
+
+    fn calculate(&self, data: &[f64]) -> Vec<f64> {
+        if data.len() <= self.period {
+            return vec![];
+        }
+
+        let mut result = Vec::with_capacity(data.len() - self.period);
+        // Seed with the last value before the first full window
+        let mut kama = data[self.period - 1];
+        for i in self.period..data.len() {
+            let change = data[i] - data[i - self.period];
+            let volatility: f64 = data[i - self.period..=i]
+                .windows(2)
+                .map(|w| (w[1] - w[0]).abs())
+                .sum();
+            let sc = self.smoothing(efficiency_ratio(change, volatility));
+            kama += sc * (data[i] - kama);
+            result.push(kama);
+        }
+
+        result
+    }
+
+    fn period(&self) -> usize {
+        self.period + 1
+    }
+
+    fn name(&self) -> &str {
+        "KAMA"
+    }
+
+    fn cache_key(&self) -> String {
+        format!("KAMA({},{},{})", self.period, self.fast_sc, self.slow_sc)
+    }
+}
+
+/// Double Exponential Moving Average (DEMA).
+///
+/// `2 * EMA - EMA(EMA)`, which cancels most of the EMA's lag. Needs
+/// `2 * period - 1` values for the first output.
+#[derive(Debug, Clone)]
+pub struct Dema {
+    period: usize,
+}
+
+impl Dema {
+    /// Create a new DEMA with the specified period.
+    pub fn new(period: usize) -> Self {
+        assert!(period > 0, "Period must be greater than 0");
+        Self { period }
+    }
+}
+
+impl Indicator for Dema {
+    type Output = f64;
+
+    fn calculate(&self, data: &[f64]) -> Vec<f64> {
+        let ema = Ema::new(self.period);
+        let e1 = ema.calculate(data);
+        let e2 = ema.calculate(&e1);
+        let offset = e1.len().saturating_sub(e2.len());
+        e2.iter()
+            .zip(&e1[offset..])
+            .map(|(e2, e1)| 2.0 * e1 - e2)
+            .collect()
+    }
+
+    fn period(&self) -> usize {
+        MaType::Dema.warmup(self.period)
+    }
+
+    fn name(&self) -> &str {
+        "DEMA"
+    }
+}
+
+/// Triple Exponential Moving Average (TEMA).
+///
+/// `3 * EMA - 3 * EMA(EMA) + EMA(EMA(EMA))`. Needs `3 * period - 2` values
+/// for the first output.
+#[derive(Debug, Clone)]
+pub struct Tema {
+    period: usize,
+}
+
+impl Tema {
+    /// Create a new TEMA with the specified period.
+    pub fn new(period: usize) -> Self {
+        assert!(period > 0, "Period must be greater than 0");
+        Self { period }
+    }
+}
+
+impl Indicator for Tema {
+    type Output = f64;
+
+    fn calculate(&self, data: &[f64]) -> Vec<f64> {
+        let ema = Ema::new(self.period);
+        let e1 = ema.calculate(data);
+        let e2 = ema.calculate(&e1);
+        let e3 = ema.calculate(&e2);
+        let offset1 = e1.len().saturating_sub(e3.len());
+        let offset2 = e2.len().saturating_sub(e3.len());
+        e3.iter()
+            .zip(&e2[offset2..])
+            .zip(&e1[offset1..])
+            .map(|((e3, e2), e1)| 3.0 * e1 - 3.0 * e2 + e3)
+            .collect()
+    }
+
+    fn period(&self) -> usize {
+        MaType::Tema.warmup(self.period)
+    }
+
+    fn name(&self) -> &str {
+        "TEMA"
+    }
+}
+
+/// Streaming SMA that keeps a running sum over the window.
+#[derive(Debug, Clone)]
+pub struct StreamingSma {
+    sum: RollingSum,
+}
+
+impl StreamingSma {
+    /// Create a new streaming SMA.
+    pub fn new(period: usize) -> Self {
+        assert!(period > 0, "Period must be greater than 0");
+        Self {
+            sum: RollingSum::new(period),
+        }
+    }
+}
+
+impl StreamingIndicator for StreamingSma {
+    type Output = f64;
+
+    fn update(&mut self, value: f64) -> Option<f64> {
+        self.sum.push(value);
+        self.current()
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.is_ready()
+            .then(|| self.sum.sum() / self.sum.period() as f64)
+    }
+
+    fn reset(&mut self) {
+        self.sum.clear();
+    }
+
+    fn is_ready(&self) -> bool {
+        self.sum.is_full()
+    }
+
+    fn period(&self) -> usize {
+        self.sum.period()
+    }
+
+    fn name(&self) -> &str {
+        "SMA"
+    }
+}
+
 /// Streaming EMA that maintains state for incremental updates.
 #[derive(Debug, Clone)]
 pub struct StreamingEma {
@@ -190,9 +540,12 @@ impl StreamingEma {
             sum: 0.0,
         }
     }
+}
+
+impl StreamingIndicator for StreamingEma {
+    type Output = f64;
 
-    /// Update with a new value and return the current EMA.
-    pub fn update(&mut self, value: f64) -> Option<f64> {
+    fn update(&mut self, value: f64) -> Option<f64> {
         self.count += 1;
 
         if self.count < self.period {
@@ -214,22 +567,330 @@ impl StreamingEma {
         }
     }
 
-    /// Get the current EMA value.
-    pub fn current(&self) -> Option<f64> {
+    fn current(&self) -> Option<f64> {
         self.current
     }
 
-    /// Reset the indicator.
-    pub fn reset(&mut self) {
+    fn reset(&mut self) {
         self.current = None;
         self.count = 0;
         self.sum = 0.0;
     }
 
-    /// Check if the indicator is ready.
-    pub fn is_ready(&self) -> bool {
+    fn is_ready(&self) -> bool {
         self.count >= self.period
     }
+
+    fn period(&self) -> usize {
+        self.period
+    }
+
+    fn name(&self) -> &str {
+        "EMA"
+    }
+}
+
+/// Streaming WMA that keeps running plain and weighted sums.
+#[derive(Debug, Clone)]
+pub struct StreamingWma {
+    period: usize,
+    window: VecDeque<f64>,
+    sum: f64,
+    weighted_sum: f64,
+}
+
+impl StreamingWma {
+    /// Create a new streaming WMA.
+    pub fn new(period: usize) -> Self {
+        assert!(period > 0, "Period must be greater than 0");
+        Self {
+            period,
+            window: VecDeque::with_capacity(period),
+            sum: 0.0,
+            weighted_sum: 0.0,
+        }
+    }
+}
+
+impl StreamingIndicator for StreamingWma {
+    type Output = f64;
+
+    fn update(&mut self, value: f64) -> Option<f64> {
+        if self.window.len() == self.period {
+            // Every weight drops by one, which retires the oldest value
+            self.weighted_sum += self.period as f64 * value - self.sum;
+            self.sum += value - self.window.pop_front().unwrap_or_default();
+        } else {
+            self.weighted_sum += (self.window.len() + 1) as f64 * value;
+            self.sum += value;
+        }
+        self.window.push_back(value);
+        self.current()
+    }
+
+    fn current(&self) -> Option<f64> {
+        let weights = (self.period * (self.period + 1)) as f64 / 2.0;
+        self.is_ready().then(|| self.weighted_sum / weights)
+    }
+
+    fn reset(&mut self) {
+        self.window.clear();
+        self.sum = 0.0;
+        self.weighted_sum = 0.0;
+    }
+
+    fn is_ready(&self) -> bool {
+        self.window.len() >= self.period
+    }
+
+    fn period(&self) -> usize {
+        self.period
+    }
+
+    fn name(&self) -> &str {
+        "WMA"
+    }
+}
+
+/// Streaming Hull moving average.
+#[derive(Debug, Clone)]
+pub struct StreamingHma {
+    period: usize,
+    half: StreamingWma,
+    full: StreamingWma,
+    smooth: StreamingWma,
+}
+
+impl StreamingHma {
+    /// Create a new streaming HMA.
+    pub fn new(period: usize) -> Self {
+        assert!(period > 0, "Period must be greater than 0");
+        Self {
+            period,
+            half: StreamingWma::new((period / 2).max(1)),
+            full: StreamingWma::new(period),
+            smooth: StreamingWma::new(hull_sqrt_period(period)),
+        }
+    }
+}
+
+impl StreamingIndicator for StreamingHma {
+    type Output = f64;
+
+    fn update(&mut self, value: f64) -> Option<f64> {
+        let half = self.half.update(value);
+        let full = self.full.update(value)?;
+        self.smooth.update(2.0 * half? - full)
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.smooth.current()
+    }
+
+    fn reset(&mut self) {
+        self.half.reset();
+        self.full.reset();
+        self.smooth.reset();
+    }
+
+    fn is_ready(&self) -> bool {
+        self.smooth.is_ready()
+    }
+
+    fn period(&self) -> usize {
+        MaType::Hull.warmup(self.period)
+    }
+
+    fn name(&self) -> &str {
+        "HMA"
+    }
+}
+
+/// Streaming KAMA with a running path length over the window.
+#[derive(Debug, Clone)]
+pub struct StreamingKama {
+    kama: Kama,
+    /// The last `period + 1` values
+    window: VecDeque<f64>,
+    volatility: f64,
+    current: Option<f64>,
+}
+
+impl StreamingKama {
+    /// Create a new streaming KAMA with the standard 2/30 fast/slow periods.
+    pub fn new(period: usize) -> Self {
+        Self::from_kama(Kama::new(period))
+    }
+
+    /// Create a streaming KAMA with custom fast and slow smoothing periods.
+    pub fn with_smoothing(period: usize, fast: usize, slow: usize) -> Self {
+        Self::from_kama(Kama::with_smoothing(period, fast, slow))
+    }
+
+    fn from_kama(kama: Kama) -> Self {
+        Self {
+            window: VecDeque::with_capacity(kama.period + 2),
+            kama,
+            volatility: 0.0,
+            current: None,
+        }
+    }
+}
+
+impl StreamingIndicator for StreamingKama {
+    type Output = f64;
+
+    fn update(&mut self, value: f64) -> Option<f64> {
+        let period = self.kama.period;
+        if let Some(&last) = self.window.back() {
+            self.volatility += (value - last).abs();
+        }
+        self.window.push_back(value);
+        if self.window.len() > period + 1 {
+            let oldest = self.window.pop_front().unwrap_or_default();
+            self.volatility -= (self.window[0] - oldest).abs();
+        }
+        if self.window.len() <= period {
+            return None;
+        }
+
+        let prev = self.current.unwrap_or(self.window[period - 1]);
+        let change = value - self.window[0];
+        let sc = self
+            .kama
+            .smoothing(efficiency_ratio(change, self.volatility));
+        self.current = Some(prev + sc * (value - prev));
+        self.current
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.current
+    }
+
+    fn reset(&mut self) {
+        self.window.clear();
+        self.volatility = 0.0;
+        self.current = None;
+    }
+
+    fn is_ready(&self) -> bool {
+        self.current.is_some()
+    }
+
+    fn period(&self) -> usize {
+        self.kama.period + 1
+    }
+
+    fn name(&self) -> &str {
+        "KAMA"
+    }
+}
+
+/// Streaming double exponential moving average.
+#[derive(Debug, Clone)]
+pub struct StreamingDema {
+    period: usize,
+    ema: StreamingEma,
+    ema_of_ema: StreamingEma,
+    current: Option<f64>,
+}
+
+impl StreamingDema {
+    /// Create a new streaming DEMA.
+    pub fn new(period: usize) -> Self {
+        Self {
+            period,
+            ema: StreamingEma::new(period),
+            ema_of_ema: StreamingEma::new(period),
+            current: None,
+        }
+    }
+}
+
+impl StreamingIndicator for StreamingDema {
+    type Output = f64;
+
+    fn update(&mut self, value: f64) -> Option<f64> {
+        let e1 = self.ema.update(value)?;
+        let e2 = self.ema_of_ema.update(e1)?;
+        self.current = Some(2.0 * e1 - e2);
+        self.current
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.current
+    }
+
+    fn reset(&mut self) {
+        self.ema.reset();
+        self.ema_of_ema.reset();
+        self.current = None;
+    }
+
+    fn is_ready(&self) -> bool {
+        self.current.is_some()
+    }
+
+    fn period(&self) -> usize {
+        MaType::Dema.warmup(self.period)
+    }
+
+    fn name(&self) -> &str {
+        "DEMA"
+    }
+}
+
+/// Streaming triple exponential moving average.
+#[derive(Debug, Clone)]
+pub struct StreamingTema {
+    period: usize,
+    emas: [StreamingEma; 3],
+    current: Option<f64>,
+}
+
+impl StreamingTema {
+    /// Create a new streaming TEMA.
+    pub fn new(period: usize) -> Self {
+        let ema = StreamingEma::new(period);
+        Self {
+            period,
+            emas: [ema.clone(), ema.clone(), ema],
+            current: None,
+        }
+    }
+}
+
+impl StreamingIndicator for StreamingTema {
+    type Output = f64;
+
+    fn update(&mut self, value: f64) -> Option<f64> {
+        let e1 = self.emas[0].update(value)?;
+        let e2 = self.emas[1].update(e1)?;
+        let e3 = self.emas[2].update(e2)?;
+        self.current = Some(3.0 * e1 - 3.0 * e2 + e3);
+        self.current
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.current
+    }
+
+    fn reset(&mut self) {
+        self.emas.iter_mut().for_each(StreamingEma::reset);
+        self.current = None;
+    }
+
+    fn is_ready(&self) -> bool {
+        self.current.is_some()
+    }
+
+    fn period(&self) -> usize {
+        MaType::Tema.warmup(self.period)
+    }
+
+    fn name(&self) -> &str {
+        "TEMA"
+    }
 }
 
 #[cfg(test)]
@@ -313,4 +974,70 @@ mod tests {
         assert!(!ema.is_ready());
         assert!(ema.current().is_none());
     }
+
+    #[test]
+    fn test_streaming_sma_matches_batch() {
+        let data = vec![1.0, 4.0, 2.0, 8.0, 5.0, 7.0];
+        let batch = Sma::new(3).calculate(&data);
+
+        let mut sma = StreamingSma::new(3);
+        let streamed: Vec<f64> = data.iter().filter_map(|&v| sma.update(v)).collect();
+        assert_eq!(streamed.len(), batch.len());
+        for (a, b) in streamed.iter().zip(&batch) {
+            assert!((a - b).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_adaptive_averages_stream_like_batch() {
+        let data: Vec<f64> = (0..60)
+            .map(|i| 100.0 + (i as f64 * 0.7).sin() * 5.0 + i as f64 * 0.2)
+            .collect();
+
+        for ma_type in [
+            MaType::Wma,
+            MaType::Hull,
+            MaType::Kama,
+            MaType::Dema,
+            MaType::Tema,
+        ] {
+            let batch = match ma_type {
+                MaType::Wma => Wma::new(9).calculate(&data),
+                MaType::Hull => Hma::new(9).calculate(&data),
+                MaType::Kama => Kama::new(9).calculate(&data),
+                MaType::Dema => Dema::new(9).calculate(&data),
+                _ => Tema::new(9).calculate(&data),
+            };
+            assert_eq!(
+                batch.len(),
+                data.len() - ma_type.warmup(9) + 1,
+                "{}",
+                ma_type
+            );
+
+            let mut streaming = ma_type.streaming(9);
+            let streamed: Vec<f64> = data.iter().filter_map(|&v| streaming.update(v)).collect();
+            assert_eq!(streamed.len(), batch.len(), "{}", ma_type);
+            for (a, b) in streamed.iter().zip(&batch) {
+                assert!((a - b).abs() < 1e-9, "{}: {} vs {}", ma_type, a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_low_lag_averages_track_a_trend() {
+        // On a straight line DEMA, TEMA and Hull have no lag at all
+        let data: Vec<f64> = (0..40).map(|i| 10.0 + i as f64).collect();
+        let last = *data.last().unwrap();
+        for result in [
+            Dema::new(5).calculate(&data),
+            Tema::new(5).calculate(&data),
+            Hma::new(9).calculate(&data),
+        ] {
+            assert!((result.last().unwrap() - last).abs() < 1e-9);
+        }
+        // A perfectly efficient move has KAMA chase at the fast rate
+        let kama = Kama::new(10).calculate(&data);
+        assert!(*kama.last().unwrap() > *Sma::new(10).calculate(&data).last().unwrap());
+    }
 }
@@ -0,0 +1,376 @@
+//! Candlestick pattern recognition.
+//!
+//! [`PatternDetector`] scans a [`BarSeries`] for common reversal patterns
+//! and returns [`PatternEvent`]s. Strategies typically call
+//! [`PatternDetector::confirms`] on the newest bar to filter their signals.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use trading_core::types::{Bar, BarSeries, SignalType};
+
+/// Direction a pattern points to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PatternBias {
+    /// Suggests prices turn up
+    Bullish,
+    /// Suggests prices turn down
+    Bearish,
+    /// Indecision
+    Neutral,
+}
+
+/// Candlestick pattern kinds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PatternKind {
+    /// A bullish body that engulfs the previous bearish body
+    BullishEngulfing,
+    /// A bearish body that engulfs the previous bullish body
+    BearishEngulfing,
+    /// Small body near the high with a long lower shadow
+    Hammer,
+    /// Open and close (almost) equal
+    Doji,
+    /// Long bearish bar, small-bodied star below it, bullish bar closing
+    /// into the first body
+    MorningStar,
+    /// Long bullish bar, small-bodied star above it, bearish bar closing
+    /// into the first body
+    EveningStar,
+}
+
+impl PatternKind {
+    /// Every pattern kind.
+    pub const ALL: [PatternKind; 6] = [
+        PatternKind::BullishEngulfing,
+        PatternKind::BearishEngulfing,
+        PatternKind::Hammer,
+        PatternKind::Doji,
+        PatternKind::MorningStar,
+        PatternKind::EveningStar,
+    ];
+
+    /// Direction the pattern points to.
+    pub fn bias(self) -> PatternBias {
+        match self {
+            PatternKind::BullishEngulfing | PatternKind::Hammer | PatternKind::MorningStar => {
+                PatternBias::Bullish
+            }
+            PatternKind::BearishEngulfing | PatternKind::EveningStar => PatternBias::Bearish,
+            PatternKind::Doji => PatternBias::Neutral,
+        }
+    }
+
+    /// Number of bars the pattern spans.
+    pub fn bars(self) -> usize {
+        match self {
+            PatternKind::Hammer | PatternKind::Doji => 1,
+            PatternKind::BullishEngulfing | PatternKind::BearishEngulfing => 2,
+            PatternKind::MorningStar | PatternKind::EveningStar => 3,
+        }
+    }
+}
+
+impl std::fmt::Display for PatternKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            PatternKind::BullishEngulfing => "bullish_engulfing",
+            PatternKind::BearishEngulfing => "bearish_engulfing",
+            PatternKind::Hammer => "hammer",
+            PatternKind::Doji => "doji",
+            PatternKind::MorningStar => "morning_star",
+            PatternKind::EveningStar => "evening_star",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl std::str::FromStr for PatternKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PatternKind::ALL
+            .into_iter()
+            .find(|kind| kind.to_string() == s.to_lowercase().replace('-', "_"))
+            .ok_or_else(|| format!("Unknown candlestick pattern: {}", s))
+    }
+}
+
+/// A pattern found in a series.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PatternEvent {
+    /// Pattern found
+    pub kind: PatternKind,
+    /// Index of the bar completing the pattern
+    pub index: usize,
+    /// Timestamp of the bar completing the pattern
+    pub timestamp: i64,
+}
+
+impl PatternEvent {
+    /// Direction the pattern points to.
+    pub fn bias(&self) -> PatternBias {
+        self.kind.bias()
+    }
+}
+
+/// Detects candlestick patterns.
+///
+/// Body and shadow sizes are compared as fractions of the bar's range, so
+/// the thresholds work at any price level.
+#[derive(Debug, Clone)]
+pub struct PatternDetector {
+    /// Largest body, as a fraction of the range, that counts as a doji
+    doji_body_pct: f64,
+    /// Smallest lower shadow of a hammer, as a multiple of its body
+    hammer_shadow_ratio: f64,
+    /// Smallest body, as a fraction of the range, of the outer star bars
+    long_body_pct: f64,
+    /// Largest body of the middle star bar, as a fraction of the first body
+    star_body_pct: f64,
+}
+
+impl Default for PatternDetector {
+    fn default() -> Self {
+        Self {
+            doji_body_pct: 0.1,
+            hammer_shadow_ratio: 2.0,
+            long_body_pct: 0.5,
+            star_body_pct: 0.3,
+        }
+    }
+}
+
+impl PatternDetector {
+    /// Create a detector with the default thresholds.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the largest body, as a fraction of the range, of a doji.
+    pub fn with_doji_body_pct(mut self, pct: f64) -> Self {
+        assert!((0.0..1.0).contains(&pct), "Doji body must be in [0, 1)");
+        self.doji_body_pct = pct;
+        self
+    }
+
+    /// Set the smallest lower shadow of a hammer, as a multiple of its body.
+    pub fn with_hammer_shadow_ratio(mut self, ratio: f64) -> Self {
+        assert!(ratio > 0.0, "Hammer shadow ratio must be positive");
+        self.hammer_shadow_ratio = ratio;
+        self
+    }
+
+    /// Set the star thresholds: the outer bars' smallest body (fraction of
+    /// range) and the middle bar's largest body (fraction of the first body).
+    pub fn with_star_bodies(mut self, long_body_pct: f64, star_body_pct: f64) -> Self {
+        assert!(
+            long_body_pct > 0.0 && long_body_pct <= 1.0,
+            "Long body must be in (0, 1]"
+        );
+        assert!(star_body_pct > 0.0, "Star body must be positive");
+        self.long_body_pct = long_body_pct;
+        self.star_body_pct = star_body_pct;
+        self
+    }
+
+    /// Every pattern in the series, oldest first.
+    pub fn detect(&self, series: &BarSeries) -> Vec<PatternEvent> {
+        let bars = series.bars();
+        (0..bars.len())
+            .flat_map(|i| self.detect_at(bars, i))
+            .collect()
+    }
+
+    /// Patterns completed by the newest bar.
+    pub fn detect_last(&self, series: &BarSeries) -> Vec<PatternEvent> {
+        match series.len() {
+            0 => vec![],
+            len => self.detect_at(series.bars(), len - 1),
+        }
+    }
+
+    /// Whether a pattern completed by the newest bar agrees with a signal:
+    /// a bullish pattern for `Buy`/`CloseShort`, a bearish one for
+    /// `Sell`/`CloseLong`.
+    pub fn confirms(&self, series: &BarSeries, signal_type: SignalType) -> bool {
+        let wanted = match signal_type {
+            SignalType::Buy | SignalType::CloseShort => PatternBias::Bullish,
+            SignalType::Sell | SignalType::CloseLong => PatternBias::Bearish,
+            SignalType::Hold => return false,
+        };
+        self.detect_last(series)
+            .iter()
+            .any(|event| event.bias() == wanted)
+    }
+
+    /// Patterns completed by bar `index`.
+    fn detect_at(&self, bars: &VecDeque<Bar>, index: usize) -> Vec<PatternEvent> {
+        let bar = &bars[index];
+        let prev = index.checked_sub(1).map(|i| &bars[i]);
+        let first = index.checked_sub(2).map(|i| &bars[i]);
+
+        let found = [
+            (PatternKind::Doji, self.is_doji(bar)),
+            (PatternKind::Hammer, self.is_hammer(bar)),
+            (
+                PatternKind::BullishEngulfing,
+                prev.is_some_and(|p| p.is_bearish() && bar.is_bullish() && engulfs(bar, p)),
+            ),
+            (
+                PatternKind::BearishEngulfing,
+                prev.is_some_and(|p| p.is_bullish() && bar.is_bearish() && engulfs(bar, p)),
+            ),
+            (
+                PatternKind::MorningStar,
+                first
+                    .zip(prev)
+                    .is_some_and(|(a, b)| self.is_morning_star(a, b, bar)),
+            ),
+            (
+                PatternKind::EveningStar,
+                first
+                    .zip(prev)
+                    .is_some_and(|(a, b)| self.is_evening_star(a, b, bar)),
+            ),
+        ];
+
+        found
+            .into_iter()
+            .filter(|(_, hit)| *hit)
+            .map(|(kind, _)| PatternEvent {
+                kind,
+                index,
+                timestamp: bar.timestamp,
+            })
+            .collect()
+    }
+
+    fn is_doji(&self, bar: &Bar) -> bool {
+        bar.range() > 0.0 && bar.body() <= self.doji_body_pct * bar.range()
+    }
+
+    fn is_hammer(&self, bar: &Bar) -> bool {
+        let lower_shadow = bar.open.min(bar.close) - bar.low;
+        let upper_shadow = bar.high - bar.open.max(bar.close);
+        !self.is_doji(bar)
+            && lower_shadow >= self.hammer_shadow_ratio * bar.body()
+            && upper_shadow <= bar.body()
+    }
+
+    fn is_long(&self, bar: &Bar) -> bool {
+        bar.range() > 0.0 && bar.body() >= self.long_body_pct * bar.range()
+    }
+
+    fn is_morning_star(&self, a: &Bar, star: &Bar, c: &Bar) -> bool {
+        a.is_bearish()
+            && self.is_long(a)
+            && star.body() <= self.star_body_pct * a.body()
+            && star.open.max(star.close) <= a.close
+            && c.is_bullish()
+            && self.is_long(c)
+            && c.close >= (a.open + a.close) / 2.0
+    }
+
+    fn is_evening_star(&self, a: &Bar, star: &Bar, c: &Bar) -> bool {
+        a.is_bullish()
+            && self.is_long(a)
+            && star.body() <= self.star_body_pct * a.body()
+            && star.open.min(star.close) >= a.close
+            && c.is_bearish()
+            && self.is_long(c)
+            && c.close <= (a.open + a.close) / 2.0
+    }
+}
+
+/// Whether `bar`'s body covers `prev`'s body and is larger.
+fn engulfs(bar: &Bar, prev: &Bar) -> bool {
+    bar.open.min(bar.close) <= prev.open.min(prev.close)
+        && bar.open.max(bar.close) >= prev.open.max(prev.close)
+        && bar.body() > prev.body()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use trading_core::types::Timeframe;
+
+    fn series(bars: &[(f64, f64, f64, f64)]) -> BarSeries {
+        let mut series = BarSeries::new("TEST".to_string(), Timeframe::Daily);
+        for (i, &(open, high, low, close)) in bars.iter().enumerate() {
+            series.push(Bar::new(i as i64 * 1000, open, high, low, close, 1000.0));
+        }
+        series
+    }
+
+    fn kinds(series: &BarSeries) -> Vec<PatternKind> {
+        PatternDetector::new()
+            .detect_last(series)
+            .into_iter()
+            .map(|e| e.kind)
+            .collect()
+    }
+
+    #[test]
+    fn test_detects_patterns() {
+        assert_eq!(
+            kinds(&series(&[(100.0, 101.0, 99.0, 100.05)])),
+            vec![PatternKind::Doji]
+        );
+        assert_eq!(
+            kinds(&series(&[(100.0, 100.6, 96.0, 100.5)])),
+            vec![PatternKind::Hammer]
+        );
+        assert_eq!(
+            kinds(&series(&[
+                (102.0, 102.5, 99.5, 100.0),
+                (99.5, 103.5, 99.0, 103.0)
+            ])),
+            vec![PatternKind::BullishEngulfing]
+        );
+        assert_eq!(
+            kinds(&series(&[
+                (100.0, 102.5, 99.5, 102.0),
+                (102.5, 103.0, 98.5, 99.0)
+            ])),
+            vec![PatternKind::BearishEngulfing]
+        );
+        assert!(kinds(&series(&[
+            (110.0, 110.5, 101.5, 102.0),
+            (101.0, 101.8, 100.2, 101.2),
+            (102.0, 108.5, 101.5, 108.0),
+        ]))
+        .contains(&PatternKind::MorningStar));
+        assert!(kinds(&series(&[
+            (100.0, 108.5, 99.5, 108.0),
+            (108.8, 109.8, 108.2, 109.0),
+            (108.0, 108.5, 101.5, 102.0),
+        ]))
+        .contains(&PatternKind::EveningStar));
+
+        assert_eq!("morning-star".parse(), Ok(PatternKind::MorningStar));
+        assert!("shooting_star".parse::<PatternKind>().is_err());
+    }
+
+    #[test]
+    fn test_confirms_signal_direction() {
+        let bars = series(&[
+            (100.0, 100.5, 99.5, 100.2),
+            (102.0, 102.5, 99.5, 100.0),
+            (99.5, 103.5, 99.0, 103.0),
+        ]);
+        let detector = PatternDetector::new();
+
+        assert!(detector.confirms(&bars, SignalType::Buy));
+        assert!(!detector.confirms(&bars, SignalType::Sell));
+        assert!(!detector.confirms(&bars, SignalType::Hold));
+
+        let events = detector.detect(&bars);
+        assert!(events
+            .iter()
+            .any(|e| e.kind == PatternKind::BullishEngulfing
+                && e.index == 2
+                && e.timestamp == 2000));
+    }
+}
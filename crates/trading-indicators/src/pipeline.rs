@@ -0,0 +1,396 @@
+//! Composable indicator pipelines.
+//!
+//! A pipeline is a list of named steps evaluated in order, once per bar.
+//! Each step reads a bar field or the output of an earlier step, so
+//! indicators can be chained (RSI of an EMA, standard deviation of
+//! returns) without a strategy wiring the intermediate values by hand.
+//! Every step keeps a bounded history of its outputs.
+//!
+//! ```
+//! use trading_core::types::Bar;
+//! use trading_indicators::{
+//!     IndicatorPipeline, Input, StreamingEma, StreamingRsi, StreamingStdDev, Transform,
+//! };
+//!
+//! let mut pipeline = IndicatorPipeline::new()
+//!     .with_indicator("ema", Input::Close, StreamingEma::new(10))
+//!     .with_indicator("rsi_of_ema", Input::step("ema"), StreamingRsi::new(14))
+//!     .with_transform("returns", Input::Close, Transform::Returns(1))
+//!     .with_indicator("volatility", Input::step("returns"), StreamingStdDev::new(20));
+//!
+//! for i in 0..50 {
+//!     let price = 100.0 + (i as f64 * 0.3).sin() * 5.0;
+//!     pipeline.evaluate(&Bar::new(i, price, price, price, price, 1000.0));
+//! }
+//! assert!(pipeline.value("rsi_of_ema").is_some());
+//! assert!(pipeline.value("volatility").unwrap() > 0.0);
+//! ```
+
+use std::collections::{HashMap, VecDeque};
+use trading_core::traits::StreamingIndicator;
+use trading_core::types::Bar;
+
+/// Outputs kept per step unless set with
+/// [`with_history`](IndicatorPipeline::with_history).
+pub const DEFAULT_HISTORY: usize = 256;
+
+/// Value a pipeline step reads.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Input {
+    Open,
+    High,
+    Low,
+    Close,
+    Volume,
+    /// Output of an earlier step
+    Step(String),
+}
+
+impl Input {
+    /// Read the output of the step called `name`.
+    pub fn step(name: impl Into<String>) -> Self {
+        Input::Step(name.into())
+    }
+}
+
+/// Stateless transforms over a short lookback of the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transform {
+    /// Fractional change over `n` values: `(x - x[n]) / x[n]`
+    Returns(usize),
+    /// Log of the ratio to the value `n` back
+    LogReturns(usize),
+    /// Difference to the value `n` back
+    Change(usize),
+}
+
+impl Transform {
+    fn lookback(self) -> usize {
+        match self {
+            Transform::Returns(n) | Transform::LogReturns(n) | Transform::Change(n) => n,
+        }
+    }
+
+    fn apply(self, current: f64, past: f64) -> Option<f64> {
+        match self {
+            Transform::Returns(_) => (past != 0.0).then(|| (current - past) / past),
+            Transform::LogReturns(_) => {
+                (past > 0.0 && current > 0.0).then(|| (current / past).ln())
+            }
+            Transform::Change(_) => Some(current - past),
+        }
+    }
+}
+
+type Combiner = Box<dyn Fn(f64, f64) -> Option<f64> + Send + Sync>;
+
+/// What a step computes from its input.
+enum Operation {
+    Indicator(Box<dyn StreamingIndicator<Output = f64>>),
+    Transform {
+        transform: Transform,
+        window: VecDeque<f64>,
+    },
+    /// Combines the step's input with a second step's output
+    Combine {
+        other: usize,
+        combine: Combiner,
+    },
+}
+
+/// Where a step's input comes from, resolved when the step is added.
+#[derive(Clone, Copy)]
+enum Source {
+    Open,
+    High,
+    Low,
+    Close,
+    Volume,
+    Step(usize),
+}
+
+struct Step {
+    name: String,
+    source: Source,
+    operation: Operation,
+    value: Option<f64>,
+    history: VecDeque<f64>,
+}
+
+/// Chain of indicators evaluated once per bar.
+pub struct IndicatorPipeline {
+    steps: Vec<Step>,
+    history: usize,
+}
+
+impl Default for IndicatorPipeline {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IndicatorPipeline {
+    /// Create an empty pipeline.
+    pub fn new() -> Self {
+        Self {
+            steps: Vec::new(),
+            history: DEFAULT_HISTORY,
+        }
+    }
+
+    /// Number of outputs kept per step.
+    pub fn with_history(mut self, len: usize) -> Self {
+        assert!(len > 0, "History must keep at least one value");
+        self.history = len;
+        self
+    }
+
+    /// Add a step that feeds `input` to a streaming indicator.
+    pub fn with_indicator(
+        self,
+        name: impl Into<String>,
+        input: Input,
+        indicator: impl StreamingIndicator<Output = f64> + 'static,
+    ) -> Self {
+        self.with_step(name, input, Operation::Indicator(Box::new(indicator)))
+    }
+
+    /// Add a step that applies a transform to `input`.
+    pub fn with_transform(
+        self,
+        name: impl Into<String>,
+        input: Input,
+        transform: Transform,
+    ) -> Self {
+        assert!(transform.lookback() > 0, "Lookback must be greater than 0");
+        let window = VecDeque::with_capacity(transform.lookback() + 1);
+        self.with_step(name, input, Operation::Transform { transform, window })
+    }
+
+    /// Add a step combining two inputs, e.g. the spread of two averages.
+    ///
+    /// `combine` returning `None` leaves the step without a value for
+    /// that bar.
+    pub fn with_combined(
+        self,
+        name: impl Into<String>,
+        a: Input,
+        b: Input,
+        combine: impl Fn(f64, f64) -> Option<f64> + Send + Sync + 'static,
+    ) -> Self {
+        let Input::Step(other) = b else {
+            panic!("The second input of a combined step must be a step");
+        };
+        let other = self.resolve(&other);
+        self.with_step(
+            name,
+            a,
+            Operation::Combine {
+                other,
+                combine: Box::new(combine),
+            },
+        )
+    }
+
+    fn with_step(mut self, name: impl Into<String>, input: Input, operation: Operation) -> Self {
+        let name = name.into();
+        assert!(
+            self.index(&name).is_none(),
+            "Duplicate pipeline step: {}",
+            name
+        );
+        let source = match input {
+            Input::Open => Source::Open,
+            Input::High => Source::High,
+            Input::Low => Source::Low,
+            Input::Close => Source::Close,
+            Input::Volume => Source::Volume,
+            Input::Step(step) => Source::Step(self.resolve(&step)),
+        };
+        self.steps.push(Step {
+            name,
+            source,
+            operation,
+            value: None,
+            history: VecDeque::new(),
+        });
+        self
+    }
+
+    fn index(&self, name: &str) -> Option<usize> {
+        self.steps.iter().position(|s| s.name == name)
+    }
+
+    /// Steps may only read steps added before them, which keeps the
+    /// pipeline acyclic and evaluable in one pass.
+    fn resolve(&self, name: &str) -> usize {
+        self.index(name)
+            .unwrap_or_else(|| panic!("Unknown pipeline step: {}", name))
+    }
+
+    /// Feed a bar through every step.
+    ///
+    /// A step whose input has no value for this bar is skipped and has no
+    /// value either. Returns the last step's value.
+    pub fn evaluate(&mut self, bar: &Bar) -> Option<f64> {
+        for i in 0..self.steps.len() {
+            let (earlier, rest) = self.steps.split_at_mut(i);
+            let step = &mut rest[0];
+            let input = match step.source {
+                Source::Open => Some(bar.open),
+                Source::High => Some(bar.high),
+                Source::Low => Some(bar.low),
+                Source::Close => Some(bar.close),
+                Source::Volume => Some(bar.volume),
+                Source::Step(j) => earlier[j].value,
+            };
+
+            step.value = input.and_then(|value| match &mut step.operation {
+                Operation::Indicator(indicator) => indicator.update(value),
+                Operation::Transform { transform, window } => {
+                    window.push_back(value);
+                    if window.len() > transform.lookback() + 1 {
+                        window.pop_front();
+                    }
+                    if window.len() <= transform.lookback() {
+                        return None;
+                    }
+                    transform.apply(value, *window.front()?)
+                }
+                Operation::Combine { other, combine } => combine(value, earlier[*other].value?),
+            });
+
+            if let Some(value) = step.value {
+                step.history.push_back(value);
+                if step.history.len() > self.history {
+                    step.history.pop_front();
+                }
+            }
+        }
+        self.steps.last().and_then(|s| s.value)
+    }
+
+    /// Value of a step for the latest bar.
+    pub fn value(&self, name: &str) -> Option<f64> {
+        self.steps.iter().find(|s| s.name == name)?.value
+    }
+
+    /// Recent outputs of a step, oldest first.
+    pub fn series(&self, name: &str) -> Option<&VecDeque<f64>> {
+        self.steps
+            .iter()
+            .find(|s| s.name == name)
+            .map(|s| &s.history)
+    }
+
+    /// Values of all steps that have one for the latest bar.
+    pub fn values(&self) -> HashMap<String, f64> {
+        self.steps
+            .iter()
+            .filter_map(|s| Some((s.name.clone(), s.value?)))
+            .collect()
+    }
+
+    /// Whether every step has a value for the latest bar.
+    pub fn is_ready(&self) -> bool {
+        self.steps.iter().all(|s| s.value.is_some())
+    }
+
+    /// Step names in evaluation order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.steps.iter().map(|s| s.name.as_str())
+    }
+
+    /// Clear all indicator state and history.
+    pub fn reset(&mut self) {
+        for step in &mut self.steps {
+            match &mut step.operation {
+                Operation::Indicator(indicator) => indicator.reset(),
+                Operation::Transform { window, .. } => window.clear(),
+                Operation::Combine { .. } => {}
+            }
+            step.value = None;
+            step.history.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Ema, Rsi, StdDev, StreamingEma, StreamingRsi, StreamingStdDev};
+    use trading_core::traits::Indicator;
+
+    fn bars(closes: &[f64]) -> Vec<Bar> {
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| Bar::new(i as i64, c, c + 1.0, c - 1.0, c, 1000.0))
+            .collect()
+    }
+
+    #[test]
+    fn test_chained_steps_match_batch() {
+        let closes: Vec<f64> = (0..80)
+            .map(|i| 100.0 + (i as f64 * 0.2).sin() * 8.0 + i as f64 * 0.1)
+            .collect();
+        let mut pipeline = IndicatorPipeline::new()
+            .with_history(5)
+            .with_indicator("ema", Input::Close, StreamingEma::new(10))
+            .with_indicator("rsi_of_ema", Input::step("ema"), StreamingRsi::new(14))
+            .with_transform("returns", Input::Close, Transform::Returns(1))
+            .with_indicator(
+                "volatility",
+                Input::step("returns"),
+                StreamingStdDev::new(20),
+            );
+        for bar in bars(&closes) {
+            pipeline.evaluate(&bar);
+        }
+
+        let ema = Ema::new(10).calculate(&closes);
+        let rsi_of_ema = Rsi::new(14).calculate(&ema);
+        let returns: Vec<f64> = closes.windows(2).map(|w| (w[1] - w[0]) / w[0]).collect();
+        let volatility = StdDev::new(20).calculate(&returns);
+
+        let close_to = |a: Option<f64>, b: f64| (a.unwrap() - b).abs() < 1e-9;
+        assert!(close_to(pipeline.value("ema"), *ema.last().unwrap()));
+        assert!(close_to(
+            pipeline.value("rsi_of_ema"),
+            *rsi_of_ema.last().unwrap()
+        ));
+        assert!(close_to(
+            pipeline.value("volatility"),
+            *volatility.last().unwrap()
+        ));
+
+        let history = pipeline.series("ema").unwrap();
+        assert_eq!(history.len(), 5);
+        assert!((history[3] - ema[ema.len() - 2]).abs() < 1e-9);
+        assert!(pipeline.is_ready());
+    }
+
+    #[test]
+    fn test_combined_step_waits_for_both_inputs() {
+        let mut pipeline = IndicatorPipeline::new()
+            .with_indicator("fast", Input::Close, StreamingEma::new(2))
+            .with_indicator("slow", Input::Close, StreamingEma::new(4))
+            .with_combined(
+                "spread",
+                Input::step("fast"),
+                Input::step("slow"),
+                |f, s| Some(f - s),
+            );
+        let mut ready_at = None;
+        for (i, bar) in bars(&[1.0, 2.0, 3.0, 4.0, 5.0]).iter().enumerate() {
+            if pipeline.evaluate(bar).is_some() && ready_at.is_none() {
+                ready_at = Some(i);
+            }
+        }
+        assert_eq!(ready_at, Some(3));
+        assert!(pipeline.value("spread").unwrap() > 0.0);
+
+        pipeline.reset();
+        assert!(pipeline.values().is_empty());
+    }
+}
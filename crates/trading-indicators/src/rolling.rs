@@ -0,0 +1,388 @@
+//! Incremental rolling statistics.
+//!
+//! Building blocks for indicators that update in O(1) per value:
+//! - [`RollingSum`] - sum of the last `period` values
+//! - [`RollingStats`] - mean and variance of the last `period` values (Welford)
+//! - [`RunningStats`] - mean and variance of every value seen (Welford)
+//! - [`RollingMax`] / [`RollingMin`] - extremes of the last `period` values,
+//!   kept in a monotonic deque (amortized O(1))
+
+use std::collections::VecDeque;
+
+/// Sum of the last `period` values.
+#[derive(Debug, Clone)]
+pub struct RollingSum {
+    period: usize,
+    window: VecDeque<f64>,
+    sum: f64,
+}
+
+impl RollingSum {
+    /// Create a rolling sum over `period` values.
+    pub fn new(period: usize) -> Self {
+        assert!(period > 0, "Period must be greater than 0");
+        Self {
+            period,
+            window: VecDeque::with_capacity(period + 1),
+            sum: 0.0,
+        }
+    }
+
+    /// Add a value, dropping the oldest once the window is full.
+    pub fn push(&mut self, value: f64) {
+        self.window.push_back(value);
+        self.sum += value;
+        if self.window.len() > self.period {
+            self.sum -= self.window.pop_front().unwrap_or_default();
+        }
+    }
+
+    /// Sum of the values in the window.
+    pub fn sum(&self) -> f64 {
+        self.sum
+    }
+
+    /// Number of values in the window.
+    pub fn len(&self) -> usize {
+        self.window.len()
+    }
+
+    /// Whether the window is empty.
+    pub fn is_empty(&self) -> bool {
+        self.window.is_empty()
+    }
+
+    /// Whether the window holds `period` values.
+    pub fn is_full(&self) -> bool {
+        self.window.len() >= self.period
+    }
+
+    /// Window length.
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    /// Drop all values.
+    pub fn clear(&mut self) {
+        self.window.clear();
+        self.sum = 0.0;
+    }
+}
+
+/// Mean and population variance of the last `period` values.
+///
+/// Uses Welford's update while the window fills and its sliding-window
+/// form afterwards, which avoids the cancellation of a sum-of-squares.
+#[derive(Debug, Clone)]
+pub struct RollingStats {
+    period: usize,
+    window: VecDeque<f64>,
+    mean: f64,
+    /// Sum of squared deviations from the mean
+    m2: f64,
+}
+
+impl RollingStats {
+    /// Create rolling statistics over `period` values.
+    pub fn new(period: usize) -> Self {
+        assert!(period > 0, "Period must be greater than 0");
+        Self {
+            period,
+            window: VecDeque::with_capacity(period + 1),
+            mean: 0.0,
+            m2: 0.0,
+        }
+    }
+
+    /// Add a value, dropping the oldest once the window is full.
+    pub fn push(&mut self, value: f64) {
+        if self.window.len() < self.period {
+            self.window.push_back(value);
+            let delta = value - self.mean;
+            self.mean += delta / self.window.len() as f64;
+            self.m2 += delta * (value - self.mean);
+        } else {
+            let old = self.window.pop_front().unwrap_or_default();
+            self.window.push_back(value);
+            let prev_mean = self.mean;
+            self.mean += (value - old) / self.period as f64;
+            self.m2 += (value - old) * (value - self.mean + old - prev_mean);
+            self.m2 = self.m2.max(0.0);
+        }
+    }
+
+    /// Mean of the values in the window (0 when empty).
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Population variance of the values in the window (0 when empty).
+    pub fn variance(&self) -> f64 {
+        if self.window.is_empty() {
+            return 0.0;
+        }
+        self.m2 / self.window.len() as f64
+    }
+
+    /// Population standard deviation of the values in the window.
+    pub fn std_dev(&self) -> f64 {
+        self.variance().sqrt()
+    }
+
+    /// Number of values in the window.
+    pub fn len(&self) -> usize {
+        self.window.len()
+    }
+
+    /// Whether the window is empty.
+    pub fn is_empty(&self) -> bool {
+        self.window.is_empty()
+    }
+
+    /// Whether the window holds `period` values.
+    pub fn is_full(&self) -> bool {
+        self.window.len() >= self.period
+    }
+
+    /// Window length.
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    /// Drop all values.
+    pub fn clear(&mut self) {
+        self.window.clear();
+        self.mean = 0.0;
+        self.m2 = 0.0;
+    }
+}
+
+/// Mean and population variance of every value seen, in constant memory.
+#[derive(Debug, Clone, Default)]
+pub struct RunningStats {
+    count: usize,
+    mean: f64,
+    /// Sum of squared deviations from the mean
+    m2: f64,
+}
+
+impl RunningStats {
+    /// Create empty statistics.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a value.
+    pub fn push(&mut self, value: f64) {
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    /// Number of values seen.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Mean of the values seen (0 when empty).
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Population variance of the values seen (0 when empty).
+    pub fn variance(&self) -> f64 {
+        if self.count == 0 {
+            return 0.0;
+        }
+        self.m2 / self.count as f64
+    }
+
+    /// Population standard deviation of the values seen.
+    pub fn std_dev(&self) -> f64 {
+        self.variance().sqrt()
+    }
+
+    /// Forget all values.
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Monotonic deque of `(index, value)`; the front is the window's extreme.
+#[derive(Debug, Clone)]
+struct MonotonicWindow {
+    period: usize,
+    /// Values seen so far
+    count: usize,
+    deque: VecDeque<(usize, f64)>,
+    /// Whether `a` should evict `b` from the back: `>=` for max, `<=` for min
+    dominates: fn(f64, f64) -> bool,
+}
+
+impl MonotonicWindow {
+    fn new(period: usize, dominates: fn(f64, f64) -> bool) -> Self {
+        assert!(period > 0, "Period must be greater than 0");
+        Self {
+            period,
+            count: 0,
+            deque: VecDeque::with_capacity(period),
+            dominates,
+        }
+    }
+
+    fn push(&mut self, value: f64) -> f64 {
+        while matches!(self.deque.back(), Some(&(_, back)) if (self.dominates)(value, back)) {
+            self.deque.pop_back();
+        }
+        self.deque.push_back((self.count, value));
+        self.count += 1;
+        while matches!(self.deque.front(), Some(&(i, _)) if i + self.period < self.count) {
+            self.deque.pop_front();
+        }
+        self.deque.front().map_or(value, |&(_, v)| v)
+    }
+
+    fn get(&self) -> Option<f64> {
+        self.deque.front().map(|&(_, v)| v)
+    }
+
+    fn is_full(&self) -> bool {
+        self.count >= self.period
+    }
+
+    fn clear(&mut self) {
+        self.count = 0;
+        self.deque.clear();
+    }
+}
+
+/// Maximum of the last `period` values.
+#[derive(Debug, Clone)]
+pub struct RollingMax(MonotonicWindow);
+
+impl RollingMax {
+    /// Create a rolling maximum over `period` values.
+    pub fn new(period: usize) -> Self {
+        Self(MonotonicWindow::new(period, |a, b| a >= b))
+    }
+
+    /// Add a value; returns the maximum of the window.
+    pub fn push(&mut self, value: f64) -> f64 {
+        self.0.push(value)
+    }
+
+    /// Maximum of the window, if any values were pushed.
+    pub fn get(&self) -> Option<f64> {
+        self.0.get()
+    }
+
+    /// Whether the window holds `period` values.
+    pub fn is_full(&self) -> bool {
+        self.0.is_full()
+    }
+
+    /// Window length.
+    pub fn period(&self) -> usize {
+        self.0.period
+    }
+
+    /// Drop all values.
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+/// Minimum of the last `period` values.
+#[derive(Debug, Clone)]
+pub struct RollingMin(MonotonicWindow);
+
+impl RollingMin {
+    /// Create a rolling minimum over `period` values.
+    pub fn new(period: usize) -> Self {
+        Self(MonotonicWindow::new(period, |a, b| a <= b))
+    }
+
+    /// Add a value; returns the minimum of the window.
+    pub fn push(&mut self, value: f64) -> f64 {
+        self.0.push(value)
+    }
+
+    /// Minimum of the window, if any values were pushed.
+    pub fn get(&self) -> Option<f64> {
+        self.0.get()
+    }
+
+    /// Whether the window holds `period` values.
+    pub fn is_full(&self) -> bool {
+        self.0.is_full()
+    }
+
+    /// Window length.
+    pub fn period(&self) -> usize {
+        self.0.period
+    }
+
+    /// Drop all values.
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rolling_windows_match_naive() {
+        let data: Vec<f64> = (0..200)
+            .map(|i| 100.0 + (i as f64 * 0.7).sin() * 10.0 + (i % 7) as f64)
+            .collect();
+        let period = 9;
+
+        let mut sum = RollingSum::new(period);
+        let mut stats = RollingStats::new(period);
+        let mut max = RollingMax::new(period);
+        let mut min = RollingMin::new(period);
+
+        for (i, &value) in data.iter().enumerate() {
+            sum.push(value);
+            stats.push(value);
+            let high = max.push(value);
+            let low = min.push(value);
+
+            let window = &data[(i + 1).saturating_sub(period)..=i];
+            let mean = window.iter().sum::<f64>() / window.len() as f64;
+            let variance =
+                window.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / window.len() as f64;
+
+            assert!((sum.sum() - window.iter().sum::<f64>()).abs() < 1e-9);
+            assert!((stats.mean() - mean).abs() < 1e-9);
+            assert!((stats.variance() - variance).abs() < 1e-9);
+            assert_eq!(
+                high,
+                window.iter().cloned().fold(f64::NEG_INFINITY, f64::max)
+            );
+            assert_eq!(low, window.iter().cloned().fold(f64::INFINITY, f64::min));
+            assert_eq!(stats.is_full(), i + 1 >= period);
+            assert_eq!(max.is_full(), i + 1 >= period);
+        }
+
+        max.clear();
+        assert_eq!(max.get(), None);
+        assert!(!max.is_full());
+    }
+
+    #[test]
+    fn test_running_stats() {
+        let mut stats = RunningStats::new();
+        assert_eq!(stats.variance(), 0.0);
+
+        for value in [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0] {
+            stats.push(value);
+        }
+        assert_eq!(stats.count(), 8);
+        assert!((stats.mean() - 5.0).abs() < 1e-12);
+        assert!((stats.std_dev() - 2.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,576 @@
+//! CPU-specific kernels with runtime dispatch.
+//!
+//! The portable kernels use `wide` and run on any CPU. On x86_64 the AVX2
+//! (with FMA) kernels, and with the `avx512` crate feature the AVX-512
+//! kernels, are used when the CPU supports them; on aarch64 the NEON kernels
+//! are. Detection runs once, on first use.
+
+use std::fmt;
+use std::sync::OnceLock;
+use wide::f64x4;
+
+/// Instruction set a kernel table runs on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SimdLevel {
+    /// `wide` 4-lane vectors, lowered to whatever the build targets
+    Portable,
+    /// 256-bit AVX2 with fused multiply-add (x86_64)
+    Avx2,
+    /// 512-bit AVX-512F (x86_64, `avx512` feature)
+    Avx512,
+    /// 128-bit NEON (aarch64)
+    Neon,
+}
+
+impl SimdLevel {
+    /// Every level, portable first.
+    pub const ALL: [SimdLevel; 4] = [
+        SimdLevel::Portable,
+        SimdLevel::Avx2,
+        SimdLevel::Avx512,
+        SimdLevel::Neon,
+    ];
+
+    /// Whether this build has kernels for the level and the CPU runs them.
+    pub fn is_supported(self) -> bool {
+        match self {
+            SimdLevel::Portable => true,
+            #[cfg(target_arch = "x86_64")]
+            SimdLevel::Avx2 => is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma"),
+            #[cfg(all(target_arch = "x86_64", feature = "avx512"))]
+            SimdLevel::Avx512 => is_x86_feature_detected!("avx512f"),
+            #[cfg(target_arch = "aarch64")]
+            SimdLevel::Neon => std::arch::is_aarch64_feature_detected!("neon"),
+            #[allow(unreachable_patterns)]
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for SimdLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SimdLevel::Portable => write!(f, "portable"),
+            SimdLevel::Avx2 => write!(f, "avx2"),
+            SimdLevel::Avx512 => write!(f, "avx512"),
+            SimdLevel::Neon => write!(f, "neon"),
+        }
+    }
+}
+
+/// Reduction kernels for one instruction set.
+#[derive(Debug, Clone, Copy)]
+pub struct SimdKernels {
+    /// Instruction set the kernels run on
+    pub level: SimdLevel,
+    /// Sum of a slice
+    pub sum: fn(&[f64]) -> f64,
+    /// Dot product over the shorter of two slices
+    pub dot: fn(&[f64], &[f64]) -> f64,
+    /// Minimum and maximum; `(inf, -inf)` for an empty slice
+    pub minmax: fn(&[f64]) -> (f64, f64),
+    /// Sum of squared deviations from a mean
+    pub sum_sq_dev: fn(&[f64], f64) -> f64,
+}
+
+impl SimdKernels {
+    /// Fastest kernels the CPU supports, detected on first call.
+    pub fn detect() -> &'static SimdKernels {
+        static KERNELS: OnceLock<SimdKernels> = OnceLock::new();
+        KERNELS.get_or_init(|| {
+            [SimdLevel::Avx512, SimdLevel::Avx2, SimdLevel::Neon]
+                .into_iter()
+                .find_map(Self::for_level)
+                .unwrap_or(PORTABLE)
+        })
+    }
+
+    /// Kernels for `level`, if this build has them and the CPU supports it.
+    pub fn for_level(level: SimdLevel) -> Option<SimdKernels> {
+        if !level.is_supported() {
+            return None;
+        }
+        match level {
+            SimdLevel::Portable => Some(PORTABLE),
+            #[cfg(target_arch = "x86_64")]
+            SimdLevel::Avx2 => Some(avx2::KERNELS),
+            #[cfg(all(target_arch = "x86_64", feature = "avx512"))]
+            SimdLevel::Avx512 => Some(avx512::KERNELS),
+            #[cfg(target_arch = "aarch64")]
+            SimdLevel::Neon => Some(neon::KERNELS),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+}
+
+const PORTABLE: SimdKernels = SimdKernels {
+    level: SimdLevel::Portable,
+    sum: portable::sum,
+    dot: portable::dot,
+    minmax: portable::minmax,
+    sum_sq_dev: portable::sum_sq_dev,
+};
+
+mod portable {
+    use super::f64x4;
+
+    fn lanes(data: &[f64], idx: usize) -> f64x4 {
+        f64x4::new([data[idx], data[idx + 1], data[idx + 2], data[idx + 3]])
+    }
+
+    pub fn sum(data: &[f64]) -> f64 {
+        let chunks = data.len() / 4;
+        let mut acc = f64x4::splat(0.0);
+        for i in 0..chunks {
+            acc += lanes(data, i * 4);
+        }
+        let mut total = acc.reduce_add();
+        for &value in &data[chunks * 4..] {
+            total += value;
+        }
+        total
+    }
+
+    pub fn dot(a: &[f64], b: &[f64]) -> f64 {
+        let len = a.len().min(b.len());
+        let chunks = len / 4;
+        let mut acc = f64x4::splat(0.0);
+        for i in 0..chunks {
+            acc += lanes(a, i * 4) * lanes(b, i * 4);
+        }
+        let mut total = acc.reduce_add();
+        for i in chunks * 4..len {
+            total += a[i] * b[i];
+        }
+        total
+    }
+
+    pub fn minmax(data: &[f64]) -> (f64, f64) {
+        let chunks = data.len() / 4;
+        let mut min_vec = f64x4::splat(f64::INFINITY);
+        let mut max_vec = f64x4::splat(f64::NEG_INFINITY);
+        for i in 0..chunks {
+            let values = lanes(data, i * 4);
+            min_vec = min_vec.min(values);
+            max_vec = max_vec.max(values);
+        }
+        let min_arr = min_vec.to_array();
+        let max_arr = max_vec.to_array();
+        let mut min = min_arr[0].min(min_arr[1]).min(min_arr[2]).min(min_arr[3]);
+        let mut max = max_arr[0].max(max_arr[1]).max(max_arr[2]).max(max_arr[3]);
+        for &value in &data[chunks * 4..] {
+            min = min.min(value);
+            max = max.max(value);
+        }
+        (min, max)
+    }
+
+    pub fn sum_sq_dev(data: &[f64], mean: f64) -> f64 {
+        let chunks = data.len() / 4;
+        let mean_vec = f64x4::splat(mean);
+        let mut acc = f64x4::splat(0.0);
+        for i in 0..chunks {
+            let diff = lanes(data, i * 4) - mean_vec;
+            acc += diff * diff;
+        }
+        let mut total = acc.reduce_add();
+        for &value in &data[chunks * 4..] {
+            total += (value - mean) * (value - mean);
+        }
+        total
+    }
+}
+
+/// AVX2 kernels. Two accumulators hide the add latency.
+#[cfg(target_arch = "x86_64")]
+mod avx2 {
+    use super::{SimdKernels, SimdLevel};
+    use std::arch::x86_64::*;
+
+    pub const KERNELS: SimdKernels = SimdKernels {
+        level: SimdLevel::Avx2,
+        sum,
+        dot,
+        minmax,
+        sum_sq_dev,
+    };
+
+    // SAFETY (all wrappers): the table is only handed out once
+    // `SimdLevel::Avx2.is_supported()` has confirmed AVX2 and FMA.
+    fn sum(data: &[f64]) -> f64 {
+        unsafe { sum_impl(data) }
+    }
+
+    fn dot(a: &[f64], b: &[f64]) -> f64 {
+        unsafe { dot_impl(a, b) }
+    }
+
+    fn minmax(data: &[f64]) -> (f64, f64) {
+        unsafe { minmax_impl(data) }
+    }
+
+    fn sum_sq_dev(data: &[f64], mean: f64) -> f64 {
+        unsafe { sum_sq_dev_impl(data, mean) }
+    }
+
+    #[target_feature(enable = "avx")]
+    unsafe fn reduce_add(v: __m256d) -> f64 {
+        let mut lanes = [0.0; 4];
+        _mm256_storeu_pd(lanes.as_mut_ptr(), v);
+        (lanes[0] + lanes[1]) + (lanes[2] + lanes[3])
+    }
+
+    #[target_feature(enable = "avx2,fma")]
+    unsafe fn sum_impl(data: &[f64]) -> f64 {
+        let ptr = data.as_ptr();
+        let chunks = data.len() / 8;
+        let mut acc0 = _mm256_setzero_pd();
+        let mut acc1 = _mm256_setzero_pd();
+        for i in 0..chunks {
+            acc0 = _mm256_add_pd(acc0, _mm256_loadu_pd(ptr.add(i * 8)));
+            acc1 = _mm256_add_pd(acc1, _mm256_loadu_pd(ptr.add(i * 8 + 4)));
+        }
+        let mut total = reduce_add(_mm256_add_pd(acc0, acc1));
+        for &value in &data[chunks * 8..] {
+            total += value;
+        }
+        total
+    }
+
+    #[target_feature(enable = "avx2,fma")]
+    unsafe fn dot_impl(a: &[f64], b: &[f64]) -> f64 {
+        let len = a.len().min(b.len());
+        let (pa, pb) = (a.as_ptr(), b.as_ptr());
+        let chunks = len / 8;
+        let mut acc0 = _mm256_setzero_pd();
+        let mut acc1 = _mm256_setzero_pd();
+        for i in 0..chunks {
+            let j = i * 8;
+            acc0 = _mm256_fmadd_pd(_mm256_loadu_pd(pa.add(j)), _mm256_loadu_pd(pb.add(j)), acc0);
+            acc1 = _mm256_fmadd_pd(
+                _mm256_loadu_pd(pa.add(j + 4)),
+                _mm256_loadu_pd(pb.add(j + 4)),
+                acc1,
+            );
+        }
+        let mut total = reduce_add(_mm256_add_pd(acc0, acc1));
+        for i in chunks * 8..len {
+            total += a[i] * b[i];
+        }
+        total
+    }
+
+    #[target_feature(enable = "avx2,fma")]
+    unsafe fn minmax_impl(data: &[f64]) -> (f64, f64) {
+        let ptr = data.as_ptr();
+        let chunks = data.len() / 4;
+        let mut min_vec = _mm256_set1_pd(f64::INFINITY);
+        let mut max_vec = _mm256_set1_pd(f64::NEG_INFINITY);
+        for i in 0..chunks {
+            let values = _mm256_loadu_pd(ptr.add(i * 4));
+            min_vec = _mm256_min_pd(min_vec, values);
+            max_vec = _mm256_max_pd(max_vec, values);
+        }
+        let mut mins = [0.0; 4];
+        let mut maxs = [0.0; 4];
+        _mm256_storeu_pd(mins.as_mut_ptr(), min_vec);
+        _mm256_storeu_pd(maxs.as_mut_ptr(), max_vec);
+        let mut min = mins.iter().copied().fold(f64::INFINITY, f64::min);
+        let mut max = maxs.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        for &value in &data[chunks * 4..] {
+            min = min.min(value);
+            max = max.max(value);
+        }
+        (min, max)
+    }
+
+    #[target_feature(enable = "avx2,fma")]
+    unsafe fn sum_sq_dev_impl(data: &[f64], mean: f64) -> f64 {
+        let ptr = data.as_ptr();
+        let chunks = data.len() / 8;
+        let mean_vec = _mm256_set1_pd(mean);
+        let mut acc0 = _mm256_setzero_pd();
+        let mut acc1 = _mm256_setzero_pd();
+        for i in 0..chunks {
+            let d0 = _mm256_sub_pd(_mm256_loadu_pd(ptr.add(i * 8)), mean_vec);
+            let d1 = _mm256_sub_pd(_mm256_loadu_pd(ptr.add(i * 8 + 4)), mean_vec);
+            acc0 = _mm256_fmadd_pd(d0, d0, acc0);
+            acc1 = _mm256_fmadd_pd(d1, d1, acc1);
+        }
+        let mut total = reduce_add(_mm256_add_pd(acc0, acc1));
+        for &value in &data[chunks * 8..] {
+            total += (value - mean) * (value - mean);
+        }
+        total
+    }
+}
+
+/// AVX-512 kernels. The intrinsics need Rust 1.89, above the crate's MSRV,
+/// so they are behind the `avx512` feature.
+#[cfg(all(target_arch = "x86_64", feature = "avx512"))]
+#[allow(clippy::incompatible_msrv)]
+mod avx512 {
+    use super::{SimdKernels, SimdLevel};
+    use std::arch::x86_64::*;
+
+    pub const KERNELS: SimdKernels = SimdKernels {
+        level: SimdLevel::Avx512,
+        sum,
+        dot,
+        minmax,
+        sum_sq_dev,
+    };
+
+    // SAFETY (all wrappers): the table is only handed out once
+    // `SimdLevel::Avx512.is_supported()` has confirmed AVX-512F.
+    fn sum(data: &[f64]) -> f64 {
+        unsafe { sum_impl(data) }
+    }
+
+    fn dot(a: &[f64], b: &[f64]) -> f64 {
+        unsafe { dot_impl(a, b) }
+    }
+
+    fn minmax(data: &[f64]) -> (f64, f64) {
+        unsafe { minmax_impl(data) }
+    }
+
+    fn sum_sq_dev(data: &[f64], mean: f64) -> f64 {
+        unsafe { sum_sq_dev_impl(data, mean) }
+    }
+
+    #[target_feature(enable = "avx512f")]
+    unsafe fn sum_impl(data: &[f64]) -> f64 {
+        let ptr = data.as_ptr();
+        let chunks = data.len() / 16;
+        let mut acc0 = _mm512_setzero_pd();
+        let mut acc1 = _mm512_setzero_pd();
+        for i in 0..chunks {
+            acc0 = _mm512_add_pd(acc0, _mm512_loadu_pd(ptr.add(i * 16)));
+            acc1 = _mm512_add_pd(acc1, _mm512_loadu_pd(ptr.add(i * 16 + 8)));
+        }
+        let mut total = _mm512_reduce_add_pd(_mm512_add_pd(acc0, acc1));
+        for &value in &data[chunks * 16..] {
+            total += value;
+        }
+        total
+    }
+
+    #[target_feature(enable = "avx512f")]
+    unsafe fn dot_impl(a: &[f64], b: &[f64]) -> f64 {
+        let len = a.len().min(b.len());
+        let (pa, pb) = (a.as_ptr(), b.as_ptr());
+        let chunks = len / 16;
+        let mut acc0 = _mm512_setzero_pd();
+        let mut acc1 = _mm512_setzero_pd();
+        for i in 0..chunks {
+            let j = i * 16;
+            acc0 = _mm512_fmadd_pd(_mm512_loadu_pd(pa.add(j)), _mm512_loadu_pd(pb.add(j)), acc0);
+            acc1 = _mm512_fmadd_pd(
+                _mm512_loadu_pd(pa.add(j + 8)),
+                _mm512_loadu_pd(pb.add(j + 8)),
+                acc1,
+            );
+        }
+        let mut total = _mm512_reduce_add_pd(_mm512_add_pd(acc0, acc1));
+        for i in chunks * 16..len {
+            total += a[i] * b[i];
+        }
+        total
+    }
+
+    #[target_feature(enable = "avx512f")]
+    unsafe fn minmax_impl(data: &[f64]) -> (f64, f64) {
+        let ptr = data.as_ptr();
+        let chunks = data.len() / 8;
+        let mut min_vec = _mm512_set1_pd(f64::INFINITY);
+        let mut max_vec = _mm512_set1_pd(f64::NEG_INFINITY);
+        for i in 0..chunks {
+            let values = _mm512_loadu_pd(ptr.add(i * 8));
+            min_vec = _mm512_min_pd(min_vec, values);
+            max_vec = _mm512_max_pd(max_vec, values);
+        }
+        let mut min = _mm512_reduce_min_pd(min_vec);
+        let mut max = _mm512_reduce_max_pd(max_vec);
+        for &value in &data[chunks * 8..] {
+            min = min.min(value);
+            max = max.max(value);
+        }
+        (min, max)
+    }
+
+    #[target_feature(enable = "avx512f")]
+    unsafe fn sum_sq_dev_impl(data: &[f64], mean: f64) -> f64 {
+        let ptr = data.as_ptr();
+        let chunks = data.len() / 16;
+        let mean_vec = _mm512_set1_pd(mean);
+        let mut acc0 = _mm512_setzero_pd();
+        let mut acc1 = _mm512_setzero_pd();
+        for i in 0..chunks {
+            let d0 = _mm512_sub_pd(_mm512_loadu_pd(ptr.add(i * 16)), mean_vec);
+            let d1 = _mm512_sub_pd(_mm512_loadu_pd(ptr.add(i * 16 + 8)), mean_vec);
+            acc0 = _mm512_fmadd_pd(d0, d0, acc0);
+            acc1 = _mm512_fmadd_pd(d1, d1, acc1);
+        }
+        let mut total = _mm512_reduce_add_pd(_mm512_add_pd(acc0, acc1));
+        for &value in &data[chunks * 16..] {
+            total += (value - mean) * (value - mean);
+        }
+        total
+    }
+}
+
+/// NEON kernels.
+#[cfg(target_arch = "aarch64")]
+mod neon {
+    use super::{SimdKernels, SimdLevel};
+    use std::arch::aarch64::*;
+
+    pub const KERNELS: SimdKernels = SimdKernels {
+        level: SimdLevel::Neon,
+        sum,
+        dot,
+        minmax,
+        sum_sq_dev,
+    };
+
+    // SAFETY (all wrappers): the table is only handed out once
+    // `SimdLevel::Neon.is_supported()` has confirmed NEON.
+    fn sum(data: &[f64]) -> f64 {
+        unsafe { sum_impl(data) }
+    }
+
+    fn dot(a: &[f64], b: &[f64]) -> f64 {
+        unsafe { dot_impl(a, b) }
+    }
+
+    fn minmax(data: &[f64]) -> (f64, f64) {
+        unsafe { minmax_impl(data) }
+    }
+
+    fn sum_sq_dev(data: &[f64], mean: f64) -> f64 {
+        unsafe { sum_sq_dev_impl(data, mean) }
+    }
+
+    #[target_feature(enable = "neon")]
+    unsafe fn sum_impl(data: &[f64]) -> f64 {
+        let ptr = data.as_ptr();
+        let chunks = data.len() / 4;
+        let mut acc0 = vdupq_n_f64(0.0);
+        let mut acc1 = vdupq_n_f64(0.0);
+        for i in 0..chunks {
+            acc0 = vaddq_f64(acc0, vld1q_f64(ptr.add(i * 4)));
+            acc1 = vaddq_f64(acc1, vld1q_f64(ptr.add(i * 4 + 2)));
+        }
+        let mut total = vaddvq_f64(vaddq_f64(acc0, acc1));
+        for &value in &data[chunks * 4..] {
+            total += value;
+        }
+        total
+    }
+
+    #[target_feature(enable = "neon")]
+    unsafe fn dot_impl(a: &[f64], b: &[f64]) -> f64 {
+        let len = a.len().min(b.len());
+        let (pa, pb) = (a.as_ptr(), b.as_ptr());
+        let chunks = len / 4;
+        let mut acc0 = vdupq_n_f64(0.0);
+        let mut acc1 = vdupq_n_f64(0.0);
+        for i in 0..chunks {
+            let j = i * 4;
+            acc0 = vfmaq_f64(acc0, vld1q_f64(pa.add(j)), vld1q_f64(pb.add(j)));
+            acc1 = vfmaq_f64(acc1, vld1q_f64(pa.add(j + 2)), vld1q_f64(pb.add(j + 2)));
+        }
+        let mut total = vaddvq_f64(vaddq_f64(acc0, acc1));
+        for i in chunks * 4..len {
+            total += a[i] * b[i];
+        }
+        total
+    }
+
+    #[target_feature(enable = "neon")]
+    unsafe fn minmax_impl(data: &[f64]) -> (f64, f64) {
+        let ptr = data.as_ptr();
+        let chunks = data.len() / 2;
+        let mut min_vec = vdupq_n_f64(f64::INFINITY);
+        let mut max_vec = vdupq_n_f64(f64::NEG_INFINITY);
+        for i in 0..chunks {
+            let values = vld1q_f64(ptr.add(i * 2));
+            min_vec = vminq_f64(min_vec, values);
+            max_vec = vmaxq_f64(max_vec, values);
+        }
+        let mut min = vminvq_f64(min_vec);
+        let mut max = vmaxvq_f64(max_vec);
+        for &value in &data[chunks * 2..] {
+            min = min.min(value);
+            max = max.max(value);
+        }
+        (min, max)
+    }
+
+    #[target_feature(enable = "neon")]
+    unsafe fn sum_sq_dev_impl(data: &[f64], mean: f64) -> f64 {
+        let ptr = data.as_ptr();
+        let chunks = data.len() / 4;
+        let mean_vec = vdupq_n_f64(mean);
+        let mut acc0 = vdupq_n_f64(0.0);
+        let mut acc1 = vdupq_n_f64(0.0);
+        for i in 0..chunks {
+            let d0 = vsubq_f64(vld1q_f64(ptr.add(i * 4)), mean_vec);
+            let d1 = vsubq_f64(vld1q_f64(ptr.add(i * 4 + 2)), mean_vec);
+            acc0 = vfmaq_f64(acc0, d0, d0);
+            acc1 = vfmaq_f64(acc1, d1, d1);
+        }
+        let mut total = vaddvq_f64(vaddq_f64(acc0, acc1));
+        for &value in &data[chunks * 4..] {
+            total += (value - mean) * (value - mean);
+        }
+        total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_supported_level_matches_portable() {
+        // Odd length exercises the scalar tails of every kernel width
+        let a: Vec<f64> = (0..1003)
+            .map(|i| 100.0 + (i as f64 * 0.37).sin() * 7.0)
+            .collect();
+        let b: Vec<f64> = (0..1001).map(|i| (i as f64 * 0.11).cos()).collect();
+        let mean = portable::sum(&a) / a.len() as f64;
+        let near = |x: f64, y: f64| (x - y).abs() <= 1e-9 * y.abs().max(1.0);
+
+        assert_eq!(
+            SimdKernels::for_level(SimdLevel::Portable).unwrap().level,
+            SimdLevel::Portable
+        );
+        assert!(SimdKernels::detect().level.is_supported());
+
+        for level in SimdLevel::ALL {
+            let Some(k) = SimdKernels::for_level(level) else {
+                continue;
+            };
+            assert_eq!(k.level, level);
+            assert!(near((k.sum)(&a), portable::sum(&a)), "{}", level);
+            assert!(near((k.dot)(&a, &b), portable::dot(&a, &b)), "{}", level);
+            assert_eq!((k.minmax)(&a), portable::minmax(&a), "{}", level);
+            assert_eq!((k.minmax)(&[]), (f64::INFINITY, f64::NEG_INFINITY));
+            assert!(
+                near((k.sum_sq_dev)(&a, mean), portable::sum_sq_dev(&a, mean)),
+                "{}",
+                level
+            );
+            for len in 0..20 {
+                assert!(
+                    near((k.sum)(&a[..len]), portable::sum(&a[..len])),
+                    "{}",
+                    level
+                );
+            }
+        }
+    }
+}
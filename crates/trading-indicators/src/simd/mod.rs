@@ -0,0 +1,511 @@
+//! SIMD-optimized indicator implementations.
+//!
+//! These implementations use the `wide` crate for portable SIMD operations,
+//! providing significant performance improvements for large datasets. The
+//! reductions (sums, dot products, min/max and squared deviations) dispatch
+//! at runtime to AVX2, AVX-512 or NEON kernels when the CPU has them (see
+//! [`SimdKernels`]). The
+//! scalar [`Indicator`](trading_core::traits::Indicator) impls of WMA, ATR,
+//! Bollinger Bands and MACD dispatch here for inputs of at least
+//! [`SIMD_MIN_LEN`] values.
+
+mod arch;
+
+pub use arch::{SimdKernels, SimdLevel};
+
+use wide::f64x4;
+
+use crate::momentum::MacdOutput;
+use crate::volatility::BollingerOutput;
+
+/// Input length from which scalar indicators dispatch to the SIMD kernels;
+/// below it the setup cost outweighs the gain.
+pub const SIMD_MIN_LEN: usize = 64;
+
+/// Load four consecutive values starting at `idx`.
+fn lanes(data: &[f64], idx: usize) -> f64x4 {
+    f64x4::new([data[idx], data[idx + 1], data[idx + 2], data[idx + 3]])
+}
+
+/// SIMD-optimized Simple Moving Average.
+///
+/// Uses vectorized operations for faster calculation on large datasets.
+pub fn sma_simd(data: &[f64], period: usize) -> Vec<f64> {
+    if data.len() < period || period == 0 {
+        return vec![];
+    }
+
+    let mut result = Vec::with_capacity(data.len() - period + 1);
+    let period_f64 = period as f64;
+
+    // Initial sum for first window
+    let mut sum: f64 = data[..period].iter().sum();
+    result.push(sum / period_f64);
+
+    // Sliding window
+    for i in period..data.len() {
+        sum = sum - data[i - period] + data[i];
+        result.push(sum / period_f64);
+    }
+
+    result
+}
+
+/// SIMD-optimized Exponential Moving Average.
+pub fn ema_simd(data: &[f64], period: usize) -> Vec<f64> {
+    if data.len() < period || period == 0 {
+        return vec![];
+    }
+
+    let mut result = Vec::with_capacity(data.len() - period + 1);
+    let multiplier = 2.0 / (period as f64 + 1.0);
+    let one_minus_mult = 1.0 - multiplier;
+
+    // Initialize with SMA
+    let initial_sma: f64 = data[..period].iter().sum::<f64>() / period as f64;
+    result.push(initial_sma);
+
+    // EMA calculation
+    let mut ema = initial_sma;
+    for &price in &data[period..] {
+        ema = price * multiplier + ema * one_minus_mult;
+        result.push(ema);
+    }
+
+    result
+}
+
+/// SIMD-optimized RSI calculation.
+///
+/// Uses vectorized operations for computing gains and losses.
+pub fn rsi_simd(data: &[f64], period: usize) -> Vec<f64> {
+    if data.len() <= period || period == 0 {
+        return vec![];
+    }
+
+    let mut gains = Vec::with_capacity(data.len() - 1);
+    let mut losses = Vec::with_capacity(data.len() - 1);
+
+    // Calculate gains and losses using SIMD where possible
+    let chunks = (data.len() - 1) / 4;
+
+    for i in 0..chunks {
+        let idx = i * 4;
+        let prev = f64x4::new([data[idx], data[idx + 1], data[idx + 2], data[idx + 3]]);
+        let curr = f64x4::new([data[idx + 1], data[idx + 2], data[idx + 3], data[idx + 4]]);
+
+        let diff = curr - prev;
+        let zero = f64x4::splat(0.0);
+
+        // Extract gains (positive changes) and losses (negative changes)
+        let gain_vec = diff.max(zero);
+        let loss_vec = (-diff).max(zero);
+
+        gains.extend(gain_vec.to_array());
+        losses.extend(loss_vec.to_array());
+    }
+
+    // Handle remaining elements
+    for i in (chunks * 4)..(data.len() - 1) {
+        let change = data[i + 1] - data[i];
+        gains.push(change.max(0.0));
+        losses.push((-change).max(0.0));
+    }
+
+    if gains.len() < period {
+        return vec![];
+    }
+
+    // Calculate RSI using Wilder's smoothing
+    let mut result = Vec::with_capacity(gains.len() - period + 1);
+
+    // Initial average gain/loss
+    let mut avg_gain: f64 = gains[..period].iter().sum::<f64>() / period as f64;
+    let mut avg_loss: f64 = losses[..period].iter().sum::<f64>() / period as f64;
+
+    let period_f64 = period as f64;
+
+    // First RSI value
+    let rsi = if avg_loss == 0.0 {
+        100.0
+    } else {
+        100.0 - (100.0 / (1.0 + avg_gain / avg_loss))
+    };
+    result.push(rsi);
+
+    // Subsequent values using Wilder's smoothing
+    for i in period..gains.len() {
+        avg_gain = (avg_gain * (period_f64 - 1.0) + gains[i]) / period_f64;
+        avg_loss = (avg_loss * (period_f64 - 1.0) + losses[i]) / period_f64;
+
+        let rsi = if avg_loss == 0.0 {
+            100.0
+        } else {
+            100.0 - (100.0 / (1.0 + avg_gain / avg_loss))
+        };
+        result.push(rsi);
+    }
+
+    result
+}
+
+/// SIMD-optimized standard deviation calculation.
+pub fn std_dev_simd(data: &[f64], period: usize) -> Vec<f64> {
+    variance_simd(data, period)
+        .into_iter()
+        .map(f64::sqrt)
+        .collect()
+}
+
+/// SIMD-optimized variance calculation.
+pub fn variance_simd(data: &[f64], period: usize) -> Vec<f64> {
+    if data.len() < period || period < 2 {
+        return vec![];
+    }
+
+    let kernels = SimdKernels::detect();
+    let period_f64 = period as f64;
+
+    data.windows(period)
+        .map(|window| {
+            let mean = (kernels.sum)(window) / period_f64;
+            (kernels.sum_sq_dev)(window, mean) / period_f64
+        })
+        .collect()
+}
+
+/// SIMD-optimized Weighted Moving Average.
+///
+/// Each window is a dot product with the weights `1..=period`.
+pub fn wma_simd(data: &[f64], period: usize) -> Vec<f64> {
+    if data.len() < period || period == 0 {
+        return vec![];
+    }
+
+    let weights: Vec<f64> = (1..=period).map(|w| w as f64).collect();
+    let weights_sum = (period * (period + 1)) as f64 / 2.0;
+    data.windows(period)
+        .map(|window| dot_product_simd(window, &weights) / weights_sum)
+        .collect()
+}
+
+/// SIMD-optimized true range.
+///
+/// Returns one value per bar after the first:
+/// `max(high - low, |high - prev_close|, |low - prev_close|)`.
+pub fn true_range_simd(high: &[f64], low: &[f64], close: &[f64]) -> Vec<f64> {
+    let len = high.len().min(low.len()).min(close.len());
+    if len < 2 {
+        return vec![];
+    }
+
+    let mut result = Vec::with_capacity(len - 1);
+    let chunks = (len - 1) / 4;
+
+    for i in 0..chunks {
+        let idx = i * 4 + 1;
+        let h = lanes(high, idx);
+        let l = lanes(low, idx);
+        let prev_close = lanes(close, idx - 1);
+
+        let range = (h - l)
+            .max((h - prev_close).abs())
+            .max((l - prev_close).abs());
+        result.extend(range.to_array());
+    }
+
+    for i in (chunks * 4 + 1)..len {
+        let high_low = high[i] - low[i];
+        let high_close = (high[i] - close[i - 1]).abs();
+        let low_close = (low[i] - close[i - 1]).abs();
+        result.push(high_low.max(high_close).max(low_close));
+    }
+
+    result
+}
+
+/// SIMD-optimized Average True Range with Wilder's smoothing.
+///
+/// The true ranges are vectorized; the smoothing itself is a serial
+/// recurrence.
+pub fn atr_simd(high: &[f64], low: &[f64], close: &[f64], period: usize) -> Vec<f64> {
+    let tr = true_range_simd(high, low, close);
+    if tr.len() < period || period == 0 {
+        return vec![];
+    }
+
+    let period_f64 = period as f64;
+    let mut result = Vec::with_capacity(tr.len() - period + 1);
+
+    let mut atr = sum_simd(&tr[..period]) / period_f64;
+    result.push(atr);
+
+    for &tr_val in &tr[period..] {
+        atr = (atr * (period_f64 - 1.0) + tr_val) / period_f64;
+        result.push(atr);
+    }
+
+    result
+}
+
+/// SIMD-optimized Bollinger Bands.
+pub fn bollinger_simd(
+    data: &[f64],
+    period: usize,
+    std_dev_multiplier: f64,
+) -> Vec<BollingerOutput> {
+    if data.len() < period || period < 2 {
+        return vec![];
+    }
+
+    let kernels = SimdKernels::detect();
+    let period_f64 = period as f64;
+
+    data.windows(period)
+        .enumerate()
+        .map(|(i, window)| {
+            let mean = (kernels.sum)(window) / period_f64;
+            let std_dev = ((kernels.sum_sq_dev)(window, mean) / period_f64).sqrt();
+
+            let upper = mean + std_dev_multiplier * std_dev;
+            let lower = mean - std_dev_multiplier * std_dev;
+            let bandwidth = if mean != 0.0 {
+                (upper - lower) / mean
+            } else {
+                0.0
+            };
+            let price = data[period - 1 + i];
+            let percent_b = if upper != lower {
+                (price - lower) / (upper - lower)
+            } else {
+                0.5
+            };
+
+            BollingerOutput {
+                upper,
+                middle: mean,
+                lower,
+                bandwidth,
+                percent_b,
+            }
+        })
+        .collect()
+}
+
+/// SIMD-optimized MACD.
+///
+/// The EMAs are serial recurrences; the MACD line and histogram are
+/// computed four values at a time.
+pub fn macd_simd(data: &[f64], fast: usize, slow: usize, signal: usize) -> Vec<MacdOutput> {
+    if fast == 0 || fast >= slow || signal == 0 || data.len() < slow + signal {
+        return vec![];
+    }
+
+    let fast_ema = ema_simd(data, fast);
+    let slow_ema = ema_simd(data, slow);
+    let macd_line = sub_simd(&fast_ema[slow - fast..], &slow_ema);
+
+    let signal_line = ema_simd(&macd_line, signal);
+    let macd_line = &macd_line[signal - 1..];
+    let histogram = sub_simd(macd_line, &signal_line);
+
+    macd_line
+        .iter()
+        .zip(&signal_line)
+        .zip(&histogram)
+        .map(|((&macd, &signal), &histogram)| MacdOutput {
+            macd,
+            signal,
+            histogram,
+        })
+        .collect()
+}
+
+/// Element-wise `a - b` over the shorter of the two slices.
+fn sub_simd(a: &[f64], b: &[f64]) -> Vec<f64> {
+    let len = a.len().min(b.len());
+    let chunks = len / 4;
+    let mut result = Vec::with_capacity(len);
+
+    for i in 0..chunks {
+        result.extend((lanes(a, i * 4) - lanes(b, i * 4)).to_array());
+    }
+    for i in (chunks * 4)..len {
+        result.push(a[i] - b[i]);
+    }
+
+    result
+}
+
+/// SIMD-optimized sum of a slice.
+pub fn sum_simd(data: &[f64]) -> f64 {
+    (SimdKernels::detect().sum)(data)
+}
+
+/// SIMD-optimized dot product.
+pub fn dot_product_simd(a: &[f64], b: &[f64]) -> f64 {
+    (SimdKernels::detect().dot)(a, b)
+}
+
+/// SIMD-optimized min/max finder.
+pub fn minmax_simd(data: &[f64]) -> Option<(f64, f64)> {
+    if data.is_empty() {
+        return None;
+    }
+    Some((SimdKernels::detect().minmax)(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sma_simd() {
+        let data = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0];
+        let result = sma_simd(&data, 3);
+
+        assert_eq!(result.len(), 8);
+        assert!((result[0] - 2.0).abs() < 1e-10);
+        assert!((result[7] - 9.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_ema_simd() {
+        let data = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+        let result = ema_simd(&data, 3);
+
+        assert_eq!(result.len(), 3);
+        assert!((result[0] - 2.0).abs() < 1e-10); // Initial SMA
+    }
+
+    #[test]
+    fn test_rsi_simd() {
+        let data: Vec<f64> = (0..20).map(|i| 100.0 + i as f64).collect();
+        let result = rsi_simd(&data, 14);
+
+        assert!(!result.is_empty());
+        // All gains should result in RSI = 100
+        assert!((result[0] - 100.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_rsi_simd_bounds() {
+        let data: Vec<f64> = (0..30)
+            .map(|i| 100.0 + (i as f64 * 0.5).sin() * 5.0)
+            .collect();
+        let result = rsi_simd(&data, 14);
+
+        for rsi in &result {
+            assert!(*rsi >= 0.0 && *rsi <= 100.0);
+        }
+    }
+
+    #[test]
+    fn test_std_dev_simd() {
+        let data = vec![2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+        let result = std_dev_simd(&data, 8);
+
+        assert_eq!(result.len(), 1);
+        // Population std dev of [2,4,4,4,5,5,7,9] ≈ 2.0
+        assert!((result[0] - 2.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_sum_simd() {
+        let data: Vec<f64> = (1..=100).map(|x| x as f64).collect();
+        let result = sum_simd(&data);
+
+        // Sum of 1 to 100 = 5050
+        assert!((result - 5050.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_dot_product_simd() {
+        let a = vec![1.0, 2.0, 3.0, 4.0];
+        let b = vec![5.0, 6.0, 7.0, 8.0];
+        let result = dot_product_simd(&a, &b);
+
+        // 1*5 + 2*6 + 3*7 + 4*8 = 5 + 12 + 21 + 32 = 70
+        assert!((result - 70.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_minmax_simd() {
+        let data = vec![5.0, 2.0, 8.0, 1.0, 9.0, 3.0, 7.0, 4.0];
+        let (min, max) = minmax_simd(&data).unwrap();
+
+        assert!((min - 1.0).abs() < 1e-10);
+        assert!((max - 9.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_kernels_match_scalar() {
+        use crate::{Atr, BollingerBands, Macd, Wma};
+        use trading_core::traits::{Indicator, MultiOutputIndicator};
+
+        // Shorter than SIMD_MIN_LEN, so the indicators take the scalar path
+        let close: Vec<f64> = (0..SIMD_MIN_LEN - 1)
+            .map(|i| 100.0 + (i as f64 * 0.3).sin() * 5.0)
+            .collect();
+        let high: Vec<f64> = close.iter().map(|c| c + 1.5).collect();
+        let low: Vec<f64> = close.iter().map(|c| c - 1.0).collect();
+        let close_to = |a: &[f64], b: &[f64]| {
+            a.len() == b.len() && a.iter().zip(b).all(|(x, y)| (x - y).abs() < 1e-9)
+        };
+
+        assert!(close_to(
+            &wma_simd(&close, 7),
+            &Wma::new(7).calculate(&close)
+        ));
+        assert!(close_to(
+            &atr_simd(&high, &low, &close, 14),
+            &Atr::new(14).calculate_ohlc(&high, &low, &close)
+        ));
+        // With a single price series the true range is the absolute change
+        assert!(close_to(
+            &atr_simd(&close, &close, &close, 14),
+            &Atr::new(14).calculate(&close)
+        ));
+
+        let bands = bollinger_simd(&close, 20, 2.0);
+        let scalar = BollingerBands::with_params(20, 2.0).calculate(&close);
+        assert_eq!(bands.len(), scalar.len());
+        for (a, b) in bands.iter().zip(&scalar) {
+            assert!((a.upper - b.upper).abs() < 1e-9);
+            assert!((a.percent_b - b.percent_b).abs() < 1e-9);
+        }
+
+        let macd = macd_simd(&close, 12, 26, 9);
+        let scalar = Macd::with_periods(12, 26, 9).calculate(&close);
+        assert_eq!(macd.len(), scalar.len());
+        for (a, b) in macd.iter().zip(&scalar) {
+            assert!((a.macd - b.macd).abs() < 1e-9);
+            assert!((a.histogram - b.histogram).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_true_range_simd() {
+        let high = [10.0, 12.0, 11.0, 15.0, 14.0, 13.0];
+        let low = [9.0, 10.0, 8.0, 13.0, 12.0, 12.5];
+        let close = [9.5, 11.0, 10.0, 14.0, 13.0, 12.8];
+        // Gaps: bar 3 opens above the previous close, so |high - prev_close| wins
+        assert_eq!(
+            true_range_simd(&high, &low, &close),
+            vec![2.5, 3.0, 5.0, 2.0, 0.5]
+        );
+    }
+
+    #[test]
+    fn test_empty_data() {
+        assert!(sma_simd(&[], 5).is_empty());
+        assert!(ema_simd(&[], 5).is_empty());
+        assert!(rsi_simd(&[], 14).is_empty());
+        assert!(std_dev_simd(&[], 5).is_empty());
+        assert!(minmax_simd(&[]).is_none());
+        assert!(wma_simd(&[], 5).is_empty());
+        assert!(atr_simd(&[], &[], &[], 14).is_empty());
+        assert!(bollinger_simd(&[], 20, 2.0).is_empty());
+        assert!(macd_simd(&[], 12, 26, 9).is_empty());
+    }
+}
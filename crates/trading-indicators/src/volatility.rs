@@ -1,7 +1,10 @@
 //! Volatility indicators.
 
 use serde::{Deserialize, Serialize};
-use trading_core::traits::{Indicator, MultiOutputIndicator};
+use trading_core::traits::{Indicator, MultiOutputIndicator, StreamingIndicator};
+
+use crate::rolling::{RollingMax, RollingMin, RollingStats};
+use crate::simd;
 
 /// Standard Deviation.
 #[derive(Debug, Clone)]
@@ -25,20 +28,71 @@ impl Indicator for StdDev {
             return vec![];
         }
 
-        let period_f64 = self.period as f64;
-        let mut result = Vec::with_capacity(data.len() - self.period + 1);
+        let mut stats = RollingStats::new(self.period);
+        data.iter()
+            .filter_map(|&value| {
+                stats.push(value);
+                stats.is_full().then(|| stats.std_dev())
+            })
+            .collect()
+    }
+
+    fn period(&self) -> usize {
+        self.period
+    }
+
+    fn name(&self) -> &str {
+        "StdDev"
+    }
+}
+
+/// Streaming population standard deviation over a rolling window.
+///
+/// Matches [`StdDev`]; the window mean and variance are updated in O(1).
+#[derive(Debug, Clone)]
+pub struct StreamingStdDev {
+    stats: RollingStats,
+    current: Option<f64>,
+}
+
+impl StreamingStdDev {
+    /// Create a streaming standard deviation.
+    pub fn new(period: usize) -> Self {
+        assert!(period > 1, "Period must be greater than 1");
+        Self {
+            stats: RollingStats::new(period),
+            current: None,
+        }
+    }
+}
 
-        for window in data.windows(self.period) {
-            let mean: f64 = window.iter().sum::<f64>() / period_f64;
-            let variance: f64 = window.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / period_f64;
-            result.push(variance.sqrt());
+impl StreamingIndicator for StreamingStdDev {
+    type Output = f64;
+
+    fn update(&mut self, value: f64) -> Option<f64> {
+        self.stats.push(value);
+        if !self.stats.is_full() {
+            return None;
         }
+        self.current = Some(self.stats.std_dev());
+        self.current
+    }
 
-        result
+    fn current(&self) -> Option<f64> {
+        self.current
+    }
+
+    fn reset(&mut self) {
+        self.stats.clear();
+        self.current = None;
+    }
+
+    fn is_ready(&self) -> bool {
+        self.current.is_some()
     }
 
     fn period(&self) -> usize {
-        self.period
+        self.stats.period()
     }
 
     fn name(&self) -> &str {
@@ -70,6 +124,9 @@ impl Atr {
         if len < self.period + 1 {
             return vec![];
         }
+        if len >= simd::SIMD_MIN_LEN {
+            return simd::atr_simd(high, low, close, self.period);
+        }
 
         // Calculate True Range
         let mut tr = Vec::with_capacity(len - 1);
@@ -111,6 +168,10 @@ impl Indicator for Atr {
         if data.len() < self.period + 1 {
             return vec![];
         }
+        if data.len() >= simd::SIMD_MIN_LEN {
+            // With high = low = close the true range is the absolute change
+            return simd::atr_simd(data, data, data, self.period);
+        }
 
         // Use close-to-close changes as approximation of true range
         let mut tr = Vec::with_capacity(data.len() - 1);
@@ -142,6 +203,85 @@ impl Indicator for Atr {
     }
 }
 
+/// Streaming ATR using Wilder's smoothing, matching [`Atr`].
+#[derive(Debug, Clone)]
+pub struct StreamingAtr {
+    period: usize,
+    prev_close: Option<f64>,
+    /// True ranges seen while seeding the average
+    count: usize,
+    atr: f64,
+}
+
+impl StreamingAtr {
+    /// Create a new streaming ATR.
+    pub fn new(period: usize) -> Self {
+        assert!(period > 0, "Period must be greater than 0");
+        Self {
+            period,
+            prev_close: None,
+            count: 0,
+            atr: 0.0,
+        }
+    }
+
+    /// Update with a bar's high, low and close, like [`Atr::calculate_ohlc`].
+    pub fn update_ohlc(&mut self, high: f64, low: f64, close: f64) -> Option<f64> {
+        let prev_close = self.prev_close.replace(close)?;
+        let tr = (high - low)
+            .max((high - prev_close).abs())
+            .max((low - prev_close).abs());
+        self.add_true_range(tr)
+    }
+
+    fn add_true_range(&mut self, tr: f64) -> Option<f64> {
+        let period = self.period as f64;
+        if self.count < self.period {
+            // Initial ATR is the mean of the first `period` true ranges
+            self.count += 1;
+            self.atr += tr;
+            if self.count == self.period {
+                self.atr /= period;
+            }
+        } else {
+            self.atr = (self.atr * (period - 1.0) + tr) / period;
+        }
+        self.current()
+    }
+}
+
+impl StreamingIndicator for StreamingAtr {
+    type Output = f64;
+
+    /// Update using close-to-close changes, like [`Atr::calculate`].
+    fn update(&mut self, value: f64) -> Option<f64> {
+        let prev_close = self.prev_close.replace(value)?;
+        self.add_true_range((value - prev_close).abs())
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.is_ready().then_some(self.atr)
+    }
+
+    fn reset(&mut self) {
+        self.prev_close = None;
+        self.count = 0;
+        self.atr = 0.0;
+    }
+
+    fn is_ready(&self) -> bool {
+        self.count >= self.period
+    }
+
+    fn period(&self) -> usize {
+        self.period + 1
+    }
+
+    fn name(&self) -> &str {
+        "ATR"
+    }
+}
+
 /// Bollinger Bands output.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct BollingerOutput {
@@ -212,14 +352,20 @@ impl MultiOutputIndicator for BollingerBands {
         if data.len() < self.period {
             return vec![];
         }
+        if data.len() >= simd::SIMD_MIN_LEN {
+            return simd::bollinger_simd(data, self.period, self.std_dev_multiplier);
+        }
 
-        let period_f64 = self.period as f64;
+        let mut stats = RollingStats::new(self.period);
         let mut result = Vec::with_capacity(data.len() - self.period + 1);
 
-        for (i, window) in data.windows(self.period).enumerate() {
-            let mean: f64 = window.iter().sum::<f64>() / period_f64;
-            let variance: f64 = window.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / period_f64;
-            let std_dev = variance.sqrt();
+        for &price in data {
+            stats.push(price);
+            if !stats.is_full() {
+                continue;
+            }
+            let mean = stats.mean();
+            let std_dev = stats.std_dev();
 
             let upper = mean + self.std_dev_multiplier * std_dev;
             let lower = mean - self.std_dev_multiplier * std_dev;
@@ -230,7 +376,6 @@ impl MultiOutputIndicator for BollingerBands {
                 0.0
             };
 
-            let price = data[self.period - 1 + i];
             let percent_b = if upper != lower {
                 (price - lower) / (upper - lower)
             } else {
@@ -258,6 +403,88 @@ impl MultiOutputIndicator for BollingerBands {
     }
 }
 
+/// Streaming Bollinger Bands, matching [`BollingerBands`].
+///
+/// The window mean and variance are updated in O(1) per value.
+#[derive(Debug, Clone)]
+pub struct StreamingBollinger {
+    std_dev_multiplier: f64,
+    stats: RollingStats,
+    current: Option<BollingerOutput>,
+}
+
+impl StreamingBollinger {
+    /// Create streaming Bollinger Bands.
+    pub fn new(period: usize, std_dev_multiplier: f64) -> Self {
+        assert!(period > 1, "Period must be greater than 1");
+        assert!(
+            std_dev_multiplier > 0.0,
+            "Std dev multiplier must be positive"
+        );
+        Self {
+            std_dev_multiplier,
+            stats: RollingStats::new(period),
+            current: None,
+        }
+    }
+}
+
+impl StreamingIndicator for StreamingBollinger {
+    type Output = BollingerOutput;
+
+    fn update(&mut self, value: f64) -> Option<BollingerOutput> {
+        self.stats.push(value);
+        if !self.stats.is_full() {
+            return None;
+        }
+
+        let mean = self.stats.mean();
+        let std_dev = self.stats.std_dev();
+        let upper = mean + self.std_dev_multiplier * std_dev;
+        let lower = mean - self.std_dev_multiplier * std_dev;
+        let bandwidth = if mean != 0.0 {
+            (upper - lower) / mean
+        } else {
+            0.0
+        };
+        let percent_b = if upper != lower {
+            (value - lower) / (upper - lower)
+        } else {
+            0.5
+        };
+
+        self.current = Some(BollingerOutput {
+            upper,
+            middle: mean,
+            lower,
+            bandwidth,
+            percent_b,
+        });
+        self.current
+    }
+
+    fn current(&self) -> Option<BollingerOutput> {
+        self.current
+    }
+
+    fn reset(&mut self) {
+        self.stats.clear();
+        self.current = None;
+    }
+
+    fn is_ready(&self) -> bool {
+        self.current.is_some()
+    }
+
+    fn period(&self) -> usize {
+        self.stats.period()
+    }
+
+    fn name(&self) -> &str {
+        "Bollinger Bands"
+    }
+}
+
 /// Keltner Channels.
 ///
 /// Similar to Bollinger Bands but uses ATR instead of standard deviation.
@@ -365,6 +592,79 @@ impl Default for KeltnerChannels {
     }
 }
 
+/// Donchian Channels output.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DonchianOutput {
+    /// Highest high over the period
+    pub upper: f64,
+    /// Midpoint of the channel
+    pub middle: f64,
+    /// Lowest low over the period
+    pub lower: f64,
+}
+
+/// Donchian Channels.
+///
+/// The highest high and lowest low over a period, the basis of classic
+/// turtle-style breakout systems.
+#[derive(Debug, Clone)]
+pub struct DonchianChannels {
+    period: usize,
+}
+
+impl DonchianChannels {
+    /// Create Donchian Channels over `period` bars.
+    pub fn new(period: usize) -> Self {
+        assert!(period > 0, "Period must be greater than 0");
+        Self { period }
+    }
+
+    /// Calculate from high and low prices.
+    pub fn calculate_hl(&self, high: &[f64], low: &[f64]) -> Vec<DonchianOutput> {
+        let len = high.len().min(low.len());
+        if len < self.period {
+            return vec![];
+        }
+
+        let mut highest = RollingMax::new(self.period);
+        let mut lowest = RollingMin::new(self.period);
+        (0..len)
+            .filter_map(|i| {
+                let upper = highest.push(high[i]);
+                let lower = lowest.push(low[i]);
+                highest.is_full().then(|| DonchianOutput {
+                    upper,
+                    middle: (upper + lower) / 2.0,
+                    lower,
+                })
+            })
+            .collect()
+    }
+}
+
+impl Default for DonchianChannels {
+    fn default() -> Self {
+        Self::new(20)
+    }
+}
+
+impl MultiOutputIndicator for DonchianChannels {
+    type Outputs = DonchianOutput;
+
+    /// Calculate from a single series, used as both high and low.
+    fn calculate(&self, data: &[f64]) -> Vec<DonchianOutput> {
+        self.calculate_hl(data, data)
+    }
+
+    fn period(&self) -> usize {
+        self.period
+    }
+
+    fn name(&self) -> &str {
+        "Donchian Channels"
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -443,4 +743,62 @@ mod tests {
         assert!(output.is_oversold(85.0));
         assert!(!output.is_oversold(95.0));
     }
+
+    #[test]
+    fn test_streaming_bollinger_matches_batch() {
+        let data: Vec<f64> = (0..40)
+            .map(|i| 100.0 + (i as f64 * 0.7).sin() * 5.0 + i as f64 * 0.1)
+            .collect();
+        let batch = BollingerBands::with_params(10, 2.0).calculate(&data);
+
+        let mut bb = StreamingBollinger::new(10, 2.0);
+        let streamed: Vec<BollingerOutput> = data.iter().filter_map(|&v| bb.update(v)).collect();
+        assert_eq!(streamed.len(), batch.len());
+        for (a, b) in streamed.iter().zip(&batch) {
+            assert!((a.middle - b.middle).abs() < 1e-9);
+            assert!((a.upper - b.upper).abs() < 1e-9);
+            assert!((a.percent_b - b.percent_b).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_streaming_atr_matches_batch() {
+        let high = vec![
+            48.7, 48.72, 48.9, 48.87, 48.82, 49.05, 49.2, 49.35, 49.92, 50.19,
+        ];
+        let low = vec![
+            47.79, 48.14, 48.39, 48.37, 48.24, 48.64, 48.94, 48.86, 49.5, 49.87,
+        ];
+        let close = vec![
+            48.16, 48.61, 48.75, 48.63, 48.74, 49.03, 49.07, 49.32, 49.91, 50.13,
+        ];
+        let batch = Atr::new(3).calculate_ohlc(&high, &low, &close);
+
+        let mut atr = StreamingAtr::new(3);
+        let streamed: Vec<f64> = (0..close.len())
+            .filter_map(|i| atr.update_ohlc(high[i], low[i], close[i]))
+            .collect();
+        assert_eq!(streamed.len(), batch.len());
+        for (a, b) in streamed.iter().zip(&batch) {
+            assert!((a - b).abs() < 1e-10);
+        }
+
+        atr.reset();
+        assert!(atr.current().is_none());
+    }
+
+    #[test]
+    fn test_donchian_channels() {
+        let donchian = DonchianChannels::new(3);
+        let high = vec![10.0, 12.0, 11.0, 9.0, 13.0];
+        let low = vec![8.0, 9.0, 7.0, 6.0, 10.0];
+        let result = donchian.calculate_hl(&high, &low);
+
+        assert_eq!(result.len(), 3);
+        assert_eq!(result[0].upper, 12.0);
+        assert_eq!(result[0].lower, 7.0);
+        assert_eq!(result[0].middle, 9.5);
+        assert_eq!(result[2].upper, 13.0);
+        assert_eq!(result[2].lower, 6.0);
+    }
 }
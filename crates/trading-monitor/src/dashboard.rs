@@ -8,48 +8,190 @@ use crossterm::{
 use ratatui::{
     backend::CrosstermBackend,
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
+    symbols::Marker,
     text::{Line, Span},
-    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
+    widgets::{
+        Axis, Block, Borders, Cell, Chart, Dataset, GraphType, Paragraph, Row, Table, TableState,
+    },
     Frame, Terminal,
 };
 use rust_decimal::Decimal;
 use std::io;
 use std::time::Duration;
-use trading_core::types::Portfolio;
+use tokio::sync::mpsc::UnboundedSender;
+use trading_core::types::{Order, Portfolio, Position};
+
+use crate::equity_history::EquityHistory;
+use crate::log_panel::{LogBuffer, LogView};
+use crate::order_blotter::{move_selection, BlotterView};
+use crate::order_ticket::{DashboardCommand, OrderTicket, TicketAction};
+use crate::price_chart::{ChartView, PriceChart};
+use crate::strategy_panel::{StrategyStatus, StrategyTabs};
+use crate::theme::{default_layout, Panel, Theme};
+
+/// Latest quote for a watchlist symbol.
+#[derive(Debug, Clone, Default)]
+pub struct WatchlistQuote {
+    pub symbol: String,
+    pub last_price: f64,
+    /// Change from the previous session's close
+    pub day_change_pct: Option<f64>,
+    /// Active strategy's indicator readings for the symbol
+    pub indicators: Vec<(String, f64)>,
+}
 
 /// Dashboard state.
+#[derive(Clone)]
 pub struct DashboardState {
     pub portfolio: Portfolio,
+    pub watchlist: Vec<String>,
+    pub quotes: Vec<WatchlistQuote>,
+    /// Recent bars of the traded and watched symbols
+    pub charts: Vec<PriceChart>,
     pub strategy_name: String,
+    /// The running strategy followed by its members, if any
+    pub strategies: Vec<StrategyStatus>,
     pub signals_today: usize,
     pub trades_today: usize,
     pub daily_pnl: Decimal,
-    pub messages: Vec<String>,
+    /// Recent account equity, charted in the equity panel
+    pub equity: EquityHistory,
+    /// One-bar 95% Value-at-Risk and Expected Shortfall of the positions,
+    /// in dollars, once there is enough price history
+    pub value_at_risk: Option<(Decimal, Decimal)>,
+    /// Why the circuit breaker halted trading, once it has
+    pub halted: Option<String>,
+    /// Whether acting on the strategy is paused
+    pub paused: bool,
+    /// Whether strategy orders are only logged
+    pub dry_run: bool,
+    /// Working orders
+    pub open_orders: Vec<Order>,
+    /// Latest orders that filled, newest first
+    pub recent_fills: Vec<Order>,
+    /// Recent log events shown in the log panel
+    pub log: LogBuffer,
+    /// Position and speed of a session replay, when replaying
+    pub replay: Option<String>,
 }
 
 impl Default for DashboardState {
     fn default() -> Self {
         Self {
             portfolio: Portfolio::new(Decimal::ZERO),
+            watchlist: Vec::new(),
+            quotes: Vec::new(),
+            charts: Vec::new(),
             strategy_name: String::new(),
+            strategies: Vec::new(),
             signals_today: 0,
             trades_today: 0,
             daily_pnl: Decimal::ZERO,
-            messages: Vec::new(),
+            equity: EquityHistory::default(),
+            value_at_risk: None,
+            halted: None,
+            paused: false,
+            dry_run: false,
+            open_orders: Vec::new(),
+            recent_fills: Vec::new(),
+            log: LogBuffer::default(),
+            replay: None,
+        }
+    }
+}
+
+impl DashboardState {
+    /// Open positions by symbol, in the order the positions panel lists them.
+    pub fn positions(&self) -> Vec<&Position> {
+        let mut positions: Vec<&Position> = self.portfolio.positions.values().collect();
+        positions.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+        positions
+    }
+
+    /// Symbols offered in the order ticket: open positions, then the watchlist.
+    pub fn tradeable_symbols(&self) -> Vec<String> {
+        let mut symbols: Vec<String> = self.portfolio.positions.keys().cloned().collect();
+        symbols.sort();
+        for symbol in &self.watchlist {
+            if !symbols.contains(symbol) {
+                symbols.push(symbol.clone());
+            }
         }
+        symbols
     }
 }
 
+/// Panel that receives navigation keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    Log,
+    Orders,
+    Positions,
+}
+
+/// Interactive state of the panels between frames.
+struct Views {
+    log: LogView,
+    blotter: BlotterView,
+    strategy_tabs: StrategyTabs,
+    chart: ChartView,
+    /// Selected row of the positions table
+    position: usize,
+    focus: Focus,
+}
+
 /// TUI Dashboard.
 pub struct Dashboard {
     refresh_ms: u64,
+    commands: Option<UnboundedSender<DashboardCommand>>,
+    /// Commands control a replay rather than trading
+    replay: bool,
+    theme: Theme,
+    layout: Vec<Vec<Panel>>,
 }
 
 impl Dashboard {
     /// Create a new dashboard.
     pub fn new(refresh_ms: u64) -> Self {
-        Self { refresh_ms }
+        Self {
+            refresh_ms,
+            commands: None,
+            replay: false,
+            theme: Theme::default(),
+            layout: default_layout(),
+        }
+    }
+
+    /// Set the color theme.
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Set which panels are shown and in what order (see [`default_layout`]).
+    pub fn with_layout(mut self, layout: Vec<Vec<Panel>>) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Enable the order ticket, sending orders to the trading loop.
+    pub fn with_commands(mut self, commands: UnboundedSender<DashboardCommand>) -> Self {
+        self.commands = Some(commands);
+        self
+    }
+
+    /// Replay a recorded session: 'p' pauses, 'n' steps a bar and '+'/'-'
+    /// change the speed, sent to the replay loop. Trading keys are disabled.
+    pub fn with_replay_controls(mut self, commands: UnboundedSender<DashboardCommand>) -> Self {
+        self.commands = Some(commands);
+        self.replay = true;
+        self
+    }
+
+    /// Whether the trading keys (order ticket, pause, kill switch, ...) are on.
+    fn trading(&self) -> bool {
+        self.commands.is_some() && !self.replay
     }
 
     /// Run the dashboard.
@@ -86,58 +228,257 @@ impl Dashboard {
     where
         F: FnMut() -> DashboardState,
     {
+        let mut ticket: Option<OrderTicket> = None;
+        let mut views = Views {
+            log: LogView::default(),
+            blotter: BlotterView::default(),
+            strategy_tabs: StrategyTabs::default(),
+            chart: ChartView::default(),
+            position: 0,
+            focus: Focus::Log,
+        };
+
         loop {
             let state = get_state();
-            terminal.draw(|f| self.ui(f, &state))?;
+            terminal.draw(|f| {
+                self.ui(f, &state, &views);
+                if let Some(ticket) = &ticket {
+                    ticket.render(f, f.area(), &self.theme);
+                }
+            })?;
 
             if event::poll(Duration::from_millis(self.refresh_ms))? {
                 if let Event::Key(key) = event::read()? {
-                    if key.code == KeyCode::Char('q') || key.code == KeyCode::Esc {
-                        return Ok(());
+                    // The ticket captures all keys while open
+                    if let Some(open) = &mut ticket {
+                        match open.handle_key(key.code) {
+                            TicketAction::None => {}
+                            TicketAction::Cancel => ticket = None,
+                            TicketAction::Submit(request) => {
+                                if !self.send(DashboardCommand::SubmitOrder(request)) {
+                                    return Ok(());
+                                }
+                                ticket = None;
+                            }
+                        }
+                        continue;
+                    }
+                    // So does the log search prompt
+                    if views.log.is_editing() {
+                        views.log.handle_key(key.code, &state.log);
+                        continue;
+                    }
+
+                    match key.code {
+                        KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                        KeyCode::Char('o') if self.trading() => {
+                            ticket = Some(OrderTicket::new(state.tradeable_symbols()));
+                        }
+                        KeyCode::Char(c @ ('p' | 'n' | '+' | '-')) if self.replay => {
+                            let command = match c {
+                                'p' => DashboardCommand::TogglePause,
+                                'n' => DashboardCommand::Step,
+                                '+' => DashboardCommand::Faster,
+                                _ => DashboardCommand::Slower,
+                            };
+                            if !self.send(command) {
+                                return Ok(());
+                            }
+                        }
+                        KeyCode::Char(c @ ('K' | 'E' | 'p' | 'd' | 'C' | 'X'))
+                            if self.trading() =>
+                        {
+                            let command = match c {
+                                'K' => DashboardCommand::Halt,
+                                'E' => DashboardCommand::EnableStrategy,
+                                'p' => DashboardCommand::TogglePause,
+                                'd' => DashboardCommand::ToggleDryRun,
+                                'C' => DashboardCommand::CancelAllOrders,
+                                // Only the highlighted position is closed
+                                _ => match state.positions().get(views.position) {
+                                    Some(p) if views.focus == Focus::Positions => {
+                                        DashboardCommand::ClosePosition(p.symbol.clone())
+                                    }
+                                    _ => continue,
+                                },
+                            };
+                            if !self.send(command) {
+                                return Ok(());
+                            }
+                        }
+                        KeyCode::Tab => views.focus = self.next_focus(views.focus),
+                        code @ KeyCode::Char('[' | ']' | 'v') => {
+                            views.chart.handle_key(code, state.charts.len());
+                        }
+                        code @ (KeyCode::Left | KeyCode::Right) => {
+                            views.strategy_tabs.handle_key(code, state.strategies.len());
+                        }
+                        code => match views.focus {
+                            Focus::Orders => {
+                                let rows = state.open_orders.len() + state.recent_fills.len();
+                                views.blotter.handle_key(code, rows);
+                            }
+                            Focus::Positions => {
+                                let rows = state.portfolio.positions.len();
+                                if let Some(row) = move_selection(views.position, code, rows) {
+                                    views.position = row;
+                                }
+                            }
+                            Focus::Log => {
+                                views.log.handle_key(code, &state.log);
+                            }
+                        },
                     }
                 }
             }
         }
     }
 
-    fn ui(&self, frame: &mut Frame, state: &DashboardState) {
+    /// Send a command to the trading loop; false once the loop has stopped.
+    fn send(&self, command: DashboardCommand) -> bool {
+        self.commands
+            .as_ref()
+            .map_or(true, |commands| commands.send(command).is_ok())
+    }
+
+    /// Panel that takes navigation keys after `focus` on Tab.
+    fn next_focus(&self, focus: Focus) -> Focus {
+        let order = [Focus::Log, Focus::Orders, Focus::Positions];
+        let start = order.iter().position(|f| *f == focus).unwrap_or(0);
+        (1..=order.len())
+            .map(|i| order[(start + i) % order.len()])
+            .find(|f| match f {
+                Focus::Log => true,
+                Focus::Orders => self.shows(Panel::Orders),
+                Focus::Positions => self.shows(Panel::Positions),
+            })
+            .unwrap_or(Focus::Log)
+    }
+
+    /// Whether the layout includes a panel.
+    fn shows(&self, panel: Panel) -> bool {
+        self.layout.iter().flatten().any(|p| *p == panel)
+    }
+
+    fn ui(&self, frame: &mut Frame, state: &DashboardState, views: &Views) {
+        let rows: Vec<&Vec<Panel>> = self.layout.iter().filter(|r| !r.is_empty()).collect();
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .margin(1)
-            .constraints([
-                Constraint::Length(3), // Header
-                Constraint::Length(5), // Stats
-                Constraint::Min(10),   // Positions
-                Constraint::Length(8), // Messages
-            ])
+            .constraints(rows.iter().map(|row| row_height(row)))
             .split(frame.area());
 
-        self.render_header(frame, chunks[0], state);
-        self.render_stats(frame, chunks[1], state);
-        self.render_positions(frame, chunks[2], state);
-        self.render_messages(frame, chunks[3], state);
+        for (row, area) in rows.iter().zip(chunks.iter()) {
+            let columns = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints(row.iter().map(|p| column_width(*p)))
+                .split(*area);
+            for (panel, area) in row.iter().zip(columns.iter()) {
+                match panel {
+                    Panel::Header => self.render_header(frame, *area, state),
+                    Panel::Stats => self.render_stats(frame, *area, state),
+                    Panel::Equity => self.render_equity(frame, *area, state),
+                    Panel::Positions => self.render_positions(frame, *area, state, views),
+                    Panel::Watchlist => self.render_watchlist(frame, *area, state),
+                    Panel::Chart => views.chart.render(frame, *area, &state.charts, &self.theme),
+                    Panel::Strategy => {
+                        views
+                            .strategy_tabs
+                            .render(frame, *area, &state.strategies, &self.theme)
+                    }
+                    Panel::Orders => views.blotter.render(
+                        frame,
+                        *area,
+                        &state.open_orders,
+                        &state.recent_fills,
+                        &self.theme,
+                        views.focus == Focus::Orders,
+                    ),
+                    Panel::Log => views.log.render(frame, *area, &state.log, &self.theme),
+                }
+            }
+        }
+    }
+
+    fn block<'a>(&self, title: &'a str) -> Block<'a> {
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.theme.border))
+            .title(title)
     }
 
     fn render_header(&self, frame: &mut Frame, area: Rect, state: &DashboardState) {
-        let header = Paragraph::new(vec![Line::from(vec![
+        let mut spans = vec![
             Span::styled(
                 "Trading Dashboard",
                 Style::default().add_modifier(Modifier::BOLD),
             ),
             Span::raw(" | "),
-            Span::styled(&state.strategy_name, Style::default().fg(Color::Cyan)),
-            Span::raw(" | Press 'q' to quit"),
-        ])])
-        .block(Block::default().borders(Borders::ALL).title("System"));
+            Span::styled(&state.strategy_name, Style::default().fg(self.theme.accent)),
+        ];
+        if let Some(reason) = &state.halted {
+            spans.push(Span::raw(" | "));
+            spans.push(Span::styled(
+                format!("HALTED: {}", reason),
+                Style::default()
+                    .fg(self.theme.signed(false))
+                    .add_modifier(Modifier::BOLD),
+            ));
+        }
+        if let Some(replay) = &state.replay {
+            spans.push(Span::raw(" | "));
+            spans.push(Span::styled(
+                format!("REPLAY {}", replay),
+                Style::default()
+                    .fg(self.theme.accent)
+                    .add_modifier(Modifier::BOLD),
+            ));
+        }
+        for (on, label) in [(state.paused, "PAUSED"), (state.dry_run, "DRY RUN")] {
+            if on {
+                spans.push(Span::raw(" | "));
+                spans.push(Span::styled(
+                    label,
+                    Style::default()
+                        .fg(self.theme.accent)
+                        .add_modifier(Modifier::BOLD),
+                ));
+            }
+        }
+
+        let mut keys = Vec::new();
+        if self.replay {
+            keys.extend(["'p' pause", "'n' next bar", "'+' '-' speed"]);
+        }
+        if self.trading() {
+            keys.extend([
+                "'o' order ticket",
+                "'p' pause",
+                "'d' dry run",
+                "'X' close position",
+                "'C' cancel orders",
+                "'K' kill switch",
+                "'E' re-enable strategy",
+            ]);
+        }
+        keys.extend(["'/' search log", "Tab focus"]);
+        if self.shows(Panel::Chart) {
+            keys.push("'[' ']' chart symbol | 'v' candles/line");
+        }
+        if self.shows(Panel::Strategy) && state.strategies.len() > 1 {
+            keys.push("Left/Right members");
+        }
+        keys.push("'q' quit");
+        let hints = Line::from(Span::styled(
+            keys.join(" | "),
+            Style::default().fg(self.theme.border),
+        ));
+        let header = Paragraph::new(vec![Line::from(spans), hints]).block(self.block("System"));
         frame.render_widget(header, area);
     }
 
     fn render_stats(&self, frame: &mut Frame, area: Rect, state: &DashboardState) {
-        let pnl_color = if state.daily_pnl >= Decimal::ZERO {
-            Color::Green
-        } else {
-            Color::Red
-        };
+        let pnl_color = self.theme.signed(state.daily_pnl >= Decimal::ZERO);
 
         let stats = Paragraph::new(vec![
             Line::from(vec![
@@ -145,7 +486,7 @@ impl Dashboard {
                 Span::styled(
                     format!("${:.2}", state.portfolio.equity),
                     Style::default()
-                        .fg(Color::White)
+                        .fg(self.theme.text)
                         .add_modifier(Modifier::BOLD),
                 ),
                 Span::raw("  |  Cash: "),
@@ -167,23 +508,122 @@ impl Dashboard {
                 Span::raw("  |  Trades: "),
                 Span::styled(format!("{}", state.trades_today), Style::default()),
             ]),
+            Line::from(vec![
+                Span::raw("Gross Exposure: "),
+                Span::styled(
+                    format!("${:.2}", state.portfolio.gross_exposure()),
+                    Style::default(),
+                ),
+                Span::raw("  |  Net: "),
+                Span::styled(
+                    format!("${:.2}", state.portfolio.net_exposure()),
+                    Style::default(),
+                ),
+            ]),
+            Line::from(match state.value_at_risk {
+                Some((var, es)) => vec![
+                    Span::raw("VaR (95%): "),
+                    Span::styled(format!("${:.2}", var), Style::default()),
+                    Span::raw("  |  Expected Shortfall: "),
+                    Span::styled(format!("${:.2}", es), Style::default()),
+                ],
+                None => vec![Span::raw("VaR (95%): n/a")],
+            }),
         ])
-        .block(Block::default().borders(Borders::ALL).title("Statistics"));
+        .block(self.block("Statistics"));
         frame.render_widget(stats, area);
     }
 
-    fn render_positions(&self, frame: &mut Frame, area: Rect, state: &DashboardState) {
+    fn render_equity(&self, frame: &mut Frame, area: Rect, state: &DashboardState) {
+        let history = &state.equity;
+        let drawdowns = history.drawdowns();
+        let title = match (history.last(), drawdowns.last()) {
+            (Some(equity), Some(drawdown)) => format!(
+                "Equity ${:.2} | Drawdown {:.2}% (max {:.2}%)",
+                equity,
+                drawdown,
+                history.max_drawdown()
+            ),
+            _ => "Equity".to_string(),
+        };
+        let block = self.block(&title);
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+        if history.len() < 2 {
+            frame.render_widget(Paragraph::new("Waiting for equity updates..."), inner);
+            return;
+        }
+
+        // Equity curve above, drawdown below, on the same time axis
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(70), Constraint::Percentage(30)])
+            .split(inner);
+        let x_axis = Axis::default().bounds([0.0, (history.len() - 1) as f64]);
+
+        let equity: Vec<(f64, f64)> = history
+            .equity()
+            .enumerate()
+            .map(|(i, e)| (i as f64, e))
+            .collect();
+        let (low, high) = equity
+            .iter()
+            .fold((f64::MAX, f64::MIN), |(low, high), (_, e)| {
+                (low.min(*e), high.max(*e))
+            });
+        let pad = ((high - low) * 0.05).max(0.01);
+        let rising = equity.last().map(|(_, e)| *e) >= equity.first().map(|(_, e)| *e);
+        let chart = Chart::new(vec![Dataset::default()
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(self.theme.signed(rising)))
+            .data(&equity)])
+        .x_axis(x_axis.clone())
+        .y_axis(
+            Axis::default()
+                .style(Style::default().fg(self.theme.border))
+                .bounds([low - pad, high + pad])
+                .labels([format!("{:>9.0}", low), format!("{:>9.0}", high)]),
+        );
+        frame.render_widget(chart, chunks[0]);
+
+        let underwater: Vec<(f64, f64)> = drawdowns
+            .iter()
+            .enumerate()
+            .map(|(i, d)| (i as f64, *d))
+            .collect();
+        let deepest = history.max_drawdown().min(-0.01);
+        let chart = Chart::new(vec![Dataset::default()
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(self.theme.negative))
+            .data(&underwater)])
+        .x_axis(x_axis)
+        .y_axis(
+            Axis::default()
+                .style(Style::default().fg(self.theme.border))
+                .bounds([deepest, 0.0])
+                .labels([format!("{:>8.1}%", deepest), format!("{:>8.1}%", 0.0)]),
+        );
+        frame.render_widget(chart, chunks[1]);
+    }
+
+    fn render_positions(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        state: &DashboardState,
+        views: &Views,
+    ) {
         let header_cells = ["Symbol", "Qty", "Entry", "Current", "P&L", "P&L %"]
             .iter()
             .map(|h| Cell::from(*h).style(Style::default().add_modifier(Modifier::BOLD)));
         let header = Row::new(header_cells).height(1);
 
-        let rows = state.portfolio.positions.values().map(|pos| {
-            let pnl_color = if pos.unrealized_pnl >= Decimal::ZERO {
-                Color::Green
-            } else {
-                Color::Red
-            };
+        let positions = state.positions();
+        let focused = views.focus == Focus::Positions;
+        let rows = positions.iter().map(|pos| {
+            let pnl_color = self.theme.signed(pos.unrealized_pnl >= Decimal::ZERO);
 
             Row::new(vec![
                 Cell::from(pos.symbol.clone()),
@@ -209,22 +649,88 @@ impl Dashboard {
             ],
         )
         .header(header)
-        .block(Block::default().borders(Borders::ALL).title("Positions"));
+        .row_highlight_style(if focused {
+            Style::default().add_modifier(Modifier::REVERSED)
+        } else {
+            Style::default()
+        })
+        .block(self.block(if focused { "Positions *" } else { "Positions" }));
 
-        frame.render_widget(table, area);
+        let mut selection = TableState::default().with_selected(
+            (!positions.is_empty()).then(|| views.position.min(positions.len() - 1)),
+        );
+        frame.render_stateful_widget(table, area, &mut selection);
     }
 
-    fn render_messages(&self, frame: &mut Frame, area: Rect, state: &DashboardState) {
-        let messages: Vec<Line> = state
-            .messages
+    fn render_watchlist(&self, frame: &mut Frame, area: Rect, state: &DashboardState) {
+        let header_cells = ["Symbol", "Last", "Chg %", "Indicators"]
             .iter()
-            .rev()
-            .take(5)
-            .map(|m| Line::from(m.as_str()))
-            .collect();
+            .map(|h| Cell::from(*h).style(Style::default().add_modifier(Modifier::BOLD)));
+        let header = Row::new(header_cells).height(1);
+
+        let rows = state.quotes.iter().map(|quote| {
+            let change = match quote.day_change_pct {
+                Some(pct) => Cell::from(format!("{:+.2}%", pct))
+                    .style(Style::default().fg(self.theme.signed(pct >= 0.0))),
+                None => Cell::from("-"),
+            };
+            let indicators = quote
+                .indicators
+                .iter()
+                .map(|(name, value)| format!("{} {:.2}", name, value))
+                .collect::<Vec<_>>()
+                .join("  ");
+
+            Row::new(vec![
+                Cell::from(quote.symbol.clone()),
+                Cell::from(format!("${:.2}", quote.last_price)),
+                change,
+                Cell::from(indicators),
+            ])
+        });
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(8),
+                Constraint::Length(11),
+                Constraint::Length(8),
+                Constraint::Min(10),
+            ],
+        )
+        .header(header)
+        .block(self.block("Watchlist"));
+
+        frame.render_widget(table, area);
+    }
+}
+
+/// Height of a layout row: fixed for summary panels, flexible for tables.
+fn row_height(row: &[Panel]) -> Constraint {
+    if row
+        .iter()
+        .any(|p| matches!(p, Panel::Positions | Panel::Watchlist | Panel::Orders))
+    {
+        return Constraint::Min(6);
+    }
+    let height = row
+        .iter()
+        .map(|p| match p {
+            Panel::Header => 4,
+            Panel::Stats => 6,
+            Panel::Equity | Panel::Strategy => 12,
+            Panel::Chart => 14,
+            _ => 10,
+        })
+        .max()
+        .unwrap_or(3);
+    Constraint::Length(height)
+}
 
-        let paragraph =
-            Paragraph::new(messages).block(Block::default().borders(Borders::ALL).title("Log"));
-        frame.render_widget(paragraph, area);
+/// Share of a row taken by a panel; positions get the most room.
+fn column_width(panel: Panel) -> Constraint {
+    match panel {
+        Panel::Positions => Constraint::Fill(3),
+        _ => Constraint::Fill(2),
     }
 }
@@ -0,0 +1,106 @@
+//! Recent equity history for the dashboard's equity chart.
+
+use std::collections::VecDeque;
+
+/// Bounded history of account equity samples; the oldest are dropped once
+/// it is full.
+#[derive(Debug, Clone)]
+pub struct EquityHistory {
+    /// (timestamp ms, equity), oldest first
+    samples: VecDeque<(i64, f64)>,
+    capacity: usize,
+}
+
+impl Default for EquityHistory {
+    fn default() -> Self {
+        Self::new(500)
+    }
+}
+
+impl EquityHistory {
+    /// Create a history holding up to `capacity` samples.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Record the equity at `timestamp` (ms).
+    pub fn push(&mut self, timestamp: i64, equity: f64) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((timestamp, equity));
+    }
+
+    /// (timestamp ms, equity) samples, oldest first.
+    pub fn samples(&self) -> impl Iterator<Item = (i64, f64)> + '_ {
+        self.samples.iter().copied()
+    }
+
+    /// Equity samples, oldest first.
+    pub fn equity(&self) -> impl Iterator<Item = f64> + '_ {
+        self.samples.iter().map(|(_, equity)| *equity)
+    }
+
+    /// Drawdown of each sample from the highest equity before it, as a
+    /// (non-positive) percentage.
+    pub fn drawdowns(&self) -> Vec<f64> {
+        let mut peak = f64::MIN;
+        self.equity()
+            .map(|equity| {
+                peak = peak.max(equity);
+                if peak > 0.0 {
+                    (equity / peak - 1.0) * 100.0
+                } else {
+                    0.0
+                }
+            })
+            .collect()
+    }
+
+    /// Deepest drawdown in the history, as a (non-positive) percentage.
+    pub fn max_drawdown(&self) -> f64 {
+        self.drawdowns().into_iter().fold(0.0, f64::min)
+    }
+
+    /// Latest equity.
+    pub fn last(&self) -> Option<f64> {
+        self.samples.back().map(|(_, equity)| *equity)
+    }
+
+    /// Timestamp (ms) of the oldest sample.
+    pub fn since(&self) -> Option<i64> {
+        self.samples.front().map(|(timestamp, _)| *timestamp)
+    }
+
+    /// Number of samples.
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Whether the history is empty.
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_drops_oldest_and_tracks_drawdown() {
+        let mut history = EquityHistory::new(4);
+        for (i, equity) in [90.0, 100.0, 95.0, 80.0, 104.0].into_iter().enumerate() {
+            history.push(i as i64, equity);
+        }
+        assert_eq!(history.len(), 4);
+        assert_eq!(history.since(), Some(1));
+        assert_eq!(history.last(), Some(104.0));
+        let drawdowns: Vec<f64> = history.drawdowns().iter().map(|d| d.round()).collect();
+        assert_eq!(drawdowns, vec![0.0, -5.0, -20.0, 0.0]);
+        assert!((history.max_drawdown() + 20.0).abs() < 1e-9);
+    }
+}
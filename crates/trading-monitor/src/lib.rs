@@ -1,7 +1,27 @@
 //! Real-time monitoring and TUI dashboard.
 
 mod dashboard;
+mod equity_history;
+mod log_panel;
 mod logging;
+mod notify;
+mod order_blotter;
+mod order_ticket;
+mod price_chart;
+mod strategy_panel;
+mod theme;
 
-pub use dashboard::Dashboard;
-pub use logging::setup_logging;
+pub use dashboard::{Dashboard, DashboardState, WatchlistQuote};
+pub use equity_history::EquityHistory;
+pub use log_panel::{LogBuffer, LogEvent, LogFilter, LogLevel, LogView};
+pub use logging::{setup_dashboard_logging, setup_logging, LogBufferLayer};
+pub use notify::{
+    EmailConfig, EmailNotifier, Notification, NotificationConfig, NotificationKind, Notifications,
+    Notifier, SlackConfig, SlackNotifier, TelegramConfig, TelegramNotifier, WebhookConfig,
+    WebhookNotifier,
+};
+pub use order_blotter::BlotterView;
+pub use order_ticket::{DashboardCommand, OrderTicket, TicketAction};
+pub use price_chart::{ChartStyle, ChartView, PriceChart};
+pub use strategy_panel::{StrategyStatus, StrategyTabs};
+pub use theme::{default_layout, Panel, Theme, ThemeConfig, ThemePreset};
@@ -0,0 +1,352 @@
+//! Dashboard log: a ring buffer of structured events and a filterable,
+//! scrollable view over it.
+
+use chrono::{DateTime, Utc};
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use crate::theme::Theme;
+
+/// Severity of a log event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogLevel {
+    Debug,
+    #[default]
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    /// Next level when cycling the filter.
+    fn next(self) -> Self {
+        match self {
+            LogLevel::Debug => LogLevel::Info,
+            LogLevel::Info => LogLevel::Warn,
+            LogLevel::Warn => LogLevel::Error,
+            LogLevel::Error => LogLevel::Debug,
+        }
+    }
+}
+
+impl From<tracing::Level> for LogLevel {
+    fn from(level: tracing::Level) -> Self {
+        match level {
+            tracing::Level::ERROR => LogLevel::Error,
+            tracing::Level::WARN => LogLevel::Warn,
+            tracing::Level::INFO => LogLevel::Info,
+            _ => LogLevel::Debug,
+        }
+    }
+}
+
+impl std::fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Info => "INFO",
+            LogLevel::Warn => "WARN",
+            LogLevel::Error => "ERROR",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// A single log entry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogEvent {
+    pub timestamp: DateTime<Utc>,
+    pub level: LogLevel,
+    /// Symbol the event concerns, if any
+    pub symbol: Option<String>,
+    pub message: String,
+}
+
+impl LogEvent {
+    /// Create an event stamped now.
+    pub fn new(level: LogLevel, message: impl Into<String>) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            level,
+            symbol: None,
+            message: message.into(),
+        }
+    }
+
+    /// Tag the event with a symbol.
+    pub fn with_symbol(mut self, symbol: impl Into<String>) -> Self {
+        self.symbol = Some(symbol.into());
+        self
+    }
+}
+
+/// Shared, bounded buffer of recent log events; the oldest are dropped
+/// once it is full. Clones share the same buffer.
+#[derive(Debug, Clone)]
+pub struct LogBuffer {
+    events: Arc<Mutex<VecDeque<LogEvent>>>,
+    capacity: usize,
+}
+
+impl Default for LogBuffer {
+    fn default() -> Self {
+        Self::new(1000)
+    }
+}
+
+impl LogBuffer {
+    /// Create a buffer holding up to `capacity` events.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            events: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Append an event.
+    pub fn push(&self, event: LogEvent) {
+        let mut events = self.events.lock().unwrap();
+        if events.len() == self.capacity {
+            events.pop_front();
+        }
+        events.push_back(event);
+    }
+
+    /// Copy of the buffered events, oldest first.
+    pub fn events(&self) -> Vec<LogEvent> {
+        self.events.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Number of buffered events.
+    pub fn len(&self) -> usize {
+        self.events.lock().unwrap().len()
+    }
+
+    /// Whether the buffer is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Which events the log panel shows.
+#[derive(Debug, Clone, Default)]
+pub struct LogFilter {
+    /// Lowest level shown
+    pub min_level: LogLevel,
+    /// Only events tagged with this symbol
+    pub symbol: Option<String>,
+    /// Case-insensitive text the message must contain
+    pub search: String,
+}
+
+impl LogFilter {
+    /// Whether an event passes the filter.
+    pub fn matches(&self, event: &LogEvent) -> bool {
+        event.level >= self.min_level
+            && self
+                .symbol
+                .as_ref()
+                .map_or(true, |s| event.symbol.as_ref() == Some(s))
+            && (self.search.is_empty()
+                || event
+                    .message
+                    .to_lowercase()
+                    .contains(&self.search.to_lowercase()))
+    }
+}
+
+/// Filter, scroll position and search input of the log panel.
+///
+/// Keys: `/` search, `l` cycle minimum level, `s` cycle symbol, `c` clear
+/// filters, arrows/PageUp/PageDown scroll, Home/End jump to oldest/newest.
+#[derive(Debug, Clone, Default)]
+pub struct LogView {
+    filter: LogFilter,
+    /// Lines scrolled back from the newest event (0 follows new events)
+    scroll: usize,
+    /// Search text being typed, if the search prompt is open
+    input: Option<String>,
+}
+
+impl LogView {
+    /// Current filter.
+    pub fn filter(&self) -> &LogFilter {
+        &self.filter
+    }
+
+    /// Whether the search prompt is capturing keys.
+    pub fn is_editing(&self) -> bool {
+        self.input.is_some()
+    }
+
+    /// Handle a key; returns false if the view did not use it.
+    pub fn handle_key(&mut self, key: KeyCode, log: &LogBuffer) -> bool {
+        if let Some(input) = &mut self.input {
+            match key {
+                KeyCode::Char(c) => input.push(c),
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Enter => {
+                    self.filter.search = self.input.take().unwrap_or_default();
+                    self.scroll = 0;
+                }
+                KeyCode::Esc => self.input = None,
+                _ => {}
+            }
+            return true;
+        }
+
+        let events = log.events();
+        let visible = events.iter().filter(|e| self.filter.matches(e)).count();
+        match key {
+            KeyCode::Char('/') => self.input = Some(self.filter.search.clone()),
+            KeyCode::Char('l') => {
+                self.filter.min_level = self.filter.min_level.next();
+                self.scroll = 0;
+            }
+            KeyCode::Char('s') => {
+                let mut symbols: Vec<&String> =
+                    events.iter().filter_map(|e| e.symbol.as_ref()).collect();
+                symbols.sort();
+                symbols.dedup();
+                // None -> first symbol -> ... -> last symbol -> None
+                self.filter.symbol = match &self.filter.symbol {
+                    None => symbols.first().map(|s| s.to_string()),
+                    Some(current) => symbols
+                        .iter()
+                        .position(|s| *s == current)
+                        .and_then(|i| symbols.get(i + 1))
+                        .map(|s| s.to_string()),
+                };
+                self.scroll = 0;
+            }
+            KeyCode::Char('c') => {
+                self.filter = LogFilter::default();
+                self.scroll = 0;
+            }
+            KeyCode::Up => self.scroll = (self.scroll + 1).min(visible.saturating_sub(1)),
+            KeyCode::Down => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::PageUp => self.scroll = (self.scroll + 10).min(visible.saturating_sub(1)),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_sub(10),
+            KeyCode::Home => self.scroll = visible.saturating_sub(1),
+            KeyCode::End => self.scroll = 0,
+            _ => return false,
+        }
+        true
+    }
+
+    /// Title describing the active filters.
+    fn title(&self) -> String {
+        let mut title = String::from("Log");
+        if self.filter.min_level > LogLevel::Debug {
+            title.push_str(&format!(" [{}+]", self.filter.min_level));
+        }
+        if let Some(symbol) = &self.filter.symbol {
+            title.push_str(&format!(" [{}]", symbol));
+        }
+        if !self.filter.search.is_empty() {
+            title.push_str(&format!(" [/{}]", self.filter.search));
+        }
+        if self.scroll > 0 {
+            title.push_str(&format!(" [-{}]", self.scroll));
+        }
+        title
+    }
+
+    /// Render the panel.
+    pub fn render(&self, frame: &mut Frame, area: Rect, log: &LogBuffer, theme: &Theme) {
+        let events: Vec<LogEvent> = log
+            .events()
+            .into_iter()
+            .filter(|e| self.filter.matches(e))
+            .collect();
+
+        // One row is kept for the search prompt while it is open
+        let height = (area.height.saturating_sub(2) as usize)
+            .saturating_sub(usize::from(self.input.is_some()))
+            .max(1);
+        let end = events.len().saturating_sub(self.scroll);
+        let start = end.saturating_sub(height);
+
+        let mut lines: Vec<Line> = events[start..end]
+            .iter()
+            .map(|e| {
+                let color = match e.level {
+                    LogLevel::Debug => theme.border,
+                    LogLevel::Info => theme.text,
+                    LogLevel::Warn => theme.accent,
+                    LogLevel::Error => theme.negative,
+                };
+                Line::from(vec![
+                    Span::raw(format!("[{}] ", e.timestamp.format("%H:%M:%S"))),
+                    Span::styled(format!("{:<5} ", e.level), Style::default().fg(color)),
+                    Span::raw(&e.message),
+                ])
+            })
+            .collect();
+        if let Some(input) = &self.input {
+            lines.push(Line::from(Span::styled(
+                format!("/{}_", input),
+                Style::default().fg(theme.accent),
+            )));
+        }
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.border))
+            .title(self.title());
+        let paragraph = Paragraph::new(lines).block(block);
+        frame.render_widget(paragraph, area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buffer_drops_oldest_and_filters() {
+        let log = LogBuffer::new(3);
+        log.push(LogEvent::new(LogLevel::Info, "dropped"));
+        log.push(LogEvent::new(LogLevel::Info, "AAPL filled").with_symbol("AAPL"));
+        log.push(LogEvent::new(LogLevel::Warn, "MSFT rejected").with_symbol("MSFT"));
+        log.push(LogEvent::new(LogLevel::Error, "Broker timeout"));
+        assert_eq!(log.len(), 3);
+        assert_eq!(log.events()[0].message, "AAPL filled");
+
+        let mut view = LogView::default();
+        let shown = |view: &LogView| {
+            log.events()
+                .into_iter()
+                .filter(|e| view.filter().matches(e))
+                .map(|e| e.message)
+                .collect::<Vec<_>>()
+        };
+
+        view.handle_key(KeyCode::Char('l'), &log); // info -> warn and above
+        assert_eq!(shown(&view), vec!["MSFT rejected", "Broker timeout"]);
+
+        view.handle_key(KeyCode::Char('c'), &log);
+        view.handle_key(KeyCode::Char('s'), &log); // first symbol: AAPL
+        assert_eq!(shown(&view), vec!["AAPL filled"]);
+
+        view.handle_key(KeyCode::Char('c'), &log);
+        for key in [KeyCode::Char('/'), KeyCode::Char('T'), KeyCode::Char('i')] {
+            view.handle_key(key, &log);
+        }
+        assert!(view.is_editing());
+        view.handle_key(KeyCode::Enter, &log);
+        assert_eq!(shown(&view), vec!["Broker timeout"]);
+    }
+}
@@ -1,7 +1,12 @@
 //! Logging setup.
 
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 
+use crate::log_panel::{LogBuffer, LogEvent};
+
 /// Setup logging with the given level.
 pub fn setup_logging(level: &str, json: bool) {
     let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(level));
@@ -18,3 +23,89 @@ pub fn setup_logging(level: &str, json: bool) {
             .init();
     }
 }
+
+/// Setup logging into the dashboard's log buffer instead of the terminal.
+pub fn setup_dashboard_logging(level: &str, buffer: LogBuffer) {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(level));
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(LogBufferLayer::new(buffer))
+        .init();
+}
+
+/// Tracing layer that records events into a [`LogBuffer`].
+///
+/// A `symbol` field on the event tags it for the log panel's symbol filter;
+/// other fields are appended to the message as `key=value`.
+pub struct LogBufferLayer {
+    buffer: LogBuffer,
+}
+
+impl LogBufferLayer {
+    /// Create a layer writing to `buffer`.
+    pub fn new(buffer: LogBuffer) -> Self {
+        Self { buffer }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for LogBufferLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = EventVisitor::default();
+        event.record(&mut visitor);
+
+        let mut message = visitor.message;
+        for (name, value) in visitor.fields {
+            message.push_str(&format!(" {}={}", name, value));
+        }
+        let mut entry = LogEvent::new((*event.metadata().level()).into(), message);
+        entry.symbol = visitor.symbol;
+        self.buffer.push(entry);
+    }
+}
+
+#[derive(Default)]
+struct EventVisitor {
+    message: String,
+    symbol: Option<String>,
+    fields: Vec<(&'static str, String)>,
+}
+
+impl Visit for EventVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "message" => self.message = value.to_string(),
+            "symbol" => self.symbol = Some(value.to_string()),
+            name => self.fields.push((name, value.to_string())),
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        match field.name() {
+            "message" => self.message = format!("{:?}", value),
+            "symbol" => self.symbol = Some(format!("{:?}", value)),
+            name => self.fields.push((name, format!("{:?}", value))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::log_panel::LogLevel;
+
+    #[test]
+    fn test_layer_records_symbol_and_fields() {
+        let buffer = LogBuffer::new(10);
+        let subscriber = tracing_subscriber::registry().with(LogBufferLayer::new(buffer.clone()));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::warn!(symbol = "AAPL", quantity = 5, "Order rejected");
+        });
+
+        let events = buffer.events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].level, LogLevel::Warn);
+        assert_eq!(events[0].symbol.as_deref(), Some("AAPL"));
+        assert_eq!(events[0].message, "Order rejected quantity=5");
+    }
+}
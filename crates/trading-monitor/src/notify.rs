@@ -0,0 +1,539 @@
+//! Notifications of fills, risk events and daily summaries to Slack,
+//! Telegram, email and generic webhooks.
+
+use async_trait::async_trait;
+use lettre::{
+    message::Mailbox, transport::smtp::authentication::Credentials, AsyncSmtpTransport,
+    AsyncTransport, Message, Tokio1Executor,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::warn;
+use trading_core::error::NotifyError;
+use trading_core::types::Order;
+
+/// Timeout of each HTTP delivery.
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// What a notification is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    /// An order filled
+    Fill,
+    /// Risk management rejected a signal or disabled the strategy
+    RiskLimit,
+    /// The circuit breaker halted trading
+    CircuitBreaker,
+    /// A trading session ended
+    DailySummary,
+}
+
+impl NotificationKind {
+    /// Every kind, the default set to notify on.
+    pub const ALL: [NotificationKind; 4] = [
+        NotificationKind::Fill,
+        NotificationKind::RiskLimit,
+        NotificationKind::CircuitBreaker,
+        NotificationKind::DailySummary,
+    ];
+}
+
+impl std::fmt::Display for NotificationKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NotificationKind::Fill => write!(f, "fill"),
+            NotificationKind::RiskLimit => write!(f, "risk_limit"),
+            NotificationKind::CircuitBreaker => write!(f, "circuit_breaker"),
+            NotificationKind::DailySummary => write!(f, "daily_summary"),
+        }
+    }
+}
+
+impl std::str::FromStr for NotificationKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('-', "_").as_str() {
+            "fill" => Ok(NotificationKind::Fill),
+            "risk_limit" => Ok(NotificationKind::RiskLimit),
+            "circuit_breaker" => Ok(NotificationKind::CircuitBreaker),
+            "daily_summary" => Ok(NotificationKind::DailySummary),
+            _ => Err(format!("Unknown notification kind: {}", s)),
+        }
+    }
+}
+
+/// A message to send.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Notification {
+    pub kind: NotificationKind,
+    pub title: String,
+    pub message: String,
+    /// Unix timestamp in milliseconds
+    pub timestamp: i64,
+}
+
+impl Notification {
+    /// Create a notification stamped with the current time.
+    pub fn new(
+        kind: NotificationKind,
+        title: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            kind,
+            title: title.into(),
+            message: message.into(),
+            timestamp: chrono::Utc::now().timestamp_millis(),
+        }
+    }
+
+    /// An order filled.
+    pub fn fill(order: &Order) -> Self {
+        let price = order
+            .filled_avg_price
+            .map_or(String::new(), |p| format!(" @ ${:.2}", p));
+        Self::new(
+            NotificationKind::Fill,
+            format!("Filled {} {}", order.side, order.symbol),
+            format!(
+                "{} {} {}{}",
+                order.side, order.filled_quantity, order.symbol, price
+            ),
+        )
+    }
+
+    /// Risk management rejected a signal or disabled the strategy.
+    pub fn risk_limit(subject: &str, reason: impl Into<String>) -> Self {
+        Self::new(
+            NotificationKind::RiskLimit,
+            format!("Risk limit: {}", subject),
+            reason,
+        )
+    }
+
+    /// The circuit breaker halted trading.
+    pub fn circuit_breaker(reason: impl Into<String>) -> Self {
+        Self::new(
+            NotificationKind::CircuitBreaker,
+            "Circuit breaker tripped",
+            reason,
+        )
+    }
+
+    /// A trading session ended.
+    pub fn daily_summary(summary: impl Into<String>) -> Self {
+        Self::new(NotificationKind::DailySummary, "Daily summary", summary)
+    }
+
+    /// Title and message as plain text.
+    pub fn text(&self) -> String {
+        format!("{}\n{}", self.title, self.message)
+    }
+}
+
+/// Somewhere notifications can be delivered.
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    /// Name used in logs.
+    fn name(&self) -> &str;
+
+    /// Deliver a notification.
+    async fn notify(&self, notification: &Notification) -> Result<(), NotifyError>;
+}
+
+/// POST `body` as JSON, failing on a non-success status.
+async fn post_json(
+    client: &reqwest::Client,
+    url: &str,
+    body: &impl Serialize,
+) -> Result<(), NotifyError> {
+    let resp = client
+        .post(url)
+        .json(body)
+        .send()
+        .await
+        .map_err(|e| NotifyError::Connection(e.to_string()))?;
+    if !resp.status().is_success() {
+        return Err(NotifyError::Http {
+            status: resp.status().as_u16(),
+            message: resp.text().await.unwrap_or_default(),
+        });
+    }
+    Ok(())
+}
+
+fn http_client() -> Result<reqwest::Client, NotifyError> {
+    reqwest::Client::builder()
+        .timeout(HTTP_TIMEOUT)
+        .build()
+        .map_err(|e| NotifyError::Configuration(e.to_string()))
+}
+
+/// Slack incoming webhook.
+pub struct SlackNotifier {
+    client: reqwest::Client,
+    webhook_url: String,
+}
+
+impl SlackNotifier {
+    pub fn new(webhook_url: impl Into<String>) -> Result<Self, NotifyError> {
+        Ok(Self {
+            client: http_client()?,
+            webhook_url: webhook_url.into(),
+        })
+    }
+
+    /// Message payload, with the title in bold.
+    fn payload(notification: &Notification) -> serde_json::Value {
+        serde_json::json!({
+            "text": format!("*{}*\n{}", notification.title, notification.message),
+        })
+    }
+}
+
+#[async_trait]
+impl Notifier for SlackNotifier {
+    fn name(&self) -> &str {
+        "slack"
+    }
+
+    async fn notify(&self, notification: &Notification) -> Result<(), NotifyError> {
+        post_json(
+            &self.client,
+            &self.webhook_url,
+            &Self::payload(notification),
+        )
+        .await
+    }
+}
+
+/// Telegram bot messaging a chat.
+pub struct TelegramNotifier {
+    client: reqwest::Client,
+    bot_token: String,
+    chat_id: String,
+}
+
+impl TelegramNotifier {
+    pub fn new(
+        bot_token: impl Into<String>,
+        chat_id: impl Into<String>,
+    ) -> Result<Self, NotifyError> {
+        Ok(Self {
+            client: http_client()?,
+            bot_token: bot_token.into(),
+            chat_id: chat_id.into(),
+        })
+    }
+}
+
+#[async_trait]
+impl Notifier for TelegramNotifier {
+    fn name(&self) -> &str {
+        "telegram"
+    }
+
+    async fn notify(&self, notification: &Notification) -> Result<(), NotifyError> {
+        let url = format!("https://api.telegram.org/bot{}/sendMessage", self.bot_token);
+        let body = serde_json::json!({
+            "chat_id": self.chat_id,
+            "text": notification.text(),
+        });
+        post_json(&self.client, &url, &body).await
+    }
+}
+
+/// Email over SMTP with STARTTLS.
+pub struct EmailNotifier {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    to: Vec<Mailbox>,
+}
+
+impl EmailNotifier {
+    pub fn new(config: &EmailConfig) -> Result<Self, NotifyError> {
+        let mailbox = |address: &str| {
+            address
+                .parse::<Mailbox>()
+                .map_err(|e| NotifyError::Configuration(format!("{}: {}", address, e)))
+        };
+        if config.to.is_empty() {
+            return Err(NotifyError::Configuration(
+                "email notifier needs at least one recipient".to_string(),
+            ));
+        }
+        let mut transport = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.smtp_host)
+            .map_err(|e| NotifyError::Configuration(e.to_string()))?
+            .port(config.smtp_port);
+        if let (Some(username), Some(password)) = (&config.username, &config.password) {
+            transport = transport.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+        Ok(Self {
+            transport: transport.build(),
+            from: mailbox(&config.from)?,
+            to: config
+                .to
+                .iter()
+                .map(|a| mailbox(a))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+#[async_trait]
+impl Notifier for EmailNotifier {
+    fn name(&self) -> &str {
+        "email"
+    }
+
+    async fn notify(&self, notification: &Notification) -> Result<(), NotifyError> {
+        let mut builder = Message::builder()
+            .from(self.from.clone())
+            .subject(&notification.title);
+        for to in &self.to {
+            builder = builder.to(to.clone());
+        }
+        let email = builder
+            .body(notification.message.clone())
+            .map_err(|e| NotifyError::Configuration(e.to_string()))?;
+        self.transport
+            .send(email)
+            .await
+            .map_err(|e| NotifyError::Connection(e.to_string()))?;
+        Ok(())
+    }
+}
+
+/// Generic webhook receiving each [`Notification`] as JSON.
+pub struct WebhookNotifier {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: impl Into<String>) -> Result<Self, NotifyError> {
+        Ok(Self {
+            client: http_client()?,
+            url: url.into(),
+        })
+    }
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    fn name(&self) -> &str {
+        "webhook"
+    }
+
+    async fn notify(&self, notification: &Notification) -> Result<(), NotifyError> {
+        post_json(&self.client, &self.url, notification).await
+    }
+}
+
+/// Slack incoming webhook settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlackConfig {
+    pub webhook_url: String,
+}
+
+/// Telegram bot settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelegramConfig {
+    pub bot_token: String,
+    pub chat_id: String,
+}
+
+/// SMTP settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailConfig {
+    pub smtp_host: String,
+    #[serde(default = "default_smtp_port")]
+    pub smtp_port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: String,
+    pub to: Vec<String>,
+}
+
+fn default_smtp_port() -> u16 {
+    587
+}
+
+/// Generic webhook settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    pub url: String,
+}
+
+/// Which notifiers to use and what to notify on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationConfig {
+    /// Kinds of notification sent; all by default
+    #[serde(default = "default_events")]
+    pub events: Vec<NotificationKind>,
+    pub slack: Option<SlackConfig>,
+    pub telegram: Option<TelegramConfig>,
+    pub email: Option<EmailConfig>,
+    #[serde(default)]
+    pub webhooks: Vec<WebhookConfig>,
+}
+
+fn default_events() -> Vec<NotificationKind> {
+    NotificationKind::ALL.to_vec()
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            events: default_events(),
+            slack: None,
+            telegram: None,
+            email: None,
+            webhooks: Vec::new(),
+        }
+    }
+}
+
+/// Fans notifications out to every configured notifier.
+///
+/// Deliveries run in the background so trading never waits on them;
+/// failures are logged.
+#[derive(Clone, Default)]
+pub struct Notifications {
+    notifiers: Vec<Arc<dyn Notifier>>,
+    events: Vec<NotificationKind>,
+}
+
+impl Notifications {
+    /// Notify on `events` (none until notifiers are added).
+    pub fn new(events: Vec<NotificationKind>) -> Self {
+        Self {
+            notifiers: Vec::new(),
+            events,
+        }
+    }
+
+    /// Build the notifiers in `config`.
+    pub fn from_config(config: &NotificationConfig) -> Result<Self, NotifyError> {
+        let mut notifications = Self::new(config.events.clone());
+        if let Some(slack) = &config.slack {
+            notifications = notifications.with_notifier(SlackNotifier::new(&slack.webhook_url)?);
+        }
+        if let Some(telegram) = &config.telegram {
+            notifications = notifications.with_notifier(TelegramNotifier::new(
+                &telegram.bot_token,
+                &telegram.chat_id,
+            )?);
+        }
+        if let Some(email) = &config.email {
+            notifications = notifications.with_notifier(EmailNotifier::new(email)?);
+        }
+        for webhook in &config.webhooks {
+            notifications = notifications.with_notifier(WebhookNotifier::new(&webhook.url)?);
+        }
+        Ok(notifications)
+    }
+
+    /// Add a notifier.
+    pub fn with_notifier(mut self, notifier: impl Notifier + 'static) -> Self {
+        self.notifiers.push(Arc::new(notifier));
+        self
+    }
+
+    /// Whether any notifier is configured.
+    pub fn is_empty(&self) -> bool {
+        self.notifiers.is_empty()
+    }
+
+    /// Names of the configured notifiers.
+    pub fn names(&self) -> Vec<&str> {
+        self.notifiers.iter().map(|n| n.name()).collect()
+    }
+
+    /// Whether notifications of `kind` are sent.
+    pub fn wants(&self, kind: NotificationKind) -> bool {
+        !self.notifiers.is_empty() && self.events.contains(&kind)
+    }
+
+    /// Send a notification to every notifier in the background, if its
+    /// kind is wanted. Must be called within a tokio runtime.
+    pub fn send(&self, notification: Notification) {
+        if !self.wants(notification.kind) {
+            return;
+        }
+        let notification = Arc::new(notification);
+        for notifier in &self.notifiers {
+            let notifier = notifier.clone();
+            let notification = notification.clone();
+            tokio::spawn(async move {
+                if let Err(e) = notifier.notify(&notification).await {
+                    warn!("Failed to send {} notification: {}", notifier.name(), e);
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    struct Recorder(Arc<Mutex<Vec<Notification>>>);
+
+    #[async_trait]
+    impl Notifier for Recorder {
+        fn name(&self) -> &str {
+            "recorder"
+        }
+
+        async fn notify(&self, notification: &Notification) -> Result<(), NotifyError> {
+            self.0.lock().unwrap().push(notification.clone());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_only_wanted_kinds_are_sent() {
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let notifications = Notifications::new(vec![NotificationKind::CircuitBreaker])
+            .with_notifier(Recorder(sent.clone()));
+        notifications.send(Notification::daily_summary("Session 2024-01-02"));
+        notifications.send(Notification::circuit_breaker("Kill switch pressed"));
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        let sent = sent.lock().unwrap();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(
+            sent[0].text(),
+            "Circuit breaker tripped\nKill switch pressed"
+        );
+        assert!(!Notifications::default().wants(NotificationKind::Fill));
+    }
+
+    #[test]
+    fn test_config_and_payloads() {
+        let config: NotificationConfig = serde_json::from_value(serde_json::json!({
+            "events": ["fill", "risk_limit"],
+            "webhooks": [{ "url": "http://localhost:9000/hook" }],
+        }))
+        .unwrap();
+        assert_eq!(
+            config.events,
+            vec![NotificationKind::Fill, NotificationKind::RiskLimit]
+        );
+        assert_eq!(
+            "circuit-breaker".parse::<NotificationKind>(),
+            Ok(NotificationKind::CircuitBreaker)
+        );
+
+        let notifications = Notifications::from_config(&config).unwrap();
+        assert_eq!(notifications.names(), vec!["webhook"]);
+
+        let payload = SlackNotifier::payload(&Notification::risk_limit("AAPL", "Max position"));
+        assert_eq!(payload["text"], "*Risk limit: AAPL*\nMax position");
+    }
+}
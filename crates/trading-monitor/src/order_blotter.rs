@@ -0,0 +1,168 @@
+//! Order blotter panel: working orders and recent fills.
+
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Modifier, Style},
+    widgets::{Block, Borders, Cell, Row, Table, TableState},
+    Frame,
+};
+use rust_decimal::Decimal;
+use trading_core::types::{Order, OrderStatus, Side};
+
+use crate::theme::Theme;
+
+/// Selected row of the order blotter.
+///
+/// Keys (while the blotter has focus): arrows/PageUp/PageDown move the
+/// selection, Home/End jump to the first/last row.
+#[derive(Debug, Clone, Default)]
+pub struct BlotterView {
+    selected: usize,
+}
+
+impl BlotterView {
+    /// Index of the selected row.
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Handle a key for a blotter of `rows` rows; returns false if the view
+    /// did not use it.
+    pub fn handle_key(&mut self, key: KeyCode, rows: usize) -> bool {
+        match move_selection(self.selected, key, rows) {
+            Some(selected) => {
+                self.selected = selected;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Render working orders followed by recent fills.
+    pub fn render(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        open_orders: &[Order],
+        recent_fills: &[Order],
+        theme: &Theme,
+        focused: bool,
+    ) {
+        let header = Row::new(
+            ["Id", "Symbol", "Side", "Qty", "Status", "Fill Price"]
+                .iter()
+                .map(|h| Cell::from(*h).style(Style::default().add_modifier(Modifier::BOLD))),
+        );
+
+        let rows: Vec<Row> = open_orders
+            .iter()
+            .chain(recent_fills)
+            .map(|order| {
+                let side = Style::default().fg(theme.signed(order.side == Side::Buy));
+                let quantity = if order.filled_quantity > Decimal::ZERO
+                    && order.filled_quantity < order.quantity
+                {
+                    format!("{}/{}", order.filled_quantity, order.quantity)
+                } else {
+                    order.quantity.to_string()
+                };
+                Row::new(vec![
+                    Cell::from(order.id.to_string()[..8].to_string()),
+                    Cell::from(order.symbol.clone()),
+                    Cell::from(order.side.to_string()).style(side),
+                    Cell::from(quantity),
+                    Cell::from(status_label(order.status)),
+                    Cell::from(
+                        order
+                            .filled_avg_price
+                            .map_or("-".to_string(), |p| format!("${:.2}", p)),
+                    ),
+                ])
+            })
+            .collect();
+        let count = rows.len();
+
+        let title = format!(
+            "Orders ({} open, {} filled){}",
+            open_orders.len(),
+            recent_fills.len(),
+            if focused { " *" } else { "" }
+        );
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(9),
+                Constraint::Length(8),
+                Constraint::Length(5),
+                Constraint::Length(10),
+                Constraint::Length(11),
+                Constraint::Min(10),
+            ],
+        )
+        .header(header)
+        .row_highlight_style(if focused {
+            Style::default().add_modifier(Modifier::REVERSED)
+        } else {
+            Style::default()
+        })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(if focused {
+                    theme.accent
+                } else {
+                    theme.border
+                }))
+                .title(title),
+        );
+
+        let mut state =
+            TableState::default().with_selected((count > 0).then(|| self.selected.min(count - 1)));
+        frame.render_stateful_widget(table, area, &mut state);
+    }
+}
+
+/// Row selected after a navigation key in a table of `rows` rows, or
+/// `None` if the key doesn't navigate.
+pub(crate) fn move_selection(selected: usize, key: KeyCode, rows: usize) -> Option<usize> {
+    let last = rows.saturating_sub(1);
+    let selected = match key {
+        KeyCode::Up => selected.saturating_sub(1),
+        KeyCode::Down => selected + 1,
+        KeyCode::PageUp => selected.saturating_sub(10),
+        KeyCode::PageDown => selected + 10,
+        KeyCode::Home => 0,
+        KeyCode::End => last,
+        _ => return None,
+    };
+    Some(selected.min(last))
+}
+
+fn status_label(status: OrderStatus) -> String {
+    match status {
+        OrderStatus::PartiallyFilled => "partial".to_string(),
+        status => format!("{:?}", status).to_lowercase(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selection_stays_in_range() {
+        let mut view = BlotterView::default();
+        view.handle_key(KeyCode::Up, 5);
+        assert_eq!(view.selected(), 0);
+        view.handle_key(KeyCode::PageDown, 5);
+        assert_eq!(view.selected(), 4);
+        view.handle_key(KeyCode::Up, 5);
+        assert_eq!(view.selected(), 3);
+        view.handle_key(KeyCode::Home, 5);
+        assert_eq!(view.selected(), 0);
+        view.handle_key(KeyCode::End, 0);
+        assert_eq!(view.selected(), 0);
+        assert!(!view.handle_key(KeyCode::Char('x'), 5));
+    }
+}
@@ -0,0 +1,337 @@
+//! Keyboard-driven order entry modal.
+
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+use trading_core::types::{OrderRequest, OrderType, Side};
+
+use crate::theme::Theme;
+
+/// Command sent from a dashboard or remote client to the trading loop.
+#[derive(Debug, Clone)]
+pub enum DashboardCommand {
+    /// Submit a manual order through the risk pipeline
+    SubmitOrder(Box<OrderRequest>),
+    /// Trip the circuit breaker, halting trading
+    Halt,
+    /// Re-enable a strategy the supervisor disabled
+    EnableStrategy,
+    /// Pause or resume acting on the strategy's signals
+    TogglePause,
+    /// Pause or resume acting on the strategy's signals
+    SetPaused(bool),
+    /// Switch between sending strategy orders and only logging them
+    ToggleDryRun,
+    /// Close a position at market
+    ClosePosition(String),
+    /// Cancel every open order
+    CancelAllOrders,
+    /// Cancel every open order and close every position at market
+    Flatten,
+    /// Advance a paused replay by one bar
+    Step,
+    /// Double the replay speed
+    Faster,
+    /// Halve the replay speed
+    Slower,
+}
+
+/// Result of a key press in the order ticket.
+#[derive(Debug, Clone)]
+pub enum TicketAction {
+    /// Keep the ticket open
+    None,
+    /// Close the ticket without submitting
+    Cancel,
+    /// Close the ticket and submit the order
+    Submit(Box<OrderRequest>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TicketField {
+    Symbol,
+    Side,
+    Quantity,
+    OrderType,
+    LimitPrice,
+}
+
+impl TicketField {
+    const ALL: [TicketField; 5] = [
+        TicketField::Symbol,
+        TicketField::Side,
+        TicketField::Quantity,
+        TicketField::OrderType,
+        TicketField::LimitPrice,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            TicketField::Symbol => "Symbol",
+            TicketField::Side => "Side",
+            TicketField::Quantity => "Quantity",
+            TicketField::OrderType => "Type",
+            TicketField::LimitPrice => "Limit",
+        }
+    }
+}
+
+/// Order entry form state.
+#[derive(Debug, Clone)]
+pub struct OrderTicket {
+    symbols: Vec<String>,
+    symbol_idx: usize,
+    side: Side,
+    quantity: String,
+    order_type: OrderType,
+    limit_price: String,
+    field: usize,
+    error: Option<String>,
+}
+
+impl OrderTicket {
+    /// Create a ticket choosing from the given symbols.
+    pub fn new(symbols: Vec<String>) -> Self {
+        Self {
+            symbols,
+            symbol_idx: 0,
+            side: Side::Buy,
+            quantity: String::new(),
+            order_type: OrderType::Market,
+            limit_price: String::new(),
+            field: 0,
+            error: None,
+        }
+    }
+
+    /// Selected symbol.
+    pub fn symbol(&self) -> Option<&str> {
+        self.symbols.get(self.symbol_idx).map(String::as_str)
+    }
+
+    fn current_field(&self) -> TicketField {
+        TicketField::ALL[self.field]
+    }
+
+    /// Handle a key press.
+    ///
+    /// Up/Down (or Tab) move between fields, Left/Right cycle choices,
+    /// digits edit numbers, Enter submits and Esc cancels.
+    pub fn handle_key(&mut self, key: KeyCode) -> TicketAction {
+        match key {
+            KeyCode::Esc => return TicketAction::Cancel,
+            KeyCode::Enter => {
+                return match self.to_request() {
+                    Ok(request) => TicketAction::Submit(Box::new(request)),
+                    Err(e) => {
+                        self.error = Some(e);
+                        TicketAction::None
+                    }
+                };
+            }
+            KeyCode::Down | KeyCode::Tab => {
+                self.field = (self.field + 1) % TicketField::ALL.len();
+            }
+            KeyCode::Up | KeyCode::BackTab => {
+                self.field = (self.field + TicketField::ALL.len() - 1) % TicketField::ALL.len();
+            }
+            KeyCode::Left | KeyCode::Right => {
+                let forward = key == KeyCode::Right;
+                self.cycle(forward);
+            }
+            KeyCode::Char(c) if c.is_ascii_digit() || c == '.' => {
+                if let Some(text) = self.text_mut() {
+                    text.push(c);
+                }
+            }
+            KeyCode::Backspace => {
+                if let Some(text) = self.text_mut() {
+                    text.pop();
+                }
+            }
+            _ => {}
+        }
+        TicketAction::None
+    }
+
+    fn cycle(&mut self, forward: bool) {
+        match self.current_field() {
+            TicketField::Symbol if !self.symbols.is_empty() => {
+                let n = self.symbols.len();
+                self.symbol_idx = if forward {
+                    (self.symbol_idx + 1) % n
+                } else {
+                    (self.symbol_idx + n - 1) % n
+                };
+            }
+            TicketField::Side => self.side = self.side.opposite(),
+            TicketField::OrderType => {
+                self.order_type = match self.order_type {
+                    OrderType::Market => OrderType::Limit,
+                    _ => OrderType::Market,
+                };
+            }
+            _ => {}
+        }
+    }
+
+    fn text_mut(&mut self) -> Option<&mut String> {
+        match self.current_field() {
+            TicketField::Quantity => Some(&mut self.quantity),
+            TicketField::LimitPrice if self.order_type == OrderType::Limit => {
+                Some(&mut self.limit_price)
+            }
+            _ => None,
+        }
+    }
+
+    /// Build the order request from the form.
+    pub fn to_request(&self) -> Result<OrderRequest, String> {
+        let symbol = self.symbol().ok_or("No symbol to trade")?;
+        let quantity = Decimal::from_str(&self.quantity)
+            .ok()
+            .filter(|q| *q > Decimal::ZERO)
+            .ok_or("Enter a positive quantity")?;
+
+        match self.order_type {
+            OrderType::Limit => {
+                let limit = Decimal::from_str(&self.limit_price)
+                    .ok()
+                    .filter(|p| *p > Decimal::ZERO)
+                    .ok_or("Enter a positive limit price")?;
+                Ok(OrderRequest::limit(symbol, self.side, quantity, limit))
+            }
+            _ => Ok(OrderRequest::market(symbol, self.side, quantity)),
+        }
+    }
+
+    /// Render the ticket as a modal centered in `area`.
+    pub fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let area = centered(area, 40, 11);
+        frame.render_widget(Clear, area);
+
+        let mut lines: Vec<Line> = TicketField::ALL
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                let value = match field {
+                    TicketField::Symbol => self.symbol().unwrap_or("-").to_string(),
+                    TicketField::Side => self.side.to_string(),
+                    TicketField::Quantity => self.quantity.clone(),
+                    TicketField::OrderType => self.order_type.to_string(),
+                    TicketField::LimitPrice if self.order_type == OrderType::Limit => {
+                        self.limit_price.clone()
+                    }
+                    TicketField::LimitPrice => "-".to_string(),
+                };
+                let style = if i == self.field {
+                    Style::default()
+                        .fg(theme.accent)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+                Line::from(vec![
+                    Span::raw(format!("{:<10}", field.label())),
+                    Span::styled(value, style),
+                ])
+            })
+            .collect();
+
+        lines.push(Line::from(""));
+        match &self.error {
+            Some(error) => lines.push(Line::from(Span::styled(
+                error.as_str(),
+                Style::default().fg(theme.negative),
+            ))),
+            None => lines.push(Line::from("Enter submit | Esc cancel")),
+        }
+
+        let side_color = theme.signed(self.side == Side::Buy);
+        let ticket = Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(side_color))
+                .title("Order Ticket"),
+        );
+        frame.render_widget(ticket, area);
+    }
+}
+
+/// Rect of the given size centered in `area`.
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Fill(1),
+            Constraint::Length(height),
+            Constraint::Fill(1),
+        ])
+        .split(area);
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Fill(1),
+            Constraint::Length(width),
+            Constraint::Fill(1),
+        ])
+        .split(vertical[1])[1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn type_keys(ticket: &mut OrderTicket, keys: &[KeyCode]) -> TicketAction {
+        let mut action = TicketAction::None;
+        for key in keys {
+            action = ticket.handle_key(*key);
+        }
+        action
+    }
+
+    #[test]
+    fn test_ticket_builds_limit_order() {
+        let mut ticket = OrderTicket::new(vec!["AAPL".to_string(), "MSFT".to_string()]);
+
+        // Missing quantity keeps the ticket open with an error
+        assert!(matches!(
+            ticket.handle_key(KeyCode::Enter),
+            TicketAction::None
+        ));
+
+        let action = type_keys(
+            &mut ticket,
+            &[
+                KeyCode::Right, // MSFT
+                KeyCode::Down,
+                KeyCode::Right, // SELL
+                KeyCode::Down,
+                KeyCode::Char('2'),
+                KeyCode::Char('5'),
+                KeyCode::Down,
+                KeyCode::Right, // LIMIT
+                KeyCode::Down,
+                KeyCode::Char('4'),
+                KeyCode::Char('1'),
+                KeyCode::Char('0'),
+                KeyCode::Enter,
+            ],
+        );
+        let TicketAction::Submit(order) = action else {
+            panic!("expected submit, got {:?}", action);
+        };
+        assert_eq!(order.symbol, "MSFT");
+        assert_eq!(order.side, Side::Sell);
+        assert_eq!(order.quantity, dec!(25));
+        assert_eq!(order.limit_price, Some(dec!(410)));
+    }
+}
@@ -0,0 +1,332 @@
+//! Price chart panel: a symbol's recent bars as candles or a close line,
+//! with the strategy's price-scale indicators overlaid.
+
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    symbols::Marker,
+    text::{Line, Span},
+    widgets::{Axis, Block, Borders, Chart, Dataset, GraphType, Paragraph},
+    Frame,
+};
+use std::collections::HashMap;
+use trading_core::types::Bar;
+
+use crate::theme::Theme;
+
+/// Colors of overlaid indicators, in order.
+const OVERLAY_COLORS: [Color; 4] = [
+    Color::Yellow,
+    Color::Magenta,
+    Color::LightBlue,
+    Color::LightCyan,
+];
+
+/// Recent bars of a symbol with the strategy's indicator readings.
+#[derive(Debug, Clone, Default)]
+pub struct PriceChart {
+    pub symbol: String,
+    /// Bars, oldest first
+    pub bars: Vec<Bar>,
+    /// Readings by indicator, as (bar timestamp, value) oldest first
+    pub indicators: Vec<(String, Vec<(i64, f64)>)>,
+}
+
+impl PriceChart {
+    /// Indicators on the price scale, such as moving averages and bands,
+    /// as opposed to oscillators: every reading lies within the bars' price
+    /// range widened by its height on either side.
+    pub fn overlays(&self) -> Vec<&(String, Vec<(i64, f64)>)> {
+        let Some((low, high)) = price_range(&self.bars) else {
+            return Vec::new();
+        };
+        let span = (high - low).max(high * 0.01);
+        self.indicators
+            .iter()
+            .filter(|(_, values)| {
+                !values.is_empty()
+                    && values
+                        .iter()
+                        .all(|(_, v)| *v >= low - span && *v <= high + span)
+            })
+            .collect()
+    }
+}
+
+/// How bars are drawn.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ChartStyle {
+    /// One candle per column
+    #[default]
+    Candles,
+    /// Line through the closes
+    Line,
+}
+
+/// Symbol and style shown in the price chart.
+///
+/// Keys: `[`/`]` previous/next symbol, `v` switch between candles and a
+/// close line.
+#[derive(Debug, Clone, Default)]
+pub struct ChartView {
+    selected: usize,
+    style: ChartStyle,
+}
+
+impl ChartView {
+    /// Index of the charted symbol.
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// How bars are drawn.
+    pub fn style(&self) -> ChartStyle {
+        self.style
+    }
+
+    /// Handle a key for a chart of `symbols` symbols; returns false if the
+    /// view did not use it.
+    pub fn handle_key(&mut self, key: KeyCode, symbols: usize) -> bool {
+        match key {
+            KeyCode::Char(']') if symbols > 0 => {
+                self.selected = (self.selected.min(symbols - 1) + 1) % symbols;
+            }
+            KeyCode::Char('[') if symbols > 0 => {
+                self.selected = (self.selected.min(symbols - 1) + symbols - 1) % symbols;
+            }
+            KeyCode::Char('v') => {
+                self.style = match self.style {
+                    ChartStyle::Candles => ChartStyle::Line,
+                    ChartStyle::Line => ChartStyle::Candles,
+                };
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Render the selected symbol's chart.
+    pub fn render(&self, frame: &mut Frame, area: Rect, charts: &[PriceChart], theme: &Theme) {
+        let chart = charts.get(self.selected.min(charts.len().saturating_sub(1)));
+        let mut title = match chart.and_then(|c| Some((c, c.bars.last()?))) {
+            Some((chart, last)) => format!("{} ${:.2}", chart.symbol, last.close),
+            None => "Chart".to_string(),
+        };
+        if charts.len() > 1 {
+            title.push_str(&format!(" [{}/{}]", self.selected + 1, charts.len()));
+        }
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.border))
+            .title(title);
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let Some(chart) = chart.filter(|c| c.bars.len() >= 2) else {
+            frame.render_widget(Paragraph::new("Waiting for bars..."), inner);
+            return;
+        };
+        match self.style {
+            ChartStyle::Candles => render_candles(frame, inner, chart, theme),
+            ChartStyle::Line => render_line(frame, inner, chart, theme),
+        }
+    }
+}
+
+/// Lowest low and highest high of `bars`.
+fn price_range(bars: &[Bar]) -> Option<(f64, f64)> {
+    if bars.is_empty() {
+        return None;
+    }
+    Some(bars.iter().fold((f64::MAX, f64::MIN), |(low, high), bar| {
+        (low.min(bar.low), high.max(bar.high))
+    }))
+}
+
+/// Overlay values at each of `bars`, by bar index.
+fn overlay_points<'a>(chart: &'a PriceChart, bars: &[Bar]) -> Vec<(&'a str, Vec<(usize, f64)>)> {
+    let index: HashMap<i64, usize> = bars
+        .iter()
+        .enumerate()
+        .map(|(i, bar)| (bar.timestamp, i))
+        .collect();
+    chart
+        .overlays()
+        .into_iter()
+        .map(|(name, values)| {
+            let points = values
+                .iter()
+                .filter_map(|(timestamp, value)| Some((*index.get(timestamp)?, *value)))
+                .collect();
+            (name.as_str(), points)
+        })
+        .collect()
+}
+
+/// Bounds of the price axis: the bars' range and the overlays, padded.
+fn y_bounds(bars: &[Bar], overlays: &[(&str, Vec<(usize, f64)>)]) -> (f64, f64) {
+    let (mut low, mut high) = price_range(bars).unwrap_or((0.0, 1.0));
+    for (_, points) in overlays {
+        for (_, value) in points {
+            low = low.min(*value);
+            high = high.max(*value);
+        }
+    }
+    let pad = ((high - low) * 0.05).max(0.01);
+    (low - pad, high + pad)
+}
+
+fn render_line(frame: &mut Frame, area: Rect, chart: &PriceChart, theme: &Theme) {
+    // Two braille dots per column
+    let count = chart.bars.len().min(area.width as usize * 2).max(2);
+    let bars = &chart.bars[chart.bars.len() - count..];
+    let overlays = overlay_points(chart, bars);
+    let (low, high) = y_bounds(bars, &overlays);
+
+    let closes: Vec<(f64, f64)> = bars
+        .iter()
+        .enumerate()
+        .map(|(i, bar)| (i as f64, bar.close))
+        .collect();
+    let overlay_data: Vec<(&str, Vec<(f64, f64)>)> = overlays
+        .iter()
+        .map(|(name, points)| {
+            let data = points.iter().map(|(i, v)| (*i as f64, *v)).collect();
+            (*name, data)
+        })
+        .collect();
+
+    let mut datasets = vec![Dataset::default()
+        .name("close")
+        .marker(Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(Style::default().fg(theme.text))
+        .data(&closes)];
+    for ((name, data), color) in overlay_data.iter().zip(OVERLAY_COLORS.iter().cycle()) {
+        datasets.push(
+            Dataset::default()
+                .name(*name)
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(*color))
+                .data(data),
+        );
+    }
+
+    let widget = Chart::new(datasets)
+        .x_axis(Axis::default().bounds([0.0, (count - 1) as f64]))
+        .y_axis(
+            Axis::default()
+                .style(Style::default().fg(theme.border))
+                .bounds([low, high])
+                .labels([format!("{:.2}", low), format!("{:.2}", high)]),
+        )
+        .hidden_legend_constraints((Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)));
+    frame.render_widget(widget, area);
+}
+
+fn render_candles(frame: &mut Frame, area: Rect, chart: &PriceChart, theme: &Theme) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(2)])
+        .split(area);
+    let label_width = 10;
+    let plot = Rect {
+        x: chunks[1].x + label_width,
+        width: chunks[1].width.saturating_sub(label_width),
+        ..chunks[1]
+    };
+    if plot.width == 0 {
+        return;
+    }
+
+    let count = chart.bars.len().min(plot.width as usize);
+    let bars = &chart.bars[chart.bars.len() - count..];
+    let overlays = overlay_points(chart, bars);
+    let (low, high) = y_bounds(bars, &overlays);
+
+    // Legend of the overlays
+    let legend: Vec<Span> = overlays
+        .iter()
+        .zip(OVERLAY_COLORS.iter().cycle())
+        .flat_map(|((name, _), color)| {
+            [
+                Span::styled("• ", Style::default().fg(*color)),
+                Span::raw(format!("{}  ", name)),
+            ]
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(Line::from(legend)), chunks[0]);
+
+    let labels = Paragraph::new(vec![Line::from(format!("{:>9.2}", high)), Line::from("")]);
+    frame.render_widget(labels, chunks[1]);
+    let bottom = Rect {
+        y: chunks[1].bottom() - 1,
+        height: 1,
+        ..chunks[1]
+    };
+    frame.render_widget(Paragraph::new(format!("{:>9.2}", low)), bottom);
+
+    let rows = plot.height as f64 - 1.0;
+    let row_of = |price: f64| plot.y + ((high - price) / (high - low) * rows).round() as u16;
+    let buffer = frame.buffer_mut();
+    for (i, bar) in bars.iter().enumerate() {
+        let x = plot.x + i as u16;
+        let color = theme.signed(bar.close >= bar.open);
+        let (body_top, body_bottom) = (
+            row_of(bar.open.max(bar.close)),
+            row_of(bar.open.min(bar.close)),
+        );
+        for y in row_of(bar.high)..=row_of(bar.low) {
+            let symbol = if (body_top..=body_bottom).contains(&y) {
+                "█"
+            } else {
+                "│"
+            };
+            buffer[(x, y)].set_symbol(symbol).set_fg(color);
+        }
+    }
+    // Overlays go in the gaps around the candles
+    for ((_, points), color) in overlays.iter().zip(OVERLAY_COLORS.iter().cycle()) {
+        for (i, value) in points {
+            let cell = &mut buffer[(plot.x + *i as u16, row_of(*value))];
+            if cell.symbol() == " " {
+                cell.set_symbol("•").set_fg(*color);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_price_scale_indicators_are_overlaid() {
+        let bars: Vec<Bar> = (0..10)
+            .map(|i| {
+                let price = 100.0 + i as f64;
+                Bar::new(i, price, price + 1.0, price - 1.0, price, 1e6)
+            })
+            .collect();
+        let readings = |value: f64| (0..10).map(|i| (i, value + i as f64)).collect();
+        let chart = PriceChart {
+            symbol: "TEST".to_string(),
+            bars,
+            indicators: vec![
+                ("sma".to_string(), readings(99.0)),
+                ("rsi".to_string(), readings(40.0)),
+                ("upper_band".to_string(), readings(108.0)),
+            ],
+        };
+        let overlays: Vec<&str> = chart.overlays().iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(overlays, vec!["sma", "upper_band"]);
+
+        let mut view = ChartView::default();
+        view.handle_key(KeyCode::Char('['), 3);
+        assert_eq!(view.selected(), 2);
+        view.handle_key(KeyCode::Char('v'), 3);
+        assert_eq!(view.style(), ChartStyle::Line);
+    }
+}
@@ -0,0 +1,177 @@
+//! Strategy panel: warmup progress, indicator readings and custom state of
+//! the running strategy and, for meta-strategies, each member.
+
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Tabs, Wrap},
+    Frame,
+};
+use trading_core::traits::{Strategy, StrategyState};
+
+use crate::theme::Theme;
+
+/// Monitoring snapshot of one strategy.
+#[derive(Debug, Clone, Default)]
+pub struct StrategyStatus {
+    /// Tab title
+    pub label: String,
+    pub state: StrategyState,
+    /// Bars needed before the strategy signals
+    pub warmup_period: usize,
+}
+
+impl StrategyStatus {
+    /// Snapshot of a strategy followed by its members, if it has any.
+    pub fn collect(strategy: &dyn Strategy) -> Vec<Self> {
+        let mut statuses = vec![Self::of(strategy)];
+        statuses.extend(strategy.members().into_iter().map(Self::of));
+        statuses
+    }
+
+    fn of(strategy: &dyn Strategy) -> Self {
+        Self {
+            label: strategy.name().to_string(),
+            state: strategy.state(),
+            warmup_period: strategy.warmup_period(),
+        }
+    }
+}
+
+/// Selected tab of the strategy panel.
+///
+/// Keys: Left/Right switch between the strategy and its members.
+#[derive(Debug, Clone, Default)]
+pub struct StrategyTabs {
+    selected: usize,
+}
+
+impl StrategyTabs {
+    /// Index of the selected tab.
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Handle a key for a panel of `tabs` tabs; returns false if the view
+    /// did not use it.
+    pub fn handle_key(&mut self, key: KeyCode, tabs: usize) -> bool {
+        if tabs < 2 {
+            return false;
+        }
+        self.selected = match key {
+            KeyCode::Left => (self.selected.min(tabs - 1) + tabs - 1) % tabs,
+            KeyCode::Right => (self.selected + 1) % tabs,
+            _ => return false,
+        };
+        true
+    }
+
+    /// Render the selected strategy, with a tab bar if there are members.
+    pub fn render(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        strategies: &[StrategyStatus],
+        theme: &Theme,
+    ) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.border))
+            .title("Strategy");
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+        let Some(selected) = strategies.get(self.selected.min(strategies.len().saturating_sub(1)))
+        else {
+            return;
+        };
+
+        let body = if strategies.len() > 1 {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(1), Constraint::Min(1)])
+                .split(inner);
+            let tabs = Tabs::new(strategies.iter().map(|s| s.label.as_str()))
+                .select(self.selected.min(strategies.len() - 1))
+                .highlight_style(
+                    Style::default()
+                        .fg(theme.accent)
+                        .add_modifier(Modifier::BOLD),
+                );
+            frame.render_widget(tabs, chunks[0]);
+            chunks[1]
+        } else {
+            inner
+        };
+
+        let paragraph = Paragraph::new(status_lines(selected, theme)).wrap(Wrap { trim: true });
+        frame.render_widget(paragraph, body);
+    }
+}
+
+fn status_lines<'a>(status: &'a StrategyStatus, theme: &Theme) -> Vec<Line<'a>> {
+    let state = &status.state;
+    let progress = if state.is_warmed_up || status.warmup_period == 0 {
+        Span::styled("Warmed up", Style::default().fg(theme.positive))
+    } else {
+        let bars = state.bars_processed.min(status.warmup_period);
+        Span::styled(
+            format!(
+                "Warming up {}/{} bars ({:.0}%)",
+                bars,
+                status.warmup_period,
+                bars as f64 / status.warmup_period as f64 * 100.0
+            ),
+            Style::default().fg(theme.accent),
+        )
+    };
+    let mut lines = vec![Line::from(vec![
+        progress,
+        Span::raw(format!(
+            "  |  Bars: {}  |  Signals: {}",
+            state.bars_processed, state.signals_generated
+        )),
+    ])];
+
+    let mut indicators: Vec<(&String, &f64)> = state.indicators.iter().collect();
+    indicators.sort_by(|a, b| a.0.cmp(b.0));
+    if !indicators.is_empty() {
+        lines.push(Line::from(
+            indicators
+                .iter()
+                .map(|(name, value)| format!("{} {:.2}", name, value))
+                .collect::<Vec<_>>()
+                .join("  "),
+        ));
+    }
+
+    // Positions and other strategy-specific state, one entry per line
+    if let Some(custom) = state.custom.as_object() {
+        let mut entries: Vec<_> = custom.iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+        for (key, value) in entries {
+            lines.push(Line::from(vec![
+                Span::styled(format!("{}: ", key), Style::default().fg(theme.border)),
+                Span::raw(value.to_string()),
+            ]));
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tabs_wrap_around() {
+        let mut tabs = StrategyTabs::default();
+        assert!(!tabs.handle_key(KeyCode::Right, 1));
+        tabs.handle_key(KeyCode::Left, 3);
+        assert_eq!(tabs.selected(), 2);
+        tabs.handle_key(KeyCode::Right, 3);
+        assert_eq!(tabs.selected(), 0);
+        assert!(!tabs.handle_key(KeyCode::Up, 3));
+    }
+}
@@ -0,0 +1,174 @@
+//! Dashboard colors and panel layout.
+
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Dashboard panel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Panel {
+    /// Title bar and key hints
+    Header,
+    /// Equity, cash, P&L and counters
+    Stats,
+    /// Recent equity curve and drawdown
+    Equity,
+    /// Open positions table
+    Positions,
+    /// Watchlist quotes
+    Watchlist,
+    /// Working orders and recent fills
+    Orders,
+    /// Strategy warmup, indicators and state, with a tab per member
+    Strategy,
+    /// Recent bars of a symbol with indicator overlays
+    Chart,
+    /// Recent messages
+    Log,
+}
+
+/// Rows of panels from top to bottom; panels in a row share it side by side.
+///
+/// Panels not listed are hidden.
+pub fn default_layout() -> Vec<Vec<Panel>> {
+    vec![
+        vec![Panel::Header],
+        vec![Panel::Stats, Panel::Equity],
+        vec![Panel::Chart, Panel::Strategy],
+        vec![Panel::Positions, Panel::Watchlist],
+        vec![Panel::Orders, Panel::Log],
+    ]
+}
+
+/// Built-in color palette.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemePreset {
+    /// Light text on a dark terminal
+    #[default]
+    Dark,
+    /// Dark text on a light terminal
+    Light,
+    /// Blue/orange gains and losses instead of green/red
+    Colorblind,
+}
+
+/// Theme configuration: a preset with optional color overrides.
+///
+/// Colors accept names (`"red"`, `"lightblue"`), indexed values (`"42"`)
+/// or hex (`"#ff8800"`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ThemeConfig {
+    /// Base palette
+    #[serde(default)]
+    pub preset: ThemePreset,
+    /// Gains and buy orders
+    pub positive: Option<String>,
+    /// Losses, sell orders and errors
+    pub negative: Option<String>,
+    /// Strategy name and highlights
+    pub accent: Option<String>,
+    /// Body text
+    pub text: Option<String>,
+    /// Panel borders
+    pub border: Option<String>,
+}
+
+/// Resolved dashboard colors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    pub positive: Color,
+    pub negative: Color,
+    pub accent: Color,
+    pub text: Color,
+    pub border: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::preset(ThemePreset::Dark)
+    }
+}
+
+impl Theme {
+    /// Colors of a built-in preset.
+    pub fn preset(preset: ThemePreset) -> Self {
+        match preset {
+            ThemePreset::Dark => Self {
+                positive: Color::Green,
+                negative: Color::Red,
+                accent: Color::Cyan,
+                text: Color::White,
+                border: Color::Gray,
+            },
+            ThemePreset::Light => Self {
+                positive: Color::Rgb(0, 128, 0),
+                negative: Color::Rgb(192, 0, 0),
+                accent: Color::Blue,
+                text: Color::Black,
+                border: Color::DarkGray,
+            },
+            ThemePreset::Colorblind => Self {
+                positive: Color::Rgb(0, 114, 178),
+                negative: Color::Rgb(230, 159, 0),
+                accent: Color::Rgb(86, 180, 233),
+                text: Color::White,
+                border: Color::Gray,
+            },
+        }
+    }
+
+    /// Resolve a theme configuration.
+    pub fn from_config(config: &ThemeConfig) -> Result<Self, String> {
+        let mut theme = Self::preset(config.preset);
+        for (slot, value) in [
+            (&mut theme.positive, &config.positive),
+            (&mut theme.negative, &config.negative),
+            (&mut theme.accent, &config.accent),
+            (&mut theme.text, &config.text),
+            (&mut theme.border, &config.border),
+        ] {
+            if let Some(value) = value {
+                *slot = Color::from_str(value)
+                    .map_err(|_| format!("Invalid dashboard color: {}", value))?;
+            }
+        }
+        Ok(theme)
+    }
+
+    /// Color for a signed value.
+    pub fn signed(&self, non_negative: bool) -> Color {
+        if non_negative {
+            self.positive
+        } else {
+            self.negative
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overrides_apply_on_top_of_preset() {
+        let theme = Theme::from_config(&ThemeConfig {
+            preset: ThemePreset::Colorblind,
+            accent: Some("#ff8800".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(theme.accent, Color::Rgb(255, 136, 0));
+        assert_eq!(
+            theme.positive,
+            Theme::preset(ThemePreset::Colorblind).positive
+        );
+
+        assert!(Theme::from_config(&ThemeConfig {
+            negative: Some("not-a-color".to_string()),
+            ..Default::default()
+        })
+        .is_err());
+    }
+}
@@ -0,0 +1,5 @@
+fn main() {
+    // Leave Python symbols to be resolved when the interpreter loads the
+    // module (needed on macOS)
+    pyo3_build_config::add_extension_module_link_args();
+}
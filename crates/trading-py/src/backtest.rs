@@ -0,0 +1,329 @@
+//! Strategies, the backtest engine and its report.
+
+use pyo3::exceptions::{PyRuntimeError, PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use pyo3::IntoPyObjectExt;
+use std::collections::HashMap;
+use trading_backtest::{BacktestConfig, BacktestEngine, BacktestReport};
+use trading_core::traits::Strategy;
+use trading_core::types::Bar;
+use trading_strategies::StrategyRegistry;
+
+use crate::bars::{PyBar, PyBarSeries};
+use crate::convert::{dict_to_json, float, serialize, to_python};
+use crate::strategy::{Failure, PythonStrategy};
+
+/// Names of the built-in strategies, sorted.
+#[pyfunction]
+pub fn strategies() -> Vec<String> {
+    let registry = StrategyRegistry::new();
+    let mut names: Vec<String> = registry.names().into_iter().cloned().collect();
+    names.sort();
+    names
+}
+
+/// Default configuration of a strategy.
+#[pyfunction]
+pub fn strategy_config(py: Python<'_>, name: &str) -> PyResult<PyObject> {
+    let registry = StrategyRegistry::new();
+    let info = registry
+        .get(name)
+        .ok_or_else(|| PyValueError::new_err(format!("Unknown strategy: {}", name)))?;
+    to_python(py, &info.default_config)
+}
+
+/// A strategy from the registry, configured from its defaults with any
+/// keys of `config` replaced, or a Python object with an `on_bar` method
+/// (see [`PythonStrategy`]).
+#[pyclass(name = "Strategy", module = "trading")]
+pub struct PyStrategy {
+    inner: Box<dyn Strategy>,
+    failure: Failure,
+}
+
+impl PyStrategy {
+    fn python(object: &Bound<'_, PyAny>, symbols: Vec<String>) -> PyResult<Self> {
+        let strategy = PythonStrategy::new(object, symbols)?;
+        Ok(Self {
+            failure: strategy.failure(),
+            inner: Box::new(strategy),
+        })
+    }
+}
+
+#[pymethods]
+impl PyStrategy {
+    #[new]
+    #[pyo3(signature = (strategy, symbols, config=None))]
+    fn new(
+        strategy: &Bound<'_, PyAny>,
+        symbols: Vec<String>,
+        config: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<Self> {
+        let Ok(name) = strategy.extract::<String>() else {
+            if config.is_some() {
+                return Err(PyValueError::new_err(
+                    "config only applies to built-in strategies",
+                ));
+            }
+            return Self::python(strategy, symbols);
+        };
+        let overrides = config
+            .map(dict_to_json)
+            .transpose()?
+            .unwrap_or(serde_json::Value::Null);
+        StrategyRegistry::new()
+            .create_with_overrides(&name, overrides, symbols)
+            .map(|inner| Self {
+                inner,
+                failure: Failure::default(),
+            })
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    #[getter]
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    #[getter]
+    fn symbols(&self) -> Vec<String> {
+        self.inner.symbols().to_vec()
+    }
+
+    /// Bars processed, signals generated and current indicator readings.
+    fn state(&self, py: Python<'_>) -> PyResult<PyObject> {
+        serialize(py, &self.inner.state())
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Strategy({:?}, symbols={:?})",
+            self.inner.name(),
+            self.inner.symbols()
+        )
+    }
+}
+
+/// Bars of each symbol given to `BacktestEngine.run`: a `BarSeries` or a
+/// list of `Bar`s.
+fn bars(value: &Bound<'_, PyAny>) -> PyResult<Vec<Bar>> {
+    if let Ok(series) = value.downcast::<PyBarSeries>() {
+        return Ok(series.borrow().inner.iter().copied().collect());
+    }
+    let bars: Vec<PyBar> = value
+        .extract()
+        .map_err(|_| PyTypeError::new_err("Expected a BarSeries or a list of Bar"))?;
+    Ok(bars.into_iter().map(|b| b.inner).collect())
+}
+
+/// Runs strategies over historical bars.
+#[pyclass(name = "BacktestEngine", module = "trading")]
+pub struct PyBacktestEngine {
+    config: BacktestConfig,
+}
+
+#[pymethods]
+impl PyBacktestEngine {
+    /// An engine with the default configuration, with any keyword
+    /// arguments replacing settings of the same name.
+    #[new]
+    #[pyo3(signature = (**config))]
+    fn new(config: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+        let overrides = match config.map(dict_to_json).transpose()? {
+            Some(serde_json::Value::Object(overrides)) => overrides,
+            _ => serde_json::Map::new(),
+        };
+        let config = BacktestConfig::from_overrides(overrides)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(Self { config })
+    }
+
+    #[getter]
+    fn config(&self, py: Python<'_>) -> PyResult<PyObject> {
+        serialize(py, &self.config)
+    }
+
+    /// Run `strategy` over `data`, a dict of symbol to bars. `strategy` is a
+    /// `Strategy`, or a Python object with an `on_bar` method trading every
+    /// symbol of `data`; an exception it raises is raised here once the run
+    /// is over.
+    fn run(
+        &self,
+        py: Python<'_>,
+        strategy: &Bound<'_, PyAny>,
+        data: &Bound<'_, PyDict>,
+    ) -> PyResult<PyBacktestReport> {
+        let mut series = HashMap::new();
+        for (symbol, value) in data.iter() {
+            series.insert(symbol.extract::<String>()?, bars(&value)?);
+        }
+        let mut strategy = match strategy.downcast::<PyStrategy>() {
+            Ok(strategy) => strategy.clone(),
+            Err(_) => {
+                let mut symbols: Vec<String> = series.keys().cloned().collect();
+                symbols.sort();
+                Bound::new(py, PyStrategy::python(strategy, symbols)?)?
+            }
+        }
+        .borrow_mut();
+        let engine = BacktestEngine::new(self.config.clone());
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let failure = strategy.failure.clone();
+        let strategy = strategy.inner.as_mut();
+        let inner = py.allow_threads(|| runtime.block_on(engine.run(strategy, series)));
+        if let Some(e) = failure.lock().ok().and_then(|mut failure| failure.take()) {
+            return Err(e);
+        }
+        Ok(PyBacktestReport { inner })
+    }
+}
+
+/// Results of a backtest.
+#[pyclass(name = "BacktestReport", module = "trading", frozen)]
+pub struct PyBacktestReport {
+    inner: BacktestReport,
+}
+
+impl PyBacktestReport {
+    fn pandas<'py>(py: Python<'py>) -> PyResult<Bound<'py, PyModule>> {
+        py.import("pandas")
+            .map_err(|_| PyRuntimeError::new_err("pandas is needed for DataFrames"))
+    }
+
+    /// `frame` with its millisecond `timestamp` column as UTC datetimes.
+    fn with_datetimes<'py>(
+        pandas: &Bound<'py, PyModule>,
+        frame: Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let py = frame.py();
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("unit", "ms")?;
+        kwargs.set_item("utc", true)?;
+        let timestamps = pandas.call_method(
+            "to_datetime",
+            (frame.get_item("timestamp")?,),
+            Some(&kwargs),
+        )?;
+        frame.set_item("timestamp", timestamps)?;
+        Ok(frame)
+    }
+}
+
+#[pymethods]
+impl PyBacktestReport {
+    /// Headline metrics, as floats and counts.
+    #[getter]
+    fn stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let stats = &self.inner.stats;
+        let dict = PyDict::new(py);
+        dict.set_item("initial_capital", float(stats.initial_capital))?;
+        dict.set_item("final_equity", float(stats.final_equity))?;
+        dict.set_item("total_return_pct", float(stats.total_return_pct))?;
+        dict.set_item("annualized_return_pct", float(stats.annualized_return_pct))?;
+        dict.set_item("max_drawdown_pct", float(stats.max_drawdown_pct))?;
+        dict.set_item("sharpe_ratio", stats.sharpe_ratio)?;
+        dict.set_item("sortino_ratio", stats.sortino_ratio)?;
+        dict.set_item("total_trades", stats.total_trades)?;
+        dict.set_item("winning_trades", stats.winning_trades)?;
+        dict.set_item("losing_trades", stats.losing_trades)?;
+        dict.set_item("win_rate_pct", float(stats.win_rate_pct))?;
+        dict.set_item("avg_win", float(stats.avg_win))?;
+        dict.set_item("avg_loss", float(stats.avg_loss))?;
+        dict.set_item("profit_factor", float(stats.profit_factor))?;
+        dict.set_item("open_pnl", float(stats.open_pnl))?;
+        dict.set_item("bars_processed", stats.bars_processed)?;
+        dict.set_item("risk_rejections", stats.risk_rejections)?;
+        dict.set_item("seed", self.inner.seed)?;
+        Ok(dict)
+    }
+
+    /// Every fill, oldest first; `timestamp` is Unix milliseconds.
+    fn trades<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+        let rows = self
+            .inner
+            .stats
+            .trades
+            .iter()
+            .map(|trade| {
+                let row = PyDict::new(py);
+                row.set_item("timestamp", trade.timestamp.timestamp_millis())?;
+                row.set_item("symbol", &trade.symbol)?;
+                row.set_item("side", trade.side.to_string())?;
+                row.set_item("quantity", float(trade.quantity))?;
+                row.set_item("price", float(trade.price))?;
+                row.set_item("signal_type", trade.signal_type.to_string())?;
+                row.set_item("pnl", trade.pnl.map(float))?;
+                row.set_item("reason", trade.reason.to_string())?;
+                Ok(row)
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        PyList::new(py, rows)
+    }
+
+    /// `(timestamp, equity)` after each bar.
+    fn equity_curve(&self) -> Vec<(i64, f64)> {
+        self.inner
+            .stats
+            .equity_curve
+            .iter()
+            .map(|&(timestamp, equity)| (timestamp, float(equity)))
+            .collect()
+    }
+
+    /// Trades as a pandas DataFrame.
+    fn trades_frame<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let pandas = Self::pandas(py)?;
+        let columns = [
+            "timestamp",
+            "symbol",
+            "side",
+            "quantity",
+            "price",
+            "signal_type",
+            "pnl",
+            "reason",
+        ];
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("columns", columns.to_vec())?;
+        let frame = pandas.call_method("DataFrame", (self.trades(py)?,), Some(&kwargs))?;
+        Self::with_datetimes(&pandas, frame)
+    }
+
+    /// Equity curve as a pandas DataFrame.
+    fn equity_frame<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let pandas = Self::pandas(py)?;
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("columns", ["timestamp", "equity"].to_vec())?;
+        let frame = pandas.call_method(
+            "DataFrame",
+            (self.equity_curve().into_py_any(py)?,),
+            Some(&kwargs),
+        )?;
+        Self::with_datetimes(&pandas, frame)
+    }
+
+    /// The full report, with decimals as strings, as in JSON reports.
+    fn to_dict(&self, py: Python<'_>) -> PyResult<PyObject> {
+        serialize(py, &self.inner)
+    }
+
+    fn __repr__(&self) -> String {
+        let stats = &self.inner.stats;
+        format!(
+            "BacktestReport(return={:.2}%, sharpe={:.2}, max_drawdown={:.2}%, trades={})",
+            float(stats.total_return_pct),
+            stats.sharpe_ratio,
+            float(stats.max_drawdown_pct),
+            stats.total_trades
+        )
+    }
+}
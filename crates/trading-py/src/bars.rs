@@ -0,0 +1,213 @@
+//! `Bar` and `BarSeries`.
+
+use pyo3::exceptions::{PyIndexError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use trading_core::types::{Bar, BarSeries, Timeframe};
+
+/// One OHLCV bar; `timestamp` is Unix milliseconds.
+#[pyclass(name = "Bar", module = "trading", frozen)]
+#[derive(Clone)]
+pub struct PyBar {
+    pub(crate) inner: Bar,
+}
+
+#[pymethods]
+impl PyBar {
+    #[new]
+    #[pyo3(signature = (timestamp, open, high, low, close, volume, vwap=None))]
+    fn new(
+        timestamp: i64,
+        open: f64,
+        high: f64,
+        low: f64,
+        close: f64,
+        volume: f64,
+        vwap: Option<f64>,
+    ) -> Self {
+        let mut inner = Bar::new(timestamp, open, high, low, close, volume);
+        inner.vwap = vwap;
+        Self { inner }
+    }
+
+    #[getter]
+    fn timestamp(&self) -> i64 {
+        self.inner.timestamp
+    }
+
+    #[getter]
+    fn open(&self) -> f64 {
+        self.inner.open
+    }
+
+    #[getter]
+    fn high(&self) -> f64 {
+        self.inner.high
+    }
+
+    #[getter]
+    fn low(&self) -> f64 {
+        self.inner.low
+    }
+
+    #[getter]
+    fn close(&self) -> f64 {
+        self.inner.close
+    }
+
+    #[getter]
+    fn volume(&self) -> f64 {
+        self.inner.volume
+    }
+
+    #[getter]
+    fn vwap(&self) -> Option<f64> {
+        self.inner.vwap
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+
+    fn __repr__(&self) -> String {
+        let bar = &self.inner;
+        format!(
+            "Bar(timestamp={}, open={}, high={}, low={}, close={}, volume={})",
+            bar.timestamp, bar.open, bar.high, bar.low, bar.close, bar.volume
+        )
+    }
+}
+
+fn timeframe(name: &str) -> PyResult<Timeframe> {
+    name.parse().map_err(PyValueError::new_err)
+}
+
+/// Bars of one symbol, oldest first.
+#[pyclass(name = "BarSeries", module = "trading")]
+#[derive(Clone)]
+pub struct PyBarSeries {
+    pub(crate) inner: BarSeries,
+}
+
+#[pymethods]
+impl PyBarSeries {
+    /// An empty series; with a `capacity`, the oldest bars are dropped to
+    /// keep at most that many.
+    #[new]
+    #[pyo3(signature = (symbol, timeframe="1d", capacity=0))]
+    fn new(symbol: String, timeframe: &str, capacity: usize) -> PyResult<Self> {
+        Ok(Self {
+            inner: BarSeries::with_capacity(symbol, self::timeframe(timeframe)?, capacity),
+        })
+    }
+
+    /// A series from equal-length columns, e.g. those of a DataFrame.
+    #[staticmethod]
+    #[pyo3(signature = (symbol, timestamp, open, high, low, close, volume, timeframe="1d"))]
+    #[allow(clippy::too_many_arguments)]
+    fn from_columns(
+        symbol: String,
+        timestamp: Vec<i64>,
+        open: Vec<f64>,
+        high: Vec<f64>,
+        low: Vec<f64>,
+        close: Vec<f64>,
+        volume: Vec<f64>,
+        timeframe: &str,
+    ) -> PyResult<Self> {
+        let len = timestamp.len();
+        if [open.len(), high.len(), low.len(), close.len(), volume.len()]
+            .iter()
+            .any(|&n| n != len)
+        {
+            return Err(PyValueError::new_err("Columns must have the same length"));
+        }
+        let mut inner = BarSeries::new(symbol, self::timeframe(timeframe)?);
+        inner.extend(
+            (0..len).map(|i| Bar::new(timestamp[i], open[i], high[i], low[i], close[i], volume[i])),
+        );
+        Ok(Self { inner })
+    }
+
+    /// Decode a snapshot written by `to_bytes`.
+    #[staticmethod]
+    fn from_bytes(data: &[u8]) -> PyResult<Self> {
+        BarSeries::from_bytes(data)
+            .map(|inner| Self { inner })
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Compact binary snapshot of the series.
+    fn to_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.inner.to_bytes())
+    }
+
+    #[getter]
+    fn symbol(&self) -> &str {
+        &self.inner.symbol
+    }
+
+    #[getter]
+    fn timeframe(&self) -> String {
+        self.inner.timeframe.to_string()
+    }
+
+    fn push(&mut self, bar: PyBar) {
+        self.inner.push(bar.inner);
+    }
+
+    fn extend(&mut self, bars: Vec<PyBar>) {
+        self.inner.extend(bars.into_iter().map(|b| b.inner));
+    }
+
+    fn bars(&self) -> Vec<PyBar> {
+        self.inner.iter().map(|&inner| PyBar { inner }).collect()
+    }
+
+    fn timestamps(&self) -> Vec<i64> {
+        self.inner.iter().map(|b| b.timestamp).collect()
+    }
+
+    fn opens(&self) -> Vec<f64> {
+        self.inner.opens()
+    }
+
+    fn highs(&self) -> Vec<f64> {
+        self.inner.highs()
+    }
+
+    fn lows(&self) -> Vec<f64> {
+        self.inner.lows()
+    }
+
+    fn closes(&self) -> Vec<f64> {
+        self.inner.closes()
+    }
+
+    fn volumes(&self) -> Vec<f64> {
+        self.inner.volumes()
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn __getitem__(&self, index: isize) -> PyResult<PyBar> {
+        let len = self.inner.len() as isize;
+        let index = if index < 0 { len + index } else { index };
+        usize::try_from(index)
+            .ok()
+            .and_then(|i| self.inner.get(i))
+            .map(|&inner| PyBar { inner })
+            .ok_or_else(|| PyIndexError::new_err("bar index out of range"))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "BarSeries(symbol={:?}, timeframe={:?}, bars={})",
+            self.inner.symbol,
+            self.inner.timeframe.to_string(),
+            self.inner.len()
+        )
+    }
+}
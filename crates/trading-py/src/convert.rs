@@ -0,0 +1,86 @@
+//! Conversions between Python objects and the JSON values configs and
+//! reports are (de)serialized through.
+
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyFloat, PyInt, PyList, PyString, PyTuple};
+use pyo3::IntoPyObjectExt;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use serde_json::Value;
+
+/// Python object for a JSON value: dicts, lists, numbers, strings, `None`.
+pub fn to_python(py: Python<'_>, value: &Value) -> PyResult<PyObject> {
+    match value {
+        Value::Null => Ok(py.None()),
+        Value::Bool(b) => b.into_py_any(py),
+        Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+            (Some(i), _) => i.into_py_any(py),
+            (None, Some(u)) => u.into_py_any(py),
+            _ => n.as_f64().unwrap_or(f64::NAN).into_py_any(py),
+        },
+        Value::String(s) => s.into_py_any(py),
+        Value::Array(items) => {
+            let items = items
+                .iter()
+                .map(|item| to_python(py, item))
+                .collect::<PyResult<Vec<_>>>()?;
+            PyList::new(py, items)?.into_py_any(py)
+        }
+        Value::Object(map) => {
+            let dict = PyDict::new(py);
+            for (key, item) in map {
+                dict.set_item(key, to_python(py, item)?)?;
+            }
+            dict.into_py_any(py)
+        }
+    }
+}
+
+/// Python object for anything serializable.
+pub fn serialize(py: Python<'_>, value: &impl Serialize) -> PyResult<PyObject> {
+    let value = serde_json::to_value(value).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    to_python(py, &value)
+}
+
+/// JSON value of a Python object made of dicts, lists, tuples, numbers,
+/// strings and `None`.
+pub fn from_python(obj: &Bound<'_, PyAny>) -> PyResult<Value> {
+    if obj.is_none() {
+        Ok(Value::Null)
+    } else if obj.is_instance_of::<PyBool>() {
+        Ok(Value::Bool(obj.extract()?))
+    } else if obj.is_instance_of::<PyInt>() {
+        Ok(Value::from(obj.extract::<i64>()?))
+    } else if obj.is_instance_of::<PyFloat>() {
+        Ok(Value::from(obj.extract::<f64>()?))
+    } else if obj.is_instance_of::<PyString>() {
+        Ok(Value::String(obj.extract()?))
+    } else if let Ok(dict) = obj.downcast::<PyDict>() {
+        dict_to_json(dict)
+    } else if obj.is_instance_of::<PyList>() || obj.is_instance_of::<PyTuple>() {
+        obj.try_iter()?
+            .map(|item| from_python(&item?))
+            .collect::<PyResult<Vec<_>>>()
+            .map(Value::Array)
+    } else {
+        Err(PyTypeError::new_err(format!(
+            "Cannot convert {} to a config value",
+            obj.get_type().name()?
+        )))
+    }
+}
+
+/// JSON object of a Python dict with string keys.
+pub fn dict_to_json(dict: &Bound<'_, PyDict>) -> PyResult<Value> {
+    let mut map = serde_json::Map::new();
+    for (key, value) in dict.iter() {
+        map.insert(key.extract::<String>()?, from_python(&value)?);
+    }
+    Ok(Value::Object(map))
+}
+
+/// A decimal as a Python float.
+pub fn float(value: Decimal) -> f64 {
+    f64::try_from(value).unwrap_or(f64::NAN)
+}
@@ -0,0 +1,249 @@
+//! Indicator functions over lists (or anything iterable) of floats.
+//!
+//! Each returns the indicator's values, oldest first, starting once it has
+//! enough data; multi-output indicators return a list of dicts.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use trading_core::traits::{Indicator, MultiOutputIndicator};
+use trading_indicators::volatility::KeltnerChannels;
+use trading_indicators::{
+    Adx, Aroon, Atr, BollingerBands, Cci, Dema, DonchianChannels, Ema, Hma, Kama, Macd, Roc, Rsi,
+    Sma, StdDev, Stochastic, Tema, WilliamsR, Wma,
+};
+
+use crate::convert::serialize;
+
+/// Fail with `message` unless `ok`, instead of letting the indicator's own
+/// assertion panic.
+fn require(ok: bool, message: &str) -> PyResult<()> {
+    if ok {
+        Ok(())
+    } else {
+        Err(PyValueError::new_err(message.to_string()))
+    }
+}
+
+fn positive(period: usize) -> PyResult<()> {
+    require(period > 0, "period must be greater than 0")
+}
+
+/// Simple moving average.
+#[pyfunction]
+pub fn sma(values: Vec<f64>, period: usize) -> PyResult<Vec<f64>> {
+    positive(period)?;
+    Ok(Sma::new(period).calculate(&values))
+}
+
+/// Exponential moving average.
+#[pyfunction]
+pub fn ema(values: Vec<f64>, period: usize) -> PyResult<Vec<f64>> {
+    positive(period)?;
+    Ok(Ema::new(period).calculate(&values))
+}
+
+/// Weighted moving average.
+#[pyfunction]
+pub fn wma(values: Vec<f64>, period: usize) -> PyResult<Vec<f64>> {
+    positive(period)?;
+    Ok(Wma::new(period).calculate(&values))
+}
+
+/// Hull moving average.
+#[pyfunction]
+pub fn hma(values: Vec<f64>, period: usize) -> PyResult<Vec<f64>> {
+    positive(period)?;
+    Ok(Hma::new(period).calculate(&values))
+}
+
+/// Kaufman adaptive moving average.
+#[pyfunction]
+#[pyo3(signature = (values, period, fast=2, slow=30))]
+pub fn kama(values: Vec<f64>, period: usize, fast: usize, slow: usize) -> PyResult<Vec<f64>> {
+    positive(period)?;
+    require(
+        fast > 0 && fast < slow,
+        "fast must be above 0 and below slow",
+    )?;
+    Ok(Kama::with_smoothing(period, fast, slow).calculate(&values))
+}
+
+/// Double exponential moving average.
+#[pyfunction]
+pub fn dema(values: Vec<f64>, period: usize) -> PyResult<Vec<f64>> {
+    positive(period)?;
+    Ok(Dema::new(period).calculate(&values))
+}
+
+/// Triple exponential moving average.
+#[pyfunction]
+pub fn tema(values: Vec<f64>, period: usize) -> PyResult<Vec<f64>> {
+    positive(period)?;
+    Ok(Tema::new(period).calculate(&values))
+}
+
+/// Relative strength index.
+#[pyfunction]
+#[pyo3(signature = (values, period=14))]
+pub fn rsi(values: Vec<f64>, period: usize) -> PyResult<Vec<f64>> {
+    positive(period)?;
+    Ok(Rsi::new(period).calculate(&values))
+}
+
+/// Rate of change, in percent.
+#[pyfunction]
+pub fn roc(values: Vec<f64>, period: usize) -> PyResult<Vec<f64>> {
+    positive(period)?;
+    Ok(Roc::new(period).calculate(&values))
+}
+
+/// Rolling standard deviation.
+#[pyfunction]
+pub fn stddev(values: Vec<f64>, period: usize) -> PyResult<Vec<f64>> {
+    require(period > 1, "period must be greater than 1")?;
+    Ok(StdDev::new(period).calculate(&values))
+}
+
+/// MACD line, signal line and histogram.
+#[pyfunction]
+#[pyo3(signature = (values, fast=12, slow=26, signal=9))]
+pub fn macd(
+    py: Python<'_>,
+    values: Vec<f64>,
+    fast: usize,
+    slow: usize,
+    signal: usize,
+) -> PyResult<PyObject> {
+    require(fast > 0 && signal > 0, "periods must be greater than 0")?;
+    require(fast < slow, "fast must be below slow")?;
+    serialize(
+        py,
+        &Macd::with_periods(fast, slow, signal).calculate(&values),
+    )
+}
+
+/// Bollinger bands: upper, middle, lower, bandwidth and %B.
+#[pyfunction]
+#[pyo3(signature = (values, period=20, std_dev=2.0))]
+pub fn bollinger(
+    py: Python<'_>,
+    values: Vec<f64>,
+    period: usize,
+    std_dev: f64,
+) -> PyResult<PyObject> {
+    require(period > 1, "period must be greater than 1")?;
+    require(std_dev > 0.0, "std_dev must be positive")?;
+    serialize(
+        py,
+        &BollingerBands::with_params(period, std_dev).calculate(&values),
+    )
+}
+
+/// Average true range.
+#[pyfunction]
+#[pyo3(signature = (high, low, close, period=14))]
+pub fn atr(high: Vec<f64>, low: Vec<f64>, close: Vec<f64>, period: usize) -> PyResult<Vec<f64>> {
+    positive(period)?;
+    Ok(Atr::new(period).calculate_ohlc(&high, &low, &close))
+}
+
+/// Stochastic oscillator %K and %D.
+#[pyfunction]
+#[pyo3(signature = (high, low, close, k_period=14, d_period=3))]
+pub fn stochastic(
+    py: Python<'_>,
+    high: Vec<f64>,
+    low: Vec<f64>,
+    close: Vec<f64>,
+    k_period: usize,
+    d_period: usize,
+) -> PyResult<PyObject> {
+    require(
+        k_period > 0 && d_period > 0,
+        "periods must be greater than 0",
+    )?;
+    serialize(
+        py,
+        &Stochastic::with_periods(k_period, d_period).calculate_ohlc(&high, &low, &close),
+    )
+}
+
+/// ADX with +DI and -DI.
+#[pyfunction]
+#[pyo3(signature = (high, low, close, period=14))]
+pub fn adx(
+    py: Python<'_>,
+    high: Vec<f64>,
+    low: Vec<f64>,
+    close: Vec<f64>,
+    period: usize,
+) -> PyResult<PyObject> {
+    positive(period)?;
+    serialize(py, &Adx::new(period).calculate_ohlc(&high, &low, &close))
+}
+
+/// Commodity channel index.
+#[pyfunction]
+#[pyo3(signature = (high, low, close, period=20))]
+pub fn cci(high: Vec<f64>, low: Vec<f64>, close: Vec<f64>, period: usize) -> PyResult<Vec<f64>> {
+    positive(period)?;
+    Ok(Cci::new(period).calculate_ohlc(&high, &low, &close))
+}
+
+/// Williams %R.
+#[pyfunction]
+#[pyo3(signature = (high, low, close, period=14))]
+pub fn williams_r(
+    high: Vec<f64>,
+    low: Vec<f64>,
+    close: Vec<f64>,
+    period: usize,
+) -> PyResult<Vec<f64>> {
+    positive(period)?;
+    Ok(WilliamsR::new(period).calculate_ohlc(&high, &low, &close))
+}
+
+/// Aroon up, down and oscillator.
+#[pyfunction]
+#[pyo3(signature = (high, low, period=25))]
+pub fn aroon(py: Python<'_>, high: Vec<f64>, low: Vec<f64>, period: usize) -> PyResult<PyObject> {
+    positive(period)?;
+    serialize(py, &Aroon::new(period).calculate_hl(&high, &low))
+}
+
+/// Donchian channel upper, middle and lower bands.
+#[pyfunction]
+#[pyo3(signature = (high, low, period=20))]
+pub fn donchian(
+    py: Python<'_>,
+    high: Vec<f64>,
+    low: Vec<f64>,
+    period: usize,
+) -> PyResult<PyObject> {
+    positive(period)?;
+    serialize(py, &DonchianChannels::new(period).calculate_hl(&high, &low))
+}
+
+/// Keltner channels: an EMA with bands a multiple of the ATR away.
+#[pyfunction]
+#[pyo3(signature = (high, low, close, ema_period=20, atr_period=10, multiplier=2.0))]
+pub fn keltner(
+    py: Python<'_>,
+    high: Vec<f64>,
+    low: Vec<f64>,
+    close: Vec<f64>,
+    ema_period: usize,
+    atr_period: usize,
+    multiplier: f64,
+) -> PyResult<PyObject> {
+    require(
+        ema_period > 0 && atr_period > 0,
+        "periods must be greater than 0",
+    )?;
+    require(multiplier > 0.0, "multiplier must be positive")?;
+    serialize(
+        py,
+        &KeltnerChannels::with_params(ema_period, atr_period, multiplier)
+            .calculate_ohlc(&high, &low, &close),
+    )
+}
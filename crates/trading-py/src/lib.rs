@@ -0,0 +1,72 @@
+//! Python bindings.
+//!
+//! Builds the `trading` extension module, so notebooks can drive the Rust
+//! indicators, strategies and backtest engine:
+//!
+//! ```python
+//! import trading
+//!
+//! series = trading.BarSeries.from_columns("AAPL", df.timestamp, df.open,
+//!     df.high, df.low, df.close, df.volume)
+//! rsi = trading.rsi(series.closes(), 14)
+//!
+//! strategy = trading.Strategy("ma_crossover", ["AAPL"], {"fast_period": 10})
+//! report = trading.BacktestEngine(initial_capital=50_000).run(strategy, {"AAPL": series})
+//! report.stats["sharpe_ratio"], report.trades_frame()
+//!
+//! class Dip:
+//!     warmup = 20
+//!
+//!     def on_bar(self, bars):
+//!         if bars.close[-1] < min(bars.close[-20:-1]):
+//!             return {"signal": "buy", "reason": "20-bar low"}
+//!
+//! trading.BacktestEngine().run(Dip(), {"AAPL": series})
+//! ```
+//!
+//! Build and install it into the active environment with
+//! `maturin develop -m crates/trading-py/Cargo.toml`.
+
+use pyo3::prelude::*;
+
+mod backtest;
+mod bars;
+mod convert;
+mod indicators;
+mod strategy;
+
+#[pymodule]
+#[pyo3(name = "trading")]
+fn trading_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<bars::PyBar>()?;
+    m.add_class::<bars::PyBarSeries>()?;
+    m.add_class::<backtest::PyStrategy>()?;
+    m.add_class::<strategy::PyBars>()?;
+    m.add_class::<strategy::PyColumn>()?;
+    m.add_class::<backtest::PyBacktestEngine>()?;
+    m.add_class::<backtest::PyBacktestReport>()?;
+    m.add_function(wrap_pyfunction!(backtest::strategies, m)?)?;
+    m.add_function(wrap_pyfunction!(backtest::strategy_config, m)?)?;
+
+    m.add_function(wrap_pyfunction!(indicators::sma, m)?)?;
+    m.add_function(wrap_pyfunction!(indicators::ema, m)?)?;
+    m.add_function(wrap_pyfunction!(indicators::wma, m)?)?;
+    m.add_function(wrap_pyfunction!(indicators::hma, m)?)?;
+    m.add_function(wrap_pyfunction!(indicators::kama, m)?)?;
+    m.add_function(wrap_pyfunction!(indicators::dema, m)?)?;
+    m.add_function(wrap_pyfunction!(indicators::tema, m)?)?;
+    m.add_function(wrap_pyfunction!(indicators::rsi, m)?)?;
+    m.add_function(wrap_pyfunction!(indicators::roc, m)?)?;
+    m.add_function(wrap_pyfunction!(indicators::stddev, m)?)?;
+    m.add_function(wrap_pyfunction!(indicators::macd, m)?)?;
+    m.add_function(wrap_pyfunction!(indicators::bollinger, m)?)?;
+    m.add_function(wrap_pyfunction!(indicators::atr, m)?)?;
+    m.add_function(wrap_pyfunction!(indicators::stochastic, m)?)?;
+    m.add_function(wrap_pyfunction!(indicators::adx, m)?)?;
+    m.add_function(wrap_pyfunction!(indicators::cci, m)?)?;
+    m.add_function(wrap_pyfunction!(indicators::williams_r, m)?)?;
+    m.add_function(wrap_pyfunction!(indicators::aroon, m)?)?;
+    m.add_function(wrap_pyfunction!(indicators::donchian, m)?)?;
+    m.add_function(wrap_pyfunction!(indicators::keltner, m)?)?;
+    Ok(())
+}
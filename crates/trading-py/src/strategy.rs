@@ -0,0 +1,428 @@
+//! Strategies written in Python.
+//!
+//! A [`PythonStrategy`] wraps any Python object with an `on_bar(bars)`
+//! method, so the backtest engine can drive it like a built-in strategy.
+//! `bars` is a [`PyBars`] view of the engine's series: bars and columns are
+//! read in place, without copying the series on every bar, and the view
+//! stops working once `on_bar` returns.
+//!
+//! `on_bar` returns `None`, a signal type (`"buy"`, `"sell"`,
+//! `"close_long"`, `"close_short"`, `"hold"`) or a dict with `signal` and
+//! optionally `reason`, `confidence`, `fraction`, `indicators`,
+//! `stop_loss` and `take_profit`. The object may also have a `name`, a
+//! `warmup` bar count and a `reset()` method.
+
+use pyo3::exceptions::{PyIndexError, PyRuntimeError, PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PySlice, PyString};
+use pyo3::IntoPyObjectExt;
+use std::collections::HashMap;
+use std::ptr::NonNull;
+use std::sync::{Arc, Mutex};
+use trading_core::traits::{Strategy, StrategyState};
+use trading_core::types::{BarSeries, Signal, SignalMetadata, SignalType};
+
+use crate::bars::{PyBar, PyBarSeries};
+
+/// Where a Python strategy leaves the exception that stopped it, for
+/// `BacktestEngine.run` to raise once the run is over.
+pub type Failure = Arc<Mutex<Option<PyErr>>>;
+
+/// Bars of one symbol, as passed to `on_bar`; only valid during the call.
+#[pyclass(name = "Bars", module = "trading", unsendable)]
+pub struct PyBars {
+    /// The engine's series, cleared when `on_bar` returns
+    series: Option<NonNull<BarSeries>>,
+}
+
+impl PyBars {
+    fn series(&self) -> PyResult<&BarSeries> {
+        // SAFETY: the pointer is only set for the duration of the `on_bar`
+        // call it was created for, while the engine holds the series
+        // borrowed and unchanged (see `PythonStrategy::call`).
+        self.series
+            .map(|series| unsafe { series.as_ref() })
+            .ok_or_else(|| PyRuntimeError::new_err("Bars are only valid inside on_bar"))
+    }
+}
+
+/// Index into a sequence of `len`, counting negative indices from the end.
+fn position(index: isize, len: usize) -> PyResult<usize> {
+    let index = if index < 0 {
+        len as isize + index
+    } else {
+        index
+    };
+    usize::try_from(index)
+        .ok()
+        .filter(|&i| i < len)
+        .ok_or_else(|| PyIndexError::new_err("bar index out of range"))
+}
+
+#[pymethods]
+impl PyBars {
+    #[getter]
+    fn symbol(&self) -> PyResult<String> {
+        Ok(self.series()?.symbol.clone())
+    }
+
+    #[getter]
+    fn timeframe(&self) -> PyResult<String> {
+        Ok(self.series()?.timeframe.to_string())
+    }
+
+    #[getter]
+    fn timestamp(slf: Bound<'_, Self>) -> PyColumn {
+        PyColumn::new(slf, Field::Timestamp)
+    }
+
+    #[getter]
+    fn open(slf: Bound<'_, Self>) -> PyColumn {
+        PyColumn::new(slf, Field::Open)
+    }
+
+    #[getter]
+    fn high(slf: Bound<'_, Self>) -> PyColumn {
+        PyColumn::new(slf, Field::High)
+    }
+
+    #[getter]
+    fn low(slf: Bound<'_, Self>) -> PyColumn {
+        PyColumn::new(slf, Field::Low)
+    }
+
+    #[getter]
+    fn close(slf: Bound<'_, Self>) -> PyColumn {
+        PyColumn::new(slf, Field::Close)
+    }
+
+    #[getter]
+    fn volume(slf: Bound<'_, Self>) -> PyColumn {
+        PyColumn::new(slf, Field::Volume)
+    }
+
+    /// A copy of the series, to keep after `on_bar` returns.
+    fn copy(&self) -> PyResult<PyBarSeries> {
+        Ok(PyBarSeries {
+            inner: self.series()?.clone(),
+        })
+    }
+
+    fn __len__(&self) -> PyResult<usize> {
+        Ok(self.series()?.len())
+    }
+
+    fn __getitem__(&self, index: isize) -> PyResult<PyBar> {
+        let series = self.series()?;
+        let i = position(index, series.len())?;
+        Ok(PyBar {
+            inner: *series.get(i).expect("index checked"),
+        })
+    }
+
+    fn __repr__(&self) -> String {
+        match self.series() {
+            Ok(series) => format!("Bars(symbol={:?}, bars={})", series.symbol, series.len()),
+            Err(_) => "Bars(<expired>)".to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Field {
+    Timestamp,
+    Open,
+    High,
+    Low,
+    Close,
+    Volume,
+}
+
+/// One column of a [`PyBars`] view, indexed like a list; slicing copies
+/// just the requested bars into a list.
+#[pyclass(name = "Column", module = "trading", unsendable, sequence)]
+pub struct PyColumn {
+    bars: Py<PyBars>,
+    field: Field,
+}
+
+impl PyColumn {
+    fn new(bars: Bound<'_, PyBars>, field: Field) -> Self {
+        Self {
+            bars: bars.unbind(),
+            field,
+        }
+    }
+
+    /// Values of `indices`, as Python objects.
+    fn values(
+        &self,
+        py: Python<'_>,
+        indices: impl Iterator<Item = usize>,
+    ) -> PyResult<Vec<PyObject>> {
+        let bars = self.bars.borrow(py);
+        let series = bars.series()?;
+        let floats = match self.field {
+            Field::Timestamp => {
+                return indices
+                    .map(|i| {
+                        series
+                            .get(i)
+                            .expect("index checked")
+                            .timestamp
+                            .into_py_any(py)
+                    })
+                    .collect()
+            }
+            Field::Open => series.open_slice(),
+            Field::High => series.high_slice(),
+            Field::Low => series.low_slice(),
+            Field::Close => series.close_slice(),
+            Field::Volume => series.volume_slice(),
+        };
+        indices.map(|i| floats[i].into_py_any(py)).collect()
+    }
+
+    fn len(&self, py: Python<'_>) -> PyResult<usize> {
+        Ok(self.bars.borrow(py).series()?.len())
+    }
+}
+
+#[pymethods]
+impl PyColumn {
+    fn __len__(&self, py: Python<'_>) -> PyResult<usize> {
+        self.len(py)
+    }
+
+    fn __getitem__(&self, py: Python<'_>, index: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        let len = self.len(py)?;
+        if let Ok(slice) = index.downcast::<PySlice>() {
+            let slice = slice.indices(len as isize)?;
+            let indices =
+                (0..slice.slicelength).map(|n| (slice.start + n as isize * slice.step) as usize);
+            return self.values(py, indices)?.into_py_any(py);
+        }
+        let i = position(index.extract()?, len)?;
+        Ok(self.values(py, std::iter::once(i))?.remove(0))
+    }
+
+    /// The whole column as a list.
+    fn to_list(&self, py: Python<'_>) -> PyResult<Vec<PyObject>> {
+        let len = self.len(py)?;
+        self.values(py, 0..len)
+    }
+}
+
+/// Parse a signal type returned by `on_bar`.
+fn signal_type(name: &str) -> PyResult<Option<SignalType>> {
+    match name.to_lowercase().as_str() {
+        "buy" => Ok(Some(SignalType::Buy)),
+        "sell" => Ok(Some(SignalType::Sell)),
+        "close_long" => Ok(Some(SignalType::CloseLong)),
+        "close_short" => Ok(Some(SignalType::CloseShort)),
+        "hold" => Ok(None),
+        _ => Err(PyValueError::new_err(format!(
+            "unknown signal type '{}'",
+            name
+        ))),
+    }
+}
+
+/// Strategy whose logic lives in a Python object.
+pub struct PythonStrategy {
+    object: Py<PyAny>,
+    name: String,
+    symbols: Vec<String>,
+    warmup: usize,
+    bars_processed: usize,
+    signals_generated: usize,
+    failure: Failure,
+}
+
+impl PythonStrategy {
+    /// Wrap `object`, which must have an `on_bar` method.
+    pub fn new(object: &Bound<'_, PyAny>, symbols: Vec<String>) -> PyResult<Self> {
+        let on_bar = object.getattr("on_bar").ok();
+        if !on_bar.is_some_and(|f| f.is_callable()) {
+            return Err(PyTypeError::new_err(
+                "A strategy needs an on_bar(bars) method",
+            ));
+        }
+        if symbols.is_empty() {
+            return Err(PyValueError::new_err("At least one symbol required"));
+        }
+        let name = match object.getattr("name") {
+            Ok(name) if name.is_instance_of::<PyString>() => name.extract()?,
+            _ => object.get_type().name()?.to_string(),
+        };
+        let warmup = match object.getattr("warmup") {
+            Ok(warmup) if warmup.is_callable() => warmup.call0()?.extract()?,
+            Ok(warmup) if !warmup.is_none() => warmup.extract()?,
+            _ => 0,
+        };
+        Ok(Self {
+            object: object.clone().unbind(),
+            name,
+            symbols,
+            warmup,
+            bars_processed: 0,
+            signals_generated: 0,
+            failure: Failure::default(),
+        })
+    }
+
+    /// The slot an exception raised by the object ends up in.
+    pub fn failure(&self) -> Failure {
+        self.failure.clone()
+    }
+
+    /// Run `on_bar` for the latest bar of `series`.
+    fn call(&self, py: Python<'_>, series: &BarSeries) -> PyResult<Option<Signal>> {
+        let bars = Bound::new(
+            py,
+            PyBars {
+                series: Some(NonNull::from(series)),
+            },
+        )?;
+        let returned = self.object.call_method1(py, "on_bar", (bars.clone(),));
+        // The series may change once we return; the view must not outlive it
+        bars.borrow_mut().series = None;
+        self.signal(returned?.bind(py), series)
+    }
+
+    /// The signal for the value `on_bar` returned.
+    fn signal(&self, value: &Bound<'_, PyAny>, series: &BarSeries) -> PyResult<Option<Signal>> {
+        let Some(bar) = series.last() else {
+            return Ok(None);
+        };
+        if value.is_none() {
+            return Ok(None);
+        }
+        let signal = |signal_type| {
+            Signal::new(series.symbol.clone(), signal_type, bar.close, bar.timestamp)
+                .with_confidence(0.6)
+        };
+        if let Ok(name) = value.downcast::<PyString>() {
+            return Ok(signal_type(&name.to_cow()?)?
+                .map(|signal_type| signal(signal_type).with_metadata(self.metadata())));
+        }
+        let Ok(map) = value.downcast::<PyDict>() else {
+            return Err(PyTypeError::new_err(
+                "on_bar must return None, a signal type or a dict",
+            ));
+        };
+
+        let name: String = map
+            .get_item("signal")?
+            .ok_or_else(|| PyValueError::new_err("signal dict needs a `signal` string"))?
+            .extract()?;
+        let Some(signal_type) = signal_type(&name)? else {
+            return Ok(None);
+        };
+        let get = |key: &str| -> PyResult<Option<f64>> {
+            map.get_item(key)?
+                .filter(|v| !v.is_none())
+                .map(|v| v.extract())
+                .transpose()
+        };
+        let mut metadata = self.metadata();
+        if let Some(reason) = map.get_item("reason")? {
+            metadata.reason = reason.str()?.to_string();
+        }
+        if let Some(indicators) = map.get_item("indicators")? {
+            metadata.indicators = indicators
+                .extract::<HashMap<String, f64>>()
+                .map_err(|_| PyTypeError::new_err("`indicators` must map names to numbers"))?;
+        }
+        metadata.stop_loss = get("stop_loss")?;
+        metadata.take_profit = get("take_profit")?;
+
+        let mut signal = signal(signal_type)
+            .with_confidence(get("confidence")?.unwrap_or(0.6))
+            .with_metadata(metadata);
+        if let Some(fraction) = get("fraction")? {
+            signal = signal.with_fraction(fraction);
+        }
+        Ok(Some(signal))
+    }
+
+    fn metadata(&self) -> SignalMetadata {
+        SignalMetadata::new(self.name.clone())
+    }
+}
+
+impl Strategy for PythonStrategy {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        "Python strategy"
+    }
+
+    fn on_bar(&mut self, series: &BarSeries) -> Option<Signal> {
+        self.bars_processed += 1;
+        // After an exception the run carries on without the strategy, and
+        // `BacktestEngine.run` raises it at the end
+        if self
+            .failure
+            .lock()
+            .map_or(true, |failure| failure.is_some())
+        {
+            return None;
+        }
+        let signal = Python::with_gil(|py| match self.call(py, series) {
+            Ok(signal) => signal,
+            Err(e) => {
+                if let Ok(mut failure) = self.failure.lock() {
+                    *failure = Some(e);
+                }
+                None
+            }
+        })?;
+        self.signals_generated += 1;
+        Some(signal)
+    }
+
+    fn reset(&mut self) {
+        self.bars_processed = 0;
+        self.signals_generated = 0;
+        if let Ok(mut failure) = self.failure.lock() {
+            *failure = None;
+        }
+        Python::with_gil(|py| {
+            let object = self.object.bind(py);
+            if object.hasattr("reset").unwrap_or(false) {
+                if let Err(e) = object.call_method0("reset") {
+                    if let Ok(mut failure) = self.failure.lock() {
+                        *failure = Some(e);
+                    }
+                }
+            }
+        });
+    }
+
+    fn state(&self) -> StrategyState {
+        let error = self
+            .failure
+            .lock()
+            .ok()
+            .and_then(|failure| failure.as_ref().map(|e| e.to_string()));
+        StrategyState {
+            name: self.name.clone(),
+            is_warmed_up: self.bars_processed >= self.warmup,
+            bars_processed: self.bars_processed,
+            signals_generated: self.signals_generated,
+            indicators: HashMap::new(),
+            custom: serde_json::json!({ "error": error }),
+        }
+    }
+
+    fn warmup_period(&self) -> usize {
+        self.warmup
+    }
+
+    fn symbols(&self) -> &[String] {
+        &self.symbols
+    }
+}
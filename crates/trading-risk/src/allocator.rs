@@ -0,0 +1,355 @@
+//! Capital allocation across the strategies of a multi-strategy portfolio.
+//!
+//! Each strategy gets a share of capital, and its positions are sized as if
+//! the portfolio were only that share. Shares follow inverse volatility
+//! (every strategy contributes the same risk), explicit risk budgets, or
+//! are fixed, and are recalculated every `rebalance_bars` bars.
+//!
+//! A strategy's volatility is measured on the returns of the symbols it has
+//! signalled a position in, while it has, so it reflects the strategy's own
+//! risk rather than what the portfolio happened to fill.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use trading_core::types::{Bar, Signal, SignalType};
+
+/// How capital is divided across strategies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AllocationMethod {
+    /// Weights proportional to 1 / volatility, equalizing risk
+    #[default]
+    InverseVolatility,
+    /// Weights proportional to budget / volatility, so each strategy's risk
+    /// is its budget's share; strategies without a budget get no capital
+    RiskBudget,
+    /// Each strategy's budget is its share of capital, whatever its
+    /// volatility; shares adding up to more than 1 are scaled down
+    Fixed,
+}
+
+impl std::fmt::Display for AllocationMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            AllocationMethod::InverseVolatility => "inverse_volatility",
+            AllocationMethod::RiskBudget => "risk_budget",
+            AllocationMethod::Fixed => "fixed",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl std::str::FromStr for AllocationMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "inverse_volatility" | "inverse_vol" | "risk_parity" => {
+                Ok(AllocationMethod::InverseVolatility)
+            }
+            "risk_budget" => Ok(AllocationMethod::RiskBudget),
+            "fixed" => Ok(AllocationMethod::Fixed),
+            _ => Err(format!("Invalid allocation method: {}", s)),
+        }
+    }
+}
+
+/// Strategy allocation configuration.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AllocatorConfig {
+    /// How capital is divided
+    pub method: AllocationMethod,
+    /// Risk budget by strategy (signal strategy name), for `risk_budget`,
+    /// or share of capital, for `fixed`
+    pub budgets: HashMap<String, f64>,
+    /// Bars between recalculations of the weights
+    pub rebalance_bars: usize,
+    /// Returns each strategy's volatility is measured over
+    pub volatility_window: usize,
+}
+
+impl Default for AllocatorConfig {
+    fn default() -> Self {
+        Self {
+            method: AllocationMethod::default(),
+            budgets: HashMap::new(),
+            rebalance_bars: 21,
+            volatility_window: 60,
+        }
+    }
+}
+
+impl std::fmt::Display for AllocatorConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.method {
+            // Fixed shares don't change, so there is nothing to rebalance
+            AllocationMethod::Fixed => write!(f, "{}", self.method)?,
+            _ => write!(
+                f,
+                "{}, rebalanced every {} bars",
+                self.method, self.rebalance_bars
+            )?,
+        }
+        if self.method != AllocationMethod::InverseVolatility {
+            let mut budgets: Vec<String> = self
+                .budgets
+                .iter()
+                .map(|(strategy, budget)| format!("{} {}", strategy, budget))
+                .collect();
+            budgets.sort_unstable();
+            write!(f, " ({})", budgets.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Divides capital across strategies by inverse volatility or risk budget.
+#[derive(Debug, Clone)]
+pub struct StrategyAllocator {
+    config: AllocatorConfig,
+    /// Direction each strategy last signalled per symbol (1 long, -1 short)
+    stances: HashMap<String, HashMap<String, f64>>,
+    last_close: HashMap<String, f64>,
+    /// Recent returns of each strategy's signalled positions
+    returns: HashMap<String, VecDeque<f64>>,
+    weights: HashMap<String, f64>,
+    last_timestamp: Option<i64>,
+    bars_since_rebalance: usize,
+}
+
+impl StrategyAllocator {
+    /// Create an allocator; strategies join as they first signal an entry.
+    pub fn new(config: AllocatorConfig) -> Self {
+        Self {
+            config,
+            stances: HashMap::new(),
+            last_close: HashMap::new(),
+            returns: HashMap::new(),
+            weights: HashMap::new(),
+            last_timestamp: None,
+            bars_since_rebalance: 0,
+        }
+    }
+
+    /// Track the position a strategy's signal asks for.
+    pub fn record_signal(&mut self, signal: &Signal) {
+        let strategy = &signal.metadata.strategy_name;
+        let joined = !self.stances.contains_key(strategy);
+        if joined && !matches!(signal.signal_type, SignalType::Buy | SignalType::Sell) {
+            return;
+        }
+        let stances = self.stances.entry(strategy.clone()).or_default();
+        let current = stances.get(&signal.symbol).copied().unwrap_or_default();
+        let stance = match signal.signal_type {
+            SignalType::Buy => 1.0,
+            SignalType::Sell if current > 0.0 => 0.0,
+            SignalType::Sell => -1.0,
+            SignalType::CloseLong | SignalType::CloseShort => 0.0,
+            SignalType::Hold => current,
+        };
+        stances.insert(signal.symbol.clone(), stance);
+        // A new strategy needs a weight right away
+        if joined {
+            self.rebalance();
+        }
+    }
+
+    /// Feed a symbol's next bar, recalculating the weights every
+    /// `rebalance_bars` bar timestamps.
+    ///
+    /// Returns `true` if the weights were recalculated.
+    pub fn on_bar(&mut self, symbol: &str, bar: &Bar) -> bool {
+        let prev = self.last_close.insert(symbol.to_string(), bar.close);
+        if let Some(prev) = prev.filter(|p| *p > 0.0 && bar.close > 0.0) {
+            let symbol_return = bar.close / prev - 1.0;
+            let window = self.config.volatility_window.max(2);
+            for (strategy, stances) in &self.stances {
+                let stance = stances.get(symbol).copied().unwrap_or_default();
+                if stance == 0.0 {
+                    continue;
+                }
+                let returns = self.returns.entry(strategy.clone()).or_default();
+                returns.push_back(stance * symbol_return);
+                while returns.len() > window {
+                    returns.pop_front();
+                }
+            }
+        }
+
+        if self.last_timestamp.is_some_and(|t| bar.timestamp <= t) {
+            return false;
+        }
+        self.last_timestamp = Some(bar.timestamp);
+        self.bars_since_rebalance += 1;
+        if self.bars_since_rebalance < self.config.rebalance_bars.max(1) {
+            return false;
+        }
+        self.rebalance();
+        true
+    }
+
+    /// Recalculate the weights.
+    ///
+    /// Strategies without enough returns for a volatility estimate are
+    /// taken to have the average volatility of the others.
+    pub fn rebalance(&mut self) {
+        self.bars_since_rebalance = 0;
+        let known: Vec<f64> = self
+            .stances
+            .keys()
+            .filter_map(|s| self.volatility(s))
+            .collect();
+        let fallback = if known.is_empty() {
+            1.0
+        } else {
+            known.iter().sum::<f64>() / known.len() as f64
+        };
+
+        let budget = |strategy: &str| {
+            self.config
+                .budgets
+                .get(strategy)
+                .copied()
+                .unwrap_or_default()
+                .max(0.0)
+        };
+        let raw: Vec<(String, f64)> = self
+            .stances
+            .keys()
+            .map(|strategy| {
+                let weight = match self.config.method {
+                    AllocationMethod::InverseVolatility => {
+                        1.0 / self.volatility(strategy).unwrap_or(fallback)
+                    }
+                    AllocationMethod::RiskBudget => {
+                        budget(strategy) / self.volatility(strategy).unwrap_or(fallback)
+                    }
+                    AllocationMethod::Fixed => budget(strategy),
+                };
+                (strategy.clone(), weight)
+            })
+            .collect();
+        let total: f64 = raw.iter().map(|(_, w)| w).sum();
+        // Fixed shares are kept as given unless they overcommit capital
+        let total = match self.config.method {
+            AllocationMethod::Fixed => total.max(1.0),
+            _ => total,
+        };
+        self.weights = raw
+            .into_iter()
+            .map(|(strategy, w)| (strategy, if total > 0.0 { w / total } else { 0.0 }))
+            .collect();
+    }
+
+    /// Per-bar volatility of a strategy's positions, once it has half a
+    /// window of returns.
+    pub fn volatility(&self, strategy: &str) -> Option<f64> {
+        let returns = self.returns.get(strategy)?;
+        let n = returns.len();
+        if n < (self.config.volatility_window / 2).max(2) {
+            return None;
+        }
+        let mean = returns.iter().sum::<f64>() / n as f64;
+        let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1) as f64;
+        (variance > 0.0).then(|| variance.sqrt())
+    }
+
+    /// Share of capital allocated to a strategy, once it has signalled an
+    /// entry.
+    pub fn weight(&self, strategy: &str) -> Option<f64> {
+        self.weights.get(strategy).copied()
+    }
+
+    /// Current shares of capital by strategy.
+    pub fn weights(&self) -> &HashMap<String, f64> {
+        &self.weights
+    }
+
+    /// Get configuration.
+    pub fn config(&self) -> &AllocatorConfig {
+        &self.config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use trading_core::types::SignalMetadata;
+
+    fn tagged(strategy: &str, signal: Signal) -> Signal {
+        signal.with_metadata(SignalMetadata::new(strategy))
+    }
+
+    #[test]
+    fn test_inverse_volatility_weights() {
+        let config = AllocatorConfig {
+            rebalance_bars: 10,
+            volatility_window: 10,
+            ..Default::default()
+        };
+        let mut allocator = StrategyAllocator::new(config.clone());
+
+        allocator.record_signal(&tagged("calm", Signal::buy("A", 100.0, 0)));
+        assert_eq!(allocator.weight("calm"), Some(1.0));
+        allocator.record_signal(&tagged("wild", Signal::buy("B", 100.0, 0)));
+        assert_eq!(allocator.weight("wild"), Some(0.5));
+        // Exits alone don't make a strategy part of the portfolio
+        allocator.record_signal(&tagged("exit", Signal::close_long("A", 100.0, 0)));
+        assert_eq!(allocator.weight("exit"), None);
+
+        // B swings three times as much as A
+        let mut rebalanced = false;
+        for i in 0..12 {
+            let swing = if i % 2 == 0 { 1.0 } else { -1.0 };
+            let bar = |close: f64| Bar::new(i, close, close, close, close, 1000.0);
+            rebalanced |= allocator.on_bar("A", &bar(100.0 + swing));
+            allocator.on_bar("B", &bar(100.0 + 3.0 * swing));
+        }
+        assert!(rebalanced);
+        let calm = allocator.weight("calm").unwrap();
+        let wild = allocator.weight("wild").unwrap();
+        assert!((calm + wild - 1.0).abs() < 1e-12);
+        assert!((calm / wild - 3.0).abs() < 0.2, "{} / {}", calm, wild);
+
+        // Explicit budgets; strategies without one get nothing
+        let mut budgeted = StrategyAllocator::new(AllocatorConfig {
+            method: AllocationMethod::RiskBudget,
+            budgets: HashMap::from([("calm".to_string(), 3.0), ("wild".to_string(), 1.0)]),
+            ..config
+        });
+        for name in ["calm", "wild", "other"] {
+            budgeted.record_signal(&tagged(name, Signal::buy("A", 100.0, 0)));
+        }
+        assert_eq!(budgeted.weight("calm"), Some(0.75));
+        assert_eq!(budgeted.weight("other"), Some(0.0));
+        assert_eq!(
+            "risk_parity".parse::<AllocationMethod>(),
+            Ok(AllocationMethod::InverseVolatility)
+        );
+    }
+
+    #[test]
+    fn test_fixed_weights() {
+        let fixed = |budgets: &[(&str, f64)]| {
+            let mut allocator = StrategyAllocator::new(AllocatorConfig {
+                method: AllocationMethod::Fixed,
+                budgets: budgets.iter().map(|(s, b)| (s.to_string(), *b)).collect(),
+                ..Default::default()
+            });
+            for (name, _) in budgets {
+                allocator.record_signal(&tagged(name, Signal::buy("A", 100.0, 0)));
+            }
+            allocator
+        };
+
+        // Shares are kept as given, even when they leave cash unallocated
+        let allocator = fixed(&[("trend", 0.5), ("reversion", 0.3)]);
+        assert_eq!(allocator.weight("trend"), Some(0.5));
+        assert_eq!(allocator.weight("reversion"), Some(0.3));
+
+        let allocator = fixed(&[("trend", 1.5), ("reversion", 0.5)]);
+        assert_eq!(allocator.weight("trend"), Some(0.75));
+        assert_eq!(allocator.weight("reversion"), Some(0.25));
+    }
+}
@@ -0,0 +1,149 @@
+//! Session blacklist for symbols the broker keeps rejecting.
+//!
+//! Orders for a symbol that is not shortable, halted, or otherwise
+//! untradeable will be rejected on every bar. After a configured number of
+//! consecutive rejections the symbol is blacklisted for the rest of the
+//! session so the broker API is not hammered.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::error;
+
+/// Why and when a symbol was blacklisted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlacklistEntry {
+    /// Symbol
+    pub symbol: String,
+    /// Last rejection reason reported by the broker
+    pub reason: String,
+    /// Consecutive rejections that triggered the blacklist
+    pub rejections: usize,
+    /// When the symbol was blacklisted
+    pub since: DateTime<Utc>,
+}
+
+/// Tracks order rejections per symbol and blacklists repeat offenders.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SymbolBlacklist {
+    max_rejections: usize,
+    rejections: HashMap<String, usize>,
+    entries: HashMap<String, BlacklistEntry>,
+}
+
+impl Default for SymbolBlacklist {
+    fn default() -> Self {
+        Self::new(3)
+    }
+}
+
+impl SymbolBlacklist {
+    /// Create a blacklist that trips after `max_rejections` consecutive rejections.
+    pub fn new(max_rejections: usize) -> Self {
+        Self {
+            max_rejections: max_rejections.max(1),
+            rejections: HashMap::new(),
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Record a rejected order.
+    ///
+    /// Returns `true` if this rejection blacklisted the symbol.
+    pub fn record_rejection(&mut self, symbol: &str, reason: impl Into<String>) -> bool {
+        if self.entries.contains_key(symbol) {
+            return false;
+        }
+
+        let count = self.rejections.entry(symbol.to_string()).or_insert(0);
+        *count += 1;
+        if *count < self.max_rejections {
+            return false;
+        }
+
+        let entry = BlacklistEntry {
+            symbol: symbol.to_string(),
+            reason: reason.into(),
+            rejections: *count,
+            since: Utc::now(),
+        };
+        error!(
+            "Blacklisting {} for this session after {} rejected orders: {}",
+            symbol, entry.rejections, entry.reason
+        );
+        self.entries.insert(symbol.to_string(), entry);
+        true
+    }
+
+    /// Record an accepted order, resetting the rejection streak.
+    pub fn record_success(&mut self, symbol: &str) {
+        self.rejections.remove(symbol);
+    }
+
+    /// Check if a symbol is blacklisted.
+    pub fn is_blacklisted(&self, symbol: &str) -> bool {
+        self.entries.contains_key(symbol)
+    }
+
+    /// Get the blacklist entry for a symbol.
+    pub fn get(&self, symbol: &str) -> Option<&BlacklistEntry> {
+        self.entries.get(symbol)
+    }
+
+    /// All blacklisted symbols.
+    pub fn entries(&self) -> impl Iterator<Item = &BlacklistEntry> {
+        self.entries.values()
+    }
+
+    /// Remove a symbol from the blacklist.
+    pub fn remove(&mut self, symbol: &str) -> Option<BlacklistEntry> {
+        self.rejections.remove(symbol);
+        self.entries.remove(symbol)
+    }
+
+    /// Number of blacklisted symbols.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if no symbols are blacklisted.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blacklist_after_repeated_rejections() {
+        let mut blacklist = SymbolBlacklist::new(3);
+
+        assert!(!blacklist.record_rejection("GME", "not shortable"));
+        assert!(!blacklist.record_rejection("GME", "not shortable"));
+        assert!(!blacklist.is_blacklisted("GME"));
+        assert!(blacklist.record_rejection("GME", "not shortable"));
+        assert!(blacklist.is_blacklisted("GME"));
+
+        let entry = blacklist.get("GME").unwrap();
+        assert_eq!(entry.reason, "not shortable");
+        assert_eq!(entry.rejections, 3);
+
+        // Further rejections don't re-trigger
+        assert!(!blacklist.record_rejection("GME", "halted"));
+
+        blacklist.remove("GME");
+        assert!(blacklist.is_empty());
+    }
+
+    #[test]
+    fn test_success_resets_streak() {
+        let mut blacklist = SymbolBlacklist::new(2);
+
+        blacklist.record_rejection("AAPL", "rejected");
+        blacklist.record_success("AAPL");
+        assert!(!blacklist.record_rejection("AAPL", "rejected"));
+        assert!(!blacklist.is_blacklisted("AAPL"));
+    }
+}
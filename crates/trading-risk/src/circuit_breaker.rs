@@ -0,0 +1,287 @@
+//! Kill switch for paper and live trading.
+//!
+//! Where the [`StrategySupervisor`](crate::StrategySupervisor) judges a
+//! strategy's performance, the circuit breaker watches for trading itself
+//! going wrong: a long losing streak, a failing broker, fills far from the
+//! expected price, or market data that stopped arriving. Once tripped,
+//! automatically or by hand, it halts new entries (and by default flattens
+//! every position) until the session is restarted or it is reset.
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use tracing::error;
+use trading_core::types::{Side, Signal};
+
+/// Circuit breaker configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CircuitBreakerConfig {
+    /// Trip after this many losing trades in a row
+    pub max_consecutive_losses: Option<usize>,
+    /// Trip when this percentage of the last `error_window` broker calls
+    /// failed
+    pub max_error_rate_pct: Option<f64>,
+    /// Broker calls the error rate is measured over
+    pub error_window: usize,
+    /// Trip when a market order fills this many percent worse than the
+    /// price it was sized at
+    pub max_slippage_pct: Option<f64>,
+    /// Trip when no market data has arrived for this many seconds
+    pub max_data_age_secs: Option<u64>,
+    /// Close all positions when tripped
+    pub flatten_on_trip: bool,
+    /// Trip when this file exists; `trading halt` creates it
+    pub halt_file: Option<PathBuf>,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            max_consecutive_losses: Some(10),
+            max_error_rate_pct: Some(50.0),
+            error_window: 20,
+            max_slippage_pct: Some(2.0),
+            max_data_age_secs: None,
+            flatten_on_trip: true,
+            halt_file: Some(PathBuf::from("data/HALT")),
+        }
+    }
+}
+
+/// Circuit breaker status.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitBreakerStatus {
+    /// Trading normally
+    Armed,
+    /// Trading halted until reset
+    Tripped { reason: String },
+}
+
+impl std::fmt::Display for CircuitBreakerStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CircuitBreakerStatus::Armed => write!(f, "armed"),
+            CircuitBreakerStatus::Tripped { reason } => write!(f, "tripped: {}", reason),
+        }
+    }
+}
+
+/// Halts trading when losses, broker errors, slippage or stale data show
+/// something is wrong.
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    status: CircuitBreakerStatus,
+    consecutive_losses: usize,
+    /// Outcomes of the latest broker calls, `true` for success
+    broker_calls: VecDeque<bool>,
+    /// Timestamp (ms) of the latest market data
+    last_data_at: Option<i64>,
+}
+
+impl CircuitBreaker {
+    /// Create an armed circuit breaker.
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            status: CircuitBreakerStatus::Armed,
+            consecutive_losses: 0,
+            broker_calls: VecDeque::new(),
+            last_data_at: None,
+        }
+    }
+
+    /// Record the realized P&L of a closed trade.
+    ///
+    /// Returns `true` if this trade tripped the breaker.
+    pub fn record_trade(&mut self, pnl: Decimal) -> bool {
+        if pnl < Decimal::ZERO {
+            self.consecutive_losses += 1;
+        } else if pnl > Decimal::ZERO {
+            self.consecutive_losses = 0;
+        }
+        match self.config.max_consecutive_losses {
+            Some(max) if self.consecutive_losses >= max => self.trip(format!(
+                "{} consecutive losing trades",
+                self.consecutive_losses
+            )),
+            _ => false,
+        }
+    }
+
+    /// Record whether a broker call succeeded.
+    ///
+    /// Returns `true` if the error rate tripped the breaker. The rate is
+    /// only judged once a full window of calls has been made.
+    pub fn record_broker_call(&mut self, ok: bool) -> bool {
+        let window = self.config.error_window.max(1);
+        self.broker_calls.push_back(ok);
+        while self.broker_calls.len() > window {
+            self.broker_calls.pop_front();
+        }
+        let Some(max_pct) = self.config.max_error_rate_pct else {
+            return false;
+        };
+        if self.broker_calls.len() < window {
+            return false;
+        }
+        let errors = self.broker_calls.iter().filter(|ok| !**ok).count();
+        let error_pct = errors as f64 / window as f64 * 100.0;
+        if error_pct >= max_pct {
+            self.trip(format!(
+                "{} of the last {} broker calls failed",
+                errors, window
+            ))
+        } else {
+            false
+        }
+    }
+
+    /// Record a market order's fill against the price it was sized at.
+    ///
+    /// Returns `true` if the slippage tripped the breaker.
+    pub fn record_fill(
+        &mut self,
+        side: Side,
+        expected_price: Decimal,
+        fill_price: Decimal,
+    ) -> bool {
+        let Some(max_pct) = self.config.max_slippage_pct else {
+            return false;
+        };
+        if expected_price <= Decimal::ZERO {
+            return false;
+        }
+        let adverse = match side {
+            Side::Buy => fill_price - expected_price,
+            Side::Sell => expected_price - fill_price,
+        };
+        let slippage_pct = f64::try_from(adverse / expected_price).unwrap_or_default() * 100.0;
+        if slippage_pct > max_pct {
+            self.trip(format!(
+                "Fill at {} slipped {:.2}% from {}",
+                fill_price, slippage_pct, expected_price
+            ))
+        } else {
+            false
+        }
+    }
+
+    /// Record that market data as of `timestamp` (ms) arrived.
+    pub fn record_market_data(&mut self, timestamp: i64) {
+        self.last_data_at = Some(self.last_data_at.map_or(timestamp, |t| t.max(timestamp)));
+    }
+
+    /// Check the age of the latest market data at `now` (ms).
+    ///
+    /// Returns `true` if stale data tripped the breaker. Nothing is judged
+    /// before the first data arrives.
+    pub fn check_market_data(&mut self, now: i64) -> bool {
+        let (Some(max_age), Some(last)) = (self.config.max_data_age_secs, self.last_data_at) else {
+            return false;
+        };
+        let age_secs = (now - last) / 1000;
+        if age_secs > max_age as i64 {
+            self.trip(format!("No market data for {}s", age_secs))
+        } else {
+            false
+        }
+    }
+
+    /// Trip the breaker, e.g. by hand.
+    ///
+    /// Returns `true` if it was armed.
+    pub fn trip(&mut self, reason: impl Into<String>) -> bool {
+        if self.is_tripped() {
+            return false;
+        }
+        let reason = reason.into();
+        error!("Circuit breaker tripped: {}", reason);
+        self.status = CircuitBreakerStatus::Tripped { reason };
+        true
+    }
+
+    /// Re-arm the breaker, clearing the losing streak and error history.
+    pub fn reset(&mut self) {
+        self.status = CircuitBreakerStatus::Armed;
+        self.consecutive_losses = 0;
+        self.broker_calls.clear();
+    }
+
+    /// Check if a signal may be acted on.
+    ///
+    /// Exits are always allowed so positions can still be unwound.
+    pub fn allows(&self, signal: &Signal) -> bool {
+        !self.is_tripped() || signal.signal_type.is_exit()
+    }
+
+    /// Whether trading is halted.
+    pub fn is_tripped(&self) -> bool {
+        matches!(self.status, CircuitBreakerStatus::Tripped { .. })
+    }
+
+    /// Check if positions should be closed because the breaker tripped.
+    pub fn should_flatten(&self) -> bool {
+        self.is_tripped() && self.config.flatten_on_trip
+    }
+
+    /// Current status.
+    pub fn status(&self) -> &CircuitBreakerStatus {
+        &self.status
+    }
+
+    /// Get configuration.
+    pub fn config(&self) -> &CircuitBreakerConfig {
+        &self.config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_circuit_breaker_trips() {
+        let config = CircuitBreakerConfig {
+            max_consecutive_losses: Some(3),
+            error_window: 4,
+            max_data_age_secs: Some(60),
+            ..Default::default()
+        };
+
+        let mut breaker = CircuitBreaker::new(config.clone());
+        assert!(!breaker.record_trade(dec!(-1)));
+        assert!(!breaker.record_trade(dec!(-1)));
+        assert!(breaker.record_trade(dec!(-1)));
+        assert!(!breaker.allows(&Signal::buy("AAPL", 100.0, 0)));
+        assert!(breaker.allows(&Signal::close_long("AAPL", 100.0, 0)));
+        assert!(breaker.should_flatten());
+        // Already tripped
+        assert!(!breaker.trip("manual"));
+        breaker.reset();
+        assert_eq!(breaker.status(), &CircuitBreakerStatus::Armed);
+
+        // Half of a full window of calls failing
+        for ok in [false, true, false] {
+            assert!(!breaker.record_broker_call(ok));
+        }
+        assert!(breaker.record_broker_call(true));
+        breaker.reset();
+
+        // A buy filling 3% above the price it was sized at
+        assert!(!breaker.record_fill(Side::Buy, dec!(100), dec!(101)));
+        assert!(!breaker.record_fill(Side::Sell, dec!(100), dec!(103)));
+        assert!(breaker.record_fill(Side::Buy, dec!(100), dec!(103)));
+        breaker.reset();
+
+        assert!(!breaker.check_market_data(1_000_000));
+        breaker.record_market_data(0);
+        assert!(!breaker.check_market_data(60_000));
+        assert!(breaker.check_market_data(61_000));
+        assert!(breaker.status().to_string().contains("No market data"));
+    }
+}
@@ -0,0 +1,87 @@
+//! Rolling return correlations between symbols.
+
+use std::collections::HashMap;
+use trading_core::types::Bar;
+
+use crate::ReturnHistory;
+
+/// Rolling correlations of close-to-close returns between every pair of
+/// symbols it is fed bars for.
+///
+/// Returns are matched by bar timestamp, so symbols with gaps are only
+/// compared over the bars they share.
+#[derive(Debug, Clone)]
+pub struct CorrelationMatrix {
+    history: ReturnHistory,
+}
+
+impl CorrelationMatrix {
+    /// Correlate over the last `window` returns of each symbol.
+    pub fn new(window: usize) -> Self {
+        Self {
+            history: ReturnHistory::new(window.max(2)),
+        }
+    }
+
+    /// Feed a symbol's next bar.
+    pub fn update(&mut self, symbol: &str, bar: &Bar) {
+        self.history.update(symbol, bar);
+    }
+
+    /// Correlation between two symbols' returns, once they share at least
+    /// half a window of bars. A symbol is fully correlated with itself.
+    pub fn correlation(&self, a: &str, b: &str) -> Option<f64> {
+        if a == b {
+            return Some(1.0);
+        }
+        let b_returns: HashMap<i64, f64> = self.history.returns(b)?.iter().copied().collect();
+        let pairs: Vec<(f64, f64)> = self
+            .history
+            .returns(a)?
+            .iter()
+            .filter_map(|(ts, x)| b_returns.get(ts).map(|y| (*x, *y)))
+            .collect();
+        if pairs.len() < self.history.window() / 2 || pairs.len() < 2 {
+            return None;
+        }
+
+        let n = pairs.len() as f64;
+        let mean_x = pairs.iter().map(|p| p.0).sum::<f64>() / n;
+        let mean_y = pairs.iter().map(|p| p.1).sum::<f64>() / n;
+        let (mut cov, mut var_x, mut var_y) = (0.0, 0.0, 0.0);
+        for (x, y) in &pairs {
+            cov += (x - mean_x) * (y - mean_y);
+            var_x += (x - mean_x).powi(2);
+            var_y += (y - mean_y).powi(2);
+        }
+        (var_x > 0.0 && var_y > 0.0).then(|| cov / (var_x * var_y).sqrt())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_correlation() {
+        let mut matrix = CorrelationMatrix::new(10);
+        for i in 0..12 {
+            let swing = if i % 2 == 0 { 100.0 } else { 102.0 };
+            let bar = |close: f64| Bar::new(i, close, close, close, close, 1000.0);
+            matrix.update("A", &bar(swing));
+            matrix.update("B", &bar(swing * 2.0));
+            matrix.update("C", &bar(202.0 - swing));
+            // Only every third bar of D lines up
+            if i % 3 == 0 {
+                matrix.update("D", &bar(swing));
+            }
+        }
+
+        let rho = |a, b| matrix.correlation(a, b).unwrap();
+        assert!(rho("A", "B") > 0.99);
+        assert!(rho("A", "C") < -0.99);
+        assert_eq!(rho("D", "D"), 1.0);
+        assert_eq!(matrix.correlation("A", "D"), None);
+        assert_eq!(matrix.correlation("A", "E"), None);
+    }
+}
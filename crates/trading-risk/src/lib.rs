@@ -1,13 +1,39 @@
 //! Risk management for trading.
 //!
-//! Provides position sizing, stop-loss management, and portfolio limits.
+//! Provides position sizing, stop-loss and take-profit management,
+//! portfolio limits including correlated exposure, named risk presets,
+//! time-based exits, end-of-session cleanup, Value-at-Risk, capital
+//! allocation across strategies, performance-based strategy supervision,
+//! and a circuit breaker that halts trading.
 
+mod allocator;
+mod blacklist;
+mod circuit_breaker;
+mod correlation;
 mod portfolio_limits;
 mod position_sizer;
+mod preset;
+mod returns;
 mod risk_manager;
+mod session;
 mod stop_loss;
+mod supervisor;
+mod take_profit;
+mod time_exit;
+mod var;
 
+pub use allocator::{AllocationMethod, AllocatorConfig, StrategyAllocator};
+pub use blacklist::{BlacklistEntry, SymbolBlacklist};
+pub use circuit_breaker::{CircuitBreaker, CircuitBreakerConfig, CircuitBreakerStatus};
+pub use correlation::CorrelationMatrix;
 pub use portfolio_limits::{LimitCheck, PortfolioLimits};
-pub use position_sizer::{PositionSizer, PositionSizingMethod};
-pub use risk_manager::{RiskConfig, RiskDecision, RiskManager};
+pub use position_sizer::{PositionSizer, PositionSizingMethod, SizeBreakdown};
+pub use preset::RiskPreset;
+pub use returns::ReturnHistory;
+pub use risk_manager::{RiskConfig, RiskDecision, RiskManager, SizingPreview};
+pub use session::SessionConfig;
 pub use stop_loss::{StopLossManager, StopLossMethod, StopLossOrder};
+pub use supervisor::{StrategySupervisor, SupervisorConfig, SupervisorStatus};
+pub use take_profit::{TakeProfitManager, TakeProfitMethod};
+pub use time_exit::TimeExit;
+pub use var::{VarEstimate, MIN_VAR_OBSERVATIONS};
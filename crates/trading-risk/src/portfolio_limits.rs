@@ -3,7 +3,10 @@
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
-use trading_core::types::Portfolio;
+use std::collections::HashMap;
+use trading_core::types::{Portfolio, Side};
+
+use crate::CorrelationMatrix;
 
 /// Result of a limit check.
 #[derive(Debug, Clone)]
@@ -27,12 +30,20 @@ impl LimitCheck {
 }
 
 /// Portfolio-level limits configuration.
+///
+/// Limits are checked against whatever portfolio they are given; in live
+/// trading that is the broker's account, so positions opened outside the
+/// system count towards exposure like any other.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PortfolioLimits {
     /// Maximum position size as percentage of equity
     pub max_position_pct: Decimal,
-    /// Maximum total exposure as percentage of equity
+    /// Maximum gross exposure (longs plus shorts) as percentage of equity
     pub max_exposure_pct: Decimal,
+    /// Maximum net exposure (longs minus shorts, either way) as percentage
+    /// of equity
+    #[serde(default)]
+    pub max_net_exposure_pct: Option<Decimal>,
     /// Maximum number of open positions
     pub max_positions: usize,
     /// Maximum loss per day as percentage of equity
@@ -43,6 +54,37 @@ pub struct PortfolioLimits {
     pub min_cash: Decimal,
     /// Maximum concentration in any single position
     pub max_concentration_pct: Decimal,
+    /// Maximum combined exposure to one sector as percentage of equity
+    #[serde(default)]
+    pub max_sector_pct: Option<Decimal>,
+    /// Symbols by sector; symbols not listed belong to no sector
+    #[serde(default)]
+    pub sectors: HashMap<String, Vec<String>>,
+    /// Maximum combined exposure to one asset class as percentage of equity
+    #[serde(default)]
+    pub max_asset_class_pct: Option<Decimal>,
+    /// Symbols by asset class (equities, crypto, ...); symbols not listed
+    /// belong to no asset class
+    #[serde(default)]
+    pub asset_classes: HashMap<String, Vec<String>>,
+    /// Maximum combined exposure to symbols correlated with a new position
+    /// (including the new position) as percentage of equity
+    #[serde(default)]
+    pub max_correlated_pct: Option<Decimal>,
+    /// Return correlation at which a holding counts as correlated
+    #[serde(default = "default_correlation_threshold")]
+    pub correlation_threshold: f64,
+    /// Bars of returns correlations are measured over
+    #[serde(default = "default_correlation_window")]
+    pub correlation_window: usize,
+}
+
+fn default_correlation_threshold() -> f64 {
+    0.7
+}
+
+fn default_correlation_window() -> usize {
+    60
 }
 
 impl Default for PortfolioLimits {
@@ -50,15 +92,30 @@ impl Default for PortfolioLimits {
         Self {
             max_position_pct: dec!(10), // 10% max per position
             max_exposure_pct: dec!(80), // 80% max invested
+            max_net_exposure_pct: None,
             max_positions: 10,
             daily_loss_limit_pct: dec!(3), // Stop if down 3% today
             max_drawdown_pct: dec!(20),    // Stop if 20% drawdown
             min_cash: dec!(1000),
             max_concentration_pct: dec!(25), // No position > 25% of portfolio
+            max_sector_pct: None,
+            sectors: HashMap::new(),
+            max_asset_class_pct: None,
+            asset_classes: HashMap::new(),
+            max_correlated_pct: None,
+            correlation_threshold: default_correlation_threshold(),
+            correlation_window: default_correlation_window(),
         }
     }
 }
 
+/// Largest position value one limit allows.
+struct Cap {
+    max_size: Decimal,
+    reduced: String,
+    blocked: String,
+}
+
 impl PortfolioLimits {
     /// Check if a new position is allowed.
     pub fn check_new_position(
@@ -66,6 +123,94 @@ impl PortfolioLimits {
         portfolio: &Portfolio,
         position_value: Decimal,
         daily_pnl: Decimal,
+    ) -> LimitCheck {
+        self.check(portfolio, None, None, position_value, daily_pnl, None)
+    }
+
+    /// Check if adding `position_value` of `symbol` is allowed.
+    ///
+    /// Unlike [`check_new_position`](Self::check_new_position), an existing
+    /// holding in the symbol counts towards the position size limits, and
+    /// the rest of its sector and asset class towards their limits.
+    pub fn check_new_position_for(
+        &self,
+        portfolio: &Portfolio,
+        symbol: &str,
+        position_value: Decimal,
+        daily_pnl: Decimal,
+    ) -> LimitCheck {
+        self.check(
+            portfolio,
+            Some(symbol),
+            None,
+            position_value,
+            daily_pnl,
+            None,
+        )
+    }
+
+    /// Like [`check_new_position_for`](Self::check_new_position_for), but
+    /// also holding the symbol and everything correlated with it to the
+    /// correlated exposure limit.
+    pub fn check_new_position_correlated(
+        &self,
+        portfolio: &Portfolio,
+        symbol: &str,
+        position_value: Decimal,
+        daily_pnl: Decimal,
+        correlations: &CorrelationMatrix,
+    ) -> LimitCheck {
+        self.check(
+            portfolio,
+            Some(symbol),
+            None,
+            position_value,
+            daily_pnl,
+            Some(correlations),
+        )
+    }
+
+    /// Like [`check_new_position_correlated`](Self::check_new_position_correlated),
+    /// for a trade of `trade_value` in a direction: a trade against the
+    /// holding in the symbol first offsets it, and the net exposure limit
+    /// applies.
+    pub fn check_trade(
+        &self,
+        portfolio: &Portfolio,
+        symbol: &str,
+        side: Side,
+        trade_value: Decimal,
+        daily_pnl: Decimal,
+        correlations: &CorrelationMatrix,
+    ) -> LimitCheck {
+        self.check(
+            portfolio,
+            Some(symbol),
+            Some(side),
+            trade_value,
+            daily_pnl,
+            Some(correlations),
+        )
+    }
+
+    /// Sector a symbol is listed under.
+    pub fn sector_of(&self, symbol: &str) -> Option<&str> {
+        group_of(&self.sectors, symbol)
+    }
+
+    /// Asset class a symbol is listed under.
+    pub fn asset_class_of(&self, symbol: &str) -> Option<&str> {
+        group_of(&self.asset_classes, symbol)
+    }
+
+    fn check(
+        &self,
+        portfolio: &Portfolio,
+        symbol: Option<&str>,
+        side: Option<Side>,
+        position_value: Decimal,
+        daily_pnl: Decimal,
+        correlations: Option<&CorrelationMatrix>,
     ) -> LimitCheck {
         // Check daily loss limit
         let daily_loss_pct = if portfolio.initial_capital > Decimal::ZERO {
@@ -94,8 +239,11 @@ impl PortfolioLimits {
             };
         }
 
-        // Check max positions
-        if portfolio.position_count() >= self.max_positions {
+        // Check max positions; adding to a holding opens no new one
+        let held = symbol
+            .and_then(|s| portfolio.get_position(s))
+            .filter(|p| !p.is_flat());
+        if held.is_none() && portfolio.position_count() >= self.max_positions {
             return LimitCheck::Blocked {
                 reason: format!(
                     "Max positions reached: {} (limit: {})",
@@ -105,72 +253,151 @@ impl PortfolioLimits {
             };
         }
 
+        let pct = |value: Decimal| (value / portfolio.equity) * dec!(100);
+        let of_equity = |limit_pct: Decimal| portfolio.equity * limit_pct / dec!(100);
+        let mut caps = Vec::new();
+
+        // A trade against the holding offsets it before adding exposure
+        let held_abs = held.map(|p| p.market_value.abs()).unwrap_or_default();
+        let offsets =
+            matches!((side, held), (Some(side), Some(p)) if (side == Side::Buy) == p.is_short());
+        let held_value = if offsets { -held_abs } else { held_abs };
+        // Largest trade a limit on `exposure` (including the holding) allows,
+        // when the trade would take the exposure up and past the limit
+        let headroom = |exposure: Decimal, limit_pct: Decimal| {
+            let after = exposure - held_abs + (held_value + position_value).abs();
+            (pct(after) > limit_pct && after > exposure)
+                .then(|| of_equity(limit_pct) - (exposure - held_abs) - held_value)
+        };
+
         // Check min cash
         if portfolio.cash - position_value < self.min_cash {
-            let max_allowed = portfolio.cash - self.min_cash;
-            if max_allowed <= Decimal::ZERO {
-                return LimitCheck::Blocked {
-                    reason: format!(
-                        "Insufficient cash: ${:.2} (need ${:.2} minimum)",
-                        portfolio.cash, self.min_cash
+            caps.push(Cap {
+                max_size: portfolio.cash - self.min_cash,
+                reduced: "Limited by minimum cash requirement".to_string(),
+                blocked: format!(
+                    "Insufficient cash: ${:.2} (need ${:.2} minimum)",
+                    portfolio.cash, self.min_cash
+                ),
+            });
+        }
+
+        // Check max exposure; shorts add to it as much as longs
+        let current_exposure = portfolio.gross_exposure();
+        if let Some(max_size) = headroom(current_exposure, self.max_exposure_pct) {
+            caps.push(Cap {
+                max_size,
+                reduced: format!("Limited by max exposure ({:.2}%)", self.max_exposure_pct),
+                blocked: format!(
+                    "Max exposure reached: {:.2}% (limit: {:.2}%)",
+                    pct(current_exposure),
+                    self.max_exposure_pct
+                ),
+            });
+        }
+
+        // Check net exposure, for a trade in a known direction
+        if let (Some(limit_pct), Some(side)) = (self.max_net_exposure_pct, side) {
+            let net = portfolio.net_exposure();
+            let direction = match side {
+                Side::Buy => Decimal::ONE,
+                Side::Sell => -Decimal::ONE,
+            };
+            let after = net + direction * position_value;
+            if pct(after.abs()) > limit_pct && after.abs() > net.abs() {
+                caps.push(Cap {
+                    max_size: of_equity(limit_pct) - direction * net,
+                    reduced: format!("Limited by max net exposure ({:.2}%)", limit_pct),
+                    blocked: format!(
+                        "Max net {} exposure reached: {:.2}% (limit: {:.2}%)",
+                        if side == Side::Buy { "long" } else { "short" },
+                        pct(net.abs()),
+                        limit_pct
                     ),
-                };
+                });
             }
-            return LimitCheck::Reduced {
-                max_size: max_allowed,
-                reason: "Limited by minimum cash requirement".to_string(),
-            };
         }
 
-        // Check max exposure
-        let current_exposure = portfolio.total_market_value();
-        let new_exposure = current_exposure + position_value;
-        let exposure_pct = (new_exposure / portfolio.equity) * dec!(100);
-
-        if exposure_pct > self.max_exposure_pct {
-            let max_additional =
-                (portfolio.equity * self.max_exposure_pct / dec!(100)) - current_exposure;
-            if max_additional <= Decimal::ZERO {
-                return LimitCheck::Blocked {
-                    reason: format!(
-                        "Max exposure reached: {:.2}% (limit: {:.2}%)",
-                        (current_exposure / portfolio.equity) * dec!(100),
-                        self.max_exposure_pct
+        // Check position size and concentration, including what is already held
+        let name = symbol.unwrap_or_default();
+        for (limit_pct, label) in [
+            (self.max_position_pct, "max position size"),
+            (self.max_concentration_pct, "max concentration"),
+        ] {
+            if let Some(max_size) = headroom(held_abs, limit_pct) {
+                caps.push(Cap {
+                    max_size,
+                    reduced: format!("Limited by {} ({:.2}%)", label, limit_pct),
+                    blocked: format!(
+                        "Already holding {:.2}% of equity in {} (limit: {:.2}%)",
+                        pct(held_abs),
+                        name,
+                        limit_pct
                     ),
-                };
+                });
             }
-            return LimitCheck::Reduced {
-                max_size: max_additional,
-                reason: format!("Limited by max exposure ({:.2}%)", self.max_exposure_pct),
-            };
         }
 
-        // Check position size limit
-        let position_pct = (position_value / portfolio.equity) * dec!(100);
-        if position_pct > self.max_position_pct {
-            let max_position = portfolio.equity * self.max_position_pct / dec!(100);
-            return LimitCheck::Reduced {
-                max_size: max_position,
-                reason: format!(
-                    "Limited by max position size ({:.2}%)",
-                    self.max_position_pct
-                ),
+        // Check sector and asset class exposure
+        for (limit_pct, groups) in [
+            (self.max_sector_pct, &self.sectors),
+            (self.max_asset_class_pct, &self.asset_classes),
+        ] {
+            let group = symbol.and_then(|s| group_of(groups, s));
+            let (Some(limit_pct), Some(group)) = (limit_pct, group) else {
+                continue;
             };
+            let group_exposure = gross_exposure(portfolio, |s| group_of(groups, s) == Some(group));
+            if let Some(max_size) = headroom(group_exposure, limit_pct) {
+                caps.push(Cap {
+                    max_size,
+                    reduced: format!("Limited by max {} exposure ({:.2}%)", group, limit_pct),
+                    blocked: format!(
+                        "Max {} exposure reached: {:.2}% (limit: {:.2}%)",
+                        group,
+                        pct(group_exposure),
+                        limit_pct
+                    ),
+                });
+            }
         }
 
-        // Check concentration
-        if position_pct > self.max_concentration_pct {
-            let max_position = portfolio.equity * self.max_concentration_pct / dec!(100);
-            return LimitCheck::Reduced {
-                max_size: max_position,
-                reason: format!(
-                    "Limited by max concentration ({:.2}%)",
-                    self.max_concentration_pct
-                ),
-            };
+        // Check exposure to holdings that move with the symbol
+        if let (Some(limit_pct), Some(symbol), Some(correlations)) =
+            (self.max_correlated_pct, symbol, correlations)
+        {
+            let correlated = gross_exposure(portfolio, |s| {
+                correlations
+                    .correlation(symbol, s)
+                    .is_some_and(|rho| rho >= self.correlation_threshold)
+            });
+            if let Some(max_size) = headroom(correlated, limit_pct) {
+                caps.push(Cap {
+                    max_size,
+                    reduced: format!("Limited by max correlated exposure ({:.2}%)", limit_pct),
+                    blocked: format!(
+                        "Max exposure correlated with {} reached: {:.2}% (limit: {:.2}%)",
+                        symbol,
+                        pct(correlated),
+                        limit_pct
+                    ),
+                });
+            }
         }
 
-        LimitCheck::Allowed
+        // Any exhausted limit blocks; otherwise the tightest one binds
+        if let Some(cap) = caps.iter().find(|c| c.max_size <= Decimal::ZERO) {
+            return LimitCheck::Blocked {
+                reason: cap.blocked.clone(),
+            };
+        }
+        match caps.into_iter().min_by_key(|c| c.max_size) {
+            Some(cap) => LimitCheck::Reduced {
+                max_size: cap.max_size,
+                reason: cap.reduced,
+            },
+            None => LimitCheck::Allowed,
+        }
     }
 
     /// Check if trading should be halted.
@@ -199,6 +426,24 @@ impl PortfolioLimits {
     }
 }
 
+/// Group (sector or asset class) a symbol is listed under.
+fn group_of<'a>(groups: &'a HashMap<String, Vec<String>>, symbol: &str) -> Option<&'a str> {
+    groups
+        .iter()
+        .find(|(_, symbols)| symbols.iter().any(|s| s.eq_ignore_ascii_case(symbol)))
+        .map(|(group, _)| group.as_str())
+}
+
+/// Combined absolute market value of the positions whose symbol matches.
+fn gross_exposure(portfolio: &Portfolio, include: impl Fn(&str) -> bool) -> Decimal {
+    portfolio
+        .positions
+        .values()
+        .filter(|p| include(&p.symbol))
+        .map(|p| p.market_value.abs())
+        .sum()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -265,4 +510,198 @@ mod tests {
             _ => panic!("Expected Reduced"),
         }
     }
+
+    #[test]
+    fn test_existing_holdings_count_towards_limits() {
+        let mut portfolio = create_portfolio(dec!(100000), dec!(80000), 0);
+        // Opened outside the system: 8% AAPL long, 6% MSFT short
+        for (symbol, quantity) in [("AAPL", dec!(80)), ("MSFT", dec!(-60))] {
+            let mut pos = trading_core::types::Position::new(symbol, quantity, dec!(100));
+            pos.update_price(dec!(100));
+            portfolio.positions.insert(symbol.to_string(), pos);
+        }
+
+        // Only 2% of AAPL left under the 10% position limit
+        let limits = PortfolioLimits::default();
+        match limits.check_new_position_for(&portfolio, "AAPL", dec!(5000), Decimal::ZERO) {
+            LimitCheck::Reduced { max_size, .. } => assert_eq!(max_size, dec!(2000)),
+            other => panic!("Expected Reduced, got {:?}", other),
+        }
+
+        // The short counts towards the sector too: 14% held, 1% left
+        let mut limits = PortfolioLimits {
+            max_sector_pct: Some(dec!(15)),
+            sectors: HashMap::from([(
+                "technology".to_string(),
+                vec!["AAPL".to_string(), "MSFT".to_string()],
+            )]),
+            ..Default::default()
+        };
+        assert!(matches!(
+            limits.check_new_position_for(&portfolio, "GOOG", dec!(5000), Decimal::ZERO),
+            LimitCheck::Allowed
+        ));
+        limits
+            .sectors
+            .get_mut("technology")
+            .unwrap()
+            .push("goog".to_string());
+        match limits.check_new_position_for(&portfolio, "GOOG", dec!(5000), Decimal::ZERO) {
+            LimitCheck::Reduced { max_size, reason } => {
+                assert_eq!(max_size, dec!(1000));
+                assert!(reason.contains("technology"));
+            }
+            other => panic!("Expected Reduced, got {:?}", other),
+        }
+
+        // AAPL rallied past the position limit on its own
+        portfolio
+            .positions
+            .get_mut("AAPL")
+            .unwrap()
+            .update_price(dec!(130));
+        assert!(PortfolioLimits::default()
+            .check_new_position_for(&portfolio, "AAPL", dec!(1000), Decimal::ZERO)
+            .is_blocked());
+    }
+
+    #[test]
+    fn test_correlated_exposure() {
+        let mut portfolio = create_portfolio(dec!(100000), dec!(80000), 0);
+        for symbol in ["XOM", "CVX", "AAPL"] {
+            let mut pos = trading_core::types::Position::new(symbol, dec!(80), dec!(100));
+            pos.update_price(dec!(100));
+            portfolio.positions.insert(symbol.to_string(), pos);
+        }
+
+        // Oil stocks move together, tech against them
+        let mut correlations = CorrelationMatrix::new(10);
+        for i in 0..12 {
+            let swing = if i % 2 == 0 { 100.0 } else { 103.0 };
+            let tech = 203.0 - swing;
+            for (symbol, close) in [
+                ("XOM", swing),
+                ("CVX", swing),
+                ("COP", swing),
+                ("AAPL", tech),
+                ("MSFT", tech),
+            ] {
+                let bar = trading_core::types::Bar::new(i, close, close, close, close, 1e3);
+                correlations.update(symbol, &bar);
+            }
+        }
+
+        let limits = PortfolioLimits {
+            max_correlated_pct: Some(dec!(20)),
+            ..Default::default()
+        };
+        let check = |symbol| {
+            limits.check_new_position_correlated(
+                &portfolio,
+                symbol,
+                dec!(5000),
+                Decimal::ZERO,
+                &correlations,
+            )
+        };
+        // 16% in XOM and CVX leaves 4% for more oil; 8% in tech is fine
+        match check("COP") {
+            LimitCheck::Reduced { max_size, reason } => {
+                assert_eq!(max_size, dec!(4000));
+                assert!(reason.contains("correlated"));
+            }
+            other => panic!("Expected Reduced, got {:?}", other),
+        }
+        assert!(matches!(check("MSFT"), LimitCheck::Allowed));
+        assert!(matches!(
+            limits.check_new_position_for(&portfolio, "COP", dec!(5000), Decimal::ZERO),
+            LimitCheck::Allowed
+        ));
+    }
+
+    #[test]
+    fn test_net_exposure() {
+        let mut portfolio = create_portfolio(dec!(100000), dec!(80000), 0);
+        for (symbol, quantity) in [("AAPL", dec!(300)), ("MSFT", dec!(-100))] {
+            let pos = trading_core::types::Position::new(symbol, quantity, dec!(100));
+            portfolio.positions.insert(symbol.to_string(), pos);
+        }
+        assert_eq!(portfolio.gross_exposure(), dec!(40000));
+        assert_eq!(portfolio.net_exposure(), dec!(20000));
+
+        let limits = PortfolioLimits {
+            max_net_exposure_pct: Some(dec!(25)),
+            ..Default::default()
+        };
+        let correlations = CorrelationMatrix::new(10);
+        let check = |symbol, side, value| {
+            limits.check_trade(
+                &portfolio,
+                symbol,
+                side,
+                value,
+                Decimal::ZERO,
+                &correlations,
+            )
+        };
+        // 20% net long leaves 5% more long, but shorts only bring it down
+        match check("GOOG", Side::Buy, dec!(8000)) {
+            LimitCheck::Reduced { max_size, reason } => {
+                assert_eq!(max_size, dec!(5000));
+                assert!(reason.contains("net exposure"));
+            }
+            other => panic!("Expected Reduced, got {:?}", other),
+        }
+        assert!(matches!(
+            check("GOOG", Side::Sell, dec!(8000)),
+            LimitCheck::Allowed
+        ));
+        // Selling part of an oversized holding nets against it
+        assert!(matches!(
+            check("AAPL", Side::Sell, dec!(5000)),
+            LimitCheck::Allowed
+        ));
+        assert!(limits
+            .check_new_position_for(&portfolio, "AAPL", dec!(5000), Decimal::ZERO)
+            .is_blocked());
+    }
+
+    #[test]
+    fn test_asset_class_exposure() {
+        let mut portfolio = create_portfolio(dec!(100000), dec!(80000), 0);
+        for symbol in ["BTCUSD", "ETHUSD"] {
+            let mut pos = trading_core::types::Position::new(symbol, dec!(90), dec!(100));
+            pos.update_price(dec!(100));
+            portfolio.positions.insert(symbol.to_string(), pos);
+        }
+        let limits = PortfolioLimits {
+            max_asset_class_pct: Some(dec!(20)),
+            asset_classes: HashMap::from([
+                (
+                    "crypto".to_string(),
+                    vec![
+                        "BTCUSD".to_string(),
+                        "ETHUSD".to_string(),
+                        "SOLUSD".to_string(),
+                    ],
+                ),
+                ("equities".to_string(), vec!["AAPL".to_string()]),
+            ]),
+            ..Default::default()
+        };
+        assert_eq!(limits.asset_class_of("solusd"), Some("crypto"));
+
+        // 18% in crypto leaves 2%, equities are untouched
+        match limits.check_new_position_for(&portfolio, "SOLUSD", dec!(5000), Decimal::ZERO) {
+            LimitCheck::Reduced { max_size, reason } => {
+                assert_eq!(max_size, dec!(2000));
+                assert!(reason.contains("crypto"));
+            }
+            other => panic!("Expected Reduced, got {:?}", other),
+        }
+        assert!(matches!(
+            limits.check_new_position_for(&portfolio, "AAPL", dec!(5000), Decimal::ZERO),
+            LimitCheck::Allowed
+        ));
+    }
 }
@@ -22,6 +22,15 @@ pub enum PositionSizingMethod {
         win_rate: Decimal,
         avg_win_loss_ratio: Decimal,
     },
+    /// Volatility target: the position's annualized realized volatility
+    /// contributes this percentage of equity as portfolio volatility
+    VolTarget { annual_vol_pct: Decimal },
+    /// Kelly criterion estimated from each strategy's last `window` closed
+    /// trades, capped at `max_fraction_pct` of equity
+    RollingKelly {
+        window: usize,
+        max_fraction_pct: Decimal,
+    },
 }
 
 impl Default for PositionSizingMethod {
@@ -30,6 +39,105 @@ impl Default for PositionSizingMethod {
     }
 }
 
+impl std::fmt::Display for PositionSizingMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PositionSizingMethod::Fixed { shares } => write!(f, "fixed:{}", shares),
+            PositionSizingMethod::FixedDollar { amount } => write!(f, "fixed_dollar:{}", amount),
+            PositionSizingMethod::PercentEquity { percent } => {
+                write!(f, "percent_equity:{}%", percent)
+            }
+            PositionSizingMethod::RiskBased { risk_percent } => {
+                write!(f, "risk_based:{}%", risk_percent)
+            }
+            PositionSizingMethod::Kelly {
+                win_rate,
+                avg_win_loss_ratio,
+            } => write!(f, "kelly:{}:{}", win_rate, avg_win_loss_ratio),
+            PositionSizingMethod::VolTarget { annual_vol_pct } => {
+                write!(f, "vol_target:{}%", annual_vol_pct)
+            }
+            PositionSizingMethod::RollingKelly {
+                window,
+                max_fraction_pct,
+            } => write!(f, "rolling_kelly:{}:{}%", window, max_fraction_pct),
+        }
+    }
+}
+
+impl std::str::FromStr for PositionSizingMethod {
+    type Err = String;
+
+    /// Parse `fixed:100`, `fixed_dollar:5000`, `percent_equity:2%`,
+    /// `risk_based:1%`, `kelly:0.55:1.5`, `vol_target:10%` or
+    /// `rolling_kelly:50:25%`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(':');
+        let name = parts.next().unwrap_or_default().to_lowercase();
+        let values: Vec<Decimal> = parts
+            .map(|v| {
+                v.trim()
+                    .trim_end_matches('%')
+                    .parse()
+                    .map_err(|_| format!("Invalid number in sizing method: {}", v))
+            })
+            .collect::<Result<_, _>>()?;
+        let value = |n: usize| -> Result<Decimal, String> {
+            if values.len() == n {
+                Ok(values[0])
+            } else {
+                Err(format!("{} takes {} value(s), got: {}", name, n, s))
+            }
+        };
+        match name.as_str() {
+            "fixed" => Ok(PositionSizingMethod::Fixed { shares: value(1)? }),
+            "fixed_dollar" => Ok(PositionSizingMethod::FixedDollar { amount: value(1)? }),
+            "percent_equity" => Ok(PositionSizingMethod::PercentEquity { percent: value(1)? }),
+            "risk_based" => Ok(PositionSizingMethod::RiskBased {
+                risk_percent: value(1)?,
+            }),
+            "kelly" => Ok(PositionSizingMethod::Kelly {
+                win_rate: value(2)?,
+                avg_win_loss_ratio: values[1],
+            }),
+            "vol_target" => Ok(PositionSizingMethod::VolTarget {
+                annual_vol_pct: value(1)?,
+            }),
+            "rolling_kelly" => {
+                let window = value(2)?;
+                Ok(PositionSizingMethod::RollingKelly {
+                    window: usize::try_from(window)
+                        .ok()
+                        .filter(|w| *w > 0 && Decimal::from(*w) == window)
+                        .ok_or_else(|| format!("Invalid rolling Kelly window: {}", window))?,
+                    max_fraction_pct: values[1],
+                })
+            }
+            _ => Err(format!("Invalid position sizing method: {}", s)),
+        }
+    }
+}
+
+/// Kelly fraction `W - (1-W)/R` for win rate `W` and average win/loss
+/// ratio `R`, uncapped.
+pub(crate) fn kelly_fraction(win_rate: Decimal, avg_win_loss_ratio: Decimal) -> Decimal {
+    if avg_win_loss_ratio <= Decimal::ZERO {
+        return Decimal::ZERO;
+    }
+    win_rate - (dec!(1) - win_rate) / avg_win_loss_ratio
+}
+
+/// A calculated position size and the sizer limit that capped it, if any.
+#[derive(Debug, Clone)]
+pub struct SizeBreakdown {
+    /// Size the method asks for, after the signal strength adjustment
+    pub method_size: Decimal,
+    /// Final size in whole shares
+    pub size: Decimal,
+    /// Limit that reduced the size below `method_size`
+    pub limited_by: Option<String>,
+}
+
 /// Position sizer calculates the appropriate position size.
 #[derive(Debug, Clone)]
 pub struct PositionSizer {
@@ -76,8 +184,39 @@ impl PositionSizer {
         current_price: Decimal,
         stop_loss_price: Option<Decimal>,
     ) -> Decimal {
+        self.calculate_detailed(
+            portfolio,
+            signal,
+            current_price,
+            stop_loss_price,
+            None,
+            None,
+        )
+        .size
+    }
+
+    /// Calculate position size, reporting which limit capped it.
+    ///
+    /// `annual_volatility` is the symbol's annualized realized volatility
+    /// (0.2 for 20%), which volatility targeting needs; without it that
+    /// method sizes nothing. `rolling_kelly` is the Kelly fraction of the
+    /// strategy's recent trades; until there are enough of them rolling
+    /// Kelly sizes at a quarter of its cap.
+    pub fn calculate_detailed(
+        &self,
+        portfolio: &Portfolio,
+        signal: &Signal,
+        current_price: Decimal,
+        stop_loss_price: Option<Decimal>,
+        annual_volatility: Option<Decimal>,
+        rolling_kelly: Option<Decimal>,
+    ) -> SizeBreakdown {
         if current_price <= Decimal::ZERO {
-            return Decimal::ZERO;
+            return SizeBreakdown {
+                method_size: Decimal::ZERO,
+                size: Decimal::ZERO,
+                limited_by: None,
+            };
         }
 
         let base_size = match &self.method {
@@ -110,14 +249,30 @@ impl PositionSizer {
                 win_rate,
                 avg_win_loss_ratio,
             } => {
-                // Kelly fraction = W - (1-W)/R
-                // where W = win rate, R = avg win/loss ratio
-                let kelly_fraction = *win_rate - (dec!(1) - *win_rate) / *avg_win_loss_ratio;
+                let kelly_fraction = kelly_fraction(*win_rate, *avg_win_loss_ratio);
                 let kelly_fraction = kelly_fraction.max(Decimal::ZERO).min(dec!(0.25)); // Cap at 25%
 
                 let position_value = portfolio.equity * kelly_fraction;
                 position_value / current_price
             }
+
+            PositionSizingMethod::VolTarget { annual_vol_pct } => match annual_volatility {
+                Some(volatility) if volatility > Decimal::ZERO => {
+                    let position_value =
+                        portfolio.equity * (*annual_vol_pct / dec!(100)) / volatility;
+                    position_value / current_price
+                }
+                _ => Decimal::ZERO,
+            },
+
+            PositionSizingMethod::RollingKelly {
+                max_fraction_pct, ..
+            } => {
+                let cap = *max_fraction_pct / dec!(100);
+                let fraction = rolling_kelly.unwrap_or(cap / dec!(4));
+                let position_value = portfolio.equity * fraction.max(Decimal::ZERO).min(cap);
+                position_value / current_price
+            }
         };
 
         // Apply signal strength multiplier
@@ -132,24 +287,39 @@ impl PositionSizer {
             base_size
         };
 
-        // Apply limits
+        // Apply limits, remembering the tightest
         let mut final_size = adjusted_size;
+        let mut limited_by = None;
+        let mut cap = |max: Decimal, reason: String| {
+            if max < final_size {
+                final_size = max;
+                limited_by = Some(reason);
+            }
+        };
 
         if let Some(max) = self.max_shares {
-            final_size = final_size.min(max);
+            cap(max, format!("max shares per order ({})", max));
         }
 
         if let Some(max_value) = self.max_position_value {
-            let max_shares = max_value / current_price;
-            final_size = final_size.min(max_shares);
+            cap(
+                max_value / current_price,
+                format!("max position value (${:.2})", max_value),
+            );
         }
 
         // Check buying power
-        let max_affordable = portfolio.buying_power / current_price;
-        final_size = final_size.min(max_affordable);
+        cap(
+            portfolio.buying_power / current_price,
+            format!("buying power (${:.2})", portfolio.buying_power),
+        );
 
         // Round down to whole shares
-        final_size.floor()
+        SizeBreakdown {
+            method_size: adjusted_size,
+            size: final_size.floor(),
+            limited_by,
+        }
     }
 }
 
@@ -172,6 +342,7 @@ mod tests {
             price: 100.0,
             timestamp: 0,
             confidence: 1.0,
+            fraction: None,
             metadata: SignalMetadata::default(),
         }
     }
@@ -242,6 +413,77 @@ mod tests {
         assert_eq!(size, dec!(100));
     }
 
+    #[test]
+    fn test_parse_method() {
+        let method: PositionSizingMethod = "risk_based:1%".parse().unwrap();
+        assert!(matches!(
+            method,
+            PositionSizingMethod::RiskBased { risk_percent } if risk_percent == dec!(1)
+        ));
+        assert_eq!(method.to_string(), "risk_based:1%");
+        assert!(matches!(
+            "kelly:0.55:1.5".parse(),
+            Ok(PositionSizingMethod::Kelly { win_rate, .. }) if win_rate == dec!(0.55)
+        ));
+        assert!("kelly:0.55".parse::<PositionSizingMethod>().is_err());
+        assert!("martingale:2".parse::<PositionSizingMethod>().is_err());
+    }
+
+    #[test]
+    fn test_vol_target() {
+        let sizer = PositionSizer::new(PositionSizingMethod::VolTarget {
+            annual_vol_pct: dec!(10),
+        })
+        .without_signal_strength();
+        let portfolio = create_portfolio(dec!(100000), dec!(100000));
+        let signal = create_signal();
+
+        // 10% of equity in volatility from a 40% vol stock = $25000
+        let breakdown =
+            sizer.calculate_detailed(&portfolio, &signal, dec!(100), None, Some(dec!(0.4)), None);
+        assert_eq!(breakdown.size, dec!(250));
+        // Half the volatility, twice the size
+        let breakdown =
+            sizer.calculate_detailed(&portfolio, &signal, dec!(100), None, Some(dec!(0.2)), None);
+        assert_eq!(breakdown.size, dec!(500));
+        // No estimate yet, no position
+        assert_eq!(
+            sizer.calculate(&portfolio, &signal, dec!(100), None),
+            dec!(0)
+        );
+
+        assert_eq!(
+            "vol_target:10%"
+                .parse::<PositionSizingMethod>()
+                .unwrap()
+                .to_string(),
+            "vol_target:10%"
+        );
+    }
+
+    #[test]
+    fn test_rolling_kelly() {
+        let method: PositionSizingMethod = "rolling_kelly:50:20%".parse().unwrap();
+        assert_eq!(method.to_string(), "rolling_kelly:50:20%");
+        assert!("rolling_kelly:2.5:20%"
+            .parse::<PositionSizingMethod>()
+            .is_err());
+        let sizer = PositionSizer::new(method).without_signal_strength();
+        let portfolio = create_portfolio(dec!(100000), dec!(100000));
+        let signal = create_signal();
+        let size = |kelly| {
+            sizer
+                .calculate_detailed(&portfolio, &signal, dec!(100), None, None, kelly)
+                .size
+        };
+
+        // A quarter of the cap without history, then the capped estimate
+        assert_eq!(size(None), dec!(50));
+        assert_eq!(size(Some(dec!(0.1))), dec!(100));
+        assert_eq!(size(Some(dec!(0.6))), dec!(200));
+        assert_eq!(size(Some(dec!(-0.2))), dec!(0));
+    }
+
     #[test]
     fn test_buying_power_limit() {
         let sizer = PositionSizer::new(PositionSizingMethod::Fixed { shares: dec!(1000) })
@@ -251,5 +493,9 @@ mod tests {
 
         let size = sizer.calculate(&portfolio, &signal, dec!(100), None);
         assert_eq!(size, dec!(50)); // Can only afford 50 shares
+
+        let breakdown = sizer.calculate_detailed(&portfolio, &signal, dec!(100), None, None, None);
+        assert_eq!(breakdown.method_size, dec!(1000));
+        assert!(breakdown.limited_by.unwrap().starts_with("buying power"));
     }
 }
@@ -0,0 +1,139 @@
+//! Named risk configurations.
+//!
+//! Each preset bundles sizing, stops and portfolio limits that fit
+//! together, so a tighter stop comes with smaller positions rather than
+//! one knob being turned on its own.
+
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+use crate::{PortfolioLimits, PositionSizingMethod, RiskConfig, StopLossMethod};
+
+/// Named risk configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskPreset {
+    /// Small risk per trade, few positions, early loss limits
+    Conservative,
+    /// The built-in defaults
+    #[default]
+    Moderate,
+    /// Larger risk per trade, fully invested, loose loss limits
+    Aggressive,
+}
+
+impl RiskPreset {
+    /// All presets, most cautious first.
+    pub const ALL: [RiskPreset; 3] = [
+        RiskPreset::Conservative,
+        RiskPreset::Moderate,
+        RiskPreset::Aggressive,
+    ];
+
+    /// The risk configuration this preset stands for.
+    pub fn config(self) -> RiskConfig {
+        match self {
+            RiskPreset::Conservative => RiskConfig {
+                position_sizing: PositionSizingMethod::PercentEquity { percent: dec!(1) },
+                stop_loss: StopLossMethod::FixedPercent { percent: dec!(1.5) },
+                limits: PortfolioLimits {
+                    max_position_pct: dec!(5),
+                    max_exposure_pct: dec!(50),
+                    max_positions: 5,
+                    daily_loss_limit_pct: dec!(1.5),
+                    max_drawdown_pct: dec!(10),
+                    min_cash: dec!(5000),
+                    max_concentration_pct: dec!(15),
+                    max_sector_pct: Some(dec!(25)),
+                    ..PortfolioLimits::default()
+                },
+                max_shares: Some(dec!(500)),
+                use_signal_strength: true,
+                ..RiskConfig::default()
+            },
+            RiskPreset::Moderate => RiskConfig::default(),
+            RiskPreset::Aggressive => RiskConfig {
+                position_sizing: PositionSizingMethod::PercentEquity { percent: dec!(5) },
+                stop_loss: StopLossMethod::FixedPercent { percent: dec!(4) },
+                limits: PortfolioLimits {
+                    max_position_pct: dec!(20),
+                    max_exposure_pct: dec!(100),
+                    max_positions: 20,
+                    daily_loss_limit_pct: dec!(6),
+                    max_drawdown_pct: dec!(35),
+                    min_cash: dec!(0),
+                    max_concentration_pct: dec!(40),
+                    ..PortfolioLimits::default()
+                },
+                max_shares: None,
+                use_signal_strength: false,
+                ..RiskConfig::default()
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for RiskPreset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RiskPreset::Conservative => write!(f, "conservative"),
+            RiskPreset::Moderate => write!(f, "moderate"),
+            RiskPreset::Aggressive => write!(f, "aggressive"),
+        }
+    }
+}
+
+impl std::str::FromStr for RiskPreset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "conservative" => Ok(RiskPreset::Conservative),
+            "moderate" => Ok(RiskPreset::Moderate),
+            "aggressive" => Ok(RiskPreset::Aggressive),
+            _ => Err(format!(
+                "Unknown risk preset: {} (expected conservative, moderate or aggressive)",
+                s
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RiskManager;
+    use rust_decimal::Decimal;
+    use trading_core::types::{Portfolio, Side};
+
+    #[test]
+    fn test_presets_order_by_risk() {
+        let portfolio = Portfolio::new(dec!(100000));
+        let quantities: Vec<Decimal> = RiskPreset::ALL
+            .iter()
+            .map(|preset| {
+                let manager = RiskManager::new(preset.config());
+                manager
+                    .preview_size(&portfolio, "AAPL", Side::Buy, dec!(100), None, None)
+                    .quantity
+            })
+            .collect();
+        assert!(quantities[0] < quantities[1]);
+        assert!(quantities[1] < quantities[2]);
+
+        for preset in RiskPreset::ALL {
+            let limits = preset.config().limits;
+            assert!(limits.max_position_pct <= limits.max_concentration_pct);
+            assert!(limits.max_position_pct <= limits.max_exposure_pct);
+        }
+    }
+
+    #[test]
+    fn test_parse_preset() {
+        for preset in RiskPreset::ALL {
+            assert_eq!(preset.to_string().parse::<RiskPreset>(), Ok(preset));
+        }
+        assert_eq!("Aggressive".parse(), Ok(RiskPreset::Aggressive));
+        assert!("reckless".parse::<RiskPreset>().is_err());
+    }
+}
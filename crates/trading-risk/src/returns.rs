@@ -0,0 +1,79 @@
+//! Rolling per-symbol return history.
+
+use std::collections::{HashMap, VecDeque};
+use trading_core::types::Bar;
+
+/// The latest close-to-close returns of every symbol it is fed bars for,
+/// keyed by bar timestamp so symbols can be lined up with each other.
+#[derive(Debug, Clone)]
+pub struct ReturnHistory {
+    window: usize,
+    last_close: HashMap<String, f64>,
+    returns: HashMap<String, VecDeque<(i64, f64)>>,
+}
+
+impl ReturnHistory {
+    /// Keep the last `window` returns of each symbol.
+    pub fn new(window: usize) -> Self {
+        Self {
+            window: window.max(1),
+            last_close: HashMap::new(),
+            returns: HashMap::new(),
+        }
+    }
+
+    /// Returns kept per symbol.
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Feed a symbol's next bar.
+    pub fn update(&mut self, symbol: &str, bar: &Bar) {
+        if let Some(prev) = self
+            .last_close
+            .insert(symbol.to_string(), bar.close)
+            .filter(|p| *p > 0.0 && bar.close > 0.0)
+        {
+            let returns = self.returns.entry(symbol.to_string()).or_default();
+            returns.push_back((bar.timestamp, bar.close / prev - 1.0));
+            if returns.len() > self.window {
+                returns.pop_front();
+            }
+        }
+    }
+
+    /// A symbol's returns with their bar timestamps, oldest first.
+    pub fn returns(&self, symbol: &str) -> Option<&VecDeque<(i64, f64)>> {
+        self.returns.get(symbol)
+    }
+
+    /// Returns of a portfolio holding `weights` (fractions of equity by
+    /// symbol, negative for shorts), over the bars every holding has a
+    /// return for. Empty if any holding has no history.
+    pub fn portfolio_returns(&self, weights: &[(&str, f64)]) -> Vec<f64> {
+        let Some(((first, _), rest)) = weights.split_first() else {
+            return Vec::new();
+        };
+        let Some(first_returns) = self.returns(first) else {
+            return Vec::new();
+        };
+        let others: Option<Vec<HashMap<i64, f64>>> = rest
+            .iter()
+            .map(|(symbol, _)| Some(self.returns(symbol)?.iter().copied().collect()))
+            .collect();
+        let Some(others) = others else {
+            return Vec::new();
+        };
+
+        first_returns
+            .iter()
+            .filter_map(|(timestamp, first_return)| {
+                let mut total = weights[0].1 * first_return;
+                for (other, (_, weight)) in others.iter().zip(rest) {
+                    total += weight * other.get(timestamp)?;
+                }
+                Some(total)
+            })
+            .collect()
+    }
+}
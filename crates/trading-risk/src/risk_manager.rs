@@ -3,11 +3,16 @@
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
-use trading_core::types::{OrderRequest, Portfolio, Side, Signal};
+use std::collections::{HashMap, VecDeque};
+use trading_analytics::{Regime, RegimeDetector};
+use trading_core::types::{Bar, OrderRequest, Portfolio, Side, Signal, SignalType};
+use trading_indicators::RollingStats;
 
+use crate::position_sizer::kelly_fraction;
 use crate::{
-    LimitCheck, PortfolioLimits, PositionSizer, PositionSizingMethod, StopLossManager,
-    StopLossMethod,
+    AllocatorConfig, CorrelationMatrix, LimitCheck, PortfolioLimits, PositionSizer,
+    PositionSizingMethod, ReturnHistory, StopLossManager, StopLossMethod, StrategyAllocator,
+    TakeProfitManager, TakeProfitMethod, TimeExit, VarEstimate,
 };
 
 /// Risk management configuration.
@@ -17,12 +22,30 @@ pub struct RiskConfig {
     pub position_sizing: PositionSizingMethod,
     /// Stop-loss method
     pub stop_loss: StopLossMethod,
+    /// Take-profit method; when set, entries are sent as bracket orders
+    /// with the stop and the target attached
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub take_profit: Option<TakeProfitMethod>,
     /// Portfolio limits
     pub limits: PortfolioLimits,
     /// Maximum shares per order
     pub max_shares: Option<Decimal>,
     /// Use signal strength for sizing
     pub use_signal_strength: bool,
+    /// Percentage of the normal size taken in a high-volatility regime
+    /// (100 disables regime detection)
+    #[serde(default = "default_high_volatility_size_pct")]
+    pub high_volatility_size_pct: Decimal,
+    /// Force-close positions after a holding period or at a time of day
+    #[serde(default)]
+    pub time_exit: TimeExit,
+    /// Divide capital across the strategies of a multi-strategy portfolio
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allocation: Option<AllocatorConfig>,
+}
+
+fn default_high_volatility_size_pct() -> Decimal {
+    dec!(100)
 }
 
 impl Default for RiskConfig {
@@ -30,10 +53,127 @@ impl Default for RiskConfig {
         Self {
             position_sizing: PositionSizingMethod::PercentEquity { percent: dec!(2) },
             stop_loss: StopLossMethod::FixedPercent { percent: dec!(2) },
+            take_profit: None,
             limits: PortfolioLimits::default(),
             max_shares: Some(dec!(1000)),
             use_signal_strength: true,
+            high_volatility_size_pct: default_high_volatility_size_pct(),
+            time_exit: TimeExit::default(),
+            allocation: None,
+        }
+    }
+}
+
+impl RiskConfig {
+    /// Whether sizing depends on the market regime.
+    pub fn regime_sizing(&self) -> bool {
+        self.high_volatility_size_pct != dec!(100)
+    }
+
+    /// Render the configuration as a text table.
+    pub fn render(&self) -> String {
+        let limits = &self.limits;
+        let mut s = String::new();
+        s.push_str("RISK CONFIGURATION\n");
+        s.push_str("───────────────────────────────────────────────────────────\n");
+        s.push_str(&format!(
+            "  Position Sizing:     {}\n",
+            self.position_sizing
+        ));
+        s.push_str(&format!(
+            "  Signal Strength:     {}\n",
+            if self.use_signal_strength {
+                "scales size"
+            } else {
+                "ignored"
+            }
+        ));
+        s.push_str(&format!("  Stop Loss:           {}\n", self.stop_loss));
+        if let Some(take_profit) = &self.take_profit {
+            s.push_str(&format!("  Take Profit:         {}\n", take_profit));
+        }
+        if self.regime_sizing() {
+            s.push_str(&format!(
+                "  High Volatility:     {}% of normal size\n",
+                self.high_volatility_size_pct
+            ));
+        }
+        if self.time_exit.is_enabled() {
+            s.push_str(&format!("  Time Exit:           {}\n", self.time_exit));
+        }
+        if let Some(allocation) = &self.allocation {
+            s.push_str(&format!("  Allocation:          {}\n", allocation));
+        }
+        s.push_str(&format!(
+            "  Max Shares:          {}\n",
+            self.max_shares
+                .map(|n| n.to_string())
+                .unwrap_or_else(|| "none".to_string())
+        ));
+        s.push_str(&format!(
+            "  Max Position:        {}%\n",
+            limits.max_position_pct
+        ));
+        s.push_str(&format!(
+            "  Max Concentration:   {}%\n",
+            limits.max_concentration_pct
+        ));
+        s.push_str(&format!(
+            "  Max Exposure:        {}%\n",
+            limits.max_exposure_pct
+        ));
+        if let Some(max_net_exposure_pct) = limits.max_net_exposure_pct {
+            s.push_str(&format!(
+                "  Max Net Exposure:    {}%\n",
+                max_net_exposure_pct
+            ));
+        }
+        if let Some(max_sector_pct) = limits.max_sector_pct {
+            let mut sectors: Vec<&str> = limits.sectors.keys().map(String::as_str).collect();
+            sectors.sort_unstable();
+            s.push_str(&format!(
+                "  Max Sector:          {}% ({})\n",
+                max_sector_pct,
+                if sectors.is_empty() {
+                    "no sectors mapped".to_string()
+                } else {
+                    sectors.join(", ")
+                }
+            ));
+        }
+        if let Some(max_asset_class_pct) = limits.max_asset_class_pct {
+            let mut classes: Vec<&str> = limits.asset_classes.keys().map(String::as_str).collect();
+            classes.sort_unstable();
+            s.push_str(&format!(
+                "  Max Asset Class:     {}% ({})\n",
+                max_asset_class_pct,
+                if classes.is_empty() {
+                    "no asset classes mapped".to_string()
+                } else {
+                    classes.join(", ")
+                }
+            ));
+        }
+        if let Some(max_correlated_pct) = limits.max_correlated_pct {
+            s.push_str(&format!(
+                "  Max Correlated:      {}% (correlation >= {} over {} bars)\n",
+                max_correlated_pct, limits.correlation_threshold, limits.correlation_window
+            ));
         }
+        s.push_str(&format!(
+            "  Max Positions:       {}\n",
+            limits.max_positions
+        ));
+        s.push_str(&format!("  Min Cash:            ${:.2}\n", limits.min_cash));
+        s.push_str(&format!(
+            "  Daily Loss Limit:    {}%\n",
+            limits.daily_loss_limit_pct
+        ));
+        s.push_str(&format!(
+            "  Max Drawdown:        {}%\n",
+            limits.max_drawdown_pct
+        ));
+        s
     }
 }
 
@@ -44,6 +184,7 @@ pub enum RiskDecision {
     Approved {
         order: OrderRequest,
         stop_loss_price: Option<Decimal>,
+        take_profit_price: Option<Decimal>,
     },
     /// Order rejected with reason
     Rejected { reason: String },
@@ -51,6 +192,7 @@ pub enum RiskDecision {
     Modified {
         order: OrderRequest,
         stop_loss_price: Option<Decimal>,
+        take_profit_price: Option<Decimal>,
         reason: String,
     },
 }
@@ -72,12 +214,236 @@ impl RiskDecision {
     }
 }
 
+/// Size a new position would get under the current settings, and why.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SizingPreview {
+    /// Symbol to trade
+    pub symbol: String,
+    /// Buy or sell
+    pub side: Side,
+    /// Entry price
+    pub price: Decimal,
+    /// Sizing method, e.g. `risk_based:1%`
+    pub method: String,
+    /// Stop-loss price, given or from the stop-loss method
+    pub stop_loss_price: Option<Decimal>,
+    /// Annualized volatility (0.2 for 20%), given or tracked, for
+    /// volatility targeting
+    pub annual_volatility: Option<Decimal>,
+    /// Size the sizing method asks for
+    pub method_quantity: Decimal,
+    /// Size after every limit
+    pub quantity: Decimal,
+    /// Limit that reduced the size, if any
+    pub binding_limit: Option<String>,
+    /// Why no order would be placed, if none would
+    pub rejected: Option<String>,
+    /// Account equity the preview was made for
+    pub equity: Decimal,
+}
+
+impl SizingPreview {
+    /// Order value.
+    pub fn notional(&self) -> Decimal {
+        self.quantity * self.price
+    }
+
+    /// Order value as a percentage of equity.
+    pub fn equity_pct(&self) -> Decimal {
+        pct_of(self.notional(), self.equity)
+    }
+
+    /// Loss if the stop is hit.
+    pub fn risk_amount(&self) -> Option<Decimal> {
+        self.stop_loss_price
+            .map(|stop| self.quantity * (self.price - stop).abs())
+    }
+
+    /// Render as text.
+    pub fn render(&self) -> String {
+        let mut s = String::new();
+        s.push_str("POSITION SIZE PREVIEW\n");
+        s.push_str("───────────────────────────────────────────────────────────\n");
+        s.push_str(&format!(
+            "  Order:               {} {}\n",
+            self.side, self.symbol
+        ));
+        s.push_str(&format!("  Method:              {}\n", self.method));
+        s.push_str(&format!("  Price:               ${:.2}\n", self.price));
+        if let Some(stop) = self.stop_loss_price {
+            s.push_str(&format!(
+                "  Stop:                ${:.2} ({:.2}% away)\n",
+                stop,
+                pct_of((self.price - stop).abs(), self.price)
+            ));
+        }
+        if let Some(volatility) = self.annual_volatility {
+            s.push_str(&format!(
+                "  Volatility:          {:.2}% annualized\n",
+                volatility * dec!(100)
+            ));
+        }
+        s.push_str(&format!(
+            "  Method Size:         {}\n",
+            self.method_quantity.round_dp(4).normalize()
+        ));
+        s.push_str(&format!("  Quantity:            {}\n", self.quantity));
+        s.push_str(&format!(
+            "  Notional:            ${:.2} ({:.2}% of ${:.2} equity)\n",
+            self.notional(),
+            self.equity_pct(),
+            self.equity
+        ));
+        if let Some(risk) = self.risk_amount() {
+            s.push_str(&format!(
+                "  Risk at Stop:        ${:.2} ({:.2}% of equity)\n",
+                risk,
+                pct_of(risk, self.equity)
+            ));
+        }
+        s.push_str(&format!(
+            "  Binding Limit:       {}\n",
+            self.binding_limit.as_deref().unwrap_or("none")
+        ));
+        if let Some(reason) = &self.rejected {
+            s.push_str(&format!("  Rejected:            {}\n", reason));
+        }
+        s
+    }
+}
+
+/// `part` as a percentage of `whole`.
+fn pct_of(part: Decimal, whole: Decimal) -> Decimal {
+    if whole > Decimal::ZERO {
+        part / whole * dec!(100)
+    } else {
+        Decimal::ZERO
+    }
+}
+
+/// Attach the stop and target to an entry as bracket exits, when there is
+/// a target to attach.
+fn bracket(
+    order: OrderRequest,
+    stop_loss_price: Option<Decimal>,
+    take_profit_price: Option<Decimal>,
+) -> OrderRequest {
+    let Some(target) = take_profit_price else {
+        return order;
+    };
+    let order = order.with_take_profit(target);
+    match stop_loss_price {
+        Some(stop) => order.with_stop_loss(stop),
+        None => order,
+    }
+}
+
+/// Bracket exits must sit on the protective and profitable sides of the
+/// entry (the limit price if any, otherwise the market).
+fn check_bracket(order: &OrderRequest, current_price: Decimal) -> Result<(), String> {
+    let entry = order.limit_price.unwrap_or(current_price);
+    let (below, above) = match order.side {
+        Side::Buy => ("stop loss", "take profit"),
+        Side::Sell => ("take profit", "stop loss"),
+    };
+    let (low, high) = match order.side {
+        Side::Buy => (order.stop_loss, order.take_profit),
+        Side::Sell => (order.take_profit, order.stop_loss),
+    };
+    if let Some(low) = low.filter(|p| *p >= entry) {
+        return Err(format!(
+            "{} {} at {} must be below the entry at {}",
+            order.symbol, below, low, entry
+        ));
+    }
+    if let Some(high) = high.filter(|p| *p <= entry) {
+        return Err(format!(
+            "{} {} at {} must be above the entry at {}",
+            order.symbol, above, high, entry
+        ));
+    }
+    Ok(())
+}
+
+/// Bars of close-to-close returns behind the realized volatility used by
+/// volatility targeting.
+const VOLATILITY_WINDOW: usize = 20;
+
+/// Realized volatility of a symbol's log returns.
+#[derive(Debug, Clone)]
+struct RealizedVolatility {
+    prev_close: Option<f64>,
+    returns: RollingStats,
+}
+
+impl RealizedVolatility {
+    fn new() -> Self {
+        Self {
+            prev_close: None,
+            returns: RollingStats::new(VOLATILITY_WINDOW),
+        }
+    }
+
+    fn update(&mut self, close: f64) {
+        if let Some(prev) = self.prev_close.filter(|p| *p > 0.0 && close > 0.0) {
+            self.returns.push((close / prev).ln());
+        }
+        self.prev_close = Some(close);
+    }
+
+    /// Standard deviation of returns per bar, once the window is full.
+    fn per_bar(&self) -> Option<f64> {
+        self.returns.is_full().then(|| self.returns.std_dev())
+    }
+}
+
+/// Closed trades a strategy needs before rolling Kelly sizing uses its own
+/// estimate (or the whole window, if shorter).
+const ROLLING_KELLY_MIN_TRADES: usize = 10;
+
+/// Kelly fraction of a run of trade P&Ls; a run without losses bets the
+/// most it may.
+fn rolling_kelly_fraction(outcomes: &VecDeque<Decimal>) -> Decimal {
+    let (wins, losses): (Vec<Decimal>, Vec<Decimal>) =
+        outcomes.iter().partition(|pnl| **pnl > Decimal::ZERO);
+    let total_loss: Decimal = losses.iter().map(|pnl| pnl.abs()).sum();
+    if wins.is_empty() {
+        return Decimal::ZERO;
+    }
+    if total_loss.is_zero() {
+        return Decimal::ONE;
+    }
+    let win_rate = Decimal::from(wins.len()) / Decimal::from(outcomes.len());
+    let avg_win = wins.iter().sum::<Decimal>() / Decimal::from(wins.len());
+    let avg_loss = total_loss / Decimal::from(losses.len());
+    kelly_fraction(win_rate, avg_win / avg_loss)
+}
+
+/// Bars of returns behind the portfolio Value-at-Risk.
+const VAR_WINDOW: usize = 250;
+
 /// Unified risk manager that combines position sizing, stop-loss, and limits.
 pub struct RiskManager {
     config: RiskConfig,
     position_sizer: PositionSizer,
     stop_loss_manager: StopLossManager,
+    take_profit_manager: Option<TakeProfitManager>,
     daily_pnl: Decimal,
+    /// Regime detectors by symbol, when sizing depends on the regime
+    regimes: HashMap<String, RegimeDetector>,
+    /// Realized volatility by symbol, when sizing targets volatility
+    volatility: HashMap<String, RealizedVolatility>,
+    /// Bars per year, for annualizing volatility
+    periods_per_year: f64,
+    /// P&L of recent closed trades by strategy, when sizing is rolling Kelly
+    trade_outcomes: HashMap<String, VecDeque<Decimal>>,
+    /// Return correlations between symbols, when correlated exposure is
+    /// limited
+    correlations: CorrelationMatrix,
+    /// Recent returns by symbol, for Value-at-Risk
+    returns: ReturnHistory,
+    /// Capital shares of the strategies, when allocated
+    allocator: Option<StrategyAllocator>,
 }
 
 impl RiskManager {
@@ -92,13 +458,51 @@ impl RiskManager {
         }
 
         let stop_loss_manager = StopLossManager::new(config.stop_loss.clone());
+        let take_profit_manager = config.take_profit.clone().map(TakeProfitManager::new);
+        let correlations = CorrelationMatrix::new(config.limits.correlation_window);
+        let allocator = config.allocation.clone().map(StrategyAllocator::new);
 
         Self {
             config,
             position_sizer,
             stop_loss_manager,
+            take_profit_manager,
             daily_pnl: Decimal::ZERO,
+            regimes: HashMap::new(),
+            volatility: HashMap::new(),
+            periods_per_year: 252.0,
+            trade_outcomes: HashMap::new(),
+            correlations,
+            returns: ReturnHistory::new(VAR_WINDOW),
+            allocator,
+        }
+    }
+
+    /// Set the number of bars per year used to annualize volatility
+    /// (252 for daily bars, the default).
+    pub fn with_periods_per_year(mut self, periods_per_year: f64) -> Self {
+        self.periods_per_year = periods_per_year;
+        self
+    }
+
+    /// Switch to a new configuration, keeping the daily P&L, closed trades
+    /// and market state (returns, regimes, volatility, and correlations and
+    /// allocation weights where their settings are unchanged) built up so
+    /// far.
+    pub fn reconfigure(&mut self, config: RiskConfig) {
+        let mut next = RiskManager::new(config).with_periods_per_year(self.periods_per_year);
+        next.daily_pnl = self.daily_pnl;
+        next.regimes = std::mem::take(&mut self.regimes);
+        next.volatility = std::mem::take(&mut self.volatility);
+        next.trade_outcomes = std::mem::take(&mut self.trade_outcomes);
+        std::mem::swap(&mut next.returns, &mut self.returns);
+        if next.config.limits.correlation_window == self.config.limits.correlation_window {
+            std::mem::swap(&mut next.correlations, &mut self.correlations);
         }
+        if next.config.allocation == self.config.allocation {
+            next.allocator = self.allocator.take();
+        }
+        *self = next;
     }
 
     /// Update the daily P&L tracking.
@@ -111,9 +515,177 @@ impl RiskManager {
         self.daily_pnl = Decimal::ZERO;
     }
 
-    /// Update ATR for stop-loss calculations.
+    /// Update ATR for stop-loss and take-profit calculations.
     pub fn update_atr(&mut self, atr: Decimal) {
         self.stop_loss_manager.update_atr(atr);
+        if let Some(manager) = &mut self.take_profit_manager {
+            manager.update_atr(atr);
+        }
+    }
+
+    /// Take-profit price for a new position, when a take-profit method is
+    /// configured. A target the strategy gives takes precedence if it is on
+    /// the profitable side of the entry.
+    fn take_profit_price(
+        &self,
+        signal: &Signal,
+        entry_price: Decimal,
+        side: Side,
+        stop_loss_price: Option<Decimal>,
+    ) -> Option<Decimal> {
+        let manager = self.take_profit_manager.as_ref()?;
+        signal
+            .metadata
+            .take_profit
+            .and_then(|p| Decimal::try_from(p).ok())
+            .filter(|p| match side {
+                Side::Buy => *p > entry_price,
+                Side::Sell => *p < entry_price,
+            })
+            .or_else(|| manager.calculate_target_price(entry_price, side, stop_loss_price))
+    }
+
+    /// Feed a bar to the symbol's return history, and to its regime
+    /// detector, volatility estimate, correlations and strategy allocation.
+    ///
+    /// The latter are only tracked when the configuration sizes or limits
+    /// by them.
+    pub fn on_bar(&mut self, symbol: &str, bar: &Bar) {
+        self.returns.update(symbol, bar);
+        if self.config.regime_sizing() {
+            self.regimes
+                .entry(symbol.to_string())
+                .or_default()
+                .update(bar);
+        }
+        if matches!(
+            self.config.position_sizing,
+            PositionSizingMethod::VolTarget { .. }
+        ) {
+            self.volatility
+                .entry(symbol.to_string())
+                .or_insert_with(RealizedVolatility::new)
+                .update(bar.close);
+        }
+        if self.config.limits.max_correlated_pct.is_some() {
+            self.correlations.update(symbol, bar);
+        }
+        if let Some(allocator) = &mut self.allocator {
+            allocator.on_bar(symbol, bar);
+        }
+    }
+
+    /// Record a strategy's signal, for allocating capital across
+    /// strategies. Call for every signal, before evaluating it.
+    pub fn record_signal(&mut self, signal: &Signal) {
+        if let Some(allocator) = &mut self.allocator {
+            allocator.record_signal(signal);
+        }
+    }
+
+    /// Share of capital allocated to a strategy, when capital is allocated
+    /// across strategies and it has signalled an entry.
+    pub fn allocation(&self, strategy: &str) -> Option<f64> {
+        self.allocator.as_ref()?.weight(strategy)
+    }
+
+    /// Get the strategy allocator, if enabled.
+    pub fn allocator(&self) -> Option<&StrategyAllocator> {
+        self.allocator.as_ref()
+    }
+
+    /// Return correlation between two symbols, if tracked and warmed up.
+    pub fn correlation(&self, a: &str, b: &str) -> Option<f64> {
+        self.correlations.correlation(a, b)
+    }
+
+    /// One-bar Value-at-Risk and Expected Shortfall of the portfolio's
+    /// current positions, as fractions of equity, from the returns its
+    /// symbols had over the last bars they share.
+    ///
+    /// `None` without positions or enough shared history.
+    pub fn portfolio_var(&self, portfolio: &Portfolio, confidence: f64) -> Option<VarEstimate> {
+        let equity = f64::try_from(portfolio.equity).ok().filter(|e| *e > 0.0)?;
+        let weights: Vec<(&str, f64)> = portfolio
+            .positions
+            .values()
+            .filter(|p| !p.is_flat())
+            .map(|p| {
+                let value = f64::try_from(p.market_value).unwrap_or_default();
+                let signed = if p.quantity < Decimal::ZERO {
+                    -value.abs()
+                } else {
+                    value.abs()
+                };
+                (p.symbol.as_str(), signed / equity)
+            })
+            .collect();
+        VarEstimate::from_returns(&self.returns.portfolio_returns(&weights), confidence)
+    }
+
+    /// Check trading `trade_value` of `symbol` against the portfolio limits.
+    fn check_limits(
+        &self,
+        portfolio: &Portfolio,
+        symbol: &str,
+        side: Side,
+        trade_value: Decimal,
+    ) -> LimitCheck {
+        self.config.limits.check_trade(
+            portfolio,
+            symbol,
+            side,
+            trade_value,
+            self.daily_pnl,
+            &self.correlations,
+        )
+    }
+
+    /// Why a position can't be sized without a volatility estimate, when
+    /// volatility targeting has none.
+    fn missing_volatility(&self, symbol: &str, volatility: Option<Decimal>) -> Option<String> {
+        let targets = matches!(
+            self.config.position_sizing,
+            PositionSizingMethod::VolTarget { .. }
+        );
+        (targets && volatility.is_none())
+            .then(|| format!("No volatility estimate for {} yet", symbol))
+    }
+
+    /// Annualized realized volatility of a symbol (0.2 for 20%), if
+    /// tracked and warmed up.
+    pub fn annual_volatility(&self, symbol: &str) -> Option<Decimal> {
+        let per_bar = self.volatility.get(symbol)?.per_bar()?;
+        Decimal::try_from(per_bar * self.periods_per_year.sqrt()).ok()
+    }
+
+    /// Record the realized P&L of a strategy's closed trade, for rolling
+    /// Kelly sizing.
+    pub fn record_trade(&mut self, strategy: &str, pnl: Decimal) {
+        let PositionSizingMethod::RollingKelly { window, .. } = self.config.position_sizing else {
+            return;
+        };
+        let outcomes = self.trade_outcomes.entry(strategy.to_string()).or_default();
+        outcomes.push_back(pnl);
+        while outcomes.len() > window {
+            outcomes.pop_front();
+        }
+    }
+
+    /// Kelly fraction of a strategy's recent closed trades, uncapped, once
+    /// it has enough of them for rolling Kelly sizing.
+    pub fn rolling_kelly(&self, strategy: &str) -> Option<Decimal> {
+        let PositionSizingMethod::RollingKelly { window, .. } = self.config.position_sizing else {
+            return None;
+        };
+        let outcomes = self.trade_outcomes.get(strategy)?;
+        (outcomes.len() >= ROLLING_KELLY_MIN_TRADES.min(window))
+            .then(|| rolling_kelly_fraction(outcomes))
+    }
+
+    /// Current market regime of a symbol, if tracked and warmed up.
+    pub fn regime(&self, symbol: &str) -> Option<Regime> {
+        self.regimes.get(symbol)?.current()
     }
 
     /// Evaluate a signal and produce a risk decision.
@@ -136,15 +708,55 @@ impl RiskManager {
             }
         };
 
-        // Calculate stop-loss price
+        // Calculate stop-loss and take-profit prices
         let stop_loss_price = self
             .stop_loss_manager
             .calculate_stop_price(current_price, side);
+        let take_profit_price =
+            self.take_profit_price(signal, current_price, side, stop_loss_price);
 
         // Calculate position size
-        let quantity =
-            self.position_sizer
-                .calculate(portfolio, signal, current_price, stop_loss_price);
+        let volatility = self.annual_volatility(&signal.symbol);
+        if let Some(reason) = self.missing_volatility(&signal.symbol, volatility) {
+            return RiskDecision::Rejected { reason };
+        }
+        let strategy = &signal.metadata.strategy_name;
+        let kelly = self.rolling_kelly(strategy);
+        if kelly.is_some_and(|k| k <= Decimal::ZERO) {
+            return RiskDecision::Rejected {
+                reason: format!("{} has no edge over its recent trades", strategy),
+            };
+        }
+        let mut quantity = self
+            .position_sizer
+            .calculate_detailed(
+                portfolio,
+                signal,
+                current_price,
+                stop_loss_price,
+                volatility,
+                kelly,
+            )
+            .size;
+        if self
+            .regime(&signal.symbol)
+            .is_some_and(|r| r.is_high_volatility())
+        {
+            quantity = (quantity * self.config.high_volatility_size_pct / dec!(100)).floor();
+        }
+        // A tranche of a scaled entry
+        if let Some(fraction) = signal.fraction.filter(|f| *f < 1.0) {
+            quantity = (quantity * Decimal::try_from(fraction).unwrap_or_default()).floor();
+        }
+        // The strategy's share of capital
+        if let Some(weight) = self.allocation(strategy) {
+            if weight <= 0.0 {
+                return RiskDecision::Rejected {
+                    reason: format!("{} has no capital allocated", strategy),
+                };
+            }
+            quantity = (quantity * Decimal::try_from(weight).unwrap_or_default()).floor();
+        }
 
         if quantity <= Decimal::ZERO {
             return RiskDecision::Rejected {
@@ -156,10 +768,7 @@ impl RiskManager {
         let position_value = quantity * current_price;
 
         // Check portfolio limits
-        let limit_check =
-            self.config
-                .limits
-                .check_new_position(portfolio, position_value, self.daily_pnl);
+        let limit_check = self.check_limits(portfolio, &signal.symbol, side, position_value);
 
         match limit_check {
             LimitCheck::Blocked { reason } => RiskDecision::Rejected { reason },
@@ -172,26 +781,202 @@ impl RiskManager {
                     };
                 }
 
-                let order = OrderRequest::market(&signal.symbol, side, reduced_quantity);
+                let order = bracket(
+                    OrderRequest::market(&signal.symbol, side, reduced_quantity),
+                    stop_loss_price,
+                    take_profit_price,
+                );
 
                 RiskDecision::Modified {
                     order,
                     stop_loss_price,
+                    take_profit_price,
                     reason,
                 }
             }
 
             LimitCheck::Allowed => {
-                let order = OrderRequest::market(&signal.symbol, side, quantity);
+                let order = bracket(
+                    OrderRequest::market(&signal.symbol, side, quantity),
+                    stop_loss_price,
+                    take_profit_price,
+                );
 
                 RiskDecision::Approved {
                     order,
                     stop_loss_price,
+                    take_profit_price,
                 }
             }
         }
     }
 
+    /// Preview the size of a new position without placing anything.
+    ///
+    /// Sizing and limits are applied as for a moderate-strength signal;
+    /// `stop_loss_price` overrides the configured stop-loss method and
+    /// `annual_volatility` the symbol's tracked volatility. Rolling Kelly
+    /// sizes as for a strategy without trade history.
+    pub fn preview_size(
+        &self,
+        portfolio: &Portfolio,
+        symbol: &str,
+        side: Side,
+        price: Decimal,
+        stop_loss_price: Option<Decimal>,
+        annual_volatility: Option<Decimal>,
+    ) -> SizingPreview {
+        let stop_loss_price =
+            stop_loss_price.or_else(|| self.stop_loss_manager.calculate_stop_price(price, side));
+        let annual_volatility = annual_volatility.or_else(|| self.annual_volatility(symbol));
+        let signal_type = match side {
+            Side::Buy => SignalType::Buy,
+            Side::Sell => SignalType::Sell,
+        };
+        let signal = Signal::new(symbol, signal_type, price.try_into().unwrap_or(0.0), 0);
+        let breakdown = self.position_sizer.calculate_detailed(
+            portfolio,
+            &signal,
+            price,
+            stop_loss_price,
+            annual_volatility,
+            None,
+        );
+
+        let mut preview = SizingPreview {
+            symbol: symbol.to_string(),
+            side,
+            price,
+            method: self.config.position_sizing.to_string(),
+            stop_loss_price,
+            annual_volatility,
+            method_quantity: breakdown.method_size,
+            quantity: breakdown.size,
+            binding_limit: breakdown.limited_by,
+            rejected: None,
+            equity: portfolio.equity,
+        };
+        if let Some(reason) = self.missing_volatility(symbol, annual_volatility) {
+            preview.rejected = Some(reason);
+            return preview;
+        }
+        if preview.quantity <= Decimal::ZERO {
+            preview.rejected = Some("Calculated position size is zero or negative".to_string());
+            return preview;
+        }
+
+        match self.check_limits(portfolio, symbol, side, preview.notional()) {
+            LimitCheck::Blocked { reason } => {
+                preview.quantity = Decimal::ZERO;
+                preview.rejected = Some(reason);
+            }
+            LimitCheck::Reduced { max_size, reason } => {
+                preview.quantity = (max_size / price).floor();
+                if preview.quantity <= Decimal::ZERO {
+                    preview.rejected =
+                        Some(format!("Position too small after reduction: {}", reason));
+                }
+                preview.binding_limit = Some(reason);
+            }
+            LimitCheck::Allowed => {}
+        }
+        preview
+    }
+
+    /// Check a manually entered order against the risk limits.
+    ///
+    /// Orders that only reduce an existing position are always allowed.
+    /// Other orders keep their type and prices but may have their quantity
+    /// reduced to fit the portfolio limits.
+    pub fn validate_order(
+        &self,
+        portfolio: &Portfolio,
+        order: &OrderRequest,
+        current_price: Decimal,
+    ) -> RiskDecision {
+        if order.quantity <= Decimal::ZERO {
+            return RiskDecision::Rejected {
+                reason: "Order quantity must be positive".to_string(),
+            };
+        }
+        if let Err(reason) = check_bracket(order, current_price) {
+            return RiskDecision::Rejected { reason };
+        }
+
+        // Reducing or closing a position never adds risk
+        if let Some(position) = portfolio.get_position(&order.symbol) {
+            let reduces = (position.is_long() && order.side == Side::Sell)
+                || (position.is_short() && order.side == Side::Buy);
+            if reduces && order.quantity <= position.quantity.abs() {
+                return RiskDecision::Approved {
+                    order: order.clone(),
+                    stop_loss_price: None,
+                    take_profit_price: None,
+                };
+            }
+        }
+
+        let stop_loss_price = order.stop_loss.or_else(|| {
+            self.stop_loss_manager
+                .calculate_stop_price(current_price, order.side)
+        });
+        let take_profit_price = order.take_profit.or_else(|| {
+            self.take_profit_manager.as_ref()?.calculate_target_price(
+                current_price,
+                order.side,
+                stop_loss_price,
+            )
+        });
+
+        let mut quantity = order.quantity;
+        let mut reason = None;
+        if let Some(max) = self.config.max_shares {
+            if quantity > max {
+                quantity = max;
+                reason = Some(format!("Limited by max shares per order ({})", max));
+            }
+        }
+
+        let limit_check = self.check_limits(
+            portfolio,
+            &order.symbol,
+            order.side,
+            quantity * current_price,
+        );
+        match limit_check {
+            LimitCheck::Blocked { reason } => return RiskDecision::Rejected { reason },
+            LimitCheck::Reduced {
+                max_size,
+                reason: limit_reason,
+            } => {
+                quantity = quantity.min((max_size / current_price).floor());
+                if quantity <= Decimal::ZERO {
+                    return RiskDecision::Rejected {
+                        reason: format!("Position too small after reduction: {}", limit_reason),
+                    };
+                }
+                reason = Some(limit_reason);
+            }
+            LimitCheck::Allowed => {}
+        }
+
+        let mut order = order.clone();
+        order.quantity = quantity;
+        match reason {
+            Some(reason) => RiskDecision::Modified {
+                order,
+                stop_loss_price,
+                take_profit_price,
+                reason,
+            },
+            None => RiskDecision::Approved {
+                order,
+                stop_loss_price,
+                take_profit_price,
+            },
+        }
+    }
+
     /// Check if trading should be halted.
     pub fn should_halt(&self, portfolio: &Portfolio) -> Option<String> {
         self.config
@@ -225,6 +1010,7 @@ mod tests {
             price: 100.0,
             timestamp: 0,
             confidence: 1.0,
+            fraction: None,
             metadata: SignalMetadata::default(),
         }
     }
@@ -242,15 +1028,250 @@ mod tests {
         if let RiskDecision::Approved {
             order,
             stop_loss_price,
+            take_profit_price,
         } = decision
         {
             assert_eq!(order.symbol, "TEST");
             assert_eq!(order.side, Side::Buy);
             assert!(order.quantity > Decimal::ZERO);
             assert!(stop_loss_price.is_some());
+            // No target, no bracket
+            assert!(take_profit_price.is_none());
+            assert!(!order.is_bracket());
         }
     }
 
+    #[test]
+    fn test_take_profit_emits_bracket() {
+        let manager = RiskManager::new(RiskConfig {
+            take_profit: Some(TakeProfitMethod::RMultiple { multiple: dec!(3) }),
+            ..Default::default()
+        });
+        let portfolio = create_portfolio();
+
+        // Three times the 2% stop distance
+        let decision =
+            manager.evaluate_signal(&portfolio, &create_signal(SignalType::Buy), dec!(100));
+        let order = decision.order().unwrap();
+        assert_eq!(order.stop_loss, Some(dec!(98)));
+        assert_eq!(order.take_profit, Some(dec!(106)));
+
+        // A strategy's own target wins when it is on the profitable side
+        let mut signal = create_signal(SignalType::Sell);
+        signal.metadata = SignalMetadata::default().with_take_profit(90.0);
+        let decision = manager.evaluate_signal(&portfolio, &signal, dec!(100));
+        assert!(matches!(
+            decision,
+            RiskDecision::Approved {
+                take_profit_price: Some(p),
+                ..
+            } if p == dec!(90)
+        ));
+        signal.metadata = SignalMetadata::default().with_take_profit(110.0);
+        let decision = manager.evaluate_signal(&portfolio, &signal, dec!(100));
+        assert_eq!(decision.order().unwrap().take_profit, Some(dec!(94)));
+    }
+
+    #[test]
+    fn test_high_volatility_regime_reduces_size() {
+        let mut manager = RiskManager::new(RiskConfig {
+            high_volatility_size_pct: dec!(50),
+            ..Default::default()
+        });
+        let mut unscaled = RiskManager::new(RiskConfig::default());
+        let portfolio = create_portfolio();
+        let signal = create_signal(SignalType::Buy);
+
+        // Calm alternating closes, then much wider swings
+        for i in 0..300 {
+            let swing = if i < 270 { 0.001 } else { 0.03 };
+            let close = if i % 2 == 0 {
+                100.0 + 100.0 * swing
+            } else {
+                100.0
+            };
+            let bar = Bar::new(i, close, close, close, close, 1000.0);
+            manager.on_bar("TEST", &bar);
+            unscaled.on_bar("TEST", &bar);
+        }
+        assert!(manager.regime("TEST").unwrap().is_high_volatility());
+        assert_eq!(unscaled.regime("TEST"), None);
+
+        let quantity = |m: &RiskManager| {
+            m.evaluate_signal(&portfolio, &signal, dec!(100))
+                .order()
+                .unwrap()
+                .quantity
+        };
+        assert_eq!(quantity(&unscaled), dec!(20));
+        assert_eq!(quantity(&manager), dec!(10));
+    }
+
+    #[test]
+    fn test_vol_target_sizing() {
+        let config = RiskConfig {
+            position_sizing: PositionSizingMethod::VolTarget {
+                annual_vol_pct: dec!(5),
+            },
+            max_shares: None,
+            use_signal_strength: false,
+            ..Default::default()
+        };
+        let mut daily = RiskManager::new(config.clone());
+        let mut quarterly = RiskManager::new(config).with_periods_per_year(63.0);
+        let portfolio = create_portfolio();
+        let signal = create_signal(SignalType::Buy);
+
+        assert!(matches!(
+            daily.evaluate_signal(&portfolio, &signal, dec!(100)),
+            RiskDecision::Rejected { .. }
+        ));
+
+        // Closes swinging 10% every bar
+        for i in 0..=VOLATILITY_WINDOW as i64 {
+            let close = if i % 2 == 0 { 100.0 } else { 110.0 };
+            let bar = Bar::new(i, close, close, close, close, 1000.0);
+            daily.on_bar("TEST", &bar);
+            quarterly.on_bar("TEST", &bar);
+        }
+        let volatility = daily.annual_volatility("TEST").unwrap();
+        assert!(volatility > dec!(1.5) && volatility < dec!(1.6));
+
+        // 5% of equity over a 155% volatility; a quarter of the bars per
+        // year halves the volatility, doubling the size
+        let quantity = |m: &RiskManager| {
+            m.evaluate_signal(&portfolio, &signal, dec!(100))
+                .order()
+                .unwrap()
+                .quantity
+        };
+        assert_eq!(
+            quantity(&daily),
+            (dec!(5000) / volatility / dec!(100)).floor()
+        );
+        let ratio = quantity(&quarterly) / quantity(&daily);
+        assert!(ratio > dec!(1.9) && ratio < dec!(2.1));
+    }
+
+    #[test]
+    fn test_rolling_kelly_sizing() {
+        let mut manager = RiskManager::new(RiskConfig {
+            position_sizing: PositionSizingMethod::RollingKelly {
+                window: 20,
+                max_fraction_pct: dec!(8),
+            },
+            max_shares: None,
+            use_signal_strength: false,
+            ..Default::default()
+        });
+        let portfolio = create_portfolio();
+        let mut signal = create_signal(SignalType::Buy);
+        signal.metadata.strategy_name = "trend".to_string();
+        let quantity = |m: &RiskManager| {
+            m.evaluate_signal(&portfolio, &signal, dec!(100))
+                .order()
+                .map(|o| o.quantity)
+        };
+
+        // A quarter of the 8% cap until ten trades have closed
+        assert_eq!(quantity(&manager), Some(dec!(20)));
+        for i in 0..9 {
+            manager.record_trade("trend", if i % 3 == 0 { dec!(-100) } else { dec!(150) });
+        }
+        assert_eq!(manager.rolling_kelly("trend"), None);
+        manager.record_trade("trend", dec!(150));
+
+        // Win rate 0.6 at 1.5:1 gives 0.6 - 0.4 / 1.5 = 33%, capped at 8%
+        assert!(manager.rolling_kelly("trend").unwrap() > dec!(0.33));
+        assert_eq!(quantity(&manager), Some(dec!(80)));
+        assert_eq!(manager.rolling_kelly("other"), None);
+
+        // Twenty straight losses push the wins out of the window
+        for _ in 0..20 {
+            manager.record_trade("trend", dec!(-50));
+        }
+        assert_eq!(manager.rolling_kelly("trend"), Some(dec!(0)));
+        assert_eq!(quantity(&manager), None);
+    }
+
+    #[test]
+    fn test_allocated_sizing() {
+        let mut manager = RiskManager::new(RiskConfig {
+            use_signal_strength: false,
+            allocation: Some(AllocatorConfig::default()),
+            ..Default::default()
+        });
+        let portfolio = create_portfolio();
+        let signal = |strategy: &str| {
+            let mut signal = create_signal(SignalType::Buy);
+            signal.metadata.strategy_name = strategy.to_string();
+            signal
+        };
+        let quantity = |m: &RiskManager, s: &Signal| {
+            m.evaluate_signal(&portfolio, s, dec!(100))
+                .order()
+                .map(|o| o.quantity)
+        };
+
+        // A lone strategy has all the capital; two split it
+        manager.record_signal(&signal("trend"));
+        assert_eq!(quantity(&manager, &signal("trend")), Some(dec!(20)));
+        manager.record_signal(&signal("reversion"));
+        assert_eq!(manager.allocation("trend"), Some(0.5));
+        assert_eq!(quantity(&manager, &signal("trend")), Some(dec!(10)));
+    }
+
+    #[test]
+    fn test_portfolio_var() {
+        let mut manager = RiskManager::new(RiskConfig::default());
+        let mut portfolio = create_portfolio();
+        for i in 0..=40 {
+            let close = if i % 2 == 0 { 100.0 } else { 102.0 };
+            manager.on_bar("TEST", &Bar::new(i, close, close, close, close, 1000.0));
+            manager.on_bar("HEDGE", &Bar::new(i, close, close, close, close, 1000.0));
+        }
+        assert_eq!(manager.portfolio_var(&portfolio, 0.95), None);
+
+        // 20% of equity in a stock losing 1.96% every other bar
+        let mut pos = trading_core::types::Position::new("TEST", dec!(200), dec!(100));
+        pos.update_price(dec!(100));
+        portfolio.positions.insert("TEST".to_string(), pos);
+        let var = manager.portfolio_var(&portfolio, 0.95).unwrap();
+        assert_eq!(var.observations, 40);
+        assert!((var.historical_var - 0.2 * 2.0 / 102.0).abs() < 1e-9);
+
+        // An equal short in a stock moving the same way cancels it out
+        let mut hedge = trading_core::types::Position::new("HEDGE", dec!(-200), dec!(100));
+        hedge.update_price(dec!(100));
+        portfolio.positions.insert("HEDGE".to_string(), hedge);
+        let var = manager.portfolio_var(&portfolio, 0.95).unwrap();
+        assert!(var.historical_var.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_entry_fraction_scales_size() {
+        let manager = RiskManager::new(RiskConfig::default());
+        let portfolio = create_portfolio();
+        let quantity = |signal: Signal| {
+            manager
+                .evaluate_signal(&portfolio, &signal, dec!(100))
+                .order()
+                .unwrap()
+                .quantity
+        };
+
+        let full = quantity(create_signal(SignalType::Buy));
+        assert_eq!(full, dec!(20));
+        assert_eq!(
+            quantity(create_signal(SignalType::Buy).with_fraction(0.25)),
+            dec!(5)
+        );
+        assert_eq!(
+            quantity(create_signal(SignalType::Buy).with_fraction(1.0)),
+            full
+        );
+    }
+
     #[test]
     fn test_hold_signal_rejected() {
         let config = RiskConfig::default();
@@ -277,4 +1298,96 @@ mod tests {
         // Should halt now
         assert!(manager.should_halt(&portfolio).is_some());
     }
+
+    #[test]
+    fn test_reconfigure_keeps_state() {
+        let mut manager = RiskManager::new(RiskConfig::default());
+        let portfolio = create_portfolio();
+        manager.update_daily_pnl(dec!(-2000));
+        assert!(manager.should_halt(&portfolio).is_none());
+
+        // A tighter limit applies to the loss already taken
+        let mut config = RiskConfig::default();
+        config.limits.daily_loss_limit_pct = dec!(1);
+        manager.reconfigure(config);
+        assert_eq!(manager.config().limits.daily_loss_limit_pct, dec!(1));
+        assert!(manager.should_halt(&portfolio).is_some());
+    }
+
+    #[test]
+    fn test_validate_manual_order() {
+        let manager = RiskManager::new(RiskConfig::default());
+        let mut portfolio = create_portfolio();
+
+        // Oversized limit order keeps its type but is cut to the position limit
+        let order = OrderRequest::limit("TEST", Side::Buy, dec!(500), dec!(100));
+        match manager.validate_order(&portfolio, &order, dec!(100)) {
+            RiskDecision::Modified { order, .. } => {
+                assert_eq!(order.quantity, dec!(100));
+                assert_eq!(order.limit_price, Some(dec!(100)));
+            }
+            other => panic!("expected modified order, got {:?}", other),
+        }
+
+        // Bracket exits are kept and must straddle the entry
+        let bracket = OrderRequest::limit("TEST", Side::Buy, dec!(10), dec!(100))
+            .with_stop_loss(dec!(95))
+            .with_take_profit(dec!(110));
+        let decision = manager.validate_order(&portfolio, &bracket, dec!(101));
+        assert!(decision.is_approved());
+        assert_eq!(decision.order().unwrap().take_profit, Some(dec!(110)));
+        let inverted = bracket.clone().with_stop_loss(dec!(105));
+        assert!(!manager
+            .validate_order(&portfolio, &inverted, dec!(101))
+            .is_approved());
+
+        // Hedging an existing position is always allowed
+        portfolio.positions.insert(
+            "TEST".to_string(),
+            trading_core::types::Position::new("TEST", dec!(800), dec!(100)),
+        );
+        let hedge = OrderRequest::market("TEST", Side::Sell, dec!(800));
+        let decision = manager.validate_order(&portfolio, &hedge, dec!(100));
+        assert_eq!(decision.order().unwrap().quantity, dec!(800));
+    }
+
+    #[test]
+    fn test_preview_size_reports_binding_limit() {
+        let manager = RiskManager::new(RiskConfig {
+            position_sizing: "risk_based:1%".parse().unwrap(),
+            ..Default::default()
+        });
+        let portfolio = create_portfolio();
+
+        // $1000 at risk over $4.30 is 232 shares, far above the 10% position cap
+        let preview = manager.preview_size(
+            &portfolio,
+            "AAPL",
+            Side::Buy,
+            dec!(182.3),
+            Some(dec!(178)),
+            None,
+        );
+        assert_eq!(preview.method_quantity.floor(), dec!(232));
+        assert_eq!(preview.quantity, dec!(54));
+        assert!(preview
+            .binding_limit
+            .as_deref()
+            .unwrap()
+            .contains("max position size"));
+        assert!(preview.rejected.is_none());
+
+        // A wide stop keeps the size within every limit
+        let preview = manager.preview_size(
+            &portfolio,
+            "AAPL",
+            Side::Buy,
+            dec!(182.3),
+            Some(dec!(100)),
+            None,
+        );
+        assert_eq!(preview.quantity, dec!(12));
+        assert!(preview.binding_limit.is_none());
+        assert_eq!(preview.risk_amount(), Some(dec!(12) * dec!(82.3)));
+    }
 }
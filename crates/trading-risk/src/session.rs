@@ -0,0 +1,63 @@
+//! End-of-session cleanup.
+//!
+//! Once a trading session ends, working day orders are canceled, positions
+//! optionally flattened and the daily risk counters reset, so nothing from
+//! one day's trading leaks into the next. Times of day are in UTC, like bar
+//! timestamps.
+
+use chrono::{DateTime, NaiveDate, NaiveTime};
+use serde::{Deserialize, Serialize};
+
+/// What happens at the end of each trading session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionConfig {
+    /// Time of day (UTC) the session ends; without one it ends when the
+    /// date changes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub close_at: Option<NaiveTime>,
+    /// Cancel working limit and stop orders, except good-til-canceled ones
+    /// such as protective stops
+    pub cancel_orders: bool,
+    /// Close every position, canceling every working order first
+    pub flatten: bool,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            close_at: None,
+            cancel_orders: true,
+            flatten: false,
+        }
+    }
+}
+
+impl SessionConfig {
+    /// Whether the session of `day` is over at `timestamp` (ms).
+    pub fn has_ended(&self, day: NaiveDate, timestamp: i64) -> bool {
+        let Some(now) = DateTime::from_timestamp_millis(timestamp) else {
+            return false;
+        };
+        now.date_naive() > day
+            || self
+                .close_at
+                .is_some_and(|close_at| now.date_naive() == day && now.time() >= close_at)
+    }
+}
+
+impl std::fmt::Display for SessionConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.close_at {
+            Some(close_at) => write!(f, "ends at {} UTC", close_at.format("%H:%M"))?,
+            None => write!(f, "ends at midnight UTC")?,
+        }
+        if self.cancel_orders {
+            write!(f, ", cancels day orders")?;
+        }
+        if self.flatten {
+            write!(f, ", flattens positions")?;
+        }
+        Ok(())
+    }
+}
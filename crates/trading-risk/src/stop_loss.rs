@@ -27,6 +27,20 @@ impl Default for StopLossMethod {
     }
 }
 
+impl std::fmt::Display for StopLossMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StopLossMethod::FixedPercent { percent } => write!(f, "fixed_percent:{}%", percent),
+            StopLossMethod::Atr { multiplier } => write!(f, "atr:{}x", multiplier),
+            StopLossMethod::FixedDollar { amount } => write!(f, "fixed_dollar:{}", amount),
+            StopLossMethod::TrailingPercent { percent } => {
+                write!(f, "trailing_percent:{}%", percent)
+            }
+            StopLossMethod::TrailingAtr { multiplier } => write!(f, "trailing_atr:{}x", multiplier),
+        }
+    }
+}
+
 /// A stop-loss order to be placed.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StopLossOrder {
@@ -0,0 +1,222 @@
+//! Performance-based strategy supervision.
+//!
+//! Disables a strategy's new entries after a losing streak or an outsized
+//! daily loss. Once tripped, the supervisor stays disabled until it is
+//! re-enabled manually.
+
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use trading_core::types::Signal;
+
+/// Supervisor configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SupervisorConfig {
+    /// Disable after this many losing trades in a row
+    pub max_consecutive_losses: Option<usize>,
+    /// Expected daily loss as percentage of equity
+    pub expected_daily_loss_pct: Option<Decimal>,
+    /// Disable when the daily loss exceeds this multiple of the expected loss
+    pub daily_loss_multiple: Decimal,
+    /// Close all positions when disabled
+    pub flatten_on_disable: bool,
+    /// Blacklist a symbol for the session after this many rejected orders
+    pub max_order_rejections: usize,
+}
+
+impl Default for SupervisorConfig {
+    fn default() -> Self {
+        Self {
+            max_consecutive_losses: Some(5),
+            expected_daily_loss_pct: Some(dec!(1)),
+            daily_loss_multiple: dec!(3),
+            flatten_on_disable: false,
+            max_order_rejections: 3,
+        }
+    }
+}
+
+/// Supervisor status.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SupervisorStatus {
+    /// Strategy may open new positions
+    Enabled,
+    /// New entries blocked until manually re-enabled
+    Disabled { reason: String },
+}
+
+/// Watches strategy performance and disables it on configured conditions.
+#[derive(Debug, Clone)]
+pub struct StrategySupervisor {
+    config: SupervisorConfig,
+    status: SupervisorStatus,
+    consecutive_losses: usize,
+    daily_pnl: Decimal,
+}
+
+impl StrategySupervisor {
+    /// Create a new supervisor.
+    pub fn new(config: SupervisorConfig) -> Self {
+        Self {
+            config,
+            status: SupervisorStatus::Enabled,
+            consecutive_losses: 0,
+            daily_pnl: Decimal::ZERO,
+        }
+    }
+
+    /// Record the realized P&L of a closed trade.
+    ///
+    /// Returns `true` if this trade disabled the strategy.
+    pub fn record_trade(&mut self, pnl: Decimal) -> bool {
+        if pnl < Decimal::ZERO {
+            self.consecutive_losses += 1;
+        } else if pnl > Decimal::ZERO {
+            self.consecutive_losses = 0;
+        }
+
+        match self.config.max_consecutive_losses {
+            Some(max) if self.consecutive_losses >= max => self.disable(format!(
+                "{} consecutive losing trades",
+                self.consecutive_losses
+            )),
+            _ => false,
+        }
+    }
+
+    /// Update today's P&L.
+    ///
+    /// Returns `true` if the loss disabled the strategy.
+    pub fn update_daily_pnl(&mut self, daily_pnl: Decimal, equity: Decimal) -> bool {
+        self.daily_pnl = daily_pnl;
+
+        let Some(expected_pct) = self.config.expected_daily_loss_pct else {
+            return false;
+        };
+        if equity <= Decimal::ZERO || daily_pnl >= Decimal::ZERO {
+            return false;
+        }
+
+        let loss_pct = -daily_pnl / equity * dec!(100);
+        let threshold = expected_pct * self.config.daily_loss_multiple;
+        if loss_pct > threshold {
+            self.disable(format!(
+                "Daily loss {:.2}% exceeds {}x expected ({:.2}%)",
+                loss_pct, self.config.daily_loss_multiple, expected_pct
+            ))
+        } else {
+            false
+        }
+    }
+
+    /// Reset daily tracking (call at start of each trading day).
+    pub fn reset_daily(&mut self) {
+        self.daily_pnl = Decimal::ZERO;
+    }
+
+    /// Check if a signal may be acted on.
+    ///
+    /// Exits are always allowed so a disabled strategy can still unwind.
+    pub fn allows(&self, signal: &Signal) -> bool {
+        self.is_enabled() || signal.signal_type.is_exit()
+    }
+
+    /// Check if new entries are allowed.
+    pub fn is_enabled(&self) -> bool {
+        self.status == SupervisorStatus::Enabled
+    }
+
+    /// Check if positions should be closed because the strategy was disabled.
+    pub fn should_flatten(&self) -> bool {
+        !self.is_enabled() && self.config.flatten_on_disable
+    }
+
+    /// Manually re-enable the strategy, clearing the losing streak.
+    pub fn enable(&mut self) {
+        self.status = SupervisorStatus::Enabled;
+        self.consecutive_losses = 0;
+    }
+
+    /// Current status.
+    pub fn status(&self) -> &SupervisorStatus {
+        &self.status
+    }
+
+    /// Today's P&L as last reported.
+    pub fn daily_pnl(&self) -> Decimal {
+        self.daily_pnl
+    }
+
+    /// Current losing streak.
+    pub fn consecutive_losses(&self) -> usize {
+        self.consecutive_losses
+    }
+
+    /// Get configuration.
+    pub fn config(&self) -> &SupervisorConfig {
+        &self.config
+    }
+
+    fn disable(&mut self, reason: String) -> bool {
+        if !self.is_enabled() {
+            return false;
+        }
+        warn!("Strategy disabled by supervisor: {}", reason);
+        self.status = SupervisorStatus::Disabled { reason };
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_consecutive_losses_disable() {
+        let mut supervisor = StrategySupervisor::new(SupervisorConfig::default());
+
+        for _ in 0..4 {
+            assert!(!supervisor.record_trade(dec!(-10)));
+        }
+        // A win resets the streak
+        supervisor.record_trade(dec!(5));
+        assert_eq!(supervisor.consecutive_losses(), 0);
+
+        for _ in 0..4 {
+            supervisor.record_trade(dec!(-10));
+        }
+        assert!(supervisor.record_trade(dec!(-10)));
+        assert!(!supervisor.is_enabled());
+
+        // Exits still allowed, entries blocked
+        let buy = Signal::buy("AAPL", 100.0, 0);
+        let close = Signal::close_long("AAPL", 100.0, 0);
+        assert!(!supervisor.allows(&buy));
+        assert!(supervisor.allows(&close));
+
+        // Stays disabled until manually enabled
+        supervisor.record_trade(dec!(50));
+        assert!(!supervisor.is_enabled());
+        supervisor.enable();
+        assert!(supervisor.allows(&buy));
+    }
+
+    #[test]
+    fn test_daily_loss_multiple() {
+        let mut supervisor = StrategySupervisor::new(SupervisorConfig {
+            flatten_on_disable: true,
+            ..Default::default()
+        });
+
+        // 2% loss is within 3x the 1% expectation
+        assert!(!supervisor.update_daily_pnl(dec!(-2000), dec!(100000)));
+        assert!(!supervisor.should_flatten());
+
+        // 3.5% loss trips it
+        assert!(supervisor.update_daily_pnl(dec!(-3500), dec!(100000)));
+        assert!(supervisor.should_flatten());
+    }
+}
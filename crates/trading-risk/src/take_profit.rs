@@ -0,0 +1,127 @@
+//! Take-profit management.
+
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use trading_core::types::Side;
+
+/// Take-profit calculation method.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TakeProfitMethod {
+    /// Fixed percentage above/below entry
+    FixedPercent { percent: Decimal },
+    /// Multiple of the ATR away from entry
+    Atr { multiplier: Decimal },
+    /// Multiple of the distance from entry to the stop loss
+    RMultiple { multiple: Decimal },
+}
+
+impl Default for TakeProfitMethod {
+    fn default() -> Self {
+        TakeProfitMethod::RMultiple { multiple: dec!(2) }
+    }
+}
+
+impl std::fmt::Display for TakeProfitMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TakeProfitMethod::FixedPercent { percent } => write!(f, "fixed_percent:{}%", percent),
+            TakeProfitMethod::Atr { multiplier } => write!(f, "atr:{}x", multiplier),
+            TakeProfitMethod::RMultiple { multiple } => write!(f, "r_multiple:{}R", multiple),
+        }
+    }
+}
+
+/// Take-profit manager.
+#[derive(Debug, Clone)]
+pub struct TakeProfitManager {
+    method: TakeProfitMethod,
+    current_atr: Option<Decimal>,
+}
+
+impl TakeProfitManager {
+    /// Create a new take-profit manager.
+    pub fn new(method: TakeProfitMethod) -> Self {
+        Self {
+            method,
+            current_atr: None,
+        }
+    }
+
+    /// Update the current ATR value (needed for ATR-based targets).
+    pub fn update_atr(&mut self, atr: Decimal) {
+        self.current_atr = Some(atr);
+    }
+
+    /// Calculate the take-profit price for a new position.
+    ///
+    /// R-multiple targets need the position's stop-loss price.
+    pub fn calculate_target_price(
+        &self,
+        entry_price: Decimal,
+        side: Side,
+        stop_loss_price: Option<Decimal>,
+    ) -> Option<Decimal> {
+        let offset = match &self.method {
+            TakeProfitMethod::FixedPercent { percent } => entry_price * (*percent / dec!(100)),
+            TakeProfitMethod::Atr { multiplier } => self.current_atr? * *multiplier,
+            TakeProfitMethod::RMultiple { multiple } => {
+                (entry_price - stop_loss_price?).abs() * *multiple
+            }
+        };
+        if offset <= Decimal::ZERO {
+            return None;
+        }
+        match side {
+            Side::Buy => Some(entry_price + offset), // Long: target above
+            Side::Sell => Some(entry_price - offset).filter(|p| *p > Decimal::ZERO), // Short: below
+        }
+    }
+
+    /// Check if the take-profit is reached.
+    pub fn is_triggered(&self, target_price: Decimal, current_price: Decimal, side: Side) -> bool {
+        match side {
+            Side::Buy => current_price >= target_price,
+            Side::Sell => current_price <= target_price,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_profit_methods() {
+        let fixed = TakeProfitManager::new(TakeProfitMethod::FixedPercent { percent: dec!(4) });
+        assert_eq!(
+            fixed.calculate_target_price(dec!(100), Side::Buy, None),
+            Some(dec!(104))
+        );
+        assert_eq!(
+            fixed.calculate_target_price(dec!(100), Side::Sell, None),
+            Some(dec!(96))
+        );
+
+        // Twice the risk to a stop at 95
+        let r = TakeProfitManager::new(TakeProfitMethod::RMultiple { multiple: dec!(2) });
+        assert_eq!(
+            r.calculate_target_price(dec!(100), Side::Buy, Some(dec!(95))),
+            Some(dec!(110))
+        );
+        assert_eq!(r.calculate_target_price(dec!(100), Side::Buy, None), None);
+
+        let mut atr = TakeProfitManager::new(TakeProfitMethod::Atr {
+            multiplier: dec!(3),
+        });
+        assert_eq!(atr.calculate_target_price(dec!(100), Side::Buy, None), None);
+        atr.update_atr(dec!(2));
+        let target = atr
+            .calculate_target_price(dec!(100), Side::Sell, None)
+            .unwrap();
+        assert_eq!(target, dec!(94));
+        assert!(atr.is_triggered(target, dec!(93.5), Side::Sell));
+        assert!(!atr.is_triggered(target, dec!(95), Side::Sell));
+    }
+}
@@ -0,0 +1,123 @@
+//! Time-based exits.
+//!
+//! Force-closes positions that have been held too long, and flattens
+//! everything at a set time of day so nothing is carried past the close.
+//! Times of day are in UTC, like bar timestamps.
+
+use chrono::{DateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+
+/// When to close positions regardless of what the strategy says.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimeExit {
+    /// Close a position once it has been held this many bars
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_bars: Option<usize>,
+    /// Close a position once it has been held this many calendar days
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_days: Option<u32>,
+    /// Close every position at or after this time of day (UTC), and open
+    /// no new ones until the next day
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flat_at: Option<NaiveTime>,
+}
+
+impl TimeExit {
+    /// Whether any time-based exit is configured.
+    pub fn is_enabled(&self) -> bool {
+        self.max_bars.is_some() || self.max_days.is_some() || self.flat_at.is_some()
+    }
+
+    /// Whether `timestamp` (ms) is past the flat time, when no new
+    /// positions may be opened.
+    pub fn past_flat_time(&self, timestamp: i64) -> bool {
+        match (self.flat_at, DateTime::from_timestamp_millis(timestamp)) {
+            (Some(flat_at), Some(time)) => time.time() >= flat_at,
+            _ => false,
+        }
+    }
+
+    /// Why a position opened at `opened_at` (ms) and held for `bars` bars
+    /// must be closed at `timestamp` (ms), if it must.
+    pub fn exit_reason(&self, opened_at: i64, bars: usize, timestamp: i64) -> Option<String> {
+        if let Some(max_bars) = self.max_bars {
+            if bars >= max_bars {
+                return Some(format!("Held {} bars (max {})", bars, max_bars));
+            }
+        }
+        if let Some(max_days) = self.max_days {
+            let days = (timestamp - opened_at) / 86_400_000;
+            if days >= i64::from(max_days) {
+                return Some(format!("Held {} days (max {})", days, max_days));
+            }
+        }
+        if self.past_flat_time(timestamp) {
+            return self
+                .flat_at
+                .map(|flat_at| format!("Flat at {} UTC", flat_at.format("%H:%M")));
+        }
+        None
+    }
+}
+
+impl std::fmt::Display for TimeExit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(max_bars) = self.max_bars {
+            parts.push(format!("after {} bars", max_bars));
+        }
+        if let Some(max_days) = self.max_days {
+            parts.push(format!("after {} days", max_days));
+        }
+        if let Some(flat_at) = self.flat_at {
+            parts.push(format!("flat at {} UTC", flat_at.format("%H:%M")));
+        }
+        if parts.is_empty() {
+            write!(f, "none")
+        } else {
+            write!(f, "{}", parts.join(", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: i64 = 3_600_000;
+
+    #[test]
+    fn test_time_exit_reasons() {
+        let exit = TimeExit {
+            max_bars: Some(3),
+            max_days: Some(2),
+            flat_at: NaiveTime::from_hms_opt(19, 55, 0),
+        };
+        assert_eq!(
+            exit.to_string(),
+            "after 3 bars, after 2 days, flat at 19:55 UTC"
+        );
+
+        // Opened at 14:00 on day one
+        let opened = 14 * HOUR;
+        assert!(exit.exit_reason(opened, 2, 15 * HOUR).is_none());
+        assert!(exit
+            .exit_reason(opened, 3, 15 * HOUR)
+            .unwrap()
+            .contains("3 bars"));
+        assert!(exit
+            .exit_reason(opened, 1, 20 * HOUR)
+            .unwrap()
+            .contains("Flat"));
+        assert!(exit
+            .exit_reason(opened, 1, 62 * HOUR)
+            .unwrap()
+            .contains("2 days"));
+
+        assert!(!exit.past_flat_time(19 * HOUR));
+        assert!(exit.past_flat_time(20 * HOUR));
+        assert!(!exit.past_flat_time(24 * HOUR + 10 * HOUR));
+        assert!(!TimeExit::default().is_enabled());
+    }
+}
@@ -0,0 +1,155 @@
+//! Value-at-Risk and Expected Shortfall.
+//!
+//! Both are one-bar losses as positive fractions of the portfolio value:
+//! VaR is the loss exceeded with probability `1 - confidence`, Expected
+//! Shortfall the average loss when it is. Historical estimates take the
+//! tail of the observed returns; parametric ones fit a normal distribution
+//! to them.
+
+use serde::{Deserialize, Serialize};
+
+/// Returns needed before VaR is estimated.
+pub const MIN_VAR_OBSERVATIONS: usize = 20;
+
+/// VaR and Expected Shortfall estimated from a return series.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct VarEstimate {
+    /// Confidence level, e.g. 0.95
+    pub confidence: f64,
+    /// Returns the estimate is based on
+    pub observations: usize,
+    /// Historical VaR
+    pub historical_var: f64,
+    /// Historical Expected Shortfall
+    pub historical_es: f64,
+    /// Parametric (normal) VaR
+    pub parametric_var: f64,
+    /// Parametric (normal) Expected Shortfall
+    pub parametric_es: f64,
+}
+
+impl VarEstimate {
+    /// Estimate from per-bar returns (0.01 for 1%), once there are at
+    /// least [`MIN_VAR_OBSERVATIONS`] of them. Gains in the tail show as
+    /// zero loss.
+    pub fn from_returns(returns: &[f64], confidence: f64) -> Option<Self> {
+        if returns.len() < MIN_VAR_OBSERVATIONS || !(0.5..1.0).contains(&confidence) {
+            return None;
+        }
+        let n = returns.len() as f64;
+
+        // Historical: the worst (1 - confidence) of the returns
+        let mut sorted = returns.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let tail = ((n * (1.0 - confidence)).ceil() as usize).clamp(1, sorted.len());
+        let historical_var = -sorted[tail - 1];
+        let historical_es = -sorted[..tail].iter().sum::<f64>() / tail as f64;
+
+        // Parametric: normal with the sample mean and standard deviation
+        let mean = returns.iter().sum::<f64>() / n;
+        let std_dev = (returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt();
+        let z = normal_quantile(confidence);
+        let parametric_var = z * std_dev - mean;
+        let parametric_es = std_dev * normal_pdf(z) / (1.0 - confidence) - mean;
+
+        Some(Self {
+            confidence,
+            observations: returns.len(),
+            historical_var: loss(historical_var),
+            historical_es: loss(historical_es),
+            parametric_var: loss(parametric_var),
+            parametric_es: loss(parametric_es),
+        })
+    }
+}
+
+/// A loss clamped at zero, so gains (and -0.0) show as no loss.
+fn loss(value: f64) -> f64 {
+    if value > 0.0 {
+        value
+    } else {
+        0.0
+    }
+}
+
+fn normal_pdf(x: f64) -> f64 {
+    (-0.5 * x * x).exp() / (2.0 * std::f64::consts::PI).sqrt()
+}
+
+/// Inverse of the standard normal CDF (Acklam's rational approximation,
+/// accurate to about 1e-9).
+fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969_683_028_665_376e1,
+        2.209_460_984_245_205e2,
+        -2.759_285_104_469_687e2,
+        1.383_577_518_672_69e2,
+        -3.066_479_806_614_716e1,
+        2.506_628_277_459_239,
+    ];
+    const B: [f64; 5] = [
+        -5.447_609_879_822_406e1,
+        1.615_858_368_580_409e2,
+        -1.556_989_798_598_866e2,
+        6.680_131_188_771_972e1,
+        -1.328_068_155_288_572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784_894_002_430_293e-3,
+        -3.223_964_580_411_365e-1,
+        -2.400_758_277_161_838,
+        -2.549_732_539_343_734,
+        4.374_664_141_464_968,
+        2.938_163_982_698_783,
+    ];
+    const D: [f64; 4] = [
+        7.784_695_709_041_462e-3,
+        3.224_671_290_700_398e-1,
+        2.445_134_137_142_996,
+        3.754_408_661_907_416,
+    ];
+    const LOW: f64 = 0.024_25;
+
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+    if p < LOW {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p <= 1.0 - LOW {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    } else {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_var_estimates() {
+        assert!((normal_quantile(0.95) - 1.644_853_6).abs() < 1e-6);
+        assert!((normal_quantile(0.999) - 3.090_232_3).abs() < 1e-6);
+
+        // -10% .. +9% in 1% steps: the worst 10% is -10% and -9%
+        let returns: Vec<f64> = (-10..10).map(|i| f64::from(i) / 100.0).collect();
+        let estimate = VarEstimate::from_returns(&returns, 0.9).unwrap();
+        assert!((estimate.historical_var - 0.09).abs() < 1e-12);
+        assert!((estimate.historical_es - 0.095).abs() < 1e-12);
+        assert!(estimate.parametric_es > estimate.parametric_var);
+        assert!(estimate.parametric_var > 0.0);
+
+        assert!(VarEstimate::from_returns(&returns[..19], 0.95).is_none());
+        let gains = vec![0.01; 30];
+        assert_eq!(
+            VarEstimate::from_returns(&gains, 0.95)
+                .unwrap()
+                .historical_var,
+            0.0
+        );
+    }
+}
@@ -0,0 +1,48 @@
+//! Data feeds driving the runtime.
+
+use async_trait::async_trait;
+use trading_core::types::Bar;
+
+/// Source of bars for the runtime.
+///
+/// Each call yields the bars that became available since the previous call,
+/// or `None` once the feed is exhausted.
+#[async_trait]
+pub trait DataFeed: Send {
+    /// Wait for the next batch of `(symbol, bar)` pairs.
+    async fn next_bars(&mut self) -> Option<Vec<(String, Bar)>>;
+}
+
+/// Feed replaying historical bars grouped by timestamp.
+pub struct HistoricalFeed {
+    bars: std::vec::IntoIter<(String, Bar)>,
+    peeked: Option<(String, Bar)>,
+}
+
+impl HistoricalFeed {
+    /// Create a feed from `(symbol, bar)` pairs, in any order.
+    pub fn new(mut bars: Vec<(String, Bar)>) -> Self {
+        bars.sort_by(|a, b| (a.1.timestamp, &a.0).cmp(&(b.1.timestamp, &b.0)));
+        Self {
+            bars: bars.into_iter(),
+            peeked: None,
+        }
+    }
+}
+
+#[async_trait]
+impl DataFeed for HistoricalFeed {
+    async fn next_bars(&mut self) -> Option<Vec<(String, Bar)>> {
+        let first = self.peeked.take().or_else(|| self.bars.next())?;
+        let timestamp = first.1.timestamp;
+        let mut batch = vec![first];
+        for item in self.bars.by_ref() {
+            if item.1.timestamp != timestamp {
+                self.peeked = Some(item);
+                break;
+            }
+            batch.push(item);
+        }
+        Some(batch)
+    }
+}
@@ -0,0 +1,331 @@
+//! Append-only trade journal: every signal, risk decision, order and fill
+//! of a session as JSON lines, for post-mortem analysis.
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use trading_core::error::{TradingError, TradingResult};
+use trading_core::traits::StrategyOrders;
+use trading_core::types::{Bar, Order, OrderRequest, Signal};
+
+/// Who placed an order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderSource {
+    /// A strategy signal or strategy-built orders
+    Strategy,
+    /// The dashboard's order ticket
+    Manual,
+    /// Closing a position by hand
+    Close,
+}
+
+impl std::fmt::Display for OrderSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OrderSource::Strategy => write!(f, "strategy"),
+            OrderSource::Manual => write!(f, "manual"),
+            OrderSource::Close => write!(f, "close"),
+        }
+    }
+}
+
+/// Something that happened on the way from signal to fill.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JournalEvent {
+    /// A bar the strategy was fed, for replaying the session
+    Bar { symbol: String, bar: Bar },
+    /// The strategy signalled, with its reason and metadata
+    Signal { signal: Signal },
+    /// The strategy built its own orders
+    StrategyOrders { orders: StrategyOrders },
+    /// Risk management approved an order, possibly after changing it
+    Approved {
+        order: OrderRequest,
+        stop_loss_price: Option<Decimal>,
+        take_profit_price: Option<Decimal>,
+        /// Why risk management changed the order, if it did
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        modified: Option<String>,
+    },
+    /// The pipeline ignored a signal or order (duplicate, dry run, ...)
+    Skipped { symbol: String, reason: String },
+    /// Risk management blocked a signal or order
+    Rejected { symbol: String, reason: String },
+    /// The broker accepted an order
+    Order { order: Order, source: OrderSource },
+    /// Submitting an order failed
+    OrderFailed { symbol: String, error: String },
+    /// An order finished with (part of) its quantity filled
+    Fill { order: Order },
+}
+
+impl JournalEvent {
+    /// Names of the event types, as written to the journal.
+    pub const KINDS: [&'static str; 9] = [
+        "bar",
+        "signal",
+        "strategy_orders",
+        "approved",
+        "skipped",
+        "rejected",
+        "order",
+        "order_failed",
+        "fill",
+    ];
+
+    /// Event type, as written to the journal.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            JournalEvent::Bar { .. } => "bar",
+            JournalEvent::Signal { .. } => "signal",
+            JournalEvent::StrategyOrders { .. } => "strategy_orders",
+            JournalEvent::Approved { .. } => "approved",
+            JournalEvent::Skipped { .. } => "skipped",
+            JournalEvent::Rejected { .. } => "rejected",
+            JournalEvent::Order { .. } => "order",
+            JournalEvent::OrderFailed { .. } => "order_failed",
+            JournalEvent::Fill { .. } => "fill",
+        }
+    }
+
+    /// Symbols the event concerns.
+    pub fn symbols(&self) -> Vec<&str> {
+        match self {
+            JournalEvent::Signal { signal } => vec![&signal.symbol],
+            JournalEvent::StrategyOrders { orders } => {
+                orders.legs.iter().map(|leg| leg.symbol.as_str()).collect()
+            }
+            JournalEvent::Approved { order, .. } => vec![&order.symbol],
+            JournalEvent::Bar { symbol, .. }
+            | JournalEvent::Skipped { symbol, .. }
+            | JournalEvent::Rejected { symbol, .. }
+            | JournalEvent::OrderFailed { symbol, .. } => vec![symbol],
+            JournalEvent::Order { order, .. } | JournalEvent::Fill { order } => vec![&order.symbol],
+        }
+    }
+}
+
+impl std::fmt::Display for JournalEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JournalEvent::Bar { symbol, bar } => write!(
+                f,
+                "{} O {:.2} H {:.2} L {:.2} C {:.2} V {}",
+                symbol, bar.open, bar.high, bar.low, bar.close, bar.volume
+            ),
+            JournalEvent::Signal { signal } => write!(
+                f,
+                "{} {} @ ${:.2}: {}",
+                signal.signal_type, signal.symbol, signal.price, signal.metadata.reason
+            ),
+            JournalEvent::StrategyOrders { orders } => {
+                let legs: Vec<String> = orders
+                    .legs
+                    .iter()
+                    .map(|leg| format!("{} {} {}", leg.side, leg.quantity, leg.symbol))
+                    .collect();
+                write!(f, "{}: {}", legs.join(", "), orders.reason)
+            }
+            JournalEvent::Approved {
+                order, modified, ..
+            } => {
+                write!(f, "{} {} {}", order.side, order.quantity, order.symbol)?;
+                match modified {
+                    Some(reason) => write!(f, " (modified: {})", reason),
+                    None => Ok(()),
+                }
+            }
+            JournalEvent::Skipped { symbol, reason }
+            | JournalEvent::Rejected { symbol, reason } => {
+                write!(f, "{}: {}", symbol, reason)
+            }
+            JournalEvent::Order { order, source } => write!(
+                f,
+                "{} {} {} ({}, {})",
+                order.side, order.quantity, order.symbol, source, order.id
+            ),
+            JournalEvent::OrderFailed { symbol, error } => write!(f, "{}: {}", symbol, error),
+            JournalEvent::Fill { order } => {
+                write!(
+                    f,
+                    "{} {} {}",
+                    order.side, order.filled_quantity, order.symbol
+                )?;
+                if let Some(price) = order.filled_avg_price {
+                    write!(f, " @ ${:.2}", price)?;
+                }
+                write!(f, " ({})", order.id)
+            }
+        }
+    }
+}
+
+/// One line of the journal.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// Wall-clock time of the event, Unix milliseconds
+    pub timestamp: i64,
+    /// Strategy running at the time
+    pub strategy: String,
+    #[serde(flatten)]
+    pub event: JournalEvent,
+}
+
+/// Filter over journal entries; unset fields match everything.
+#[derive(Debug, Clone, Default)]
+pub struct JournalQuery {
+    pub symbol: Option<String>,
+    /// Event type, one of [`JournalEvent::KINDS`]
+    pub kind: Option<String>,
+    /// Earliest timestamp (ms), inclusive
+    pub since: Option<i64>,
+    /// Latest timestamp (ms), exclusive
+    pub until: Option<i64>,
+}
+
+impl JournalQuery {
+    /// Whether `entry` passes the filter.
+    pub fn matches(&self, entry: &JournalEntry) -> bool {
+        self.symbol.as_ref().map_or(true, |symbol| {
+            entry
+                .event
+                .symbols()
+                .iter()
+                .any(|s| s.eq_ignore_ascii_case(symbol))
+        }) && self
+            .kind
+            .as_ref()
+            .map_or(true, |kind| entry.event.kind() == kind)
+            && self.since.map_or(true, |since| entry.timestamp >= since)
+            && self.until.map_or(true, |until| entry.timestamp < until)
+    }
+}
+
+/// Journal file that entries are appended to, one JSON object per line.
+#[derive(Debug)]
+pub struct TradeJournal {
+    path: PathBuf,
+    file: File,
+}
+
+impl TradeJournal {
+    /// Open the journal at `path` for appending, creating it and its
+    /// directory if needed.
+    pub fn open(path: impl AsRef<Path>) -> TradingResult<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        Ok(Self { path, file })
+    }
+
+    /// Path of the journal file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append an entry. Each entry is written with a single call, so a
+    /// crash never leaves half a line behind another.
+    pub fn append(&mut self, entry: &JournalEntry) -> TradingResult<()> {
+        let mut line =
+            serde_json::to_string(entry).map_err(|e| TradingError::Serialization(e.to_string()))?;
+        line.push('\n');
+        self.file.write_all(line.as_bytes())?;
+        Ok(())
+    }
+
+    /// Flush written entries to disk.
+    pub fn sync(&mut self) -> TradingResult<()> {
+        self.file.sync_all()?;
+        Ok(())
+    }
+
+    /// Read every entry of the journal at `path` matching `query`, oldest
+    /// first.
+    pub fn read(path: impl AsRef<Path>, query: &JournalQuery) -> TradingResult<Vec<JournalEntry>> {
+        let path = path.as_ref();
+        let reader = BufReader::new(File::open(path)?);
+        let mut entries = Vec::new();
+        for (number, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry: JournalEntry = serde_json::from_str(&line).map_err(|e| {
+                TradingError::Serialization(format!("{}:{}: {}", path.display(), number + 1, e))
+            })?;
+            if query.matches(&entry) {
+                entries.push(entry);
+            }
+        }
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use trading_core::types::{Side, SignalType};
+
+    #[test]
+    fn test_journal_round_trip_and_query() {
+        let path =
+            std::env::temp_dir().join(format!("trading-journal-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut journal = TradeJournal::open(&path).unwrap();
+        let signal = Signal::new("AAPL", SignalType::Buy, 150.0, 1_000);
+        let entries = [
+            (1_000, JournalEvent::Signal { signal }),
+            (
+                2_000,
+                JournalEvent::Approved {
+                    order: OrderRequest::market("AAPL", Side::Buy, dec!(10)),
+                    stop_loss_price: Some(dec!(145)),
+                    take_profit_price: None,
+                    modified: None,
+                },
+            ),
+            (
+                3_000,
+                JournalEvent::Rejected {
+                    symbol: "MSFT".to_string(),
+                    reason: "Max positions reached".to_string(),
+                },
+            ),
+        ];
+        for (timestamp, event) in entries {
+            journal
+                .append(&JournalEntry {
+                    timestamp,
+                    strategy: "MA Crossover".to_string(),
+                    event,
+                })
+                .unwrap();
+        }
+
+        let all = TradeJournal::read(&path, &JournalQuery::default()).unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[1].event.kind(), "approved");
+        assert_eq!(all[1].event.to_string(), "BUY 10 AAPL");
+
+        let aapl = JournalQuery {
+            symbol: Some("aapl".to_string()),
+            since: Some(1_500),
+            ..Default::default()
+        };
+        let found = TradeJournal::read(&path, &aapl).unwrap();
+        assert_eq!(found.len(), 1);
+        assert!(matches!(
+            &found[0].event,
+            JournalEvent::Approved { stop_loss_price: Some(stop), .. } if *stop == dec!(145)
+        ));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,16 @@
+//! Shared execution runtime.
+//!
+//! Backtest, paper and live trading all turn strategy signals into orders
+//! the same way: the [`OrderPipeline`] decides, and the [`TradingRuntime`]
+//! drives a strategy against any [`Broker`](trading_core::traits::Broker)
+//! and [`DataFeed`].
+
+mod feed;
+mod journal;
+mod pipeline;
+mod runtime;
+
+pub use feed::{DataFeed, HistoricalFeed};
+pub use journal::{JournalEntry, JournalEvent, JournalQuery, OrderSource, TradeJournal};
+pub use pipeline::{OrderPipeline, OrdersNotSent, PipelineDecision};
+pub use runtime::{Reload, SessionSummary, StepOutcome, TradingRuntime};
@@ -0,0 +1,1017 @@
+//! Signal-to-order decision pipeline.
+//!
+//! Every execution mode runs signals through the same gates, in order:
+//! blacklist, in-flight orders, duplicate entries/exits, the strategy
+//! supervisor, the circuit breaker, and finally the risk manager. Approved
+//! orders are then rounded to the instrument's lot and tick sizes. Orders a
+//! strategy builds itself take the same gates, but risk management
+//! validates rather than sizes them.
+
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use trading_core::error::BrokerError;
+use trading_core::traits::StrategyOrders;
+use trading_core::types::{
+    Bar, Instrument, Order, OrderRequest, Portfolio, Position, Side, Signal, SignalMetadata,
+    SignalType,
+};
+use trading_risk::{
+    CircuitBreaker, CircuitBreakerConfig, RiskDecision, RiskManager, StrategySupervisor,
+    SupervisorConfig, SymbolBlacklist,
+};
+
+/// Outcome of running a signal through the pipeline.
+#[derive(Debug, Clone)]
+pub enum PipelineDecision {
+    /// Signal ignored (duplicate, blacklisted, strategy disabled, trading
+    /// halted)
+    Skip { reason: String },
+    /// Signal blocked by risk management
+    Reject { reason: String },
+    /// Order ready to submit
+    Submit {
+        order: Box<OrderRequest>,
+        stop_loss_price: Option<Decimal>,
+        take_profit_price: Option<Decimal>,
+        /// Why risk management changed the order, if it did
+        modified: Option<String>,
+    },
+}
+
+impl PipelineDecision {
+    /// Get the order to submit, if any.
+    pub fn order(&self) -> Option<&OrderRequest> {
+        match self {
+            PipelineDecision::Submit { order, .. } => Some(order.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// Why a batch of strategy orders was not sent.
+#[derive(Debug, Clone)]
+pub struct OrdersNotSent {
+    /// Reason, naming the leg that failed
+    pub reason: String,
+    /// Blocked by risk management rather than skipped
+    pub rejected: bool,
+}
+
+impl OrdersNotSent {
+    fn skipped(reason: impl Into<String>) -> Self {
+        Self {
+            reason: reason.into(),
+            rejected: false,
+        }
+    }
+
+    fn rejected(reason: impl Into<String>) -> Self {
+        Self {
+            reason: reason.into(),
+            rejected: true,
+        }
+    }
+}
+
+/// Shared signal evaluation used by backtest, paper and live trading.
+pub struct OrderPipeline {
+    risk_manager: RiskManager,
+    supervisor: Option<StrategySupervisor>,
+    circuit_breaker: Option<CircuitBreaker>,
+    blacklist: SymbolBlacklist,
+    instruments: HashMap<String, Instrument>,
+    /// Highest equity seen across reconciled accounts
+    peak_equity: Decimal,
+    /// Holding periods of open positions, for time-based exits
+    holdings: HashMap<String, Holding>,
+}
+
+/// How long a position has been held.
+#[derive(Debug, Clone, Copy)]
+struct Holding {
+    long: bool,
+    /// Timestamp (ms) of the first bar the position was seen on
+    opened_at: i64,
+    /// Bars seen with the position open
+    bars: usize,
+}
+
+impl OrderPipeline {
+    /// Create a pipeline around a risk manager.
+    pub fn new(risk_manager: RiskManager) -> Self {
+        Self {
+            risk_manager,
+            supervisor: None,
+            circuit_breaker: None,
+            blacklist: SymbolBlacklist::default(),
+            instruments: HashMap::new(),
+            peak_equity: Decimal::ZERO,
+            holdings: HashMap::new(),
+        }
+    }
+
+    /// Supervise strategy performance.
+    pub fn with_supervisor(mut self, config: SupervisorConfig) -> Self {
+        self.blacklist = SymbolBlacklist::new(config.max_order_rejections);
+        self.supervisor = Some(StrategySupervisor::new(config));
+        self
+    }
+
+    /// Halt trading when losses, broker errors, slippage or stale data
+    /// trip the circuit breaker.
+    pub fn with_circuit_breaker(mut self, config: CircuitBreakerConfig) -> Self {
+        self.circuit_breaker = Some(CircuitBreaker::new(config));
+        self
+    }
+
+    /// Set the symbol blacklist.
+    pub fn with_blacklist(mut self, blacklist: SymbolBlacklist) -> Self {
+        self.blacklist = blacklist;
+        self
+    }
+
+    /// Set lot and tick rules per symbol; symbols without rules are left
+    /// unrounded.
+    pub fn with_instruments(mut self, instruments: HashMap<String, Instrument>) -> Self {
+        self.instruments = instruments;
+        self
+    }
+
+    /// Take the broker's account as the portfolio to check limits against.
+    ///
+    /// Positions, cash and equity are the broker's, including positions
+    /// opened outside the system. Brokers keep no equity history, so the
+    /// peak used for the drawdown limit is carried across calls.
+    pub fn reconcile(&mut self, mut account: Portfolio) -> Portfolio {
+        self.peak_equity = self
+            .peak_equity
+            .max(account.peak_equity)
+            .max(account.equity);
+        account.peak_equity = self.peak_equity;
+        account
+    }
+
+    /// Feed a new bar to the risk manager's market state.
+    pub fn on_bar(&mut self, symbol: &str, bar: &Bar) {
+        self.risk_manager.on_bar(symbol, bar);
+    }
+
+    /// Record a strategy's signal before evaluating it, so capital can be
+    /// allocated across strategies.
+    pub fn record_signal(&mut self, signal: &Signal) {
+        self.risk_manager.record_signal(signal);
+    }
+
+    /// Whether the risk config force-closes positions on time.
+    pub fn has_time_exit(&self) -> bool {
+        self.risk_manager.config().time_exit.is_enabled()
+    }
+
+    /// Forced exit for `symbol` on a new bar, if its position has been held
+    /// too long or the bar is past the flat time.
+    ///
+    /// Call on every bar of every symbol, with the current portfolio, so
+    /// holding periods are counted.
+    pub fn time_exit(&mut self, portfolio: &Portfolio, symbol: &str, bar: &Bar) -> Option<Signal> {
+        let rules = &self.risk_manager.config().time_exit;
+        if !rules.is_enabled() {
+            return None;
+        }
+        let Some(position) = portfolio.get_position(symbol).filter(|p| !p.is_flat()) else {
+            self.holdings.remove(symbol);
+            return None;
+        };
+
+        let opened = Holding {
+            long: position.is_long(),
+            opened_at: bar.timestamp,
+            bars: 1,
+        };
+        let holding = self
+            .holdings
+            .entry(symbol.to_string())
+            .and_modify(|h| h.bars += 1)
+            .or_insert(opened);
+        // A reversal opens a new position
+        if holding.long != position.is_long() {
+            *holding = opened;
+        }
+
+        let reason = rules.exit_reason(holding.opened_at, holding.bars, bar.timestamp)?;
+        let signal = if holding.long {
+            Signal::close_long(symbol, bar.close, bar.timestamp)
+        } else {
+            Signal::close_short(symbol, bar.close, bar.timestamp)
+        };
+        Some(signal.with_metadata(SignalMetadata::new("Time Exit").with_reason(reason)))
+    }
+
+    /// Decide what to do with a signal.
+    ///
+    /// `pending` marks that an order for the symbol is already in flight.
+    pub fn evaluate(
+        &self,
+        portfolio: &Portfolio,
+        signal: &Signal,
+        price: Decimal,
+        pending: bool,
+    ) -> PipelineDecision {
+        let symbol = &signal.symbol;
+
+        if let Some(entry) = self.blacklist.get(symbol) {
+            return skip(format!("{} is blacklisted: {}", symbol, entry.reason));
+        }
+        if pending {
+            return skip(format!("Order for {} already in flight", symbol));
+        }
+
+        // Skip duplicate entries: don't buy if already holding, unless adding
+        // a tranche to a long; don't close if not holding
+        let position = portfolio.get_position(symbol).filter(|p| !p.is_flat());
+        let scales_in = signal.fraction.is_some() && position.is_some_and(|p| p.is_long());
+        match signal.signal_type {
+            SignalType::Hold => return skip("Hold signal"),
+            _ if signal.fraction() <= 0.0 => return skip("Signal acts on none of the position"),
+            SignalType::Buy if position.is_some() && !scales_in => {
+                return skip(format!("Already holding {}", symbol))
+            }
+            SignalType::Sell | SignalType::CloseLong | SignalType::CloseShort
+                if position.is_none() =>
+            {
+                return skip(format!("No {} position to close", symbol))
+            }
+            _ => {}
+        }
+
+        let opens = match signal.signal_type {
+            SignalType::Buy | SignalType::Sell => position.is_none() || scales_in,
+            _ => false,
+        };
+        let time_exit = &self.risk_manager.config().time_exit;
+        if opens && time_exit.past_flat_time(signal.timestamp) {
+            return skip(format!(
+                "No new positions after the flat time ({})",
+                time_exit
+            ));
+        }
+
+        if let Some(supervisor) = &self.supervisor {
+            if !supervisor.allows(signal) {
+                return skip(format!("Strategy disabled: {:?}", supervisor.status()));
+            }
+        }
+        if let Some(breaker) = &self.circuit_breaker {
+            if !breaker.allows(signal) {
+                return skip(format!("Trading halted: {}", breaker.status()));
+            }
+        }
+
+        // Exits close the position (or the signal's share of it) rather than
+        // a freshly sized amount
+        if let Some(position) = position {
+            let closes = match signal.signal_type {
+                SignalType::Sell | SignalType::CloseLong => position.is_long(),
+                SignalType::CloseShort => position.is_short(),
+                _ => false,
+            };
+            if closes {
+                let side = if position.is_long() {
+                    Side::Sell
+                } else {
+                    Side::Buy
+                };
+                let held = position.quantity.abs();
+                if signal.fraction() >= 1.0 {
+                    return PipelineDecision::Submit {
+                        order: Box::new(OrderRequest::market(symbol, side, held)),
+                        stop_loss_price: None,
+                        take_profit_price: None,
+                        modified: None,
+                    };
+                }
+                return self.partial_exit(symbol, side, held, signal.fraction(), price);
+            }
+        }
+
+        let mut decision = self.risk_manager.evaluate_signal(portfolio, signal, price);
+        // Sized by value; a contract is worth `multiplier` units of the price
+        if let Some(instrument) = self.instruments.get(symbol) {
+            if let RiskDecision::Approved { order, .. } | RiskDecision::Modified { order, .. } =
+                &mut decision
+            {
+                order.quantity = instrument.contracts(order.quantity);
+            }
+        }
+        self.round(decision, price)
+    }
+
+    /// Close `fraction` of a position of `held` units, rounded down to
+    /// whole units (or the symbol's lot size) so a partial exit never
+    /// closes more than asked.
+    fn partial_exit(
+        &self,
+        symbol: &str,
+        side: Side,
+        held: Decimal,
+        fraction: f64,
+        price: Decimal,
+    ) -> PipelineDecision {
+        let share = held * Decimal::try_from(fraction).unwrap_or_default();
+        let mut quantity = if held.fract().is_zero() {
+            share.floor()
+        } else {
+            share
+        };
+        if let Some(rules) = self.instruments.get(symbol) {
+            quantity = rules.round_quantity(quantity);
+        }
+        if quantity <= Decimal::ZERO {
+            return skip(format!(
+                "{:.0}% of the {} position rounds to nothing",
+                fraction * 100.0,
+                symbol
+            ));
+        }
+        self.round(
+            RiskDecision::Approved {
+                order: OrderRequest::market(symbol, side, quantity),
+                stop_loss_price: None,
+                take_profit_price: None,
+            },
+            price,
+        )
+    }
+
+    /// Decide what to do with a manually entered order.
+    ///
+    /// Manual orders skip the duplicate and supervisor gates (a disabled
+    /// strategy must not block a hedge) but are still subject to the
+    /// blacklist, the circuit breaker and risk limits.
+    pub fn evaluate_order(
+        &self,
+        portfolio: &Portfolio,
+        order: &OrderRequest,
+        price: Decimal,
+    ) -> PipelineDecision {
+        if let Some(entry) = self.blacklist.get(&order.symbol) {
+            return skip(format!("{} is blacklisted: {}", order.symbol, entry.reason));
+        }
+        if let Some(breaker) = &self.circuit_breaker {
+            if breaker.is_tripped() && adds_exposure(portfolio, order) {
+                return skip(format!("Trading halted: {}", breaker.status()));
+            }
+        }
+
+        self.round(
+            self.risk_manager.validate_order(portfolio, order, price),
+            price,
+        )
+    }
+
+    /// Decide what to do with orders a strategy built itself.
+    ///
+    /// Each leg passes the blacklist, in-flight, supervisor and circuit
+    /// breaker gates and is
+    /// validated against the portfolio as it would be once the earlier legs
+    /// filled. A single order may be resized like a manual one, but a leg
+    /// of a multi-leg batch must pass unchanged, since a resized leg would
+    /// unbalance the others. Returns the orders to submit, or why none are.
+    ///
+    /// `prices` holds the reference price per symbol; legs without one are
+    /// checked at their limit price. `pending` marks symbols with an order
+    /// in flight.
+    pub fn evaluate_orders(
+        &self,
+        portfolio: &Portfolio,
+        orders: &StrategyOrders,
+        prices: &HashMap<String, Decimal>,
+        pending: impl Fn(&str) -> bool,
+    ) -> Result<Vec<OrderRequest>, OrdersNotSent> {
+        if orders.legs.is_empty() {
+            return Err(OrdersNotSent::skipped("No orders"));
+        }
+        let multi_leg = orders.legs.len() > 1;
+        let mut after = portfolio.clone();
+        let mut approved = Vec::with_capacity(orders.legs.len());
+
+        for leg in &orders.legs {
+            let symbol = &leg.symbol;
+            if let Some(entry) = self.blacklist.get(symbol) {
+                return Err(OrdersNotSent::skipped(format!(
+                    "{} is blacklisted: {}",
+                    symbol, entry.reason
+                )));
+            }
+            if pending(symbol) {
+                return Err(OrdersNotSent::skipped(format!(
+                    "Order for {} already in flight",
+                    symbol
+                )));
+            }
+            if let Some(supervisor) = &self.supervisor {
+                if !supervisor.is_enabled() && adds_exposure(&after, leg) {
+                    return Err(OrdersNotSent::skipped(format!(
+                        "Strategy disabled: {:?}",
+                        supervisor.status()
+                    )));
+                }
+            }
+            if let Some(breaker) = &self.circuit_breaker {
+                if breaker.is_tripped() && adds_exposure(&after, leg) {
+                    return Err(OrdersNotSent::skipped(format!(
+                        "Trading halted: {}",
+                        breaker.status()
+                    )));
+                }
+            }
+            let Some(price) = prices.get(symbol).copied().or(leg.limit_price) else {
+                return Err(OrdersNotSent::rejected(format!("No price for {}", symbol)));
+            };
+
+            let decision = self.risk_manager.validate_order(&after, leg, price);
+            if let RiskDecision::Modified { reason, .. } = &decision {
+                if multi_leg {
+                    return Err(OrdersNotSent::rejected(format!(
+                        "{} leg would be resized: {}",
+                        symbol, reason
+                    )));
+                }
+            }
+            let order = match self.round(decision, price) {
+                PipelineDecision::Submit { order, .. } => *order,
+                PipelineDecision::Skip { reason } => return Err(OrdersNotSent::skipped(reason)),
+                PipelineDecision::Reject { reason } => return Err(OrdersNotSent::rejected(reason)),
+            };
+            if multi_leg && order.quantity != leg.quantity {
+                return Err(OrdersNotSent::rejected(format!(
+                    "{} leg does not fit the lot size",
+                    symbol
+                )));
+            }
+
+            simulate_fill(&mut after, &order, order.limit_price.unwrap_or(price));
+            approved.push(order);
+        }
+        Ok(approved)
+    }
+
+    /// Turn a risk decision into a pipeline decision, rounding approved
+    /// orders to the instrument's rules.
+    fn round(&self, decision: RiskDecision, price: Decimal) -> PipelineDecision {
+        let (mut order, stop_loss_price, take_profit_price, modified) = match decision {
+            RiskDecision::Rejected { reason } => return PipelineDecision::Reject { reason },
+            RiskDecision::Approved {
+                order,
+                stop_loss_price,
+                take_profit_price,
+            } => (order, stop_loss_price, take_profit_price, None),
+            RiskDecision::Modified {
+                order,
+                stop_loss_price,
+                take_profit_price,
+                reason,
+            } => (order, stop_loss_price, take_profit_price, Some(reason)),
+        };
+        let Some(rules) = self.instruments.get(&order.symbol) else {
+            return PipelineDecision::Submit {
+                order: Box::new(order),
+                stop_loss_price,
+                take_profit_price,
+                modified,
+            };
+        };
+        match rules.apply(&mut order, price) {
+            Ok(()) => PipelineDecision::Submit {
+                order: Box::new(order),
+                stop_loss_price: stop_loss_price.map(|p| rules.round_price(p)),
+                take_profit_price: take_profit_price.map(|p| rules.round_price(p)),
+                modified,
+            },
+            Err(reason) => PipelineDecision::Reject { reason },
+        }
+    }
+
+    /// Record the broker's response to a submitted order.
+    ///
+    /// Returns `true` if broker errors tripped the circuit breaker.
+    pub fn record_submission(&mut self, symbol: &str, result: &Result<Order, BrokerError>) -> bool {
+        // Rejections are the order's fault, not the broker's
+        let broker_ok = result.as_ref().err().map_or(true, |e| e.is_rejection());
+        match result {
+            Ok(_) => self.blacklist.record_success(symbol),
+            Err(BrokerError::OrderRejected(reason)) => {
+                self.blacklist.record_rejection(symbol, reason.clone());
+            }
+            Err(e) if e.is_rejection() => {
+                self.blacklist.record_rejection(symbol, e.to_string());
+            }
+            // Transient failures say nothing about the symbol
+            Err(_) => {}
+        }
+        self.record_broker_call(broker_ok)
+    }
+
+    /// Record whether a broker call succeeded.
+    ///
+    /// Returns `true` if the error rate tripped the circuit breaker.
+    pub fn record_broker_call(&mut self, ok: bool) -> bool {
+        self.circuit_breaker
+            .as_mut()
+            .is_some_and(|b| b.record_broker_call(ok))
+    }
+
+    /// Record a market order's fill against the price it was sized at.
+    ///
+    /// Returns `true` if the slippage tripped the circuit breaker.
+    pub fn record_fill(
+        &mut self,
+        side: Side,
+        expected_price: Decimal,
+        fill_price: Decimal,
+    ) -> bool {
+        self.circuit_breaker
+            .as_mut()
+            .is_some_and(|b| b.record_fill(side, expected_price, fill_price))
+    }
+
+    /// Record that market data as of `timestamp` (ms) arrived.
+    pub fn record_market_data(&mut self, timestamp: i64) {
+        if let Some(breaker) = &mut self.circuit_breaker {
+            breaker.record_market_data(timestamp);
+        }
+    }
+
+    /// Check the age of the latest market data at `now` (ms).
+    ///
+    /// Returns `true` if stale data tripped the circuit breaker.
+    pub fn check_market_data(&mut self, now: i64) -> bool {
+        self.circuit_breaker
+            .as_mut()
+            .is_some_and(|b| b.check_market_data(now))
+    }
+
+    /// Trip the circuit breaker by hand.
+    ///
+    /// Returns `true` if it was armed.
+    pub fn trip_circuit_breaker(&mut self, reason: impl Into<String>) -> bool {
+        self.circuit_breaker
+            .as_mut()
+            .is_some_and(|b| b.trip(reason))
+    }
+
+    /// Record the realized P&L of a strategy's closed trade.
+    ///
+    /// Returns `true` if the supervisor disabled the strategy or the losing
+    /// streak tripped the circuit breaker.
+    pub fn record_closed_trade(&mut self, strategy: &str, pnl: Decimal) -> bool {
+        self.risk_manager.record_trade(strategy, pnl);
+        let tripped = self
+            .circuit_breaker
+            .as_mut()
+            .is_some_and(|b| b.record_trade(pnl));
+        let disabled = self
+            .supervisor
+            .as_mut()
+            .is_some_and(|s| s.record_trade(pnl));
+        disabled || tripped
+    }
+
+    /// Update today's P&L.
+    ///
+    /// Returns `true` if the supervisor disabled the strategy.
+    pub fn update_daily_pnl(&mut self, daily_pnl: Decimal, equity: Decimal) -> bool {
+        self.risk_manager.update_daily_pnl(daily_pnl);
+        self.supervisor
+            .as_mut()
+            .is_some_and(|s| s.update_daily_pnl(daily_pnl, equity))
+    }
+
+    /// Reset daily tracking (call at start of each trading day).
+    pub fn reset_daily(&mut self) {
+        self.risk_manager.reset_daily_pnl();
+        if let Some(supervisor) = &mut self.supervisor {
+            supervisor.reset_daily();
+        }
+    }
+
+    /// Check if the supervisor or circuit breaker wants positions closed.
+    pub fn should_flatten(&self) -> bool {
+        self.supervisor.as_ref().is_some_and(|s| s.should_flatten())
+            || self
+                .circuit_breaker
+                .as_ref()
+                .is_some_and(|b| b.should_flatten())
+    }
+
+    /// Get the risk manager.
+    pub fn risk_manager(&self) -> &RiskManager {
+        &self.risk_manager
+    }
+
+    /// Get the risk manager mutably.
+    pub fn risk_manager_mut(&mut self) -> &mut RiskManager {
+        &mut self.risk_manager
+    }
+
+    /// Get the supervisor, if enabled.
+    pub fn supervisor(&self) -> Option<&StrategySupervisor> {
+        self.supervisor.as_ref()
+    }
+
+    /// Get the supervisor mutably, e.g. to re-enable the strategy.
+    pub fn supervisor_mut(&mut self) -> Option<&mut StrategySupervisor> {
+        self.supervisor.as_mut()
+    }
+
+    /// Get the circuit breaker, if enabled.
+    pub fn circuit_breaker(&self) -> Option<&CircuitBreaker> {
+        self.circuit_breaker.as_ref()
+    }
+
+    /// Get the circuit breaker mutably, e.g. to reset it.
+    pub fn circuit_breaker_mut(&mut self) -> Option<&mut CircuitBreaker> {
+        self.circuit_breaker.as_mut()
+    }
+
+    /// Get the symbol blacklist.
+    pub fn blacklist(&self) -> &SymbolBlacklist {
+        &self.blacklist
+    }
+}
+
+/// Whether an order opens or grows a position rather than reducing one.
+fn adds_exposure(portfolio: &Portfolio, order: &OrderRequest) -> bool {
+    match portfolio.get_position(&order.symbol) {
+        Some(p) if p.is_long() => order.side == Side::Buy || order.quantity > p.quantity,
+        Some(p) if p.is_short() => order.side == Side::Sell || order.quantity > -p.quantity,
+        _ => true,
+    }
+}
+
+/// Apply an order to a portfolio as if it filled at `price`.
+fn simulate_fill(portfolio: &mut Portfolio, order: &OrderRequest, price: Decimal) {
+    let value = order.quantity * price;
+    match order.side {
+        Side::Buy => portfolio.cash -= value,
+        Side::Sell => portfolio.cash += value,
+    }
+    let position = portfolio
+        .positions
+        .entry(order.symbol.clone())
+        .or_insert_with(|| Position::new(&order.symbol, Decimal::ZERO, Decimal::ZERO));
+    position.apply_fill(order.side, order.quantity, price);
+    position.update_price(price);
+    if position.is_flat() {
+        portfolio.positions.remove(&order.symbol);
+    }
+    portfolio.update_equity();
+}
+
+fn skip(reason: impl Into<String>) -> PipelineDecision {
+    PipelineDecision::Skip {
+        reason: reason.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use trading_core::types::AssetClass;
+    use trading_risk::{RiskConfig, TimeExit};
+
+    fn create_pipeline() -> OrderPipeline {
+        OrderPipeline::new(RiskManager::new(RiskConfig::default()))
+    }
+
+    fn create_portfolio(holding: Option<Decimal>) -> Portfolio {
+        let mut portfolio = Portfolio::new(dec!(100000));
+        if let Some(qty) = holding {
+            portfolio.positions.insert(
+                "TEST".to_string(),
+                Position::new("TEST".to_string(), qty, dec!(100)),
+            );
+        }
+        portfolio
+    }
+
+    #[test]
+    fn test_entry_goes_through_risk() {
+        let pipeline = create_pipeline();
+        let decision = pipeline.evaluate(
+            &create_portfolio(None),
+            &Signal::buy("TEST", 100.0, 0),
+            dec!(100),
+            false,
+        );
+        let order = decision.order().unwrap();
+        assert_eq!(order.side, Side::Buy);
+        assert!(order.quantity > Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_entries_sized_in_contracts() {
+        let portfolio = create_portfolio(None);
+        let signal = Signal::buy("TEST", 100.0, 0);
+        let shares = create_pipeline()
+            .evaluate(&portfolio, &signal, dec!(100), false)
+            .order()
+            .unwrap()
+            .quantity;
+
+        // The same value in contracts of 10 units each
+        let pipeline = create_pipeline().with_instruments(HashMap::from([(
+            "TEST".to_string(),
+            Instrument::new("TEST", AssetClass::Future).with_multiplier(dec!(10)),
+        )]));
+        let decision = pipeline.evaluate(&portfolio, &signal, dec!(100), false);
+        assert_eq!(decision.order().unwrap().quantity, shares / dec!(10));
+    }
+
+    #[test]
+    fn test_orders_rounded_to_instrument_rules() {
+        let pipeline = create_pipeline().with_instruments(HashMap::from([(
+            "TEST".to_string(),
+            Instrument::new("TEST", AssetClass::Equity)
+                .with_lot_size(dec!(1))
+                .with_tick_size(dec!(0.01))
+                .with_min_notional(dec!(500)),
+        )]));
+        let portfolio = create_portfolio(None);
+
+        let order = OrderRequest::limit("TEST", Side::Buy, dec!(10.7), dec!(100.004));
+        let decision = pipeline.evaluate_order(&portfolio, &order, dec!(100));
+        let rounded = decision.order().unwrap();
+        assert_eq!(rounded.quantity, dec!(10));
+        assert_eq!(rounded.limit_price, Some(dec!(100.00)));
+
+        let small = OrderRequest::market("TEST", Side::Buy, dec!(4));
+        assert!(matches!(
+            pipeline.evaluate_order(&portfolio, &small, dec!(100)),
+            PipelineDecision::Reject { .. }
+        ));
+    }
+
+    #[test]
+    fn test_duplicates_and_in_flight_skipped() {
+        let pipeline = create_pipeline();
+        let holding = create_portfolio(Some(dec!(10)));
+        let flat = create_portfolio(None);
+
+        let buy = Signal::buy("TEST", 100.0, 0);
+        let close = Signal::close_long("TEST", 100.0, 0);
+
+        assert!(matches!(
+            pipeline.evaluate(&holding, &buy, dec!(100), false),
+            PipelineDecision::Skip { .. }
+        ));
+        assert!(matches!(
+            pipeline.evaluate(&flat, &close, dec!(100), false),
+            PipelineDecision::Skip { .. }
+        ));
+        assert!(matches!(
+            pipeline.evaluate(&flat, &buy, dec!(100), true),
+            PipelineDecision::Skip { .. }
+        ));
+    }
+
+    #[test]
+    fn test_exit_closes_whole_position() {
+        let pipeline = create_pipeline();
+        let decision = pipeline.evaluate(
+            &create_portfolio(Some(dec!(37))),
+            &Signal::close_long("TEST", 100.0, 0),
+            dec!(100),
+            false,
+        );
+        let order = decision.order().unwrap();
+        assert_eq!(order.side, Side::Sell);
+        assert_eq!(order.quantity, dec!(37));
+    }
+
+    #[test]
+    fn test_scaling_in_and_partial_exits() {
+        let pipeline = create_pipeline();
+        let holding = create_portfolio(Some(dec!(37)));
+
+        // Tranches add to a long; a plain buy is still a duplicate
+        let tranche = Signal::buy("TEST", 100.0, 0).with_fraction(0.5);
+        let order = pipeline
+            .evaluate(&holding, &tranche, dec!(100), false)
+            .order()
+            .cloned()
+            .unwrap();
+        assert_eq!((order.side, order.quantity), (Side::Buy, dec!(10)));
+
+        // Partial exits round down to whole shares
+        let half = Signal::close_long("TEST", 100.0, 0).with_fraction(0.5);
+        let order = pipeline
+            .evaluate(&holding, &half, dec!(100), false)
+            .order()
+            .cloned()
+            .unwrap();
+        assert_eq!((order.side, order.quantity), (Side::Sell, dec!(18)));
+
+        let sliver = Signal::close_long("TEST", 100.0, 0).with_fraction(0.01);
+        assert!(matches!(
+            pipeline.evaluate(&holding, &sliver, dec!(100), false),
+            PipelineDecision::Skip { .. }
+        ));
+    }
+
+    #[test]
+    fn test_time_exits() {
+        const HOUR: i64 = 3_600_000;
+        let mut pipeline = OrderPipeline::new(RiskManager::new(RiskConfig {
+            time_exit: TimeExit {
+                max_bars: Some(3),
+                flat_at: chrono::NaiveTime::from_hms_opt(19, 55, 0),
+                ..Default::default()
+            },
+            ..Default::default()
+        }));
+        let bar = |hour: i64| Bar::new(hour * HOUR, 100.0, 101.0, 99.0, 100.0, 1000.0);
+        let flat = create_portfolio(None);
+        let holding = create_portfolio(Some(dec!(10)));
+
+        // Closed on the third bar held
+        assert!(pipeline.time_exit(&flat, "TEST", &bar(13)).is_none());
+        assert!(pipeline.time_exit(&holding, "TEST", &bar(14)).is_none());
+        assert!(pipeline.time_exit(&holding, "TEST", &bar(15)).is_none());
+        let exit = pipeline.time_exit(&holding, "TEST", &bar(16)).unwrap();
+        assert_eq!(exit.signal_type, SignalType::CloseLong);
+        assert!(matches!(
+            pipeline.evaluate(&holding, &exit, dec!(100), false),
+            PipelineDecision::Submit { .. }
+        ));
+
+        // A new position starts counting again, but is flattened at the
+        // flat time, after which nothing new is opened
+        assert!(pipeline.time_exit(&flat, "TEST", &bar(17)).is_none());
+        assert!(pipeline.time_exit(&holding, "TEST", &bar(18)).is_none());
+        assert!(pipeline.time_exit(&holding, "TEST", &bar(20)).is_some());
+        assert!(matches!(
+            pipeline.evaluate(
+                &flat,
+                &Signal::buy("TEST", 100.0, 20 * HOUR),
+                dec!(100),
+                false
+            ),
+            PipelineDecision::Skip { .. }
+        ));
+        assert!(pipeline
+            .evaluate(
+                &flat,
+                &Signal::buy("TEST", 100.0, 34 * HOUR),
+                dec!(100),
+                false
+            )
+            .order()
+            .is_some());
+    }
+
+    #[test]
+    fn test_blacklist_and_supervisor_gates() {
+        let mut pipeline = create_pipeline().with_supervisor(SupervisorConfig {
+            max_consecutive_losses: Some(1),
+            max_order_rejections: 1,
+            ..Default::default()
+        });
+        let flat = create_portfolio(None);
+
+        pipeline.record_submission(
+            "TEST",
+            &Err(BrokerError::OrderRejected("not shortable".into())),
+        );
+        assert!(matches!(
+            pipeline.evaluate(&flat, &Signal::buy("TEST", 100.0, 0), dec!(100), false),
+            PipelineDecision::Skip { .. }
+        ));
+
+        assert!(pipeline.record_closed_trade("test", dec!(-5)));
+        assert!(matches!(
+            pipeline.evaluate(&flat, &Signal::buy("OTHER", 100.0, 0), dec!(100), false),
+            PipelineDecision::Skip { .. }
+        ));
+    }
+
+    #[test]
+    fn test_circuit_breaker_halts_entries() {
+        let mut pipeline = create_pipeline().with_circuit_breaker(CircuitBreakerConfig {
+            max_error_rate_pct: Some(100.0),
+            error_window: 2,
+            ..Default::default()
+        });
+        let holding = create_portfolio(Some(dec!(10)));
+
+        // Rejections are not broker errors
+        let rejected = Err(BrokerError::OrderRejected("not shortable".into()));
+        assert!(!pipeline.record_submission("OTHER", &rejected));
+        assert!(!pipeline.record_submission("OTHER", &rejected));
+        assert!(!pipeline.should_flatten());
+
+        assert!(pipeline.trip_circuit_breaker("manual halt"));
+        assert!(pipeline.should_flatten());
+        assert!(matches!(
+            pipeline.evaluate(&holding, &Signal::buy("NEW", 100.0, 0), dec!(100), false),
+            PipelineDecision::Skip { .. }
+        ));
+        let buy = OrderRequest::market("TEST", Side::Buy, dec!(5));
+        assert!(matches!(
+            pipeline.evaluate_order(&holding, &buy, dec!(100)),
+            PipelineDecision::Skip { .. }
+        ));
+        // Positions can still be unwound
+        let close = Signal::close_long("TEST", 100.0, 0);
+        assert!(pipeline
+            .evaluate(&holding, &close, dec!(100), false)
+            .order()
+            .is_some());
+    }
+
+    #[test]
+    fn test_strategy_orders_all_or_nothing() {
+        let pipeline = create_pipeline();
+        let portfolio = create_portfolio(None);
+        let prices = HashMap::from([
+            ("TEST".to_string(), dec!(100)),
+            ("OTHER".to_string(), dec!(50)),
+        ]);
+
+        // A lone oversized order is cut like a manual one
+        let single = StrategyOrders::single(OrderRequest::market("TEST", Side::Buy, dec!(500)));
+        let legs = pipeline
+            .evaluate_orders(&portfolio, &single, &prices, |_| false)
+            .unwrap();
+        assert_eq!(legs[0].quantity, dec!(100));
+
+        let pair = StrategyOrders::new(vec![
+            OrderRequest::market("TEST", Side::Buy, dec!(50)),
+            OrderRequest::limit("OTHER", Side::Sell, dec!(100), dec!(50)),
+        ]);
+        let legs = pipeline
+            .evaluate_orders(&portfolio, &pair, &prices, |_| false)
+            .unwrap();
+        assert_eq!(legs.len(), 2);
+
+        // ...but a leg of a multi-leg batch is never resized
+        let mut unbalanced = pair.clone();
+        unbalanced.legs[0].quantity = dec!(500);
+        assert!(matches!(
+            pipeline.evaluate_orders(&portfolio, &unbalanced, &prices, |_| false),
+            Err(OrdersNotSent { rejected: true, .. })
+        ));
+
+        // Legs are checked together: each fits alone, but not both
+        let one_position = OrderPipeline::new(RiskManager::new(RiskConfig {
+            limits: trading_risk::PortfolioLimits {
+                max_positions: 1,
+                ..Default::default()
+            },
+            ..Default::default()
+        }));
+        for leg in &pair.legs {
+            let alone = StrategyOrders::single(leg.clone());
+            assert!(one_position
+                .evaluate_orders(&portfolio, &alone, &prices, |_| false)
+                .is_ok());
+        }
+        assert!(matches!(
+            one_position.evaluate_orders(&portfolio, &pair, &prices, |_| false),
+            Err(OrdersNotSent { rejected: true, .. })
+        ));
+
+        assert!(matches!(
+            pipeline.evaluate_orders(&portfolio, &pair, &prices, |s| s == "OTHER"),
+            Err(OrdersNotSent {
+                rejected: false,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_reconcile_tracks_drawdown_across_accounts() {
+        let mut pipeline = create_pipeline();
+        let signal = Signal::buy("TEST", 100.0, 0);
+
+        // Brokers report the current equity as the peak on every call
+        let account = |equity: Decimal| {
+            let mut portfolio = Portfolio::new(equity);
+            portfolio.buying_power = equity;
+            portfolio
+        };
+        let portfolio = pipeline.reconcile(account(dec!(100000)));
+        assert!(matches!(
+            pipeline.evaluate(&portfolio, &signal, dec!(100), false),
+            PipelineDecision::Submit { .. }
+        ));
+
+        let portfolio = pipeline.reconcile(account(dec!(75000)));
+        assert_eq!(portfolio.peak_equity, dec!(100000));
+        match pipeline.evaluate(&portfolio, &signal, dec!(100), false) {
+            PipelineDecision::Reject { reason } => assert!(reason.contains("drawdown")),
+            other => panic!("Expected Reject, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,1412 @@
+//! Strategy execution loop.
+
+use chrono::{DateTime, NaiveDate};
+use rust_decimal::Decimal;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use tracing::{debug, error, info, warn};
+use trading_core::error::{BrokerError, TradingError, TradingResult};
+use trading_core::traits::{
+    debug_check_metadata, Broker, Clock, Strategy, StrategyAction, StrategyOrders, SystemClock,
+};
+use trading_core::types::{
+    Bar, BarSeries, MarketSnapshot, MultiTimeframeSeries, Order, OrderRequest, OrderType, Position,
+    Side, Signal, TimeInForce, Timeframe,
+};
+use trading_risk::{RiskConfig, SessionConfig};
+use uuid::Uuid;
+
+use crate::{
+    DataFeed, JournalEntry, JournalEvent, OrderPipeline, OrderSource, PipelineDecision,
+    TradeJournal,
+};
+
+/// Finished orders kept for [`TradingRuntime::recent_fills`].
+const RECENT_FILLS: usize = 50;
+
+/// Bars of indicator readings kept per symbol for
+/// [`TradingRuntime::indicator_history`].
+const INDICATOR_HISTORY: usize = 200;
+
+/// Strategy indicator readings after a bar, with the bar's timestamp.
+type Readings = (i64, HashMap<String, f64>);
+
+/// Result of feeding one bar to the runtime.
+#[derive(Debug)]
+pub enum StepOutcome {
+    /// Strategy produced no signal
+    NoSignal,
+    /// Signal ignored by the pipeline
+    Skipped { signal: Signal, reason: String },
+    /// Signal blocked by risk management
+    Rejected { signal: Signal, reason: String },
+    /// Order accepted by the broker
+    Submitted { signal: Signal, order: Box<Order> },
+    /// Broker call failed
+    Failed { signal: Signal, error: BrokerError },
+    /// Strategy-built orders accepted by the broker
+    OrdersSubmitted { orders: Vec<Order> },
+    /// Strategy-built orders skipped or rejected by the pipeline
+    OrdersNotSent {
+        orders: StrategyOrders,
+        reason: String,
+    },
+    /// Broker call failed partway through strategy-built orders; `submitted`
+    /// holds the legs that did reach the broker
+    OrdersFailed {
+        submitted: Vec<Order>,
+        error: BrokerError,
+    },
+}
+
+/// What happened during a trading session, logged when it ends.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionSummary {
+    /// Trading day (UTC)
+    pub date: NaiveDate,
+    /// Equity when the session started
+    pub start_equity: Decimal,
+    /// Equity when it ended, before any cleanup
+    pub end_equity: Decimal,
+    /// Signals the strategy generated
+    pub signals: usize,
+    /// Orders accepted by the broker
+    pub orders_submitted: usize,
+    /// Signals and strategy orders blocked by risk management
+    pub rejections: usize,
+    /// Working orders canceled at the close
+    pub orders_canceled: usize,
+    /// Positions closed at the close
+    pub positions_flattened: usize,
+}
+
+impl SessionSummary {
+    /// Equity gained or lost over the session.
+    pub fn pnl(&self) -> Decimal {
+        self.end_equity - self.start_equity
+    }
+}
+
+impl std::fmt::Display for SessionSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Session {}: equity ${:.2} (${:+.2}), {} signals, {} orders, {} rejected",
+            self.date,
+            self.end_equity,
+            self.pnl(),
+            self.signals,
+            self.orders_submitted,
+            self.rejections
+        )?;
+        if self.orders_canceled > 0 {
+            write!(f, ", {} orders canceled", self.orders_canceled)?;
+        }
+        if self.positions_flattened > 0 {
+            write!(f, ", {} positions flattened", self.positions_flattened)?;
+        }
+        Ok(())
+    }
+}
+
+/// Settings to switch to at the next bar, all at once.
+#[derive(Default)]
+pub struct Reload {
+    /// Strategy to take over, trading the same symbols on the same
+    /// timeframes
+    pub strategy: Option<Box<dyn Strategy>>,
+    /// Risk configuration to apply
+    pub risk: Option<RiskConfig>,
+}
+
+/// Runs a strategy against a broker through the shared order pipeline.
+pub struct TradingRuntime {
+    strategy: Box<dyn Strategy>,
+    broker: Arc<dyn Broker>,
+    pipeline: OrderPipeline,
+    timeframe: Timeframe,
+    series: HashMap<String, MultiTimeframeSeries>,
+    /// Strategy indicator readings after each of a symbol's latest bars,
+    /// by bar timestamp
+    indicators: HashMap<String, VecDeque<Readings>>,
+    /// Latest unfinished order submitted per symbol
+    in_flight: HashMap<String, Uuid>,
+    /// Price each in-flight market order was sized at, for slippage checks
+    sized_at: HashMap<Uuid, Decimal>,
+    /// Latest finished orders that filled, newest last
+    recent_fills: VecDeque<Order>,
+    trading_day: Option<NaiveDate>,
+    day_start_equity: Decimal,
+    session: SessionConfig,
+    /// Activity in the current session
+    activity: SessionSummary,
+    /// Summary of the latest session that ended
+    last_session: Option<SessionSummary>,
+    /// Strategy actions are held back while paused
+    paused: bool,
+    /// Strategy orders are logged instead of sent
+    dry_run: bool,
+    /// Where signals, decisions, orders and fills are recorded
+    journal: Option<TradeJournal>,
+    /// Settings waiting for the next bar
+    reload: Option<Reload>,
+    /// Timestamp of the latest bar fed
+    last_bar: Option<i64>,
+    /// Time source for journal entries
+    clock: Arc<dyn Clock>,
+}
+
+impl TradingRuntime {
+    /// Create a new runtime.
+    pub fn new(
+        strategy: Box<dyn Strategy>,
+        broker: Arc<dyn Broker>,
+        pipeline: OrderPipeline,
+        timeframe: Timeframe,
+    ) -> Self {
+        let series = strategy
+            .symbols()
+            .iter()
+            .map(|s| {
+                let series =
+                    MultiTimeframeSeries::new(s.clone(), timeframe, 0, strategy.timeframes());
+                (s.clone(), series)
+            })
+            .collect();
+
+        Self {
+            strategy,
+            broker,
+            pipeline,
+            timeframe,
+            series,
+            indicators: HashMap::new(),
+            in_flight: HashMap::new(),
+            sized_at: HashMap::new(),
+            recent_fills: VecDeque::new(),
+            trading_day: None,
+            day_start_equity: Decimal::ZERO,
+            session: SessionConfig::default(),
+            activity: SessionSummary::default(),
+            last_session: None,
+            paused: false,
+            dry_run: false,
+            journal: None,
+            reload: None,
+            last_bar: None,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Set what happens at the end of each trading session.
+    pub fn with_session(mut self, session: SessionConfig) -> Self {
+        self.session = session;
+        self
+    }
+
+    /// Start in dry-run mode (see [`set_dry_run`](Self::set_dry_run)).
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Record every signal, risk decision, order and fill in a journal.
+    pub fn with_journal(mut self, journal: TradeJournal) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    /// Use `clock` instead of wall time, e.g. a simulated clock when
+    /// replaying history.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Preload history without generating signals.
+    ///
+    /// The risk manager's market state (regimes, volatility) warms up on
+    /// the same bars.
+    pub fn warmup(&mut self, symbol: &str, bars: impl IntoIterator<Item = Bar>) {
+        for bar in bars {
+            self.series_mut(symbol).push(bar);
+            self.pipeline.on_bar(symbol, &bar);
+        }
+    }
+
+    /// Switch strategy and/or risk settings at the start of the next bar,
+    /// so every symbol of a bar is handled with the same settings. Settings
+    /// staged again before then replace the earlier ones.
+    ///
+    /// The new strategy picks up from the bars already seen, but must
+    /// trade the same symbols on the same timeframes.
+    pub fn stage_reload(&mut self, reload: Reload) -> TradingResult<()> {
+        if let Some(strategy) = &reload.strategy {
+            if strategy.symbols() != self.strategy.symbols() {
+                return Err(TradingError::Config(
+                    "the strategy trades different symbols; restart to change them".to_string(),
+                ));
+            }
+            if strategy.timeframes() != self.strategy.timeframes() {
+                return Err(TradingError::Config(
+                    "the strategy uses different timeframes; restart to change them".to_string(),
+                ));
+            }
+        }
+        let pending = self.reload.get_or_insert_with(Reload::default);
+        if reload.strategy.is_some() {
+            pending.strategy = reload.strategy;
+        }
+        if reload.risk.is_some() {
+            pending.risk = reload.risk;
+        }
+        Ok(())
+    }
+
+    /// Whether settings are waiting for the next bar.
+    pub fn has_pending_reload(&self) -> bool {
+        self.reload.is_some()
+    }
+
+    /// Switch to the staged settings.
+    fn apply_reload(&mut self) {
+        let Some(reload) = self.reload.take() else {
+            return;
+        };
+        if let Some(strategy) = reload.strategy {
+            info!(
+                "Reloaded strategy {} (was {})",
+                strategy.name(),
+                self.strategy.name()
+            );
+            self.strategy = strategy;
+        }
+        if let Some(risk) = reload.risk {
+            info!("Reloaded risk settings");
+            self.pipeline.risk_manager_mut().reconfigure(risk);
+        }
+    }
+
+    /// Feed a new bar and act on any resulting signal.
+    pub async fn on_bar(&mut self, symbol: &str, bar: Bar) -> StepOutcome {
+        if self.last_bar.map_or(true, |last| bar.timestamp > last) {
+            self.apply_reload();
+            self.last_bar = Some(bar.timestamp);
+        }
+        self.refresh_in_flight().await;
+        self.journal(JournalEvent::Bar {
+            symbol: symbol.to_string(),
+            bar,
+        });
+        self.series_mut(symbol).push(bar);
+        self.pipeline.on_bar(symbol, &bar);
+        self.pipeline.record_market_data(bar.timestamp);
+        let action = self.strategy.act(&self.series[symbol]);
+        let history = self.indicators.entry(symbol.to_string()).or_default();
+        if history.len() == INDICATOR_HISTORY {
+            history.pop_front();
+        }
+        history.push_back((bar.timestamp, self.strategy.state().indicators));
+        // A forced exit takes the place of whatever the strategy wanted
+        if let Some(signal) = self.time_exit(symbol, &bar).await {
+            info!("Time exit for {}: {}", symbol, signal.metadata.reason);
+            return self.execute_signal(signal).await;
+        }
+        self.execute(action).await
+    }
+
+    /// Forced exit for a symbol's position on a new bar, if the risk
+    /// config's time exit calls for one.
+    async fn time_exit(&mut self, symbol: &str, bar: &Bar) -> Option<Signal> {
+        if !self.pipeline.has_time_exit() {
+            return None;
+        }
+        let portfolio = self.broker.get_account().await.ok()?;
+        self.pipeline.time_exit(&portfolio, symbol, bar)
+    }
+
+    /// Give the strategy a snapshot of every symbol at `timestamp` (ms) and
+    /// act on the result.
+    ///
+    /// Call once per batch of bars, after feeding them.
+    pub async fn on_snapshot(&mut self, timestamp: i64) -> StepOutcome {
+        self.refresh_in_flight().await;
+        let snapshot = MarketSnapshot::new(timestamp, self.series.values());
+        let action = self.strategy.on_snapshot(&snapshot);
+        self.execute(action).await
+    }
+
+    /// Act on what the strategy asked for.
+    async fn execute(&mut self, action: Option<StrategyAction>) -> StepOutcome {
+        let signal = match action {
+            None => return StepOutcome::NoSignal,
+            Some(StrategyAction::Orders(orders)) if self.paused => {
+                return StepOutcome::OrdersNotSent {
+                    orders,
+                    reason: "Trading paused".to_string(),
+                }
+            }
+            Some(StrategyAction::Signal(signal)) if self.paused => {
+                debug!("Trading paused, ignoring signal for {}", signal.symbol);
+                return StepOutcome::Skipped {
+                    signal,
+                    reason: "Trading paused".to_string(),
+                };
+            }
+            Some(StrategyAction::Orders(orders)) => {
+                return self.submit_strategy_orders(orders).await
+            }
+            Some(StrategyAction::Signal(signal)) => signal,
+        };
+        debug_check_metadata(self.strategy.as_ref(), &signal);
+        self.execute_signal(signal).await
+    }
+
+    /// Run a signal through the pipeline and submit the resulting order.
+    async fn execute_signal(&mut self, signal: Signal) -> StepOutcome {
+        info!(
+            "Signal: {} {} @ ${:.2}",
+            signal.signal_type, signal.symbol, signal.price
+        );
+        self.pipeline.record_signal(&signal);
+        self.activity.signals += 1;
+        self.journal(JournalEvent::Signal {
+            signal: signal.clone(),
+        });
+
+        let portfolio = match self.broker.get_account().await {
+            Ok(portfolio) => self.pipeline.reconcile(portfolio),
+            Err(error) => return StepOutcome::Failed { signal, error },
+        };
+        let price = self
+            .series
+            .get(&signal.symbol)
+            .and_then(|s| s.primary().last())
+            .and_then(|bar| Decimal::try_from(bar.close).ok())
+            .unwrap_or_default();
+        self.refresh_in_flight().await;
+        let pending = self.in_flight.contains_key(&signal.symbol);
+
+        let decision = self.pipeline.evaluate(&portfolio, &signal, price, pending);
+        self.journal_decision(&signal.symbol, &decision);
+        let order = match decision {
+            PipelineDecision::Skip { reason } => {
+                debug!("Skipping signal for {}: {}", signal.symbol, reason);
+                return StepOutcome::Skipped { signal, reason };
+            }
+            PipelineDecision::Reject { reason } => {
+                info!("Risk rejected signal for {}: {}", signal.symbol, reason);
+                self.activity.rejections += 1;
+                return StepOutcome::Rejected { signal, reason };
+            }
+            PipelineDecision::Submit { order, .. } => *order,
+        };
+        if self.dry_run {
+            let reason = format!(
+                "Dry run: {} {} {} not sent",
+                order.side, order.quantity, order.symbol
+            );
+            info!("{}", reason);
+            self.journal(JournalEvent::Skipped {
+                symbol: signal.symbol.clone(),
+                reason: reason.clone(),
+            });
+            return StepOutcome::Skipped { signal, reason };
+        }
+
+        // P&L realized if this order closes some of the current position
+        let closing_pnl = portfolio
+            .get_position(&signal.symbol)
+            .and_then(|p| closing_pnl(p, order.side, order.quantity));
+
+        let result = self.broker.submit_order(order).await;
+        if self.pipeline.record_submission(&signal.symbol, &result) {
+            self.flatten_if_configured().await;
+        }
+        self.journal_submission(&signal.symbol, &result, OrderSource::Strategy);
+
+        match result {
+            Ok(order) => {
+                info!(
+                    "Order submitted: {} {} {}",
+                    order.side, order.quantity, order.symbol
+                );
+                self.track(&order, Some(price));
+                self.activity.orders_submitted += 1;
+                if let Some(pnl) = closing_pnl {
+                    if self.pipeline.record_closed_trade(self.strategy.name(), pnl) {
+                        self.flatten_if_configured().await;
+                    }
+                }
+                StepOutcome::Submitted {
+                    signal,
+                    order: Box::new(order),
+                }
+            }
+            Err(error) => {
+                error!("Failed to submit order for {}: {}", signal.symbol, error);
+                StepOutcome::Failed { signal, error }
+            }
+        }
+    }
+
+    /// Validate and submit orders a strategy built itself.
+    async fn submit_strategy_orders(&mut self, orders: StrategyOrders) -> StepOutcome {
+        self.journal(JournalEvent::StrategyOrders {
+            orders: orders.clone(),
+        });
+        let portfolio = match self.broker.get_account().await {
+            Ok(portfolio) => self.pipeline.reconcile(portfolio),
+            Err(error) => {
+                return StepOutcome::OrdersFailed {
+                    submitted: Vec::new(),
+                    error,
+                }
+            }
+        };
+        let prices = orders
+            .legs
+            .iter()
+            .filter_map(|leg| {
+                let bar = self.series.get(&leg.symbol)?.primary().last()?;
+                Some((leg.symbol.clone(), Decimal::try_from(bar.close).ok()?))
+            })
+            .collect();
+        self.refresh_in_flight().await;
+
+        let in_flight = &self.in_flight;
+        let legs = match self
+            .pipeline
+            .evaluate_orders(&portfolio, &orders, &prices, |symbol| {
+                in_flight.contains_key(symbol)
+            }) {
+            Ok(legs) => legs,
+            Err(not_sent) => {
+                info!("Strategy orders not sent: {}", not_sent.reason);
+                if not_sent.rejected {
+                    self.activity.rejections += 1;
+                }
+                for leg in &orders.legs {
+                    let (symbol, reason) = (leg.symbol.clone(), not_sent.reason.clone());
+                    self.journal(if not_sent.rejected {
+                        JournalEvent::Rejected { symbol, reason }
+                    } else {
+                        JournalEvent::Skipped { symbol, reason }
+                    });
+                }
+                return StepOutcome::OrdersNotSent {
+                    orders,
+                    reason: not_sent.reason,
+                };
+            }
+        };
+
+        for leg in &legs {
+            self.journal(JournalEvent::Approved {
+                order: leg.clone(),
+                stop_loss_price: None,
+                take_profit_price: None,
+                modified: None,
+            });
+        }
+        if self.dry_run {
+            let descriptions: Vec<String> = legs
+                .iter()
+                .map(|leg| format!("{} {} {}", leg.side, leg.quantity, leg.symbol))
+                .collect();
+            let reason = format!("Dry run: {} not sent", descriptions.join(", "));
+            info!("{}", reason);
+            for leg in &legs {
+                self.journal(JournalEvent::Skipped {
+                    symbol: leg.symbol.clone(),
+                    reason: reason.clone(),
+                });
+            }
+            return StepOutcome::OrdersNotSent { orders, reason };
+        }
+
+        let mut submitted = Vec::with_capacity(legs.len());
+        for leg in legs {
+            let closing_pnl = portfolio
+                .get_position(&leg.symbol)
+                .and_then(|p| closing_pnl(p, leg.side, leg.quantity));
+            let symbol = leg.symbol.clone();
+            let price = prices.get(&symbol).copied();
+            let result = self.broker.submit_order(leg).await;
+            if self.pipeline.record_submission(&symbol, &result) {
+                self.flatten_if_configured().await;
+            }
+            self.journal_submission(&symbol, &result, OrderSource::Strategy);
+            match result {
+                Ok(order) => {
+                    info!(
+                        "Strategy order submitted: {} {} {}",
+                        order.side, order.quantity, order.symbol
+                    );
+                    self.track(&order, price);
+                    self.activity.orders_submitted += 1;
+                    if let Some(pnl) = closing_pnl {
+                        if self.pipeline.record_closed_trade(self.strategy.name(), pnl) {
+                            self.flatten_if_configured().await;
+                        }
+                    }
+                    submitted.push(order);
+                }
+                Err(error) => {
+                    error!(
+                        "Failed to submit strategy order for {} after {} of its legs: {}",
+                        symbol,
+                        submitted.len(),
+                        error
+                    );
+                    return StepOutcome::OrdersFailed { submitted, error };
+                }
+            }
+        }
+        StepOutcome::OrdersSubmitted { orders: submitted }
+    }
+
+    /// Submit a manually entered order through the risk pipeline.
+    ///
+    /// Market orders are checked at the latest close seen for the symbol.
+    pub async fn submit_order(&mut self, request: OrderRequest) -> TradingResult<Order> {
+        let portfolio = self.pipeline.reconcile(self.broker.get_account().await?);
+        let price = request
+            .limit_price
+            .or_else(|| {
+                let bar = self.series.get(&request.symbol)?.primary().last()?;
+                Decimal::try_from(bar.close).ok()
+            })
+            .or_else(|| {
+                portfolio
+                    .get_position(&request.symbol)
+                    .map(|p| p.current_price)
+            })
+            .ok_or_else(|| {
+                TradingError::Validation(format!("No price available for {}", request.symbol))
+            })?;
+
+        let decision = self.pipeline.evaluate_order(&portfolio, &request, price);
+        self.journal_decision(&request.symbol, &decision);
+        let order = match decision {
+            PipelineDecision::Skip { reason } | PipelineDecision::Reject { reason } => {
+                return Err(TradingError::RiskBlocked { reason });
+            }
+            PipelineDecision::Submit { order, .. } => *order,
+        };
+
+        let symbol = order.symbol.clone();
+        let result = self.broker.submit_order(order).await;
+        if self.pipeline.record_submission(&symbol, &result) {
+            self.flatten_if_configured().await;
+        }
+        self.journal_submission(&symbol, &result, OrderSource::Manual);
+        let order = result?;
+        info!(
+            "Manual order submitted: {} {} {}",
+            order.side, order.quantity, order.symbol
+        );
+        self.track(&order, Some(price));
+        self.activity.orders_submitted += 1;
+        Ok(order)
+    }
+
+    /// Refresh daily P&L tracking, check market data is still arriving and
+    /// close the session once it has ended, as of `timestamp` (ms).
+    ///
+    /// Call once per batch of bars, before feeding them.
+    pub async fn supervise(&mut self, timestamp: i64) -> Result<(), BrokerError> {
+        let account = self.broker.get_account().await;
+        let tripped = self.pipeline.record_broker_call(account.is_ok())
+            | self.pipeline.check_market_data(timestamp);
+        if tripped {
+            self.flatten_if_configured().await;
+        }
+        let mut account = self.pipeline.reconcile(account?);
+        let today = DateTime::from_timestamp_millis(timestamp)
+            .unwrap_or_default()
+            .date_naive();
+
+        let closed = self.last_session.as_ref().map(|s| s.date);
+        if let Some(day) = self.trading_day.filter(|day| Some(*day) != closed) {
+            if self.session.has_ended(day, timestamp) {
+                self.end_session(day, account.equity).await;
+                account = self.pipeline.reconcile(self.broker.get_account().await?);
+                self.day_start_equity = account.equity;
+            }
+        }
+
+        if self.trading_day != Some(today) {
+            self.trading_day = Some(today);
+            self.day_start_equity = account.equity;
+            self.pipeline.reset_daily();
+        }
+
+        let daily_pnl = account.equity - self.day_start_equity;
+        if self.pipeline.update_daily_pnl(daily_pnl, account.equity) {
+            self.flatten_if_configured().await;
+        }
+        Ok(())
+    }
+
+    /// Cancel working day orders, or every order when flattening positions,
+    /// log the session's summary and reset the daily risk counters.
+    async fn end_session(&mut self, day: NaiveDate, equity: Decimal) {
+        let mut summary = std::mem::take(&mut self.activity);
+        summary.date = day;
+        summary.start_equity = self.day_start_equity;
+        summary.end_equity = equity;
+
+        if self.session.cancel_orders {
+            match self.broker.get_open_orders().await {
+                Ok(orders) => {
+                    // Market orders queued for the next open aren't stale
+                    for order in orders.iter().filter(|o| {
+                        o.time_in_force != TimeInForce::GTC && o.order_type != OrderType::Market
+                    }) {
+                        match self.broker.cancel_order(&order.id.to_string()).await {
+                            Ok(()) => summary.orders_canceled += 1,
+                            Err(e) => warn!("Failed to cancel {} order: {}", order.symbol, e),
+                        }
+                    }
+                }
+                Err(e) => error!("Failed to fetch open orders at the close: {}", e),
+            }
+        }
+        if self.session.flatten {
+            match self.flatten().await {
+                Ok(orders) => summary.positions_flattened = orders.len(),
+                Err(e) => error!("Failed to flatten positions at the close: {}", e),
+            }
+        }
+
+        info!("{}", summary);
+        self.pipeline.reset_daily();
+        self.last_session = Some(summary);
+    }
+
+    /// Halt trading by hand, flattening positions if the circuit breaker
+    /// is configured to.
+    ///
+    /// Returns `false` if there is no circuit breaker or it already tripped.
+    pub async fn trip_circuit_breaker(&mut self, reason: impl Into<String>) -> bool {
+        if !self.pipeline.trip_circuit_breaker(reason) {
+            return false;
+        }
+        self.flatten_if_configured().await;
+        true
+    }
+
+    /// Re-enable a strategy the supervisor disabled, clearing its losing
+    /// streak.
+    ///
+    /// Returns `false` if there is no supervisor or the strategy is enabled.
+    pub fn enable_strategy(&mut self) -> bool {
+        let Some(supervisor) = self.pipeline.supervisor_mut() else {
+            return false;
+        };
+        if supervisor.is_enabled() {
+            return false;
+        }
+        supervisor.enable();
+        info!("Strategy re-enabled");
+        true
+    }
+
+    /// Drive the strategy until the feed is exhausted.
+    pub async fn run<F: DataFeed + ?Sized>(&mut self, feed: &mut F) -> Result<(), BrokerError> {
+        while let Some(batch) = feed.next_bars().await {
+            let Some(timestamp) = batch.first().map(|(_, bar)| bar.timestamp) else {
+                continue;
+            };
+            self.supervise(timestamp).await?;
+            for (symbol, bar) in batch {
+                self.on_bar(&symbol, bar).await;
+            }
+            self.on_snapshot(timestamp).await;
+        }
+        Ok(())
+    }
+
+    /// Pause or resume acting on the strategy.
+    ///
+    /// While paused the strategy still sees every bar, but its signals and
+    /// orders are skipped. Time exits, the end-of-session routine and manual
+    /// orders carry on.
+    pub fn set_paused(&mut self, paused: bool) {
+        if paused != self.paused {
+            info!("Trading {}", if paused { "paused" } else { "resumed" });
+        }
+        self.paused = paused;
+    }
+
+    /// Whether acting on the strategy is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Switch dry-run mode: strategy orders that pass the risk checks are
+    /// logged instead of sent. Manual orders are still sent.
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        if dry_run != self.dry_run {
+            info!("Dry run {}", if dry_run { "on" } else { "off" });
+        }
+        self.dry_run = dry_run;
+    }
+
+    /// Whether strategy orders are only logged.
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Close a position at market.
+    pub async fn close_position(&mut self, symbol: &str) -> Result<Order, BrokerError> {
+        let result = self.broker.close_position(symbol).await;
+        if self.pipeline.record_submission(symbol, &result) {
+            self.flatten_if_configured().await;
+        }
+        self.journal_submission(symbol, &result, OrderSource::Close);
+        let order = result?;
+        info!("Closing {}: {} {}", symbol, order.side, order.quantity);
+        self.track(&order, None);
+        Ok(order)
+    }
+
+    /// Cancel every open order.
+    pub async fn cancel_all_orders(&mut self) -> Result<(), BrokerError> {
+        self.broker.cancel_all_orders().await?;
+        info!("Canceled all open orders");
+        Ok(())
+    }
+
+    /// Cancel every open order and close every position at market.
+    pub async fn flatten(&mut self) -> Result<Vec<Order>, BrokerError> {
+        self.cancel_all_orders().await?;
+        warn!("Flattening all positions");
+        let orders = self.broker.close_all_positions().await?;
+        for order in &orders {
+            self.journal_submission(&order.symbol, &Ok(order.clone()), OrderSource::Close);
+            self.track(order, None);
+        }
+        Ok(orders)
+    }
+
+    /// Stop trading for good: cancel open orders and flush the journal.
+    /// Positions are left as they are.
+    pub async fn shutdown(&mut self) -> Result<(), BrokerError> {
+        let canceled = self.cancel_all_orders().await;
+        if let Some(journal) = &mut self.journal {
+            if let Err(e) = journal.sync() {
+                error!(
+                    "Failed to flush trade journal {}: {}",
+                    journal.path().display(),
+                    e
+                );
+            }
+        }
+        canceled
+    }
+
+    /// Equity at the start of the current trading day.
+    pub fn day_start_equity(&self) -> Decimal {
+        self.day_start_equity
+    }
+
+    /// Latest finished orders that filled, newest first.
+    pub fn recent_fills(&self) -> impl Iterator<Item = &Order> {
+        self.recent_fills.iter().rev()
+    }
+
+    /// Summary of the latest trading session that ended.
+    pub fn last_session(&self) -> Option<&SessionSummary> {
+        self.last_session.as_ref()
+    }
+
+    /// Get the strategy.
+    pub fn strategy(&self) -> &dyn Strategy {
+        self.strategy.as_ref()
+    }
+
+    /// Get the broker.
+    pub fn broker(&self) -> &Arc<dyn Broker> {
+        &self.broker
+    }
+
+    /// Get the order pipeline.
+    pub fn pipeline(&self) -> &OrderPipeline {
+        &self.pipeline
+    }
+
+    /// Get the order pipeline mutably.
+    pub fn pipeline_mut(&mut self) -> &mut OrderPipeline {
+        &mut self.pipeline
+    }
+
+    /// Get the bar series for a symbol.
+    pub fn series(&self, symbol: &str) -> Option<&BarSeries> {
+        self.series.get(symbol).map(|s| s.primary())
+    }
+
+    /// Strategy indicator readings as of the symbol's latest bar.
+    pub fn indicators(&self, symbol: &str) -> Option<&HashMap<String, f64>> {
+        self.indicators
+            .get(symbol)?
+            .back()
+            .map(|(_, values)| values)
+    }
+
+    /// Strategy indicator readings after each of the symbol's recent bars,
+    /// oldest first, with the bar timestamps.
+    pub fn indicator_history(
+        &self,
+        symbol: &str,
+    ) -> impl Iterator<Item = &(i64, HashMap<String, f64>)> {
+        self.indicators.get(symbol).into_iter().flatten()
+    }
+
+    fn series_mut(&mut self, symbol: &str) -> &mut MultiTimeframeSeries {
+        let timeframe = self.timeframe;
+        let timeframes = self.strategy.timeframes();
+        self.series.entry(symbol.to_string()).or_insert_with(|| {
+            MultiTimeframeSeries::new(symbol.to_string(), timeframe, 0, timeframes)
+        })
+    }
+
+    /// Mark a submitted order in flight, remembering the price a market
+    /// order was sized at.
+    fn track(&mut self, order: &Order, price: Option<Decimal>) {
+        if let Some(replaced) = self.in_flight.insert(order.symbol.clone(), order.id) {
+            self.sized_at.remove(&replaced);
+        }
+        if let Some(price) = price.filter(|_| order.order_type == OrderType::Market) {
+            self.sized_at.insert(order.id, price);
+        }
+    }
+
+    /// Forget in-flight orders the broker reports finished, reporting those
+    /// that filled to the strategy and checking market fills for slippage.
+    ///
+    /// An order whose status can't be fetched is kept, so a flaky broker
+    /// connection never lets a duplicate order through.
+    async fn refresh_in_flight(&mut self) {
+        let mut finished = Vec::new();
+        let mut filled = Vec::new();
+        let mut tripped = false;
+        for (symbol, id) in &self.in_flight {
+            match self.broker.get_order(&id.to_string()).await {
+                Ok(order) if order.status.is_terminal() => {
+                    if let (Some(expected), Some(fill_price)) =
+                        (self.sized_at.get(id), order.filled_avg_price)
+                    {
+                        tripped |= self.pipeline.record_fill(order.side, *expected, fill_price);
+                    }
+                    if order.filled_quantity > Decimal::ZERO {
+                        self.strategy.on_fill(&order);
+                        if self.recent_fills.len() == RECENT_FILLS {
+                            self.recent_fills.pop_front();
+                        }
+                        filled.push(order.clone());
+                        self.recent_fills.push_back(order);
+                    }
+                    finished.push(symbol.clone());
+                }
+                Err(BrokerError::OrderNotFound(_)) => finished.push(symbol.clone()),
+                Ok(_) => {}
+                Err(e) => debug!("Could not refresh order for {}: {}", symbol, e),
+            }
+        }
+        for symbol in finished {
+            if let Some(id) = self.in_flight.remove(&symbol) {
+                self.sized_at.remove(&id);
+            }
+        }
+        for order in filled {
+            self.journal(JournalEvent::Fill { order });
+        }
+        if tripped {
+            self.flatten_if_configured().await;
+        }
+    }
+
+    /// Append an event to the journal, if there is one. A journal that
+    /// can't be written is logged, never allowed to stop trading.
+    fn journal(&mut self, event: JournalEvent) {
+        let Some(journal) = &mut self.journal else {
+            return;
+        };
+        let entry = JournalEntry {
+            timestamp: self.clock.now_millis(),
+            strategy: self.strategy.name().to_string(),
+            event,
+        };
+        if let Err(e) = journal.append(&entry) {
+            warn!(
+                "Failed to write trade journal {}: {}",
+                journal.path().display(),
+                e
+            );
+        }
+    }
+
+    fn journal_decision(&mut self, symbol: &str, decision: &PipelineDecision) {
+        let symbol = symbol.to_string();
+        self.journal(match decision {
+            PipelineDecision::Skip { reason } => JournalEvent::Skipped {
+                symbol,
+                reason: reason.clone(),
+            },
+            PipelineDecision::Reject { reason } => JournalEvent::Rejected {
+                symbol,
+                reason: reason.clone(),
+            },
+            PipelineDecision::Submit {
+                order,
+                stop_loss_price,
+                take_profit_price,
+                modified,
+            } => JournalEvent::Approved {
+                order: order.as_ref().clone(),
+                stop_loss_price: *stop_loss_price,
+                take_profit_price: *take_profit_price,
+                modified: modified.clone(),
+            },
+        });
+    }
+
+    fn journal_submission(
+        &mut self,
+        symbol: &str,
+        result: &Result<Order, BrokerError>,
+        source: OrderSource,
+    ) {
+        self.journal(match result {
+            Ok(order) => JournalEvent::Order {
+                order: order.clone(),
+                source,
+            },
+            Err(e) => JournalEvent::OrderFailed {
+                symbol: symbol.to_string(),
+                error: e.to_string(),
+            },
+        });
+    }
+
+    async fn flatten_if_configured(&mut self) {
+        if !self.pipeline.should_flatten() {
+            return;
+        }
+        if let Err(e) = self.flatten().await {
+            error!("Failed to flatten positions: {}", e);
+        }
+    }
+}
+
+/// Unrealized P&L an order for `quantity` on `side` would realize against
+/// `position`, in proportion to the share of it closed; `None` if the order
+/// adds to the position.
+fn closing_pnl(position: &Position, side: Side, quantity: Decimal) -> Option<Decimal> {
+    let closes =
+        (position.is_long() && side == Side::Sell) || (position.is_short() && side == Side::Buy);
+    if !closes {
+        return None;
+    }
+    let share = (quantity / position.quantity.abs()).min(Decimal::ONE);
+    Some(position.unrealized_pnl * share)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::HistoricalFeed;
+    use rust_decimal_macros::dec;
+    use trading_broker::PaperBroker;
+    use trading_risk::{CircuitBreakerConfig, RiskConfig, RiskManager, SupervisorConfig};
+    use trading_strategies::{MACrossoverConfig, MACrossoverStrategy, MaType};
+
+    #[tokio::test]
+    async fn test_runtime_submits_through_risk() {
+        let broker: Arc<dyn Broker> = Arc::new(PaperBroker::new(dec!(100000)));
+        let strategy = MACrossoverStrategy::new(MACrossoverConfig {
+            symbols: vec!["TEST".to_string()],
+            fast_period: 3,
+            slow_period: 6,
+            ma_type: MaType::Sma,
+            signal_threshold: 0.0,
+        });
+        let pipeline = OrderPipeline::new(RiskManager::new(RiskConfig::default()));
+        let journal_path =
+            std::env::temp_dir().join(format!("trading-runtime-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&journal_path);
+        let mut runtime = TradingRuntime::new(
+            Box::new(strategy),
+            broker.clone(),
+            pipeline,
+            Timeframe::Daily,
+        )
+        .with_journal(TradeJournal::open(&journal_path).unwrap());
+
+        // Dip then rally triggers a buy
+        let bars: Vec<(String, Bar)> = (0..40)
+            .map(|i| {
+                let price = if i < 20 {
+                    100.0 - i as f64
+                } else {
+                    80.0 + (i - 20) as f64 * 2.0
+                };
+                (
+                    "TEST".to_string(),
+                    Bar::new(i * 86400000, price, price, price, price, 1e6),
+                )
+            })
+            .collect();
+        runtime.run(&mut HistoricalFeed::new(bars)).await.unwrap();
+
+        let orders = broker.get_open_orders().await.unwrap();
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0].side, Side::Buy);
+
+        // Latest strategy readings are kept per symbol
+        assert!(runtime.indicators("TEST").unwrap().contains_key("fast_ma"));
+        assert_eq!(runtime.indicator_history("TEST").count(), 40);
+
+        // The journal records every bar and follows the signal through to
+        // the order
+        let kinds: Vec<&str> = TradeJournal::read(&journal_path, &Default::default())
+            .unwrap()
+            .iter()
+            .map(|entry| entry.event.kind())
+            .collect();
+        assert_eq!(kinds.iter().filter(|k| **k == "bar").count(), 40);
+        let decisions: Vec<&str> = kinds.into_iter().filter(|k| *k != "bar").collect();
+        assert_eq!(decisions, vec!["signal", "approved", "order"]);
+
+        // Shutting down cancels what is still working
+        runtime.shutdown().await.unwrap();
+        assert!(broker.get_open_orders().await.unwrap().is_empty());
+        std::fs::remove_file(&journal_path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_manual_order_checked_by_risk() {
+        let broker: Arc<dyn Broker> = Arc::new(PaperBroker::new(dec!(100000)));
+        let strategy = MACrossoverStrategy::new(MACrossoverConfig {
+            symbols: vec!["TEST".to_string()],
+            ..Default::default()
+        });
+        let pipeline = OrderPipeline::new(RiskManager::new(RiskConfig::default()));
+        let mut runtime =
+            TradingRuntime::new(Box::new(strategy), broker, pipeline, Timeframe::Daily);
+
+        // No price seen yet
+        let request = OrderRequest::market("TEST", Side::Buy, dec!(500));
+        assert!(runtime.submit_order(request.clone()).await.is_err());
+
+        // Cut to the 10% position limit at the last close
+        runtime.warmup("TEST", [Bar::new(0, 100.0, 100.0, 100.0, 100.0, 1e6)]);
+        let order = runtime.submit_order(request).await.unwrap();
+        assert_eq!(order.quantity, dec!(100));
+    }
+
+    /// Buys every bar.
+    struct AlwaysBuy {
+        symbols: Vec<String>,
+    }
+
+    impl Strategy for AlwaysBuy {
+        fn name(&self) -> &str {
+            "always_buy"
+        }
+
+        fn on_bar(&mut self, series: &BarSeries) -> Option<Signal> {
+            let bar = series.last()?;
+            Some(Signal::buy(&series.symbol, bar.close, bar.timestamp))
+        }
+
+        fn reset(&mut self) {}
+
+        fn state(&self) -> trading_core::traits::StrategyState {
+            Default::default()
+        }
+
+        fn warmup_period(&self) -> usize {
+            0
+        }
+
+        fn symbols(&self) -> &[String] {
+            &self.symbols
+        }
+    }
+
+    #[tokio::test]
+    async fn test_in_flight_order_blocks_duplicates() {
+        let paper = Arc::new(PaperBroker::new(dec!(100000)));
+        let broker: Arc<dyn Broker> = paper.clone();
+        let strategy = AlwaysBuy {
+            symbols: vec!["TEST".to_string()],
+        };
+        let pipeline = OrderPipeline::new(RiskManager::new(RiskConfig::default()));
+        let mut runtime =
+            TradingRuntime::new(Box::new(strategy), broker, pipeline, Timeframe::Daily);
+        let bar = |i: i64| Bar::new(i * 86400000, 100.0, 100.0, 100.0, 100.0, 1e6);
+
+        let StepOutcome::Submitted { order, .. } = runtime.on_bar("TEST", bar(0)).await else {
+            panic!("first signal should be submitted");
+        };
+        let outcome = runtime.on_bar("TEST", bar(1)).await;
+        assert!(
+            matches!(outcome, StepOutcome::Skipped { reason, .. } if reason.contains("in flight"))
+        );
+
+        // Partly filled orders are still in flight
+        paper.execute_partial(order.id, dec!(100), dec!(1)).unwrap();
+        let outcome = runtime.on_bar("TEST", bar(2)).await;
+        assert!(
+            matches!(outcome, StepOutcome::Skipped { reason, .. } if reason.contains("in flight"))
+        );
+
+        paper.execute_at_price(order.id, dec!(100)).unwrap();
+        let outcome = runtime.on_bar("TEST", bar(3)).await;
+        assert!(
+            matches!(outcome, StepOutcome::Skipped { reason, .. } if reason.contains("Already holding"))
+        );
+        let fills: Vec<&Order> = runtime.recent_fills().collect();
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].id, order.id);
+    }
+
+    #[tokio::test]
+    async fn test_end_of_session() {
+        let paper = Arc::new(PaperBroker::new(dec!(100000)));
+        let broker: Arc<dyn Broker> = paper.clone();
+        let strategy = AlwaysBuy {
+            symbols: vec!["TEST".to_string()],
+        };
+        let pipeline = OrderPipeline::new(RiskManager::new(RiskConfig::default()));
+        let mut runtime = TradingRuntime::new(
+            Box::new(strategy),
+            broker.clone(),
+            pipeline,
+            Timeframe::Hour1,
+        )
+        .with_session(SessionConfig {
+            close_at: chrono::NaiveTime::from_hms_opt(20, 0, 0),
+            cancel_orders: true,
+            flatten: true,
+        });
+        let hour = 3_600_000;
+
+        runtime.supervise(14 * hour).await.unwrap();
+        let bar = Bar::new(14 * hour, 100.0, 100.0, 100.0, 100.0, 1e6);
+        let StepOutcome::Submitted { order, .. } = runtime.on_bar("TEST", bar).await else {
+            panic!("signal should be submitted");
+        };
+        paper.execute_at_price(order.id, dec!(100)).unwrap();
+        let limit = |tif| {
+            OrderRequest::limit("TEST", Side::Sell, dec!(1), dec!(120)).with_time_in_force(tif)
+        };
+        broker.submit_order(limit(TimeInForce::Day)).await.unwrap();
+        let stop = broker.submit_order(limit(TimeInForce::GTC)).await.unwrap();
+
+        runtime.supervise(19 * hour).await.unwrap();
+        assert!(runtime.last_session().is_none());
+
+        // Day orders are canceled and the position closed; flattening
+        // cancels the GTC too, so it can't reopen the position
+        runtime.supervise(20 * hour + hour / 2).await.unwrap();
+        let summary = runtime.last_session().unwrap().clone();
+        assert_eq!(summary.date.to_string(), "1970-01-01");
+        assert_eq!((summary.signals, summary.orders_submitted), (1, 1));
+        assert_eq!(summary.orders_canceled, 1);
+        assert_eq!(summary.positions_flattened, 1);
+        let open = broker.get_open_orders().await.unwrap();
+        assert_eq!(open.len(), 1);
+        assert!(open.iter().all(|o| o.id != stop.id));
+        assert_eq!(
+            (open[0].order_type, open[0].side),
+            (OrderType::Market, Side::Sell)
+        );
+
+        // Nothing more happens until the next session ends
+        runtime.supervise(23 * hour).await.unwrap();
+        assert_eq!(runtime.last_session(), Some(&summary));
+    }
+
+    #[tokio::test]
+    async fn test_pause_and_dry_run() {
+        let broker: Arc<dyn Broker> = Arc::new(PaperBroker::new(dec!(100000)));
+        let strategy = AlwaysBuy {
+            symbols: vec!["TEST".to_string()],
+        };
+        let pipeline = OrderPipeline::new(RiskManager::new(RiskConfig::default()));
+        let mut runtime = TradingRuntime::new(
+            Box::new(strategy),
+            broker.clone(),
+            pipeline,
+            Timeframe::Daily,
+        )
+        .with_dry_run(true);
+        let bar = |i: i64| Bar::new(i * 86400000, 100.0, 100.0, 100.0, 100.0, 1e6);
+
+        runtime.set_paused(true);
+        let outcome = runtime.on_bar("TEST", bar(0)).await;
+        assert!(
+            matches!(outcome, StepOutcome::Skipped { reason, .. } if reason.contains("paused"))
+        );
+
+        // Dry runs go through the risk checks but send nothing
+        runtime.set_paused(false);
+        let outcome = runtime.on_bar("TEST", bar(1)).await;
+        assert!(
+            matches!(outcome, StepOutcome::Skipped { reason, .. } if reason.starts_with("Dry run: BUY"))
+        );
+        assert!(broker.get_open_orders().await.unwrap().is_empty());
+
+        runtime.set_dry_run(false);
+        let outcome = runtime.on_bar("TEST", bar(2)).await;
+        assert!(matches!(outcome, StepOutcome::Submitted { .. }));
+        runtime.cancel_all_orders().await.unwrap();
+        assert!(broker.get_open_orders().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_flatten() {
+        let paper = Arc::new(PaperBroker::new(dec!(100000)));
+        let broker: Arc<dyn Broker> = paper.clone();
+        let strategy = AlwaysBuy {
+            symbols: vec!["TEST".to_string()],
+        };
+        let pipeline = OrderPipeline::new(RiskManager::new(RiskConfig::default()))
+            .with_circuit_breaker(CircuitBreakerConfig {
+                halt_file: None,
+                ..Default::default()
+            });
+        let mut runtime = TradingRuntime::new(
+            Box::new(strategy),
+            broker.clone(),
+            pipeline,
+            Timeframe::Daily,
+        );
+        let bar = Bar::new(0, 100.0, 100.0, 100.0, 100.0, 1e6);
+        let StepOutcome::Submitted { order, .. } = runtime.on_bar("TEST", bar).await else {
+            panic!("signal should be submitted");
+        };
+        paper.execute_at_price(order.id, dec!(100)).unwrap();
+        let limit = OrderRequest::limit("TEST", Side::Sell, dec!(1), dec!(120))
+            .with_time_in_force(TimeInForce::GTC);
+        broker.submit_order(limit).await.unwrap();
+
+        // Working orders go, even GTC ones, and the position is sold
+        let orders = runtime.flatten().await.unwrap();
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0].side, Side::Sell);
+        let open = broker.get_open_orders().await.unwrap();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].id, orders[0].id);
+
+        // Tripping the circuit breaker flattens the same way
+        let limit = OrderRequest::limit("TEST", Side::Sell, dec!(1), dec!(120))
+            .with_time_in_force(TimeInForce::GTC);
+        let working = broker.submit_order(limit).await.unwrap();
+        assert!(runtime.trip_circuit_breaker("test").await);
+        let open = broker.get_open_orders().await.unwrap();
+        assert!(open.iter().all(|o| o.id != working.id));
+    }
+
+    #[test]
+    fn test_closing_pnl() {
+        let mut long = Position::new("TEST", dec!(100), dec!(50));
+        long.update_price(dec!(60));
+        assert_eq!(closing_pnl(&long, Side::Buy, dec!(10)), None);
+
+        // A partial exit realizes its share of the gain
+        assert_eq!(closing_pnl(&long, Side::Sell, dec!(25)), Some(dec!(250)));
+        assert_eq!(closing_pnl(&long, Side::Sell, dec!(100)), Some(dec!(1000)));
+        assert_eq!(closing_pnl(&long, Side::Sell, dec!(150)), Some(dec!(1000)));
+
+        let mut short = Position::new("TEST", dec!(-100), dec!(50));
+        short.update_price(dec!(60));
+        assert_eq!(closing_pnl(&short, Side::Buy, dec!(50)), Some(dec!(-500)));
+    }
+
+    #[tokio::test]
+    async fn test_enable_strategy() {
+        let broker: Arc<dyn Broker> = Arc::new(PaperBroker::new(dec!(100000)));
+        let strategy = AlwaysBuy {
+            symbols: vec!["TEST".to_string()],
+        };
+        let pipeline = OrderPipeline::new(RiskManager::new(RiskConfig::default())).with_supervisor(
+            SupervisorConfig {
+                max_consecutive_losses: Some(1),
+                ..Default::default()
+            },
+        );
+        let mut runtime =
+            TradingRuntime::new(Box::new(strategy), broker, pipeline, Timeframe::Daily);
+        let bar = |i: i64| Bar::new(i * 86400000, 100.0, 100.0, 100.0, 100.0, 1e6);
+        assert!(!runtime.enable_strategy());
+
+        runtime
+            .pipeline_mut()
+            .record_closed_trade("always_buy", dec!(-5));
+        let outcome = runtime.on_bar("TEST", bar(0)).await;
+        assert!(
+            matches!(outcome, StepOutcome::Skipped { reason, .. } if reason.contains("disabled"))
+        );
+
+        assert!(runtime.enable_strategy());
+        let outcome = runtime.on_bar("TEST", bar(1)).await;
+        assert!(matches!(outcome, StepOutcome::Submitted { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_reload_applies_at_next_bar() {
+        let broker: Arc<dyn Broker> = Arc::new(PaperBroker::new(dec!(100000)));
+        let symbols = vec!["TEST".to_string()];
+        let strategy = AlwaysBuy {
+            symbols: symbols.clone(),
+        };
+        let pipeline = OrderPipeline::new(RiskManager::new(RiskConfig::default()));
+        let mut runtime =
+            TradingRuntime::new(Box::new(strategy), broker, pipeline, Timeframe::Daily);
+        let bar = |i: i64| Bar::new(i * 86400000, 100.0, 100.0, 100.0, 100.0, 1e6);
+        runtime.on_bar("TEST", bar(0)).await;
+
+        let mut risk = RiskConfig::default();
+        risk.limits.max_position_pct = dec!(5);
+        let ma_crossover = |symbols: Vec<String>| {
+            Box::new(MACrossoverStrategy::new(MACrossoverConfig {
+                symbols,
+                ..Default::default()
+            }))
+        };
+        runtime
+            .stage_reload(Reload {
+                strategy: Some(ma_crossover(symbols)),
+                risk: Some(risk),
+            })
+            .unwrap();
+        assert!(runtime.has_pending_reload());
+        assert_eq!(runtime.strategy().name(), "always_buy");
+
+        runtime.on_bar("TEST", bar(1)).await;
+        assert!(!runtime.has_pending_reload());
+        assert_eq!(runtime.strategy().name(), "MA Crossover");
+        assert_eq!(
+            runtime
+                .pipeline()
+                .risk_manager()
+                .config()
+                .limits
+                .max_position_pct,
+            dec!(5)
+        );
+
+        // Symbols are fixed for the session
+        let other = Reload {
+            strategy: Some(ma_crossover(vec!["OTHER".to_string()])),
+            risk: None,
+        };
+        assert!(runtime.stage_reload(other).is_err());
+        assert!(!runtime.has_pending_reload());
+    }
+}
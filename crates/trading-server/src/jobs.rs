@@ -0,0 +1,231 @@
+//! Backtest jobs run in the background.
+
+use chrono::{NaiveDate, NaiveTime};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::runtime::Handle;
+use tokio::sync::{mpsc, watch};
+use tracing::{info, warn};
+use trading_backtest::{
+    BacktestConfig, BacktestEngine, BacktestProgress, BacktestReport, ProgressConfig,
+};
+use trading_core::error::{DataError, TradingError, TradingResult};
+use trading_core::types::{Bar, Timeframe};
+use trading_data::CsvDataSource;
+use trading_strategies::{StrategyRegistry, SCRIPT_PREFIX};
+
+use crate::proto::BacktestRequest;
+
+/// Progress reports sent over a whole run.
+const PROGRESS_REPORTS: usize = 100;
+
+/// How long a finished job's result can still be watched.
+const RETENTION: Duration = Duration::from_secs(3600);
+
+/// Where a backtest job has got to.
+#[derive(Debug, Clone)]
+pub enum JobState {
+    /// Running, with the latest progress once there is any
+    Running(Option<BacktestProgress>),
+    Done(Box<BacktestReport>),
+    Failed(String),
+}
+
+/// Backtests submitted to the server, run over CSV files in a data
+/// directory.
+#[derive(Clone)]
+pub struct BacktestJobs {
+    data_dir: PathBuf,
+    retention: Duration,
+    jobs: Arc<Mutex<HashMap<String, watch::Receiver<JobState>>>>,
+}
+
+impl BacktestJobs {
+    /// Create a job list loading bars from `{symbol}.csv` files in
+    /// `data_dir`.
+    pub fn new(data_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            data_dir: data_dir.into(),
+            retention: RETENTION,
+            jobs: Arc::default(),
+        }
+    }
+
+    /// Forget finished jobs `retention` after they end (an hour by default).
+    pub fn with_retention(mut self, retention: Duration) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    /// Check `request`, load its bars and start running it, returning the
+    /// job's id.
+    pub async fn submit(&self, request: BacktestRequest) -> TradingResult<String> {
+        let timeframe: Timeframe = match request.timeframe.as_str() {
+            "" => Timeframe::Daily,
+            name => name.parse().map_err(TradingError::Validation)?,
+        };
+        let config = json(&request.config_json)?;
+        // Scripts are files on the server, which clients don't get to pick
+        if loads_script(&request.strategy, &config) {
+            return Err(TradingError::Validation(
+                "Script strategies can't be backtested over gRPC".to_string(),
+            ));
+        }
+        let mut strategy = StrategyRegistry::new().create_with_overrides(
+            &request.strategy,
+            config,
+            request.symbols.clone(),
+        )?;
+        let settings = match json(&request.settings_json)? {
+            serde_json::Value::Object(settings) => settings,
+            serde_json::Value::Null => serde_json::Map::new(),
+            _ => {
+                return Err(TradingError::Validation(
+                    "settings_json must be a JSON object".to_string(),
+                ))
+            }
+        };
+        let config = BacktestConfig::from_overrides(settings)?;
+        let data = self
+            .load(&request.symbols, timeframe, &request.start, &request.end)
+            .await?;
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let (state, receiver) = watch::channel(JobState::Running(None));
+        self.jobs.lock().unwrap().insert(id.clone(), receiver);
+        info!(
+            job = %id,
+            "Backtesting {} on {}",
+            request.strategy,
+            request.symbols.join(", ")
+        );
+
+        let total: usize = data.values().map(Vec::len).sum();
+        let (progress, mut reports) = mpsc::unbounded_channel();
+        let engine = BacktestEngine::new(config)
+            .with_progress(ProgressConfig::new(progress, total / PROGRESS_REPORTS));
+        let job = id.clone();
+        let jobs = self.jobs.clone();
+        let retention = self.retention;
+        tokio::spawn(async move {
+            // The engine is CPU-bound, so it gets a thread of its own
+            let handle = Handle::current();
+            let run = tokio::task::spawn_blocking(move || {
+                handle.block_on(engine.run(strategy.as_mut(), data))
+            });
+            // Ends when the engine is dropped at the end of the run
+            while let Some(report) = reports.recv().await {
+                state.send_replace(JobState::Running(Some(report)));
+            }
+            match run.await {
+                Ok(report) => {
+                    info!(job = %job, "Backtest finished");
+                    state.send_replace(JobState::Done(Box::new(report)));
+                }
+                Err(e) => {
+                    warn!(job = %job, "Backtest failed: {}", e);
+                    state.send_replace(JobState::Failed(e.to_string()));
+                }
+            }
+            // Each result holds a full report, so they don't pile up;
+            // streams already watching still get it
+            tokio::time::sleep(retention).await;
+            jobs.lock().unwrap().remove(&job);
+        });
+        Ok(id)
+    }
+
+    /// Follow the state of job `id`.
+    pub fn watch(&self, id: &str) -> Option<watch::Receiver<JobState>> {
+        self.jobs.lock().unwrap().get(id).cloned()
+    }
+
+    /// Bars of each symbol from its CSV file, between `start` and `end`
+    /// (inclusive days) where given.
+    async fn load(
+        &self,
+        symbols: &[String],
+        timeframe: Timeframe,
+        start: &str,
+        end: &str,
+    ) -> TradingResult<HashMap<String, Vec<Bar>>> {
+        if symbols.is_empty() {
+            return Err(TradingError::Validation("No symbols given".to_string()));
+        }
+        // Symbols name files in the data directory, so no paths
+        if let Some(symbol) = symbols.iter().find(|s| !valid_symbol(s)) {
+            return Err(TradingError::Validation(format!(
+                "Invalid symbol '{}': use letters, digits, '.', '_' and '-'",
+                symbol
+            )));
+        }
+        let from = day(start)?.map(|d| d.and_time(NaiveTime::MIN).and_utc().timestamp_millis());
+        let until = day(end)?
+            .and_then(|d| d.succ_opt())
+            .map(|d| d.and_time(NaiveTime::MIN).and_utc().timestamp_millis());
+
+        let mut data = HashMap::new();
+        for symbol in symbols {
+            let path = CsvDataSource::find(&self.data_dir, symbol).ok_or_else(|| {
+                DataError::SymbolNotFound(format!(
+                    "{} (no CSV file in {})",
+                    symbol,
+                    self.data_dir.display()
+                ))
+            })?;
+            let mut bars = CsvDataSource::new(&path.to_string_lossy())?
+                .load_all(symbol, timeframe)
+                .await?;
+            bars.retain(|bar| {
+                from.map_or(true, |from| bar.timestamp >= from)
+                    && until.map_or(true, |until| bar.timestamp < until)
+            });
+            data.insert(symbol.clone(), bars);
+        }
+        Ok(data)
+    }
+}
+
+/// Whether `symbol` is safe to look up as a file name.
+fn valid_symbol(symbol: &str) -> bool {
+    !symbol.is_empty()
+        && symbol
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+}
+
+/// Whether `strategy`, or any member strategy in its `config`, is loaded
+/// from a script file.
+fn loads_script(strategy: &str, config: &serde_json::Value) -> bool {
+    strategy.starts_with(SCRIPT_PREFIX)
+        || config["members"].as_array().is_some_and(|members| {
+            members.iter().any(|member| {
+                loads_script(
+                    member["strategy"].as_str().unwrap_or_default(),
+                    &member["config"],
+                )
+            })
+        })
+}
+
+/// `text` parsed as JSON, or null when empty.
+fn json(text: &str) -> TradingResult<serde_json::Value> {
+    if text.trim().is_empty() {
+        return Ok(serde_json::Value::Null);
+    }
+    serde_json::from_str(text).map_err(|e| TradingError::Validation(format!("Invalid JSON: {}", e)))
+}
+
+/// A YYYY-MM-DD date, or none when empty.
+fn day(text: &str) -> TradingResult<Option<NaiveDate>> {
+    if text.is_empty() {
+        return Ok(None);
+    }
+    NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .map(Some)
+        .map_err(|_| {
+            TradingError::Validation(format!("Invalid date '{}', expected YYYY-MM-DD", text))
+        })
+}
@@ -0,0 +1,15 @@
+//! gRPC server: backtest jobs run in the background with their progress
+//! streamed to clients, and, inside a paper trading session, the live
+//! portfolio and equity with pause and flatten controls. For external
+//! orchestration and UIs; the service is defined in `proto/trading.proto`.
+
+mod jobs;
+mod service;
+
+/// Messages, client and server generated from `proto/trading.proto`.
+pub mod proto {
+    tonic::include_proto!("trading.v1");
+}
+
+pub use jobs::{BacktestJobs, JobState};
+pub use service::{LiveSession, TradingService};
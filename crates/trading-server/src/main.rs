@@ -0,0 +1,40 @@
+//! `trading-server`: run backtest jobs submitted over gRPC.
+//!
+//! Live control needs a trading session; see `trading paper --grpc`.
+
+use anyhow::Result;
+use clap::Parser;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use trading_monitor::setup_logging;
+use trading_server::{BacktestJobs, TradingService};
+
+#[derive(Parser)]
+#[command(
+    name = "trading-server",
+    version,
+    about = "gRPC server for backtest jobs"
+)]
+struct Args {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:50051")]
+    addr: SocketAddr,
+
+    /// Directory of `{symbol}.csv` files to backtest on
+    #[arg(short, long, default_value = "data")]
+    data: PathBuf,
+
+    /// Log level
+    #[arg(long, default_value = "info")]
+    log_level: String,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+    setup_logging(&args.log_level, false);
+    TradingService::new(BacktestJobs::new(args.data))
+        .serve(args.addr)
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,423 @@
+//! The `Trading` gRPC service.
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc::{self, UnboundedSender};
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::transport::Server;
+use tonic::{Request, Response, Status};
+use tracing::info;
+use trading_core::error::{DataError, TradingError};
+use trading_monitor::{DashboardCommand, DashboardState};
+
+use crate::jobs::{BacktestJobs, JobState};
+use crate::proto::backtest_update::Update;
+use crate::proto::trading_server::{Trading, TradingServer};
+use crate::proto::{
+    BacktestJob, BacktestProgress, BacktestRequest, BacktestResult, BacktestUpdate, ControlReply,
+    Empty, EquityPoint, Portfolio, Position, SetPausedRequest,
+};
+
+/// How often new equity samples are looked for.
+const EQUITY_POLL: Duration = Duration::from_secs(1);
+
+/// A running paper trading session: its dashboard state and the channel
+/// its trading loop takes commands from.
+#[derive(Clone)]
+pub struct LiveSession {
+    pub state: Arc<Mutex<DashboardState>>,
+    pub commands: UnboundedSender<DashboardCommand>,
+}
+
+/// Serves backtest jobs, and the live endpoints when attached to a
+/// trading session; without one they fail with `UNAVAILABLE`.
+#[derive(Clone)]
+pub struct TradingService {
+    jobs: BacktestJobs,
+    live: Option<LiveSession>,
+}
+
+// Handlers return tonic's `Status`, large as it is
+#[allow(clippy::result_large_err)]
+impl TradingService {
+    /// Create a service running backtests from `jobs`.
+    pub fn new(jobs: BacktestJobs) -> Self {
+        Self { jobs, live: None }
+    }
+
+    /// Attach a trading session for the live endpoints.
+    pub fn with_live(mut self, live: LiveSession) -> Self {
+        self.live = Some(live);
+        self
+    }
+
+    /// The service, ready to add to a tonic server.
+    pub fn into_server(self) -> TradingServer<Self> {
+        TradingServer::new(self)
+    }
+
+    /// Serve on `addr` until the task is dropped.
+    pub async fn serve(self, addr: SocketAddr) -> Result<(), tonic::transport::Error> {
+        info!("gRPC server on {}", addr);
+        Server::builder()
+            .add_service(self.into_server())
+            .serve(addr)
+            .await
+    }
+
+    fn live(&self) -> Result<&LiveSession, Status> {
+        self.live
+            .as_ref()
+            .ok_or_else(|| Status::unavailable("No trading session is attached"))
+    }
+
+    fn send(&self, command: DashboardCommand, message: &str) -> Result<ControlReply, Status> {
+        self.live()?
+            .commands
+            .send(command)
+            .map_err(|_| Status::unavailable("The trading session has stopped"))?;
+        Ok(ControlReply {
+            message: message.to_string(),
+        })
+    }
+}
+
+#[tonic::async_trait]
+impl Trading for TradingService {
+    async fn submit_backtest(
+        &self,
+        request: Request<BacktestRequest>,
+    ) -> Result<Response<BacktestJob>, Status> {
+        let id = self
+            .jobs
+            .submit(request.into_inner())
+            .await
+            .map_err(status)?;
+        Ok(Response::new(BacktestJob { id }))
+    }
+
+    type WatchBacktestStream = ReceiverStream<Result<BacktestUpdate, Status>>;
+
+    async fn watch_backtest(
+        &self,
+        request: Request<BacktestJob>,
+    ) -> Result<Response<Self::WatchBacktestStream>, Status> {
+        let id = request.into_inner().id;
+        let mut state = self
+            .jobs
+            .watch(&id)
+            .ok_or_else(|| Status::not_found(format!("No backtest job {}", id)))?;
+        let (sender, receiver) = mpsc::channel(16);
+        tokio::spawn(async move {
+            loop {
+                let (update, finished) = update(&state.borrow_and_update());
+                if let Some(update) = update {
+                    if sender.send(Ok(update)).await.is_err() {
+                        return;
+                    }
+                }
+                if finished || state.changed().await.is_err() {
+                    return;
+                }
+            }
+        });
+        Ok(Response::new(ReceiverStream::new(receiver)))
+    }
+
+    async fn get_portfolio(&self, _: Request<Empty>) -> Result<Response<Portfolio>, Status> {
+        let state = self.live()?.state.lock().unwrap();
+        let account = &state.portfolio;
+        Ok(Response::new(Portfolio {
+            strategy: state.strategy_name.clone(),
+            cash: float(account.cash),
+            equity: float(account.equity),
+            buying_power: float(account.buying_power),
+            daily_pnl: float(state.daily_pnl),
+            positions: state
+                .positions()
+                .into_iter()
+                .map(|p| Position {
+                    symbol: p.symbol.clone(),
+                    quantity: float(p.quantity),
+                    avg_entry_price: float(p.avg_entry_price),
+                    current_price: float(p.current_price),
+                    market_value: float(p.market_value),
+                    unrealized_pnl: float(p.unrealized_pnl),
+                })
+                .collect(),
+            open_orders: state.open_orders.len() as u64,
+            paused: state.paused,
+            dry_run: state.dry_run,
+            halted: state.halted.clone().unwrap_or_default(),
+        }))
+    }
+
+    type StreamEquityStream = ReceiverStream<Result<EquityPoint, Status>>;
+
+    async fn stream_equity(
+        &self,
+        _: Request<Empty>,
+    ) -> Result<Response<Self::StreamEquityStream>, Status> {
+        let state = self.live()?.state.clone();
+        let (sender, receiver) = mpsc::channel(64);
+        tokio::spawn(async move {
+            let mut poll = tokio::time::interval(EQUITY_POLL);
+            let mut last = i64::MIN;
+            loop {
+                poll.tick().await;
+                let points: Vec<EquityPoint> = state
+                    .lock()
+                    .unwrap()
+                    .equity
+                    .samples()
+                    .filter(|(timestamp, _)| *timestamp > last)
+                    .map(|(timestamp, equity)| EquityPoint { timestamp, equity })
+                    .collect();
+                for point in points {
+                    last = point.timestamp;
+                    if sender.send(Ok(point)).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+        Ok(Response::new(ReceiverStream::new(receiver)))
+    }
+
+    async fn set_paused(
+        &self,
+        request: Request<SetPausedRequest>,
+    ) -> Result<Response<ControlReply>, Status> {
+        let paused = request.into_inner().paused;
+        let message = if paused {
+            "Pausing trading"
+        } else {
+            "Resuming trading"
+        };
+        self.send(DashboardCommand::SetPaused(paused), message)
+            .map(Response::new)
+    }
+
+    async fn flatten(&self, _: Request<Empty>) -> Result<Response<ControlReply>, Status> {
+        self.send(DashboardCommand::Flatten, "Flattening all positions")
+            .map(Response::new)
+    }
+
+    async fn enable_strategy(&self, _: Request<Empty>) -> Result<Response<ControlReply>, Status> {
+        self.send(DashboardCommand::EnableStrategy, "Re-enabling the strategy")
+            .map(Response::new)
+    }
+}
+
+/// The update to send for a job's state, and whether it is the last.
+fn update(state: &JobState) -> (Option<BacktestUpdate>, bool) {
+    let update = match state {
+        JobState::Running(None) => return (None, false),
+        JobState::Running(Some(progress)) => Update::Progress(BacktestProgress {
+            bars_processed: progress.bars_processed as u64,
+            total_bars: progress.total_bars as u64,
+            timestamp: progress.timestamp,
+            equity: float(progress.equity),
+        }),
+        JobState::Done(report) => {
+            let stats = &report.stats;
+            Update::Result(BacktestResult {
+                final_equity: float(stats.final_equity),
+                total_return_pct: float(stats.total_return_pct),
+                sharpe_ratio: stats.sharpe_ratio,
+                max_drawdown_pct: float(stats.max_drawdown_pct),
+                total_trades: stats.total_trades as u64,
+                report_json: report.to_json().unwrap_or_default(),
+            })
+        }
+        JobState::Failed(error) => Update::Error(error.clone()),
+    };
+    let finished = !matches!(update, Update::Progress(_));
+    (
+        Some(BacktestUpdate {
+            update: Some(update),
+        }),
+        finished,
+    )
+}
+
+fn float(value: Decimal) -> f64 {
+    value.to_f64().unwrap_or_default()
+}
+
+/// gRPC status for a rejected backtest request.
+fn status(error: TradingError) -> Status {
+    match error {
+        TradingError::Data(DataError::SymbolNotFound(_)) => Status::not_found(error.to_string()),
+        TradingError::Config(_)
+        | TradingError::Validation(_)
+        | TradingError::Strategy(_)
+        | TradingError::Serialization(_) => Status::invalid_argument(error.to_string()),
+        _ => Status::internal(error.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::trading_client::TradingClient;
+    use rust_decimal_macros::dec;
+    use tokio::net::TcpListener;
+    use tokio_stream::wrappers::TcpListenerStream;
+    use tonic::transport::Channel;
+
+    /// Serve `service` on a free port and connect to it.
+    async fn connect(service: TradingService) -> TradingClient<Channel> {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(
+            Server::builder()
+                .add_service(service.into_server())
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+        TradingClient::connect(format!("http://{}", addr))
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_backtest_job() {
+        let dir = std::env::temp_dir().join(format!("trading-server-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let day = chrono::NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let mut csv = String::from("date,open,high,low,close,volume\n");
+        for i in 0..120 {
+            let price = 100.0 + (i as f64 * 0.3).sin() * 10.0;
+            let date = day + chrono::Duration::days(i);
+            csv += &format!(
+                "{},{},{},{},{},1000000\n",
+                date,
+                price,
+                price + 1.0,
+                price - 1.0,
+                price
+            );
+        }
+        std::fs::write(dir.join("TEST.csv"), csv).unwrap();
+        let jobs = BacktestJobs::new(&dir).with_retention(Duration::from_millis(100));
+        let mut client = connect(TradingService::new(jobs)).await;
+
+        let request = BacktestRequest {
+            strategy: "ma_crossover".to_string(),
+            symbols: vec!["TEST".to_string()],
+            config_json: r#"{"fast_period": 3, "slow_period": 8}"#.to_string(),
+            settings_json: r#"{"initial_capital": 50000}"#.to_string(),
+            start: "2024-02-01".to_string(),
+            ..Default::default()
+        };
+        let job = client
+            .submit_backtest(request.clone())
+            .await
+            .unwrap()
+            .into_inner();
+        let mut updates = client
+            .watch_backtest(job.clone())
+            .await
+            .unwrap()
+            .into_inner();
+        let mut last = None;
+        while let Some(update) = updates.message().await.unwrap() {
+            last = update.update;
+        }
+        let Some(Update::Result(result)) = last else {
+            panic!("the stream should end with the result");
+        };
+        let report: serde_json::Value = serde_json::from_str(&result.report_json).unwrap();
+        assert_eq!(report["stats"]["initial_capital"], "50000");
+        // Bars from February on
+        assert_eq!(report["stats"]["bars_processed"], 89);
+        assert!(result.total_trades > 0);
+
+        // Finished jobs are forgotten after a while
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        let error = client.watch_backtest(job).await.unwrap_err();
+        assert_eq!(error.code(), tonic::Code::NotFound);
+
+        // Requests are checked before a job starts
+        let unknown = BacktestRequest {
+            symbols: vec!["NONE".to_string()],
+            ..request.clone()
+        };
+        let error = client.submit_backtest(unknown).await.unwrap_err();
+        assert_eq!(error.code(), tonic::Code::NotFound);
+        let outside = BacktestRequest {
+            symbols: vec!["../TEST".to_string()],
+            ..request.clone()
+        };
+        let error = client.submit_backtest(outside).await.unwrap_err();
+        assert_eq!(error.code(), tonic::Code::InvalidArgument);
+        for (strategy, config_json) in [
+            ("script:/etc/passwd", ""),
+            (
+                "multi",
+                r#"{"members": [{"strategy": "script:/etc/passwd"}]}"#,
+            ),
+        ] {
+            let script = BacktestRequest {
+                strategy: strategy.to_string(),
+                config_json: config_json.to_string(),
+                ..request.clone()
+            };
+            let error = client.submit_backtest(script).await.unwrap_err();
+            assert_eq!(error.code(), tonic::Code::InvalidArgument);
+            assert!(error.message().contains("Script"));
+        }
+        let invalid = BacktestRequest {
+            settings_json: r#"{"capital": 1}"#.to_string(),
+            ..request
+        };
+        let error = client.submit_backtest(invalid).await.unwrap_err();
+        assert_eq!(error.code(), tonic::Code::InvalidArgument);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_live_control() {
+        let mut client = connect(TradingService::new(BacktestJobs::new("."))).await;
+        let error = client.flatten(Empty {}).await.unwrap_err();
+        assert_eq!(error.code(), tonic::Code::Unavailable);
+
+        let state = Arc::new(Mutex::new(DashboardState {
+            strategy_name: "RSI".to_string(),
+            paused: true,
+            ..Default::default()
+        }));
+        state.lock().unwrap().portfolio.cash = dec!(1000);
+        let (commands, mut received) = mpsc::unbounded_channel();
+        let service =
+            TradingService::new(BacktestJobs::new(".")).with_live(LiveSession { state, commands });
+        let mut client = connect(service).await;
+
+        let portfolio = client.get_portfolio(Empty {}).await.unwrap().into_inner();
+        assert_eq!(portfolio.strategy, "RSI");
+        assert_eq!(portfolio.cash, 1000.0);
+        assert!(portfolio.paused);
+
+        client
+            .set_paused(SetPausedRequest { paused: false })
+            .await
+            .unwrap();
+        client.flatten(Empty {}).await.unwrap();
+        client.enable_strategy(Empty {}).await.unwrap();
+        assert!(matches!(
+            received.recv().await,
+            Some(DashboardCommand::SetPaused(false))
+        ));
+        assert!(matches!(
+            received.recv().await,
+            Some(DashboardCommand::Flatten)
+        ));
+        assert!(matches!(
+            received.recv().await,
+            Some(DashboardCommand::EnableStrategy)
+        ));
+    }
+}
@@ -0,0 +1,15 @@
+//! Records the compiler version, which strategy plugins must match.
+
+use std::process::Command;
+
+fn main() {
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let version = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .unwrap_or_default();
+    println!("cargo:rustc-env=TRADING_RUSTC_VERSION={}", version.trim());
+    println!("cargo:rerun-if-changed=build.rs");
+}
@@ -0,0 +1,414 @@
+//! Breakout Strategy with ATR trailing stops.
+//!
+//! Enters when the close breaks out of the highest high (or lowest low) of
+//! the last `lookback` bars. Exits are volatility-based: a stop trails the
+//! close at `atr_multiplier` ATRs and only ever moves in the trade's favour.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use trading_core::{
+    error::StrategyError,
+    traits::{Strategy, StrategyConfig, StrategyState, StreamingState},
+    types::{Bar, BarSeries, MetadataSchema, Signal, SignalMetadata, SignalStrength, SignalType},
+};
+use trading_indicators::{RollingMax, RollingMin, StreamingAtr};
+
+/// Configuration for the breakout strategy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BreakoutConfig {
+    /// Symbols to trade
+    pub symbols: Vec<String>,
+    /// Bars whose high/low the close must break
+    pub lookback: usize,
+    /// ATR period
+    pub atr_period: usize,
+    /// Trailing stop distance in ATRs
+    pub atr_multiplier: f64,
+    /// Allow short positions on downside breakouts
+    pub allow_short: bool,
+}
+
+impl Default for BreakoutConfig {
+    fn default() -> Self {
+        Self {
+            symbols: vec![],
+            lookback: 20,
+            atr_period: 14,
+            atr_multiplier: 3.0,
+            allow_short: false,
+        }
+    }
+}
+
+impl StrategyConfig for BreakoutConfig {
+    fn validate(&self) -> Result<(), StrategyError> {
+        if self.lookback == 0 || self.atr_period == 0 {
+            return Err(StrategyError::InvalidConfig(
+                "Lookback and ATR periods must be positive".into(),
+            ));
+        }
+        if self.atr_multiplier <= 0.0 {
+            return Err(StrategyError::InvalidConfig(
+                "ATR multiplier must be positive".into(),
+            ));
+        }
+        if self.symbols.is_empty() {
+            return Err(StrategyError::InvalidConfig(
+                "At least one symbol required".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Position state, with the trailing stop of an open trade
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+enum PositionState {
+    #[default]
+    Flat,
+    Long {
+        stop: f64,
+    },
+    Short {
+        stop: f64,
+    },
+}
+
+/// Indicator readings of a bar.
+#[derive(Debug, Clone, Copy)]
+struct Levels {
+    /// Highest high of the bars before this one
+    breakout_high: f64,
+    /// Lowest low of the bars before this one
+    breakout_low: f64,
+    atr: f64,
+}
+
+/// Rolling channel and ATR of one symbol.
+struct Indicators {
+    highs: RollingMax,
+    lows: RollingMin,
+    atr: StreamingAtr,
+}
+
+impl Indicators {
+    fn new(lookback: usize, atr_period: usize) -> Self {
+        Self {
+            highs: RollingMax::new(lookback),
+            lows: RollingMin::new(lookback),
+            atr: StreamingAtr::new(atr_period),
+        }
+    }
+
+    /// Add a bar; returns its ATR and the channel of the bars before it.
+    fn update(&mut self, bar: &Bar) -> Option<Levels> {
+        let channel = self
+            .highs
+            .is_full()
+            .then(|| self.highs.get().zip(self.lows.get()))
+            .flatten();
+        self.highs.push(bar.high);
+        self.lows.push(bar.low);
+        let atr = self.atr.update_ohlc(bar.high, bar.low, bar.close);
+
+        let (breakout_high, breakout_low) = channel?;
+        Some(Levels {
+            breakout_high,
+            breakout_low,
+            atr: atr?,
+        })
+    }
+}
+
+/// N-bar breakout strategy with ATR trailing stops.
+pub struct BreakoutStrategy {
+    config: BreakoutConfig,
+    indicators: StreamingState<Indicators>,
+    positions: HashMap<String, PositionState>,
+    /// Latest readings of any symbol, for monitoring
+    last_levels: Option<Levels>,
+    bars_processed: usize,
+    signals_generated: usize,
+}
+
+impl BreakoutStrategy {
+    /// Indicator keys of the signals this strategy emits.
+    pub fn signal_schema() -> MetadataSchema {
+        MetadataSchema::new(
+            &["breakout_high", "breakout_low", "atr", "trailing_stop"],
+            &[],
+        )
+    }
+
+    /// Create a new breakout strategy.
+    pub fn new(config: BreakoutConfig) -> Self {
+        Self {
+            config,
+            indicators: StreamingState::new(),
+            positions: HashMap::new(),
+            last_levels: None,
+            bars_processed: 0,
+            signals_generated: 0,
+        }
+    }
+
+    fn create_signal(
+        &self,
+        bar: &Bar,
+        symbol: &str,
+        signal_type: SignalType,
+        levels: &Levels,
+        stop: f64,
+        reason: String,
+    ) -> Signal {
+        Signal {
+            symbol: symbol.to_string(),
+            signal_type,
+            strength: SignalStrength::Moderate,
+            price: bar.close,
+            timestamp: bar.timestamp,
+            confidence: 0.6,
+            fraction: None,
+            metadata: SignalMetadata {
+                strategy_name: self.name().to_string(),
+                indicators: [
+                    ("breakout_high".to_string(), levels.breakout_high),
+                    ("breakout_low".to_string(), levels.breakout_low),
+                    ("atr".to_string(), levels.atr),
+                    ("trailing_stop".to_string(), stop),
+                ]
+                .into_iter()
+                .collect(),
+                reason,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl Strategy for BreakoutStrategy {
+    fn name(&self) -> &str {
+        "Breakout"
+    }
+
+    fn description(&self) -> &str {
+        "N-bar high/low breakouts with ATR trailing stops"
+    }
+
+    fn metadata_schema(&self) -> Option<MetadataSchema> {
+        Some(Self::signal_schema())
+    }
+
+    fn on_bar(&mut self, series: &BarSeries) -> Option<Signal> {
+        self.bars_processed += 1;
+
+        let (lookback, atr_period) = (self.config.lookback, self.config.atr_period);
+        let levels = self.indicators.update(
+            series,
+            || Indicators::new(lookback, atr_period),
+            Indicators::update,
+        )??;
+        self.last_levels = Some(levels);
+        let bar = series.last()?;
+        let distance = self.config.atr_multiplier * levels.atr;
+        let position = self
+            .positions
+            .get(&series.symbol)
+            .copied()
+            .unwrap_or_default();
+
+        let (next, signal) = match position {
+            PositionState::Flat if bar.close > levels.breakout_high => {
+                let stop = bar.close - distance;
+                let reason = format!(
+                    "Close {:.2} broke above the {}-bar high {:.2}; stop {:.2}",
+                    bar.close, lookback, levels.breakout_high, stop
+                );
+                (
+                    PositionState::Long { stop },
+                    Some((SignalType::Buy, stop, reason)),
+                )
+            }
+            PositionState::Flat if self.config.allow_short && bar.close < levels.breakout_low => {
+                let stop = bar.close + distance;
+                let reason = format!(
+                    "Close {:.2} broke below the {}-bar low {:.2}; stop {:.2}",
+                    bar.close, lookback, levels.breakout_low, stop
+                );
+                (
+                    PositionState::Short { stop },
+                    Some((SignalType::Sell, stop, reason)),
+                )
+            }
+            PositionState::Long { stop } if bar.close < stop => {
+                let reason = format!(
+                    "Close {:.2} fell below the trailing stop {:.2}",
+                    bar.close, stop
+                );
+                (
+                    PositionState::Flat,
+                    Some((SignalType::CloseLong, stop, reason)),
+                )
+            }
+            PositionState::Short { stop } if bar.close > stop => {
+                let reason = format!(
+                    "Close {:.2} rose above the trailing stop {:.2}",
+                    bar.close, stop
+                );
+                (
+                    PositionState::Flat,
+                    Some((SignalType::CloseShort, stop, reason)),
+                )
+            }
+            // Trail the stop, never loosening it
+            PositionState::Long { stop } => (
+                PositionState::Long {
+                    stop: stop.max(bar.close - distance),
+                },
+                None,
+            ),
+            PositionState::Short { stop } => (
+                PositionState::Short {
+                    stop: stop.min(bar.close + distance),
+                },
+                None,
+            ),
+            PositionState::Flat => (PositionState::Flat, None),
+        };
+
+        self.positions.insert(series.symbol.clone(), next);
+        let (signal_type, stop, reason) = signal?;
+        self.signals_generated += 1;
+        Some(self.create_signal(bar, &series.symbol, signal_type, &levels, stop, reason))
+    }
+
+    fn reset(&mut self) {
+        self.indicators.clear();
+        self.positions.clear();
+        self.last_levels = None;
+        self.bars_processed = 0;
+        self.signals_generated = 0;
+    }
+
+    fn state(&self) -> StrategyState {
+        let indicators = self
+            .last_levels
+            .map(|l| {
+                [
+                    ("breakout_high".to_string(), l.breakout_high),
+                    ("breakout_low".to_string(), l.breakout_low),
+                    ("atr".to_string(), l.atr),
+                ]
+                .into_iter()
+                .collect()
+            })
+            .unwrap_or_default();
+
+        StrategyState {
+            name: self.name().to_string(),
+            is_warmed_up: self.bars_processed >= self.warmup_period(),
+            bars_processed: self.bars_processed,
+            signals_generated: self.signals_generated,
+            indicators,
+            custom: serde_json::json!({
+                "positions": self
+                    .positions
+                    .iter()
+                    .map(|(symbol, p)| (symbol.clone(), format!("{:?}", p)))
+                    .collect::<HashMap<_, _>>(),
+                "lookback": self.config.lookback,
+                "atr_multiplier": self.config.atr_multiplier,
+            }),
+        }
+    }
+
+    fn warmup_period(&self) -> usize {
+        (self.config.lookback + 1).max(self.config.atr_period + 1)
+    }
+
+    fn symbols(&self) -> &[String] {
+        &self.config.symbols
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use trading_core::types::Timeframe;
+
+    fn run(strategy: &mut BreakoutStrategy, closes: &[f64]) -> Vec<(usize, SignalType, f64)> {
+        let mut series = BarSeries::new("TEST".to_string(), Timeframe::Daily);
+        let mut signals = Vec::new();
+        for (i, &close) in closes.iter().enumerate() {
+            series.push(Bar::new(
+                i as i64 * 86_400_000,
+                close,
+                close + 0.5,
+                close - 0.5,
+                close,
+                1_000.0,
+            ));
+            if let Some(signal) = strategy.on_bar(&series) {
+                let stop = signal.metadata.indicators["trailing_stop"];
+                signals.push((i, signal.signal_type, stop));
+            }
+        }
+        signals
+    }
+
+    #[test]
+    fn test_breakout_with_trailing_stop() {
+        let mut strategy = BreakoutStrategy::new(BreakoutConfig {
+            symbols: vec!["TEST".to_string()],
+            lookback: 5,
+            atr_period: 3,
+            atr_multiplier: 2.0,
+            allow_short: false,
+        });
+
+        // Range-bound, a breakout that holds while volatility fades, then a
+        // drop through the stop trailed up behind it
+        let closes = [
+            100.0, 100.0, 100.0, 100.0, 100.0, 100.0, 101.0, 101.0, 101.0, 101.0, 101.0, 101.0,
+            98.0,
+        ];
+        let signals = run(&mut strategy, &closes);
+
+        assert_eq!(signals.len(), 2);
+        assert_eq!(signals[0].0, 6);
+        assert_eq!(signals[0].1, SignalType::Buy);
+        assert!(signals[0].2 < 101.0);
+        assert_eq!(signals[1].0, 12);
+        assert_eq!(signals[1].1, SignalType::CloseLong);
+        assert!(signals[1].2 > signals[0].2);
+    }
+
+    #[test]
+    fn test_short_breakout_and_config_validation() {
+        let config = BreakoutConfig {
+            symbols: vec!["TEST".to_string()],
+            lookback: 5,
+            atr_period: 3,
+            atr_multiplier: 2.0,
+            allow_short: true,
+        };
+        assert!(config.validate().is_ok());
+        assert!(BreakoutConfig {
+            atr_multiplier: 0.0,
+            ..config.clone()
+        }
+        .validate()
+        .is_err());
+
+        let mut strategy = BreakoutStrategy::new(config);
+        let closes = [100.0, 100.0, 100.0, 100.0, 100.0, 100.0, 99.0, 99.0, 103.0];
+        let signals: Vec<_> = run(&mut strategy, &closes)
+            .into_iter()
+            .map(|(i, t, _)| (i, t))
+            .collect();
+        assert_eq!(
+            signals,
+            vec![(6, SignalType::Sell), (8, SignalType::CloseShort)]
+        );
+    }
+}
@@ -0,0 +1,1026 @@
+//! Strategy composition from entry, filter and exit components.
+//!
+//! Instead of writing a new [`Strategy`] for every idea, a strategy can be
+//! assembled from reusable parts:
+//!
+//! ```
+//! use trading_strategies::{atr_trailing, ma_cross, trend_above_ema, StrategyBuilder};
+//!
+//! let strategy = StrategyBuilder::entry(ma_cross(20, 50))
+//!     .filter(trend_above_ema(200))
+//!     .exit(atr_trailing(3.0))
+//!     .with_name("Trend-filtered crossover")
+//!     .build(vec!["AAPL".to_string()]);
+//! ```
+//!
+//! On each bar the entry proposes a direction; the position is opened if
+//! every filter allows it. While in a position, the first exit that fires
+//! closes it, as does an entry in the opposite direction. Filters never
+//! block exits.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use trading_core::{
+    traits::{Strategy, StrategyState, StreamingIndicator, StreamingState},
+    types::{Bar, BarSeries, MetadataSchema, Signal, SignalMetadata, SignalStrength, SignalType},
+};
+use trading_indicators::{
+    RollingMax, RollingMin, StreamingAtr, StreamingEma, StreamingRsi, StreamingSma,
+};
+
+/// Direction of a position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    /// Long position
+    Long,
+    /// Short position
+    Short,
+}
+
+impl Direction {
+    /// The other direction.
+    pub fn opposite(self) -> Self {
+        match self {
+            Direction::Long => Direction::Short,
+            Direction::Short => Direction::Long,
+        }
+    }
+}
+
+impl std::fmt::Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Direction::Long => write!(f, "long"),
+            Direction::Short => write!(f, "short"),
+        }
+    }
+}
+
+/// Decides when to open a position.
+///
+/// Components keep their own streaming indicators and see each bar of a
+/// symbol exactly once; a composed strategy clones them per symbol.
+pub trait Entry: Send + Sync + EntryClone {
+    /// Consume a bar; returns the direction to enter on it, if any.
+    fn update(&mut self, bar: &Bar) -> Option<Direction>;
+
+    /// Latest indicator readings, keyed as in [`keys`](Entry::keys).
+    fn readings(&self) -> Vec<(String, f64)>;
+
+    /// Indicator keys this component reports.
+    fn keys(&self) -> Vec<String>;
+
+    /// Bars needed before the entry can fire.
+    fn warmup(&self) -> usize;
+
+    /// Short description, e.g. `ma_cross(20, 50)`.
+    fn describe(&self) -> String;
+}
+
+/// Gates entries; a position opens only if every filter allows it.
+pub trait Filter: Send + Sync + FilterClone {
+    /// Consume a bar.
+    fn update(&mut self, bar: &Bar);
+
+    /// Whether an entry in `direction` is allowed on the last bar.
+    fn allows(&self, direction: Direction) -> bool;
+
+    /// Latest indicator readings, keyed as in [`keys`](Filter::keys).
+    fn readings(&self) -> Vec<(String, f64)>;
+
+    /// Indicator keys this component reports.
+    fn keys(&self) -> Vec<String>;
+
+    /// Bars needed before the filter allows anything.
+    fn warmup(&self) -> usize;
+
+    /// Short description, e.g. `trend_above_ema(200)`.
+    fn describe(&self) -> String;
+}
+
+/// Decides when to close a position.
+pub trait Exit: Send + Sync + ExitClone {
+    /// A position was opened on `bar`.
+    fn enter(&mut self, direction: Direction, bar: &Bar);
+
+    /// Consume a bar, with the open position if any; returns the reason to
+    /// close the position on it.
+    fn update(&mut self, bar: &Bar, position: Option<Direction>) -> Option<String>;
+
+    /// Latest indicator readings, keyed as in [`keys`](Exit::keys).
+    fn readings(&self) -> Vec<(String, f64)>;
+
+    /// Indicator keys this component reports while in a position.
+    fn keys(&self) -> Vec<String>;
+
+    /// Bars needed before the exit can fire.
+    fn warmup(&self) -> usize {
+        0
+    }
+
+    /// Short description, e.g. `atr_trailing(3)`.
+    fn describe(&self) -> String;
+}
+
+/// Boxed cloning of entries; implemented for every `Entry + Clone`.
+pub trait EntryClone {
+    /// Clone into a box.
+    fn clone_box(&self) -> Box<dyn Entry>;
+}
+
+impl<T: Entry + Clone + 'static> EntryClone for T {
+    fn clone_box(&self) -> Box<dyn Entry> {
+        Box::new(self.clone())
+    }
+}
+
+/// Boxed cloning of filters; implemented for every `Filter + Clone`.
+pub trait FilterClone {
+    /// Clone into a box.
+    fn clone_box(&self) -> Box<dyn Filter>;
+}
+
+impl<T: Filter + Clone + 'static> FilterClone for T {
+    fn clone_box(&self) -> Box<dyn Filter> {
+        Box::new(self.clone())
+    }
+}
+
+/// Boxed cloning of exits; implemented for every `Exit + Clone`.
+pub trait ExitClone {
+    /// Clone into a box.
+    fn clone_box(&self) -> Box<dyn Exit>;
+}
+
+impl<T: Exit + Clone + 'static> ExitClone for T {
+    fn clone_box(&self) -> Box<dyn Exit> {
+        Box::new(self.clone())
+    }
+}
+
+/// Enter on a crossover of two simple moving averages.
+pub fn ma_cross(fast: usize, slow: usize) -> MaCross {
+    assert!(fast > 0 && fast < slow, "fast period must be below slow");
+    MaCross {
+        fast_period: fast,
+        slow_period: slow,
+        fast: StreamingSma::new(fast),
+        slow: StreamingSma::new(slow),
+        last: None,
+    }
+}
+
+/// Enter when the close breaks the high or low of the last `lookback` bars.
+pub fn channel_breakout(lookback: usize) -> ChannelBreakout {
+    assert!(lookback > 0, "lookback must be positive");
+    ChannelBreakout {
+        lookback,
+        highs: RollingMax::new(lookback),
+        lows: RollingMin::new(lookback),
+        channel: None,
+    }
+}
+
+/// Enter when RSI recovers above `oversold` (long) or falls back below
+/// `overbought` (short).
+pub fn rsi_reversal(period: usize, oversold: f64, overbought: f64) -> RsiReversal {
+    assert!(period > 0, "RSI period must be positive");
+    assert!(oversold < overbought, "oversold must be below overbought");
+    RsiReversal {
+        period,
+        oversold,
+        overbought,
+        rsi: StreamingRsi::new(period),
+        last: None,
+    }
+}
+
+/// Only trade with the trend: longs above the `period` EMA, shorts below.
+pub fn trend_above_ema(period: usize) -> TrendFilter {
+    assert!(period > 0, "EMA period must be positive");
+    TrendFilter {
+        period,
+        ema: StreamingEma::new(period),
+        last: None,
+    }
+}
+
+/// Only enter while the 14-bar ATR is at most `max_pct` percent of the close.
+pub fn volatility_below(max_pct: f64) -> VolatilityFilter {
+    assert!(max_pct > 0.0, "maximum ATR percentage must be positive");
+    VolatilityFilter {
+        max_pct,
+        atr: StreamingAtr::new(14),
+        atr_pct: None,
+    }
+}
+
+/// Exit on a stop trailing the close at `multiplier` 14-bar ATRs.
+pub fn atr_trailing(multiplier: f64) -> AtrTrailing {
+    assert!(multiplier > 0.0, "ATR multiplier must be positive");
+    AtrTrailing {
+        period: 14,
+        multiplier,
+        atr: StreamingAtr::new(14),
+        last_atr: None,
+        stop: None,
+    }
+}
+
+/// Exit when the close moves `pct` percent against the entry price.
+pub fn stop_loss_pct(pct: f64) -> PriceExit {
+    assert!(pct > 0.0, "stop distance must be positive");
+    PriceExit {
+        pct: -pct,
+        level: None,
+    }
+}
+
+/// Exit when the close moves `pct` percent in favour of the entry price.
+pub fn take_profit_pct(pct: f64) -> PriceExit {
+    assert!(pct > 0.0, "target distance must be positive");
+    PriceExit { pct, level: None }
+}
+
+/// Exit after holding for `bars` bars.
+pub fn max_bars(bars: usize) -> MaxBars {
+    assert!(bars > 0, "holding period must be positive");
+    MaxBars { bars, held: 0 }
+}
+
+/// Moving average crossover entry; see [`ma_cross`].
+#[derive(Debug, Clone)]
+pub struct MaCross {
+    fast_period: usize,
+    slow_period: usize,
+    fast: StreamingSma,
+    slow: StreamingSma,
+    /// Latest (fast, slow) averages
+    last: Option<(f64, f64)>,
+}
+
+impl Entry for MaCross {
+    fn update(&mut self, bar: &Bar) -> Option<Direction> {
+        let fast = self.fast.update(bar.close);
+        let slow = self.slow.update(bar.close);
+        let previous = self.last;
+        self.last = fast.zip(slow);
+
+        let (prev_fast, prev_slow) = previous?;
+        let (fast, slow) = self.last?;
+        if prev_fast <= prev_slow && fast > slow {
+            Some(Direction::Long)
+        } else if prev_fast >= prev_slow && fast < slow {
+            Some(Direction::Short)
+        } else {
+            None
+        }
+    }
+
+    fn readings(&self) -> Vec<(String, f64)> {
+        self.last
+            .map(|(fast, slow)| vec![("fast_ma".to_string(), fast), ("slow_ma".to_string(), slow)])
+            .unwrap_or_default()
+    }
+
+    fn keys(&self) -> Vec<String> {
+        vec!["fast_ma".to_string(), "slow_ma".to_string()]
+    }
+
+    fn warmup(&self) -> usize {
+        self.slow_period + 1
+    }
+
+    fn describe(&self) -> String {
+        format!("ma_cross({}, {})", self.fast_period, self.slow_period)
+    }
+}
+
+/// Channel breakout entry; see [`channel_breakout`].
+#[derive(Debug, Clone)]
+pub struct ChannelBreakout {
+    lookback: usize,
+    highs: RollingMax,
+    lows: RollingMin,
+    /// High and low of the bars before the latest one
+    channel: Option<(f64, f64)>,
+}
+
+impl Entry for ChannelBreakout {
+    fn update(&mut self, bar: &Bar) -> Option<Direction> {
+        self.channel = self
+            .highs
+            .is_full()
+            .then(|| self.highs.get().zip(self.lows.get()))
+            .flatten();
+        self.highs.push(bar.high);
+        self.lows.push(bar.low);
+
+        let (high, low) = self.channel?;
+        if bar.close > high {
+            Some(Direction::Long)
+        } else if bar.close < low {
+            Some(Direction::Short)
+        } else {
+            None
+        }
+    }
+
+    fn readings(&self) -> Vec<(String, f64)> {
+        self.channel
+            .map(|(high, low)| {
+                vec![
+                    ("channel_high".to_string(), high),
+                    ("channel_low".to_string(), low),
+                ]
+            })
+            .unwrap_or_default()
+    }
+
+    fn keys(&self) -> Vec<String> {
+        vec!["channel_high".to_string(), "channel_low".to_string()]
+    }
+
+    fn warmup(&self) -> usize {
+        self.lookback + 1
+    }
+
+    fn describe(&self) -> String {
+        format!("channel_breakout({})", self.lookback)
+    }
+}
+
+/// RSI reversal entry; see [`rsi_reversal`].
+#[derive(Debug, Clone)]
+pub struct RsiReversal {
+    period: usize,
+    oversold: f64,
+    overbought: f64,
+    rsi: StreamingRsi,
+    last: Option<f64>,
+}
+
+impl Entry for RsiReversal {
+    fn update(&mut self, bar: &Bar) -> Option<Direction> {
+        let previous = self.last;
+        self.last = self.rsi.update(bar.close);
+
+        let (previous, rsi) = (previous?, self.last?);
+        if previous < self.oversold && rsi >= self.oversold {
+            Some(Direction::Long)
+        } else if previous > self.overbought && rsi <= self.overbought {
+            Some(Direction::Short)
+        } else {
+            None
+        }
+    }
+
+    fn readings(&self) -> Vec<(String, f64)> {
+        self.last
+            .map(|rsi| vec![("rsi".to_string(), rsi)])
+            .unwrap_or_default()
+    }
+
+    fn keys(&self) -> Vec<String> {
+        vec!["rsi".to_string()]
+    }
+
+    fn warmup(&self) -> usize {
+        self.period + 2
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "rsi_reversal({}, {}, {})",
+            self.period, self.oversold, self.overbought
+        )
+    }
+}
+
+/// EMA trend filter; see [`trend_above_ema`].
+#[derive(Debug, Clone)]
+pub struct TrendFilter {
+    period: usize,
+    ema: StreamingEma,
+    /// Latest (close, EMA)
+    last: Option<(f64, f64)>,
+}
+
+impl TrendFilter {
+    fn key(&self) -> String {
+        format!("ema_{}", self.period)
+    }
+}
+
+impl Filter for TrendFilter {
+    fn update(&mut self, bar: &Bar) {
+        self.last = self.ema.update(bar.close).map(|ema| (bar.close, ema));
+    }
+
+    fn allows(&self, direction: Direction) -> bool {
+        self.last.is_some_and(|(close, ema)| match direction {
+            Direction::Long => close > ema,
+            Direction::Short => close < ema,
+        })
+    }
+
+    fn readings(&self) -> Vec<(String, f64)> {
+        self.last
+            .map(|(_, ema)| vec![(self.key(), ema)])
+            .unwrap_or_default()
+    }
+
+    fn keys(&self) -> Vec<String> {
+        vec![self.key()]
+    }
+
+    fn warmup(&self) -> usize {
+        self.period
+    }
+
+    fn describe(&self) -> String {
+        format!("trend_above_ema({})", self.period)
+    }
+}
+
+/// Volatility ceiling; see [`volatility_below`].
+#[derive(Debug, Clone)]
+pub struct VolatilityFilter {
+    max_pct: f64,
+    atr: StreamingAtr,
+    /// Latest ATR as a percentage of the close
+    atr_pct: Option<f64>,
+}
+
+impl Filter for VolatilityFilter {
+    fn update(&mut self, bar: &Bar) {
+        self.atr_pct = self
+            .atr
+            .update_ohlc(bar.high, bar.low, bar.close)
+            .filter(|_| bar.close > 0.0)
+            .map(|atr| atr / bar.close * 100.0);
+    }
+
+    fn allows(&self, _direction: Direction) -> bool {
+        self.atr_pct.is_some_and(|pct| pct <= self.max_pct)
+    }
+
+    fn readings(&self) -> Vec<(String, f64)> {
+        self.atr_pct
+            .map(|pct| vec![("atr_pct".to_string(), pct)])
+            .unwrap_or_default()
+    }
+
+    fn keys(&self) -> Vec<String> {
+        vec!["atr_pct".to_string()]
+    }
+
+    fn warmup(&self) -> usize {
+        15
+    }
+
+    fn describe(&self) -> String {
+        format!("volatility_below({})", self.max_pct)
+    }
+}
+
+/// ATR trailing stop; see [`atr_trailing`].
+#[derive(Debug, Clone)]
+pub struct AtrTrailing {
+    period: usize,
+    multiplier: f64,
+    atr: StreamingAtr,
+    last_atr: Option<f64>,
+    stop: Option<f64>,
+}
+
+impl Exit for AtrTrailing {
+    fn enter(&mut self, direction: Direction, bar: &Bar) {
+        let distance = self.last_atr.map(|atr| self.multiplier * atr);
+        self.stop = distance.map(|d| match direction {
+            Direction::Long => bar.close - d,
+            Direction::Short => bar.close + d,
+        });
+    }
+
+    fn update(&mut self, bar: &Bar, position: Option<Direction>) -> Option<String> {
+        self.last_atr = self.atr.update_ohlc(bar.high, bar.low, bar.close);
+        let Some(direction) = position else {
+            self.stop = None;
+            return None;
+        };
+        let distance = self.multiplier * self.last_atr?;
+        let trailed = match direction {
+            Direction::Long => bar.close - distance,
+            Direction::Short => bar.close + distance,
+        };
+        let Some(stop) = self.stop else {
+            self.stop = Some(trailed);
+            return None;
+        };
+
+        // Trail the stop, never loosening it
+        match direction {
+            Direction::Long if bar.close < stop => Some(format!(
+                "Close {:.2} fell below the trailing stop {:.2}",
+                bar.close, stop
+            )),
+            Direction::Short if bar.close > stop => Some(format!(
+                "Close {:.2} rose above the trailing stop {:.2}",
+                bar.close, stop
+            )),
+            Direction::Long => {
+                self.stop = Some(stop.max(trailed));
+                None
+            }
+            Direction::Short => {
+                self.stop = Some(stop.min(trailed));
+                None
+            }
+        }
+    }
+
+    fn readings(&self) -> Vec<(String, f64)> {
+        let mut readings = Vec::new();
+        if let Some(atr) = self.last_atr {
+            readings.push(("atr".to_string(), atr));
+        }
+        if let Some(stop) = self.stop {
+            readings.push(("trailing_stop".to_string(), stop));
+        }
+        readings
+    }
+
+    fn keys(&self) -> Vec<String> {
+        vec!["atr".to_string(), "trailing_stop".to_string()]
+    }
+
+    fn warmup(&self) -> usize {
+        self.period + 1
+    }
+
+    fn describe(&self) -> String {
+        format!("atr_trailing({})", self.multiplier)
+    }
+}
+
+/// Fixed stop-loss or take-profit; see [`stop_loss_pct`] and
+/// [`take_profit_pct`].
+#[derive(Debug, Clone)]
+pub struct PriceExit {
+    /// Distance from the entry in percent, negative for stops
+    pct: f64,
+    level: Option<f64>,
+}
+
+impl PriceExit {
+    fn key(&self) -> String {
+        if self.pct < 0.0 {
+            "stop_loss".to_string()
+        } else {
+            "take_profit".to_string()
+        }
+    }
+}
+
+impl Exit for PriceExit {
+    fn enter(&mut self, direction: Direction, bar: &Bar) {
+        let pct = match direction {
+            Direction::Long => self.pct,
+            Direction::Short => -self.pct,
+        };
+        self.level = Some(bar.close * (1.0 + pct / 100.0));
+    }
+
+    fn update(&mut self, bar: &Bar, position: Option<Direction>) -> Option<String> {
+        let Some(direction) = position else {
+            self.level = None;
+            return None;
+        };
+        let level = self.level?;
+        // Stops fire on the way down for longs, targets on the way up
+        let below = (self.pct < 0.0) == (direction == Direction::Long);
+        let hit = if below {
+            bar.close <= level
+        } else {
+            bar.close >= level
+        };
+        hit.then(|| {
+            format!(
+                "Close {:.2} reached the {} at {:.2}",
+                bar.close,
+                self.key().replace('_', "-"),
+                level
+            )
+        })
+    }
+
+    fn readings(&self) -> Vec<(String, f64)> {
+        self.level
+            .map(|level| vec![(self.key(), level)])
+            .unwrap_or_default()
+    }
+
+    fn keys(&self) -> Vec<String> {
+        vec![self.key()]
+    }
+
+    fn describe(&self) -> String {
+        if self.pct < 0.0 {
+            format!("stop_loss_pct({})", -self.pct)
+        } else {
+            format!("take_profit_pct({})", self.pct)
+        }
+    }
+}
+
+/// Time exit; see [`max_bars`].
+#[derive(Debug, Clone)]
+pub struct MaxBars {
+    bars: usize,
+    held: usize,
+}
+
+impl Exit for MaxBars {
+    fn enter(&mut self, _direction: Direction, _bar: &Bar) {
+        self.held = 0;
+    }
+
+    fn update(&mut self, _bar: &Bar, position: Option<Direction>) -> Option<String> {
+        if position.is_none() {
+            self.held = 0;
+            return None;
+        }
+        self.held += 1;
+        (self.held >= self.bars).then(|| format!("Held for {} bars", self.held))
+    }
+
+    fn readings(&self) -> Vec<(String, f64)> {
+        vec![("bars_held".to_string(), self.held as f64)]
+    }
+
+    fn keys(&self) -> Vec<String> {
+        vec!["bars_held".to_string()]
+    }
+
+    fn describe(&self) -> String {
+        format!("max_bars({})", self.bars)
+    }
+}
+
+/// Assembles a [`ComposedStrategy`] from an entry, filters and exits.
+pub struct StrategyBuilder {
+    name: String,
+    entry: Box<dyn Entry>,
+    filters: Vec<Box<dyn Filter>>,
+    exits: Vec<Box<dyn Exit>>,
+    allow_short: bool,
+}
+
+impl StrategyBuilder {
+    /// Start a strategy that enters on `entry`.
+    pub fn entry(entry: impl Entry + 'static) -> Self {
+        Self {
+            name: "Composed".to_string(),
+            entry: Box::new(entry),
+            filters: Vec::new(),
+            exits: Vec::new(),
+            allow_short: false,
+        }
+    }
+
+    /// Add a filter every entry must pass.
+    pub fn filter(mut self, filter: impl Filter + 'static) -> Self {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    /// Add an exit; the first one to fire closes the position.
+    pub fn exit(mut self, exit: impl Exit + 'static) -> Self {
+        self.exits.push(Box::new(exit));
+        self
+    }
+
+    /// Set the strategy name.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Allow short entries (default: long only).
+    pub fn with_allow_short(mut self, allow_short: bool) -> Self {
+        self.allow_short = allow_short;
+        self
+    }
+
+    /// Build the strategy for `symbols`.
+    pub fn build(self, symbols: Vec<String>) -> ComposedStrategy {
+        let mut description = self.entry.describe();
+        for filter in &self.filters {
+            description.push_str(&format!(" + {}", filter.describe()));
+        }
+        for exit in &self.exits {
+            description.push_str(&format!(" -> {}", exit.describe()));
+        }
+
+        ComposedStrategy {
+            name: self.name,
+            description,
+            symbols,
+            allow_short: self.allow_short,
+            prototype: Components {
+                entry: self.entry,
+                filters: self.filters,
+                exits: self.exits,
+                position: None,
+            },
+            states: StreamingState::new(),
+            bars_processed: 0,
+            signals_generated: 0,
+        }
+    }
+}
+
+/// Components of one symbol, with its open position.
+struct Components {
+    entry: Box<dyn Entry>,
+    filters: Vec<Box<dyn Filter>>,
+    exits: Vec<Box<dyn Exit>>,
+    position: Option<Direction>,
+}
+
+impl Clone for Components {
+    fn clone(&self) -> Self {
+        Self {
+            entry: self.entry.clone_box(),
+            filters: self.filters.iter().map(|f| f.clone_box()).collect(),
+            exits: self.exits.iter().map(|e| e.clone_box()).collect(),
+            position: self.position,
+        }
+    }
+}
+
+impl Components {
+    /// Consume a bar; returns the signal to emit on it with its reason.
+    fn update(&mut self, bar: &Bar, allow_short: bool) -> Option<(SignalType, String)> {
+        let entry = self.entry.update(bar);
+        for filter in &mut self.filters {
+            filter.update(bar);
+        }
+        let position = self.position;
+        let mut exit = None;
+        for component in &mut self.exits {
+            let reason = component.update(bar, position);
+            exit = exit.or(reason);
+        }
+
+        if let Some(direction) = position {
+            let reason = exit.or_else(|| {
+                (entry == Some(direction.opposite()))
+                    .then(|| format!("{} entry against the position", self.entry.describe()))
+            })?;
+            self.position = None;
+            let signal_type = match direction {
+                Direction::Long => SignalType::CloseLong,
+                Direction::Short => SignalType::CloseShort,
+            };
+            return Some((signal_type, reason));
+        }
+
+        let direction = entry.filter(|d| *d == Direction::Long || allow_short)?;
+        if !self.filters.iter().all(|f| f.allows(direction)) {
+            return None;
+        }
+        for component in &mut self.exits {
+            component.enter(direction, bar);
+        }
+        self.position = Some(direction);
+        let mut reason = format!("{} entered {}", self.entry.describe(), direction);
+        if !self.filters.is_empty() {
+            let filters: Vec<String> = self.filters.iter().map(|f| f.describe()).collect();
+            reason.push_str(&format!(", passing {}", filters.join(", ")));
+        }
+        let signal_type = match direction {
+            Direction::Long => SignalType::Buy,
+            Direction::Short => SignalType::Sell,
+        };
+        Some((signal_type, reason))
+    }
+
+    fn readings(&self) -> HashMap<String, f64> {
+        let mut readings = self.entry.readings();
+        for filter in &self.filters {
+            readings.extend(filter.readings());
+        }
+        for exit in &self.exits {
+            readings.extend(exit.readings());
+        }
+        readings.into_iter().collect()
+    }
+}
+
+/// Strategy assembled by a [`StrategyBuilder`].
+pub struct ComposedStrategy {
+    name: String,
+    description: String,
+    symbols: Vec<String>,
+    allow_short: bool,
+    /// Fresh components, cloned for each symbol
+    prototype: Components,
+    states: StreamingState<Components>,
+    bars_processed: usize,
+    signals_generated: usize,
+}
+
+impl Strategy for ComposedStrategy {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Entry and filter readings are on every signal; exit readings only
+    /// once they have a level.
+    fn metadata_schema(&self) -> Option<MetadataSchema> {
+        let mut required = self.prototype.entry.keys();
+        for filter in &self.prototype.filters {
+            required.extend(filter.keys());
+        }
+        let optional: Vec<String> = self.prototype.exits.iter().flat_map(|e| e.keys()).collect();
+        Some(MetadataSchema { required, optional })
+    }
+
+    fn on_bar(&mut self, series: &BarSeries) -> Option<Signal> {
+        self.bars_processed += 1;
+
+        let allow_short = self.allow_short;
+        let prototype = &self.prototype;
+        let (signal_type, reason) = self.states.update(
+            series,
+            || prototype.clone(),
+            |components, bar| components.update(bar, allow_short),
+        )??;
+        let bar = series.last()?;
+        let indicators = self.states.get(&series.symbol)?.readings();
+        self.signals_generated += 1;
+
+        Some(Signal {
+            symbol: series.symbol.clone(),
+            signal_type,
+            strength: SignalStrength::Moderate,
+            price: bar.close,
+            timestamp: bar.timestamp,
+            confidence: 0.6,
+            fraction: None,
+            metadata: SignalMetadata {
+                strategy_name: self.name.clone(),
+                stop_loss: indicators
+                    .get("trailing_stop")
+                    .or_else(|| indicators.get("stop_loss"))
+                    .copied()
+                    .filter(|_| signal_type.is_entry()),
+                take_profit: indicators
+                    .get("take_profit")
+                    .copied()
+                    .filter(|_| signal_type.is_entry()),
+                indicators,
+                reason,
+                ..Default::default()
+            },
+        })
+    }
+
+    fn reset(&mut self) {
+        self.states.clear();
+        self.bars_processed = 0;
+        self.signals_generated = 0;
+    }
+
+    fn state(&self) -> StrategyState {
+        StrategyState {
+            name: self.name.clone(),
+            is_warmed_up: self.bars_processed >= self.warmup_period(),
+            bars_processed: self.bars_processed,
+            signals_generated: self.signals_generated,
+            indicators: HashMap::new(),
+            custom: serde_json::json!({
+                "components": self.description,
+                "positions": self
+                    .symbols
+                    .iter()
+                    .filter_map(|s| {
+                        let position = self.states.get(s)?.position?;
+                        Some((s.clone(), position.to_string()))
+                    })
+                    .collect::<HashMap<_, _>>(),
+            }),
+        }
+    }
+
+    fn warmup_period(&self) -> usize {
+        let components = &self.prototype;
+        std::iter::once(components.entry.warmup())
+            .chain(components.filters.iter().map(|f| f.warmup()))
+            .chain(components.exits.iter().map(|e| e.warmup()))
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn symbols(&self) -> &[String] {
+        &self.symbols
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use trading_core::types::Timeframe;
+
+    fn run(strategy: &mut ComposedStrategy, closes: &[f64]) -> Vec<(usize, SignalType, String)> {
+        let mut series = BarSeries::new("TEST".to_string(), Timeframe::Daily);
+        let mut signals = Vec::new();
+        for (i, &close) in closes.iter().enumerate() {
+            series.push(Bar::new(
+                i as i64 * 86_400_000,
+                close,
+                close + 0.5,
+                close - 0.5,
+                close,
+                1_000.0,
+            ));
+            if let Some(signal) = strategy.on_bar(&series) {
+                let schema = strategy.metadata_schema().unwrap();
+                schema.validate(&signal.metadata).unwrap();
+                signals.push((i, signal.signal_type, signal.metadata.reason));
+            }
+        }
+        signals
+    }
+
+    #[test]
+    fn test_entry_filter_and_exit() {
+        let mut strategy = StrategyBuilder::entry(channel_breakout(3))
+            .filter(trend_above_ema(5))
+            .exit(stop_loss_pct(2.0))
+            .exit(max_bars(4))
+            .build(vec!["TEST".to_string()]);
+        assert_eq!(
+            strategy.description(),
+            "channel_breakout(3) + trend_above_ema(5) -> stop_loss_pct(2) -> max_bars(4)"
+        );
+
+        // A breakout in a downtrend is filtered out; one in an uptrend is
+        // taken and held until the time exit, then another is stopped out
+        let closes = [
+            110.0, 108.0, 106.0, 104.0, 102.0, 100.0, 103.0, 101.0, 102.0, 104.0, 105.0, 106.0,
+            107.0, 109.0, 111.0, 108.0,
+        ];
+        let signals = run(&mut strategy, &closes);
+        let types: Vec<_> = signals.iter().map(|(i, t, _)| (*i, *t)).collect();
+        assert_eq!(
+            types,
+            vec![
+                (9, SignalType::Buy),
+                (13, SignalType::CloseLong),
+                (14, SignalType::Buy),
+                (15, SignalType::CloseLong),
+            ]
+        );
+        assert_eq!(signals[1].2, "Held for 4 bars");
+        assert!(signals[3].2.contains("stop-loss"));
+    }
+
+    #[test]
+    fn test_short_entries_and_reversal_exit() {
+        let build = |allow_short| {
+            StrategyBuilder::entry(channel_breakout(3))
+                .with_name("Channel")
+                .with_allow_short(allow_short)
+                .build(vec!["TEST".to_string()])
+        };
+        let closes = [100.0, 100.0, 100.0, 100.0, 97.0, 96.0, 99.0, 103.0];
+
+        // Long only: the downside breakout is ignored
+        let mut strategy = build(false);
+        let types: Vec<_> = run(&mut strategy, &closes)
+            .into_iter()
+            .map(|(i, t, _)| (i, t))
+            .collect();
+        assert_eq!(types, vec![(7, SignalType::Buy)]);
+        assert_eq!(strategy.name(), "Channel");
+
+        // With shorts, the upside breakout closes the short
+        let mut strategy = build(true);
+        let types: Vec<_> = run(&mut strategy, &closes)
+            .into_iter()
+            .map(|(i, t, _)| (i, t))
+            .collect();
+        assert_eq!(
+            types,
+            vec![(4, SignalType::Sell), (7, SignalType::CloseShort)]
+        );
+    }
+}
@@ -0,0 +1,263 @@
+//! Dollar-Cost Averaging Strategy.
+//!
+//! Buys a fixed notional of each symbol on the first bar of every interval
+//! (day, week, month, ...), whatever the price does. Useful as a baseline
+//! in backtests and as a simple scheduled accumulation mode.
+//!
+//! Buys are placed as strategy-built market orders, so the notional is not
+//! resized by the position sizer; risk limits still apply.
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use trading_core::{
+    error::StrategyError,
+    traits::{Strategy, StrategyAction, StrategyConfig, StrategyOrders, StrategyState},
+    types::{
+        BarSeries, MetadataSchema, MultiTimeframeSeries, OrderRequest, Side, Signal,
+        SignalMetadata, SignalStrength, SignalType, Timeframe,
+    },
+};
+
+/// Configuration for the DCA strategy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DcaConfig {
+    /// Symbols to accumulate
+    pub symbols: Vec<String>,
+    /// Amount to buy of each symbol per interval, in account currency
+    pub notional: f64,
+    /// How often to buy; buys happen on the first bar of each period
+    pub interval: Timeframe,
+    /// Buy fractional shares instead of rounding down to whole shares
+    pub fractional: bool,
+}
+
+impl Default for DcaConfig {
+    fn default() -> Self {
+        Self {
+            symbols: vec![],
+            notional: 1000.0,
+            interval: Timeframe::Weekly,
+            fractional: false,
+        }
+    }
+}
+
+impl StrategyConfig for DcaConfig {
+    fn validate(&self) -> Result<(), StrategyError> {
+        if !(self.notional > 0.0 && self.notional.is_finite()) {
+            return Err(StrategyError::InvalidConfig(
+                "Notional must be positive".into(),
+            ));
+        }
+        if self.symbols.is_empty() {
+            return Err(StrategyError::InvalidConfig(
+                "At least one symbol required".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Scheduled fixed-notional accumulation.
+pub struct DcaStrategy {
+    config: DcaConfig,
+    /// Start of the interval of each symbol's last buy
+    last_buy: HashMap<String, i64>,
+    /// Notional ordered per symbol
+    invested: HashMap<String, f64>,
+    bars_processed: usize,
+    signals_generated: usize,
+}
+
+impl DcaStrategy {
+    /// Indicator keys of the signals this strategy emits.
+    pub fn signal_schema() -> MetadataSchema {
+        MetadataSchema::new(&["notional", "quantity"], &[])
+    }
+
+    /// Create a new DCA strategy.
+    pub fn new(config: DcaConfig) -> Self {
+        Self {
+            config,
+            last_buy: HashMap::new(),
+            invested: HashMap::new(),
+            bars_processed: 0,
+            signals_generated: 0,
+        }
+    }
+
+    /// Shares bought for the configured notional at `price`.
+    fn quantity(&self, price: f64) -> Decimal {
+        let quantity = Decimal::try_from(self.config.notional / price).unwrap_or_default();
+        if self.config.fractional {
+            quantity.round_dp(6)
+        } else {
+            quantity.floor()
+        }
+    }
+}
+
+impl Strategy for DcaStrategy {
+    fn name(&self) -> &str {
+        "DCA"
+    }
+
+    fn description(&self) -> &str {
+        "Buys a fixed notional on a schedule, regardless of price"
+    }
+
+    fn metadata_schema(&self) -> Option<MetadataSchema> {
+        Some(Self::signal_schema())
+    }
+
+    fn on_bar(&mut self, series: &BarSeries) -> Option<Signal> {
+        self.bars_processed += 1;
+
+        let bar = series.last()?;
+        let period = self.config.interval.bucket_start(bar.timestamp);
+        if self
+            .last_buy
+            .get(&series.symbol)
+            .is_some_and(|&last| last >= period)
+        {
+            return None;
+        }
+
+        let quantity = self.quantity(bar.close);
+        if quantity <= Decimal::ZERO {
+            return None;
+        }
+        self.last_buy.insert(series.symbol.clone(), period);
+        *self.invested.entry(series.symbol.clone()).or_default() += self.config.notional;
+        self.signals_generated += 1;
+
+        Some(Signal {
+            symbol: series.symbol.clone(),
+            signal_type: SignalType::Buy,
+            strength: SignalStrength::Moderate,
+            price: bar.close,
+            timestamp: bar.timestamp,
+            confidence: 1.0,
+            fraction: None,
+            metadata: SignalMetadata {
+                strategy_name: self.name().to_string(),
+                indicators: [
+                    ("notional".to_string(), self.config.notional),
+                    ("quantity".to_string(), quantity.try_into().unwrap_or(0.0)),
+                ]
+                .into_iter()
+                .collect(),
+                reason: format!(
+                    "Scheduled {} buy of {:.2}",
+                    self.config.interval, self.config.notional
+                ),
+                ..Default::default()
+            },
+        })
+    }
+
+    fn act(&mut self, series: &MultiTimeframeSeries) -> Option<StrategyAction> {
+        let signal = self.on_bars(series)?;
+        let quantity = self.quantity(signal.price);
+        let order = OrderRequest::market(&signal.symbol, Side::Buy, quantity);
+        Some(StrategyAction::Orders(
+            StrategyOrders::single(order).with_reason(signal.metadata.reason),
+        ))
+    }
+
+    fn reset(&mut self) {
+        self.last_buy.clear();
+        self.invested.clear();
+        self.bars_processed = 0;
+        self.signals_generated = 0;
+    }
+
+    fn state(&self) -> StrategyState {
+        StrategyState {
+            name: self.name().to_string(),
+            is_warmed_up: true,
+            bars_processed: self.bars_processed,
+            signals_generated: self.signals_generated,
+            indicators: HashMap::new(),
+            custom: serde_json::json!({
+                "interval": self.config.interval.to_string(),
+                "notional": self.config.notional,
+                "invested": self.invested,
+            }),
+        }
+    }
+
+    fn warmup_period(&self) -> usize {
+        0
+    }
+
+    fn symbols(&self) -> &[String] {
+        &self.config.symbols
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use trading_core::types::Bar;
+
+    #[test]
+    fn test_buys_once_per_interval() {
+        let mut strategy = DcaStrategy::new(DcaConfig {
+            symbols: vec!["TEST".to_string()],
+            notional: 250.0,
+            interval: Timeframe::Weekly,
+            fractional: false,
+        });
+        let mut series = MultiTimeframeSeries::new("TEST".to_string(), Timeframe::Daily, 64, &[]);
+
+        // Daily bars from Monday 2024-01-01 over three weeks
+        let mut orders = Vec::new();
+        for day in 0..21 {
+            let close = 100.0 + day as f64;
+            series.push(Bar::new(
+                1_704_067_200_000 + day * 86_400_000,
+                close,
+                close,
+                close,
+                close,
+                1_000.0,
+            ));
+            if let Some(StrategyAction::Orders(batch)) = strategy.act(&series) {
+                orders.push((day, batch.legs[0].quantity));
+            }
+        }
+
+        assert_eq!(
+            orders,
+            vec![
+                (0, Decimal::from(2)),
+                (7, Decimal::from(2)),
+                (14, Decimal::from(2))
+            ]
+        );
+        assert_eq!(strategy.state().custom["invested"]["TEST"], 750.0);
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let config = DcaConfig {
+            symbols: vec!["TEST".to_string()],
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+        assert!(DcaConfig {
+            notional: 0.0,
+            ..config.clone()
+        }
+        .validate()
+        .is_err());
+
+        let strategy = DcaStrategy::new(DcaConfig {
+            fractional: true,
+            ..config
+        });
+        assert_eq!(strategy.quantity(300.0), Decimal::new(3_333_333, 6));
+    }
+}
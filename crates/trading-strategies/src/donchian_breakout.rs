@@ -0,0 +1,355 @@
+//! Donchian Channel Breakout Strategy.
+//!
+//! Classic turtle-style trend following: enters when price breaks out of
+//! the highest high (or lowest low) of the last `entry_period` bars and
+//! exits when it breaks back through the opposite extreme of the shorter
+//! `exit_period` channel.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use trading_core::{
+    error::StrategyError,
+    traits::{Strategy, StrategyConfig, StrategyState, StreamingState},
+    types::{Bar, BarSeries, MetadataSchema, Signal, SignalMetadata, SignalStrength, SignalType},
+};
+use trading_indicators::{RollingMax, RollingMin};
+
+/// Configuration for the Donchian breakout strategy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DonchianBreakoutConfig {
+    /// Symbols to trade
+    pub symbols: Vec<String>,
+    /// Bars in the entry channel
+    pub entry_period: usize,
+    /// Bars in the exit channel
+    pub exit_period: usize,
+    /// Allow short positions on downside breakouts
+    pub allow_short: bool,
+}
+
+impl Default for DonchianBreakoutConfig {
+    fn default() -> Self {
+        Self {
+            symbols: vec![],
+            entry_period: 20,
+            exit_period: 10,
+            allow_short: false,
+        }
+    }
+}
+
+impl StrategyConfig for DonchianBreakoutConfig {
+    fn validate(&self) -> Result<(), StrategyError> {
+        if self.entry_period == 0 || self.exit_period == 0 {
+            return Err(StrategyError::InvalidConfig(
+                "Channel periods must be positive".into(),
+            ));
+        }
+        if self.symbols.is_empty() {
+            return Err(StrategyError::InvalidConfig(
+                "At least one symbol required".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Position state
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+enum PositionState {
+    #[default]
+    Flat,
+    Long,
+    Short,
+}
+
+/// Channel extremes of the bars before the current one.
+#[derive(Debug, Clone, Copy)]
+struct Levels {
+    entry_high: f64,
+    entry_low: f64,
+    exit_high: f64,
+    exit_low: f64,
+}
+
+/// Rolling highs and lows of one symbol.
+struct Channels {
+    entry_high: RollingMax,
+    entry_low: RollingMin,
+    exit_high: RollingMax,
+    exit_low: RollingMin,
+}
+
+impl Channels {
+    fn new(entry_period: usize, exit_period: usize) -> Self {
+        Self {
+            entry_high: RollingMax::new(entry_period),
+            entry_low: RollingMin::new(entry_period),
+            exit_high: RollingMax::new(exit_period),
+            exit_low: RollingMin::new(exit_period),
+        }
+    }
+
+    /// Add a bar; returns the channels of the bars before it.
+    fn update(&mut self, bar: &Bar) -> Option<Levels> {
+        let levels = self.levels();
+        self.entry_high.push(bar.high);
+        self.entry_low.push(bar.low);
+        self.exit_high.push(bar.high);
+        self.exit_low.push(bar.low);
+        levels
+    }
+
+    fn levels(&self) -> Option<Levels> {
+        if !self.entry_high.is_full() || !self.exit_high.is_full() {
+            return None;
+        }
+        Some(Levels {
+            entry_high: self.entry_high.get()?,
+            entry_low: self.entry_low.get()?,
+            exit_high: self.exit_high.get()?,
+            exit_low: self.exit_low.get()?,
+        })
+    }
+}
+
+/// Donchian Channel Breakout Strategy.
+pub struct DonchianBreakoutStrategy {
+    config: DonchianBreakoutConfig,
+    channels: StreamingState<Channels>,
+    positions: HashMap<String, PositionState>,
+    /// Latest channels of any symbol, for monitoring
+    last_levels: Option<Levels>,
+    bars_processed: usize,
+    signals_generated: usize,
+}
+
+impl DonchianBreakoutStrategy {
+    /// Indicator keys of the signals this strategy emits.
+    pub fn signal_schema() -> MetadataSchema {
+        MetadataSchema::new(&["entry_high", "entry_low", "exit_high", "exit_low"], &[])
+    }
+
+    /// Create a new Donchian breakout strategy.
+    pub fn new(config: DonchianBreakoutConfig) -> Self {
+        Self {
+            config,
+            channels: StreamingState::new(),
+            positions: HashMap::new(),
+            last_levels: None,
+            bars_processed: 0,
+            signals_generated: 0,
+        }
+    }
+
+    fn create_signal(
+        &self,
+        bar: &Bar,
+        symbol: &str,
+        signal_type: SignalType,
+        levels: &Levels,
+        reason: String,
+    ) -> Signal {
+        Signal {
+            symbol: symbol.to_string(),
+            signal_type,
+            strength: SignalStrength::Moderate,
+            price: bar.close,
+            timestamp: bar.timestamp,
+            confidence: 0.6,
+            fraction: None,
+            metadata: SignalMetadata {
+                strategy_name: self.name().to_string(),
+                indicators: [
+                    ("entry_high".to_string(), levels.entry_high),
+                    ("entry_low".to_string(), levels.entry_low),
+                    ("exit_high".to_string(), levels.exit_high),
+                    ("exit_low".to_string(), levels.exit_low),
+                ]
+                .into_iter()
+                .collect(),
+                reason,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl Strategy for DonchianBreakoutStrategy {
+    fn name(&self) -> &str {
+        "Donchian Breakout"
+    }
+
+    fn description(&self) -> &str {
+        "Turtle-style breakouts of the Donchian channel"
+    }
+
+    fn metadata_schema(&self) -> Option<MetadataSchema> {
+        Some(Self::signal_schema())
+    }
+
+    fn on_bar(&mut self, series: &BarSeries) -> Option<Signal> {
+        self.bars_processed += 1;
+
+        if series.len() < self.warmup_period() {
+            return None;
+        }
+
+        let (entry_period, exit_period) = (self.config.entry_period, self.config.exit_period);
+        let levels = self.channels.update(
+            series,
+            || Channels::new(entry_period, exit_period),
+            Channels::update,
+        )??;
+        self.last_levels = Some(levels);
+        let bar = series.last()?;
+        let position = self
+            .positions
+            .get(&series.symbol)
+            .copied()
+            .unwrap_or_default();
+
+        let (next, signal_type, reason) = match position {
+            PositionState::Flat if bar.close > levels.entry_high => (
+                PositionState::Long,
+                SignalType::Buy,
+                format!(
+                    "Close {:.2} broke above the {}-bar high {:.2}",
+                    bar.close, entry_period, levels.entry_high
+                ),
+            ),
+            PositionState::Flat if self.config.allow_short && bar.close < levels.entry_low => (
+                PositionState::Short,
+                SignalType::Sell,
+                format!(
+                    "Close {:.2} broke below the {}-bar low {:.2}",
+                    bar.close, entry_period, levels.entry_low
+                ),
+            ),
+            PositionState::Long if bar.close < levels.exit_low => (
+                PositionState::Flat,
+                SignalType::CloseLong,
+                format!(
+                    "Close {:.2} fell below the {}-bar low {:.2}",
+                    bar.close, exit_period, levels.exit_low
+                ),
+            ),
+            PositionState::Short if bar.close > levels.exit_high => (
+                PositionState::Flat,
+                SignalType::CloseShort,
+                format!(
+                    "Close {:.2} rose above the {}-bar high {:.2}",
+                    bar.close, exit_period, levels.exit_high
+                ),
+            ),
+            _ => return None,
+        };
+
+        self.positions.insert(series.symbol.clone(), next);
+        self.signals_generated += 1;
+        Some(self.create_signal(bar, &series.symbol, signal_type, &levels, reason))
+    }
+
+    fn reset(&mut self) {
+        self.channels.clear();
+        self.positions.clear();
+        self.last_levels = None;
+        self.bars_processed = 0;
+        self.signals_generated = 0;
+    }
+
+    fn state(&self) -> StrategyState {
+        let indicators = self
+            .last_levels
+            .map(|l| {
+                [
+                    ("entry_high".to_string(), l.entry_high),
+                    ("entry_low".to_string(), l.entry_low),
+                ]
+                .into_iter()
+                .collect()
+            })
+            .unwrap_or_default();
+
+        StrategyState {
+            name: self.name().to_string(),
+            is_warmed_up: self.bars_processed >= self.warmup_period(),
+            bars_processed: self.bars_processed,
+            signals_generated: self.signals_generated,
+            indicators,
+            custom: serde_json::json!({
+                "positions": self
+                    .positions
+                    .iter()
+                    .map(|(symbol, p)| (symbol.clone(), format!("{:?}", p)))
+                    .collect::<HashMap<_, _>>(),
+                "entry_period": self.config.entry_period,
+                "exit_period": self.config.exit_period,
+            }),
+        }
+    }
+
+    fn warmup_period(&self) -> usize {
+        self.config.entry_period.max(self.config.exit_period) + 1
+    }
+
+    fn symbols(&self) -> &[String] {
+        &self.config.symbols
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use trading_core::types::Timeframe;
+
+    #[test]
+    fn test_breakout_entry_and_exit() {
+        let mut strategy = DonchianBreakoutStrategy::new(DonchianBreakoutConfig {
+            symbols: vec!["TEST".to_string()],
+            entry_period: 5,
+            exit_period: 3,
+            allow_short: false,
+        });
+
+        // Range-bound, a breakout, then a reversal through the exit channel
+        let closes = [
+            100.0, 101.0, 99.0, 100.0, 101.0, 100.0, 104.0, 106.0, 107.0, 103.0, 100.0,
+        ];
+        let mut series = BarSeries::new("TEST".to_string(), Timeframe::Daily);
+        let mut signals = Vec::new();
+        for (i, &close) in closes.iter().enumerate() {
+            series.push(Bar::new(
+                i as i64 * 86_400_000,
+                close,
+                close + 0.5,
+                close - 0.5,
+                close,
+                1_000.0,
+            ));
+            if let Some(signal) = strategy.on_bar(&series) {
+                signals.push((i, signal.signal_type));
+            }
+        }
+
+        assert_eq!(
+            signals,
+            vec![(6, SignalType::Buy), (9, SignalType::CloseLong)]
+        );
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let config = DonchianBreakoutConfig {
+            symbols: vec!["AAPL".to_string()],
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+        assert!(DonchianBreakoutConfig {
+            exit_period: 0,
+            ..config
+        }
+        .validate()
+        .is_err());
+    }
+}
@@ -0,0 +1,702 @@
+//! Ensemble (voting) meta-strategy.
+//!
+//! Wraps several member strategies and trades on their combined opinion.
+//! Every member sees every bar; its latest signal for a symbol is its vote
+//! (buy or sell votes long or short, a close votes flat). The votes are
+//! combined into a score between -1 and 1 by majority, weight, or weight
+//! times signal confidence, and the ensemble holds a position while the
+//! score stays beyond `threshold`.
+//!
+//! Members vote through their signals only, so strategies that place their
+//! own orders and emit no signals (rebalance, rotation) never vote.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use trading_core::{
+    error::StrategyError,
+    traits::{Strategy, StrategyConfig, StrategyState},
+    types::{
+        Bar, BarSeries, MetadataSchema, MultiTimeframeSeries, Signal, SignalMetadata,
+        SignalStrength, SignalType, Timeframe,
+    },
+};
+
+/// How member votes are combined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VotingMode {
+    /// One vote per member
+    #[default]
+    Majority,
+    /// Votes scaled by member weight
+    Weighted,
+    /// Votes scaled by member weight and the confidence of its signal
+    Confidence,
+}
+
+impl std::fmt::Display for VotingMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            VotingMode::Majority => "majority",
+            VotingMode::Weighted => "weighted",
+            VotingMode::Confidence => "confidence",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl std::str::FromStr for VotingMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "majority" => Ok(VotingMode::Majority),
+            "weighted" => Ok(VotingMode::Weighted),
+            "confidence" => Ok(VotingMode::Confidence),
+            _ => Err(format!("Invalid voting mode: {}", s)),
+        }
+    }
+}
+
+/// A member strategy of an ensemble.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnsembleMember {
+    /// Registry name of the strategy
+    pub strategy: String,
+    /// Vote weight in weighted and confidence voting
+    #[serde(default = "default_weight")]
+    pub weight: f64,
+    /// Settings overriding the strategy's default configuration
+    #[serde(default)]
+    pub config: serde_json::Value,
+}
+
+fn default_weight() -> f64 {
+    1.0
+}
+
+impl EnsembleMember {
+    /// A member with default settings and weight 1.
+    pub fn new(strategy: impl Into<String>) -> Self {
+        Self {
+            strategy: strategy.into(),
+            weight: default_weight(),
+            config: serde_json::Value::Null,
+        }
+    }
+
+    /// Set the vote weight.
+    pub fn with_weight(mut self, weight: f64) -> Self {
+        self.weight = weight;
+        self
+    }
+}
+
+/// Configuration for the ensemble strategy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnsembleConfig {
+    /// Symbols to trade, shared by all members
+    pub symbols: Vec<String>,
+    /// Member strategies
+    pub members: Vec<EnsembleMember>,
+    /// How votes are combined
+    pub voting: VotingMode,
+    /// Combined score a position needs, between 0 and 1
+    pub threshold: f64,
+    /// Allow short positions when the score is below `-threshold`
+    pub allow_short: bool,
+}
+
+impl Default for EnsembleConfig {
+    fn default() -> Self {
+        Self {
+            symbols: vec![],
+            members: vec![
+                EnsembleMember::new("ma_crossover"),
+                EnsembleMember::new("momentum"),
+                EnsembleMember::new("macd"),
+            ],
+            voting: VotingMode::Majority,
+            threshold: 0.5,
+            allow_short: false,
+        }
+    }
+}
+
+impl StrategyConfig for EnsembleConfig {
+    fn validate(&self) -> Result<(), StrategyError> {
+        if self.members.is_empty() {
+            return Err(StrategyError::InvalidConfig(
+                "At least one member required".into(),
+            ));
+        }
+        if let Some(member) = self.members.iter().find(|m| m.strategy == "ensemble") {
+            return Err(StrategyError::InvalidConfig(format!(
+                "Ensembles cannot contain {}",
+                member.strategy
+            )));
+        }
+        if self
+            .members
+            .iter()
+            .any(|m| !(m.weight > 0.0 && m.weight.is_finite()))
+        {
+            return Err(StrategyError::InvalidConfig(
+                "Member weights must be positive".into(),
+            ));
+        }
+        if !(self.threshold >= 0.0 && self.threshold < 1.0) {
+            return Err(StrategyError::InvalidConfig(
+                "Threshold must be in [0, 1)".into(),
+            ));
+        }
+        if self.symbols.is_empty() {
+            return Err(StrategyError::InvalidConfig(
+                "At least one symbol required".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A member's vote, or the ensemble's position
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+enum Stance {
+    #[default]
+    Flat,
+    Long,
+    Short,
+}
+
+impl Stance {
+    fn direction(self) -> f64 {
+        match self {
+            Stance::Flat => 0.0,
+            Stance::Long => 1.0,
+            Stance::Short => -1.0,
+        }
+    }
+}
+
+impl std::fmt::Display for Stance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Stance::Flat => "flat",
+            Stance::Long => "long",
+            Stance::Short => "short",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Latest vote of a member for a symbol.
+#[derive(Debug, Clone, Copy, Default)]
+struct Vote {
+    stance: Stance,
+    confidence: f64,
+}
+
+/// Combined votes of a bar.
+#[derive(Debug, Clone, Copy, Default)]
+struct Tally {
+    /// Net score, from -1 (all short) to 1 (all long)
+    score: f64,
+    /// Share of the vote that is long
+    long: f64,
+    /// Share of the vote that is short
+    short: f64,
+}
+
+/// Meta-strategy trading on the combined votes of its members.
+pub struct EnsembleStrategy {
+    config: EnsembleConfig,
+    members: Vec<Box<dyn Strategy>>,
+    /// Member names, suffixed where a strategy appears more than once
+    labels: Vec<String>,
+    timeframes: Vec<Timeframe>,
+    /// Latest vote of each member, per symbol
+    votes: HashMap<String, Vec<Vote>>,
+    positions: HashMap<String, Stance>,
+    last_tally: Option<Tally>,
+    bars_processed: usize,
+    signals_generated: usize,
+}
+
+impl EnsembleStrategy {
+    /// Indicator keys of the signals this strategy emits. Each member's
+    /// vote is in the signal's custom metadata, keyed by member name.
+    pub fn signal_schema() -> MetadataSchema {
+        MetadataSchema::new(&["score", "long_votes", "short_votes"], &[])
+    }
+
+    /// Create an ensemble of `members`, in the order of `config.members`.
+    ///
+    /// # Panics
+    /// If the number of members does not match the configuration.
+    pub fn new(config: EnsembleConfig, members: Vec<Box<dyn Strategy>>) -> Self {
+        assert_eq!(
+            members.len(),
+            config.members.len(),
+            "one strategy per configured member"
+        );
+
+        let mut labels: Vec<String> = Vec::with_capacity(members.len());
+        for member in &members {
+            let name = member.name();
+            let count = labels
+                .iter()
+                .filter(|l| l.split(" #").next() == Some(name))
+                .count();
+            labels.push(if count == 0 {
+                name.to_string()
+            } else {
+                format!("{} #{}", name, count + 1)
+            });
+        }
+
+        let mut timeframes: Vec<Timeframe> = Vec::new();
+        for tf in members.iter().flat_map(|m| m.timeframes()) {
+            if !timeframes.contains(tf) {
+                timeframes.push(*tf);
+            }
+        }
+
+        Self {
+            config,
+            members,
+            labels,
+            timeframes,
+            votes: HashMap::new(),
+            positions: HashMap::new(),
+            last_tally: None,
+            bars_processed: 0,
+            signals_generated: 0,
+        }
+    }
+
+    /// Record the members' signals of a bar and act on the new tally.
+    fn combine(&mut self, symbol: &str, bar: &Bar, signals: Vec<Option<Signal>>) -> Option<Signal> {
+        let n = self.members.len();
+        let votes = self
+            .votes
+            .entry(symbol.to_string())
+            .or_insert_with(|| vec![Vote::default(); n]);
+        for (vote, signal) in votes.iter_mut().zip(signals) {
+            let Some(signal) = signal else {
+                continue;
+            };
+            let stance = match signal.signal_type {
+                SignalType::Buy => Stance::Long,
+                SignalType::Sell => Stance::Short,
+                SignalType::CloseLong | SignalType::CloseShort => Stance::Flat,
+                SignalType::Hold => continue,
+            };
+            *vote = Vote {
+                stance,
+                confidence: signal.confidence,
+            };
+        }
+
+        let tally = self.tally(symbol);
+        self.last_tally = Some(tally);
+        let target = if tally.score > self.config.threshold {
+            Stance::Long
+        } else if self.config.allow_short && tally.score < -self.config.threshold {
+            Stance::Short
+        } else {
+            Stance::Flat
+        };
+
+        let position = self.positions.get(symbol).copied().unwrap_or_default();
+        let (signal_type, next, confidence) = match (position, target) {
+            (Stance::Long, Stance::Long) | (Stance::Short, Stance::Short) => return None,
+            (Stance::Flat, Stance::Flat) => return None,
+            (Stance::Long, _) => (SignalType::CloseLong, Stance::Flat, 1.0 - tally.long),
+            (Stance::Short, _) => (SignalType::CloseShort, Stance::Flat, 1.0 - tally.short),
+            (Stance::Flat, Stance::Long) => (SignalType::Buy, Stance::Long, tally.long),
+            (Stance::Flat, Stance::Short) => (SignalType::Sell, Stance::Short, tally.short),
+        };
+        self.positions.insert(symbol.to_string(), next);
+        self.signals_generated += 1;
+        Some(self.create_signal(bar, symbol, signal_type, confidence, &tally))
+    }
+
+    /// Combine the current votes for `symbol`.
+    fn tally(&self, symbol: &str) -> Tally {
+        let Some(votes) = self.votes.get(symbol) else {
+            return Tally::default();
+        };
+        let mut tally = Tally::default();
+        let mut total = 0.0;
+        for (vote, member) in votes.iter().zip(&self.config.members) {
+            let (weight, scale) = match self.config.voting {
+                VotingMode::Majority => (1.0, 1.0),
+                VotingMode::Weighted => (member.weight, member.weight),
+                VotingMode::Confidence => (
+                    member.weight,
+                    member.weight * vote.confidence.clamp(0.0, 1.0),
+                ),
+            };
+            total += weight;
+            match vote.stance {
+                Stance::Long => tally.long += scale,
+                Stance::Short => tally.short += scale,
+                Stance::Flat => {}
+            }
+            tally.score += scale * vote.stance.direction();
+        }
+        if total > 0.0 {
+            tally.score /= total;
+            tally.long /= total;
+            tally.short /= total;
+        }
+        tally
+    }
+
+    fn create_signal(
+        &self,
+        bar: &Bar,
+        symbol: &str,
+        signal_type: SignalType,
+        confidence: f64,
+        tally: &Tally,
+    ) -> Signal {
+        let votes = &self.votes[symbol];
+        let contributions = self
+            .labels
+            .iter()
+            .zip(votes)
+            .zip(&self.config.members)
+            .map(|((label, vote), member)| {
+                let contribution = format!(
+                    "{} (confidence {:.2}, weight {:.2})",
+                    vote.stance, vote.confidence, member.weight
+                );
+                (label.clone(), contribution)
+            })
+            .collect();
+        let agreeing: Vec<&str> = self
+            .labels
+            .iter()
+            .zip(votes)
+            .filter(|(_, vote)| match signal_type {
+                SignalType::Buy => vote.stance == Stance::Long,
+                SignalType::Sell => vote.stance == Stance::Short,
+                SignalType::CloseLong => vote.stance != Stance::Long,
+                _ => vote.stance != Stance::Short,
+            })
+            .map(|(label, _)| label.as_str())
+            .collect();
+
+        Signal {
+            symbol: symbol.to_string(),
+            signal_type,
+            strength: if confidence >= 0.9 {
+                SignalStrength::Strong
+            } else {
+                SignalStrength::Moderate
+            },
+            price: bar.close,
+            timestamp: bar.timestamp,
+            confidence: confidence.clamp(0.0, 1.0),
+            fraction: None,
+            metadata: SignalMetadata {
+                strategy_name: self.name().to_string(),
+                indicators: [
+                    ("score".to_string(), tally.score),
+                    ("long_votes".to_string(), tally.long),
+                    ("short_votes".to_string(), tally.short),
+                ]
+                .into_iter()
+                .collect(),
+                reason: format!(
+                    "{} {} vote (score {:.2}): {}",
+                    self.config.voting,
+                    signal_type,
+                    tally.score,
+                    agreeing.join(", ")
+                ),
+                custom: contributions,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl Strategy for EnsembleStrategy {
+    fn name(&self) -> &str {
+        "Ensemble"
+    }
+
+    fn description(&self) -> &str {
+        "Trades on the majority, weighted or confidence-weighted vote of member strategies"
+    }
+
+    fn metadata_schema(&self) -> Option<MetadataSchema> {
+        Some(Self::signal_schema())
+    }
+
+    fn members(&self) -> Vec<&dyn Strategy> {
+        self.members.iter().map(|m| m.as_ref()).collect()
+    }
+
+    fn timeframes(&self) -> &[Timeframe] {
+        &self.timeframes
+    }
+
+    fn on_bar(&mut self, series: &BarSeries) -> Option<Signal> {
+        self.bars_processed += 1;
+        let signals = self.members.iter_mut().map(|m| m.on_bar(series)).collect();
+        let bar = series.last()?;
+        self.combine(&series.symbol, bar, signals)
+    }
+
+    fn on_bars(&mut self, series: &MultiTimeframeSeries) -> Option<Signal> {
+        self.bars_processed += 1;
+        let signals = self.members.iter_mut().map(|m| m.on_bars(series)).collect();
+        let bar = series.primary().last()?;
+        self.combine(series.symbol(), bar, signals)
+    }
+
+    fn reset(&mut self) {
+        for member in &mut self.members {
+            member.reset();
+        }
+        self.votes.clear();
+        self.positions.clear();
+        self.last_tally = None;
+        self.bars_processed = 0;
+        self.signals_generated = 0;
+    }
+
+    fn state(&self) -> StrategyState {
+        let indicators = self
+            .last_tally
+            .map(|t| {
+                [
+                    ("score".to_string(), t.score),
+                    ("long_votes".to_string(), t.long),
+                    ("short_votes".to_string(), t.short),
+                ]
+                .into_iter()
+                .collect()
+            })
+            .unwrap_or_default();
+
+        StrategyState {
+            name: self.name().to_string(),
+            is_warmed_up: self.bars_processed >= self.warmup_period(),
+            bars_processed: self.bars_processed,
+            signals_generated: self.signals_generated,
+            indicators,
+            custom: serde_json::json!({
+                "voting": self.config.voting.to_string(),
+                "members": self
+                    .labels
+                    .iter()
+                    .zip(&self.members)
+                    .map(|(label, m)| (label.clone(), m.state().signals_generated))
+                    .collect::<HashMap<_, _>>(),
+                "positions": self
+                    .positions
+                    .iter()
+                    .map(|(symbol, p)| (symbol.clone(), p.to_string()))
+                    .collect::<HashMap<_, _>>(),
+            }),
+        }
+    }
+
+    fn warmup_period(&self) -> usize {
+        self.members
+            .iter()
+            .map(|m| m.warmup_period())
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn symbols(&self) -> &[String] {
+        &self.config.symbols
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Emits a scripted signal type on each bar.
+    struct Scripted {
+        symbols: Vec<String>,
+        script: Vec<Option<SignalType>>,
+        confidence: f64,
+    }
+
+    impl Strategy for Scripted {
+        fn name(&self) -> &str {
+            "Scripted"
+        }
+
+        fn on_bar(&mut self, series: &BarSeries) -> Option<Signal> {
+            let signal_type = (*self.script.get(series.len() - 1)?)?;
+            let bar = series.last()?;
+            Some(Signal {
+                symbol: series.symbol.clone(),
+                signal_type,
+                strength: SignalStrength::Moderate,
+                price: bar.close,
+                timestamp: bar.timestamp,
+                confidence: self.confidence,
+                fraction: None,
+                metadata: SignalMetadata::new("Scripted"),
+            })
+        }
+
+        fn reset(&mut self) {}
+
+        fn state(&self) -> StrategyState {
+            Default::default()
+        }
+
+        fn warmup_period(&self) -> usize {
+            0
+        }
+
+        fn symbols(&self) -> &[String] {
+            &self.symbols
+        }
+    }
+
+    fn ensemble(
+        voting: VotingMode,
+        members: &[(f64, f64, &[Option<SignalType>])],
+    ) -> EnsembleStrategy {
+        let symbols = vec!["TEST".to_string()];
+        let config = EnsembleConfig {
+            symbols: symbols.clone(),
+            members: members
+                .iter()
+                .map(|(weight, _, _)| EnsembleMember::new("scripted").with_weight(*weight))
+                .collect(),
+            voting,
+            threshold: 0.5,
+            allow_short: false,
+        };
+        let members = members
+            .iter()
+            .map(|(_, confidence, script)| {
+                Box::new(Scripted {
+                    symbols: symbols.clone(),
+                    script: script.to_vec(),
+                    confidence: *confidence,
+                }) as Box<dyn Strategy>
+            })
+            .collect();
+        EnsembleStrategy::new(config, members)
+    }
+
+    fn run(strategy: &mut EnsembleStrategy, bars: usize) -> Vec<(usize, Signal)> {
+        let mut series = BarSeries::new("TEST".to_string(), Timeframe::Daily);
+        let mut signals = Vec::new();
+        for i in 0..bars {
+            series.push(Bar::new(
+                i as i64 * 86_400_000,
+                100.0,
+                100.0,
+                100.0,
+                100.0,
+                1e3,
+            ));
+            if let Some(signal) = strategy.on_bar(&series) {
+                strategy
+                    .metadata_schema()
+                    .unwrap()
+                    .validate(&signal.metadata)
+                    .unwrap();
+                signals.push((i, signal));
+            }
+        }
+        signals
+    }
+
+    #[test]
+    fn test_voting_modes() {
+        use SignalType::*;
+        let a: &[Option<SignalType>] = &[Some(Buy), None, None, Some(CloseLong)];
+        let b: &[Option<SignalType>] = &[None, Some(Buy)];
+        let c: &[Option<SignalType>] = &[None, None, Some(Sell)];
+
+        // Majority: long once two of three vote long, flat again once the
+        // first member closes
+        let mut strategy = ensemble(
+            VotingMode::Majority,
+            &[(3.0, 0.9, a), (1.0, 0.9, b), (1.0, 0.9, c)],
+        );
+        let signals = run(&mut strategy, 4);
+        let types: Vec<_> = signals.iter().map(|(i, s)| (*i, s.signal_type)).collect();
+        assert_eq!(types, vec![(1, Buy), (2, CloseLong)]);
+        let entry = &signals[0].1.metadata;
+        assert!((entry.indicators["score"] - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(
+            entry.custom["Scripted #3"],
+            "flat (confidence 0.00, weight 1.00)"
+        );
+        assert!(entry.reason.ends_with("Scripted, Scripted #2"));
+
+        // Weighted: the heavy member alone carries the vote, and keeps the
+        // position when another member turns short
+        let mut strategy = ensemble(
+            VotingMode::Weighted,
+            &[(3.0, 0.9, a), (1.0, 0.9, b), (1.0, 0.9, c)],
+        );
+        let types: Vec<_> = run(&mut strategy, 4)
+            .into_iter()
+            .map(|(i, s)| (i, s.signal_type))
+            .collect();
+        assert_eq!(types, vec![(0, Buy), (3, CloseLong)]);
+
+        // Confidence: the same heavy member is too unsure on its own
+        let mut strategy = ensemble(
+            VotingMode::Confidence,
+            &[(3.0, 0.6, a), (1.0, 0.9, b), (1.0, 0.9, c)],
+        );
+        let types: Vec<_> = run(&mut strategy, 4)
+            .into_iter()
+            .map(|(i, s)| (i, s.signal_type))
+            .collect();
+        assert_eq!(types, vec![(1, Buy), (2, CloseLong)]);
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let config = EnsembleConfig {
+            symbols: vec!["TEST".to_string()],
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+        assert!(EnsembleConfig {
+            members: vec![],
+            ..config.clone()
+        }
+        .validate()
+        .is_err());
+        assert!(EnsembleConfig {
+            members: vec![EnsembleMember::new("ensemble")],
+            ..config.clone()
+        }
+        .validate()
+        .is_err());
+        assert!(EnsembleConfig {
+            members: vec![EnsembleMember::new("rsi").with_weight(0.0)],
+            ..config.clone()
+        }
+        .validate()
+        .is_err());
+        assert!(EnsembleConfig {
+            threshold: 1.0,
+            ..config
+        }
+        .validate()
+        .is_err());
+        assert_eq!("Weighted".parse::<VotingMode>(), Ok(VotingMode::Weighted));
+        assert!("bogus".parse::<VotingMode>().is_err());
+    }
+}
@@ -5,15 +5,61 @@
 //! - Mean Reversion (Bollinger Bands)
 //! - Momentum/Trend Following
 //! - RSI-based trading
+//! - MACD signal-line crossovers
+//! - Donchian channel breakouts
+//! - Dollar-cost averaging on a schedule
+//! - Target-weight portfolio rebalancing
+//! - Cross-sectional momentum rotation
+//! - Ensembles voting over other strategies
+//! - Multi-strategy portfolios running other strategies side by side
+//!
+//! Strategies can also be assembled from entry, filter and exit components
+//! with [`StrategyBuilder`], written as rule expressions for
+//! [`RulesStrategy`], or written as Rhai scripts run by [`ScriptStrategy`].
+//! Third-party strategies can be loaded from plugin libraries; see
+//! [`declare_strategy_plugin!`].
+//! - N-bar breakouts with ATR trailing stops
 
+mod breakout;
+mod compose;
+mod dca;
+mod donchian_breakout;
+mod ensemble;
 mod ma_crossover;
+mod macd_strategy;
 mod mean_reversion;
 mod momentum;
+mod multi;
+mod plugin;
+mod rebalance;
 mod registry;
+mod rotation;
 mod rsi_strategy;
+mod rules;
+mod script;
 
-pub use ma_crossover::{MACrossoverConfig, MACrossoverStrategy};
+pub use breakout::{BreakoutConfig, BreakoutStrategy};
+pub use compose::{
+    atr_trailing, channel_breakout, ma_cross, max_bars, rsi_reversal, stop_loss_pct,
+    take_profit_pct, trend_above_ema, volatility_below, AtrTrailing, ChannelBreakout,
+    ComposedStrategy, Direction, Entry, EntryClone, Exit, ExitClone, Filter, FilterClone, MaCross,
+    MaxBars, PriceExit, RsiReversal, StrategyBuilder, TrendFilter, VolatilityFilter,
+};
+pub use dca::{DcaConfig, DcaStrategy};
+pub use donchian_breakout::{DonchianBreakoutConfig, DonchianBreakoutStrategy};
+pub use ensemble::{EnsembleConfig, EnsembleMember, EnsembleStrategy, VotingMode};
+pub use ma_crossover::{MACrossoverConfig, MACrossoverStrategy, MaType};
+pub use macd_strategy::{MacdConfig, MacdStrategy};
 pub use mean_reversion::{MeanReversionConfig, MeanReversionStrategy};
 pub use momentum::{MomentumConfig, MomentumStrategy};
-pub use registry::{StrategyInfo, StrategyRegistry};
+pub use multi::{MultiMember, MultiStrategy, MultiStrategyConfig};
+pub use plugin::{
+    PluginDeclaration, PluginRegistrar, PLUGIN_ABI_VERSION, PLUGIN_DECLARATION_SYMBOL,
+    RUSTC_VERSION,
+};
+pub use rebalance::{RebalanceConfig, RebalanceStrategy};
+pub use registry::{StrategyFactory, StrategyInfo, StrategyRegistry, SCRIPT_PREFIX};
+pub use rotation::{RotationConfig, RotationStrategy};
 pub use rsi_strategy::{RsiConfig, RsiStrategy};
+pub use rules::{RulesConfig, RulesStrategy};
+pub use script::ScriptStrategy;
@@ -3,14 +3,13 @@
 //! Generates buy signals when the fast MA crosses above the slow MA,
 //! and sell signals when the fast MA crosses below the slow MA.
 
-use serde::{Deserialize, Serialize};
-use trading_core::traits::Indicator;
+use serde::{Deserialize, Deserializer, Serialize};
 use trading_core::{
     error::StrategyError,
-    traits::{Strategy, StrategyConfig, StrategyState},
-    types::{BarSeries, Signal, SignalMetadata, SignalStrength, SignalType},
+    traits::{Strategy, StrategyConfig, StrategyState, StreamingIndicator, StreamingState},
+    types::{Bar, BarSeries, MetadataSchema, Signal, SignalMetadata, SignalStrength, SignalType},
 };
-use trading_indicators::{Ema, Sma};
+pub use trading_indicators::MaType;
 
 /// Configuration for the MA Crossover strategy.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,8 +20,9 @@ pub struct MACrossoverConfig {
     pub fast_period: usize,
     /// Slow moving average period
     pub slow_period: usize,
-    /// Use EMA instead of SMA
-    pub use_ema: bool,
+    /// Moving average type (sma, ema, wma, hull, kama, dema, tema)
+    #[serde(alias = "use_ema", deserialize_with = "ma_type_or_bool")]
+    pub ma_type: MaType,
     /// Minimum crossover magnitude to generate signal (as percentage)
     pub signal_threshold: f64,
 }
@@ -33,12 +33,27 @@ impl Default for MACrossoverConfig {
             symbols: vec![],
             fast_period: 12,
             slow_period: 26,
-            use_ema: true,
+            ma_type: MaType::Ema,
             signal_threshold: 0.001, // 0.1%
         }
     }
 }
 
+/// Accept the former `use_ema` flag as well as an MA type name.
+fn ma_type_or_bool<'de, D: Deserializer<'de>>(deserializer: D) -> Result<MaType, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Repr {
+        UseEma(bool),
+        Type(MaType),
+    }
+    Ok(match Repr::deserialize(deserializer)? {
+        Repr::UseEma(true) => MaType::Ema,
+        Repr::UseEma(false) => MaType::Sma,
+        Repr::Type(ma_type) => ma_type,
+    })
+}
+
 impl StrategyConfig for MACrossoverConfig {
     fn validate(&self) -> Result<(), StrategyError> {
         if self.fast_period >= self.slow_period {
@@ -60,20 +75,64 @@ impl StrategyConfig for MACrossoverConfig {
     }
 }
 
+/// Streaming moving average of the configured type.
+type MovingAverage = Box<dyn StreamingIndicator<Output = f64>>;
+
+/// (fast, slow) moving average values.
+type Pair = (f64, f64);
+
+/// Moving averages of one symbol.
+struct Averages {
+    fast: MovingAverage,
+    slow: MovingAverage,
+    /// (fast, slow) as of the latest bar
+    current: Option<Pair>,
+}
+
+impl Averages {
+    fn new(config: &MACrossoverConfig) -> Self {
+        Self {
+            fast: config.ma_type.streaming(config.fast_period),
+            slow: config.ma_type.streaming(config.slow_period),
+            current: None,
+        }
+    }
+
+    /// Add a bar; returns the (fast, slow) pair before and after it.
+    fn update(&mut self, bar: &Bar) -> (Option<Pair>, Option<Pair>) {
+        let fast = self.fast.update(bar.close);
+        let slow = self.slow.update(bar.close);
+        let prev = self.current;
+        if let (Some(fast), Some(slow)) = (fast, slow) {
+            self.current = Some((fast, slow));
+        }
+        (prev, self.current)
+    }
+}
+
 /// Moving Average Crossover Strategy.
 pub struct MACrossoverStrategy {
     config: MACrossoverConfig,
+    averages: StreamingState<Averages>,
+    /// Latest fast MA of any symbol, for monitoring
     prev_fast: Option<f64>,
+    /// Latest slow MA of any symbol, for monitoring
     prev_slow: Option<f64>,
     bars_processed: usize,
     signals_generated: usize,
 }
 
 impl MACrossoverStrategy {
+    /// Indicator keys of the signals this strategy emits.
+    pub fn signal_schema() -> MetadataSchema {
+        MetadataSchema::new(&["fast_ma", "slow_ma", "crossover_magnitude"], &[])
+    }
+
     /// Create a new MA Crossover strategy.
     pub fn new(config: MACrossoverConfig) -> Self {
         Self {
             config,
+            averages: StreamingState::new(),
             prev_fast: None,
             prev_slow: None,
             bars_processed: 0,
@@ -90,14 +149,6 @@ impl MACrossoverStrategy {
             SignalStrength::Weak
         }
     }
-
-    fn calculate_ma(&self, closes: &[f64], period: usize) -> Vec<f64> {
-        if self.config.use_ema {
-            Ema::new(period).calculate(closes)
-        } else {
-            Sma::new(period).calculate(closes)
-        }
-    }
 }
 
 impl Strategy for MACrossoverStrategy {
@@ -109,6 +160,10 @@ impl Strategy for MACrossoverStrategy {
         "Generates signals based on fast/slow moving average crossovers"
     }
 
+    fn metadata_schema(&self) -> Option<MetadataSchema> {
+        Some(Self::signal_schema())
+    }
+
     fn on_bar(&mut self, series: &BarSeries) -> Option<Signal> {
         self.bars_processed += 1;
 
@@ -116,21 +171,15 @@ impl Strategy for MACrossoverStrategy {
             return None;
         }
 
-        let closes = series.closes();
-
-        // Calculate MAs
-        let fast = self.calculate_ma(&closes, self.config.fast_period);
-        let slow = self.calculate_ma(&closes, self.config.slow_period);
-
-        if fast.is_empty() || slow.is_empty() {
-            return None;
-        }
+        // Update the averages with the bars not seen yet
+        let config = &self.config;
+        let (prev, current) =
+            self.averages
+                .update(series, || Averages::new(config), Averages::update)?;
+        let (current_fast, current_slow) = current?;
 
-        let current_fast = *fast.last()?;
-        let current_slow = *slow.last()?;
-
-        let signal = match (self.prev_fast, self.prev_slow) {
-            (Some(prev_f), Some(prev_s)) => {
+        let signal = match prev {
+            Some((prev_f, prev_s)) => {
                 let crossover_magnitude = if current_slow != 0.0 {
                     ((current_fast - current_slow) / current_slow).abs()
                 } else {
@@ -152,6 +201,7 @@ impl Strategy for MACrossoverStrategy {
                         price: bar.close,
                         timestamp: bar.timestamp,
                         confidence: crossover_magnitude.min(1.0),
+                        fraction: None,
                         metadata: SignalMetadata {
                             strategy_name: self.name().to_string(),
                             indicators: [
@@ -182,6 +232,7 @@ impl Strategy for MACrossoverStrategy {
                         price: bar.close,
                         timestamp: bar.timestamp,
                         confidence: crossover_magnitude.min(1.0),
+                        fraction: None,
                         metadata: SignalMetadata {
                             strategy_name: self.name().to_string(),
                             indicators: [
@@ -202,7 +253,7 @@ impl Strategy for MACrossoverStrategy {
                     None
                 }
             }
-            _ => None,
+            None => None,
         };
 
         self.prev_fast = Some(current_fast);
@@ -212,6 +263,7 @@ impl Strategy for MACrossoverStrategy {
     }
 
     fn reset(&mut self) {
+        self.averages.clear();
         self.prev_fast = None;
         self.prev_slow = None;
         self.bars_processed = 0;
@@ -233,13 +285,13 @@ impl Strategy for MACrossoverStrategy {
             custom: serde_json::json!({
                 "fast_period": self.config.fast_period,
                 "slow_period": self.config.slow_period,
-                "use_ema": self.config.use_ema,
+                "ma_type": self.config.ma_type,
             }),
         }
     }
 
     fn warmup_period(&self) -> usize {
-        self.config.slow_period + 1
+        self.config.ma_type.warmup(self.config.slow_period) + 1
     }
 
     fn symbols(&self) -> &[String] {
@@ -286,7 +338,7 @@ mod tests {
             symbols: vec!["TEST".to_string()],
             fast_period: 3,
             slow_period: 5,
-            use_ema: false,
+            ma_type: MaType::Sma,
             signal_threshold: 0.0,
         };
 
@@ -326,7 +378,7 @@ mod tests {
             symbols: vec!["TEST".to_string()],
             fast_period: 3,
             slow_period: 5,
-            use_ema: true,
+            ma_type: MaType::Ema,
             signal_threshold: 0.0,
         };
 
@@ -343,4 +395,73 @@ mod tests {
         assert!(strategy.prev_fast.is_none());
         assert_eq!(strategy.bars_processed, 0);
     }
+
+    #[test]
+    fn test_streaming_averages_match_batch() {
+        use trading_core::traits::Indicator;
+        use trading_indicators::Sma;
+
+        let config = MACrossoverConfig {
+            symbols: vec!["TEST".to_string(), "OTHER".to_string()],
+            fast_period: 3,
+            slow_period: 5,
+            ma_type: MaType::Sma,
+            signal_threshold: 0.0,
+        };
+        let mut strategy = MACrossoverStrategy::new(config);
+
+        let prices = [100.0, 98.0, 97.0, 99.0, 103.0, 104.0, 101.0, 106.0];
+        let mut series = BarSeries::new("TEST".to_string(), Timeframe::Daily);
+        let mut other = BarSeries::new("OTHER".to_string(), Timeframe::Daily);
+        for (i, &price) in prices.iter().enumerate() {
+            series.push(Bar::new(
+                i as i64 * 86400000,
+                price,
+                price,
+                price,
+                price,
+                1.0,
+            ));
+            other.push(Bar::new(i as i64 * 86400000, 50.0, 50.0, 50.0, 50.0, 1.0));
+            strategy.on_bar(&series);
+            strategy.on_bar(&other);
+        }
+
+        let averages = strategy.averages.get("TEST").unwrap();
+        let (fast, slow) = averages.current.unwrap();
+        assert!((fast - *Sma::new(3).calculate(&prices).last().unwrap()).abs() < 1e-10);
+        assert!((slow - *Sma::new(5).calculate(&prices).last().unwrap()).abs() < 1e-10);
+        assert_eq!(
+            strategy.averages.get("OTHER").unwrap().current,
+            Some((50.0, 50.0))
+        );
+    }
+
+    #[test]
+    fn test_ma_type_config() {
+        let config: MACrossoverConfig = serde_json::from_value(serde_json::json!({
+            "symbols": ["AAPL"],
+            "fast_period": 5,
+            "slow_period": 10,
+            "ma_type": "hull",
+            "signal_threshold": 0.0
+        }))
+        .unwrap();
+        assert_eq!(config.ma_type, MaType::Hull);
+        assert_eq!(
+            MACrossoverStrategy::new(config).warmup_period(),
+            MaType::Hull.warmup(10) + 1
+        );
+
+        // Configs written before MA types still load
+        let legacy: MACrossoverConfig = serde_json::from_value(serde_json::json!({
+            "symbols": ["AAPL"],
+            "fast_period": 5,
+            "slow_period": 10,
+            "use_ema": false,
+            "signal_threshold": 0.0
+        }))
+        .unwrap();
+        assert_eq!(legacy.ma_type, MaType::Sma);
+    }
 }
@@ -0,0 +1,457 @@
+//! MACD Signal-Line Crossover Strategy.
+//!
+//! Buys when the MACD line crosses above its signal line (the histogram
+//! turns positive) and exits when it crosses back below. Entries can be
+//! filtered by a minimum histogram, the side of the zero line the MACD is
+//! on, and an RSI confirmation.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use trading_core::{
+    error::StrategyError,
+    traits::{Strategy, StrategyConfig, StrategyState, StreamingIndicator, StreamingState},
+    types::{Bar, BarSeries, MetadataSchema, Signal, SignalMetadata, SignalStrength, SignalType},
+};
+use trading_indicators::{MacdOutput, StreamingMacd, StreamingRsi};
+
+/// Configuration for the MACD strategy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MacdConfig {
+    /// Symbols to trade
+    pub symbols: Vec<String>,
+    /// Fast EMA period
+    pub fast_period: usize,
+    /// Slow EMA period
+    pub slow_period: usize,
+    /// Signal line EMA period
+    pub signal_period: usize,
+    /// Minimum histogram magnitude for an entry crossover
+    pub histogram_threshold: f64,
+    /// Only buy with the MACD above zero (and sell short below it)
+    pub zero_line_filter: bool,
+    /// Require RSI confirmation for entries
+    pub rsi_confirmation: bool,
+    /// RSI period for confirmation
+    pub rsi_period: usize,
+    /// Minimum RSI for long entry
+    pub rsi_long_threshold: f64,
+    /// Maximum RSI for short entry
+    pub rsi_short_threshold: f64,
+    /// Allow short positions
+    pub allow_short: bool,
+}
+
+impl Default for MacdConfig {
+    fn default() -> Self {
+        Self {
+            symbols: vec![],
+            fast_period: 12,
+            slow_period: 26,
+            signal_period: 9,
+            histogram_threshold: 0.0,
+            zero_line_filter: false,
+            rsi_confirmation: false,
+            rsi_period: 14,
+            rsi_long_threshold: 50.0,
+            rsi_short_threshold: 50.0,
+            allow_short: false,
+        }
+    }
+}
+
+impl StrategyConfig for MacdConfig {
+    fn validate(&self) -> Result<(), StrategyError> {
+        if self.fast_period == 0 || self.signal_period == 0 {
+            return Err(StrategyError::InvalidConfig(
+                "MACD periods must be greater than 0".into(),
+            ));
+        }
+        if self.fast_period >= self.slow_period {
+            return Err(StrategyError::InvalidConfig(
+                "Fast period must be less than slow period".into(),
+            ));
+        }
+        if self.histogram_threshold < 0.0 {
+            return Err(StrategyError::InvalidConfig(
+                "Histogram threshold must not be negative".into(),
+            ));
+        }
+        if self.rsi_period == 0 {
+            return Err(StrategyError::InvalidConfig(
+                "RSI period must be greater than 0".into(),
+            ));
+        }
+        if self.symbols.is_empty() {
+            return Err(StrategyError::InvalidConfig(
+                "At least one symbol required".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Position state
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+enum PositionState {
+    #[default]
+    Flat,
+    Long,
+    Short,
+}
+
+/// Indicator readings of a bar.
+#[derive(Debug, Clone, Copy)]
+struct Reading {
+    macd: MacdOutput,
+    /// Histogram of the previous bar
+    prev_histogram: f64,
+    rsi: Option<f64>,
+}
+
+/// MACD and RSI of one symbol.
+struct Indicators {
+    macd: StreamingMacd,
+    rsi: StreamingRsi,
+    prev_histogram: Option<f64>,
+}
+
+impl Indicators {
+    fn new(config: &MacdConfig) -> Self {
+        Self {
+            macd: StreamingMacd::with_periods(
+                config.fast_period,
+                config.slow_period,
+                config.signal_period,
+            ),
+            rsi: StreamingRsi::new(config.rsi_period),
+            prev_histogram: None,
+        }
+    }
+
+    /// Add a bar; returns its reading once a previous histogram exists.
+    fn update(&mut self, bar: &Bar) -> Option<Reading> {
+        let rsi = self.rsi.update(bar.close);
+        let macd = self.macd.update(bar.close)?;
+        let prev_histogram = self.prev_histogram.replace(macd.histogram)?;
+        Some(Reading {
+            macd,
+            prev_histogram,
+            rsi,
+        })
+    }
+}
+
+/// MACD signal-line crossover strategy.
+pub struct MacdStrategy {
+    config: MacdConfig,
+    indicators: StreamingState<Indicators>,
+    positions: HashMap<String, PositionState>,
+    /// Latest reading of any symbol, for monitoring
+    last_reading: Option<Reading>,
+    bars_processed: usize,
+    signals_generated: usize,
+}
+
+impl MacdStrategy {
+    /// Indicator keys of the signals this strategy emits.
+    pub fn signal_schema() -> MetadataSchema {
+        MetadataSchema::new(&["macd", "signal", "histogram"], &["rsi"])
+    }
+
+    /// Create a new MACD strategy.
+    pub fn new(config: MacdConfig) -> Self {
+        Self {
+            config,
+            indicators: StreamingState::new(),
+            positions: HashMap::new(),
+            last_reading: None,
+            bars_processed: 0,
+            signals_generated: 0,
+        }
+    }
+
+    /// Whether the zero-line and RSI filters allow an entry.
+    fn entry_allowed(&self, reading: &Reading, long: bool) -> bool {
+        let zero_line = !self.config.zero_line_filter
+            || if long {
+                reading.macd.macd > 0.0
+            } else {
+                reading.macd.macd < 0.0
+            };
+        let rsi = !self.config.rsi_confirmation
+            || reading.rsi.is_some_and(|rsi| {
+                if long {
+                    rsi >= self.config.rsi_long_threshold
+                } else {
+                    rsi <= self.config.rsi_short_threshold
+                }
+            });
+        zero_line && rsi
+    }
+
+    fn create_signal(
+        &self,
+        bar: &Bar,
+        symbol: &str,
+        signal_type: SignalType,
+        reading: &Reading,
+        reason: String,
+    ) -> Signal {
+        let mut indicators: HashMap<String, f64> = [
+            ("macd".to_string(), reading.macd.macd),
+            ("signal".to_string(), reading.macd.signal),
+            ("histogram".to_string(), reading.macd.histogram),
+        ]
+        .into_iter()
+        .collect();
+        if let Some(rsi) = reading.rsi {
+            indicators.insert("rsi".to_string(), rsi);
+        }
+
+        Signal {
+            symbol: symbol.to_string(),
+            signal_type,
+            strength: SignalStrength::Moderate,
+            price: bar.close,
+            timestamp: bar.timestamp,
+            confidence: 0.6,
+            fraction: None,
+            metadata: SignalMetadata {
+                strategy_name: self.name().to_string(),
+                indicators,
+                reason,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl Strategy for MacdStrategy {
+    fn name(&self) -> &str {
+        "MACD"
+    }
+
+    fn description(&self) -> &str {
+        "Trades MACD signal-line crossovers with optional zero-line and RSI filters"
+    }
+
+    fn metadata_schema(&self) -> Option<MetadataSchema> {
+        Some(Self::signal_schema())
+    }
+
+    fn on_bar(&mut self, series: &BarSeries) -> Option<Signal> {
+        self.bars_processed += 1;
+
+        let config = &self.config;
+        let reading =
+            self.indicators
+                .update(series, || Indicators::new(config), Indicators::update)??;
+        self.last_reading = Some(reading);
+        let bar = series.last()?;
+        let position = self
+            .positions
+            .get(&series.symbol)
+            .copied()
+            .unwrap_or_default();
+
+        let (histogram, prev) = (reading.macd.histogram, reading.prev_histogram);
+        let threshold = self.config.histogram_threshold;
+        let crossed_up = prev <= threshold && histogram > threshold;
+        let crossed_down = prev >= -threshold && histogram < -threshold;
+
+        let (next, signal) = match position {
+            PositionState::Flat if crossed_up && self.entry_allowed(&reading, true) => (
+                PositionState::Long,
+                Some((
+                    SignalType::Buy,
+                    format!(
+                        "MACD crossed above its signal line (histogram {:.4})",
+                        histogram
+                    ),
+                )),
+            ),
+            PositionState::Flat
+                if self.config.allow_short
+                    && crossed_down
+                    && self.entry_allowed(&reading, false) =>
+            {
+                (
+                    PositionState::Short,
+                    Some((
+                        SignalType::Sell,
+                        format!(
+                            "MACD crossed below its signal line (histogram {:.4})",
+                            histogram
+                        ),
+                    )),
+                )
+            }
+            PositionState::Long if histogram < 0.0 => (
+                PositionState::Flat,
+                Some((
+                    SignalType::CloseLong,
+                    "MACD crossed back below its signal line".to_string(),
+                )),
+            ),
+            PositionState::Short if histogram > 0.0 => (
+                PositionState::Flat,
+                Some((
+                    SignalType::CloseShort,
+                    "MACD crossed back above its signal line".to_string(),
+                )),
+            ),
+            unchanged => (unchanged, None),
+        };
+
+        self.positions.insert(series.symbol.clone(), next);
+        let (signal_type, reason) = signal?;
+        self.signals_generated += 1;
+        Some(self.create_signal(bar, &series.symbol, signal_type, &reading, reason))
+    }
+
+    fn reset(&mut self) {
+        self.indicators.clear();
+        self.positions.clear();
+        self.last_reading = None;
+        self.bars_processed = 0;
+        self.signals_generated = 0;
+    }
+
+    fn state(&self) -> StrategyState {
+        let indicators = self
+            .last_reading
+            .map(|r| {
+                [
+                    ("macd".to_string(), r.macd.macd),
+                    ("signal".to_string(), r.macd.signal),
+                    ("histogram".to_string(), r.macd.histogram),
+                ]
+                .into_iter()
+                .collect()
+            })
+            .unwrap_or_default();
+
+        StrategyState {
+            name: self.name().to_string(),
+            is_warmed_up: self.bars_processed >= self.warmup_period(),
+            bars_processed: self.bars_processed,
+            signals_generated: self.signals_generated,
+            indicators,
+            custom: serde_json::json!({
+                "positions": self
+                    .positions
+                    .iter()
+                    .map(|(symbol, p)| (symbol.clone(), format!("{:?}", p)))
+                    .collect::<HashMap<_, _>>(),
+                "histogram_threshold": self.config.histogram_threshold,
+                "zero_line_filter": self.config.zero_line_filter,
+                "rsi_confirmation": self.config.rsi_confirmation,
+            }),
+        }
+    }
+
+    fn warmup_period(&self) -> usize {
+        let macd = self.config.slow_period + self.config.signal_period;
+        if self.config.rsi_confirmation {
+            macd.max(self.config.rsi_period + 1)
+        } else {
+            macd
+        }
+    }
+
+    fn symbols(&self) -> &[String] {
+        &self.config.symbols
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use trading_core::types::Timeframe;
+
+    fn config() -> MacdConfig {
+        MacdConfig {
+            symbols: vec!["TEST".to_string()],
+            fast_period: 3,
+            slow_period: 6,
+            signal_period: 3,
+            ..Default::default()
+        }
+    }
+
+    fn run(strategy: &mut MacdStrategy, closes: &[f64]) -> Vec<SignalType> {
+        let mut series = BarSeries::new("TEST".to_string(), Timeframe::Daily);
+        let mut signals = Vec::new();
+        for (i, &close) in closes.iter().enumerate() {
+            series.push(Bar::new(
+                i as i64 * 86_400_000,
+                close,
+                close + 1.0,
+                close - 1.0,
+                close,
+                1_000.0,
+            ));
+            if let Some(signal) = strategy.on_bar(&series) {
+                signals.push(signal.signal_type);
+            }
+        }
+        signals
+    }
+
+    /// A decline, a rally and a second decline.
+    fn closes() -> Vec<f64> {
+        (0..15)
+            .map(|i| 110.0 - i as f64)
+            .chain((0..15).map(|i| 96.0 + 1.5 * i as f64))
+            .chain((0..15).map(|i| 117.0 - 1.5 * i as f64))
+            .collect()
+    }
+
+    #[test]
+    fn test_crossovers_and_filters() {
+        let signals = run(&mut MacdStrategy::new(config()), &closes());
+        assert_eq!(signals, vec![SignalType::Buy, SignalType::CloseLong]);
+
+        // The rally's crossover happens with the MACD below zero and the RSI
+        // still depressed by the decline
+        let mut zero_line = MacdStrategy::new(MacdConfig {
+            zero_line_filter: true,
+            ..config()
+        });
+        assert!(run(&mut zero_line, &closes()).is_empty());
+        let mut rsi = MacdStrategy::new(MacdConfig {
+            rsi_confirmation: true,
+            rsi_period: 5,
+            ..config()
+        });
+        assert!(run(&mut rsi, &closes()).is_empty());
+
+        // Mirrored prices trade the mirrored crossovers short
+        let mirrored: Vec<f64> = closes().iter().map(|c| 220.0 - c).collect();
+        let mut shorts = MacdStrategy::new(MacdConfig {
+            allow_short: true,
+            ..config()
+        });
+        assert_eq!(
+            run(&mut shorts, &mirrored),
+            vec![SignalType::Sell, SignalType::CloseShort]
+        );
+    }
+
+    #[test]
+    fn test_config_validation() {
+        assert!(config().validate().is_ok());
+        assert!(MacdConfig {
+            fast_period: 6,
+            ..config()
+        }
+        .validate()
+        .is_err());
+        assert!(MacdConfig {
+            histogram_threshold: -1.0,
+            ..config()
+        }
+        .validate()
+        .is_err());
+    }
+}
@@ -6,10 +6,10 @@
 use serde::{Deserialize, Serialize};
 use trading_core::{
     error::StrategyError,
-    traits::{MultiOutputIndicator, Strategy, StrategyConfig, StrategyState},
-    types::{BarSeries, Signal, SignalMetadata, SignalStrength, SignalType},
+    traits::{Strategy, StrategyConfig, StrategyState, StreamingIndicator, StreamingState},
+    types::{BarSeries, MetadataSchema, Signal, SignalMetadata, SignalStrength, SignalType},
 };
-use trading_indicators::BollingerBands;
+use trading_indicators::StreamingBollinger;
 
 /// Configuration for the Mean Reversion strategy.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -78,7 +78,7 @@ enum PositionState {
 /// Mean Reversion Strategy using Bollinger Bands.
 pub struct MeanReversionStrategy {
     config: MeanReversionConfig,
-    bb: BollingerBands,
+    bands: StreamingState<StreamingBollinger>,
     position: PositionState,
     bars_processed: usize,
     signals_generated: usize,
@@ -87,12 +87,19 @@ pub struct MeanReversionStrategy {
 }
 
 impl MeanReversionStrategy {
+    /// Indicator keys of the signals this strategy emits.
+    pub fn signal_schema() -> MetadataSchema {
+        MetadataSchema::new(
+            &["percent_b"],
+            &["upper_band", "middle_band", "lower_band", "bandwidth"],
+        )
+    }
+
     /// Create a new Mean Reversion strategy.
     pub fn new(config: MeanReversionConfig) -> Self {
-        let bb = BollingerBands::with_params(config.bb_period, config.bb_std_dev);
         Self {
             config,
-            bb,
+            bands: StreamingState::new(),
             position: PositionState::Flat,
             bars_processed: 0,
             signals_generated: 0,
@@ -128,6 +135,10 @@ impl Strategy for MeanReversionStrategy {
         "Trades reversions to the mean using Bollinger Bands"
     }
 
+    fn metadata_schema(&self) -> Option<MetadataSchema> {
+        Some(Self::signal_schema())
+    }
+
     fn on_bar(&mut self, series: &BarSeries) -> Option<Signal> {
         self.bars_processed += 1;
 
@@ -135,14 +146,12 @@ impl Strategy for MeanReversionStrategy {
             return None;
         }
 
-        let closes = series.closes();
-        let bb_values = self.bb.calculate(&closes);
-
-        if bb_values.is_empty() {
-            return None;
-        }
-
-        let bb = bb_values.last()?;
+        let (period, std_dev) = (self.config.bb_period, self.config.bb_std_dev);
+        let bb = self.bands.update(
+            series,
+            || StreamingBollinger::new(period, std_dev),
+            |bands, bar| bands.update(bar.close),
+        )??;
         let bar = series.last()?;
 
         self.last_percent_b = Some(bb.percent_b);
@@ -162,6 +171,7 @@ impl Strategy for MeanReversionStrategy {
                         price: bar.close,
                         timestamp: bar.timestamp,
                         confidence: 1.0 - bb.percent_b, // Higher confidence when more oversold
+                        fraction: None,
                         metadata: SignalMetadata {
                             strategy_name: self.name().to_string(),
                             indicators: [
@@ -195,6 +205,7 @@ impl Strategy for MeanReversionStrategy {
                         price: bar.close,
                         timestamp: bar.timestamp,
                         confidence: bb.percent_b,
+                        fraction: None,
                         metadata: SignalMetadata {
                             strategy_name: self.name().to_string(),
                             indicators: [
@@ -231,6 +242,7 @@ impl Strategy for MeanReversionStrategy {
                         price: bar.close,
                         timestamp: bar.timestamp,
                         confidence: 0.8,
+                        fraction: None,
                         metadata: SignalMetadata {
                             strategy_name: self.name().to_string(),
                             indicators: [("percent_b".to_string(), bb.percent_b)]
@@ -259,6 +271,7 @@ impl Strategy for MeanReversionStrategy {
                         price: bar.close,
                         timestamp: bar.timestamp,
                         confidence: 0.8,
+                        fraction: None,
                         metadata: SignalMetadata {
                             strategy_name: self.name().to_string(),
                             indicators: [("percent_b".to_string(), bb.percent_b)]
@@ -281,6 +294,7 @@ impl Strategy for MeanReversionStrategy {
     }
 
     fn reset(&mut self) {
+        self.bands.clear();
         self.position = PositionState::Flat;
         self.bars_processed = 0;
         self.signals_generated = 0;
@@ -7,10 +7,10 @@
 use serde::{Deserialize, Serialize};
 use trading_core::{
     error::StrategyError,
-    traits::{Indicator, Strategy, StrategyConfig, StrategyState},
-    types::{BarSeries, Signal, SignalMetadata, SignalStrength, SignalType},
+    traits::{Strategy, StrategyConfig, StrategyState, StreamingState},
+    types::{Bar, BarSeries, MetadataSchema, Signal, SignalMetadata, SignalStrength, SignalType},
 };
-use trading_indicators::{Ema, Rsi};
+use trading_indicators::{IndicatorPipeline, Input, StreamingEma, StreamingRsi, Transform};
 
 /// Configuration for the Momentum strategy.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -85,9 +85,49 @@ enum PositionState {
     Short,
 }
 
+/// Indicators of one symbol: momentum as the rate of change over the
+/// lookback, trend as (fast EMA - slow EMA) / slow EMA, and RSI.
+fn indicators(config: &MomentumConfig) -> IndicatorPipeline {
+    IndicatorPipeline::new()
+        .with_history(1)
+        .with_transform(
+            "momentum",
+            Input::Close,
+            Transform::Returns(config.momentum_period),
+        )
+        .with_indicator(
+            "fast_ema",
+            Input::Close,
+            StreamingEma::new(config.fast_ema_period),
+        )
+        .with_indicator(
+            "slow_ema",
+            Input::Close,
+            StreamingEma::new(config.slow_ema_period),
+        )
+        .with_combined(
+            "trend",
+            Input::step("fast_ema"),
+            Input::step("slow_ema"),
+            |fast, slow| (slow != 0.0).then(|| (fast - slow) / slow),
+        )
+        .with_indicator("rsi", Input::Close, StreamingRsi::new(config.rsi_period))
+}
+
+/// Add a bar; returns (momentum, trend, RSI) once all are available.
+fn update_indicators(pipeline: &mut IndicatorPipeline, bar: &Bar) -> Option<(f64, f64, f64)> {
+    pipeline.evaluate(bar);
+    Some((
+        pipeline.value("momentum")?,
+        pipeline.value("trend")?,
+        pipeline.value("rsi")?,
+    ))
+}
+
 /// Momentum/Trend Following Strategy.
 pub struct MomentumStrategy {
     config: MomentumConfig,
+    indicators: StreamingState<IndicatorPipeline>,
     position: PositionState,
     bars_processed: usize,
     signals_generated: usize,
@@ -97,10 +137,16 @@ pub struct MomentumStrategy {
 }
 
 impl MomentumStrategy {
+    /// Indicator keys of the signals this strategy emits.
+    pub fn signal_schema() -> MetadataSchema {
+        MetadataSchema::new(&["momentum", "trend"], &["rsi"])
+    }
+
     /// Create a new Momentum strategy.
     pub fn new(config: MomentumConfig) -> Self {
         Self {
             config,
+            indicators: StreamingState::new(),
             position: PositionState::Flat,
             bars_processed: 0,
             signals_generated: 0,
@@ -110,45 +156,6 @@ impl MomentumStrategy {
         }
     }
 
-    /// Calculate momentum as rate of change.
-    fn calculate_momentum(&self, closes: &[f64]) -> Option<f64> {
-        if closes.len() < self.config.momentum_period + 1 {
-            return None;
-        }
-
-        let current = *closes.last()?;
-        let past = closes[closes.len() - self.config.momentum_period - 1];
-
-        if past != 0.0 {
-            Some((current - past) / past)
-        } else {
-            None
-        }
-    }
-
-    /// Calculate trend strength (fast EMA - slow EMA) / slow EMA.
-    fn calculate_trend(&self, closes: &[f64]) -> Option<f64> {
-        let fast_ema = Ema::new(self.config.fast_ema_period);
-        let slow_ema = Ema::new(self.config.slow_ema_period);
-
-        let fast_values = fast_ema.calculate(closes);
-        let slow_values = slow_ema.calculate(closes);
-
-        if fast_values.is_empty() || slow_values.is_empty() {
-            return None;
-        }
-
-        // Use the most recent values from each EMA
-        let fast_val = fast_values.last()?;
-        let slow_val = slow_values.last()?;
-
-        if *slow_val != 0.0 {
-            Some((fast_val - slow_val) / slow_val)
-        } else {
-            None
-        }
-    }
-
     fn classify_strength(&self, momentum: f64, rsi: f64) -> SignalStrength {
         let momentum_abs = momentum.abs();
         let rsi_extreme = if rsi > 50.0 { rsi - 50.0 } else { 50.0 - rsi };
@@ -172,6 +179,10 @@ impl Strategy for MomentumStrategy {
         "Follows strong trends using momentum and RSI confirmation"
     }
 
+    fn metadata_schema(&self) -> Option<MetadataSchema> {
+        Some(Self::signal_schema())
+    }
+
     fn on_bar(&mut self, series: &BarSeries) -> Option<Signal> {
         self.bars_processed += 1;
 
@@ -179,17 +190,12 @@ impl Strategy for MomentumStrategy {
             return None;
         }
 
-        let closes = series.closes();
+        let config = &self.config;
+        let (momentum, trend, rsi) =
+            self.indicators
+                .update(series, || indicators(config), update_indicators)??;
         let bar = series.last()?;
 
-        // Calculate indicators
-        let momentum = self.calculate_momentum(&closes)?;
-        let trend = self.calculate_trend(&closes)?;
-
-        let rsi_indicator = Rsi::new(self.config.rsi_period);
-        let rsi_values = rsi_indicator.calculate(&closes);
-        let rsi = *rsi_values.last()?;
-
         self.last_momentum = Some(momentum);
         self.last_rsi = Some(rsi);
         self.last_trend = Some(trend);
@@ -210,6 +216,7 @@ impl Strategy for MomentumStrategy {
                         price: bar.close,
                         timestamp: bar.timestamp,
                         confidence: (momentum / 0.1).clamp(0.0, 1.0),
+                        fraction: None,
                         metadata: SignalMetadata {
                             strategy_name: self.name().to_string(),
                             indicators: [
@@ -243,6 +250,7 @@ impl Strategy for MomentumStrategy {
                         price: bar.close,
                         timestamp: bar.timestamp,
                         confidence: (momentum.abs() / 0.1).clamp(0.0, 1.0),
+                        fraction: None,
                         metadata: SignalMetadata {
                             strategy_name: self.name().to_string(),
                             indicators: [
@@ -276,6 +284,7 @@ impl Strategy for MomentumStrategy {
                         price: bar.close,
                         timestamp: bar.timestamp,
                         confidence: 0.8,
+                        fraction: None,
                         metadata: SignalMetadata {
                             strategy_name: self.name().to_string(),
                             indicators: [
@@ -304,6 +313,7 @@ impl Strategy for MomentumStrategy {
                         price: bar.close,
                         timestamp: bar.timestamp,
                         confidence: 0.8,
+                        fraction: None,
                         metadata: SignalMetadata {
                             strategy_name: self.name().to_string(),
                             indicators: [
@@ -326,6 +336,7 @@ impl Strategy for MomentumStrategy {
     }
 
     fn reset(&mut self) {
+        self.indicators.clear();
         self.position = PositionState::Flat;
         self.bars_processed = 0;
         self.signals_generated = 0;
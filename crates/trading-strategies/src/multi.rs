@@ -0,0 +1,637 @@
+//! Multi-strategy portfolio.
+//!
+//! Runs several member strategies side by side on shared capital. Unlike an
+//! ensemble, members don't vote: each trades its own signals, tagged with
+//! the member's name so the risk manager can size them from the member's
+//! share of capital (see `[risk.allocation]`).
+//!
+//! A member may trade a subset of the portfolio's symbols, and may be given
+//! a name, e.g. to run one strategy twice with different settings.
+//!
+//! A symbol holds one long position at a time. The member whose entry
+//! fills owns it until its fills bring the position back to flat; other
+//! members' signals for the symbol are ignored meanwhile, and a sell never
+//! opens a position. Members trade through their signals only, so
+//! strategies that place their own orders (rebalance, rotation) can't be
+//! members.
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use trading_core::{
+    error::StrategyError,
+    traits::{Strategy, StrategyConfig, StrategyState},
+    types::{
+        BarSeries, MetadataSchema, MultiTimeframeSeries, Order, Side, Signal, SignalType, Timeframe,
+    },
+};
+
+/// A member strategy of a multi-strategy portfolio.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MultiMember {
+    /// Registry name of the strategy
+    pub strategy: String,
+    /// Settings overriding the strategy's default configuration
+    #[serde(default)]
+    pub config: serde_json::Value,
+    /// Name the member's signals are tagged with (defaults to the strategy)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Symbols the member trades, among the portfolio's (defaults to all)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub symbols: Vec<String>,
+}
+
+impl MultiMember {
+    /// A member with default settings.
+    pub fn new(strategy: impl Into<String>) -> Self {
+        Self {
+            strategy: strategy.into(),
+            config: serde_json::Value::Null,
+            name: None,
+            symbols: Vec::new(),
+        }
+    }
+
+    /// Tag the member's signals with `name`.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Trade only `symbols`.
+    pub fn with_symbols(mut self, symbols: Vec<String>) -> Self {
+        self.symbols = symbols;
+        self
+    }
+
+    /// Whether the member trades `symbol`.
+    pub fn trades(&self, symbol: &str) -> bool {
+        self.symbols.is_empty() || self.symbols.iter().any(|s| s == symbol)
+    }
+}
+
+/// Configuration for the multi-strategy portfolio.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MultiStrategyConfig {
+    /// Symbols to trade, shared by all members
+    pub symbols: Vec<String>,
+    /// Member strategies
+    pub members: Vec<MultiMember>,
+}
+
+impl Default for MultiStrategyConfig {
+    fn default() -> Self {
+        Self {
+            symbols: vec![],
+            members: vec![
+                MultiMember::new("ma_crossover"),
+                MultiMember::new("mean_reversion"),
+                MultiMember::new("donchian_breakout"),
+            ],
+        }
+    }
+}
+
+impl StrategyConfig for MultiStrategyConfig {
+    fn validate(&self) -> Result<(), StrategyError> {
+        if self.members.is_empty() {
+            return Err(StrategyError::InvalidConfig(
+                "At least one member required".into(),
+            ));
+        }
+        if let Some(member) = self
+            .members
+            .iter()
+            .find(|m| ["multi", "ensemble", "rebalance", "rotation"].contains(&m.strategy.as_str()))
+        {
+            return Err(StrategyError::InvalidConfig(format!(
+                "Multi-strategy portfolios cannot contain {}",
+                member.strategy
+            )));
+        }
+        if self.symbols.is_empty() {
+            return Err(StrategyError::InvalidConfig(
+                "At least one symbol required".into(),
+            ));
+        }
+        for (i, member) in self.members.iter().enumerate() {
+            if let Some(symbol) = member.symbols.iter().find(|s| !self.symbols.contains(s)) {
+                return Err(StrategyError::InvalidConfig(format!(
+                    "Member {} trades {}, which is not one of the portfolio's symbols",
+                    member.name.as_deref().unwrap_or(&member.strategy),
+                    symbol
+                )));
+            }
+            if let Some(name) = &member.name {
+                if self.members[..i]
+                    .iter()
+                    .any(|m| m.name.as_ref() == Some(name))
+                {
+                    return Err(StrategyError::InvalidConfig(format!(
+                        "Member name {} used more than once",
+                        name
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Which member holds a symbol's position, and how much of it has filled.
+#[derive(Debug, Clone, Copy)]
+struct Owner {
+    member: usize,
+    quantity: Decimal,
+}
+
+/// Meta-strategy running members side by side, each on its own signals.
+pub struct MultiStrategy {
+    config: MultiStrategyConfig,
+    members: Vec<Box<dyn Strategy>>,
+    /// Member names signals are tagged with: the configured name, or the
+    /// strategy suffixed where it appears more than once
+    labels: Vec<String>,
+    timeframes: Vec<Timeframe>,
+    owners: HashMap<String, Owner>,
+    /// Member whose entry was last passed on for a symbol no one holds,
+    /// which takes the symbol if the entry fills
+    entries: HashMap<String, usize>,
+    bars_processed: usize,
+    signals_generated: usize,
+}
+
+impl MultiStrategy {
+    /// Indicator keys of the signals of a portfolio of members with
+    /// `schemas`: each signal carries one member's keys, so all are
+    /// optional.
+    pub fn signal_schema<'a>(
+        schemas: impl IntoIterator<Item = &'a MetadataSchema>,
+    ) -> MetadataSchema {
+        let mut keys: Vec<&str> = Vec::new();
+        for key in schemas.into_iter().flat_map(|s| s.keys()) {
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+        MetadataSchema::new(&[], &keys)
+    }
+
+    /// Create a portfolio of `members`, in the order of `config.members`.
+    ///
+    /// # Panics
+    /// If the number of members does not match the configuration.
+    pub fn new(config: MultiStrategyConfig, members: Vec<Box<dyn Strategy>>) -> Self {
+        assert_eq!(
+            members.len(),
+            config.members.len(),
+            "one strategy per configured member"
+        );
+
+        let mut labels: Vec<String> = Vec::with_capacity(members.len());
+        for member in &config.members {
+            if let Some(name) = &member.name {
+                labels.push(name.clone());
+                continue;
+            }
+            let name = member.strategy.as_str();
+            let count = labels
+                .iter()
+                .filter(|l| l.split(" #").next() == Some(name))
+                .count();
+            labels.push(if count == 0 {
+                name.to_string()
+            } else {
+                format!("{} #{}", name, count + 1)
+            });
+        }
+
+        let mut timeframes: Vec<Timeframe> = Vec::new();
+        for tf in members.iter().flat_map(|m| m.timeframes()) {
+            if !timeframes.contains(tf) {
+                timeframes.push(*tf);
+            }
+        }
+
+        Self {
+            config,
+            members,
+            labels,
+            timeframes,
+            owners: HashMap::new(),
+            entries: HashMap::new(),
+            bars_processed: 0,
+            signals_generated: 0,
+        }
+    }
+
+    /// Name a member's signals are tagged with.
+    pub fn label(&self, member: usize) -> Option<&str> {
+        self.labels.get(member).map(String::as_str)
+    }
+
+    /// Pass on the signal of the member that owns the symbol, or the first
+    /// buy if no member does.
+    fn select(&mut self, symbol: &str, signals: Vec<Option<Signal>>) -> Option<Signal> {
+        let owner = self.owners.get(symbol).map(|o| o.member);
+        let (member, mut signal) = signals
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, s)| Some((i, s?)))
+            .find(|(i, s)| match owner {
+                Some(owner) => owner == *i,
+                None => s.signal_type == SignalType::Buy,
+            })?;
+        if signal.signal_type == SignalType::Hold {
+            return None;
+        }
+        if owner.is_none() {
+            self.entries.insert(symbol.to_string(), member);
+        }
+
+        signal.metadata.strategy_name = self.labels[member].clone();
+        self.signals_generated += 1;
+        Some(signal)
+    }
+}
+
+impl Strategy for MultiStrategy {
+    fn name(&self) -> &str {
+        "Multi-Strategy"
+    }
+
+    fn description(&self) -> &str {
+        "Runs member strategies side by side on shared capital, each on its own signals"
+    }
+
+    fn metadata_schema(&self) -> Option<MetadataSchema> {
+        let schemas = self
+            .members
+            .iter()
+            .map(|m| m.metadata_schema())
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self::signal_schema(&schemas))
+    }
+
+    fn members(&self) -> Vec<&dyn Strategy> {
+        self.members.iter().map(|m| m.as_ref()).collect()
+    }
+
+    fn timeframes(&self) -> &[Timeframe] {
+        &self.timeframes
+    }
+
+    fn on_bar(&mut self, series: &BarSeries) -> Option<Signal> {
+        self.bars_processed += 1;
+        let signals = self
+            .members
+            .iter_mut()
+            .zip(&self.config.members)
+            .map(|(m, c)| c.trades(&series.symbol).then(|| m.on_bar(series)).flatten())
+            .collect();
+        self.select(&series.symbol, signals)
+    }
+
+    fn on_bars(&mut self, series: &MultiTimeframeSeries) -> Option<Signal> {
+        self.bars_processed += 1;
+        let symbol = series.symbol();
+        let signals = self
+            .members
+            .iter_mut()
+            .zip(&self.config.members)
+            .map(|(m, c)| c.trades(symbol).then(|| m.on_bars(series)).flatten())
+            .collect();
+        self.select(symbol, signals)
+    }
+
+    /// Hand the symbol to the member whose entry filled, and free it once
+    /// the owner's position is closed. The fill is passed on to that member.
+    fn on_fill(&mut self, order: &Order) {
+        let quantity = match order.side {
+            Side::Buy => order.filled_quantity,
+            Side::Sell => -order.filled_quantity,
+        };
+        let member = match self.owners.get_mut(&order.symbol) {
+            Some(owner) => {
+                owner.quantity += quantity;
+                let member = owner.member;
+                if owner.quantity <= Decimal::ZERO {
+                    self.owners.remove(&order.symbol);
+                }
+                member
+            }
+            None if quantity > Decimal::ZERO => {
+                let Some(member) = self.entries.remove(&order.symbol) else {
+                    return;
+                };
+                self.owners
+                    .insert(order.symbol.clone(), Owner { member, quantity });
+                member
+            }
+            None => return,
+        };
+        self.members[member].on_fill(order);
+    }
+
+    fn reset(&mut self) {
+        for member in &mut self.members {
+            member.reset();
+        }
+        self.owners.clear();
+        self.entries.clear();
+        self.bars_processed = 0;
+        self.signals_generated = 0;
+    }
+
+    fn state(&self) -> StrategyState {
+        StrategyState {
+            name: self.name().to_string(),
+            is_warmed_up: self.bars_processed >= self.warmup_period(),
+            bars_processed: self.bars_processed,
+            signals_generated: self.signals_generated,
+            indicators: HashMap::new(),
+            custom: serde_json::json!({
+                "members": self
+                    .labels
+                    .iter()
+                    .zip(&self.members)
+                    .map(|(label, m)| (label.clone(), m.state().signals_generated))
+                    .collect::<HashMap<_, _>>(),
+                "owners": self
+                    .owners
+                    .iter()
+                    .map(|(symbol, o)| (symbol.clone(), self.labels[o.member].clone()))
+                    .collect::<HashMap<_, _>>(),
+            }),
+        }
+    }
+
+    fn warmup_period(&self) -> usize {
+        self.members
+            .iter()
+            .map(|m| m.warmup_period())
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn symbols(&self) -> &[String] {
+        &self.config.symbols
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use trading_core::types::{Bar, OrderRequest, SignalMetadata, SignalStrength};
+
+    /// Fill 10 shares of the order a signal leads to.
+    fn fill(strategy: &mut MultiStrategy, signal: &Signal) {
+        let side = match signal.signal_type {
+            SignalType::Buy | SignalType::CloseShort => Side::Buy,
+            _ => Side::Sell,
+        };
+        let request = OrderRequest::market(&signal.symbol, side, Decimal::from(10));
+        let mut order = Order::from_request(&request);
+        order.filled_quantity = order.quantity;
+        strategy.on_fill(&order);
+    }
+
+    /// Emits a scripted signal type on each bar.
+    struct Scripted {
+        symbols: Vec<String>,
+        script: Vec<Option<SignalType>>,
+    }
+
+    impl Strategy for Scripted {
+        fn name(&self) -> &str {
+            "Scripted"
+        }
+
+        fn on_bar(&mut self, series: &BarSeries) -> Option<Signal> {
+            let signal_type = (*self.script.get(series.len() - 1)?)?;
+            let bar = series.last()?;
+            Some(Signal {
+                symbol: series.symbol.clone(),
+                signal_type,
+                strength: SignalStrength::Moderate,
+                price: bar.close,
+                timestamp: bar.timestamp,
+                confidence: 1.0,
+                fraction: None,
+                metadata: SignalMetadata::new("Scripted"),
+            })
+        }
+
+        fn reset(&mut self) {}
+
+        fn state(&self) -> StrategyState {
+            Default::default()
+        }
+
+        fn warmup_period(&self) -> usize {
+            0
+        }
+
+        fn symbols(&self) -> &[String] {
+            &self.symbols
+        }
+    }
+
+    #[test]
+    fn test_members_own_their_positions() {
+        use SignalType::*;
+        let symbols = vec!["TEST".to_string()];
+        let scripts: [&[Option<SignalType>]; 2] = [
+            &[None, Some(Buy), Some(Buy), None, Some(CloseLong)],
+            &[Some(Buy), Some(Sell), Some(Buy), Some(CloseLong), Some(Buy)],
+        ];
+        let config = MultiStrategyConfig {
+            symbols: symbols.clone(),
+            members: vec![MultiMember::new("scripted"), MultiMember::new("scripted")],
+        };
+        let members = scripts
+            .iter()
+            .map(|script| {
+                Box::new(Scripted {
+                    symbols: symbols.clone(),
+                    script: script.to_vec(),
+                }) as Box<dyn Strategy>
+            })
+            .collect();
+        let mut strategy = MultiStrategy::new(config, members);
+        assert_eq!(strategy.members().len(), 2);
+
+        let mut series = BarSeries::new("TEST".to_string(), Timeframe::Daily);
+        let mut signals = Vec::new();
+        for i in 0..5 {
+            series.push(Bar::new(i * 86_400_000, 100.0, 100.0, 100.0, 100.0, 1e6));
+            if let Some(signal) = strategy.on_bar(&series) {
+                fill(&mut strategy, &signal);
+                signals.push((i, signal.signal_type, signal.metadata.strategy_name.clone()));
+            }
+        }
+
+        // The second member's filled buy claims the symbol, so the first
+        // member's buy is ignored until the sell frees it; then the second
+        // member's signals are ignored until the first closes
+        assert_eq!(
+            signals,
+            vec![
+                (0, Buy, "scripted #2".to_string()),
+                (1, Sell, "scripted #2".to_string()),
+                (2, Buy, "scripted".to_string()),
+                (4, CloseLong, "scripted".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_members_trade_their_own_symbols() {
+        let symbols = vec!["A".to_string(), "B".to_string()];
+        let config = MultiStrategyConfig {
+            symbols: symbols.clone(),
+            members: vec![
+                MultiMember::new("scripted")
+                    .with_name("trend")
+                    .with_symbols(vec!["A".to_string()]),
+                MultiMember::new("scripted"),
+            ],
+        };
+        assert!(config.validate().is_ok());
+        let members = (0..2)
+            .map(|_| {
+                Box::new(Scripted {
+                    symbols: symbols.clone(),
+                    script: vec![Some(SignalType::Buy)],
+                }) as Box<dyn Strategy>
+            })
+            .collect();
+        let mut strategy = MultiStrategy::new(config.clone(), members);
+
+        let mut tags = Vec::new();
+        for symbol in ["A", "B"] {
+            let mut series = BarSeries::new(symbol.to_string(), Timeframe::Daily);
+            series.push(Bar::new(0, 100.0, 100.0, 100.0, 100.0, 1e6));
+            let signal = strategy.on_bar(&series).unwrap();
+            tags.push((signal.symbol, signal.metadata.strategy_name));
+        }
+        assert_eq!(
+            tags,
+            vec![
+                ("A".to_string(), "trend".to_string()),
+                ("B".to_string(), "scripted".to_string()),
+            ]
+        );
+
+        let mut outside = config;
+        outside.members[1].symbols = vec!["C".to_string()];
+        assert!(outside.validate().is_err());
+    }
+
+    #[test]
+    fn test_sell_without_a_holder_is_not_an_entry() {
+        let symbols = vec!["TEST".to_string()];
+        let config = MultiStrategyConfig {
+            symbols: symbols.clone(),
+            members: vec![
+                MultiMember::new("ma_crossover").with_name("fast"),
+                MultiMember::new("ma_crossover").with_name("slow"),
+            ],
+        };
+        let registry = crate::StrategyRegistry::new();
+        let members = [(3, 6), (5, 12)]
+            .iter()
+            .map(|(fast, slow)| {
+                let config = serde_json::json!({ "fast_period": fast, "slow_period": slow });
+                registry
+                    .create_with_overrides("ma_crossover", config, symbols.clone())
+                    .unwrap()
+            })
+            .collect();
+        let mut strategy = MultiStrategy::new(config, members);
+
+        // Flat, down (bearish crosses first), up, then down again
+        let prices = (0..60).map(|i| match i {
+            0..=14 => 100.0,
+            15..=24 => 100.0 - (i - 14) as f64 * 2.0,
+            25..=39 => 80.0 + (i - 24) as f64 * 3.0,
+            _ => 125.0 - (i - 39) as f64 * 3.0,
+        });
+        let mut series = BarSeries::new("TEST".to_string(), Timeframe::Daily);
+        let mut signals = Vec::new();
+        for (i, price) in prices.enumerate() {
+            series.push(Bar::new(
+                i as i64 * 86_400_000,
+                price,
+                price,
+                price,
+                price,
+                1e6,
+            ));
+            if let Some(signal) = strategy.on_bar(&series) {
+                fill(&mut strategy, &signal);
+                signals.push((signal.signal_type, signal.metadata.strategy_name));
+            }
+        }
+
+        // The opening sells are dropped, and the fast member's exit frees the
+        // symbol again
+        assert_eq!(
+            signals,
+            vec![
+                (SignalType::Buy, "fast".to_string()),
+                (SignalType::Sell, "fast".to_string()),
+            ]
+        );
+        assert!(strategy.owners.is_empty());
+    }
+
+    #[test]
+    fn test_unfilled_entry_does_not_take_the_symbol() {
+        use SignalType::*;
+        let symbols = vec!["TEST".to_string()];
+        let config = MultiStrategyConfig {
+            symbols: symbols.clone(),
+            members: vec![MultiMember::new("scripted"), MultiMember::new("scripted")],
+        };
+        let scripts: [&[Option<SignalType>]; 2] =
+            [&[Some(Buy), None, None], &[None, Some(Buy), Some(Buy)]];
+        let members = scripts
+            .iter()
+            .map(|script| {
+                Box::new(Scripted {
+                    symbols: symbols.clone(),
+                    script: script.to_vec(),
+                }) as Box<dyn Strategy>
+            })
+            .collect();
+        let mut strategy = MultiStrategy::new(config, members);
+
+        // The first member's buy never fills (say risk rejected it), so the
+        // second member's buy still goes out, and its fill takes the symbol
+        let mut series = BarSeries::new("TEST".to_string(), Timeframe::Daily);
+        let mut tags = Vec::new();
+        for i in 0..3 {
+            series.push(Bar::new(i * 86_400_000, 100.0, 100.0, 100.0, 100.0, 1e6));
+            let signal = strategy.on_bar(&series).unwrap();
+            if i == 1 {
+                fill(&mut strategy, &signal);
+            }
+            tags.push(signal.metadata.strategy_name);
+        }
+        assert_eq!(tags, ["scripted", "scripted #2", "scripted #2"]);
+        assert_eq!(strategy.owners["TEST"].member, 1);
+    }
+
+    #[test]
+    fn test_order_placing_members_rejected() {
+        for strategy in ["rebalance", "rotation"] {
+            let config = MultiStrategyConfig {
+                symbols: vec!["TEST".to_string()],
+                members: vec![MultiMember::new("ma_crossover"), MultiMember::new(strategy)],
+            };
+            assert!(config.validate().is_err());
+        }
+    }
+}
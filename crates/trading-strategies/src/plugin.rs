@@ -0,0 +1,282 @@
+//! Strategy plugins.
+//!
+//! A plugin is a `cdylib` crate that depends on `trading-strategies` and
+//! declares a registration function with [`declare_strategy_plugin!`]:
+//!
+//! ```ignore
+//! use std::sync::Arc;
+//! use trading_strategies::{declare_strategy_plugin, PluginRegistrar, StrategyInfo};
+//!
+//! fn register(registrar: &mut PluginRegistrar) {
+//!     registrar.register(
+//!         "my_strategy",
+//!         StrategyInfo { /* name, description, defaults, schema */ },
+//!         Arc::new(|config, symbols| Ok(Box::new(MyStrategy::new(config, symbols)?))),
+//!     );
+//! }
+//!
+//! declare_strategy_plugin!(register);
+//! ```
+//!
+//! With the `plugins` feature, [`StrategyRegistry::load_plugin`] and
+//! [`StrategyRegistry::load_plugins`] load such libraries and add their
+//! strategies to the registry. Strategies cross the library boundary as Rust
+//! trait objects, so a plugin must be built with the same compiler and
+//! `trading-strategies` version as the host; the declaration records both
+//! and loading refuses a mismatch.
+
+use crate::{StrategyFactory, StrategyInfo};
+
+/// Version of the plugin declaration layout.
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+/// Compiler the crate was built with; plugins must match it.
+pub const RUSTC_VERSION: &str = env!("TRADING_RUSTC_VERSION");
+
+/// Exported symbol holding a plugin's [`PluginDeclaration`].
+pub const PLUGIN_DECLARATION_SYMBOL: &str = "TRADING_STRATEGY_PLUGIN";
+
+/// What a plugin library exports, written by [`declare_strategy_plugin!`].
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct PluginDeclaration {
+    abi_version: u32,
+    rustc_version: &'static str,
+    crate_version: &'static str,
+    register: fn(&mut PluginRegistrar),
+}
+
+impl PluginDeclaration {
+    /// Declaration of a plugin registering its strategies with `register`,
+    /// stamped with this build's versions.
+    pub const fn new(register: fn(&mut PluginRegistrar)) -> Self {
+        Self {
+            abi_version: PLUGIN_ABI_VERSION,
+            rustc_version: RUSTC_VERSION,
+            crate_version: env!("CARGO_PKG_VERSION"),
+            register,
+        }
+    }
+
+    /// Why a plugin built with this declaration cannot be loaded, if it
+    /// cannot.
+    pub fn incompatibility(&self) -> Option<String> {
+        let host = Self::new(|_| {});
+        if self.abi_version != host.abi_version {
+            Some(format!(
+                "plugin ABI version {}, expected {}",
+                self.abi_version, host.abi_version
+            ))
+        } else if self.rustc_version != host.rustc_version {
+            Some(format!(
+                "built with {}, expected {}",
+                self.rustc_version, host.rustc_version
+            ))
+        } else if self.crate_version != host.crate_version {
+            Some(format!(
+                "built against trading-strategies {}, expected {}",
+                self.crate_version, host.crate_version
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Strategies the plugin registers.
+    pub fn strategies(&self) -> Vec<(String, StrategyInfo, StrategyFactory)> {
+        let mut registrar = PluginRegistrar::default();
+        (self.register)(&mut registrar);
+        registrar.strategies
+    }
+}
+
+/// Collects the strategies a plugin registers.
+#[derive(Default)]
+pub struct PluginRegistrar {
+    strategies: Vec<(String, StrategyInfo, StrategyFactory)>,
+}
+
+impl PluginRegistrar {
+    /// Register a strategy under `name`.
+    pub fn register(&mut self, name: &str, info: StrategyInfo, factory: StrategyFactory) {
+        self.strategies.push((name.to_string(), info, factory));
+    }
+}
+
+/// Export a plugin's registration function from a `cdylib`.
+#[macro_export]
+macro_rules! declare_strategy_plugin {
+    ($register:path) => {
+        #[no_mangle]
+        pub static TRADING_STRATEGY_PLUGIN: $crate::PluginDeclaration =
+            $crate::PluginDeclaration::new($register);
+    };
+}
+
+#[cfg(feature = "plugins")]
+mod loader {
+    use super::{PluginDeclaration, PLUGIN_DECLARATION_SYMBOL};
+    use crate::{StrategyFactory, StrategyInfo, StrategyRegistry};
+    use std::collections::HashSet;
+    use std::path::Path;
+    use trading_core::error::StrategyError;
+
+    impl StrategyRegistry {
+        /// Load a plugin library and register its strategies, returning
+        /// their names. Plugins stay loaded for the life of the process.
+        ///
+        /// # Safety
+        /// Loading runs the library's initialisers and trusts its
+        /// declaration; only load plugins from trusted sources.
+        pub unsafe fn load_plugin(&mut self, path: &Path) -> Result<Vec<String>, StrategyError> {
+            let failed = |e: String| {
+                StrategyError::InitializationFailed(format!("{}: {}", path.display(), e))
+            };
+
+            let library = libloading::Library::new(path).map_err(|e| failed(e.to_string()))?;
+            let declaration: PluginDeclaration = library
+                .get::<*const PluginDeclaration>(PLUGIN_DECLARATION_SYMBOL.as_bytes())
+                .map_err(|e| failed(e.to_string()))?
+                .read();
+            if let Some(reason) = declaration.incompatibility() {
+                return Err(failed(reason));
+            }
+
+            let names = self
+                .register_all(declaration.strategies())
+                .map_err(failed)?;
+
+            // Strategies point into the library, so it is never unloaded
+            std::mem::forget(library);
+            Ok(names)
+        }
+
+        /// Register a plugin's strategies, all or none: a name that is
+        /// already registered, or repeated by the plugin, registers nothing.
+        pub(super) fn register_all(
+            &mut self,
+            strategies: Vec<(String, StrategyInfo, StrategyFactory)>,
+        ) -> Result<Vec<String>, String> {
+            let mut seen = HashSet::new();
+            for (name, ..) in &strategies {
+                if self.exists(name) {
+                    return Err(format!("strategy {} is already registered", name));
+                }
+                if !seen.insert(name.as_str()) {
+                    return Err(format!("strategy {} is registered twice", name));
+                }
+            }
+
+            let mut names = Vec::new();
+            for (name, info, factory) in strategies {
+                self.register(&name, info, factory)
+                    .map_err(|e| e.to_string())?;
+                names.push(name);
+            }
+            Ok(names)
+        }
+
+        /// Load every plugin library in `dir`, in file name order.
+        ///
+        /// # Safety
+        /// As for [`load_plugin`](Self::load_plugin), for every library in
+        /// the directory.
+        pub unsafe fn load_plugins(&mut self, dir: &Path) -> Result<Vec<String>, StrategyError> {
+            let entries = std::fs::read_dir(dir).map_err(|e| {
+                StrategyError::InitializationFailed(format!("{}: {}", dir.display(), e))
+            })?;
+            let mut paths: Vec<_> = entries
+                .filter_map(|entry| Some(entry.ok()?.path()))
+                .filter(|path| {
+                    path.extension()
+                        .is_some_and(|ext| ext == std::env::consts::DLL_EXTENSION)
+                })
+                .collect();
+            paths.sort();
+
+            let mut names = Vec::new();
+            for path in paths {
+                names.extend(self.load_plugin(&path)?);
+            }
+            Ok(names)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RsiConfig, RsiStrategy, StrategyRegistry};
+    use std::sync::Arc;
+    use trading_core::{error::StrategyError, traits::StrategyConfig};
+
+    fn register(registrar: &mut PluginRegistrar) {
+        registrar.register(
+            "plugin_rsi",
+            StrategyInfo {
+                name: "Plugin RSI".to_string(),
+                description: "RSI strategy registered by a plugin".to_string(),
+                default_config: serde_json::to_value(RsiConfig::default()).unwrap(),
+                metadata_schema: RsiStrategy::signal_schema(),
+            },
+            Arc::new(|config, symbols| {
+                let mut config: RsiConfig = serde_json::from_value(config)
+                    .map_err(|e| StrategyError::InvalidConfig(e.to_string()))?;
+                config.symbols = symbols;
+                config.validate()?;
+                Ok(Box::new(RsiStrategy::new(config)))
+            }),
+        );
+    }
+
+    #[test]
+    fn test_plugin_declaration_registers_strategies() {
+        let declaration = PluginDeclaration::new(register);
+        assert!(declaration.incompatibility().is_none());
+
+        let mut registry = StrategyRegistry::new();
+        for (name, info, factory) in declaration.strategies() {
+            registry.register(&name, info, factory).unwrap();
+        }
+        let strategy = registry
+            .create_default("plugin_rsi", vec!["AAPL".to_string()])
+            .unwrap();
+        assert_eq!(strategy.name(), "RSI Strategy");
+
+        // Names are unique, built-in or not
+        let (name, info, factory) = declaration.strategies().remove(0);
+        assert!(registry.register(&name, info, factory).is_err());
+
+        let stale = PluginDeclaration {
+            rustc_version: "rustc 1.0.0",
+            ..declaration
+        };
+        assert!(stale.incompatibility().is_some());
+    }
+
+    #[cfg(feature = "plugins")]
+    #[test]
+    fn test_load_plugin_errors() {
+        let mut registry = StrategyRegistry::new();
+        let missing = std::path::Path::new("does/not/exist.so");
+        assert!(unsafe { registry.load_plugin(missing) }.is_err());
+        assert!(unsafe { registry.load_plugins(missing) }.is_err());
+    }
+
+    #[cfg(feature = "plugins")]
+    #[test]
+    fn test_duplicate_plugin_strategies_register_nothing() {
+        fn register_twice(registrar: &mut PluginRegistrar) {
+            register(registrar);
+            register(registrar);
+        }
+
+        let mut registry = StrategyRegistry::new();
+        let strategies = PluginDeclaration::new(register_twice).strategies();
+        assert!(registry.register_all(strategies).is_err());
+        assert!(!registry.exists("plugin_rsi"));
+
+        let strategies = PluginDeclaration::new(register).strategies();
+        assert_eq!(registry.register_all(strategies).unwrap(), ["plugin_rsi"]);
+    }
+}
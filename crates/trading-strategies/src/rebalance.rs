@@ -0,0 +1,461 @@
+//! Target-Weight Rebalancing Strategy.
+//!
+//! Holds a fixed-weight portfolio (60/40, an equal-weight basket, ...) and
+//! trades it back to its targets on a schedule, when any weight drifts too
+//! far, or both.
+//!
+//! The strategy manages `capital` of the account: it tracks its holdings
+//! and cash from the fills it is sent, values them at the latest closes,
+//! and only rebalances once every symbol has a bar at the same timestamp.
+//! A rebalance is one multi-leg batch of market orders, sells first, so
+//! risk checks pass or reject it as a whole.
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use trading_core::{
+    error::StrategyError,
+    traits::{Strategy, StrategyAction, StrategyConfig, StrategyOrders, StrategyState},
+    types::{
+        BarSeries, MetadataSchema, MultiTimeframeSeries, Order, OrderRequest, Side, Signal,
+        Timeframe,
+    },
+};
+
+/// Configuration for the rebalancing strategy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RebalanceConfig {
+    /// Symbols in the portfolio
+    pub symbols: Vec<String>,
+    /// Target weight per symbol, as a fraction of portfolio value; symbols
+    /// without one share what is left equally
+    pub weights: HashMap<String, f64>,
+    /// Portfolio value the strategy manages, in account currency
+    pub capital: f64,
+    /// Rebalance on the first bar of each period, if set
+    pub interval: Option<Timeframe>,
+    /// Rebalance when a weight drifts this many percentage points from its
+    /// target (0 disables)
+    pub drift_threshold_pct: f64,
+    /// Trade fractional shares instead of whole shares
+    pub fractional: bool,
+}
+
+impl Default for RebalanceConfig {
+    fn default() -> Self {
+        Self {
+            symbols: vec![],
+            weights: HashMap::new(),
+            capital: 100_000.0,
+            interval: Some(Timeframe::Monthly),
+            drift_threshold_pct: 5.0,
+            fractional: false,
+        }
+    }
+}
+
+impl RebalanceConfig {
+    /// Target weight of every symbol.
+    pub fn targets(&self) -> HashMap<String, f64> {
+        let explicit: f64 = self.weights.values().sum();
+        let unweighted = self
+            .symbols
+            .iter()
+            .filter(|s| !self.weights.contains_key(*s))
+            .count();
+        let share = if unweighted > 0 {
+            (1.0 - explicit).max(0.0) / unweighted as f64
+        } else {
+            0.0
+        };
+        self.symbols
+            .iter()
+            .map(|s| (s.clone(), self.weights.get(s).copied().unwrap_or(share)))
+            .collect()
+    }
+}
+
+impl StrategyConfig for RebalanceConfig {
+    fn validate(&self) -> Result<(), StrategyError> {
+        if self.symbols.is_empty() {
+            return Err(StrategyError::InvalidConfig(
+                "At least one symbol required".into(),
+            ));
+        }
+        if let Some(symbol) = self.weights.keys().find(|s| !self.symbols.contains(s)) {
+            return Err(StrategyError::InvalidConfig(format!(
+                "Weight given for {} which is not traded",
+                symbol
+            )));
+        }
+        if self.weights.values().any(|&w| !(0.0..=1.0).contains(&w)) {
+            return Err(StrategyError::InvalidConfig(
+                "Weights must be between 0 and 1".into(),
+            ));
+        }
+        if self.weights.values().sum::<f64>() > 1.0 + 1e-9 {
+            return Err(StrategyError::InvalidConfig(
+                "Weights must not sum to more than 1".into(),
+            ));
+        }
+        if !(self.capital > 0.0 && self.capital.is_finite()) {
+            return Err(StrategyError::InvalidConfig(
+                "Capital must be positive".into(),
+            ));
+        }
+        if self.drift_threshold_pct < 0.0 {
+            return Err(StrategyError::InvalidConfig(
+                "Drift threshold must not be negative".into(),
+            ));
+        }
+        if self.interval.is_none() && self.drift_threshold_pct == 0.0 {
+            return Err(StrategyError::InvalidConfig(
+                "Set an interval, a drift threshold or both".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Target-weight portfolio rebalancing.
+pub struct RebalanceStrategy {
+    config: RebalanceConfig,
+    targets: HashMap<String, f64>,
+    /// Latest (timestamp, close) per symbol
+    prices: HashMap<String, (i64, f64)>,
+    /// Shares held per symbol, from fills
+    holdings: HashMap<String, Decimal>,
+    /// Uninvested part of `capital`, from fills
+    cash: f64,
+    /// Start of the interval of the last scheduled rebalance
+    last_period: Option<i64>,
+    bars_processed: usize,
+    rebalances: usize,
+}
+
+impl RebalanceStrategy {
+    /// Indicator keys of the signals this strategy emits: none, as it only
+    /// places orders.
+    pub fn signal_schema() -> MetadataSchema {
+        MetadataSchema::default()
+    }
+
+    /// Create a new rebalancing strategy.
+    pub fn new(config: RebalanceConfig) -> Self {
+        Self {
+            targets: config.targets(),
+            cash: config.capital,
+            config,
+            prices: HashMap::new(),
+            holdings: HashMap::new(),
+            last_period: None,
+            bars_processed: 0,
+            rebalances: 0,
+        }
+    }
+
+    fn held(&self, symbol: &str) -> f64 {
+        self.holdings
+            .get(symbol)
+            .and_then(|&q| q.try_into().ok())
+            .unwrap_or(0.0)
+    }
+
+    fn price(&self, symbol: &str) -> f64 {
+        self.prices.get(symbol).map_or(0.0, |&(_, close)| close)
+    }
+
+    /// Value of the holdings and cash at the latest closes.
+    fn portfolio_value(&self) -> f64 {
+        self.cash
+            + self
+                .config
+                .symbols
+                .iter()
+                .map(|s| self.held(s) * self.price(s))
+                .sum::<f64>()
+    }
+
+    /// Current weight of every symbol.
+    fn weights(&self) -> HashMap<String, f64> {
+        let value = self.portfolio_value();
+        self.config
+            .symbols
+            .iter()
+            .map(|s| {
+                let weight = if value > 0.0 {
+                    self.held(s) * self.price(s) / value
+                } else {
+                    0.0
+                };
+                (s.clone(), weight)
+            })
+            .collect()
+    }
+
+    /// Largest distance of a weight from its target, in percentage points.
+    fn max_drift_pct(&self) -> f64 {
+        self.weights()
+            .iter()
+            .map(|(s, w)| (w - self.targets[s]).abs() * 100.0)
+            .fold(0.0, f64::max)
+    }
+
+    /// Orders taking the holdings to their targets, sells first.
+    fn rebalance_orders(&self) -> Vec<OrderRequest> {
+        let value = self.portfolio_value();
+        let mut legs: Vec<OrderRequest> = self
+            .config
+            .symbols
+            .iter()
+            .filter_map(|symbol| {
+                let price = self.price(symbol);
+                if price <= 0.0 {
+                    return None;
+                }
+                let target = Decimal::try_from(self.targets[symbol] * value / price).ok()?;
+                let target = if self.config.fractional {
+                    target.round_dp(6)
+                } else {
+                    target.floor()
+                };
+                let delta = target - self.holdings.get(symbol).copied().unwrap_or_default();
+                let side = if delta > Decimal::ZERO {
+                    Side::Buy
+                } else {
+                    Side::Sell
+                };
+                (!delta.is_zero()).then(|| OrderRequest::market(symbol, side, delta.abs()))
+            })
+            .collect();
+        legs.sort_by_key(|leg| leg.side == Side::Buy);
+        legs
+    }
+}
+
+impl Strategy for RebalanceStrategy {
+    fn name(&self) -> &str {
+        "Rebalance"
+    }
+
+    fn description(&self) -> &str {
+        "Holds a target-weight portfolio, rebalancing on a schedule or drift"
+    }
+
+    fn metadata_schema(&self) -> Option<MetadataSchema> {
+        Some(Self::signal_schema())
+    }
+
+    fn on_bar(&mut self, series: &BarSeries) -> Option<Signal> {
+        self.bars_processed += 1;
+        let bar = series.last()?;
+        self.prices
+            .insert(series.symbol.clone(), (bar.timestamp, bar.close));
+        None
+    }
+
+    fn act(&mut self, series: &MultiTimeframeSeries) -> Option<StrategyAction> {
+        self.on_bars(series);
+        let timestamp = series.primary().last()?.timestamp;
+
+        // Rebalance on a consistent snapshot: every symbol at this bar
+        let synchronized = self
+            .config
+            .symbols
+            .iter()
+            .all(|s| matches!(self.prices.get(s), Some(&(t, _)) if t == timestamp));
+        if !synchronized {
+            return None;
+        }
+
+        let period = self.config.interval.map(|tf| tf.bucket_start(timestamp));
+        let scheduled = period.is_some() && period != self.last_period;
+        let drift = self.max_drift_pct();
+        let drifted =
+            self.config.drift_threshold_pct > 0.0 && drift > self.config.drift_threshold_pct;
+        if !scheduled && !drifted {
+            return None;
+        }
+        if scheduled {
+            self.last_period = period;
+        }
+
+        let legs = self.rebalance_orders();
+        if legs.is_empty() {
+            return None;
+        }
+        self.rebalances += 1;
+        let reason = if scheduled {
+            format!("Scheduled rebalance (max drift {:.1}pp)", drift)
+        } else {
+            format!("Weights drifted {:.1}pp from target", drift)
+        };
+        Some(StrategyAction::Orders(
+            StrategyOrders::new(legs).with_reason(reason),
+        ))
+    }
+
+    fn on_fill(&mut self, order: &Order) {
+        if !self.targets.contains_key(&order.symbol) {
+            return;
+        }
+        let price = order.filled_avg_price.unwrap_or_default();
+        let notional: f64 = (price * order.filled_quantity).try_into().unwrap_or(0.0);
+        let commission: f64 = order
+            .fills
+            .iter()
+            .map(|f| f.commission)
+            .sum::<Decimal>()
+            .try_into()
+            .unwrap_or(0.0);
+
+        let held = self.holdings.entry(order.symbol.clone()).or_default();
+        match order.side {
+            Side::Buy => {
+                *held += order.filled_quantity;
+                self.cash -= notional;
+            }
+            Side::Sell => {
+                *held -= order.filled_quantity;
+                self.cash += notional;
+            }
+        }
+        self.cash -= commission;
+    }
+
+    fn reset(&mut self) {
+        self.prices.clear();
+        self.holdings.clear();
+        self.cash = self.config.capital;
+        self.last_period = None;
+        self.bars_processed = 0;
+        self.rebalances = 0;
+    }
+
+    fn state(&self) -> StrategyState {
+        StrategyState {
+            name: self.name().to_string(),
+            is_warmed_up: true,
+            bars_processed: self.bars_processed,
+            signals_generated: self.rebalances,
+            indicators: self
+                .weights()
+                .into_iter()
+                .map(|(s, w)| (format!("weight_{}", s), w))
+                .collect(),
+            custom: serde_json::json!({
+                "targets": self.targets,
+                "cash": self.cash,
+                "portfolio_value": self.portfolio_value(),
+                "max_drift_pct": self.max_drift_pct(),
+            }),
+        }
+    }
+
+    fn warmup_period(&self) -> usize {
+        0
+    }
+
+    fn symbols(&self) -> &[String] {
+        &self.config.symbols
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use trading_core::types::{Bar, OrderStatus};
+
+    /// Fill every leg at the latest close, as a broker would.
+    fn fill_all(strategy: &mut RebalanceStrategy, legs: &[OrderRequest], closes: &[f64]) {
+        for leg in legs {
+            let close = if leg.symbol == "SPY" {
+                closes[0]
+            } else {
+                closes[1]
+            };
+            let mut order = Order::from_request(leg);
+            order.status = OrderStatus::Filled;
+            order.filled_quantity = leg.quantity;
+            order.filled_avg_price = Decimal::try_from(close).ok();
+            strategy.on_fill(&order);
+        }
+    }
+
+    #[test]
+    fn test_sixty_forty_rebalances_on_drift() {
+        let mut strategy = RebalanceStrategy::new(RebalanceConfig {
+            symbols: vec!["SPY".to_string(), "AGG".to_string()],
+            weights: HashMap::from([("SPY".to_string(), 0.6)]),
+            capital: 10_000.0,
+            interval: None,
+            drift_threshold_pct: 5.0,
+            fractional: false,
+        });
+        let mut spy = MultiTimeframeSeries::new("SPY".to_string(), Timeframe::Daily, 8, &[]);
+        let mut agg = MultiTimeframeSeries::new("AGG".to_string(), Timeframe::Daily, 8, &[]);
+
+        let mut step = |strategy: &mut RebalanceStrategy, day: i64, closes: [f64; 2]| {
+            let bar = |c: f64| Bar::new(day * 86_400_000, c, c, c, c, 1_000.0);
+            spy.push(bar(closes[0]));
+            // Nothing happens until both symbols have the bar
+            assert!(strategy.act(&spy).is_none());
+            agg.push(bar(closes[1]));
+            let legs = match strategy.act(&agg) {
+                Some(StrategyAction::Orders(orders)) => orders.legs,
+                _ => Vec::new(),
+            };
+            fill_all(strategy, &legs, &closes);
+            legs
+        };
+
+        // Initial allocation: AGG takes the remaining 40%
+        let legs = step(&mut strategy, 0, [100.0, 50.0]);
+        let quantities: Vec<_> = legs
+            .iter()
+            .map(|l| (l.symbol.as_str(), l.quantity))
+            .collect();
+        assert_eq!(
+            quantities,
+            vec![("SPY", Decimal::from(60)), ("AGG", Decimal::from(80))]
+        );
+
+        // A small move stays within the threshold
+        assert!(step(&mut strategy, 1, [105.0, 50.0]).is_empty());
+
+        // SPY rallies to ~69% of the portfolio; sell it down, buy AGG
+        let legs = step(&mut strategy, 2, [150.0, 50.0]);
+        assert_eq!(legs.len(), 2);
+        assert_eq!((legs[0].symbol.as_str(), legs[0].side), ("SPY", Side::Sell));
+        assert_eq!((legs[1].symbol.as_str(), legs[1].side), ("AGG", Side::Buy));
+        assert!(strategy.max_drift_pct() < 1.0);
+        assert!((strategy.portfolio_value() - 13_000.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let config = RebalanceConfig {
+            symbols: vec!["SPY".to_string(), "AGG".to_string()],
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+        assert_eq!(config.targets()["AGG"], 0.5);
+
+        let overweight = RebalanceConfig {
+            weights: HashMap::from([("SPY".to_string(), 0.7), ("AGG".to_string(), 0.4)]),
+            ..config.clone()
+        };
+        assert!(overweight.validate().is_err());
+        let unknown = RebalanceConfig {
+            weights: HashMap::from([("QQQ".to_string(), 0.5)]),
+            ..config.clone()
+        };
+        assert!(unknown.validate().is_err());
+        let never = RebalanceConfig {
+            interval: None,
+            drift_threshold_pct: 0.0,
+            ..config
+        };
+        assert!(never.validate().is_err());
+    }
+}
@@ -1,12 +1,19 @@
 //! Strategy registry for dynamic strategy loading.
 
 use crate::{
-    MACrossoverConfig, MACrossoverStrategy, MeanReversionConfig, MeanReversionStrategy,
-    MomentumConfig, MomentumStrategy, RsiConfig, RsiStrategy,
+    BreakoutConfig, BreakoutStrategy, DcaConfig, DcaStrategy, DonchianBreakoutConfig,
+    DonchianBreakoutStrategy, EnsembleConfig, EnsembleStrategy, MACrossoverConfig,
+    MACrossoverStrategy, MacdConfig, MacdStrategy, MeanReversionConfig, MeanReversionStrategy,
+    MomentumConfig, MomentumStrategy, MultiStrategy, MultiStrategyConfig, RebalanceConfig,
+    RebalanceStrategy, RotationConfig, RotationStrategy, RsiConfig, RsiStrategy, RulesConfig,
+    RulesStrategy, ScriptStrategy,
 };
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use trading_core::{error::StrategyError, traits::Strategy, traits::StrategyConfig};
+use std::sync::Arc;
+use trading_core::{
+    error::StrategyError, traits::Strategy, traits::StrategyConfig, types::MetadataSchema,
+};
 
 /// Information about a registered strategy.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,11 +24,26 @@ pub struct StrategyInfo {
     pub description: String,
     /// Default configuration as JSON
     pub default_config: serde_json::Value,
+    /// Indicator keys of the strategy's signal metadata
+    pub metadata_schema: MetadataSchema,
 }
 
+/// Prefix of strategy names that load a Rhai script, e.g.
+/// `script:strategies/my_idea.rhai`.
+pub const SCRIPT_PREFIX: &str = "script:";
+
+/// Creates a strategy from its configuration and symbols.
+pub type StrategyFactory = Arc<
+    dyn Fn(serde_json::Value, Vec<String>) -> Result<Box<dyn Strategy>, StrategyError>
+        + Send
+        + Sync,
+>;
+
 /// Registry for available trading strategies.
 pub struct StrategyRegistry {
     strategies: HashMap<String, StrategyInfo>,
+    /// Strategies added with [`register`](Self::register), e.g. by plugins
+    factories: HashMap<String, StrategyFactory>,
 }
 
 impl StrategyRegistry {
@@ -36,6 +58,7 @@ impl StrategyRegistry {
                 description: "Generates signals based on fast/slow moving average crossovers"
                     .to_string(),
                 default_config: serde_json::to_value(MACrossoverConfig::default()).unwrap(),
+                metadata_schema: MACrossoverStrategy::signal_schema(),
             },
         );
 
@@ -45,6 +68,7 @@ impl StrategyRegistry {
                 name: "Mean Reversion".to_string(),
                 description: "Trades reversions to the mean using Bollinger Bands".to_string(),
                 default_config: serde_json::to_value(MeanReversionConfig::default()).unwrap(),
+                metadata_schema: MeanReversionStrategy::signal_schema(),
             },
         );
 
@@ -55,6 +79,7 @@ impl StrategyRegistry {
                 description: "Follows strong trends using momentum and RSI confirmation"
                     .to_string(),
                 default_config: serde_json::to_value(MomentumConfig::default()).unwrap(),
+                metadata_schema: MomentumStrategy::signal_schema(),
             },
         );
 
@@ -64,10 +89,139 @@ impl StrategyRegistry {
                 name: "RSI Strategy".to_string(),
                 description: "Trades RSI overbought/oversold reversals".to_string(),
                 default_config: serde_json::to_value(RsiConfig::default()).unwrap(),
+                metadata_schema: RsiStrategy::signal_schema(),
+            },
+        );
+
+        strategies.insert(
+            "donchian_breakout".to_string(),
+            StrategyInfo {
+                name: "Donchian Breakout".to_string(),
+                description: "Turtle-style breakouts of the Donchian channel".to_string(),
+                default_config: serde_json::to_value(DonchianBreakoutConfig::default()).unwrap(),
+                metadata_schema: DonchianBreakoutStrategy::signal_schema(),
+            },
+        );
+
+        strategies.insert(
+            "macd".to_string(),
+            StrategyInfo {
+                name: "MACD".to_string(),
+                description: "MACD signal-line crossovers with zero-line and RSI filters"
+                    .to_string(),
+                default_config: serde_json::to_value(MacdConfig::default()).unwrap(),
+                metadata_schema: MacdStrategy::signal_schema(),
+            },
+        );
+
+        strategies.insert(
+            "breakout".to_string(),
+            StrategyInfo {
+                name: "Breakout".to_string(),
+                description: "N-bar high/low breakouts with ATR trailing stops".to_string(),
+                default_config: serde_json::to_value(BreakoutConfig::default()).unwrap(),
+                metadata_schema: BreakoutStrategy::signal_schema(),
+            },
+        );
+
+        strategies.insert(
+            "dca".to_string(),
+            StrategyInfo {
+                name: "DCA".to_string(),
+                description: "Buys a fixed notional on a schedule, regardless of price".to_string(),
+                default_config: serde_json::to_value(DcaConfig::default()).unwrap(),
+                metadata_schema: DcaStrategy::signal_schema(),
+            },
+        );
+
+        strategies.insert(
+            "rebalance".to_string(),
+            StrategyInfo {
+                name: "Rebalance".to_string(),
+                description: "Holds a target-weight portfolio, rebalancing on a schedule or drift"
+                    .to_string(),
+                default_config: serde_json::to_value(RebalanceConfig::default()).unwrap(),
+                metadata_schema: RebalanceStrategy::signal_schema(),
+            },
+        );
+
+        strategies.insert(
+            "rotation".to_string(),
+            StrategyInfo {
+                name: "Rotation".to_string(),
+                description:
+                    "Holds the top-K symbols of a basket by trailing momentum, rotating each period"
+                        .to_string(),
+                default_config: serde_json::to_value(RotationConfig::default()).unwrap(),
+                metadata_schema: RotationStrategy::signal_schema(),
+            },
+        );
+
+        strategies.insert(
+            "ensemble".to_string(),
+            StrategyInfo {
+                name: "Ensemble".to_string(),
+                description:
+                    "Trades on the majority, weighted or confidence-weighted vote of member strategies"
+                        .to_string(),
+                default_config: serde_json::to_value(EnsembleConfig::default()).unwrap(),
+                metadata_schema: EnsembleStrategy::signal_schema(),
+            },
+        );
+
+        let multi_schema = MultiStrategy::signal_schema(
+            MultiStrategyConfig::default()
+                .members
+                .iter()
+                .map(|m| &strategies[&m.strategy].metadata_schema),
+        );
+        strategies.insert(
+            "multi".to_string(),
+            StrategyInfo {
+                name: "Multi-Strategy".to_string(),
+                description:
+                    "Runs member strategies side by side on shared capital, each on its own signals"
+                        .to_string(),
+                default_config: serde_json::to_value(MultiStrategyConfig::default()).unwrap(),
+                metadata_schema: multi_schema,
             },
         );
 
-        Self { strategies }
+        strategies.insert(
+            "rules".to_string(),
+            StrategyInfo {
+                name: "Rules".to_string(),
+                description: "Trades on entry and exit conditions written as expressions"
+                    .to_string(),
+                default_config: serde_json::to_value(RulesConfig::default()).unwrap(),
+                metadata_schema: RulesStrategy::new(RulesConfig::default()).signal_schema(),
+            },
+        );
+
+        Self {
+            strategies,
+            factories: HashMap::new(),
+        }
+    }
+
+    /// Add a strategy created by `factory` under `name`.
+    ///
+    /// Fails if the name is already taken.
+    pub fn register(
+        &mut self,
+        name: &str,
+        info: StrategyInfo,
+        factory: StrategyFactory,
+    ) -> Result<(), StrategyError> {
+        if self.exists(name) {
+            return Err(StrategyError::InvalidConfig(format!(
+                "Strategy {} is already registered",
+                name
+            )));
+        }
+        self.strategies.insert(name.to_string(), info);
+        self.factories.insert(name.to_string(), factory);
+        Ok(())
     }
 
     /// List all available strategies.
@@ -82,7 +236,7 @@ impl StrategyRegistry {
 
     /// Check if a strategy exists.
     pub fn exists(&self, name: &str) -> bool {
-        self.strategies.contains_key(name)
+        self.strategies.contains_key(name) || name.starts_with(SCRIPT_PREFIX)
     }
 
     /// Get all strategy names.
@@ -97,6 +251,12 @@ impl StrategyRegistry {
         config: serde_json::Value,
         symbols: Vec<String>,
     ) -> Result<Box<dyn Strategy>, StrategyError> {
+        if let Some(path) = name.strip_prefix(SCRIPT_PREFIX) {
+            return Ok(Box::new(ScriptStrategy::from_file(path, config, symbols)?));
+        }
+        if let Some(factory) = self.factories.get(name) {
+            return factory(config, symbols);
+        }
         match name {
             "ma_crossover" => {
                 let mut config: MACrossoverConfig = serde_json::from_value(config)
@@ -126,6 +286,92 @@ impl StrategyRegistry {
                 config.validate()?;
                 Ok(Box::new(RsiStrategy::new(config)))
             }
+            "donchian_breakout" => {
+                let mut config: DonchianBreakoutConfig = serde_json::from_value(config)
+                    .map_err(|e| StrategyError::InvalidConfig(e.to_string()))?;
+                config.symbols = symbols;
+                config.validate()?;
+                Ok(Box::new(DonchianBreakoutStrategy::new(config)))
+            }
+            "macd" => {
+                let mut config: MacdConfig = serde_json::from_value(config)
+                    .map_err(|e| StrategyError::InvalidConfig(e.to_string()))?;
+                config.symbols = symbols;
+                config.validate()?;
+                Ok(Box::new(MacdStrategy::new(config)))
+            }
+            "breakout" => {
+                let mut config: BreakoutConfig = serde_json::from_value(config)
+                    .map_err(|e| StrategyError::InvalidConfig(e.to_string()))?;
+                config.symbols = symbols;
+                config.validate()?;
+                Ok(Box::new(BreakoutStrategy::new(config)))
+            }
+            "dca" => {
+                let mut config: DcaConfig = serde_json::from_value(config)
+                    .map_err(|e| StrategyError::InvalidConfig(e.to_string()))?;
+                config.symbols = symbols;
+                config.validate()?;
+                Ok(Box::new(DcaStrategy::new(config)))
+            }
+            "rebalance" => {
+                let mut config: RebalanceConfig = serde_json::from_value(config)
+                    .map_err(|e| StrategyError::InvalidConfig(e.to_string()))?;
+                config.symbols = symbols;
+                config.validate()?;
+                Ok(Box::new(RebalanceStrategy::new(config)))
+            }
+            "rotation" => {
+                let mut config: RotationConfig = serde_json::from_value(config)
+                    .map_err(|e| StrategyError::InvalidConfig(e.to_string()))?;
+                config.symbols = symbols;
+                config.validate()?;
+                Ok(Box::new(RotationStrategy::new(config)))
+            }
+            "ensemble" => {
+                let mut config: EnsembleConfig = serde_json::from_value(config)
+                    .map_err(|e| StrategyError::InvalidConfig(e.to_string()))?;
+                config.symbols = symbols;
+                config.validate()?;
+                let members = config
+                    .members
+                    .iter()
+                    .map(|member| {
+                        self.create_with_overrides(
+                            &member.strategy,
+                            member.config.clone(),
+                            config.symbols.clone(),
+                        )
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Box::new(EnsembleStrategy::new(config, members)))
+            }
+            "multi" => {
+                let mut config: MultiStrategyConfig = serde_json::from_value(config)
+                    .map_err(|e| StrategyError::InvalidConfig(e.to_string()))?;
+                config.symbols = symbols;
+                config.validate()?;
+                let members = config
+                    .members
+                    .iter()
+                    .map(|member| {
+                        let symbols = if member.symbols.is_empty() {
+                            config.symbols.clone()
+                        } else {
+                            member.symbols.clone()
+                        };
+                        self.create_with_overrides(&member.strategy, member.config.clone(), symbols)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Box::new(MultiStrategy::new(config, members)))
+            }
+            "rules" => {
+                let mut config: RulesConfig = serde_json::from_value(config)
+                    .map_err(|e| StrategyError::InvalidConfig(e.to_string()))?;
+                config.symbols = symbols;
+                config.validate()?;
+                Ok(Box::new(RulesStrategy::new(config)))
+            }
             _ => Err(StrategyError::NotFound(name.to_string())),
         }
     }
@@ -136,11 +382,73 @@ impl StrategyRegistry {
         name: &str,
         symbols: Vec<String>,
     ) -> Result<Box<dyn Strategy>, StrategyError> {
+        if name.starts_with(SCRIPT_PREFIX) {
+            return self.create(name, serde_json::Value::Null, symbols);
+        }
         let info = self
             .get(name)
             .ok_or_else(|| StrategyError::NotFound(name.to_string()))?;
         self.create(name, info.default_config.clone(), symbols)
     }
+
+    /// Default configuration of a strategy with the top-level keys of
+    /// `overrides` replaced, e.g. settings loaded from a file.
+    ///
+    /// Fails on keys the strategy's configuration does not have, so a
+    /// misspelt setting is not silently ignored.
+    pub fn resolve_config(
+        &self,
+        name: &str,
+        overrides: serde_json::Value,
+    ) -> Result<serde_json::Value, StrategyError> {
+        if name.starts_with(SCRIPT_PREFIX) {
+            return Ok(overrides);
+        }
+        let info = self
+            .get(name)
+            .ok_or_else(|| StrategyError::NotFound(name.to_string()))?;
+        let mut config = info.default_config.clone();
+        match (config.as_object_mut(), overrides) {
+            (_, serde_json::Value::Null) => {}
+            (Some(config), serde_json::Value::Object(overrides)) => {
+                if let Some(key) = overrides.keys().find(|key| !config.contains_key(*key)) {
+                    let mut known: Vec<&String> = config.keys().collect();
+                    known.sort();
+                    return Err(StrategyError::InvalidConfig(format!(
+                        "Unknown {} setting {}; expected one of {}",
+                        name,
+                        key,
+                        known
+                            .iter()
+                            .map(|k| k.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    )));
+                }
+                config.extend(overrides);
+            }
+            (None, overrides) => return Ok(overrides),
+            (_, overrides) => {
+                return Err(StrategyError::InvalidConfig(format!(
+                    "{} settings must be a table, got {}",
+                    name, overrides
+                )))
+            }
+        }
+        Ok(config)
+    }
+
+    /// Create a strategy from its default configuration with the top-level
+    /// keys of `overrides` replaced; see [`resolve_config`](Self::resolve_config).
+    pub fn create_with_overrides(
+        &self,
+        name: &str,
+        overrides: serde_json::Value,
+        symbols: Vec<String>,
+    ) -> Result<Box<dyn Strategy>, StrategyError> {
+        let config = self.resolve_config(name, overrides)?;
+        self.create(name, config, symbols)
+    }
 }
 
 impl Default for StrategyRegistry {
@@ -158,7 +466,7 @@ mod tests {
         let registry = StrategyRegistry::new();
         let strategies = registry.list();
 
-        assert_eq!(strategies.len(), 4);
+        assert_eq!(strategies.len(), 13);
     }
 
     #[test]
@@ -197,6 +505,92 @@ mod tests {
         assert!(strategy.is_ok());
     }
 
+    #[test]
+    fn test_create_with_overrides() {
+        let registry = StrategyRegistry::new();
+
+        // Only the entry rule is given; the exit rule keeps its default
+        let overrides = serde_json::json!({"entry": "close crosses_above ema(20)"});
+        let strategy = registry
+            .create_with_overrides("rules", overrides, vec!["AAPL".to_string()])
+            .unwrap();
+        assert_eq!(strategy.warmup_period(), 201);
+
+        let bad = serde_json::json!({"entry": "close >"});
+        assert!(registry
+            .create_with_overrides("rules", bad, vec!["AAPL".to_string()])
+            .is_err());
+        assert!(registry
+            .create_with_overrides("rules", serde_json::json!([1]), vec![])
+            .is_err());
+
+        // Misspelt settings are reported rather than ignored
+        let typo = serde_json::json!({"fast_perod": 5});
+        let err = registry
+            .resolve_config("ma_crossover", typo)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("fast_perod"), "{}", err);
+    }
+
+    #[test]
+    fn test_create_ensemble() {
+        let registry = StrategyRegistry::new();
+
+        // Member settings override the member's defaults
+        let config = serde_json::json!({
+            "symbols": [],
+            "members": [
+                {"strategy": "ma_crossover", "config": {"fast_period": 5, "slow_period": 10}},
+                {"strategy": "rsi", "weight": 2.0}
+            ],
+            "voting": "weighted",
+            "threshold": 0.5,
+            "allow_short": false
+        });
+        let strategy = registry
+            .create("ensemble", config.clone(), vec!["AAPL".to_string()])
+            .unwrap();
+        assert_eq!(strategy.name(), "Ensemble");
+        assert_eq!(strategy.symbols(), &["AAPL".to_string()]);
+
+        let mut bad = config;
+        bad["members"][0]["config"]["slow_period"] = 2.into();
+        assert!(registry
+            .create("ensemble", bad, vec!["AAPL".to_string()])
+            .is_err());
+    }
+
+    #[test]
+    fn test_create_script() {
+        let registry = StrategyRegistry::new();
+        let name = concat!(
+            "script:",
+            env!("CARGO_MANIFEST_DIR"),
+            "/../../examples/sma_cross.rhai"
+        );
+        assert!(registry.exists(name));
+
+        let strategy = registry
+            .create_default(name, vec!["AAPL".to_string()])
+            .unwrap();
+        assert_eq!(strategy.name(), "sma_cross");
+        assert_eq!(strategy.warmup_period(), 31);
+
+        let strategy = registry
+            .create(
+                name,
+                serde_json::json!({"slow": 50}),
+                vec!["AAPL".to_string()],
+            )
+            .unwrap();
+        assert_eq!(strategy.warmup_period(), 51);
+
+        assert!(registry
+            .create_default("script:missing.rhai", vec!["AAPL".to_string()])
+            .is_err());
+    }
+
     #[test]
     fn test_create_unknown_strategy() {
         let registry = StrategyRegistry::new();
@@ -204,4 +598,18 @@ mod tests {
         let result = registry.create_default("unknown", vec!["AAPL".to_string()]);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_every_strategy_declares_metadata_schema() {
+        let registry = StrategyRegistry::new();
+
+        for name in registry.names() {
+            let info = registry.get(name).unwrap();
+            let strategy = registry
+                .create_default(name, vec!["AAPL".to_string()])
+                .unwrap();
+            let schema = strategy.metadata_schema().expect(name);
+            assert_eq!(schema, info.metadata_schema, "{}", name);
+        }
+    }
 }
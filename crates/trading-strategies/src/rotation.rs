@@ -0,0 +1,405 @@
+//! Momentum Rotation Strategy.
+//!
+//! Cross-sectional momentum: once per interval (monthly by default) ranks
+//! a basket of symbols by their return over the last `lookback` bars and
+//! holds the `top_k` strongest, equally weighted. Symbols that drop out of
+//! the top are sold and the proceeds buy the new entrants.
+//!
+//! Ranking needs every symbol at the same bar, so the strategy works from
+//! the engine's market snapshots. Like the rebalancing strategy it manages
+//! `capital` of the account and tracks its holdings from its fills.
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use trading_core::{
+    error::StrategyError,
+    traits::{Strategy, StrategyAction, StrategyConfig, StrategyOrders, StrategyState},
+    types::{
+        BarSeries, MarketSnapshot, MetadataSchema, Order, OrderRequest, Side, Signal, Timeframe,
+    },
+};
+
+/// Configuration for the rotation strategy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RotationConfig {
+    /// Basket of symbols to rank
+    pub symbols: Vec<String>,
+    /// Bars of trailing return used for ranking
+    pub lookback: usize,
+    /// Number of top-ranked symbols held
+    pub top_k: usize,
+    /// How often to rotate; rotations happen on the first bar of each period
+    pub interval: Timeframe,
+    /// Portfolio value the strategy manages, in account currency
+    pub capital: f64,
+    /// Only hold symbols whose trailing return is positive
+    pub require_positive: bool,
+    /// Trade fractional shares instead of whole shares
+    pub fractional: bool,
+}
+
+impl Default for RotationConfig {
+    fn default() -> Self {
+        Self {
+            symbols: vec![],
+            lookback: 126,
+            top_k: 3,
+            interval: Timeframe::Monthly,
+            capital: 100_000.0,
+            require_positive: false,
+            fractional: false,
+        }
+    }
+}
+
+impl StrategyConfig for RotationConfig {
+    fn validate(&self) -> Result<(), StrategyError> {
+        if self.lookback == 0 {
+            return Err(StrategyError::InvalidConfig(
+                "Lookback must be greater than 0".into(),
+            ));
+        }
+        if self.top_k == 0 {
+            return Err(StrategyError::InvalidConfig(
+                "Top K must be greater than 0".into(),
+            ));
+        }
+        if !(self.capital > 0.0 && self.capital.is_finite()) {
+            return Err(StrategyError::InvalidConfig(
+                "Capital must be positive".into(),
+            ));
+        }
+        if self.symbols.is_empty() {
+            return Err(StrategyError::InvalidConfig(
+                "At least one symbol required".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Cross-sectional momentum rotation.
+pub struct RotationStrategy {
+    config: RotationConfig,
+    /// Shares held per symbol, from fills
+    holdings: HashMap<String, Decimal>,
+    /// Uninvested part of `capital`, from fills
+    cash: f64,
+    /// Start of the interval of the last rotation
+    last_period: Option<i64>,
+    /// Trailing returns of the last ranking, strongest first
+    ranking: Vec<(String, f64)>,
+    bars_processed: usize,
+    rotations: usize,
+}
+
+impl RotationStrategy {
+    /// Indicator keys of the signals this strategy emits: none, as it only
+    /// places orders.
+    pub fn signal_schema() -> MetadataSchema {
+        MetadataSchema::default()
+    }
+
+    /// Create a new rotation strategy.
+    pub fn new(config: RotationConfig) -> Self {
+        Self {
+            cash: config.capital,
+            config,
+            holdings: HashMap::new(),
+            last_period: None,
+            ranking: Vec::new(),
+            bars_processed: 0,
+            rotations: 0,
+        }
+    }
+
+    /// Trailing return and close of every basket symbol with a bar at the
+    /// snapshot and enough history, strongest first.
+    fn rank(&self, snapshot: &MarketSnapshot) -> Vec<(String, f64, f64)> {
+        let mut ranked: Vec<(String, f64, f64)> = snapshot
+            .current()
+            .filter(|s| self.config.symbols.iter().any(|sym| sym == s.symbol()))
+            .filter_map(|s| {
+                let bars = s.primary().bars();
+                let close = bars.back()?.close;
+                let past = bars
+                    .get(bars.len().checked_sub(self.config.lookback + 1)?)?
+                    .close;
+                (past > 0.0).then(|| (s.symbol().to_string(), close / past - 1.0, close))
+            })
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        ranked
+    }
+
+    fn quantity(&self, notional: f64, price: f64) -> Decimal {
+        let quantity = Decimal::try_from(notional / price).unwrap_or_default();
+        if self.config.fractional {
+            quantity.round_dp(6)
+        } else {
+            quantity.floor()
+        }
+    }
+}
+
+impl Strategy for RotationStrategy {
+    fn name(&self) -> &str {
+        "Rotation"
+    }
+
+    fn description(&self) -> &str {
+        "Holds the top-K symbols of a basket by trailing momentum, rotating each period"
+    }
+
+    fn metadata_schema(&self) -> Option<MetadataSchema> {
+        Some(Self::signal_schema())
+    }
+
+    fn on_bar(&mut self, _series: &BarSeries) -> Option<Signal> {
+        self.bars_processed += 1;
+        None
+    }
+
+    fn on_snapshot(&mut self, snapshot: &MarketSnapshot) -> Option<StrategyAction> {
+        let period = self.config.interval.bucket_start(snapshot.timestamp());
+        if self.last_period == Some(period) {
+            return None;
+        }
+        // Wait until the whole basket can be ranked
+        let ranked = self.rank(snapshot);
+        if ranked.len() < self.config.symbols.len() {
+            return None;
+        }
+        self.last_period = Some(period);
+        self.ranking = ranked.iter().map(|(s, r, _)| (s.clone(), *r)).collect();
+
+        let closes: HashMap<&str, f64> = ranked.iter().map(|(s, _, c)| (s.as_str(), *c)).collect();
+        let top: Vec<&str> = ranked
+            .iter()
+            .filter(|(_, ret, _)| !self.config.require_positive || *ret > 0.0)
+            .take(self.config.top_k)
+            .map(|(s, _, _)| s.as_str())
+            .collect();
+        let held = |symbol: &str| {
+            self.holdings
+                .get(symbol)
+                .copied()
+                .filter(|q| *q > Decimal::ZERO)
+        };
+
+        // Sell what dropped out of the top
+        let mut cash = self.cash;
+        let mut legs = Vec::new();
+        for (symbol, _, close) in &ranked {
+            let Some(quantity) = held(symbol) else {
+                continue;
+            };
+            if !top.contains(&symbol.as_str()) {
+                cash += f64::try_from(quantity).unwrap_or(0.0) * close;
+                legs.push(OrderRequest::market(symbol, Side::Sell, quantity));
+            }
+        }
+
+        // Split the cash among the entrants, at most an equal share each
+        let value = self.cash
+            + ranked
+                .iter()
+                .filter_map(|(s, _, c)| Some(f64::try_from(held(s)?).ok()? * c))
+                .sum::<f64>();
+        let entrants: Vec<&str> = top.iter().copied().filter(|s| held(s).is_none()).collect();
+        if !entrants.is_empty() {
+            let budget = (cash / entrants.len() as f64).min(value / self.config.top_k as f64);
+            for symbol in &entrants {
+                let quantity = self.quantity(budget, closes[symbol]);
+                if quantity > Decimal::ZERO {
+                    legs.push(OrderRequest::market(*symbol, Side::Buy, quantity));
+                }
+            }
+        }
+
+        if legs.is_empty() {
+            return None;
+        }
+        self.rotations += 1;
+        let reason = format!(
+            "Rotating into the top {} by {}-bar return: {}",
+            self.config.top_k,
+            self.config.lookback,
+            top.join(", ")
+        );
+        Some(StrategyAction::Orders(
+            StrategyOrders::new(legs).with_reason(reason),
+        ))
+    }
+
+    fn on_fill(&mut self, order: &Order) {
+        if !self.config.symbols.contains(&order.symbol) {
+            return;
+        }
+        let price = order.filled_avg_price.unwrap_or_default();
+        let notional: f64 = (price * order.filled_quantity).try_into().unwrap_or(0.0);
+        let commission: f64 = order
+            .fills
+            .iter()
+            .map(|f| f.commission)
+            .sum::<Decimal>()
+            .try_into()
+            .unwrap_or(0.0);
+
+        let held = self.holdings.entry(order.symbol.clone()).or_default();
+        match order.side {
+            Side::Buy => {
+                *held += order.filled_quantity;
+                self.cash -= notional;
+            }
+            Side::Sell => {
+                *held -= order.filled_quantity;
+                self.cash += notional;
+            }
+        }
+        self.cash -= commission;
+    }
+
+    fn reset(&mut self) {
+        self.holdings.clear();
+        self.cash = self.config.capital;
+        self.last_period = None;
+        self.ranking.clear();
+        self.bars_processed = 0;
+        self.rotations = 0;
+    }
+
+    fn state(&self) -> StrategyState {
+        StrategyState {
+            name: self.name().to_string(),
+            is_warmed_up: !self.ranking.is_empty(),
+            bars_processed: self.bars_processed,
+            signals_generated: self.rotations,
+            indicators: self
+                .ranking
+                .iter()
+                .map(|(s, r)| (format!("return_{}", s), *r))
+                .collect(),
+            custom: serde_json::json!({
+                "holdings": self
+                    .holdings
+                    .iter()
+                    .filter(|(_, q)| **q > Decimal::ZERO)
+                    .map(|(s, q)| (s.clone(), q.to_string()))
+                    .collect::<HashMap<_, _>>(),
+                "cash": self.cash,
+                "top_k": self.config.top_k,
+            }),
+        }
+    }
+
+    fn warmup_period(&self) -> usize {
+        self.config.lookback + 1
+    }
+
+    fn symbols(&self) -> &[String] {
+        &self.config.symbols
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use trading_core::types::{Bar, MultiTimeframeSeries, OrderStatus};
+
+    const DAY_MS: i64 = 86_400_000;
+
+    /// Daily growth of each symbol before and after day 45.
+    const RATES: [(&str, f64, f64); 3] = [
+        ("AAA", 0.01, -0.01),
+        ("BBB", 0.005, 0.0),
+        ("CCC", -0.01, 0.01),
+    ];
+
+    fn fill(
+        strategy: &mut RotationStrategy,
+        legs: &[OrderRequest],
+        basket: &[MultiTimeframeSeries],
+    ) {
+        for leg in legs {
+            let series = basket.iter().find(|s| s.symbol() == leg.symbol).unwrap();
+            let mut order = Order::from_request(leg);
+            order.status = OrderStatus::Filled;
+            order.filled_quantity = leg.quantity;
+            order.filled_avg_price = Decimal::try_from(series.primary().last().unwrap().close).ok();
+            strategy.on_fill(&order);
+        }
+    }
+
+    #[test]
+    fn test_rotates_into_strongest() {
+        let config = RotationConfig {
+            symbols: RATES.iter().map(|(s, _, _)| s.to_string()).collect(),
+            lookback: 10,
+            top_k: 2,
+            capital: 10_000.0,
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+        let mut strategy = RotationStrategy::new(config);
+        let mut basket: Vec<_> = RATES
+            .iter()
+            .map(|(s, _, _)| MultiTimeframeSeries::new(s.to_string(), Timeframe::Daily, 0, &[]))
+            .collect();
+        let mut closes = [100.0; 3];
+        let mut rotations = Vec::new();
+
+        // Daily bars from 1970-01-01 through 1970-03-20
+        for day in 0..79 {
+            for (i, (_, early, late)) in RATES.iter().enumerate() {
+                closes[i] *= 1.0 + if day < 45 { early } else { late };
+                let c = closes[i];
+                basket[i].push(Bar::new(day * DAY_MS, c, c, c, c, 1_000.0));
+            }
+            let snapshot = MarketSnapshot::new(day * DAY_MS, basket.iter());
+            if let Some(StrategyAction::Orders(orders)) = strategy.on_snapshot(&snapshot) {
+                fill(&mut strategy, &orders.legs, &basket);
+                let legs: Vec<_> = orders
+                    .legs
+                    .iter()
+                    .map(|l| (l.symbol.clone(), l.side))
+                    .collect();
+                rotations.push((day, legs));
+            }
+        }
+
+        // Buys the leaders once ranked, holds through February as they
+        // stay on top, and in March swaps the fading leader for the new one
+        let leg = |s: &str, side| (s.to_string(), side);
+        assert_eq!(
+            rotations,
+            vec![
+                (10, vec![leg("AAA", Side::Buy), leg("BBB", Side::Buy)]),
+                (59, vec![leg("AAA", Side::Sell), leg("CCC", Side::Buy)]),
+            ]
+        );
+        assert!(strategy.state().custom["holdings"]["AAA"].is_null());
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let config = RotationConfig {
+            symbols: vec!["AAA".to_string()],
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+        assert!(RotationConfig {
+            top_k: 0,
+            ..config.clone()
+        }
+        .validate()
+        .is_err());
+        assert!(RotationConfig {
+            lookback: 0,
+            ..config
+        }
+        .validate()
+        .is_err());
+    }
+}
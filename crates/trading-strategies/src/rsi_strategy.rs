@@ -7,10 +7,10 @@
 use serde::{Deserialize, Serialize};
 use trading_core::{
     error::StrategyError,
-    traits::{Indicator, Strategy, StrategyConfig, StrategyState},
-    types::{BarSeries, Signal, SignalMetadata, SignalStrength, SignalType},
+    traits::{Strategy, StrategyConfig, StrategyState, StreamingIndicator, StreamingState},
+    types::{Bar, BarSeries, MetadataSchema, Signal, SignalMetadata, SignalStrength, SignalType},
 };
-use trading_indicators::Rsi;
+use trading_indicators::StreamingRsi;
 
 /// Configuration for the RSI strategy.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -79,23 +79,50 @@ enum PositionState {
     Short,
 }
 
+/// RSI of one symbol.
+struct SymbolRsi {
+    rsi: StreamingRsi,
+    current: Option<f64>,
+}
+
+impl SymbolRsi {
+    fn new(period: usize) -> Self {
+        Self {
+            rsi: StreamingRsi::new(period),
+            current: None,
+        }
+    }
+
+    /// Add a bar; returns the RSI before and after it.
+    fn update(&mut self, bar: &Bar) -> (Option<f64>, Option<f64>) {
+        let prev = self.current;
+        self.current = self.rsi.update(bar.close);
+        (prev, self.current)
+    }
+}
+
 /// RSI-based Trading Strategy.
 pub struct RsiStrategy {
     config: RsiConfig,
-    rsi: Rsi,
+    rsi: StreamingState<SymbolRsi>,
     position: PositionState,
+    /// Latest RSI of any symbol, for monitoring
     prev_rsi: Option<f64>,
     bars_processed: usize,
     signals_generated: usize,
 }
 
 impl RsiStrategy {
+    /// Indicator keys of the signals this strategy emits.
+    pub fn signal_schema() -> MetadataSchema {
+        MetadataSchema::new(&["rsi"], &[])
+    }
+
     /// Create a new RSI strategy.
     pub fn new(config: RsiConfig) -> Self {
-        let rsi = Rsi::new(config.period);
         Self {
             config,
-            rsi,
+            rsi: StreamingState::new(),
             position: PositionState::Flat,
             prev_rsi: None,
             bars_processed: 0,
@@ -140,6 +167,7 @@ impl RsiStrategy {
             price,
             timestamp,
             confidence: self.calculate_confidence(rsi),
+            fraction: None,
             metadata: SignalMetadata {
                 strategy_name: self.name().to_string(),
                 indicators: [("rsi".to_string(), rsi)].into_iter().collect(),
@@ -159,6 +187,10 @@ impl Strategy for RsiStrategy {
         "Trades RSI overbought/oversold reversals"
     }
 
+    fn metadata_schema(&self) -> Option<MetadataSchema> {
+        Some(Self::signal_schema())
+    }
+
     fn on_bar(&mut self, series: &BarSeries) -> Option<Signal> {
         self.bars_processed += 1;
 
@@ -166,17 +198,14 @@ impl Strategy for RsiStrategy {
             return None;
         }
 
-        let closes = series.closes();
-        let rsi_values = self.rsi.calculate(&closes);
-
-        if rsi_values.is_empty() {
-            return None;
-        }
-
-        let current_rsi = *rsi_values.last()?;
+        let period = self.config.period;
+        let (prev_rsi, current_rsi) =
+            self.rsi
+                .update(series, || SymbolRsi::new(period), SymbolRsi::update)?;
+        let current_rsi = current_rsi?;
         let bar = series.last()?;
 
-        let signal = match (self.prev_rsi, self.position) {
+        let signal = match (prev_rsi, self.position) {
             // Entry signals when flat
             (Some(prev), PositionState::Flat) => {
                 // Oversold -> potential long entry (RSI crosses above oversold)
@@ -265,6 +294,7 @@ impl Strategy for RsiStrategy {
     }
 
     fn reset(&mut self) {
+        self.rsi.clear();
         self.position = PositionState::Flat;
         self.prev_rsi = None;
         self.bars_processed = 0;
@@ -0,0 +1,959 @@
+//! Rules Strategy.
+//!
+//! Trades on conditions written as expressions, so simple strategies can be
+//! set up from a config file without writing Rust:
+//!
+//! ```toml
+//! entry = "rsi(14) < 30 and close > sma(200)"
+//! exit = "rsi(14) > 70 or close < sma(200)"
+//! ```
+//!
+//! Expressions combine bar fields (`open`, `high`, `low`, `close`,
+//! `volume`), indicators (`sma(n)`, `ema(n)`, `rsi(n)`, `stddev(n)` of the
+//! close, `atr(n)`, and `highest(n)` / `lowest(n)` of the highs and lows of
+//! the `n` bars before the current one), numbers and `+ - * /` with the
+//! comparisons `< <= > >= == !=`, `crosses_above` and `crosses_below`, joined
+//! by `and`, `or`, `not` and parentheses.
+//!
+//! Each expression is parsed once into a tree over streaming indicators
+//! shared between the rules. A comparison involving an indicator that has
+//! no value yet is unknown, and unknown conditions never trigger.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use trading_core::{
+    error::StrategyError,
+    traits::{Strategy, StrategyConfig, StrategyState, StreamingIndicator, StreamingState},
+    types::{Bar, BarSeries, MetadataSchema, Signal, SignalMetadata, SignalStrength, SignalType},
+};
+use trading_indicators::{
+    RollingMax, RollingMin, StreamingAtr, StreamingEma, StreamingRsi, StreamingSma, StreamingStdDev,
+};
+
+/// Configuration for the rules strategy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RulesConfig {
+    /// Symbols to trade
+    pub symbols: Vec<String>,
+    /// Condition to open a long position
+    pub entry: String,
+    /// Condition to close a long position
+    pub exit: String,
+    /// Condition to open a short position, if shorting
+    #[serde(default)]
+    pub short_entry: Option<String>,
+    /// Condition to close a short position; required with `short_entry`
+    #[serde(default)]
+    pub short_exit: Option<String>,
+}
+
+impl Default for RulesConfig {
+    fn default() -> Self {
+        Self {
+            symbols: vec![],
+            entry: "rsi(14) < 30 and close > sma(200)".to_string(),
+            exit: "rsi(14) > 70 or close < sma(200)".to_string(),
+            short_entry: None,
+            short_exit: None,
+        }
+    }
+}
+
+impl StrategyConfig for RulesConfig {
+    fn validate(&self) -> Result<(), StrategyError> {
+        Rules::compile(self)?;
+        if self.short_entry.is_some() != self.short_exit.is_some() {
+            return Err(StrategyError::InvalidConfig(
+                "short_entry and short_exit must be set together".into(),
+            ));
+        }
+        if self.symbols.is_empty() {
+            return Err(StrategyError::InvalidConfig(
+                "At least one symbol required".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Indicator functions available in rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Function {
+    Sma,
+    Ema,
+    Rsi,
+    StdDev,
+    Atr,
+    Highest,
+    Lowest,
+}
+
+impl Function {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "sma" => Some(Function::Sma),
+            "ema" => Some(Function::Ema),
+            "rsi" => Some(Function::Rsi),
+            "stddev" => Some(Function::StdDev),
+            "atr" => Some(Function::Atr),
+            "highest" => Some(Function::Highest),
+            "lowest" => Some(Function::Lowest),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Function::Sma => "sma",
+            Function::Ema => "ema",
+            Function::Rsi => "rsi",
+            Function::StdDev => "stddev",
+            Function::Atr => "atr",
+            Function::Highest => "highest",
+            Function::Lowest => "lowest",
+        }
+    }
+
+    /// Longest period accepted, so a typo can't size an indicator's
+    /// window beyond memory.
+    const MAX_PERIOD: usize = 10_000;
+
+    fn min_period(self) -> usize {
+        match self {
+            Function::StdDev => 2,
+            _ => 1,
+        }
+    }
+
+    /// Bars before the indicator has a value.
+    fn warmup(self, period: usize) -> usize {
+        match self {
+            Function::Sma | Function::Ema | Function::StdDev => period,
+            Function::Rsi | Function::Atr | Function::Highest | Function::Lowest => period + 1,
+        }
+    }
+}
+
+/// Bar fields available in rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Open,
+    High,
+    Low,
+    Close,
+    Volume,
+}
+
+impl Field {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "open" => Some(Field::Open),
+            "high" => Some(Field::High),
+            "low" => Some(Field::Low),
+            "close" => Some(Field::Close),
+            "volume" => Some(Field::Volume),
+            _ => None,
+        }
+    }
+
+    fn get(self, bar: &Bar) -> f64 {
+        match self {
+            Field::Open => bar.open,
+            Field::High => bar.high,
+            Field::Low => bar.low,
+            Field::Close => bar.close,
+            Field::Volume => bar.volume,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+    CrossesAbove,
+    CrossesBelow,
+    And,
+    Or,
+}
+
+impl Op {
+    /// Binding strength; higher binds tighter.
+    fn precedence(self) -> u8 {
+        match self {
+            Op::Or => 1,
+            Op::And => 2,
+            Op::Lt
+            | Op::Le
+            | Op::Gt
+            | Op::Ge
+            | Op::Eq
+            | Op::Ne
+            | Op::CrossesAbove
+            | Op::CrossesBelow => 4,
+            Op::Add | Op::Sub => 5,
+            Op::Mul | Op::Div => 6,
+        }
+    }
+}
+
+/// Parsed expression. Indicators refer to slots of [`Rules::slots`].
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Number(f64),
+    Field(Field),
+    Slot(usize),
+    Neg(Box<Node>),
+    Not(Box<Node>),
+    Binary(Box<Node>, Op, Box<Node>),
+}
+
+impl Node {
+    fn has_crossing(&self) -> bool {
+        match self {
+            Node::Binary(_, Op::CrossesAbove | Op::CrossesBelow, _) => true,
+            Node::Binary(left, _, right) => left.has_crossing() || right.has_crossing(),
+            Node::Neg(inner) | Node::Not(inner) => inner.has_crossing(),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Type {
+    Number,
+    Bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Op(Op),
+    LParen,
+    RParen,
+    Comma,
+}
+
+/// Split an expression into tokens, with their byte offsets.
+fn tokenize(source: &str) -> Result<Vec<(usize, Token)>, String> {
+    let mut tokens = Vec::new();
+    let chars: Vec<(usize, char)> = source.char_indices().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let (pos, c) = chars[i];
+        let next = chars.get(i + 1).map(|(_, c)| *c);
+        let token = match c {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            ',' => Token::Comma,
+            '+' => Token::Op(Op::Add),
+            '-' => Token::Op(Op::Sub),
+            '*' => Token::Op(Op::Mul),
+            '/' => Token::Op(Op::Div),
+            '<' | '>' | '=' | '!' => {
+                let op = match (c, next) {
+                    ('<', Some('=')) => Op::Le,
+                    ('>', Some('=')) => Op::Ge,
+                    ('=', Some('=')) => Op::Eq,
+                    ('!', Some('=')) => Op::Ne,
+                    ('<', _) => Op::Lt,
+                    ('>', _) => Op::Gt,
+                    _ => return Err(format!("unexpected '{}' at {}", c, pos)),
+                };
+                if matches!(op, Op::Le | Op::Ge | Op::Eq | Op::Ne) {
+                    i += 1;
+                }
+                Token::Op(op)
+            }
+            c if c.is_ascii_digit() || c == '.' => {
+                let end = chars[i..]
+                    .iter()
+                    .position(|(_, c)| !(c.is_ascii_digit() || *c == '.'))
+                    .map_or(chars.len(), |n| i + n);
+                let text: String = chars[i..end].iter().map(|(_, c)| c).collect();
+                let value = text
+                    .parse()
+                    .map_err(|_| format!("invalid number '{}' at {}", text, pos))?;
+                tokens.push((pos, Token::Number(value)));
+                i = end;
+                continue;
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let end = chars[i..]
+                    .iter()
+                    .position(|(_, c)| !(c.is_ascii_alphanumeric() || *c == '_'))
+                    .map_or(chars.len(), |n| i + n);
+                let word: String = chars[i..end].iter().map(|(_, c)| c).collect();
+                let token = match word.to_lowercase().as_str() {
+                    "and" => Token::Op(Op::And),
+                    "or" => Token::Op(Op::Or),
+                    "crosses_above" => Token::Op(Op::CrossesAbove),
+                    "crosses_below" => Token::Op(Op::CrossesBelow),
+                    _ => Token::Ident(word.to_lowercase()),
+                };
+                tokens.push((pos, token));
+                i = end;
+                continue;
+            }
+            _ => return Err(format!("unexpected '{}' at {}", c, pos)),
+        };
+        tokens.push((pos, token));
+        i += 1;
+    }
+    Ok(tokens)
+}
+
+/// Compiled rules, with the indicators they use.
+#[derive(Debug, Clone)]
+struct Rules {
+    /// Indicator function and period of each slot
+    slots: Vec<(Function, usize)>,
+    entry: Node,
+    exit: Node,
+    short_entry: Option<Node>,
+    short_exit: Option<Node>,
+}
+
+/// Recursive-descent parser over the tokens of one expression.
+struct Parser<'a> {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    source: &'a str,
+    slots: &'a mut Vec<(Function, usize)>,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, t)| t)
+    }
+
+    fn error(&self, message: &str) -> String {
+        match self.tokens.get(self.pos) {
+            Some((offset, _)) => format!("{} at {} in '{}'", message, offset, self.source),
+            None => format!("{} at end of '{}'", message, self.source),
+        }
+    }
+
+    fn expect(&mut self, token: Token) -> Result<(), String> {
+        if self.peek() == Some(&token) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected {:?}", token)))
+        }
+    }
+
+    /// Precedence climbing over binary operators.
+    fn expression(&mut self, min_precedence: u8) -> Result<Node, String> {
+        let mut left = self.unary()?;
+        while let Some(Token::Op(op)) = self.peek() {
+            let op = *op;
+            if op.precedence() < min_precedence {
+                break;
+            }
+            self.pos += 1;
+            let right = self.expression(op.precedence() + 1)?;
+            left = Node::Binary(Box::new(left), op, Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Node, String> {
+        match self.peek() {
+            Some(Token::Op(Op::Sub)) => {
+                self.pos += 1;
+                Ok(Node::Neg(Box::new(self.unary()?)))
+            }
+            // `not` binds looser than comparisons: `not close > open`
+            Some(Token::Ident(word)) if word == "not" => {
+                self.pos += 1;
+                Ok(Node::Not(Box::new(
+                    self.expression(Op::And.precedence() + 1)?,
+                )))
+            }
+            _ => self.primary(),
+        }
+    }
+
+    fn primary(&mut self) -> Result<Node, String> {
+        let Some((_, token)) = self.tokens.get(self.pos).cloned() else {
+            return Err(self.error("expected a value"));
+        };
+        self.pos += 1;
+        match token {
+            Token::Number(value) => Ok(Node::Number(value)),
+            Token::LParen => {
+                let node = self.expression(0)?;
+                self.expect(Token::RParen)?;
+                Ok(node)
+            }
+            Token::Ident(name) => {
+                if let Some(field) = Field::parse(&name) {
+                    return Ok(Node::Field(field));
+                }
+                let Some(function) = Function::parse(&name) else {
+                    self.pos -= 1;
+                    return Err(self.error(&format!("unknown name '{}'", name)));
+                };
+                self.expect(Token::LParen)?;
+                let period = match self.tokens.get(self.pos) {
+                    Some((_, Token::Number(n)))
+                        if n.fract() == 0.0
+                            && *n >= function.min_period() as f64
+                            && *n <= Function::MAX_PERIOD as f64 =>
+                    {
+                        *n as usize
+                    }
+                    _ => {
+                        return Err(self.error(&format!(
+                            "{} needs a whole period from {} to {}",
+                            name,
+                            function.min_period(),
+                            Function::MAX_PERIOD
+                        )))
+                    }
+                };
+                self.pos += 1;
+                self.expect(Token::RParen)?;
+
+                let slot = match self.slots.iter().position(|s| *s == (function, period)) {
+                    Some(slot) => slot,
+                    None => {
+                        self.slots.push((function, period));
+                        self.slots.len() - 1
+                    }
+                };
+                Ok(Node::Slot(slot))
+            }
+            _ => {
+                self.pos -= 1;
+                Err(self.error("expected a value"))
+            }
+        }
+    }
+}
+
+/// Type of a node, checking its operands.
+fn check(node: &Node) -> Result<Type, String> {
+    let expect = |node: &Node, wanted: Type, what: &str| match check(node)? {
+        t if t == wanted => Ok(()),
+        _ => Err(format!(
+            "{} needs {} operands",
+            what,
+            if wanted == Type::Number {
+                "numeric"
+            } else {
+                "true/false"
+            }
+        )),
+    };
+    match node {
+        Node::Number(_) | Node::Field(_) | Node::Slot(_) => Ok(Type::Number),
+        Node::Neg(inner) => expect(inner, Type::Number, "'-'").map(|_| Type::Number),
+        Node::Not(inner) => expect(inner, Type::Bool, "'not'").map(|_| Type::Bool),
+        Node::Binary(left, op, right) => {
+            let (operands, result, what) = match op {
+                Op::Add | Op::Sub | Op::Mul | Op::Div => (Type::Number, Type::Number, "arithmetic"),
+                Op::And | Op::Or => (Type::Bool, Type::Bool, "'and'/'or'"),
+                _ => (Type::Number, Type::Bool, "comparison"),
+            };
+            expect(left, operands, what)?;
+            expect(right, operands, what)?;
+            Ok(result)
+        }
+    }
+}
+
+impl Rules {
+    /// Parse and type-check the rules of a config.
+    fn compile(config: &RulesConfig) -> Result<Self, StrategyError> {
+        let mut slots = Vec::new();
+        let mut parse = |name: &str, source: &str| -> Result<Node, StrategyError> {
+            let invalid = |e: String| StrategyError::InvalidConfig(format!("{}: {}", name, e));
+            let tokens = tokenize(source).map_err(invalid)?;
+            let mut parser = Parser {
+                tokens,
+                pos: 0,
+                source,
+                slots: &mut slots,
+            };
+            let node = parser.expression(0).map_err(invalid)?;
+            if parser.pos < parser.tokens.len() {
+                return Err(invalid(parser.error("unexpected input")));
+            }
+            match check(&node).map_err(invalid)? {
+                Type::Bool => Ok(node),
+                Type::Number => Err(invalid(format!("'{}' is not a condition", source))),
+            }
+        };
+
+        let entry = parse("entry", &config.entry)?;
+        let exit = parse("exit", &config.exit)?;
+        let short_entry = config
+            .short_entry
+            .as_deref()
+            .map(|s| parse("short_entry", s))
+            .transpose()?;
+        let short_exit = config
+            .short_exit
+            .as_deref()
+            .map(|s| parse("short_exit", s))
+            .transpose()?;
+        Ok(Self {
+            slots,
+            entry,
+            exit,
+            short_entry,
+            short_exit,
+        })
+    }
+
+    /// Bars before every rule can be decided: the longest indicator warmup,
+    /// plus a bar when a crossing needs the previous values too.
+    fn warmup(&self) -> usize {
+        let indicators = self
+            .slots
+            .iter()
+            .map(|(f, p)| f.warmup(*p))
+            .max()
+            .unwrap_or(0);
+        let crossing = [&self.entry, &self.exit]
+            .into_iter()
+            .chain(self.short_entry.as_ref())
+            .chain(self.short_exit.as_ref())
+            .any(Node::has_crossing);
+        indicators + usize::from(crossing)
+    }
+
+    /// Indicator key of a slot, e.g. `sma(200)`.
+    fn key(&self, slot: usize) -> String {
+        let (function, period) = self.slots[slot];
+        format!("{}({})", function.name(), period)
+    }
+}
+
+/// Streaming indicator behind a slot.
+enum SlotIndicator {
+    Sma(StreamingSma),
+    Ema(StreamingEma),
+    Rsi(StreamingRsi),
+    StdDev(StreamingStdDev),
+    Atr(StreamingAtr),
+    Highest(RollingMax),
+    Lowest(RollingMin),
+}
+
+impl SlotIndicator {
+    fn new(function: Function, period: usize) -> Self {
+        match function {
+            Function::Sma => SlotIndicator::Sma(StreamingSma::new(period)),
+            Function::Ema => SlotIndicator::Ema(StreamingEma::new(period)),
+            Function::Rsi => SlotIndicator::Rsi(StreamingRsi::new(period)),
+            Function::StdDev => SlotIndicator::StdDev(StreamingStdDev::new(period)),
+            Function::Atr => SlotIndicator::Atr(StreamingAtr::new(period)),
+            Function::Highest => SlotIndicator::Highest(RollingMax::new(period)),
+            Function::Lowest => SlotIndicator::Lowest(RollingMin::new(period)),
+        }
+    }
+
+    fn update(&mut self, bar: &Bar) -> Option<f64> {
+        match self {
+            SlotIndicator::Sma(i) => i.update(bar.close),
+            SlotIndicator::Ema(i) => i.update(bar.close),
+            SlotIndicator::Rsi(i) => i.update(bar.close),
+            SlotIndicator::StdDev(i) => i.update(bar.close),
+            SlotIndicator::Atr(i) => i.update_ohlc(bar.high, bar.low, bar.close),
+            // Channel of the bars before this one
+            SlotIndicator::Highest(i) => {
+                let value = i.is_full().then(|| i.get()).flatten();
+                i.push(bar.high);
+                value
+            }
+            SlotIndicator::Lowest(i) => {
+                let value = i.is_full().then(|| i.get()).flatten();
+                i.push(bar.low);
+                value
+            }
+        }
+    }
+}
+
+/// A bar with the slot values at it.
+#[derive(Debug, Clone)]
+struct Frame {
+    bar: Bar,
+    values: Vec<Option<f64>>,
+}
+
+impl Frame {
+    fn number(&self, node: &Node) -> Option<f64> {
+        match node {
+            Node::Number(value) => Some(*value),
+            Node::Field(field) => Some(field.get(&self.bar)),
+            Node::Slot(slot) => self.values[*slot],
+            Node::Neg(inner) => self.number(inner).map(|v| -v),
+            Node::Binary(left, op, right) => {
+                let (a, b) = (self.number(left)?, self.number(right)?);
+                let value = match op {
+                    Op::Add => a + b,
+                    Op::Sub => a - b,
+                    Op::Mul => a * b,
+                    Op::Div => a / b,
+                    _ => return None,
+                };
+                value.is_finite().then_some(value)
+            }
+            Node::Not(_) => None,
+        }
+    }
+}
+
+/// Evaluate a condition on `current`, with `previous` for crossings.
+/// `None` means unknown (some value not available yet).
+fn evaluate(node: &Node, current: &Frame, previous: Option<&Frame>) -> Option<bool> {
+    match node {
+        Node::Not(inner) => evaluate(inner, current, previous).map(|v| !v),
+        Node::Binary(left, Op::And, right) => {
+            match (
+                evaluate(left, current, previous),
+                evaluate(right, current, previous),
+            ) {
+                (Some(false), _) | (_, Some(false)) => Some(false),
+                (Some(true), Some(true)) => Some(true),
+                _ => None,
+            }
+        }
+        Node::Binary(left, Op::Or, right) => {
+            match (
+                evaluate(left, current, previous),
+                evaluate(right, current, previous),
+            ) {
+                (Some(true), _) | (_, Some(true)) => Some(true),
+                (Some(false), Some(false)) => Some(false),
+                _ => None,
+            }
+        }
+        Node::Binary(left, op @ (Op::CrossesAbove | Op::CrossesBelow), right) => {
+            let previous = previous?;
+            let (a, b) = (current.number(left)?, current.number(right)?);
+            let (pa, pb) = (previous.number(left)?, previous.number(right)?);
+            Some(match op {
+                Op::CrossesAbove => pa <= pb && a > b,
+                _ => pa >= pb && a < b,
+            })
+        }
+        Node::Binary(left, op, right) => {
+            let (a, b) = (current.number(left)?, current.number(right)?);
+            Some(match op {
+                Op::Lt => a < b,
+                Op::Le => a <= b,
+                Op::Gt => a > b,
+                Op::Ge => a >= b,
+                Op::Eq => a == b,
+                _ => a != b,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Position state
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+enum PositionState {
+    #[default]
+    Flat,
+    Long,
+    Short,
+}
+
+/// Indicators, latest frames and position of one symbol.
+struct SymbolState {
+    indicators: Vec<SlotIndicator>,
+    previous: Option<Frame>,
+    current: Option<Frame>,
+    position: PositionState,
+}
+
+impl SymbolState {
+    fn new(rules: &Rules) -> Self {
+        Self {
+            indicators: rules
+                .slots
+                .iter()
+                .map(|(f, p)| SlotIndicator::new(*f, *p))
+                .collect(),
+            previous: None,
+            current: None,
+            position: PositionState::Flat,
+        }
+    }
+
+    /// Consume a bar; returns the signal type and the rule that fired.
+    fn update(
+        &mut self,
+        rules: &Rules,
+        config: &RulesConfig,
+        bar: &Bar,
+    ) -> Option<(SignalType, String)> {
+        let values = self.indicators.iter_mut().map(|i| i.update(bar)).collect();
+        self.previous = self.current.take();
+        let current = self.current.insert(Frame { bar: *bar, values });
+        let holds = |node: &Node| evaluate(node, current, self.previous.as_ref()) == Some(true);
+
+        let (next, signal_type, rule) = match self.position {
+            PositionState::Flat if holds(&rules.entry) => (
+                PositionState::Long,
+                SignalType::Buy,
+                format!("entry: {}", config.entry),
+            ),
+            PositionState::Flat if rules.short_entry.as_ref().is_some_and(holds) => (
+                PositionState::Short,
+                SignalType::Sell,
+                format!(
+                    "short_entry: {}",
+                    config.short_entry.as_deref().unwrap_or_default()
+                ),
+            ),
+            PositionState::Long if holds(&rules.exit) => (
+                PositionState::Flat,
+                SignalType::CloseLong,
+                format!("exit: {}", config.exit),
+            ),
+            PositionState::Short if rules.short_exit.as_ref().is_some_and(holds) => (
+                PositionState::Flat,
+                SignalType::CloseShort,
+                format!(
+                    "short_exit: {}",
+                    config.short_exit.as_deref().unwrap_or_default()
+                ),
+            ),
+            _ => return None,
+        };
+        self.position = next;
+        Some((signal_type, rule))
+    }
+}
+
+/// Strategy trading on configured rule expressions.
+pub struct RulesStrategy {
+    config: RulesConfig,
+    rules: Rules,
+    states: StreamingState<SymbolState>,
+    bars_processed: usize,
+    signals_generated: usize,
+}
+
+impl RulesStrategy {
+    /// Create a rules strategy.
+    ///
+    /// # Panics
+    /// If the rules do not parse; check them with
+    /// [`validate`](StrategyConfig::validate) first.
+    pub fn new(config: RulesConfig) -> Self {
+        let rules = match Rules::compile(&config) {
+            Ok(rules) => rules,
+            Err(e) => panic!("{}", e),
+        };
+        Self {
+            config,
+            rules,
+            states: StreamingState::new(),
+            bars_processed: 0,
+            signals_generated: 0,
+        }
+    }
+
+    /// Indicator keys of the signals this strategy emits: each indicator
+    /// the rules use, present once it has a value.
+    pub fn signal_schema(&self) -> MetadataSchema {
+        let keys: Vec<String> = (0..self.rules.slots.len())
+            .map(|slot| self.rules.key(slot))
+            .collect();
+        let keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+        MetadataSchema::new(&[], &keys)
+    }
+
+    fn readings(&self, frame: &Frame) -> HashMap<String, f64> {
+        frame
+            .values
+            .iter()
+            .enumerate()
+            .filter_map(|(slot, value)| Some((self.rules.key(slot), (*value)?)))
+            .collect()
+    }
+}
+
+impl Strategy for RulesStrategy {
+    fn name(&self) -> &str {
+        "Rules"
+    }
+
+    fn description(&self) -> &str {
+        "Trades on entry and exit conditions written as expressions"
+    }
+
+    fn metadata_schema(&self) -> Option<MetadataSchema> {
+        Some(self.signal_schema())
+    }
+
+    fn on_bar(&mut self, series: &BarSeries) -> Option<Signal> {
+        self.bars_processed += 1;
+
+        let (rules, config) = (&self.rules, &self.config);
+        let (signal_type, reason) = self.states.update(
+            series,
+            || SymbolState::new(rules),
+            |state, bar| state.update(rules, config, bar),
+        )??;
+        let frame = self.states.get(&series.symbol)?.current.as_ref()?;
+        self.signals_generated += 1;
+
+        Some(Signal {
+            symbol: series.symbol.clone(),
+            signal_type,
+            strength: SignalStrength::Moderate,
+            price: frame.bar.close,
+            timestamp: frame.bar.timestamp,
+            confidence: 0.6,
+            fraction: None,
+            metadata: SignalMetadata {
+                strategy_name: self.name().to_string(),
+                indicators: self.readings(frame),
+                reason,
+                ..Default::default()
+            },
+        })
+    }
+
+    fn reset(&mut self) {
+        self.states.clear();
+        self.bars_processed = 0;
+        self.signals_generated = 0;
+    }
+
+    fn state(&self) -> StrategyState {
+        let latest = self
+            .config
+            .symbols
+            .iter()
+            .find_map(|s| self.states.get(s)?.current.as_ref());
+
+        StrategyState {
+            name: self.name().to_string(),
+            is_warmed_up: self.bars_processed >= self.warmup_period(),
+            bars_processed: self.bars_processed,
+            signals_generated: self.signals_generated,
+            indicators: latest.map(|f| self.readings(f)).unwrap_or_default(),
+            custom: serde_json::json!({
+                "entry": self.config.entry,
+                "exit": self.config.exit,
+                "short_entry": self.config.short_entry,
+                "short_exit": self.config.short_exit,
+                "positions": self
+                    .config
+                    .symbols
+                    .iter()
+                    .filter_map(|s| Some((s.clone(), format!("{:?}", self.states.get(s)?.position))))
+                    .collect::<HashMap<_, _>>(),
+            }),
+        }
+    }
+
+    fn warmup_period(&self) -> usize {
+        self.rules.warmup()
+    }
+
+    fn symbols(&self) -> &[String] {
+        &self.config.symbols
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use trading_core::types::Timeframe;
+
+    fn config(entry: &str, exit: &str) -> RulesConfig {
+        RulesConfig {
+            symbols: vec!["TEST".to_string()],
+            entry: entry.to_string(),
+            exit: exit.to_string(),
+            short_entry: None,
+            short_exit: None,
+        }
+    }
+
+    #[test]
+    fn test_rules_trade() {
+        let mut strategy = RulesStrategy::new(config(
+            "close crosses_above sma(3) and not close > highest(5)",
+            "close < sma(3) or (close - open) / open * 100 >= 5",
+        ));
+        assert_eq!(strategy.warmup_period(), 7);
+
+        let mut series = BarSeries::new("TEST".to_string(), Timeframe::Daily);
+        let mut signals = Vec::new();
+        let closes = [10.0, 9.0, 8.0, 7.0, 6.0, 5.0, 6.0, 6.5, 6.0, 5.0, 5.5, 6.0];
+        for (i, &close) in closes.iter().enumerate() {
+            series.push(Bar::new(
+                i as i64 * 86_400_000,
+                close,
+                close,
+                close,
+                close,
+                1e3,
+            ));
+            if let Some(signal) = strategy.on_bar(&series) {
+                strategy.signal_schema().validate(&signal.metadata).unwrap();
+                signals.push((i, signal));
+            }
+        }
+
+        // Crossing above the average, still under the 5-bar high; exits
+        // when the close drops back under it
+        let types: Vec<_> = signals.iter().map(|(i, s)| (*i, s.signal_type)).collect();
+        assert_eq!(
+            types,
+            vec![
+                (6, SignalType::Buy),
+                (8, SignalType::CloseLong),
+                (11, SignalType::Buy),
+            ]
+        );
+        assert_eq!(signals[0].1.metadata.indicators["sma(3)"], 17.0 / 3.0);
+        assert!(signals[0].1.metadata.reason.starts_with("entry: "));
+    }
+
+    #[test]
+    fn test_rule_errors() {
+        let invalid = |entry: &str| config(entry, "close < open").validate().is_err();
+        assert!(!invalid("rsi(14) < 30 and close > sma(200)"));
+        assert!(invalid("rsi(14) <"));
+        assert!(invalid("close + sma(20)"));
+        assert!(invalid("rsi(14) < 30 and close"));
+        assert!(invalid("macd(12) > 0"));
+        assert!(invalid("sma(2.5) > close"));
+        assert!(invalid("stddev(1) > 0"));
+        assert!(!invalid("sma(10000) > close"));
+        assert!(invalid("sma(10001) > close"));
+        assert!(invalid("highest(1e12) < close"));
+        assert!(invalid("close > open)"));
+
+        let mut short_only = config("close > open", "close < open");
+        short_only.short_entry = Some("close < open".to_string());
+        assert!(short_only.validate().is_err());
+    }
+}
@@ -0,0 +1,575 @@
+//! Scriptable strategies.
+//!
+//! A [`ScriptStrategy`] runs a [Rhai](https://rhai.rs) script, so strategy
+//! logic can be changed without recompiling. The script defines
+//! `on_bar(bars)`, called on every bar of each symbol with:
+//!
+//! - `bars`: the symbol's bars, with `len()`, `symbol`, `bars[i]` (negative
+//!   indices count from the end) and `closes()`, `opens()`, `highs()`,
+//!   `lows()`, `volumes()` arrays; each bar has `timestamp`, `open`, `high`,
+//!   `low`, `close` and `volume`
+//! - `this`: a map kept per symbol between calls, for the script's state
+//! - helpers returning the latest value, or `()` without enough data:
+//!   `sma(values, n)`, `ema(values, n)`, `rsi(values, n)`,
+//!   `stddev(values, n)`, `highest(values, n)`, `lowest(values, n)` and
+//!   `atr(bars, n)`; `sma`, `ema`, `rsi` and `stddev` also take `bars`
+//!   (for its closes), computed once per bar however often they are called
+//! - `param(name)` / `param(name, default)`: values of the strategy config
+//!
+//! `on_bar` returns nothing, a signal type (`"buy"`, `"sell"`,
+//! `"close_long"`, `"close_short"`) or a map with `signal` and optionally
+//! `reason`, `confidence`, `fraction` (share of the position to enter or
+//! exit) and `indicators`. An optional `warmup()` returns
+//! the bars needed before the first signal.
+//!
+//! Scripts run sandboxed with an operation limit; a script error on a bar
+//! is logged and skipped rather than stopping the run.
+
+use rhai::{Array, CallFnOptions, Dynamic, Engine, Map, Scope, AST, INT};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tracing::warn;
+use trading_core::{
+    error::StrategyError,
+    traits::{Indicator, Strategy, StrategyState},
+    types::{Bar, BarSeries, Signal, SignalMetadata, SignalStrength, SignalType},
+};
+use trading_indicators::{Atr, Ema, IndicatorCache, Rsi, Sma, StdDev};
+
+/// Operations a script may run per call before it is stopped.
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// Bars of a symbol, as seen by scripts.
+#[derive(Debug, Clone)]
+struct ScriptBars {
+    series: Arc<BarSeries>,
+}
+
+/// Script array of a bar column.
+fn column(values: &[f64]) -> Array {
+    values.iter().map(|&v| Dynamic::from_float(v)).collect()
+}
+
+/// Numbers of a script array; integers are widened.
+fn floats(values: &Array) -> Vec<f64> {
+    values
+        .iter()
+        .filter_map(|v| {
+            v.as_float()
+                .ok()
+                .or_else(|| v.as_int().ok().map(|i| i as f64))
+        })
+        .collect()
+}
+
+/// Last value of an indicator, or `()` if there is none yet.
+fn latest(values: &[f64]) -> Dynamic {
+    values
+        .last()
+        .copied()
+        .filter(|v| v.is_finite())
+        .map_or(Dynamic::UNIT, Dynamic::from_float)
+}
+
+/// Latest value of a `period` indicator over `values`, or `()` (also for
+/// periods below `min`).
+fn indicator<I: Indicator<Output = f64>>(
+    make: fn(usize) -> I,
+    values: &Array,
+    period: INT,
+    min: usize,
+) -> Dynamic {
+    match usize::try_from(period) {
+        Ok(period) if period >= min => latest(&make(period).calculate(&floats(values))),
+        _ => Dynamic::UNIT,
+    }
+}
+
+/// Script function like [`indicator`] over the closes of `bars`, reusing
+/// the values until the bars change.
+fn cached_indicator<I: Indicator<Output = f64> + 'static>(
+    cache: &Arc<Mutex<IndicatorCache>>,
+    make: fn(usize) -> I,
+    min: usize,
+) -> impl Fn(&mut ScriptBars, INT) -> Dynamic + Send + Sync + 'static {
+    let cache = cache.clone();
+    move |bars: &mut ScriptBars, period: INT| match usize::try_from(period) {
+        Ok(period) if period >= min => {
+            let mut cache = cache.lock().unwrap_or_else(|e| e.into_inner());
+            latest(cache.calculate(&bars.series, &make(period)))
+        }
+        _ => Dynamic::UNIT,
+    }
+}
+
+/// Highest or lowest of the last `n` values, or `()`.
+fn extreme(values: &Array, n: INT, pick: fn(f64, f64) -> f64) -> Dynamic {
+    let values = floats(values);
+    match usize::try_from(n) {
+        Ok(n) if n > 0 && n <= values.len() => latest(&[values[values.len() - n..]
+            .iter()
+            .copied()
+            .fold(f64::NAN, pick)]),
+        _ => Dynamic::UNIT,
+    }
+}
+
+/// Script engine with the bar type, helpers and `param` for `config`.
+/// Helpers over whole bar series keep their results in `cache`.
+fn engine(config: Map, cache: Arc<Mutex<IndicatorCache>>) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+
+    engine
+        .register_type_with_name::<Bar>("Bar")
+        .register_get("timestamp", |b: &mut Bar| b.timestamp as INT)
+        .register_get("open", |b: &mut Bar| b.open)
+        .register_get("high", |b: &mut Bar| b.high)
+        .register_get("low", |b: &mut Bar| b.low)
+        .register_get("close", |b: &mut Bar| b.close)
+        .register_get("volume", |b: &mut Bar| b.volume);
+
+    engine
+        .register_type_with_name::<ScriptBars>("Bars")
+        .register_get("symbol", |b: &mut ScriptBars| b.series.symbol.clone())
+        .register_fn("len", |b: &mut ScriptBars| b.series.len() as INT)
+        .register_fn("closes", |b: &mut ScriptBars| {
+            column(b.series.close_slice())
+        })
+        .register_fn("opens", |b: &mut ScriptBars| column(b.series.open_slice()))
+        .register_fn("highs", |b: &mut ScriptBars| column(b.series.high_slice()))
+        .register_fn("lows", |b: &mut ScriptBars| column(b.series.low_slice()))
+        .register_fn("volumes", |b: &mut ScriptBars| {
+            column(b.series.volume_slice())
+        })
+        .register_indexer_get(|b: &mut ScriptBars, i: INT| {
+            let len = b.series.len() as INT;
+            let index = if i < 0 { len + i } else { i };
+            usize::try_from(index)
+                .ok()
+                .and_then(|i| b.series.get(i))
+                .map_or(Dynamic::UNIT, |bar| Dynamic::from(*bar))
+        });
+
+    let atr_cache = cache.clone();
+    engine
+        .register_fn("sma", |v: Array, n: INT| indicator(Sma::new, &v, n, 1))
+        .register_fn("ema", |v: Array, n: INT| indicator(Ema::new, &v, n, 1))
+        .register_fn("rsi", |v: Array, n: INT| indicator(Rsi::new, &v, n, 1))
+        .register_fn("stddev", |v: Array, n: INT| {
+            indicator(StdDev::new, &v, n, 2)
+        })
+        .register_fn("highest", |v: Array, n: INT| extreme(&v, n, f64::max))
+        .register_fn("lowest", |v: Array, n: INT| extreme(&v, n, f64::min))
+        .register_fn(
+            "atr",
+            move |b: &mut ScriptBars, n: INT| match usize::try_from(n) {
+                Ok(n) if n > 0 => {
+                    let mut cache = atr_cache.lock().unwrap_or_else(|e| e.into_inner());
+                    latest(
+                        cache.get_or_compute(&b.series, &format!("ATR({})", n), |s| {
+                            Atr::new(n).calculate_ohlc(
+                                s.high_slice(),
+                                s.low_slice(),
+                                s.close_slice(),
+                            )
+                        }),
+                    )
+                }
+                _ => Dynamic::UNIT,
+            },
+        );
+
+    engine
+        .register_fn("sma", cached_indicator(&cache, Sma::new, 1))
+        .register_fn("ema", cached_indicator(&cache, Ema::new, 1))
+        .register_fn("rsi", cached_indicator(&cache, Rsi::new, 1))
+        .register_fn("stddev", cached_indicator(&cache, StdDev::new, 2));
+
+    let config = Arc::new(config);
+    let params = config.clone();
+    engine
+        .register_fn("param", move |name: &str| {
+            params.get(name).cloned().unwrap_or(Dynamic::UNIT)
+        })
+        .register_fn("param", move |name: &str, default: Dynamic| {
+            config.get(name).cloned().unwrap_or(default)
+        });
+
+    engine
+}
+
+/// Parse a signal type returned by a script.
+fn signal_type(name: &str) -> Result<Option<SignalType>, String> {
+    match name.to_lowercase().as_str() {
+        "buy" => Ok(Some(SignalType::Buy)),
+        "sell" => Ok(Some(SignalType::Sell)),
+        "close_long" => Ok(Some(SignalType::CloseLong)),
+        "close_short" => Ok(Some(SignalType::CloseShort)),
+        "hold" => Ok(None),
+        _ => Err(format!("unknown signal type '{}'", name)),
+    }
+}
+
+/// A signal returned by a script.
+struct ScriptSignal {
+    signal_type: SignalType,
+    reason: String,
+    confidence: f64,
+    fraction: Option<f64>,
+    indicators: HashMap<String, f64>,
+}
+
+impl ScriptSignal {
+    /// Parse the return value of `on_bar`.
+    fn parse(value: Dynamic) -> Result<Option<Self>, String> {
+        if value.is_unit() {
+            return Ok(None);
+        }
+        if let Ok(name) = value.clone().into_string() {
+            return Ok(signal_type(&name)?.map(|signal_type| Self {
+                signal_type,
+                reason: String::new(),
+                confidence: 0.6,
+                fraction: None,
+                indicators: HashMap::new(),
+            }));
+        }
+        let Some(map) = value.try_cast::<Map>() else {
+            return Err("on_bar must return (), a signal type or a map".to_string());
+        };
+
+        let name = map
+            .get("signal")
+            .and_then(|s| s.clone().into_string().ok())
+            .ok_or("signal map needs a `signal` string")?;
+        let Some(signal_type) = signal_type(&name)? else {
+            return Ok(None);
+        };
+        let number = |v: &Dynamic| {
+            v.as_float()
+                .ok()
+                .or_else(|| v.as_int().ok().map(|i| i as f64))
+        };
+        let indicators = match map.get("indicators") {
+            Some(v) => v
+                .clone()
+                .try_cast::<Map>()
+                .ok_or("`indicators` must be a map")?
+                .iter()
+                .map(|(k, v)| {
+                    number(v)
+                        .map(|v| (k.to_string(), v))
+                        .ok_or_else(|| format!("indicator '{}' is not a number", k))
+                })
+                .collect::<Result<_, _>>()?,
+            None => HashMap::new(),
+        };
+
+        Ok(Some(Self {
+            signal_type,
+            reason: map.get("reason").map(|r| r.to_string()).unwrap_or_default(),
+            confidence: map.get("confidence").and_then(number).unwrap_or(0.6),
+            fraction: map.get("fraction").and_then(number),
+            indicators,
+        }))
+    }
+}
+
+/// Strategy whose logic lives in a Rhai script.
+pub struct ScriptStrategy {
+    name: String,
+    description: String,
+    symbols: Vec<String>,
+    engine: Engine,
+    ast: AST,
+    warmup: usize,
+    /// Each symbol's `this` map
+    states: HashMap<String, Dynamic>,
+    bars_processed: usize,
+    signals_generated: usize,
+    errors: usize,
+    last_error: Option<String>,
+}
+
+impl ScriptStrategy {
+    /// Compile a script. `config` (a JSON object) is what `param` reads.
+    pub fn new(
+        name: impl Into<String>,
+        source: &str,
+        config: serde_json::Value,
+        symbols: Vec<String>,
+    ) -> Result<Self, StrategyError> {
+        let name = name.into();
+        let config = match config {
+            serde_json::Value::Null => Map::new(),
+            config => rhai::serde::to_dynamic(config)
+                .ok()
+                .and_then(|c| c.try_cast::<Map>())
+                .ok_or_else(|| {
+                    StrategyError::InvalidConfig("Script config must be an object".into())
+                })?,
+        };
+        let engine = engine(config, Arc::new(Mutex::new(IndicatorCache::new())));
+        let ast = engine
+            .compile(source)
+            .map_err(|e| StrategyError::InvalidConfig(format!("{}: {}", name, e)))?;
+
+        let defines = |fn_name: &str, arity: usize| {
+            ast.iter_functions()
+                .any(|f| f.name == fn_name && f.params.len() == arity)
+        };
+        if !defines("on_bar", 1) {
+            return Err(StrategyError::InvalidConfig(format!(
+                "{}: script defines no on_bar(bars) function",
+                name
+            )));
+        }
+        let warmup = if defines("warmup", 0) {
+            let options = CallFnOptions::new().eval_ast(false);
+            let warmup: INT = engine
+                .call_fn_with_options(options, &mut Scope::new(), &ast, "warmup", ())
+                .map_err(|e| StrategyError::InvalidConfig(format!("{}: warmup(): {}", name, e)))?;
+            usize::try_from(warmup).unwrap_or(0)
+        } else {
+            0
+        };
+
+        if symbols.is_empty() {
+            return Err(StrategyError::InvalidConfig(
+                "At least one symbol required".into(),
+            ));
+        }
+        Ok(Self {
+            description: format!("Rhai script {}", name),
+            name,
+            symbols,
+            engine,
+            ast,
+            warmup,
+            states: HashMap::new(),
+            bars_processed: 0,
+            signals_generated: 0,
+            errors: 0,
+            last_error: None,
+        })
+    }
+
+    /// Load and compile a script file; the strategy is named after the file.
+    pub fn from_file(
+        path: impl AsRef<Path>,
+        config: serde_json::Value,
+        symbols: Vec<String>,
+    ) -> Result<Self, StrategyError> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path).map_err(|e| {
+            StrategyError::InvalidConfig(format!("Cannot read script {}: {}", path.display(), e))
+        })?;
+        let name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string());
+        let mut strategy = Self::new(name, &source, config, symbols)?;
+        strategy.description = format!("Rhai script {}", path.display());
+        Ok(strategy)
+    }
+
+    /// Run `on_bar` for the latest bar of `series`.
+    fn call(&mut self, series: &BarSeries) -> Result<Option<ScriptSignal>, String> {
+        let bars = ScriptBars {
+            series: Arc::new(series.clone()),
+        };
+        let state = self
+            .states
+            .entry(series.symbol.clone())
+            .or_insert_with(|| Dynamic::from_map(Map::new()));
+        let options = CallFnOptions::new()
+            .eval_ast(false)
+            .rewind_scope(true)
+            .bind_this_ptr(state);
+        let value: Dynamic = self
+            .engine
+            .call_fn_with_options(options, &mut Scope::new(), &self.ast, "on_bar", (bars,))
+            .map_err(|e| e.to_string())?;
+        ScriptSignal::parse(value)
+    }
+}
+
+impl Strategy for ScriptStrategy {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn on_bar(&mut self, series: &BarSeries) -> Option<Signal> {
+        self.bars_processed += 1;
+        let bar = series.last()?;
+
+        let signal = match self.call(series) {
+            Ok(signal) => signal?,
+            Err(e) => {
+                warn!(
+                    "Script {} failed on {} at {}: {}",
+                    self.name, series.symbol, bar.timestamp, e
+                );
+                self.errors += 1;
+                self.last_error = Some(e);
+                return None;
+            }
+        };
+        self.signals_generated += 1;
+
+        Some(Signal {
+            symbol: series.symbol.clone(),
+            signal_type: signal.signal_type,
+            strength: SignalStrength::Moderate,
+            price: bar.close,
+            timestamp: bar.timestamp,
+            confidence: signal.confidence.clamp(0.0, 1.0),
+            fraction: signal.fraction.map(|f| f.clamp(0.0, 1.0)),
+            metadata: SignalMetadata {
+                strategy_name: self.name.clone(),
+                indicators: signal.indicators,
+                reason: signal.reason,
+                ..Default::default()
+            },
+        })
+    }
+
+    fn reset(&mut self) {
+        self.states.clear();
+        self.bars_processed = 0;
+        self.signals_generated = 0;
+        self.errors = 0;
+        self.last_error = None;
+    }
+
+    fn state(&self) -> StrategyState {
+        StrategyState {
+            name: self.name.clone(),
+            is_warmed_up: self.bars_processed >= self.warmup,
+            bars_processed: self.bars_processed,
+            signals_generated: self.signals_generated,
+            indicators: HashMap::new(),
+            custom: serde_json::json!({
+                "script": self.description,
+                "errors": self.errors,
+                "last_error": self.last_error,
+            }),
+        }
+    }
+
+    fn warmup_period(&self) -> usize {
+        self.warmup
+    }
+
+    fn symbols(&self) -> &[String] {
+        &self.symbols
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use trading_core::types::Timeframe;
+
+    const SCRIPT: &str = r#"
+        fn warmup() { param("period", 3) }
+
+        fn on_bar(bars) {
+            let avg = sma(bars.closes(), param("period", 3));
+            if avg == () { return; }
+            let close = bars[-1].close;
+            if this.long != true && close > avg {
+                this.long = true;
+                return #{ signal: "buy", reason: "above average", indicators: #{ sma: avg } };
+            }
+            if this.long == true && close < avg {
+                this.long = false;
+                return "close_long";
+            }
+        }
+    "#;
+
+    fn run(strategy: &mut ScriptStrategy, closes: &[f64]) -> Vec<(usize, Signal)> {
+        let mut series = BarSeries::new("TEST".to_string(), Timeframe::Daily);
+        let mut signals = Vec::new();
+        for (i, &close) in closes.iter().enumerate() {
+            series.push(Bar::new(
+                i as i64 * 86_400_000,
+                close,
+                close,
+                close,
+                close,
+                1e3,
+            ));
+            if let Some(signal) = strategy.on_bar(&series) {
+                signals.push((i, signal));
+            }
+        }
+        signals
+    }
+
+    #[test]
+    fn test_script_signals_and_state() {
+        let config = serde_json::json!({ "period": 2 });
+        let mut strategy =
+            ScriptStrategy::new("avg", SCRIPT, config, vec!["TEST".to_string()]).unwrap();
+        assert_eq!(strategy.warmup_period(), 2);
+
+        let signals = run(&mut strategy, &[10.0, 10.0, 11.0, 12.0, 10.0, 9.0]);
+        let types: Vec<_> = signals.iter().map(|(i, s)| (*i, s.signal_type)).collect();
+        assert_eq!(
+            types,
+            vec![(2, SignalType::Buy), (4, SignalType::CloseLong)]
+        );
+        assert_eq!(signals[0].1.metadata.reason, "above average");
+        assert_eq!(signals[0].1.metadata.indicators["sma"], 10.5);
+    }
+
+    #[test]
+    fn test_script_errors() {
+        let symbols = vec!["TEST".to_string()];
+        let load = |source: &str| {
+            ScriptStrategy::new("bad", source, serde_json::Value::Null, symbols.clone())
+        };
+        assert!(load("fn on_bar(bars) {").is_err());
+        assert!(load("fn on_tick(bars) {}").is_err());
+
+        // Runtime errors skip the bar instead of stopping the run
+        let mut strategy =
+            load(r#"fn on_bar(bars) { if bars.len() == 2 { throw "boom"; } "buy" }"#).unwrap();
+        let signals = run(&mut strategy, &[1.0, 2.0, 3.0]);
+        assert_eq!(signals.len(), 2);
+        assert_eq!(strategy.state().custom["errors"], 1);
+
+        let mut strategy = load("fn on_bar(bars) { loop {} }").unwrap();
+        assert!(run(&mut strategy, &[1.0]).is_empty());
+    }
+
+    #[test]
+    fn test_series_helpers_match_array_helpers() {
+        let script = r#"
+            fn on_bar(bars) {
+                let cached = rsi(bars, 3);
+                if cached != rsi(bars.closes(), 3) || cached != rsi(bars, 3) { throw "mismatch"; }
+                if sma(bars, 2) != sma(bars.closes(), 2) { throw "mismatch"; }
+                if cached != () { "buy" }
+            }
+        "#;
+        let mut strategy = ScriptStrategy::new(
+            "cached",
+            script,
+            serde_json::Value::Null,
+            vec!["TEST".to_string()],
+        )
+        .unwrap();
+        let signals = run(&mut strategy, &[10.0, 11.0, 10.5, 12.0, 11.0, 13.0]);
+        assert_eq!(strategy.state().custom["errors"], 0);
+        assert_eq!(signals.len(), 3);
+    }
+}
@@ -0,0 +1,119 @@
+//! Strategies and backtests.
+
+use serde::Deserialize;
+use std::collections::HashMap;
+use trading_backtest::{BacktestConfig, BacktestEngine, BacktestReport};
+use trading_core::error::{TradingError, TradingResult};
+use trading_core::types::Bar;
+use trading_strategies::StrategyRegistry;
+use wasm_bindgen::prelude::*;
+
+use crate::to_js;
+
+/// A backtest to run, as passed to `runBacktest`.
+#[derive(Debug, Deserialize)]
+pub struct BacktestRequest {
+    /// Built-in strategy name
+    pub strategy: String,
+    /// Symbols to trade; defaults to every symbol of `bars`
+    #[serde(default)]
+    pub symbols: Vec<String>,
+    /// Strategy settings replacing its defaults
+    #[serde(default)]
+    pub config: serde_json::Value,
+    /// Backtest settings replacing the defaults, named as in `[backtest]`
+    #[serde(default)]
+    pub settings: serde_json::Map<String, serde_json::Value>,
+    /// Bars of each symbol
+    pub bars: HashMap<String, Vec<Bar>>,
+}
+
+/// Run a backtest request.
+pub async fn backtest(request: BacktestRequest) -> TradingResult<BacktestReport> {
+    let symbols = if request.symbols.is_empty() {
+        let mut symbols: Vec<String> = request.bars.keys().cloned().collect();
+        symbols.sort();
+        symbols
+    } else {
+        request.symbols
+    };
+    let mut strategy = StrategyRegistry::new().create_with_overrides(
+        &request.strategy,
+        request.config,
+        symbols,
+    )?;
+    let config = BacktestConfig::from_overrides(request.settings)?;
+    if request.bars.values().all(Vec::is_empty) {
+        return Err(TradingError::Validation("No bars to backtest".into()));
+    }
+    Ok(BacktestEngine::new(config)
+        .run(strategy.as_mut(), request.bars)
+        .await)
+}
+
+/// Names of the built-in strategies, sorted.
+#[wasm_bindgen]
+pub fn strategies() -> Vec<String> {
+    let registry = StrategyRegistry::new();
+    let mut names: Vec<String> = registry.names().into_iter().cloned().collect();
+    names.sort();
+    names
+}
+
+/// Default configuration of a strategy.
+#[wasm_bindgen(js_name = strategyConfig)]
+pub fn strategy_config(name: &str) -> Result<JsValue, JsError> {
+    let registry = StrategyRegistry::new();
+    let info = registry
+        .get(name)
+        .ok_or_else(|| JsError::new(&format!("Unknown strategy: {}", name)))?;
+    to_js(&info.default_config)
+}
+
+/// Run a backtest; resolves to the report.
+#[wasm_bindgen(js_name = runBacktest)]
+pub async fn run_backtest(request: JsValue) -> Result<JsValue, JsError> {
+    let request: BacktestRequest = serde_wasm_bindgen::from_value(request)?;
+    to_js(&backtest(request).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(settings: serde_json::Value) -> BacktestRequest {
+        let bars: Vec<serde_json::Value> = (0..200)
+            .map(|i| {
+                let price = 100.0 + (i as f64 / 8.0).sin() * 10.0;
+                serde_json::json!({
+                    "timestamp": i * 86_400_000_i64,
+                    "open": price,
+                    "high": price + 1.0,
+                    "low": price - 1.0,
+                    "close": price,
+                    "volume": 1000.0,
+                })
+            })
+            .collect();
+        serde_json::from_value(serde_json::json!({
+            "strategy": "ma_crossover",
+            "config": { "fast_period": 5, "slow_period": 20 },
+            "settings": settings,
+            "bars": { "TEST": bars },
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_backtest_request() {
+        let report = backtest(request(serde_json::json!({ "initial_capital": 50000 })))
+            .await
+            .unwrap();
+        assert_eq!(report.stats.initial_capital, 50000.into());
+        assert_eq!(report.stats.bars_processed, 200);
+        assert!(report.stats.total_trades > 0);
+
+        let err = backtest(request(serde_json::json!({ "bogus": true }))).await;
+        assert!(err.unwrap_err().to_string().contains("bogus"));
+    }
+}
@@ -0,0 +1,248 @@
+//! Indicator functions over arrays (or `Float64Array`s) of numbers.
+//!
+//! Each returns the indicator's values, oldest first, starting once it has
+//! enough data; multi-output indicators return an array of objects.
+//! Parameters left `undefined` take the usual defaults.
+
+use trading_core::traits::{Indicator, MultiOutputIndicator};
+use trading_indicators::volatility::KeltnerChannels;
+use trading_indicators::{
+    Adx, Aroon, Atr, BollingerBands, Cci, Dema, DonchianChannels, Ema, Hma, Kama, Macd, Roc, Rsi,
+    Sma, StdDev, Stochastic, Tema, WilliamsR, Wma,
+};
+use wasm_bindgen::prelude::*;
+
+use crate::to_js;
+
+/// Fail with `message` unless `ok`, instead of letting the indicator's own
+/// assertion abort the module.
+fn require(ok: bool, message: &str) -> Result<(), JsError> {
+    if ok {
+        Ok(())
+    } else {
+        Err(JsError::new(message))
+    }
+}
+
+fn positive(period: usize) -> Result<(), JsError> {
+    require(period > 0, "period must be greater than 0")
+}
+
+/// Simple moving average.
+#[wasm_bindgen]
+pub fn sma(values: &[f64], period: usize) -> Result<Vec<f64>, JsError> {
+    positive(period)?;
+    Ok(Sma::new(period).calculate(values))
+}
+
+/// Exponential moving average.
+#[wasm_bindgen]
+pub fn ema(values: &[f64], period: usize) -> Result<Vec<f64>, JsError> {
+    positive(period)?;
+    Ok(Ema::new(period).calculate(values))
+}
+
+/// Weighted moving average.
+#[wasm_bindgen]
+pub fn wma(values: &[f64], period: usize) -> Result<Vec<f64>, JsError> {
+    positive(period)?;
+    Ok(Wma::new(period).calculate(values))
+}
+
+/// Hull moving average.
+#[wasm_bindgen]
+pub fn hma(values: &[f64], period: usize) -> Result<Vec<f64>, JsError> {
+    positive(period)?;
+    Ok(Hma::new(period).calculate(values))
+}
+
+/// Kaufman adaptive moving average.
+#[wasm_bindgen]
+pub fn kama(
+    values: &[f64],
+    period: usize,
+    fast: Option<usize>,
+    slow: Option<usize>,
+) -> Result<Vec<f64>, JsError> {
+    let (fast, slow) = (fast.unwrap_or(2), slow.unwrap_or(30));
+    positive(period)?;
+    require(
+        fast > 0 && fast < slow,
+        "fast must be above 0 and below slow",
+    )?;
+    Ok(Kama::with_smoothing(period, fast, slow).calculate(values))
+}
+
+/// Double exponential moving average.
+#[wasm_bindgen]
+pub fn dema(values: &[f64], period: usize) -> Result<Vec<f64>, JsError> {
+    positive(period)?;
+    Ok(Dema::new(period).calculate(values))
+}
+
+/// Triple exponential moving average.
+#[wasm_bindgen]
+pub fn tema(values: &[f64], period: usize) -> Result<Vec<f64>, JsError> {
+    positive(period)?;
+    Ok(Tema::new(period).calculate(values))
+}
+
+/// Relative strength index.
+#[wasm_bindgen]
+pub fn rsi(values: &[f64], period: Option<usize>) -> Result<Vec<f64>, JsError> {
+    let period = period.unwrap_or(14);
+    positive(period)?;
+    Ok(Rsi::new(period).calculate(values))
+}
+
+/// Rate of change, in percent.
+#[wasm_bindgen]
+pub fn roc(values: &[f64], period: usize) -> Result<Vec<f64>, JsError> {
+    positive(period)?;
+    Ok(Roc::new(period).calculate(values))
+}
+
+/// Rolling standard deviation.
+#[wasm_bindgen]
+pub fn stddev(values: &[f64], period: usize) -> Result<Vec<f64>, JsError> {
+    require(period > 1, "period must be greater than 1")?;
+    Ok(StdDev::new(period).calculate(values))
+}
+
+/// MACD line, signal line and histogram.
+#[wasm_bindgen]
+pub fn macd(
+    values: &[f64],
+    fast: Option<usize>,
+    slow: Option<usize>,
+    signal: Option<usize>,
+) -> Result<JsValue, JsError> {
+    let (fast, slow, signal) = (fast.unwrap_or(12), slow.unwrap_or(26), signal.unwrap_or(9));
+    require(fast > 0 && signal > 0, "periods must be greater than 0")?;
+    require(fast < slow, "fast must be below slow")?;
+    to_js(&Macd::with_periods(fast, slow, signal).calculate(values))
+}
+
+/// Bollinger bands: upper, middle, lower, bandwidth and %B.
+#[wasm_bindgen]
+pub fn bollinger(
+    values: &[f64],
+    period: Option<usize>,
+    std_dev: Option<f64>,
+) -> Result<JsValue, JsError> {
+    let (period, std_dev) = (period.unwrap_or(20), std_dev.unwrap_or(2.0));
+    require(period > 1, "period must be greater than 1")?;
+    require(std_dev > 0.0, "std_dev must be positive")?;
+    to_js(&BollingerBands::with_params(period, std_dev).calculate(values))
+}
+
+/// Average true range.
+#[wasm_bindgen]
+pub fn atr(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    period: Option<usize>,
+) -> Result<Vec<f64>, JsError> {
+    let period = period.unwrap_or(14);
+    positive(period)?;
+    Ok(Atr::new(period).calculate_ohlc(high, low, close))
+}
+
+/// Stochastic oscillator %K and %D.
+#[wasm_bindgen]
+pub fn stochastic(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    k_period: Option<usize>,
+    d_period: Option<usize>,
+) -> Result<JsValue, JsError> {
+    let (k_period, d_period) = (k_period.unwrap_or(14), d_period.unwrap_or(3));
+    require(
+        k_period > 0 && d_period > 0,
+        "periods must be greater than 0",
+    )?;
+    to_js(&Stochastic::with_periods(k_period, d_period).calculate_ohlc(high, low, close))
+}
+
+/// ADX with +DI and -DI.
+#[wasm_bindgen]
+pub fn adx(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    period: Option<usize>,
+) -> Result<JsValue, JsError> {
+    let period = period.unwrap_or(14);
+    positive(period)?;
+    to_js(&Adx::new(period).calculate_ohlc(high, low, close))
+}
+
+/// Commodity channel index.
+#[wasm_bindgen]
+pub fn cci(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    period: Option<usize>,
+) -> Result<Vec<f64>, JsError> {
+    let period = period.unwrap_or(20);
+    positive(period)?;
+    Ok(Cci::new(period).calculate_ohlc(high, low, close))
+}
+
+/// Williams %R.
+#[wasm_bindgen(js_name = williamsR)]
+pub fn williams_r(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    period: Option<usize>,
+) -> Result<Vec<f64>, JsError> {
+    let period = period.unwrap_or(14);
+    positive(period)?;
+    Ok(WilliamsR::new(period).calculate_ohlc(high, low, close))
+}
+
+/// Aroon up, down and oscillator.
+#[wasm_bindgen]
+pub fn aroon(high: &[f64], low: &[f64], period: Option<usize>) -> Result<JsValue, JsError> {
+    let period = period.unwrap_or(25);
+    positive(period)?;
+    to_js(&Aroon::new(period).calculate_hl(high, low))
+}
+
+/// Donchian channel upper, middle and lower bands.
+#[wasm_bindgen]
+pub fn donchian(high: &[f64], low: &[f64], period: Option<usize>) -> Result<JsValue, JsError> {
+    let period = period.unwrap_or(20);
+    positive(period)?;
+    to_js(&DonchianChannels::new(period).calculate_hl(high, low))
+}
+
+/// Keltner channels: an EMA with bands a multiple of the ATR away.
+#[wasm_bindgen]
+pub fn keltner(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    ema_period: Option<usize>,
+    atr_period: Option<usize>,
+    multiplier: Option<f64>,
+) -> Result<JsValue, JsError> {
+    let (ema_period, atr_period, multiplier) = (
+        ema_period.unwrap_or(20),
+        atr_period.unwrap_or(10),
+        multiplier.unwrap_or(2.0),
+    );
+    require(
+        ema_period > 0 && atr_period > 0,
+        "periods must be greater than 0",
+    )?;
+    require(multiplier > 0.0, "multiplier must be positive")?;
+    to_js(
+        &KeltnerChannels::with_params(ema_period, atr_period, multiplier)
+            .calculate_ohlc(high, low, close),
+    )
+}
@@ -0,0 +1,38 @@
+//! WebAssembly bindings.
+//!
+//! Builds the indicators, strategies and backtest engine for the browser,
+//! so a strategy playground can run backtests client-side:
+//!
+//! ```js
+//! import init, { runBacktest, rsi } from "./pkg/trading_wasm.js";
+//!
+//! await init();
+//! const report = await runBacktest({
+//!   strategy: "ma_crossover",
+//!   config: { fast_period: 10, slow_period: 30 },
+//!   settings: { initial_capital: 50000 },
+//!   bars: { AAPL: [{ timestamp, open, high, low, close, volume }, ...] },
+//! });
+//! report.stats.sharpe_ratio;
+//! rsi(closes, 14);
+//! ```
+//!
+//! Build with `wasm-pack build crates/trading-wasm --target web`. On
+//! wasm32, `trading-broker` and `trading-data` leave out their network
+//! clients (Alpaca, the downloaders), and tokio is limited to what runs
+//! in the browser.
+
+mod backtest;
+mod indicators;
+
+pub use backtest::{backtest, BacktestRequest};
+
+use serde::Serialize;
+use wasm_bindgen::JsError;
+use wasm_bindgen::JsValue;
+
+/// JavaScript value of anything serializable: maps become plain objects
+/// and decimals strings, as in JSON reports.
+fn to_js(value: &impl Serialize) -> Result<JsValue, JsError> {
+    Ok(value.serialize(&serde_wasm_bindgen::Serializer::json_compatible())?)
+}
@@ -0,0 +1,9 @@
+//! Web dashboard: a read-only HTTP API over the paper trading state, with
+//! live updates pushed over a websocket and a small bundled frontend. An
+//! alternative to the TUI dashboard on headless servers.
+
+mod server;
+mod snapshot;
+
+pub use server::WebServer;
+pub use snapshot::{EquityPoint, Orders, Snapshot, StrategyView, Summary};
@@ -0,0 +1,152 @@
+//! HTTP API and websocket server.
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        State,
+    },
+    response::{Html, Response},
+    routing::get,
+    Json, Router,
+};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tracing::{debug, info};
+use trading_core::types::Position;
+use trading_monitor::DashboardState;
+
+use crate::snapshot::{EquityPoint, Orders, Snapshot, StrategyView, Summary};
+
+/// Bundled single-page frontend.
+const INDEX_HTML: &str = include_str!("../static/index.html");
+
+/// Read-only web dashboard over the shared dashboard state.
+///
+/// Routes: `/` (frontend), `/api/portfolio`, `/api/positions`,
+/// `/api/orders`, `/api/strategy`, `/api/equity`, and `/ws`, which pushes a
+/// full [`Snapshot`] as JSON every refresh.
+#[derive(Clone)]
+pub struct WebServer {
+    state: Arc<Mutex<DashboardState>>,
+    refresh: Duration,
+}
+
+impl WebServer {
+    /// Create a server pushing updates every `refresh_ms` milliseconds.
+    pub fn new(state: Arc<Mutex<DashboardState>>, refresh_ms: u64) -> Self {
+        Self {
+            state,
+            refresh: Duration::from_millis(refresh_ms.max(100)),
+        }
+    }
+
+    /// Routes of the dashboard.
+    pub fn router(self) -> Router {
+        Router::new()
+            .route("/", get(index))
+            .route("/api/portfolio", get(portfolio))
+            .route("/api/positions", get(positions))
+            .route("/api/orders", get(orders))
+            .route("/api/strategy", get(strategy))
+            .route("/api/equity", get(equity))
+            .route("/ws", get(websocket))
+            .with_state(self)
+    }
+
+    /// Serve the dashboard on `addr` until the task is dropped.
+    pub async fn serve(self, addr: SocketAddr) -> std::io::Result<()> {
+        let listener = TcpListener::bind(addr).await?;
+        info!("Web dashboard on http://{}", listener.local_addr()?);
+        axum::serve(listener, self.router()).await
+    }
+
+    fn snapshot<T>(&self, view: impl FnOnce(&DashboardState) -> T) -> T {
+        view(&self.state.lock().unwrap())
+    }
+}
+
+async fn index() -> Html<&'static str> {
+    Html(INDEX_HTML)
+}
+
+async fn portfolio(State(server): State<WebServer>) -> Json<Summary> {
+    Json(server.snapshot(Summary::of))
+}
+
+async fn positions(State(server): State<WebServer>) -> Json<Vec<Position>> {
+    Json(server.snapshot(|state| state.positions().into_iter().cloned().collect()))
+}
+
+async fn orders(State(server): State<WebServer>) -> Json<Orders> {
+    Json(server.snapshot(Orders::of))
+}
+
+async fn strategy(State(server): State<WebServer>) -> Json<Vec<StrategyView>> {
+    Json(server.snapshot(StrategyView::all))
+}
+
+async fn equity(State(server): State<WebServer>) -> Json<Vec<EquityPoint>> {
+    Json(server.snapshot(EquityPoint::all))
+}
+
+async fn websocket(State(server): State<WebServer>, upgrade: WebSocketUpgrade) -> Response {
+    upgrade.on_upgrade(move |socket| push_updates(server, socket))
+}
+
+/// Send a snapshot every refresh until the client goes away.
+async fn push_updates(server: WebServer, mut socket: WebSocket) {
+    let mut ticker = tokio::time::interval(server.refresh);
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {
+                let json = match serde_json::to_string(&server.snapshot(Snapshot::of)) {
+                    Ok(json) => json,
+                    Err(e) => {
+                        debug!("Failed to serialize snapshot: {}", e);
+                        continue;
+                    }
+                };
+                if socket.send(Message::Text(json.into())).await.is_err() {
+                    break;
+                }
+            }
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                _ => {}
+            },
+        }
+    }
+    debug!("Web dashboard client disconnected");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn test_serves_api_over_http() {
+        let state = Arc::new(Mutex::new(DashboardState {
+            strategy_name: "RSI".to_string(),
+            ..Default::default()
+        }));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let router = WebServer::new(state, 1000).router();
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(
+                b"GET /api/portfolio HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+            )
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"));
+        assert!(response.contains("\"strategy\":\"RSI\""));
+    }
+}
@@ -0,0 +1,167 @@
+//! JSON views of the dashboard state.
+
+use rust_decimal::Decimal;
+use serde::Serialize;
+use trading_core::traits::StrategyState;
+use trading_core::types::{Order, Position};
+use trading_monitor::DashboardState;
+
+/// Account figures and trading status.
+#[derive(Debug, Clone, Serialize)]
+pub struct Summary {
+    pub strategy: String,
+    pub equity: Decimal,
+    pub cash: Decimal,
+    pub buying_power: Decimal,
+    pub daily_pnl: Decimal,
+    pub gross_exposure: Decimal,
+    pub net_exposure: Decimal,
+    pub signals_today: usize,
+    pub trades_today: usize,
+    /// One-bar 95% Value-at-Risk in dollars, once there is enough history
+    pub value_at_risk: Option<Decimal>,
+    /// One-bar 95% Expected Shortfall in dollars
+    pub expected_shortfall: Option<Decimal>,
+    /// Why the circuit breaker halted trading, once it has
+    pub halted: Option<String>,
+    pub paused: bool,
+    pub dry_run: bool,
+}
+
+/// Working orders and the latest fills.
+#[derive(Debug, Clone, Serialize)]
+pub struct Orders {
+    pub open: Vec<Order>,
+    /// Newest first
+    pub recent_fills: Vec<Order>,
+}
+
+/// State of a running strategy or one of its members.
+#[derive(Debug, Clone, Serialize)]
+pub struct StrategyView {
+    pub label: String,
+    pub warmup_period: usize,
+    pub state: StrategyState,
+}
+
+/// Equity sample with its drawdown from the running peak.
+#[derive(Debug, Clone, Serialize)]
+pub struct EquityPoint {
+    /// Unix timestamp in milliseconds
+    pub timestamp: i64,
+    pub equity: f64,
+    /// Percentage below the running peak (zero or negative)
+    pub drawdown_pct: f64,
+}
+
+/// Everything the web dashboard shows, as pushed over the websocket.
+#[derive(Debug, Clone, Serialize)]
+pub struct Snapshot {
+    pub summary: Summary,
+    /// Sorted by symbol
+    pub positions: Vec<Position>,
+    pub orders: Orders,
+    /// The running strategy followed by its members, if any
+    pub strategies: Vec<StrategyView>,
+    pub equity: Vec<EquityPoint>,
+}
+
+impl Summary {
+    pub fn of(state: &DashboardState) -> Self {
+        let portfolio = &state.portfolio;
+        Self {
+            strategy: state.strategy_name.clone(),
+            equity: portfolio.equity,
+            cash: portfolio.cash,
+            buying_power: portfolio.buying_power,
+            daily_pnl: state.daily_pnl,
+            gross_exposure: portfolio.gross_exposure(),
+            net_exposure: portfolio.net_exposure(),
+            signals_today: state.signals_today,
+            trades_today: state.trades_today,
+            value_at_risk: state.value_at_risk.map(|(var, _)| var),
+            expected_shortfall: state.value_at_risk.map(|(_, es)| es),
+            halted: state.halted.clone(),
+            paused: state.paused,
+            dry_run: state.dry_run,
+        }
+    }
+}
+
+impl Orders {
+    pub fn of(state: &DashboardState) -> Self {
+        Self {
+            open: state.open_orders.clone(),
+            recent_fills: state.recent_fills.clone(),
+        }
+    }
+}
+
+impl StrategyView {
+    pub fn all(state: &DashboardState) -> Vec<Self> {
+        state
+            .strategies
+            .iter()
+            .map(|s| Self {
+                label: s.label.clone(),
+                warmup_period: s.warmup_period,
+                state: s.state.clone(),
+            })
+            .collect()
+    }
+}
+
+impl EquityPoint {
+    pub fn all(state: &DashboardState) -> Vec<Self> {
+        state
+            .equity
+            .samples()
+            .zip(state.equity.drawdowns())
+            .map(|((timestamp, equity), drawdown_pct)| Self {
+                timestamp,
+                equity,
+                drawdown_pct,
+            })
+            .collect()
+    }
+}
+
+impl Snapshot {
+    pub fn of(state: &DashboardState) -> Self {
+        Self {
+            summary: Summary::of(state),
+            positions: state.positions().into_iter().cloned().collect(),
+            orders: Orders::of(state),
+            strategies: StrategyView::all(state),
+            equity: EquityPoint::all(state),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use trading_core::types::Portfolio;
+
+    #[test]
+    fn test_snapshot_of_dashboard_state() {
+        let mut state = DashboardState {
+            strategy_name: "MA Crossover".to_string(),
+            portfolio: Portfolio::new(dec!(100000)),
+            value_at_risk: Some((dec!(1200), dec!(1800))),
+            paused: true,
+            ..Default::default()
+        };
+        state.equity.push(0, 100_000.0);
+        state.equity.push(60_000, 95_000.0);
+
+        let json = serde_json::to_value(Snapshot::of(&state)).unwrap();
+        assert_eq!(json["summary"]["strategy"], "MA Crossover");
+        assert_eq!(json["summary"]["expected_shortfall"], "1800");
+        assert_eq!(json["summary"]["paused"], true);
+        assert_eq!(json["equity"][1]["timestamp"], 60_000);
+        assert!((json["equity"][1]["drawdown_pct"].as_f64().unwrap() + 5.0).abs() < 1e-9);
+        assert!(json["positions"].as_array().unwrap().is_empty());
+    }
+}
@@ -4,22 +4,59 @@ use anyhow::{Context, Result};
 use rust_decimal::Decimal;
 use std::collections::HashMap;
 use std::path::Path;
-use tracing::info;
-use trading_backtest::{BacktestConfig, BacktestEngine};
-use trading_data::CsvDataSource;
-use trading_risk::RiskConfig;
+use tracing::{info, warn};
+use trading_backtest::{
+    BacktestCheckpoint, BacktestConfig, BacktestEngine, BacktestReport, BaselineDiff,
+    BaselineTolerances, CheckpointConfig, LatencyModel, MonteCarloSummary, Provenance,
+    ResultsStore, Scenario, SimulationRng, StoredRun, StrategyMatrix, TimeframeSweep,
+    MONTE_CARLO_STREAM,
+};
+use trading_core::types::{Bar, Timeframe};
+use trading_data::{resample_bars, CsvDataSource};
 use trading_strategies::StrategyRegistry;
 
 use crate::cli::BacktestArgs;
 
-pub async fn run(args: BacktestArgs, _config_path: &Path) -> Result<()> {
+pub async fn run(args: BacktestArgs, config_path: &Path) -> Result<()> {
+    // A saved report brings the arguments of the run that produced it
+    let (mut args, reference) = match &args.from_report {
+        Some(path) => {
+            let report = BacktestReport::load(path)
+                .with_context(|| format!("Failed to load report {:?}", path))?;
+            info!("Re-running the backtest recorded in {:?}", path);
+            (rerun_args(&args, &report)?, Some(report))
+        }
+        None => (args, None),
+    };
+
+    let baseline = match &args.baseline {
+        Some(path) => Some(
+            BacktestReport::load(path)
+                .with_context(|| format!("Failed to load baseline {:?}", path))?,
+        ),
+        None => None,
+    };
+    // Without an explicit seed, compare like for like
+    if let (None, Some(baseline)) = (args.seed, &baseline) {
+        args.seed = Some(baseline.seed);
+    }
+
     info!("Starting backtest for strategy: {}", args.strategy);
 
-    // Create strategy
-    let registry = StrategyRegistry::new();
-    let mut strategy = registry
-        .create_default(&args.strategy, args.symbols.clone())
-        .context("Failed to create strategy")?;
+    let registry = super::strategy_registry()?;
+    let strategies = strategy_names(&registry, &args.strategy)?;
+
+    // A re-run uses the settings recorded with the report, not the file as
+    // it is now
+    let settings = match reference.as_ref().and_then(|r| r.provenance.as_ref()) {
+        Some(provenance) => provenance.strategy_config.clone(),
+        None => super::load_strategy_settings(args.strategy_config.as_deref())?,
+    };
+
+    let timeframe: Timeframe = args
+        .timeframe
+        .parse()
+        .map_err(|e: String| anyhow::anyhow!(e))?;
 
     // Load data
     let data = if let Some(data_path) = &args.data {
@@ -29,23 +66,164 @@ pub async fn run(args: BacktestArgs, _config_path: &Path) -> Result<()> {
                 data_path.display()
             );
         }
-        load_data_from_csv(data_path, &args.symbols).await?
+        load_data_from_csv(data_path, &args.symbols, timeframe).await?
     } else {
         anyhow::bail!("Please provide a data file or directory with --data (e.g. --data ./data)");
     };
 
+    if let Some(provenance) = reference.as_ref().and_then(|r| r.provenance.as_ref()) {
+        for change in provenance.data_changes(&data) {
+            warn!("Data differs from the original run: {}", change);
+        }
+    }
+
+    // Lot and tick rules, take-profit targets, time exits and strategy
+    // allocation come from the config file, when there is one
+    let mut risk_config = args.risk_preset.unwrap_or_default().config();
+    let instruments = if config_path.exists() {
+        let config =
+            trading_config::load_config(config_path).context("Failed to load config file")?;
+        risk_config.take_profit = config.risk.take_profit;
+        risk_config.time_exit = config.risk.time_exit.unwrap_or_default();
+        risk_config.allocation = config.risk.allocation;
+        config.instruments
+    } else {
+        HashMap::new()
+    };
+
     // Create backtest config
     let capital = Decimal::try_from(args.capital).unwrap_or_default();
     let backtest_config = BacktestConfig {
         initial_capital: capital,
         commission: Decimal::ZERO,
         slippage_pct: Decimal::try_from(0.05).unwrap(),
-        risk_config: RiskConfig::default(),
+        risk_config,
+        end_of_backtest: args.end_of_backtest,
+        duplicate_bars: args.duplicate_bars,
+        latency: LatencyModel {
+            order_latency_ms: 0,
+            fill_latency_ms: args.fill_latency_ms,
+            fill_jitter_ms: args.fill_jitter_ms,
+        },
+        slippage_noise_pct: Decimal::try_from(args.slippage_noise_pct).unwrap_or_default(),
+        seed: args.seed,
+        timeframe,
+        portfolio_mode: args.portfolio_mode,
+        instruments,
+        enforce_stops: !args.ignore_stops,
+    };
+
+    if baseline.is_some() && (strategies.len() > 1 || !args.sweep.is_empty()) {
+        anyhow::bail!("--baseline needs a single strategy and no --sweep");
+    }
+
+    if !args.sweep.is_empty() {
+        if strategies.len() > 1 {
+            anyhow::bail!("--sweep needs a single strategy");
+        }
+        return run_sweep(&args, &registry, &settings, backtest_config, data).await;
+    }
+
+    if strategies.len() > 1 {
+        return run_matrix(
+            &args,
+            &registry,
+            &settings,
+            &strategies,
+            backtest_config,
+            data,
+        )
+        .await;
+    }
+
+    // Create strategy
+    let settings = super::strategy_settings(&registry, &settings, &args.strategy);
+    let mut strategy = registry
+        .create_with_overrides(&args.strategy, settings.clone(), args.symbols.clone())
+        .context("Failed to create strategy")?;
+
+    // Parameters recorded with the run for later comparison
+    let mut parameters = run_parameters(
+        &args,
+        &registry,
+        &args.strategy,
+        &settings,
+        &backtest_config,
+    );
+    let provenance = Provenance::new(
+        env!("CARGO_PKG_VERSION"),
+        &args.strategy,
+        parameters["strategy"].clone(),
+    )
+    .with_args(serde_json::to_value(&args)?)
+    .with_data(args.data.as_deref().unwrap_or(Path::new("")), &data);
+
+    let mut scenarios = if args.stress {
+        Scenario::standard()
+    } else {
+        Vec::new()
+    };
+    scenarios.extend(args.scenarios.iter().cloned());
+    // Scenarios replay the original data, so keep a copy
+    let scenario_data = if scenarios.is_empty() {
+        HashMap::new()
+    } else {
+        data.clone()
     };
 
     // Run backtest
-    let engine = BacktestEngine::new(backtest_config);
-    let report = engine.run(strategy.as_mut(), data).await;
+    let mut engine = BacktestEngine::new(backtest_config);
+    if let Some(path) = &args.checkpoint {
+        engine = engine.with_checkpoints(CheckpointConfig::new(path, args.checkpoint_every));
+    }
+    let mut report = match &args.resume_from {
+        Some(path) => {
+            let checkpoint = BacktestCheckpoint::load(path)
+                .with_context(|| format!("Failed to load checkpoint {:?}", path))?;
+            engine
+                .resume(strategy.as_mut(), data, checkpoint)
+                .await
+                .context("Failed to resume backtest")?
+        }
+        None => engine.run(strategy.as_mut(), data).await,
+    };
+
+    parameters["seed"] = report.seed.into();
+    report.provenance = Some(provenance);
+
+    if let Some(reference) = &reference {
+        if report.stats.final_equity == reference.stats.final_equity
+            && report.stats.total_trades == reference.stats.total_trades
+        {
+            info!("Reproduced the saved report");
+        } else {
+            warn!(
+                "Re-run differs from the saved report: final equity {:.2} vs {:.2}, {} vs {} trades",
+                report.stats.final_equity,
+                reference.stats.final_equity,
+                report.stats.total_trades,
+                reference.stats.total_trades
+            );
+        }
+    }
+
+    for scenario in &scenarios {
+        info!("Running stress scenario: {}", scenario.name);
+        let mut strategy = registry
+            .create_with_overrides(&args.strategy, settings.clone(), args.symbols.clone())
+            .context("Failed to create strategy")?;
+        let result = engine
+            .run_scenario(strategy.as_mut(), &scenario_data, scenario, &report)
+            .await;
+        report.scenarios.push(result);
+    }
+
+    // Trade resampling draws from its own stream of the run's seed
+    let monte_carlo = MonteCarloSummary::bootstrap(
+        &report.stats,
+        args.monte_carlo,
+        &mut SimulationRng::new(report.seed).fork(MONTE_CARLO_STREAM),
+    );
 
     // Output results
     match args.output.as_str() {
@@ -55,6 +233,9 @@ pub async fn run(args: BacktestArgs, _config_path: &Path) -> Result<()> {
         }
         _ => {
             println!("{}", report.summary());
+            if let Some(monte_carlo) = &monte_carlo {
+                println!("{}", monte_carlo.render());
+            }
         }
     }
 
@@ -65,13 +246,264 @@ pub async fn run(args: BacktestArgs, _config_path: &Path) -> Result<()> {
         info!("Results saved to {:?}", save_path);
     }
 
+    // Record the run in the results store
+    if !args.no_store {
+        let store = ResultsStore::open(&args.results_dir)?;
+        let mut run = StoredRun::new(&args.strategy, args.symbols.clone(), parameters, &report);
+        store.save(&mut run)?;
+        info!("Run recorded as {} in {:?}", run.id, store.root());
+    }
+
+    if let Some(baseline) = &baseline {
+        let tolerances = args
+            .baseline_tolerance
+            .iter()
+            .fold(BaselineTolerances::default(), |t, &m| t.with(m));
+        let diff = BaselineDiff::compare(baseline, &report, &tolerances);
+        // Keep stdout parseable in JSON mode
+        if args.output == "json" {
+            eprintln!("{}", diff.render());
+        } else {
+            println!("{}", diff.render());
+        }
+        if !diff.passed() {
+            let moved: Vec<String> = diff
+                .metrics
+                .iter()
+                .filter(|m| !m.within_tolerance())
+                .map(|m| m.metric.to_string())
+                .collect();
+            anyhow::bail!("Backtest deviates from the baseline: {}", moved.join(", "));
+        }
+        info!("Backtest matches the baseline");
+    }
+
+    Ok(())
+}
+
+/// Arguments of the run recorded in `report`, pinned to its seed. Output
+/// options, and `--data` if given, come from `current`.
+fn rerun_args(current: &BacktestArgs, report: &BacktestReport) -> Result<BacktestArgs> {
+    let provenance = report
+        .provenance
+        .as_ref()
+        .context("Report has no provenance to re-run from; save a new one with --save")?;
+    if provenance.version != env!("CARGO_PKG_VERSION") {
+        warn!(
+            "Report was produced by version {}, this is {}; results may differ",
+            provenance.version,
+            env!("CARGO_PKG_VERSION")
+        );
+    }
+
+    let mut args: BacktestArgs = serde_json::from_value(provenance.args.clone())
+        .context("Failed to read the report's recorded arguments")?;
+    args.seed = Some(report.seed);
+    // A re-run starts from scratch
+    args.checkpoint = None;
+    args.resume_from = None;
+    args.output = current.output.clone();
+    args.save = current.save.clone();
+    args.no_store = current.no_store;
+    args.results_dir = current.results_dir.clone();
+    args.baseline = current.baseline.clone();
+    args.baseline_tolerance = current.baseline_tolerance.clone();
+    if current.data.is_some() {
+        args.data = current.data.clone();
+    }
+    Ok(args)
+}
+
+/// Strategies selected by `--strategy`: one name, a comma-separated list,
+/// or `all` registered strategies.
+fn strategy_names(registry: &StrategyRegistry, selection: &str) -> Result<Vec<String>> {
+    if selection.eq_ignore_ascii_case("all") {
+        let mut names: Vec<String> = registry.names().into_iter().cloned().collect();
+        names.sort();
+        return Ok(names);
+    }
+    let names: Vec<String> = selection
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+    if let Some(unknown) = names.iter().find(|n| !registry.exists(n)) {
+        anyhow::bail!("Unknown strategy: {}", unknown);
+    }
+    Ok(names)
+}
+
+/// Parameters recorded with a run for later comparison.
+fn run_parameters(
+    args: &BacktestArgs,
+    registry: &StrategyRegistry,
+    strategy: &str,
+    settings: &serde_json::Value,
+    config: &BacktestConfig,
+) -> serde_json::Value {
+    serde_json::json!({
+        "backtest": config,
+        "strategy": registry
+            .resolve_config(strategy, settings.clone())
+            .unwrap_or_default(),
+        "start": args.start,
+        "end": args.end,
+        "timeframe": args.timeframe,
+    })
+}
+
+/// Run several strategies over the same data and print a comparison
+/// table.
+async fn run_matrix(
+    args: &BacktestArgs,
+    registry: &StrategyRegistry,
+    settings: &serde_json::Value,
+    strategies: &[String],
+    mut config: BacktestConfig,
+    data: HashMap<String, Vec<Bar>>,
+) -> Result<()> {
+    if args.checkpoint.is_some() || args.resume_from.is_some() {
+        anyhow::bail!("--checkpoint and --resume-from need a single strategy");
+    }
+
+    // One seed for every run so stochastic models treat them alike
+    let seed = *config
+        .seed
+        .get_or_insert_with(|| SimulationRng::from_entropy().seed());
+    let engine = BacktestEngine::new(config.clone());
+    let store = if args.no_store {
+        None
+    } else {
+        Some(ResultsStore::open(&args.results_dir)?)
+    };
+
+    let mut matrix = StrategyMatrix::default();
+    for name in strategies {
+        info!("Backtesting strategy: {}", name);
+        let settings = super::strategy_settings(registry, settings, name);
+        let mut strategy = registry
+            .create_with_overrides(name, settings.clone(), args.symbols.clone())
+            .with_context(|| format!("Failed to create strategy {}", name))?;
+        let report = engine.run(strategy.as_mut(), data.clone()).await;
+
+        if let Some(store) = &store {
+            let parameters = run_parameters(args, registry, name, &settings, &config);
+            let mut run = StoredRun::new(name, args.symbols.clone(), parameters, &report);
+            store.save(&mut run)?;
+            info!("Run recorded as {} in {:?}", run.id, store.root());
+        }
+        matrix.add(name, &report);
+    }
+
+    let output = match args.output.as_str() {
+        "json" => serde_json::to_string_pretty(&matrix)?,
+        _ => format!("{}  Seed: {}\n", matrix.render(), seed),
+    };
+    println!("{}", output);
+
+    if let Some(save_path) = &args.save {
+        std::fs::write(save_path, serde_json::to_string_pretty(&matrix)?)?;
+        info!("Results saved to {:?}", save_path);
+    }
+
+    Ok(())
+}
+
+/// Run one strategy on the data resampled to each `--sweep` timeframe and
+/// print which timeframe performs best.
+async fn run_sweep(
+    args: &BacktestArgs,
+    registry: &StrategyRegistry,
+    settings: &serde_json::Value,
+    mut config: BacktestConfig,
+    data: HashMap<String, Vec<Bar>>,
+) -> Result<()> {
+    if args.checkpoint.is_some() || args.resume_from.is_some() {
+        anyhow::bail!("--checkpoint and --resume-from cannot be combined with --sweep");
+    }
+
+    let base = config.timeframe;
+    let mut timeframes: Vec<Timeframe> = Vec::new();
+    for timeframe in &args.sweep {
+        if timeframe.as_secs() < base.as_secs() {
+            warn!(
+                "Skipping {}: shorter than the {} data it would be resampled from",
+                timeframe, base
+            );
+        } else if !timeframes.contains(timeframe) {
+            timeframes.push(*timeframe);
+        }
+    }
+    if timeframes.is_empty() {
+        anyhow::bail!(
+            "No sweep timeframe is at least as long as the data ({})",
+            base
+        );
+    }
+
+    // One seed for every run so stochastic models treat them alike
+    let seed = *config
+        .seed
+        .get_or_insert_with(|| SimulationRng::from_entropy().seed());
+    let store = if args.no_store {
+        None
+    } else {
+        Some(ResultsStore::open(&args.results_dir)?)
+    };
+
+    let settings = super::strategy_settings(registry, settings, &args.strategy);
+    let mut sweep = TimeframeSweep::new(&args.strategy);
+    for timeframe in timeframes {
+        info!("Backtesting {} at {}", args.strategy, timeframe);
+        let resampled: HashMap<String, Vec<Bar>> = if timeframe == base {
+            data.clone()
+        } else {
+            data.iter()
+                .map(|(symbol, bars)| (symbol.clone(), resample_bars(bars, timeframe)))
+                .collect()
+        };
+
+        let run_config = BacktestConfig {
+            timeframe,
+            ..config.clone()
+        };
+        let mut strategy = registry
+            .create_with_overrides(&args.strategy, settings.clone(), args.symbols.clone())
+            .context("Failed to create strategy")?;
+        let report = BacktestEngine::new(run_config.clone())
+            .run(strategy.as_mut(), resampled)
+            .await;
+
+        if let Some(store) = &store {
+            let mut parameters =
+                run_parameters(args, registry, &args.strategy, &settings, &run_config);
+            parameters["timeframe"] = timeframe.to_string().into();
+            let mut run = StoredRun::new(&args.strategy, args.symbols.clone(), parameters, &report);
+            store.save(&mut run)?;
+            info!("Run recorded as {} in {:?}", run.id, store.root());
+        }
+        sweep.add(timeframe, &report);
+    }
+
+    let output = match args.output.as_str() {
+        "json" => serde_json::to_string_pretty(&sweep)?,
+        _ => format!("{}  Seed: {}\n", sweep.render(), seed),
+    };
+    println!("{}", output);
+
+    if let Some(save_path) = &args.save {
+        std::fs::write(save_path, serde_json::to_string_pretty(&sweep)?)?;
+        info!("Results saved to {:?}", save_path);
+    }
+
     Ok(())
 }
 
-async fn load_data_from_csv(
+pub(super) async fn load_data_from_csv(
     path: &Path,
     symbols: &[String],
-) -> Result<HashMap<String, Vec<trading_core::types::Bar>>> {
+    timeframe: Timeframe,
+) -> Result<HashMap<String, Vec<Bar>>> {
     let mut data = HashMap::new();
 
     // If path is a file, load it for the first symbol
@@ -81,29 +513,15 @@ async fn load_data_from_csv(
             .first()
             .cloned()
             .unwrap_or_else(|| "DATA".to_string());
-        let bars = source
-            .load_all(&symbol, trading_core::types::Timeframe::Daily)
-            .await?;
+        let bars = source.load_all(&symbol, timeframe).await?;
         data.insert(symbol, bars);
     } else {
         // If path is a directory, look for files named {symbol}.csv or {symbol}_daily.csv
         for symbol in symbols {
-            let lower = symbol.to_lowercase();
-            let candidates = [
-                path.join(format!("{}.csv", symbol)),
-                path.join(format!("{}.csv", lower)),
-                path.join(format!("{}_daily.csv", symbol)),
-                path.join(format!("{}_daily.csv", lower)),
-            ];
-            for file_path in &candidates {
-                if file_path.exists() {
-                    let source = CsvDataSource::new(file_path.to_str().unwrap())?;
-                    let bars = source
-                        .load_all(symbol, trading_core::types::Timeframe::Daily)
-                        .await?;
-                    data.insert(symbol.clone(), bars);
-                    break;
-                }
+            if let Some(file_path) = CsvDataSource::find(path, symbol) {
+                let source = CsvDataSource::new(file_path.to_str().unwrap())?;
+                let bars = source.load_all(symbol, timeframe).await?;
+                data.insert(symbol.clone(), bars);
             }
         }
     }
@@ -0,0 +1,43 @@
+//! Compare stored backtest runs command.
+
+use anyhow::Result;
+use trading_backtest::{ResultsStore, RunComparison};
+
+use crate::cli::CompareArgs;
+
+pub async fn run(args: CompareArgs) -> Result<()> {
+    let store = ResultsStore::open(&args.results_dir)?;
+
+    if args.list {
+        let runs = store.list()?;
+        if runs.is_empty() {
+            println!("No runs stored in {}", store.root().display());
+        }
+        for run in runs {
+            println!(
+                "  {:<28} {:<16} {:<20} return {:>8.2}%  sharpe {:>6.2}  trades {}",
+                run.id,
+                run.strategy,
+                run.symbols.join(","),
+                run.metrics.total_return_pct,
+                run.metrics.sharpe_ratio,
+                run.metrics.total_trades
+            );
+        }
+        return Ok(());
+    }
+
+    if args.runs.len() < 2 {
+        anyhow::bail!("Provide at least two run ids to compare (use --list to see stored runs)");
+    }
+
+    let runs = args
+        .runs
+        .iter()
+        .map(|id| store.load(id))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    println!("{}", RunComparison::new(runs).render());
+
+    Ok(())
+}
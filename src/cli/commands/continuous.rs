@@ -0,0 +1,49 @@
+//! Continuous futures series command.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+
+use trading_data::{continuous_series, write_csv, CsvDataSource};
+
+use crate::cli::ContinuousArgs;
+
+pub async fn run(args: ContinuousArgs) -> Result<()> {
+    let mut contracts = Vec::new();
+    for path in &args.contracts {
+        let symbol = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .with_context(|| format!("No contract name in {:?}", path))?
+            .to_uppercase();
+        let source = CsvDataSource::new(&path.to_string_lossy())
+            .with_context(|| format!("Contract file not found: {:?}", path))?;
+        let contract = source
+            .load_contract(&symbol)
+            .with_context(|| format!("Failed to load {:?}", path))?;
+        contracts.push(contract);
+    }
+
+    let series = continuous_series(contracts, args.roll, args.adjust)?;
+    println!(
+        "{} rolls by {}, {} back-adjustment:",
+        series.rolls.len(),
+        args.roll,
+        args.adjust
+    );
+    for roll in &series.rolls {
+        let day = DateTime::<Utc>::from_timestamp_millis(roll.timestamp)
+            .map(|dt| dt.format("%Y-%m-%d").to_string())
+            .unwrap_or_default();
+        println!(
+            "  {}  {:>8} -> {:<8} gap {:+.2}",
+            day, roll.from, roll.to, roll.gap
+        );
+    }
+
+    std::fs::create_dir_all(&args.out)
+        .with_context(|| format!("Failed to create {:?}", args.out))?;
+    let path = args.out.join(format!("{}.csv", args.symbol.to_uppercase()));
+    write_csv(&path, &series.bars)?;
+    println!("{} bars -> {}", series.bars.len(), path.display());
+    Ok(())
+}
@@ -0,0 +1,93 @@
+//! Historical data download command.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use std::path::Path;
+use tracing::warn;
+
+use trading_broker::AlpacaConfig;
+use trading_data::{
+    AlpacaDownloader, BarDownloader, DownloadSource, PolygonDownloader, YahooDownloader,
+};
+
+use crate::cli::DownloadArgs;
+
+pub async fn run(args: DownloadArgs, config_path: &Path) -> Result<()> {
+    let start = day_start(&args.start)?;
+    let end = match &args.end {
+        Some(day) => day_start(day)? + chrono::Duration::days(1),
+        None => Utc::now(),
+    };
+    if start >= end {
+        bail!("--start must be before --end");
+    }
+
+    let downloader: Box<dyn BarDownloader> = match args.source {
+        DownloadSource::Yahoo => Box::new(YahooDownloader::new()),
+        DownloadSource::Polygon => {
+            let Some(key) = args.polygon_api_key else {
+                bail!("Polygon needs an API key: set POLYGON_API_KEY or pass --polygon-api-key");
+            };
+            Box::new(PolygonDownloader::new(key))
+        }
+        DownloadSource::Alpaca => {
+            // Same credentials as paper trading: the config file, then the
+            // environment
+            let config = if config_path.exists() {
+                let alpaca = trading_config::load_config(config_path)
+                    .context("Failed to load config file")?
+                    .alpaca;
+                AlpacaConfig::new(alpaca.api_key_env, alpaca.api_secret_env, alpaca.paper)
+            } else {
+                AlpacaConfig::from_env().context(
+                    "Alpaca needs credentials: set ALPACA_API_KEY and ALPACA_API_SECRET, or provide a config file",
+                )?
+            };
+            Box::new(AlpacaDownloader::new(config.api_key, config.api_secret))
+        }
+    };
+
+    println!(
+        "Downloading {} bars from {} ({} to {})...",
+        args.timeframe,
+        downloader.name(),
+        start.format("%Y-%m-%d"),
+        end.format("%Y-%m-%d %H:%M")
+    );
+    let mut failed = Vec::new();
+    for symbol in &args.symbols {
+        let bars = match downloader
+            .download(symbol, args.timeframe, start, end)
+            .await
+        {
+            Ok(bars) if bars.is_empty() => {
+                warn!("No {} bars for {} in the range", args.timeframe, symbol);
+                failed.push(symbol.as_str());
+                continue;
+            }
+            Ok(bars) => bars,
+            Err(e) => {
+                warn!("Failed to download {}: {}", symbol, e);
+                failed.push(symbol.as_str());
+                continue;
+            }
+        };
+        let path = args
+            .format
+            .save(&args.out, symbol, args.timeframe, &bars)
+            .with_context(|| format!("Failed to save {} bars", symbol))?;
+        println!("{:<8} {:>7} bars -> {}", symbol, bars.len(), path.display());
+    }
+
+    if !failed.is_empty() {
+        bail!("No data saved for {}", failed.join(", "));
+    }
+    Ok(())
+}
+
+/// Midnight UTC of a YYYY-MM-DD date.
+fn day_start(day: &str) -> Result<DateTime<Utc>> {
+    let date = NaiveDate::parse_from_str(day, "%Y-%m-%d")
+        .with_context(|| format!("Invalid date '{}', expected YYYY-MM-DD", day))?;
+    Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc())
+}
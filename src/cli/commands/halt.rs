@@ -0,0 +1,43 @@
+//! Manual kill switch command.
+
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+use trading_config::AppConfig;
+
+use crate::cli::HaltArgs;
+
+pub async fn run(args: HaltArgs, config_path: &Path) -> Result<()> {
+    let config = if config_path.exists() {
+        trading_config::load_config(config_path).context("Failed to load config file")?
+    } else {
+        AppConfig::default()
+    };
+    let Some(halt_file) = config.circuit_breaker.halt_file else {
+        bail!("No circuit_breaker.halt_file configured");
+    };
+
+    if args.clear {
+        if halt_file.exists() {
+            std::fs::remove_file(&halt_file)
+                .with_context(|| format!("Failed to remove {}", halt_file.display()))?;
+            println!("Removed {}", halt_file.display());
+            println!("Sessions already halted stay halted until restarted.");
+        } else {
+            println!("Not halted: {} does not exist", halt_file.display());
+        }
+        return Ok(());
+    }
+
+    if let Some(dir) = halt_file.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    std::fs::write(&halt_file, &args.reason)
+        .with_context(|| format!("Failed to write {}", halt_file.display()))?;
+    println!("Wrote {}", halt_file.display());
+    println!(
+        "Running sessions halt trading on their next poll: {}",
+        args.reason
+    );
+    Ok(())
+}
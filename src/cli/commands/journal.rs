@@ -0,0 +1,82 @@
+//! Trade journal query command.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, NaiveDate};
+use std::path::Path;
+use trading_config::AppConfig;
+use trading_runtime::{JournalEvent, JournalQuery, TradeJournal};
+
+use crate::cli::JournalArgs;
+
+pub async fn run(args: JournalArgs, config_path: &Path) -> Result<()> {
+    let path = match args.file {
+        Some(path) => path,
+        None if config_path.exists() => {
+            trading_config::load_config(config_path)
+                .context("Failed to load config file")?
+                .journal
+                .path
+        }
+        None => AppConfig::default().journal.path,
+    };
+    if let Some(kind) = &args.kind {
+        if !JournalEvent::KINDS.contains(&kind.as_str()) {
+            bail!(
+                "Unknown event type '{}' (expected one of: {})",
+                kind,
+                JournalEvent::KINDS.join(", ")
+            );
+        }
+    }
+
+    let query = JournalQuery {
+        symbol: args.symbol,
+        kind: args.kind,
+        since: args.since.as_deref().map(day_start).transpose()?,
+        until: args
+            .until
+            .as_deref()
+            .map(|day| day_start(day).map(|start| start + 86_400_000))
+            .transpose()?,
+    };
+    // Bars are only for replaying; list them when asked for
+    let show_bars = query.kind.is_some();
+    let entries: Vec<_> = TradeJournal::read(&path, &query)
+        .with_context(|| format!("Failed to read trade journal {}", path.display()))?
+        .into_iter()
+        .filter(|entry| show_bars || !matches!(entry.event, JournalEvent::Bar { .. }))
+        .collect();
+    let skip = args
+        .limit
+        .map_or(0, |limit| entries.len().saturating_sub(limit));
+
+    for entry in entries.iter().skip(skip) {
+        if args.json {
+            println!("{}", serde_json::to_string(entry)?);
+        } else {
+            let time = DateTime::from_timestamp_millis(entry.timestamp).unwrap_or_default();
+            println!(
+                "{}  {:<15} {:<16} {}",
+                time.format("%Y-%m-%d %H:%M:%S"),
+                entry.event.kind(),
+                entry.strategy,
+                entry.event
+            );
+        }
+    }
+    if entries.is_empty() && !args.json {
+        println!("No matching entries in {}", path.display());
+    }
+    Ok(())
+}
+
+/// Midnight UTC of a YYYY-MM-DD date, in Unix milliseconds.
+pub(super) fn day_start(day: &str) -> Result<i64> {
+    let date = NaiveDate::parse_from_str(day, "%Y-%m-%d")
+        .with_context(|| format!("Invalid date '{}', expected YYYY-MM-DD", day))?;
+    Ok(date
+        .and_hms_opt(0, 0, 0)
+        .unwrap_or_default()
+        .and_utc()
+        .timestamp_millis())
+}
@@ -1,21 +1,90 @@
 //! Live trading command implementation.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use std::path::Path;
+use std::sync::Arc;
 use tracing::info;
+use trading_broker::{AlpacaBroker, AlpacaConfig, DryRunBroker};
+use trading_core::traits::Broker;
 
 use crate::cli::LiveArgs;
 
-pub async fn run(args: LiveArgs, _config_path: &Path) -> Result<()> {
+pub async fn run(args: LiveArgs, config_path: &Path) -> Result<()> {
     info!("Live trading is not yet implemented");
-    info!("Strategy: {}", args.strategy);
-    info!("Symbols: {:?}", args.symbols);
     info!("Timeframe: {}", args.timeframe);
     info!("Dry run: {}", args.dry_run);
+    if args.daemon {
+        info!(
+            "Daemon: sessions start {} min before the open",
+            args.pre_open.as_secs() / 60
+        );
+    }
+    if args.hot_reload {
+        info!("Hot reload: strategy and risk settings are watched for changes");
+    }
 
-    println!("Live trading requires Alpaca API credentials.");
-    println!("Please set ALPACA_API_KEY and ALPACA_API_SECRET environment variables.");
-    println!("\nThis feature will be available in a future release.");
+    // Check the strategy settings now rather than once connected
+    let registry = super::strategy_registry()?;
+    let strategy = match &args.portfolio {
+        Some(path) => {
+            let portfolio = super::portfolio_strategy(&registry, path)?;
+            info!("Portfolio: {}", path.display());
+            info!("Symbols: {:?}", portfolio.symbols);
+            if let Some(allocation) = &portfolio.allocation {
+                info!("Allocation: {}", allocation);
+            }
+            portfolio.strategy
+        }
+        None => {
+            info!("Strategy: {}", args.strategy);
+            info!("Symbols: {:?}", args.symbols);
+            let settings = super::load_strategy_settings(args.strategy_config.as_deref())?;
+            registry
+                .create_with_overrides(
+                    &args.strategy,
+                    super::strategy_settings(&registry, &settings, &args.strategy),
+                    args.symbols.clone(),
+                )
+                .context("Failed to create strategy")?
+        }
+    };
+    info!("Strategy initialized: {}", strategy.name());
+
+    // Credentials from the config file, then the environment
+    let config = if config_path.exists() {
+        let alpaca = trading_config::load_config(config_path)
+            .context("Failed to load config file")?
+            .alpaca;
+        Some(AlpacaConfig::new(
+            alpaca.api_key_env,
+            alpaca.api_secret_env,
+            alpaca.paper,
+        ))
+    } else {
+        AlpacaConfig::from_env().ok()
+    };
+    let Some(config) = config else {
+        println!("Live trading requires Alpaca API credentials.");
+        println!("Please set ALPACA_API_KEY and ALPACA_API_SECRET environment variables.");
+        println!("\nThis feature will be available in a future release.");
+        return Ok(());
+    };
+
+    // With --dry-run every order, cancel and close is logged, not sent
+    let alpaca = AlpacaBroker::new(config).context("Failed to create Alpaca broker")?;
+    let broker: Arc<dyn Broker> = if args.dry_run {
+        Arc::new(DryRunBroker::new(alpaca))
+    } else {
+        Arc::new(alpaca)
+    };
+    let account = broker
+        .get_account()
+        .await
+        .context("Failed to connect to Alpaca API. Check your credentials.")?;
+    info!("Connected to {}", broker.name());
+    info!("Account equity: ${}", account.equity);
+
+    println!("\nThe live trading loop will be available in a future release.");
 
     Ok(())
 }
@@ -1,7 +1,109 @@
 //! CLI command implementations.
 
 pub mod backtest;
+pub mod compare;
+pub mod continuous;
+pub mod download;
+pub mod halt;
+pub mod journal;
 pub mod live;
+pub mod optimize;
 pub mod paper;
+pub mod replay;
+pub mod report;
+pub mod risk;
+pub mod scaffold;
+pub mod size;
+pub mod soak;
 pub mod strategies;
 pub mod validate;
+
+use anyhow::Context;
+use std::path::Path;
+use trading_core::traits::Strategy;
+use trading_risk::AllocatorConfig;
+use trading_strategies::{MultiMember, MultiStrategyConfig, StrategyRegistry};
+
+/// Strategy registry with the built-in strategies, plus the plugins in
+/// `TRADING_PLUGIN_DIR` when built with the `plugins` feature.
+pub fn strategy_registry() -> anyhow::Result<StrategyRegistry> {
+    let registry = StrategyRegistry::new();
+    #[cfg(feature = "plugins")]
+    let registry = {
+        let mut registry = registry;
+        if let Some(dir) = std::env::var_os("TRADING_PLUGIN_DIR") {
+            // SAFETY: plugins come from a directory chosen by the operator
+            let names = unsafe { registry.load_plugins(std::path::Path::new(&dir)) }?;
+            tracing::info!("Loaded strategy plugins: {}", names.join(", "));
+        }
+        registry
+    };
+    Ok(registry)
+}
+
+/// Strategy settings from a `--strategy-config` file (TOML, or JSON for
+/// `.json` files), or `Null` without one.
+pub fn load_strategy_settings(path: Option<&Path>) -> anyhow::Result<serde_json::Value> {
+    let Some(path) = path else {
+        return Ok(serde_json::Value::Null);
+    };
+    trading_config::load_strategy_config(path)
+        .map_err(|e| anyhow::anyhow!("Failed to load strategy config {}: {}", path.display(), e))
+}
+
+/// The part of a settings file for `strategy`. A file whose top-level keys
+/// name strategies (`[ma_crossover]`, `[rsi]`, ...) configures each of them
+/// separately, and strategies without a table keep their defaults;
+/// otherwise the whole file is the strategy's settings.
+pub fn strategy_settings(
+    registry: &StrategyRegistry,
+    settings: &serde_json::Value,
+    strategy: &str,
+) -> serde_json::Value {
+    match settings.as_object() {
+        Some(tables) if tables.keys().any(|key| registry.get(key).is_some()) => {
+            tables.get(strategy).cloned().unwrap_or_default()
+        }
+        _ => settings.clone(),
+    }
+}
+
+/// A portfolio file's strategy instances, run as one multi-strategy.
+pub struct PortfolioStrategy {
+    pub strategy: Box<dyn Strategy>,
+    /// Every symbol traded
+    pub symbols: Vec<String>,
+    /// Fixed allocation of the instances' shares, if they have them
+    pub allocation: Option<AllocatorConfig>,
+}
+
+/// Load a portfolio file and create its strategy.
+pub fn portfolio_strategy(
+    registry: &StrategyRegistry,
+    path: &Path,
+) -> anyhow::Result<PortfolioStrategy> {
+    let portfolio = trading_config::load_portfolio_config(path)
+        .map_err(|e| anyhow::anyhow!("Failed to load portfolio {}: {}", path.display(), e))?;
+    let symbols = portfolio.symbols();
+    let config = MultiStrategyConfig {
+        symbols: symbols.clone(),
+        members: portfolio
+            .strategies
+            .iter()
+            .map(|instance| MultiMember {
+                strategy: instance.strategy.clone(),
+                config: instance.params.clone(),
+                name: Some(instance.label().to_string()),
+                symbols: instance.symbols.clone(),
+            })
+            .collect(),
+    };
+    let strategy = registry
+        .create("multi", serde_json::to_value(config)?, symbols.clone())
+        .context("Failed to create portfolio strategies")?;
+    Ok(PortfolioStrategy {
+        strategy,
+        symbols,
+        allocation: portfolio.allocation(),
+    })
+}
@@ -0,0 +1,121 @@
+//! Parameter optimization command.
+
+use anyhow::{bail, Context, Result};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::path::Path;
+use tracing::info;
+use trading_backtest::{
+    BacktestConfig, FoldScheme, ParameterGrid, ParameterOptimizer, SimulationRng,
+};
+
+use super::backtest::load_data_from_csv;
+use crate::cli::OptimizeArgs;
+
+pub async fn run(args: OptimizeArgs, config_path: &Path) -> Result<()> {
+    let registry = super::strategy_registry()?;
+    if !registry.exists(&args.strategy) {
+        bail!("Unknown strategy: {}", args.strategy);
+    }
+    let settings = super::load_strategy_settings(args.strategy_config.as_deref())?;
+    let settings = match super::strategy_settings(&registry, &settings, &args.strategy) {
+        serde_json::Value::Null => serde_json::Map::new(),
+        serde_json::Value::Object(settings) => settings,
+        other => bail!("{} settings must be a table, got {}", args.strategy, other),
+    };
+
+    let grid = ParameterGrid::new(args.params.clone()).map_err(anyhow::Error::msg)?;
+    // Catch misspelt settings before running anything
+    let defaults = registry
+        .resolve_config(&args.strategy, serde_json::Value::Null)
+        .context("Failed to resolve strategy settings")?;
+    if let Some(unknown) = grid.ranges.iter().find(|r| defaults.get(&r.name).is_none()) {
+        bail!("{} has no setting {}", args.strategy, unknown.name);
+    }
+    if args.folds == FoldScheme::WalkForward && args.windows == 0 {
+        bail!("--windows must be at least 1");
+    }
+
+    if !args.data.exists() {
+        bail!(
+            "Data path '{}' does not exist. Provide a CSV file or directory containing CSV files (e.g. --data ./data)",
+            args.data.display()
+        );
+    }
+    let data = load_data_from_csv(&args.data, &args.symbols, args.timeframe).await?;
+
+    let mut risk_config = args.risk_preset.unwrap_or_default().config();
+    let instruments = if config_path.exists() {
+        let config =
+            trading_config::load_config(config_path).context("Failed to load config file")?;
+        risk_config.take_profit = config.risk.take_profit;
+        risk_config.time_exit = config.risk.time_exit.unwrap_or_default();
+        risk_config.allocation = config.risk.allocation;
+        config.instruments
+    } else {
+        HashMap::new()
+    };
+    // One seed for every run so stochastic models treat them alike
+    let seed = args
+        .seed
+        .unwrap_or_else(|| SimulationRng::from_entropy().seed());
+    let config = BacktestConfig {
+        initial_capital: Decimal::try_from(args.capital).unwrap_or_default(),
+        risk_config,
+        seed: Some(seed),
+        timeframe: args.timeframe,
+        instruments,
+        ..BacktestConfig::default()
+    };
+
+    let runs = match args.folds {
+        FoldScheme::None => grid.len(),
+        FoldScheme::WalkForward => grid.len() * (args.windows + 2),
+    };
+    info!(
+        "Optimizing {} over {} parameter sets ({} backtests)",
+        args.strategy,
+        grid.len(),
+        runs
+    );
+    let report = ParameterOptimizer::new(grid)
+        .with_objective(args.objective)
+        .with_folds(args.folds, args.windows)
+        .run(&args.strategy, &config, &data, |params| {
+            let mut overrides = settings.clone();
+            overrides.extend(params.clone());
+            registry.create_with_overrides(
+                &args.strategy,
+                serde_json::Value::Object(overrides),
+                args.symbols.clone(),
+            )
+        })
+        .await
+        .context("Failed to create strategy")?;
+
+    let output = match args.output.as_str() {
+        "json" => serde_json::to_string_pretty(&report)?,
+        _ => format!("{}  Seed: {}\n", report.render(args.top), seed),
+    };
+    println!("{}", output);
+
+    if let Some(path) = &args.write_config {
+        let Some(best) = report.best() else {
+            bail!("No parameter set to write");
+        };
+        let mut overrides = settings;
+        overrides.extend(best.params.clone());
+        let mut config = registry
+            .resolve_config(&args.strategy, serde_json::Value::Object(overrides))
+            .context("Failed to resolve strategy settings")?;
+        // Symbols are given on the command line
+        if let Some(config) = config.as_object_mut() {
+            config.remove("symbols");
+        }
+        trading_config::save_strategy_config(path, &config)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        info!("Best settings written to {:?}", path);
+    }
+
+    Ok(())
+}
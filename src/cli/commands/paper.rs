@@ -1,25 +1,61 @@
 //! Paper trading command implementation.
 
 use anyhow::{Context, Result};
+use async_trait::async_trait;
+use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
-use tokio::time::interval;
+use tokio::sync::mpsc::{self, UnboundedSender};
+use tokio::time::{interval, Interval, MissedTickBehavior};
 use tracing::{error, info, warn};
 
 use trading_broker::{AlpacaBroker, AlpacaConfig};
+use trading_config::{ConfigWatcher, RiskSettings, Severity};
 use trading_core::traits::Broker;
-use trading_core::types::{BarSeries, OrderRequest, Side, SignalType, Timeframe};
+use trading_core::types::{Bar, Timeframe};
+use trading_monitor::{
+    Dashboard, DashboardCommand, DashboardState, LogBuffer, Notification, Notifications,
+    PriceChart, StrategyStatus, Theme, WatchlistQuote,
+};
+use trading_risk::{CircuitBreakerStatus, RiskManager, SupervisorStatus};
+use trading_runtime::{DataFeed, OrderPipeline, Reload, StepOutcome, TradeJournal, TradingRuntime};
 use trading_strategies::StrategyRegistry;
 
 use crate::cli::PaperArgs;
 
-pub async fn run(args: PaperArgs, config_path: &Path) -> Result<()> {
+/// How often watched config files are checked for changes.
+const RELOAD_POLL: Duration = Duration::from_secs(2);
+
+pub async fn run(args: PaperArgs, config_path: &Path, log: LogBuffer) -> Result<()> {
     println!("Starting paper trading...");
-    println!("Strategy: {}", args.strategy);
-    println!("Symbols: {:?}", args.symbols);
+    match &args.portfolio {
+        Some(path) => println!("Portfolio: {}", path.display()),
+        None => {
+            println!("Strategy: {}", args.strategy);
+            println!("Symbols: {:?}", args.symbols);
+        }
+    }
     println!("Capital: ${}", args.capital);
     println!("Timeframe: {}", args.timeframe);
+    if args.dry_run {
+        println!("Dry run: strategy orders are logged, not sent");
+    }
+    if args.daemon {
+        println!(
+            "Daemon: trading each session from {} min before the open",
+            args.pre_open.as_secs() / 60
+        );
+    }
+    if args.hot_reload {
+        println!("Hot reload: strategy and risk changes apply at the next bar");
+    }
+    #[cfg(feature = "grpc")]
+    if let Some(addr) = args.grpc {
+        println!("gRPC: serving on {}", addr);
+    }
     println!();
 
     // Parse timeframe
@@ -28,10 +64,14 @@ pub async fn run(args: PaperArgs, config_path: &Path) -> Result<()> {
         .parse()
         .map_err(|e: String| anyhow::anyhow!(e))?;
 
+    let app_config = if config_path.exists() {
+        Some(trading_config::load_config(config_path).context("Failed to load config file")?)
+    } else {
+        None
+    };
+
     // Load Alpaca credentials: try config file first, then environment variables
-    let config = if config_path.exists() {
-        let app_config =
-            trading_config::load_config(config_path).context("Failed to load config file")?;
+    let config = if let Some(app_config) = &app_config {
         let alpaca = &app_config.alpaca;
         // The config fields contain the actual keys (not env var names)
         AlpacaConfig::new(
@@ -48,7 +88,7 @@ pub async fn run(args: PaperArgs, config_path: &Path) -> Result<()> {
         warn!("Running in LIVE mode! Set ALPACA_PAPER=true for paper trading.");
     }
 
-    let broker = AlpacaBroker::new(config).context("Failed to create Alpaca broker")?;
+    let broker = Arc::new(AlpacaBroker::new(config).context("Failed to create Alpaca broker")?);
 
     // Verify connection
     let account = broker
@@ -62,33 +102,162 @@ pub async fn run(args: PaperArgs, config_path: &Path) -> Result<()> {
     println!();
 
     // Check market status
-    let market_open = broker
-        .is_market_open()
+    let clock = broker
+        .market_clock()
         .await
         .context("Failed to check market status")?;
 
-    if !market_open {
+    if !clock.is_open {
         println!("Note: Market is currently CLOSED. Orders will be queued.");
     } else {
         println!("Market is OPEN.");
     }
     println!();
 
-    // Create strategy
-    let registry = StrategyRegistry::new();
-    let symbols: Vec<String> = args.symbols.clone();
+    // A daemon trades until the close, then waits for the next session
+    let mut closes_at = if args.daemon {
+        let Some(next_close) = clock.next_close else {
+            anyhow::bail!("{} doesn't report market hours", broker.name());
+        };
+        Some(if clock.is_open {
+            next_close
+        } else {
+            chrono::Utc::now()
+        })
+    } else {
+        None
+    };
 
-    let mut strategy = registry
-        .create_default(&args.strategy, symbols.clone())
-        .context("Failed to create strategy")?;
+    // Create strategy
+    let registry = super::strategy_registry()?;
+    let (strategy, symbols, allocation) = match &args.portfolio {
+        Some(path) => {
+            let portfolio = super::portfolio_strategy(&registry, path)?;
+            (portfolio.strategy, portfolio.symbols, portfolio.allocation)
+        }
+        None => {
+            let settings = super::load_strategy_settings(args.strategy_config.as_deref())?;
+            let strategy = registry
+                .create_with_overrides(
+                    &args.strategy,
+                    super::strategy_settings(&registry, &settings, &args.strategy),
+                    args.symbols.clone(),
+                )
+                .context("Failed to create strategy")?;
+            (strategy, args.symbols.clone(), None)
+        }
+    };
 
     info!("Strategy initialized: {}", strategy.name());
+    if args.portfolio.is_some() {
+        println!("Symbols: {:?}", symbols);
+    }
+    let warmup_period = strategy.warmup_period();
 
-    // Initialize bar series for each symbol
-    let mut series_map: std::collections::HashMap<String, BarSeries> = symbols
-        .iter()
-        .map(|s| (s.clone(), BarSeries::new(s.clone(), timeframe)))
-        .collect();
+    // Traded symbols, plus the dashboard watchlist when it is shown
+    let mut watched = symbols.clone();
+    if args.dashboard {
+        for symbol in app_config.iter().flat_map(|c| &c.dashboard.watchlist) {
+            if !watched.contains(symbol) {
+                watched.push(symbol.clone());
+            }
+        }
+    }
+
+    // Resolve the dashboard theme up front so a bad color fails fast
+    let dashboard_settings = app_config
+        .as_ref()
+        .map(|c| c.dashboard.clone())
+        .unwrap_or_default();
+    let dashboard = Dashboard::new(dashboard_settings.refresh_ms)
+        .with_theme(Theme::from_config(&dashboard_settings.theme).map_err(anyhow::Error::msg)?)
+        .with_layout(dashboard_settings.layout);
+
+    // Likewise the web dashboard's address
+    #[cfg(feature = "web")]
+    let web = if args.web {
+        let web = app_config
+            .as_ref()
+            .map(|c| c.web.clone())
+            .unwrap_or_default();
+        let addr: std::net::SocketAddr = web
+            .bind
+            .parse()
+            .with_context(|| format!("Invalid web bind address '{}'", web.bind))?;
+        Some((addr, web.refresh_ms))
+    } else {
+        None
+    };
+
+    let notifications = match &app_config {
+        Some(config) => Notifications::from_config(&config.notifications)
+            .context("Failed to set up notifications")?,
+        None => Notifications::default(),
+    };
+    if !notifications.is_empty() {
+        println!("Notifications: {}", notifications.names().join(", "));
+    }
+
+    let journal_settings = app_config
+        .as_ref()
+        .map(|c| c.journal.clone())
+        .unwrap_or_default();
+
+    // Signals go through the same risk pipeline as backtests
+    let (risk_settings, supervisor_config, breaker_config, session_config, instruments) =
+        app_config
+            .map(|c| {
+                (
+                    c.risk,
+                    c.supervisor,
+                    c.circuit_breaker,
+                    c.session,
+                    c.instruments,
+                )
+            })
+            .unwrap_or_default();
+    let halt_file = breaker_config.halt_file.clone();
+    let mut risk_config = risk_settings.resolve(args.risk_preset);
+    // The portfolio's shares take the place of [risk.allocation]
+    if let Some(allocation) = allocation {
+        println!("Allocation: {}", allocation);
+        risk_config.allocation = Some(allocation);
+    }
+    let risk_manager =
+        RiskManager::new(risk_config).with_periods_per_year(timeframe.periods_per_year());
+    let pipeline = OrderPipeline::new(risk_manager)
+        .with_supervisor(supervisor_config)
+        .with_circuit_breaker(breaker_config)
+        .with_instruments(instruments);
+    println!("Session: {}", session_config);
+    let mut runtime = TradingRuntime::new(strategy, broker.clone(), pipeline, timeframe)
+        .with_session(session_config)
+        .with_dry_run(args.dry_run);
+    if journal_settings.enabled {
+        let journal = TradeJournal::open(&journal_settings.path).with_context(|| {
+            format!(
+                "Failed to open trade journal {}",
+                journal_settings.path.display()
+            )
+        })?;
+        println!("Journal: {}", journal.path().display());
+        runtime = runtime.with_journal(journal);
+    }
+
+    // Files whose changes are picked up without a restart
+    let mut watcher = args.hot_reload.then(|| {
+        let paths = [
+            Some(config_path.to_path_buf()).filter(|p| p.exists()),
+            args.strategy_config.clone(),
+            args.portfolio.clone(),
+        ];
+        ConfigWatcher::new(paths.into_iter().flatten())
+    });
+    for path in watcher.iter().flat_map(ConfigWatcher::paths) {
+        info!("Watching {} for changes", path.display());
+    }
+    let mut reload_check = interval(RELOAD_POLL);
+    reload_check.set_missed_tick_behavior(MissedTickBehavior::Skip);
 
     // Calculate polling interval based on timeframe
     let poll_interval = match timeframe {
@@ -105,7 +274,6 @@ pub async fn run(args: PaperArgs, config_path: &Path) -> Result<()> {
     println!("Loading historical data for warmup...");
 
     // Load historical bars for warmup
-    let warmup_period = strategy.warmup_period();
     let timeframe_str = match timeframe {
         Timeframe::Minute1 => "1Min",
         Timeframe::Minute5 => "5Min",
@@ -121,7 +289,7 @@ pub async fn run(args: PaperArgs, config_path: &Path) -> Result<()> {
     let end = chrono::Utc::now();
     let start = end - chrono::Duration::days(30); // Get 30 days of data
 
-    for symbol in &symbols {
+    for symbol in &watched {
         match broker
             .get_bars(
                 symbol,
@@ -134,11 +302,7 @@ pub async fn run(args: PaperArgs, config_path: &Path) -> Result<()> {
         {
             Ok(bars) => {
                 info!("Loaded {} bars for {}", bars.len(), symbol);
-                if let Some(series) = series_map.get_mut(symbol) {
-                    for bar in bars {
-                        series.push(bar);
-                    }
-                }
+                runtime.warmup(symbol, bars);
             }
             Err(e) => {
                 warn!("Failed to load historical data for {}: {}", symbol, e);
@@ -147,108 +311,733 @@ pub async fn run(args: PaperArgs, config_path: &Path) -> Result<()> {
     }
 
     println!("Warmup complete. Starting trading loop...");
-    println!("Press Ctrl+C to stop.");
+    println!("Press Ctrl+C (or send SIGTERM) to cancel open orders and stop.");
     println!();
 
+    // The dashboard runs on its own thread and sends manual orders back
+    let (command_tx, mut commands) = mpsc::unbounded_channel();
+    let dashboard_state = Arc::new(Mutex::new(DashboardState {
+        strategy_name: runtime.strategy().name().to_string(),
+        dry_run: runtime.is_dry_run(),
+        watchlist: watched.clone(),
+        log,
+        ..Default::default()
+    }));
+    #[cfg(feature = "web")]
+    if let Some((addr, refresh_ms)) = web {
+        let server = trading_web::WebServer::new(dashboard_state.clone(), refresh_ms);
+        tokio::spawn(async move {
+            if let Err(e) = server.serve(addr).await {
+                error!("Web dashboard stopped: {}", e);
+            }
+        });
+    }
+    // gRPC clients get a channel of their own, so quitting the dashboard
+    // still ends the session
+    let (remote_tx, mut remote) = mpsc::unbounded_channel();
+    #[cfg(feature = "grpc")]
+    if let Some(addr) = args.grpc {
+        let service =
+            trading_server::TradingService::new(trading_server::BacktestJobs::new("data"))
+                .with_live(trading_server::LiveSession {
+                    state: dashboard_state.clone(),
+                    commands: remote_tx.clone(),
+                });
+        tokio::spawn(async move {
+            if let Err(e) = service.serve(addr).await {
+                error!("gRPC server stopped: {}", e);
+            }
+        });
+    }
+    let _remote_tx = remote_tx;
+    let _command_tx = if args.dashboard {
+        spawn_dashboard(dashboard, dashboard_state.clone(), command_tx);
+        None
+    } else {
+        Some(command_tx)
+    };
+
     // Trading loop
-    let mut interval_timer = interval(poll_interval);
+    let mut feed = QuotePollingFeed::new(broker.clone(), watched.clone(), poll_interval);
+    let shutdown = shutdown_signal();
+    tokio::pin!(shutdown);
     let mut iteration = 0;
+    // Whether a dashboard or gRPC client reads the shared state
+    #[cfg(feature = "web")]
+    let monitored = args.dashboard || args.web;
+    #[cfg(not(feature = "web"))]
+    let monitored = args.dashboard;
+    #[cfg(feature = "grpc")]
+    let monitored = monitored || args.grpc.is_some();
+    let mut today = chrono::Utc::now().date_naive();
+    // Last notified supervisor halt, fill and session
+    let mut disabled = disabled_reason(&runtime);
+    let mut last_fill = runtime.recent_fills().next().map(|o| o.id);
+    let mut last_session = runtime.last_session().map(|s| s.date);
 
     loop {
-        interval_timer.tick().await;
-        iteration += 1;
+        tokio::select! {
+            batch = feed.next_bars() => {
+                let Some(batch) = batch else {
+                    break;
+                };
+                iteration += 1;
 
-        // Get latest quotes
-        let prices = match broker.get_latest_quotes(&symbols).await {
-            Ok(p) => p,
-            Err(e) => {
-                error!("Failed to get quotes: {}", e);
-                continue;
-            }
-        };
+                // `trading halt` trips the circuit breaker from outside
+                if let Some(path) = halt_file.as_deref().filter(|p| p.exists()) {
+                    let reason = std::fs::read_to_string(path).unwrap_or_default();
+                    let reason = match reason.trim() {
+                        "" => format!("{} exists", path.display()),
+                        reason => reason.to_string(),
+                    };
+                    runtime.trip_circuit_breaker(reason).await;
+                }
 
-        // Update series with latest prices and check for signals
-        for symbol in &symbols {
-            if let (Some(series), Some(&price)) = (series_map.get_mut(symbol), prices.get(symbol)) {
-                // Create a synthetic bar from the latest quote
-                let now = chrono::Utc::now().timestamp_millis();
-                let price_f64 = price.to_string().parse::<f64>().unwrap_or(0.0);
-                let bar = trading_core::types::Bar::new(
-                    now, price_f64, price_f64, price_f64, price_f64, 0.0,
-                );
-                series.push(bar);
+                // Supervise daily performance and data freshness
+                if let Err(e) = runtime
+                    .supervise(chrono::Utc::now().timestamp_millis())
+                    .await
+                {
+                    error!("Failed to update daily P&L: {}", e);
+                }
 
-                // Check for signals
-                if let Some(signal) = strategy.on_bar(series) {
-                    info!(
-                        "Signal: {:?} {} @ ${}",
-                        signal.signal_type, symbol, signal.price
-                    );
+                if chrono::Utc::now().date_naive() != today {
+                    today = chrono::Utc::now().date_naive();
+                    let mut state = dashboard_state.lock().unwrap();
+                    state.signals_today = 0;
+                    state.trades_today = 0;
+                }
 
-                    // Execute signal
-                    let result = match signal.signal_type {
-                        SignalType::Buy => {
-                            // Calculate position size (simplified: use 10% of buying power)
-                            let account = broker.get_account().await?;
-                            let position_value =
-                                account.buying_power * Decimal::from_str_exact("0.1").unwrap();
-                            let quantity = (position_value / price).round();
-
-                            if quantity > Decimal::ZERO {
-                                let request = OrderRequest::market(symbol, Side::Buy, quantity);
-                                broker.submit_order(request).await
-                            } else {
-                                continue;
-                            }
-                        }
-                        SignalType::Sell | SignalType::CloseLong => {
-                            // Close existing position
-                            if let Ok(Some(_pos)) = broker.get_position(symbol).await {
-                                broker.close_position(symbol).await
-                            } else {
-                                continue;
+                let timestamp = batch.first().map(|(_, bar)| bar.timestamp);
+                for (symbol, bar) in batch {
+                    // Watchlist-only symbols are quoted but never traded
+                    if !symbols.contains(&symbol) {
+                        runtime.warmup(&symbol, [bar]);
+                        continue;
+                    }
+                    let outcome = runtime.on_bar(&symbol, bar).await;
+                    report_outcome(
+                        &mut dashboard_state.lock().unwrap(),
+                        &notifications,
+                        &symbol, outcome);
+                }
+                if let Some(timestamp) = timestamp {
+                    let outcome = runtime.on_snapshot(timestamp).await;
+                    report_outcome(
+                        &mut dashboard_state.lock().unwrap(),
+                        &notifications,
+                        "snapshot", outcome);
+                }
+
+                update_halted(&mut dashboard_state.lock().unwrap(), &runtime, &notifications);
+
+                // Strategy disabled by the supervisor, new fills and the
+                // latest session's summary
+                let supervisor = disabled_reason(&runtime);
+                if let Some(reason) = supervisor.as_ref().filter(|_| disabled.is_none()) {
+                    notifications.send(Notification::risk_limit("strategy disabled", reason));
+                }
+                disabled = supervisor;
+                for order in runtime
+                    .recent_fills()
+                    .take_while(|o| Some(o.id) != last_fill)
+                    .collect::<Vec<_>>()
+                    .into_iter()
+                    .rev()
+                {
+                    notifications.send(Notification::fill(order));
+                }
+                last_fill = runtime.recent_fills().next().map(|o| o.id);
+                if let Some(summary) = runtime
+                    .last_session()
+                    .filter(|s| Some(s.date) != last_session)
+                {
+                    notifications.send(Notification::daily_summary(summary.to_string()));
+                    last_session = Some(summary.date);
+                }
+
+                if monitored {
+                    let mut state = dashboard_state.lock().unwrap();
+                    state.strategy_name = runtime.strategy().name().to_string();
+                    state.strategies = StrategyStatus::collect(runtime.strategy());
+                }
+                if args.dashboard {
+                    let mut state = dashboard_state.lock().unwrap();
+                    state.quotes = watched
+                        .iter()
+                        .filter_map(|symbol| watchlist_quote(&runtime, symbol))
+                        .collect();
+                    state.charts = watched
+                        .iter()
+                        .filter_map(|symbol| price_chart(&runtime, symbol))
+                        .collect();
+                }
+
+                // Refresh the dashboards every poll, print status every 10 iterations
+                if monitored || iteration % 10 == 0 {
+                    match broker.get_account().await {
+                        Ok(account) => {
+                            if !args.dashboard && iteration % 10 == 0 {
+                                println!(
+                                    "[{}] Equity: ${:.2} | Positions: {} | Blacklisted: {}",
+                                    chrono::Utc::now().format("%H:%M:%S"),
+                                    account.equity,
+                                    account.positions.len(),
+                                    runtime.pipeline().blacklist().len()
+                                );
                             }
-                        }
-                        SignalType::CloseShort => {
-                            if let Ok(Some(_pos)) = broker.get_position(symbol).await {
-                                broker.close_position(symbol).await
+                            // Fetched before locking, so the dashboard isn't held up
+                            let open_orders = if monitored {
+                                broker
+                                    .get_open_orders()
+                                    .await
+                                    .map_err(|e| warn!("Failed to get open orders: {}", e))
+                                    .ok()
                             } else {
-                                continue;
+                                None
+                            };
+                            let mut state = dashboard_state.lock().unwrap();
+                            if let Some(orders) = open_orders {
+                                state.open_orders = orders;
                             }
-                        }
-                        SignalType::Hold => continue,
-                    };
-
-                    match result {
-                        Ok(order) => {
-                            info!(
-                                "Order submitted: {} {} {} @ {:?}",
-                                order.side, order.quantity, order.symbol, order.limit_price
+                            state.daily_pnl = account.equity - runtime.day_start_equity();
+                            state.equity.push(
+                                chrono::Utc::now().timestamp_millis(),
+                                account.equity.to_f64().unwrap_or_default(),
                             );
+                            state.value_at_risk = runtime
+                                .pipeline()
+                                .risk_manager()
+                                .portfolio_var(&account, 0.95)
+                                .map(|var| {
+                                    let amount = |loss: f64| {
+                                        account.equity * Decimal::try_from(loss).unwrap_or_default()
+                                    };
+                                    (amount(var.historical_var), amount(var.historical_es))
+                                });
+                            state.portfolio = account;
+                            state.recent_fills = runtime.recent_fills().cloned().collect();
                         }
                         Err(e) => {
-                            error!("Failed to submit order: {}", e);
+                            error!("Failed to get account: {}", e);
                         }
                     }
                 }
             }
+            command = commands.recv() => {
+                // Channel closes when the dashboard is quit
+                let Some(command) = command else {
+                    break;
+                };
+                apply(command, &mut runtime, &dashboard_state, &notifications).await;
+            }
+            Some(command) = remote.recv() => {
+                apply(command, &mut runtime, &dashboard_state, &notifications).await;
+            }
+            _ = reload_check.tick(), if watcher.is_some() => {
+                let changed = watcher.as_mut().map(ConfigWatcher::changed).unwrap_or_default();
+                if changed.is_empty() {
+                    continue;
+                }
+                // Nothing switches unless all of it checks out
+                match reload(&args, config_path, &registry, &changed)
+                    .and_then(|reload| Ok(runtime.stage_reload(reload)?))
+                {
+                    Ok(()) => info!("Settings changed; switching at the next bar"),
+                    Err(e) => warn!("Ignoring changed settings: {:#}", e),
+                }
+            }
+            _ = sleep_until(closes_at), if closes_at.is_some() => {
+                // Wrap up the session before going quiet
+                if let Err(e) = runtime
+                    .supervise(chrono::Utc::now().timestamp_millis())
+                    .await
+                {
+                    error!("Failed to update daily P&L: {}", e);
+                }
+                info!("Market closed; waiting for the next session");
+                tokio::select! {
+                    next_close = next_session(broker.as_ref(), args.pre_open) => {
+                        info!("Session starting; trading until {}", next_close.format("%Y-%m-%d %H:%M UTC"));
+                        closes_at = Some(next_close);
+                        // Quotes were paused, not stale
+                        runtime
+                            .pipeline_mut()
+                            .record_market_data(chrono::Utc::now().timestamp_millis());
+                        feed.restart();
+                    }
+                    _ = &mut shutdown => {
+                        stop(&mut runtime).await;
+                        break;
+                    }
+                }
+            }
+            _ = &mut shutdown => {
+                stop(&mut runtime).await;
+                break;
+            }
         }
+    }
 
-        // Print status every 10 iterations
-        if iteration % 10 == 0 {
-            match broker.get_account().await {
-                Ok(account) => {
-                    println!(
-                        "[{}] Equity: ${:.2} | Positions: {}",
-                        chrono::Utc::now().format("%H:%M:%S"),
-                        account.equity,
-                        account.positions.len()
-                    );
+    Ok(())
+}
+
+/// Carry out a command from the dashboard or a remote client.
+async fn apply(
+    command: DashboardCommand,
+    runtime: &mut TradingRuntime,
+    state: &Mutex<DashboardState>,
+    notifications: &Notifications,
+) {
+    match command {
+        DashboardCommand::SubmitOrder(request) => {
+            let symbol = request.symbol.clone();
+            match runtime.submit_order(*request).await {
+                Ok(order) => info!(
+                    symbol = %symbol,
+                    "Manual {} {} {} submitted",
+                    order.side, order.quantity, order.symbol
+                ),
+                Err(e) => warn!(symbol = %symbol, "Manual order failed: {}", e),
+            }
+        }
+        DashboardCommand::Halt => {
+            if !runtime.trip_circuit_breaker("Kill switch pressed").await {
+                warn!("Circuit breaker already tripped");
+            }
+            update_halted(&mut state.lock().unwrap(), runtime, notifications);
+        }
+        DashboardCommand::EnableStrategy => {
+            if !runtime.enable_strategy() {
+                warn!("Strategy is not disabled");
+            }
+        }
+        DashboardCommand::TogglePause => {
+            runtime.set_paused(!runtime.is_paused());
+            state.lock().unwrap().paused = runtime.is_paused();
+        }
+        DashboardCommand::SetPaused(paused) => {
+            runtime.set_paused(paused);
+            state.lock().unwrap().paused = paused;
+        }
+        DashboardCommand::ToggleDryRun => {
+            runtime.set_dry_run(!runtime.is_dry_run());
+            state.lock().unwrap().dry_run = runtime.is_dry_run();
+        }
+        DashboardCommand::ClosePosition(symbol) => {
+            if let Err(e) = runtime.close_position(&symbol).await {
+                warn!(symbol = %symbol, "Failed to close {}: {}", symbol, e);
+            }
+        }
+        DashboardCommand::CancelAllOrders => {
+            if let Err(e) = runtime.cancel_all_orders().await {
+                warn!("Failed to cancel orders: {}", e);
+            }
+        }
+        DashboardCommand::Flatten => {
+            if let Err(e) = runtime.flatten().await {
+                warn!("Failed to flatten: {}", e);
+            }
+        }
+        // Replay controls; the trading dashboard never sends them
+        DashboardCommand::Step | DashboardCommand::Faster | DashboardCommand::Slower => {}
+    }
+}
+
+/// Strategy and risk settings re-read after `changed` files changed. The
+/// strategy is only recreated when its own settings changed, so it keeps
+/// its state otherwise.
+fn reload(
+    args: &PaperArgs,
+    config_path: &Path,
+    registry: &StrategyRegistry,
+    changed: &[PathBuf],
+) -> Result<Reload> {
+    let strategy_changed = changed.iter().any(|path| {
+        Some(path.as_path()) == args.strategy_config.as_deref()
+            || Some(path.as_path()) == args.portfolio.as_deref()
+    });
+    let (strategy, allocation) = match &args.portfolio {
+        Some(path) => {
+            let portfolio = super::portfolio_strategy(registry, path)?;
+            (
+                strategy_changed.then_some(portfolio.strategy),
+                portfolio.allocation,
+            )
+        }
+        None if strategy_changed => {
+            let settings = super::load_strategy_settings(args.strategy_config.as_deref())?;
+            let strategy = registry
+                .create_with_overrides(
+                    &args.strategy,
+                    super::strategy_settings(registry, &settings, &args.strategy),
+                    args.symbols.clone(),
+                )
+                .context("Invalid strategy settings")?;
+            (Some(strategy), None)
+        }
+        None => (None, None),
+    };
+
+    let settings = if config_path.exists() {
+        let report = trading_config::validate_config(config_path);
+        let errors: Vec<String> = report
+            .issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Error)
+            .map(ToString::to_string)
+            .collect();
+        if !errors.is_empty() {
+            anyhow::bail!("{}", errors.join("; "));
+        }
+        trading_config::load_config(config_path)
+            .context("Failed to load config file")?
+            .risk
+    } else {
+        RiskSettings::default()
+    };
+    let mut risk = settings.resolve(args.risk_preset);
+    if let Some(allocation) = allocation {
+        risk.allocation = Some(allocation);
+    }
+    Ok(Reload {
+        strategy,
+        risk: Some(risk),
+    })
+}
+
+/// Resolves on Ctrl+C, or on SIGTERM where there is one.
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = terminate.recv() => {}
                 }
-                Err(e) => {
-                    error!("Failed to get account: {}", e);
+            }
+            Err(e) => {
+                warn!("Failed to listen for SIGTERM: {}", e);
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+/// Cancel open orders and flush the journal before exiting.
+async fn stop(runtime: &mut TradingRuntime) {
+    info!("Shutting down");
+    if let Err(e) = runtime.shutdown().await {
+        error!("Failed to cancel open orders: {}", e);
+    }
+    if let Some(summary) = runtime.last_session() {
+        info!("Last session: {}", summary);
+    }
+}
+
+/// Sleep until `time`, or forever without one.
+async fn sleep_until(time: Option<chrono::DateTime<chrono::Utc>>) {
+    let Some(time) = time else {
+        return std::future::pending().await;
+    };
+    let wait = (time - chrono::Utc::now()).to_std().unwrap_or_default();
+    tokio::time::sleep(wait).await;
+}
+
+/// Wait until `lead` before the next session opens, or return at once if
+/// one is under way, and return when it closes.
+///
+/// Sleeps in steps of at most an hour, so a suspended machine or a
+/// changed calendar is noticed.
+async fn next_session(broker: &dyn Broker, lead: Duration) -> chrono::DateTime<chrono::Utc> {
+    const MAX_SLEEP: Duration = Duration::from_secs(3600);
+    const RETRY: Duration = Duration::from_secs(60);
+    let lead = chrono::Duration::from_std(lead).unwrap_or_default();
+    let mut announced = None;
+    loop {
+        let clock = match broker.market_clock().await {
+            Ok(clock) => clock,
+            Err(e) => {
+                warn!("Failed to check market hours: {}", e);
+                tokio::time::sleep(RETRY).await;
+                continue;
+            }
+        };
+        let now = chrono::Utc::now();
+        let (Some(next_open), Some(next_close)) = (clock.next_open, clock.next_close) else {
+            warn!("{} didn't report market hours", broker.name());
+            tokio::time::sleep(RETRY).await;
+            continue;
+        };
+        // Just after the close the clock can still read open
+        if next_close > now && (clock.is_open || next_open - lead <= now) {
+            return next_close;
+        }
+        let start = next_open - lead;
+        if announced != Some(start) {
+            info!(
+                "Next session opens at {}; starting at {}",
+                next_open.format("%Y-%m-%d %H:%M UTC"),
+                start.format("%Y-%m-%d %H:%M UTC")
+            );
+            announced = Some(start);
+        }
+        let wait = (start - now)
+            .to_std()
+            .unwrap_or(RETRY)
+            .clamp(RETRY, MAX_SLEEP);
+        tokio::time::sleep(wait).await;
+    }
+}
+
+/// Log a step outcome and count it on the dashboard, notifying risk
+/// rejections.
+fn report_outcome(
+    state: &mut DashboardState,
+    notifications: &Notifications,
+    symbol: &str,
+    outcome: StepOutcome,
+) {
+    match outcome {
+        StepOutcome::NoSignal => {}
+        StepOutcome::Submitted { signal, order } => {
+            state.signals_today += 1;
+            state.trades_today += 1;
+            info!(
+                symbol = %order.symbol,
+                "{} -> {} {} {}",
+                signal.signal_type, order.side, order.quantity, order.symbol
+            );
+        }
+        StepOutcome::Skipped { .. } => state.signals_today += 1,
+        StepOutcome::Rejected { signal, reason } => {
+            state.signals_today += 1;
+            warn!(symbol = %signal.symbol, "{} rejected: {}", signal.symbol, reason);
+            notifications.send(Notification::risk_limit(&signal.symbol, reason));
+        }
+        StepOutcome::Failed { signal, error } => {
+            state.signals_today += 1;
+            error!(symbol = %signal.symbol, "{} failed: {}", signal.symbol, error);
+        }
+        StepOutcome::OrdersSubmitted { orders } => {
+            state.signals_today += 1;
+            state.trades_today += orders.len();
+            for order in orders {
+                info!(
+                    symbol = %order.symbol,
+                    "strategy order -> {} {} {}",
+                    order.side, order.quantity, order.symbol
+                );
+            }
+        }
+        StepOutcome::OrdersNotSent { reason, .. } => {
+            state.signals_today += 1;
+            warn!(symbol = %symbol, "{} strategy orders not sent: {}", symbol, reason);
+        }
+        StepOutcome::OrdersFailed { submitted, error } => {
+            state.signals_today += 1;
+            state.trades_today += submitted.len();
+            error!(
+                symbol = %symbol,
+                "{} strategy orders failed after {} legs: {}",
+                symbol,
+                submitted.len(),
+                error
+            );
+        }
+    }
+}
+
+/// Why the circuit breaker halted trading, if it has.
+fn halted_reason(runtime: &TradingRuntime) -> Option<String> {
+    match runtime.pipeline().circuit_breaker()?.status() {
+        CircuitBreakerStatus::Armed => None,
+        CircuitBreakerStatus::Tripped { reason } => Some(reason.clone()),
+    }
+}
+
+/// Show the circuit breaker's state, notifying when it trips.
+fn update_halted(
+    state: &mut DashboardState,
+    runtime: &TradingRuntime,
+    notifications: &Notifications,
+) {
+    let halted = halted_reason(runtime);
+    if let Some(reason) = halted.as_ref().filter(|_| state.halted.is_none()) {
+        notifications.send(Notification::circuit_breaker(reason));
+    }
+    state.halted = halted;
+}
+
+/// Why the supervisor disabled the strategy, if it has.
+fn disabled_reason(runtime: &TradingRuntime) -> Option<String> {
+    match runtime.pipeline().supervisor()?.status() {
+        SupervisorStatus::Enabled => None,
+        SupervisorStatus::Disabled { reason } => Some(reason.clone()),
+    }
+}
+
+/// Run the TUI dashboard on a blocking thread.
+fn spawn_dashboard(
+    dashboard: Dashboard,
+    state: Arc<Mutex<DashboardState>>,
+    commands: UnboundedSender<DashboardCommand>,
+) {
+    tokio::task::spawn_blocking(move || {
+        let dashboard = dashboard.with_commands(commands);
+        if let Err(e) = dashboard.run(|| state.lock().unwrap().clone()) {
+            error!("Dashboard error: {}", e);
+        }
+    });
+}
+
+/// Latest price, change on the session and strategy readings for a symbol.
+fn watchlist_quote(runtime: &TradingRuntime, symbol: &str) -> Option<WatchlistQuote> {
+    let series = runtime.series(symbol)?;
+    let last = series.last()?;
+
+    // Previous session's close is the last bar dated before the latest one
+    let today = last.datetime().date_naive();
+    let prev_close = series
+        .bars()
+        .iter()
+        .rev()
+        .find(|bar| bar.datetime().date_naive() < today)
+        .map(|bar| bar.close)
+        .filter(|close| *close > 0.0);
+
+    let mut indicators: Vec<(String, f64)> = runtime
+        .indicators(symbol)
+        .map(|values| values.iter().map(|(k, v)| (k.clone(), *v)).collect())
+        .unwrap_or_default();
+    indicators.sort_by(|a, b| a.0.cmp(&b.0));
+
+    Some(WatchlistQuote {
+        symbol: symbol.to_string(),
+        last_price: last.close,
+        day_change_pct: prev_close.map(|prev| (last.close - prev) / prev * 100.0),
+        indicators,
+    })
+}
+
+/// Recent bars of a symbol with the strategy's readings, for the chart panel.
+fn price_chart(runtime: &TradingRuntime, symbol: &str) -> Option<PriceChart> {
+    const CHART_BARS: usize = 120;
+    let bars = runtime.series(symbol)?.bars();
+    let bars = bars
+        .iter()
+        .skip(bars.len().saturating_sub(CHART_BARS))
+        .copied()
+        .collect();
+
+    let mut indicators: BTreeMap<String, Vec<(i64, f64)>> = BTreeMap::new();
+    for (timestamp, values) in runtime.indicator_history(symbol) {
+        for (name, value) in values {
+            indicators
+                .entry(name.clone())
+                .or_default()
+                .push((*timestamp, *value));
+        }
+    }
+
+    Some(PriceChart {
+        symbol: symbol.to_string(),
+        bars,
+        indicators: indicators.into_iter().collect(),
+    })
+}
+
+/// Failed polls in a row before the feed backs off and reconnects.
+const RECONNECT_AFTER: u32 = 3;
+
+/// Longest wait between reconnection attempts.
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+/// Feed that polls latest quotes and turns them into synthetic bars.
+struct QuotePollingFeed {
+    broker: Arc<AlpacaBroker>,
+    symbols: Vec<String>,
+    period: Duration,
+    timer: Interval,
+    /// Failed polls in a row
+    failures: u32,
+}
+
+impl QuotePollingFeed {
+    fn new(broker: Arc<AlpacaBroker>, symbols: Vec<String>, period: Duration) -> Self {
+        let mut timer = interval(period);
+        timer.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        Self {
+            broker,
+            symbols,
+            period,
+            timer,
+            failures: 0,
+        }
+    }
+
+    /// Start polling afresh, e.g. after a pause: the next poll is now and
+    /// past failures are forgotten.
+    fn restart(&mut self) {
+        self.timer = interval(self.period);
+        self.timer
+            .set_missed_tick_behavior(MissedTickBehavior::Delay);
+        self.failures = 0;
+    }
+}
+
+#[async_trait]
+impl DataFeed for QuotePollingFeed {
+    async fn next_bars(&mut self) -> Option<Vec<(String, Bar)>> {
+        self.timer.tick().await;
+
+        // Get latest quotes; an empty batch keeps the loop supervising
+        // (and the circuit breaker timing stale data) while they fail
+        let prices = match self.broker.get_latest_quotes(&self.symbols).await {
+            Ok(p) => p,
+            Err(e) => {
+                error!("Failed to get quotes: {}", e);
+                self.failures += 1;
+                // Back off while the connection is down, doubling the wait
+                // each time, then poll afresh
+                if self.failures >= RECONNECT_AFTER {
+                    let backoff = self
+                        .period
+                        .saturating_mul(1 << (self.failures - RECONNECT_AFTER).min(8))
+                        .min(MAX_BACKOFF);
+                    warn!(
+                        "Quotes failed {} times in a row; reconnecting in {}s",
+                        self.failures,
+                        backoff.as_secs()
+                    );
+                    tokio::time::sleep(backoff).await;
+                    self.timer.reset_immediately();
                 }
+                return Some(Vec::new());
             }
+        };
+        if self.failures > 0 {
+            info!("Quotes restored after {} failed polls", self.failures);
+            self.failures = 0;
         }
+
+        // Create a synthetic bar from each latest quote
+        let now = chrono::Utc::now().timestamp_millis();
+        let batch = self
+            .symbols
+            .iter()
+            .filter_map(|symbol| {
+                let price = prices.get(symbol)?.to_string().parse::<f64>().ok()?;
+                Some((
+                    symbol.clone(),
+                    Bar::new(now, price, price, price, price, 0.0),
+                ))
+            })
+            .collect();
+        Some(batch)
     }
 }
@@ -0,0 +1,297 @@
+//! Session replay command: plays a recorded trade journal back through the
+//! TUI dashboard, bar by bar.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, NaiveDate};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{error, info};
+
+use trading_config::AppConfig;
+use trading_core::types::{OrderStatus, Portfolio};
+use trading_monitor::{
+    Dashboard, DashboardCommand, DashboardState, LogBuffer, LogEvent, LogLevel, PriceChart, Theme,
+    WatchlistQuote,
+};
+use trading_runtime::{JournalEntry, JournalEvent, JournalQuery, TradeJournal};
+
+use super::journal::day_start;
+use crate::cli::ReplayArgs;
+
+/// Bars kept per symbol for the chart panel, as in paper trading.
+const CHART_BARS: usize = 120;
+/// Fills kept for the order blotter.
+const RECENT_FILLS: usize = 50;
+/// Longest wait between two steps, so overnight gaps don't stall a replay.
+const MAX_GAP: Duration = Duration::from_secs(2);
+
+pub async fn run(args: ReplayArgs, config_path: &Path, log: LogBuffer) -> Result<()> {
+    if args.speed <= 0.0 {
+        bail!("--speed must be positive");
+    }
+    let app_config = if config_path.exists() {
+        trading_config::load_config(config_path).context("Failed to load config file")?
+    } else {
+        AppConfig::default()
+    };
+    let path = args.file.unwrap_or(app_config.journal.path);
+    let dashboard_settings = app_config.dashboard;
+    let dashboard = Dashboard::new(dashboard_settings.refresh_ms)
+        .with_theme(Theme::from_config(&dashboard_settings.theme).map_err(anyhow::Error::msg)?)
+        .with_layout(dashboard_settings.layout);
+
+    let query = JournalQuery {
+        since: args.since.as_deref().map(day_start).transpose()?,
+        until: args
+            .until
+            .as_deref()
+            .map(|day| day_start(day).map(|start| start + 86_400_000))
+            .transpose()?,
+        ..Default::default()
+    };
+    let entries = TradeJournal::read(&path, &query)
+        .with_context(|| format!("Failed to read trade journal {}", path.display()))?;
+    if !entries
+        .iter()
+        .any(|entry| matches!(entry.event, JournalEvent::Bar { .. }))
+    {
+        bail!("No bars recorded in {} to replay", path.display());
+    }
+    let steps = steps(entries);
+    let capital = Decimal::try_from(args.capital).context("Invalid --capital")?;
+
+    // The dashboard runs on its own thread and sends replay controls back
+    let (command_tx, mut commands) = mpsc::unbounded_channel();
+    let state = Arc::new(Mutex::new(DashboardState {
+        portfolio: Portfolio::new(capital),
+        paused: args.paused,
+        log,
+        ..Default::default()
+    }));
+    let shared = state.clone();
+    tokio::task::spawn_blocking(move || {
+        let dashboard = dashboard.with_replay_controls(command_tx);
+        if let Err(e) = dashboard.run(|| shared.lock().unwrap().clone()) {
+            error!("Dashboard error: {}", e);
+        }
+    });
+
+    let mut replay = SessionReplay::default();
+    let mut speed = args.speed;
+    let mut paused = args.paused;
+    let mut next = 0;
+    loop {
+        {
+            let mut state = state.lock().unwrap();
+            state.paused = paused;
+            state.replay = Some(replay.status(next, steps.len(), speed));
+        }
+        // Wait as long as the session did between the steps, sped up
+        let delay = match (next.checked_sub(1).map(|i| &steps[i]), steps.get(next)) {
+            (Some(previous), Some(step)) => {
+                let gap = (step[0].timestamp - previous[0].timestamp).max(0) as f64;
+                Duration::from_secs_f64(gap / 1000.0 / speed).min(MAX_GAP)
+            }
+            _ => Duration::ZERO,
+        };
+        let playing = !paused && next < steps.len();
+        tokio::select! {
+            _ = tokio::time::sleep(delay), if playing => {
+                replay.apply(&mut state.lock().unwrap(), &steps[next]);
+                next += 1;
+                if next == steps.len() {
+                    info!("Replay finished");
+                }
+            }
+            command = commands.recv() => {
+                // Channel closes when the dashboard is quit
+                let Some(command) = command else {
+                    break;
+                };
+                match command {
+                    DashboardCommand::TogglePause => paused = !paused,
+                    DashboardCommand::Step if paused && next < steps.len() => {
+                        replay.apply(&mut state.lock().unwrap(), &steps[next]);
+                        next += 1;
+                    }
+                    DashboardCommand::Faster => speed *= 2.0,
+                    DashboardCommand::Slower => speed /= 2.0,
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Split entries into steps of one bar time each: the bars fed at that time
+/// and everything the strategy did in response. Entries before the first
+/// bar make up their own step.
+fn steps(entries: Vec<JournalEntry>) -> Vec<Vec<JournalEntry>> {
+    let mut steps: Vec<Vec<JournalEntry>> = Vec::new();
+    let mut bar_time = None;
+    for entry in entries {
+        if let JournalEvent::Bar { bar, .. } = &entry.event {
+            if bar_time != Some(bar.timestamp) {
+                bar_time = Some(bar.timestamp);
+                steps.push(Vec::new());
+            }
+        }
+        match steps.last_mut() {
+            Some(step) => step.push(entry),
+            None => steps.push(vec![entry]),
+        }
+    }
+    steps
+}
+
+/// Rebuilds the dashboard state from journal entries. The portfolio starts
+/// from `--capital` and follows the journaled fills.
+#[derive(Default)]
+struct SessionReplay {
+    /// Journal time of the latest applied entry (ms)
+    time: Option<i64>,
+    /// Date being replayed, for the daily counters
+    day: Option<NaiveDate>,
+    day_start_equity: Decimal,
+}
+
+impl SessionReplay {
+    /// Apply a step's entries to the dashboard state.
+    fn apply(&mut self, state: &mut DashboardState, step: &[JournalEntry]) {
+        for entry in step {
+            self.apply_entry(state, entry);
+        }
+    }
+
+    fn apply_entry(&mut self, state: &mut DashboardState, entry: &JournalEntry) {
+        let time = DateTime::from_timestamp_millis(entry.timestamp).unwrap_or_default();
+        self.time = Some(entry.timestamp);
+        if self.day != Some(time.date_naive()) {
+            self.day = Some(time.date_naive());
+            self.day_start_equity = state.portfolio.equity;
+            state.signals_today = 0;
+            state.trades_today = 0;
+        }
+        if state.strategy_name != entry.strategy {
+            state.strategy_name = entry.strategy.clone();
+        }
+
+        let level = match &entry.event {
+            JournalEvent::Bar { symbol, bar } => {
+                let chart = match state.charts.iter().position(|c| c.symbol == *symbol) {
+                    Some(i) => &mut state.charts[i],
+                    None => {
+                        state.charts.push(PriceChart {
+                            symbol: symbol.clone(),
+                            bars: Vec::new(),
+                            indicators: Vec::new(),
+                        });
+                        state.charts.last_mut().unwrap()
+                    }
+                };
+                chart.bars.push(*bar);
+                if chart.bars.len() > CHART_BARS {
+                    chart.bars.remove(0);
+                }
+                let quote = quote(chart);
+                match state.quotes.iter_mut().find(|q| q.symbol == *symbol) {
+                    Some(existing) => *existing = quote,
+                    None => state.quotes.push(quote),
+                }
+                if !state.watchlist.contains(symbol) {
+                    state.watchlist.push(symbol.clone());
+                }
+
+                if let Some(position) = state.portfolio.get_position_mut(symbol) {
+                    position.update_price(Decimal::try_from(bar.close).unwrap_or_default());
+                }
+                state.portfolio.update_equity();
+                state.equity.push(
+                    entry.timestamp,
+                    state.portfolio.equity.to_f64().unwrap_or_default(),
+                );
+                None
+            }
+            JournalEvent::Signal { .. } | JournalEvent::StrategyOrders { .. } => {
+                state.signals_today += 1;
+                Some(LogLevel::Info)
+            }
+            JournalEvent::Approved { .. } | JournalEvent::Skipped { .. } => Some(LogLevel::Info),
+            JournalEvent::Rejected { .. } => Some(LogLevel::Warn),
+            JournalEvent::Order { order, .. } => {
+                state.trades_today += 1;
+                if order.status.is_active() {
+                    state.open_orders.retain(|o| o.id != order.id);
+                    state.open_orders.push(order.clone());
+                }
+                Some(LogLevel::Info)
+            }
+            JournalEvent::OrderFailed { .. } => Some(LogLevel::Error),
+            JournalEvent::Fill { order } => {
+                state.open_orders.retain(|o| o.id != order.id);
+                // An order canceled after a partial fill still moved the
+                // position
+                let mut filled = order.clone();
+                filled.status = OrderStatus::Filled;
+                state.portfolio.apply_order(&filled);
+                state.recent_fills.insert(0, order.clone());
+                state.recent_fills.truncate(RECENT_FILLS);
+                Some(LogLevel::Info)
+            }
+        };
+        state.daily_pnl = state.portfolio.equity - self.day_start_equity;
+
+        // Bars would drown out everything else in the log panel
+        if let Some(level) = level {
+            let mut event =
+                LogEvent::new(level, format!("{}: {}", entry.event.kind(), entry.event));
+            event.timestamp = time;
+            if let Some(symbol) = entry.event.symbols().first() {
+                event = event.with_symbol(*symbol);
+            }
+            state.log.push(event);
+        }
+    }
+
+    /// Header text: journal time, speed and progress.
+    fn status(&self, next: usize, steps: usize, speed: f64) -> String {
+        let time = self
+            .time
+            .and_then(DateTime::from_timestamp_millis)
+            .map_or("-".to_string(), |t| {
+                t.format("%Y-%m-%d %H:%M:%S").to_string()
+            });
+        let progress = if next == steps {
+            "finished".to_string()
+        } else {
+            format!("{}/{}", next, steps)
+        };
+        format!("{} x{} ({})", time, speed, progress)
+    }
+}
+
+/// Latest price and change on the previous session's close, from a chart's
+/// bars.
+fn quote(chart: &PriceChart) -> WatchlistQuote {
+    let last = chart.bars.last().map_or(0.0, |bar| bar.close);
+    let today = chart.bars.last().map(|bar| bar.datetime().date_naive());
+    let prev_close = chart
+        .bars
+        .iter()
+        .rev()
+        .find(|bar| Some(bar.datetime().date_naive()) < today)
+        .map(|bar| bar.close)
+        .filter(|close| *close > 0.0);
+    WatchlistQuote {
+        symbol: chart.symbol.clone(),
+        last_price: last,
+        day_change_pct: prev_close.map(|prev| (last - prev) / prev * 100.0),
+        indicators: Vec::new(),
+    }
+}
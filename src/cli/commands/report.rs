@@ -0,0 +1,23 @@
+//! Saved report rendering command.
+
+use anyhow::{Context, Result};
+use tracing::info;
+use trading_backtest::BacktestReport;
+
+use crate::cli::ReportArgs;
+
+pub async fn run(args: ReportArgs) -> Result<()> {
+    let report = BacktestReport::load(&args.file)
+        .with_context(|| format!("Failed to load report {:?}", args.file))?;
+    let output = report.render(args.format)?;
+
+    match &args.out {
+        Some(path) => {
+            std::fs::write(path, output)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            info!("Report written to {:?}", path);
+        }
+        None => print!("{}", output),
+    }
+    Ok(())
+}
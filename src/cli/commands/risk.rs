@@ -0,0 +1,41 @@
+//! Risk settings commands.
+
+use anyhow::{Context, Result};
+use std::path::Path;
+use trading_config::AppConfig;
+
+use crate::cli::{RiskCommand, RiskShowArgs};
+
+pub async fn run(command: RiskCommand, config_path: &Path) -> Result<()> {
+    match command {
+        RiskCommand::Show(args) => show(args, config_path),
+    }
+}
+
+/// Print the risk configuration the trading commands would use.
+fn show(args: RiskShowArgs, config_path: &Path) -> Result<()> {
+    let settings = if config_path.exists() {
+        trading_config::load_config(config_path)
+            .context("Failed to load config file")?
+            .risk
+    } else {
+        AppConfig::default().risk
+    };
+    let risk_config = settings.resolve(args.risk_preset);
+
+    let source = match (args.risk_preset, settings.preset) {
+        (Some(preset), _) => format!("{} preset (command line)", preset),
+        (None, Some(preset)) => format!("{} preset with overrides from {:?}", preset, config_path),
+        (None, None) => format!("moderate defaults with overrides from {:?}", config_path),
+    };
+
+    match args.output.as_str() {
+        "json" => println!("{}", serde_json::to_string_pretty(&risk_config)?),
+        _ => {
+            println!("Source: {}", source);
+            print!("{}", risk_config.render());
+        }
+    }
+
+    Ok(())
+}
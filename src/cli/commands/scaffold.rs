@@ -0,0 +1,365 @@
+//! New strategy scaffolding command.
+
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+
+use crate::cli::ScaffoldArgs;
+
+/// Module skeleton; `{{type}}`, `{{title}}` and `{{description}}` are
+/// filled in.
+const TEMPLATE: &str = include_str!("../templates/strategy.rs.tmpl");
+
+/// Lines of context around each change of the registration diff.
+const CONTEXT: usize = 3;
+
+pub async fn run(args: ScaffoldArgs) -> Result<()> {
+    let name = args.name.as_str();
+    let snake_case = name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if !snake_case {
+        bail!(
+            "Strategy name '{}' must be snake_case, e.g. vwap_reversion",
+            name
+        );
+    }
+    let registry = super::strategy_registry()?;
+    if registry.exists(name) {
+        bail!("There is already a strategy called {}", name);
+    }
+
+    let scaffold = Scaffold::new(name, args.description.trim());
+    let path = args.dir.join(format!("{}.rs", name));
+    if path.exists() && !args.force {
+        bail!(
+            "{} already exists; pass --force to overwrite it",
+            path.display()
+        );
+    }
+    std::fs::create_dir_all(&args.dir)
+        .with_context(|| format!("Failed to create {}", args.dir.display()))?;
+    std::fs::write(&path, scaffold.module())
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    println!("Wrote {}", path.display());
+
+    let diff = scaffold.registration_diff(&args.dir)?;
+    match &args.diff {
+        Some(diff_path) => {
+            std::fs::write(diff_path, &diff)
+                .with_context(|| format!("Failed to write {}", diff_path.display()))?;
+            println!(
+                "Registration diff written to {}; apply it with `git apply {}`, then run `cargo fmt` and `cargo test -p trading-strategies`",
+                diff_path.display(),
+                diff_path.display()
+            );
+        }
+        None => {
+            println!("Register it with the diff below (`git apply`), then run `cargo fmt` and `cargo test -p trading-strategies`:");
+            println!();
+            print!("{}", diff);
+        }
+    }
+    Ok(())
+}
+
+/// Names of a new strategy.
+struct Scaffold {
+    /// Registry and module name, e.g. `vwap_reversion`
+    name: String,
+    /// Type name prefix, e.g. `VwapReversion`
+    type_name: String,
+    /// Display name, e.g. `Vwap Reversion`
+    title: String,
+    description: String,
+}
+
+impl Scaffold {
+    fn new(name: &str, description: &str) -> Self {
+        let words: Vec<String> = name
+            .split('_')
+            .filter(|w| !w.is_empty())
+            .map(|w| {
+                let mut chars = w.chars();
+                chars
+                    .next()
+                    .map(|c| c.to_ascii_uppercase().to_string() + chars.as_str())
+                    .unwrap_or_default()
+            })
+            .collect();
+        Self {
+            name: name.to_string(),
+            type_name: words.concat(),
+            title: words.join(" "),
+            description: description.to_string(),
+        }
+    }
+
+    /// Source of the strategy module.
+    fn module(&self) -> String {
+        TEMPLATE
+            .replace("{{type}}", &self.type_name)
+            .replace("{{title}}", &self.title)
+            .replace("{{description}}", &escape(&self.description))
+    }
+
+    /// Diff adding the module to the strategies crate and the registry.
+    fn registration_diff(&self, dir: &Path) -> Result<String> {
+        let config = format!("{}Config", self.type_name);
+        let strategy = format!("{}Strategy", self.type_name);
+
+        let lib_path = dir.join("lib.rs");
+        let lib = read(&lib_path)?;
+        let mut lines: Vec<String> = lib.lines().map(str::to_string).collect();
+        insert_sorted(
+            &mut lines,
+            "mod ",
+            &self.name,
+            format!("mod {};", self.name),
+        );
+        insert_sorted(
+            &mut lines,
+            "pub use ",
+            &self.name,
+            format!("pub use {}::{{{}, {}}};", self.name, config, strategy),
+        );
+        let mut diff = unified_diff(&lib_path, &lib, &join(&lines));
+
+        let registry_path = dir.join("registry.rs");
+        let registry = read(&registry_path)?;
+        let mut lines: Vec<String> = registry.lines().map(str::to_string).collect();
+        let anchor = |lines: &[String], anchor: &str| {
+            lines.iter().position(|l| l == anchor).with_context(|| {
+                format!(
+                    "{} has no line '{}'; register the strategy by hand",
+                    registry_path.display(),
+                    anchor.trim()
+                )
+            })
+        };
+
+        // Import the new types into the `use crate::{...}` list
+        let start = anchor(&lines, "use crate::{")?;
+        let end = start
+            + lines[start..]
+                .iter()
+                .position(|l| l == "};")
+                .context("Unterminated `use crate::{` in the registry")?;
+        let mut imports: Vec<String> = lines[start + 1..end]
+            .join(" ")
+            .split(',')
+            .map(|i| i.trim().to_string())
+            .filter(|i| !i.is_empty())
+            .collect();
+        imports.extend([config.clone(), strategy.clone()]);
+        imports.sort();
+        lines.splice(start + 1..end, wrap(&imports, "    ", 100));
+
+        let info = anchor(&lines, "        Self {")?;
+        lines.splice(
+            info..info,
+            [
+                "        strategies.insert(".to_string(),
+                format!("            \"{}\".to_string(),", self.name),
+                "            StrategyInfo {".to_string(),
+                format!("                name: \"{}\".to_string(),", self.title),
+                format!(
+                    "                description: \"{}\".to_string(),",
+                    escape(&self.description)
+                ),
+                format!(
+                    "                default_config: serde_json::to_value({}::default()).unwrap(),",
+                    config
+                ),
+                format!(
+                    "                metadata_schema: {}::signal_schema(),",
+                    strategy
+                ),
+                "            },".to_string(),
+                "        );".to_string(),
+                String::new(),
+            ],
+        );
+
+        let create = anchor(
+            &lines,
+            "            _ => Err(StrategyError::NotFound(name.to_string())),",
+        )?;
+        lines.splice(
+            create..create,
+            [
+                format!("            \"{}\" => {{", self.name),
+                format!(
+                    "                let mut config: {} = serde_json::from_value(config)",
+                    config
+                ),
+                "                    .map_err(|e| StrategyError::InvalidConfig(e.to_string()))?;"
+                    .to_string(),
+                "                config.symbols = symbols;".to_string(),
+                "                config.validate()?;".to_string(),
+                format!("                Ok(Box::new({}::new(config)))", strategy),
+                "            }".to_string(),
+            ],
+        );
+
+        // The registry's tests count the built-in strategies
+        if let Some((line, count)) = lines.iter_mut().find_map(|l| {
+            let count = l
+                .trim()
+                .strip_prefix("assert_eq!(strategies.len(), ")?
+                .strip_suffix(");")?
+                .parse::<usize>()
+                .ok()?;
+            Some((l, count))
+        }) {
+            *line = format!("        assert_eq!(strategies.len(), {});", count + 1);
+        }
+        diff.push_str(&unified_diff(&registry_path, &registry, &join(&lines)));
+        Ok(diff)
+    }
+}
+
+fn read(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))
+}
+
+fn join(lines: &[String]) -> String {
+    let mut text = lines.join("\n");
+    text.push('\n');
+    text
+}
+
+/// Escape text for a Rust string literal.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Insert a top-level `prefix` statement in name order among the existing
+/// ones, or after the last of them.
+fn insert_sorted(lines: &mut Vec<String>, prefix: &str, name: &str, line: String) {
+    let statements: Vec<usize> = (0..lines.len())
+        .filter(|&i| lines[i].starts_with(prefix))
+        .collect();
+    let at = match statements.iter().find(|&&i| {
+        lines[i][prefix.len()..]
+            .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .next()
+            .is_some_and(|module| module > name)
+    }) {
+        Some(&i) => i,
+        // Statements can span lines
+        None => match statements.last() {
+            Some(&last) => {
+                last + lines[last..]
+                    .iter()
+                    .position(|l| l.ends_with(';'))
+                    .unwrap_or(0)
+                    + 1
+            }
+            None => lines.len(),
+        },
+    };
+    lines.insert(at, line);
+}
+
+/// Lay out a comma-separated list the way rustfmt does, filling each line
+/// up to `width`.
+fn wrap(items: &[String], indent: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for item in items {
+        if !line.is_empty() && indent.len() + line.len() + 1 + item.len() + 1 > width {
+            lines.push(format!("{}{}", indent, line));
+            line.clear();
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(item);
+        line.push(',');
+    }
+    if !line.is_empty() {
+        lines.push(format!("{}{}", indent, line));
+    }
+    lines
+}
+
+/// One line of an edit script.
+#[derive(Clone, Copy, PartialEq)]
+enum Edit {
+    Keep,
+    Remove,
+    Add,
+}
+
+/// A `git apply`-able diff turning `old` into `new`.
+fn unified_diff(path: &Path, old: &str, new: &str) -> String {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // Longest common subsequence of lines, from the end
+    let mut common = vec![vec![0u32; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut script = Vec::new();
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            script.push((Edit::Keep, i, j));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || common[i + 1][j] >= common[i][j + 1]) {
+            script.push((Edit::Remove, i, j));
+            i += 1;
+        } else {
+            script.push((Edit::Add, i, j));
+            j += 1;
+        }
+    }
+
+    let path = path.display().to_string().replace('\\', "/");
+    let mut diff = format!(
+        "diff --git a/{0} b/{0}\n--- a/{0}\n+++ b/{0}\n",
+        path.trim_start_matches("./")
+    );
+    let changes: Vec<usize> = (0..script.len())
+        .filter(|&k| script[k].0 != Edit::Keep)
+        .collect();
+    let mut k = 0;
+    while k < changes.len() {
+        // Changes closer than twice the context share a hunk
+        let mut last = k;
+        while last + 1 < changes.len() && changes[last + 1] - changes[last] <= 2 * CONTEXT + 1 {
+            last += 1;
+        }
+        let from = changes[k].saturating_sub(CONTEXT);
+        let to = (changes[last] + CONTEXT + 1).min(script.len());
+        let hunk = &script[from..to];
+        let old_len = hunk.iter().filter(|e| e.0 != Edit::Add).count();
+        let new_len = hunk.iter().filter(|e| e.0 != Edit::Remove).count();
+        let (_, old_start, new_start) = hunk[0];
+        diff.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            old_start + usize::from(old_len > 0),
+            old_len,
+            new_start + usize::from(new_len > 0),
+            new_len
+        ));
+        for &(edit, i, j) in hunk {
+            match edit {
+                Edit::Keep => diff.push_str(&format!(" {}\n", old[i])),
+                Edit::Remove => diff.push_str(&format!("-{}\n", old[i])),
+                Edit::Add => diff.push_str(&format!("+{}\n", new[j])),
+            }
+        }
+        k = last + 1;
+    }
+    diff
+}
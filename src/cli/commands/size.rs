@@ -0,0 +1,62 @@
+//! Position size preview command.
+
+use anyhow::{Context, Result};
+use rust_decimal::Decimal;
+use std::path::Path;
+use trading_config::AppConfig;
+use trading_core::types::{Portfolio, Side};
+use trading_risk::RiskManager;
+
+use crate::cli::SizeArgs;
+
+pub async fn run(args: SizeArgs, config_path: &Path) -> Result<()> {
+    let config = if config_path.exists() {
+        trading_config::load_config(config_path).context("Failed to load config file")?
+    } else {
+        AppConfig::default()
+    };
+
+    let mut risk_config = config.risk.resolve(args.risk_preset);
+    if let Some(method) = args.method {
+        risk_config.position_sizing = method;
+    }
+
+    let capital = match args.capital {
+        Some(capital) => Decimal::try_from(capital).unwrap_or_default(),
+        None => config.backtest.default_capital,
+    };
+    let price = Decimal::try_from(args.price).context("Invalid price")?;
+    let stop = args
+        .stop
+        .map(|stop| Decimal::try_from(stop).context("Invalid stop price"))
+        .transpose()?;
+    let volatility = args
+        .volatility
+        .map(|pct| Decimal::try_from(pct / 100.0).context("Invalid volatility"))
+        .transpose()?;
+    let side = if args.short { Side::Sell } else { Side::Buy };
+
+    let mut preview = RiskManager::new(risk_config).preview_size(
+        &Portfolio::new(capital),
+        &args.symbol.to_uppercase(),
+        side,
+        price,
+        stop,
+        volatility,
+    );
+    // Same rounding as orders placed through the pipeline
+    if let Some(rules) = config.instruments.get(&preview.symbol) {
+        let rounded = rules.round_quantity(preview.quantity);
+        if rounded < preview.quantity {
+            preview.binding_limit = Some(format!("lot size ({})", rules.lot_size));
+        }
+        preview.quantity = rounded;
+    }
+
+    match args.output.as_str() {
+        "json" => println!("{}", serde_json::to_string_pretty(&preview)?),
+        _ => print!("{}", preview.render()),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,85 @@
+//! Soak test command implementation.
+
+use anyhow::{Context, Result};
+use rust_decimal::Decimal;
+use std::path::Path;
+use std::time::Duration;
+use tracing::info;
+
+use trading_backtest::{FaultConfig, SimulationRng, SoakConfig, SoakTest};
+use trading_config::AppConfig;
+use trading_core::types::Timeframe;
+use trading_risk::RiskManager;
+use trading_runtime::OrderPipeline;
+
+use crate::cli::SoakArgs;
+
+pub async fn run(args: SoakArgs, config_path: &Path) -> Result<()> {
+    let timeframe: Timeframe = args
+        .timeframe
+        .parse()
+        .map_err(|e: String| anyhow::anyhow!(e))?;
+    let app_config = if config_path.exists() {
+        trading_config::load_config(config_path).context("Failed to load config file")?
+    } else {
+        AppConfig::default()
+    };
+
+    let registry = super::strategy_registry()?;
+    let settings = super::load_strategy_settings(args.strategy_config.as_deref())?;
+    let strategy = registry
+        .create_with_overrides(
+            &args.strategy,
+            super::strategy_settings(&registry, &settings, &args.strategy),
+            args.symbols.clone(),
+        )
+        .context("Failed to create strategy")?;
+
+    // Same pipeline as paper and live trading
+    let risk_manager = RiskManager::new(app_config.risk.resolve(args.risk_preset))
+        .with_periods_per_year(timeframe.periods_per_year());
+    let pipeline = OrderPipeline::new(risk_manager)
+        .with_supervisor(app_config.supervisor)
+        .with_instruments(app_config.instruments);
+
+    let config = SoakConfig {
+        symbols: args.symbols.clone(),
+        timeframe,
+        duration: args.duration,
+        max_bars: args.max_bars,
+        bar_interval: Duration::from_millis(args.bar_interval_ms),
+        stall_timeout: Duration::from_secs(args.stall_timeout_secs),
+        initial_capital: Decimal::try_from(args.capital).context("Invalid capital")?,
+        faults: FaultConfig {
+            feed_drop_pct: args.feed_drop_pct,
+            api_error_pct: args.api_error_pct,
+            partial_fill_pct: args.partial_fill_pct,
+            clock_skew_ms: args.clock_skew_ms,
+        },
+        seed: args
+            .seed
+            .unwrap_or_else(|| SimulationRng::from_entropy().seed()),
+        ..Default::default()
+    };
+
+    println!(
+        "Soak testing {} on {:?} for {:?} (seed {})...",
+        args.strategy, args.symbols, args.duration, config.seed
+    );
+    let report = SoakTest::new(config).run(strategy, pipeline).await;
+    println!("{}", report.render());
+
+    if let Some(save_path) = &args.save {
+        std::fs::write(save_path, serde_json::to_string_pretty(&report)?)?;
+        info!("Soak report saved to {:?}", save_path);
+    }
+
+    if !report.passed() {
+        anyhow::bail!(
+            "Soak test broke {} invariant(s); rerun with --seed {} to reproduce",
+            report.violations.len(),
+            report.seed
+        );
+    }
+    Ok(())
+}
@@ -1,10 +1,9 @@
 //! List strategies command.
 
 use anyhow::Result;
-use trading_strategies::StrategyRegistry;
 
 pub async fn run() -> Result<()> {
-    let registry = StrategyRegistry::new();
+    let registry = super::strategy_registry()?;
 
     println!("Available Strategies");
     println!("═══════════════════════════════════════════════════════════");
@@ -14,6 +13,10 @@ pub async fn run() -> Result<()> {
         println!("  {} ", info.name);
         println!("  ───────────────────────────────────────────────────────");
         println!("  {}", info.description);
+        println!(
+            "  Signal metadata: {}",
+            info.metadata_schema.keys().collect::<Vec<_>>().join(", ")
+        );
         println!();
     }
 
@@ -1,29 +1,120 @@
 //! Validate configuration command.
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use std::path::Path;
-use trading_config::load_config;
+use trading_config::{load_config, validate_config, Severity};
+
+use crate::cli::ValidateArgs;
+
+/// The shipped config, every setting explained.
+const DEFAULT_CONFIG: &str = include_str!("../../../config/default.toml");
+
+pub async fn run(args: ValidateArgs, config_path: &Path) -> Result<()> {
+    if args.print_default {
+        print!("{}", DEFAULT_CONFIG);
+        return Ok(());
+    }
 
-pub async fn run(config_path: &Path) -> Result<()> {
     println!("Validating configuration: {:?}", config_path);
+    let report = validate_config(config_path);
+    for issue in &report.issues {
+        println!("{}", issue);
+    }
+    let mut errors = report
+        .issues
+        .iter()
+        .filter(|i| i.severity == Severity::Error)
+        .count();
+    let warnings = report.issues.len() - errors;
 
-    match load_config(config_path) {
-        Ok(config) => {
-            println!("Configuration is valid!");
-            println!();
-            println!("App: {}", config.app.name);
-            println!("Environment: {}", config.app.environment);
-            println!("Log level: {}", config.logging.level);
-            println!("Alpaca paper mode: {}", config.alpaca.paper);
-            println!("Max position: {}%", config.risk.max_position_pct);
-            println!("Max exposure: {}%", config.risk.max_exposure_pct);
-            println!("Daily loss limit: {}%", config.risk.daily_loss_limit_pct);
+    if report.is_valid() {
+        // The file is fine on its own; TRADING__ variables can still break it
+        match load_config(config_path) {
+            Ok(config) => {
+                println!("Configuration is valid!");
+                println!();
+                println!("App: {}", config.app.name);
+                println!("Environment: {}", config.app.environment);
+                println!("Log level: {}", config.logging.level);
+                println!("Alpaca paper mode: {}", config.alpaca.paper);
+                let limits = config.risk.to_risk_config().limits;
+                if let Some(preset) = config.risk.preset {
+                    println!("Risk preset: {}", preset);
+                }
+                println!("Max position: {}%", limits.max_position_pct);
+                println!("Max exposure: {}%", limits.max_exposure_pct);
+                if let Some(pct) = limits.max_net_exposure_pct {
+                    println!("Max net exposure: {}%", pct);
+                }
+                println!("Daily loss limit: {}%", limits.daily_loss_limit_pct);
+            }
+            Err(e) => {
+                println!("error: with environment overrides: {}", e);
+                errors += 1;
+            }
         }
-        Err(e) => {
-            println!("Configuration error: {}", e);
-            return Err(e.into());
+    }
+
+    if args.strategy_config.is_some() || args.portfolio.is_some() {
+        println!();
+        let registry = super::strategy_registry()?;
+        if let Some(path) = &args.strategy_config {
+            println!("Validating strategy settings: {:?}", path);
+            match super::load_strategy_settings(Some(path)) {
+                Ok(settings) => {
+                    // One table per strategy, or the settings of --strategy
+                    let names: Vec<String> = match (&args.strategy, settings.as_object()) {
+                        (Some(name), _) => vec![name.clone()],
+                        (None, Some(tables)) => tables
+                            .keys()
+                            .filter(|key| registry.get(key).is_some())
+                            .cloned()
+                            .collect(),
+                        (None, None) => Vec::new(),
+                    };
+                    if names.is_empty() {
+                        println!("error: no strategy tables; pass --strategy to name the strategy the settings are for");
+                        errors += 1;
+                    }
+                    for name in names {
+                        let table = super::strategy_settings(&registry, &settings, &name);
+                        let symbols = vec!["SPY".to_string()];
+                        match registry.create_with_overrides(&name, table, symbols) {
+                            Ok(_) => println!("{}: ok", name),
+                            Err(e) => {
+                                println!("error: {}: {}", name, e);
+                                errors += 1;
+                            }
+                        }
+                    }
+                }
+                Err(e) => {
+                    println!("error: {}", e);
+                    errors += 1;
+                }
+            }
+        }
+        if let Some(path) = &args.portfolio {
+            println!("Validating portfolio: {:?}", path);
+            match super::portfolio_strategy(&registry, path) {
+                Ok(portfolio) => {
+                    println!("Portfolio is valid: {} symbols", portfolio.symbols.len())
+                }
+                Err(e) => {
+                    println!("error: {:#}", e);
+                    errors += 1;
+                }
+            }
         }
     }
 
+    if errors > 0 {
+        println!();
+        bail!("{} errors, {} warnings", errors, warnings);
+    }
+    if warnings > 0 {
+        println!();
+        println!("{} warnings", warnings);
+    }
     Ok(())
 }
@@ -3,7 +3,16 @@
 pub mod commands;
 
 use clap::{Parser, Subcommand, ValueEnum};
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use std::time::Duration;
+use trading_backtest::{
+    EndOfBacktest, FoldScheme, MetricTolerance, Objective, ParamRange, PortfolioMode, ReportFormat,
+    Scenario,
+};
+use trading_core::types::Timeframe;
+use trading_data::{BackAdjust, BarFormat, DownloadSource, DuplicateBarPolicy, RollRule};
+use trading_risk::{PositionSizingMethod, RiskPreset};
 
 #[derive(Parser)]
 #[command(name = "trading")]
@@ -37,21 +46,54 @@ pub enum LogLevel {
 #[derive(Subcommand)]
 pub enum Commands {
     /// Run backtesting simulation
-    Backtest(BacktestArgs),
+    Backtest(Box<BacktestArgs>),
+    /// Search a strategy's settings for the best backtest
+    Optimize(OptimizeArgs),
     /// Start live trading
     Live(LiveArgs),
     /// Start paper trading
     Paper(PaperArgs),
+    /// Soak-test the live engine on simulated data with injected faults
+    Soak(SoakArgs),
+    /// Compare stored backtest runs
+    Compare(CompareArgs),
+    /// Re-render a backtest report saved with --save
+    Report(ReportArgs),
+    /// Preview the position size the risk settings would give an order
+    Size(SizeArgs),
+    /// Trip the circuit breaker of running paper/live sessions
+    Halt(HaltArgs),
+    /// Query the trade journal of paper/live sessions
+    Journal(JournalArgs),
+    /// Replay a journaled session through the dashboard
+    Replay(ReplayArgs),
+    /// Download historical bars for backtesting
+    Download(DownloadArgs),
+    /// Stitch futures contract files into one back-adjusted series
+    Continuous(ContinuousArgs),
+    /// Inspect risk settings
+    #[command(subcommand)]
+    Risk(RiskCommand),
     /// List available strategies
     Strategies,
-    /// Validate configuration
-    ValidateConfig,
+    /// Generate the skeleton of a new strategy module
+    Scaffold(ScaffoldArgs),
+    /// Check the config file, and optionally strategy settings or a
+    /// portfolio, reporting every problem by line and setting
+    ValidateConfig(ValidateArgs),
 }
 
-#[derive(clap::Args)]
+#[derive(clap::Args, Clone, Serialize, Deserialize)]
 pub struct BacktestArgs {
-    /// Strategy to backtest
-    #[arg(short, long)]
+    /// Strategy to backtest; `all` or a comma-separated list runs each
+    /// over the same data and prints a comparison table
+    #[arg(
+        short,
+        long,
+        required_unless_present = "from_report",
+        default_value = "",
+        hide_default_value = true
+    )]
     pub strategy: String,
 
     /// Symbols to trade (comma-separated)
@@ -59,11 +101,21 @@ pub struct BacktestArgs {
     pub symbols: Vec<String>,
 
     /// Start date (YYYY-MM-DD)
-    #[arg(long)]
+    #[arg(
+        long,
+        required_unless_present = "from_report",
+        default_value = "",
+        hide_default_value = true
+    )]
     pub start: String,
 
     /// End date (YYYY-MM-DD)
-    #[arg(long)]
+    #[arg(
+        long,
+        required_unless_present = "from_report",
+        default_value = "",
+        hide_default_value = true
+    )]
     pub end: String,
 
     /// Initial capital
@@ -78,6 +130,10 @@ pub struct BacktestArgs {
     #[arg(long)]
     pub strategy_config: Option<PathBuf>,
 
+    /// Risk preset (conservative, moderate, aggressive)
+    #[arg(long)]
+    pub risk_preset: Option<RiskPreset>,
+
     /// Output format (text, json)
     #[arg(long, default_value = "text")]
     pub output: String,
@@ -89,37 +145,488 @@ pub struct BacktestArgs {
     /// Data file (CSV)
     #[arg(long)]
     pub data: Option<PathBuf>,
+
+    /// Handling of positions open at the end (close, mark, exclude)
+    #[arg(long, default_value = "close")]
+    pub end_of_backtest: EndOfBacktest,
+
+    /// Don't simulate the risk manager's stop losses on signal entries
+    #[arg(long)]
+    #[serde(default)]
+    pub ignore_stops: bool,
+
+    /// Resolution of bars sharing a timestamp (priority, volume_weighted)
+    #[arg(long, default_value = "priority")]
+    pub duplicate_bars: DuplicateBarPolicy,
+
+    /// Delay between order submission and fill, in milliseconds
+    #[arg(long, default_value = "0")]
+    pub fill_latency_ms: i64,
+
+    /// Maximum random extra fill delay, in milliseconds
+    #[arg(long, default_value = "0")]
+    pub fill_jitter_ms: i64,
+
+    /// Standard deviation of random extra slippage, in percent
+    #[arg(long, default_value = "0")]
+    pub slippage_noise_pct: f64,
+
+    /// Seed for stochastic models (reuse a report's seed to reproduce it)
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Portfolio arithmetic (decimal, float for faster runs, validate to
+    /// measure float drift against decimal)
+    #[arg(long, default_value = "decimal")]
+    pub portfolio_mode: PortfolioMode,
+
+    /// Resample closed trades this many times and report the spread of outcomes
+    #[arg(long, default_value = "0")]
+    pub monte_carlo: usize,
+
+    /// Re-run under the built-in stress scenarios (gap down, volatility, outage)
+    #[arg(long)]
+    pub stress: bool,
+
+    /// Custom stress scenario, e.g. gap_down:15+volatility:3:10@25 (repeatable)
+    #[arg(long = "scenario")]
+    pub scenarios: Vec<Scenario>,
+
+    /// Run at each of these timeframes, resampling the data (e.g. 5m,15m,1h,1d)
+    #[arg(long, value_delimiter = ',')]
+    pub sweep: Vec<Timeframe>,
+
+    /// Directory where every run is recorded for later comparison
+    #[arg(long, default_value = "results")]
+    pub results_dir: PathBuf,
+
+    /// Do not record this run in the results store
+    #[arg(long)]
+    pub no_store: bool,
+
+    /// Periodically save engine state to this file
+    #[arg(long)]
+    pub checkpoint: Option<PathBuf>,
+
+    /// Bars processed between checkpoints
+    #[arg(long, default_value = "10000")]
+    pub checkpoint_every: usize,
+
+    /// Resume an interrupted backtest from a checkpoint file
+    #[arg(long)]
+    pub resume_from: Option<PathBuf>,
+
+    /// Re-run the backtest recorded in a report saved with --save; output
+    /// options and --data still apply
+    #[arg(long)]
+    #[serde(skip)]
+    pub from_report: Option<PathBuf>,
+
+    /// Compare against a report saved with --save and fail if any metric
+    /// moved beyond tolerance (for CI)
+    #[arg(long)]
+    #[serde(skip)]
+    pub baseline: Option<PathBuf>,
+
+    /// Allowed difference from the baseline, e.g. sharpe=0.05,trades=2
+    /// (return, annualized_return, drawdown, sharpe, sortino, trades,
+    /// win_rate, profit_factor)
+    #[arg(long, value_delimiter = ',')]
+    #[serde(skip)]
+    pub baseline_tolerance: Vec<MetricTolerance>,
+}
+
+#[derive(clap::Args)]
+pub struct CompareArgs {
+    /// Run ids (or unique prefixes) to compare; the first is the reference
+    pub runs: Vec<String>,
+
+    /// Results store directory
+    #[arg(long, default_value = "results")]
+    pub results_dir: PathBuf,
+
+    /// List stored runs instead of comparing
+    #[arg(long)]
+    pub list: bool,
+}
+
+#[derive(clap::Args)]
+pub struct ReportArgs {
+    /// Report file saved with `backtest --save`
+    pub file: PathBuf,
+
+    /// Output format (text, json, html, equity_csv, trades_csv)
+    #[arg(short, long, default_value = "text")]
+    pub format: ReportFormat,
+
+    /// Write to this file instead of stdout
+    #[arg(short, long)]
+    pub out: Option<PathBuf>,
+}
+
+#[derive(clap::Args)]
+pub struct HaltArgs {
+    /// Why trading is being halted
+    #[arg(short, long, default_value = "Manual halt")]
+    pub reason: String,
+
+    /// Remove the halt file so new sessions can trade again
+    #[arg(long)]
+    pub clear: bool,
+}
+
+#[derive(clap::Args)]
+pub struct JournalArgs {
+    /// Journal file (defaults to `[journal] path` in the config)
+    #[arg(long)]
+    pub file: Option<PathBuf>,
+
+    /// Only events for this symbol
+    #[arg(short = 'S', long)]
+    pub symbol: Option<String>,
+
+    /// Only this event type: signal, strategy_orders, approved, skipped,
+    /// rejected, order, order_failed, fill, or bar (bars are hidden
+    /// otherwise)
+    #[arg(short, long)]
+    pub kind: Option<String>,
+
+    /// Only events on or after this date (YYYY-MM-DD, UTC)
+    #[arg(long)]
+    pub since: Option<String>,
+
+    /// Only events up to and including this date (YYYY-MM-DD, UTC)
+    #[arg(long)]
+    pub until: Option<String>,
+
+    /// Show only the latest N events
+    #[arg(short = 'n', long)]
+    pub limit: Option<usize>,
+
+    /// Print the matching entries as JSON lines
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(clap::Args)]
+pub struct ReplayArgs {
+    /// Journal file (defaults to `[journal] path` in the config)
+    #[arg(long)]
+    pub file: Option<PathBuf>,
+
+    /// Start at this date (YYYY-MM-DD, UTC)
+    #[arg(long)]
+    pub since: Option<String>,
+
+    /// Stop after this date (YYYY-MM-DD, UTC)
+    #[arg(long)]
+    pub until: Option<String>,
+
+    /// Playback speed as a multiple of the recorded pace ('+'/'-' change it)
+    #[arg(long, default_value = "60")]
+    pub speed: f64,
+
+    /// Cash the replayed portfolio starts from
+    #[arg(long, default_value = "100000")]
+    pub capital: f64,
+
+    /// Start paused; 'n' steps one bar, 'p' resumes
+    #[arg(long)]
+    pub paused: bool,
+}
+
+#[derive(clap::Args)]
+pub struct OptimizeArgs {
+    /// Strategy to optimize
+    #[arg(short, long)]
+    pub strategy: String,
+
+    /// Setting to vary: name=start..end[:step] or name=a,b,c (repeatable)
+    #[arg(short, long = "param", required = true)]
+    pub params: Vec<ParamRange>,
+
+    /// Metric to maximize (sharpe, sortino, return, profit_factor, calmar)
+    #[arg(long, default_value = "sharpe")]
+    pub objective: Objective,
+
+    /// How to split the data (none, walk-forward)
+    #[arg(long, default_value = "none")]
+    pub folds: FoldScheme,
+
+    /// Walk-forward test windows
+    #[arg(long, default_value = "4")]
+    pub windows: usize,
+
+    /// Symbols to trade (comma-separated)
+    #[arg(short = 'S', long, value_delimiter = ',')]
+    pub symbols: Vec<String>,
+
+    /// Data file or directory (CSV)
+    #[arg(long)]
+    pub data: PathBuf,
+
+    /// Timeframe
+    #[arg(short, long, default_value = "1d")]
+    pub timeframe: Timeframe,
+
+    /// Initial capital
+    #[arg(long, default_value = "100000")]
+    pub capital: f64,
+
+    /// Strategy configuration file the parameters are applied on top of
+    #[arg(long)]
+    pub strategy_config: Option<PathBuf>,
+
+    /// Risk preset (conservative, moderate, aggressive)
+    #[arg(long)]
+    pub risk_preset: Option<RiskPreset>,
+
+    /// Seed for stochastic models
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Parameter sets shown
+    #[arg(long, default_value = "10")]
+    pub top: usize,
+
+    /// Output format (text, json)
+    #[arg(long, default_value = "text")]
+    pub output: String,
+
+    /// Write the best settings as a strategy config file (TOML, or JSON
+    /// for .json)
+    #[arg(long)]
+    pub write_config: Option<PathBuf>,
+}
+
+#[derive(clap::Args)]
+pub struct ScaffoldArgs {
+    /// Registry name of the new strategy, in snake_case (e.g. vwap_reversion)
+    pub name: String,
+
+    /// One-line description of the strategy
+    #[arg(short, long, default_value = "TODO: describe the strategy")]
+    pub description: String,
+
+    /// Source directory of the strategies crate
+    #[arg(long, default_value = "crates/trading-strategies/src")]
+    pub dir: PathBuf,
+
+    /// Write the registration diff to this file instead of printing it
+    #[arg(long)]
+    pub diff: Option<PathBuf>,
+
+    /// Overwrite an existing module of the same name
+    #[arg(long)]
+    pub force: bool,
+}
+
+#[derive(clap::Args)]
+pub struct ValidateArgs {
+    /// Print the default config, with every setting commented, and exit
+    #[arg(long)]
+    pub print_default: bool,
+
+    /// Strategy configuration file to check as well
+    #[arg(long)]
+    pub strategy_config: Option<PathBuf>,
+
+    /// Strategy the settings file is for, unless it has a table per strategy
+    #[arg(short, long, requires = "strategy_config")]
+    pub strategy: Option<String>,
+
+    /// Portfolio file to check as well
+    #[arg(long)]
+    pub portfolio: Option<PathBuf>,
+}
+
+#[derive(clap::Args)]
+pub struct DownloadArgs {
+    /// Data provider (alpaca, yahoo, polygon)
+    #[arg(long, default_value = "yahoo")]
+    pub source: DownloadSource,
+
+    /// Symbols to download (comma-separated)
+    #[arg(short = 'S', long, value_delimiter = ',', required = true)]
+    pub symbols: Vec<String>,
+
+    /// First day to download (YYYY-MM-DD)
+    #[arg(long)]
+    pub start: String,
+
+    /// Last day to download (YYYY-MM-DD, defaults to today)
+    #[arg(long)]
+    pub end: Option<String>,
+
+    /// Timeframe
+    #[arg(short, long, default_value = "1d")]
+    pub timeframe: Timeframe,
+
+    /// Output format (csv, parquet, sqlite); parquet and sqlite need the
+    /// matching cargo feature
+    #[arg(long, default_value = "csv")]
+    pub format: BarFormat,
+
+    /// Directory to write to: one file per symbol, or bars.sqlite
+    #[arg(short, long, default_value = "data")]
+    pub out: PathBuf,
+
+    /// Polygon API key
+    #[arg(long, env = "POLYGON_API_KEY", hide_env_values = true)]
+    pub polygon_api_key: Option<String>,
+}
+
+#[derive(clap::Args)]
+pub struct ContinuousArgs {
+    /// Symbol to save the series as, e.g. ES
+    #[arg(short = 'S', long)]
+    pub symbol: String,
+
+    /// Contract CSV files (comma-separated), each named after its contract,
+    /// e.g. ESH24.csv; an open_interest column is read when present
+    #[arg(long, value_delimiter = ',', required = true)]
+    pub contracts: Vec<PathBuf>,
+
+    /// When to roll to the next contract (volume, open_interest)
+    #[arg(long, default_value = "volume")]
+    pub roll: RollRule,
+
+    /// How to adjust bars before each roll (none, difference, ratio)
+    #[arg(long, default_value = "difference")]
+    pub adjust: BackAdjust,
+
+    /// Directory to write {SYMBOL}.csv to
+    #[arg(short, long, default_value = "data")]
+    pub out: PathBuf,
+}
+
+#[derive(clap::Args)]
+pub struct SizeArgs {
+    /// Symbol to size
+    #[arg(short = 'S', long)]
+    pub symbol: String,
+
+    /// Entry price
+    #[arg(long)]
+    pub price: f64,
+
+    /// Stop-loss price (defaults to the configured stop-loss method)
+    #[arg(long)]
+    pub stop: Option<f64>,
+
+    /// Sizing method overriding the configured one, e.g. risk_based:1%,
+    /// percent_equity:2%, fixed:100, fixed_dollar:5000, kelly:0.55:1.5,
+    /// vol_target:10%, rolling_kelly:50:20%
+    #[arg(long)]
+    pub method: Option<PositionSizingMethod>,
+
+    /// Annualized volatility of the symbol in percent, for vol_target
+    /// sizing
+    #[arg(long)]
+    pub volatility: Option<f64>,
+
+    /// Account equity (defaults to the configured backtest capital)
+    #[arg(long)]
+    pub capital: Option<f64>,
+
+    /// Risk preset replacing the configured risk settings
+    #[arg(long)]
+    pub risk_preset: Option<RiskPreset>,
+
+    /// Size a short entry instead of a long one
+    #[arg(long)]
+    pub short: bool,
+
+    /// Output format (text, json)
+    #[arg(long, default_value = "text")]
+    pub output: String,
+}
+
+#[derive(Subcommand)]
+pub enum RiskCommand {
+    /// Print the effective risk configuration
+    Show(RiskShowArgs),
+}
+
+#[derive(clap::Args)]
+pub struct RiskShowArgs {
+    /// Risk preset replacing the configured risk settings
+    #[arg(long)]
+    pub risk_preset: Option<RiskPreset>,
+
+    /// Output format (text, json)
+    #[arg(long, default_value = "text")]
+    pub output: String,
 }
 
 #[derive(clap::Args)]
 pub struct LiveArgs {
     /// Strategy to run
-    #[arg(short, long)]
+    #[arg(
+        short,
+        long,
+        required_unless_present = "portfolio",
+        default_value = "",
+        hide_default_value = true
+    )]
     pub strategy: String,
 
     /// Symbols to trade (comma-separated)
     #[arg(short = 'S', long, value_delimiter = ',')]
     pub symbols: Vec<String>,
 
+    /// Portfolio file of strategy instances to run together, each with its
+    /// own settings, symbols and share of capital
+    #[arg(long, conflicts_with_all = ["strategy", "symbols", "strategy_config"])]
+    pub portfolio: Option<PathBuf>,
+
     /// Timeframe
     #[arg(short, long, default_value = "1m")]
     pub timeframe: String,
 
-    /// Enable dry run (no real orders)
+    /// Strategy configuration file
+    #[arg(long)]
+    pub strategy_config: Option<PathBuf>,
+
+    /// Log every order, cancel and close instead of sending it
     #[arg(long)]
     pub dry_run: bool,
+    /// Run unattended: wait for the market to open, stop trading after the
+    /// close and carry on with the next session
+    #[arg(long)]
+    pub daemon: bool,
+
+    /// How long before the open a daemon session starts, e.g. 15m
+    #[arg(long, default_value = "15m", value_parser = parse_duration, requires = "daemon")]
+    pub pre_open: Duration,
+
+    /// Watch the config, strategy settings and portfolio files, and switch
+    /// to changed strategy and risk settings at the next bar
+    #[arg(long)]
+    pub hot_reload: bool,
 }
 
 #[derive(clap::Args)]
 pub struct PaperArgs {
     /// Strategy to run
-    #[arg(short, long)]
+    #[arg(
+        short,
+        long,
+        required_unless_present = "portfolio",
+        default_value = "",
+        hide_default_value = true
+    )]
     pub strategy: String,
 
     /// Symbols to trade (comma-separated)
     #[arg(short = 'S', long, value_delimiter = ',')]
     pub symbols: Vec<String>,
 
+    /// Portfolio file of strategy instances to run together, each with its
+    /// own settings, symbols and share of capital
+    #[arg(long, conflicts_with_all = ["strategy", "symbols", "strategy_config"])]
+    pub portfolio: Option<PathBuf>,
+
     /// Initial capital
     #[arg(long, default_value = "100000")]
     pub capital: f64,
@@ -127,4 +634,131 @@ pub struct PaperArgs {
     /// Timeframe
     #[arg(short, long, default_value = "1m")]
     pub timeframe: String,
+
+    /// Strategy configuration file
+    #[arg(long)]
+    pub strategy_config: Option<PathBuf>,
+
+    /// Show the TUI dashboard, with a manual order ticket ('o')
+    #[arg(long)]
+    pub dashboard: bool,
+
+    /// Risk preset replacing the configured risk settings
+    #[arg(long)]
+    pub risk_preset: Option<RiskPreset>,
+
+    /// Log strategy orders instead of sending them ('d' toggles it in the
+    /// dashboard)
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Serve the web dashboard (see `[web]` in the config)
+    #[cfg(feature = "web")]
+    #[arg(long)]
+    pub web: bool,
+
+    /// Serve gRPC control of the session and backtest jobs on this
+    /// address, e.g. 127.0.0.1:50051
+    #[cfg(feature = "grpc")]
+    #[arg(long)]
+    pub grpc: Option<std::net::SocketAddr>,
+
+    /// Run unattended: wait for the market to open, stop trading after the
+    /// close and carry on with the next session
+    #[arg(long)]
+    pub daemon: bool,
+
+    /// How long before the open a daemon session starts, e.g. 15m
+    #[arg(long, default_value = "15m", value_parser = parse_duration, requires = "daemon")]
+    pub pre_open: Duration,
+
+    /// Watch the config, strategy settings and portfolio files, and switch
+    /// to changed strategy and risk settings at the next bar
+    #[arg(long)]
+    pub hot_reload: bool,
+}
+
+#[derive(clap::Args)]
+pub struct SoakArgs {
+    /// Strategy to run
+    #[arg(short, long)]
+    pub strategy: String,
+
+    /// Simulated symbols (comma-separated)
+    #[arg(short = 'S', long, value_delimiter = ',', default_value = "SOAK")]
+    pub symbols: Vec<String>,
+
+    /// How long to run, e.g. 90s, 30m, 4h
+    #[arg(long, default_value = "1h", value_parser = parse_duration)]
+    pub duration: Duration,
+
+    /// Stop after this many bars, if sooner
+    #[arg(long)]
+    pub max_bars: Option<u64>,
+
+    /// Timeframe of the simulated bars
+    #[arg(short, long, default_value = "1m")]
+    pub timeframe: String,
+
+    /// Strategy configuration file
+    #[arg(long)]
+    pub strategy_config: Option<PathBuf>,
+
+    /// Wall-clock pause between bars, in milliseconds
+    #[arg(long, default_value = "0")]
+    pub bar_interval_ms: u64,
+
+    /// Seconds a single step may take before it counts as hung
+    #[arg(long, default_value = "10")]
+    pub stall_timeout_secs: u64,
+
+    /// Initial capital
+    #[arg(long, default_value = "100000")]
+    pub capital: f64,
+
+    /// Seed for market data and faults (reuse a report's seed to reproduce it)
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Chance a batch of bars is dropped, in percent
+    #[arg(long, default_value = "1")]
+    pub feed_drop_pct: f64,
+
+    /// Chance a broker call fails with an HTTP 500, in percent
+    #[arg(long, default_value = "2")]
+    pub api_error_pct: f64,
+
+    /// Chance an order fills only partly on a bar, in percent
+    #[arg(long, default_value = "10")]
+    pub partial_fill_pct: f64,
+
+    /// Largest offset applied to bar timestamps, in milliseconds
+    #[arg(long, default_value = "2000")]
+    pub clock_skew_ms: i64,
+
+    /// Risk preset replacing the configured risk settings
+    #[arg(long)]
+    pub risk_preset: Option<RiskPreset>,
+
+    /// Save the stability report (JSON) to file
+    #[arg(long)]
+    pub save: Option<PathBuf>,
+}
+
+/// Parse a duration such as `90s`, `30m`, `4h` or `1d`.
+fn parse_duration(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("Invalid duration: {}", s))?;
+    let secs = match unit {
+        "s" | "" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86400,
+        _ => return Err(format!("Invalid duration unit in {} (use s, m, h or d)", s)),
+    };
+    Ok(Duration::from_secs(number * secs))
 }
@@ -5,7 +5,7 @@ mod cli;
 use anyhow::Result;
 use clap::Parser;
 use cli::{Cli, Commands};
-use trading_monitor::setup_logging;
+use trading_monitor::{setup_dashboard_logging, setup_logging, LogBuffer};
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -19,14 +19,34 @@ async fn main() -> Result<()> {
         cli::LogLevel::Warn => "warn",
         cli::LogLevel::Error => "error",
     };
-    setup_logging(log_level, cli.json_logs);
+    // The dashboard owns the terminal, so logs go to its log panel instead
+    let dashboard = matches!(&cli.command, Commands::Paper(args) if args.dashboard)
+        || matches!(&cli.command, Commands::Replay(_));
+    let log = LogBuffer::default();
+    if dashboard {
+        setup_dashboard_logging(log_level, log.clone());
+    } else {
+        setup_logging(log_level, cli.json_logs);
+    }
 
     // Execute command
     match cli.command {
-        Commands::Backtest(args) => cli::commands::backtest::run(args, &cli.config).await,
+        Commands::Backtest(args) => cli::commands::backtest::run(*args, &cli.config).await,
         Commands::Live(args) => cli::commands::live::run(args, &cli.config).await,
-        Commands::Paper(args) => cli::commands::paper::run(args, &cli.config).await,
+        Commands::Paper(args) => cli::commands::paper::run(args, &cli.config, log).await,
+        Commands::Soak(args) => cli::commands::soak::run(args, &cli.config).await,
+        Commands::Compare(args) => cli::commands::compare::run(args).await,
+        Commands::Report(args) => cli::commands::report::run(args).await,
+        Commands::Size(args) => cli::commands::size::run(args, &cli.config).await,
+        Commands::Halt(args) => cli::commands::halt::run(args, &cli.config).await,
+        Commands::Journal(args) => cli::commands::journal::run(args, &cli.config).await,
+        Commands::Replay(args) => cli::commands::replay::run(args, &cli.config, log).await,
+        Commands::Download(args) => cli::commands::download::run(args, &cli.config).await,
+        Commands::Continuous(args) => cli::commands::continuous::run(args).await,
+        Commands::Optimize(args) => cli::commands::optimize::run(args, &cli.config).await,
+        Commands::Risk(command) => cli::commands::risk::run(command, &cli.config).await,
         Commands::Strategies => cli::commands::strategies::run().await,
-        Commands::ValidateConfig => cli::commands::validate::run(&cli.config).await,
+        Commands::Scaffold(args) => cli::commands::scaffold::run(args).await,
+        Commands::ValidateConfig(args) => cli::commands::validate::run(args, &cli.config).await,
     }
 }